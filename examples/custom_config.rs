@@ -29,7 +29,7 @@
 
 use anyhow::Result;
 use selfware::config::{
-    AgentConfig, Config, ExecutionMode, SafetyConfig, UiConfig, YoloFileConfig,
+    AgentConfig, Config, ExecutionMode, Provider, SafetyConfig, UiConfig, YoloFileConfig,
 };
 use std::path::PathBuf;
 
@@ -98,8 +98,12 @@ fn build_custom_config() -> Config {
         // API settings
         endpoint: "http://localhost:8000/v1".to_string(),
         model: "Qwen/Qwen3-Coder-Next-FP8".to_string(),
+        provider: Provider::Generic,
         max_tokens: 32768,
+        max_reference_tokens: 16_000,
         temperature: 0.7,
+        store_reasoning: false,
+        dyslexia_friendly: false,
         api_key: None,
 
         // Safety settings
@@ -127,6 +131,10 @@ fn build_custom_config() -> Config {
                 "container_exec".to_string(),
             ],
             strict_permissions: false,
+            large_edit_lines: 200,
+            large_edit_ratio: 0.5,
+            large_edit_min_lines: 20,
+            guard_self_source: true,
         },
 
         // Agent behavior
@@ -158,6 +166,8 @@ fn build_custom_config() -> Config {
             verbose_mode: false,
             show_tokens: false,
             animation_speed: 1.0,
+            cognitive_load: Default::default(),
+            token_hog_share: 0.2,
         },
 
         // Continuous-work settings
@@ -172,12 +182,21 @@ fn build_custom_config() -> Config {
         execution_mode: ExecutionMode::Normal,
 
         evolution: Default::default(),
+        carbon: Default::default(),
+        encryption: Default::default(),
+        checkpoint: Default::default(),
+        telemetry: Default::default(),
+        tools: Default::default(),
+        redaction: Default::default(),
+        notifications: Default::default(),
         models: Default::default(),
 
         // CLI-only flags (not persisted in config)
         compact_mode: false,
         verbose_mode: false,
         show_tokens: false,
+        allow_self_edit: false,
+        focus: Vec::new(),
     }
 }
 
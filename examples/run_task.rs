@@ -22,7 +22,7 @@
 
 use anyhow::Result;
 use selfware::agent::Agent;
-use selfware::config::{AgentConfig, Config, ExecutionMode, SafetyConfig};
+use selfware::config::{AgentConfig, Config, ExecutionMode, Provider, SafetyConfig};
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -40,8 +40,12 @@ async fn main() -> Result<()> {
             .unwrap_or_else(|_| "Qwen/Qwen3-Coder-Next-FP8".to_string()),
 
         // Token limits
+        provider: Provider::Generic,
         max_tokens: 65536,
+        max_reference_tokens: 16_000,
         temperature: 0.7,
+        store_reasoning: false,
+        dyslexia_friendly: false,
 
         // API key (if required by your backend)
         api_key: std::env::var("SELFWARE_API_KEY")
@@ -63,6 +67,10 @@ async fn main() -> Result<()> {
                 "shell_exec".to_string(),
             ],
             strict_permissions: false,
+            large_edit_lines: 200,
+            large_edit_ratio: 0.5,
+            large_edit_min_lines: 20,
+            guard_self_source: true,
         },
 
         // Agent behavior
@@ -99,12 +107,21 @@ async fn main() -> Result<()> {
         retry: Default::default(),
 
         evolution: Default::default(),
+        carbon: Default::default(),
+        encryption: Default::default(),
+        checkpoint: Default::default(),
+        telemetry: Default::default(),
+        tools: Default::default(),
+        redaction: Default::default(),
+        notifications: Default::default(),
         models: Default::default(),
 
         // CLI-only flags (not persisted in config)
         compact_mode: false,
         verbose_mode: false,
         show_tokens: false,
+        allow_self_edit: false,
+        focus: Vec::new(),
     };
 
     println!("Configuration:");
@@ -147,7 +164,7 @@ async fn main() -> Result<()> {
         let _ = std::fs::remove_file("calculator.rs");
     }
 
-    task_result?;
+    task_result?.into_result()?;
 
     println!("\n--- Task Complete ---");
     println!("Duration: {:.2}s", duration.as_secs_f64());
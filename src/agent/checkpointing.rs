@@ -4,17 +4,26 @@ use std::time::Instant;
 use tracing::{debug, info, warn};
 
 use super::*;
-use crate::checkpoint::{capture_git_state, CheckpointManager, TaskCheckpoint, TaskStatus};
+use crate::checkpoint::{
+    capture_git_state, CheckpointManager, RetentionPolicy, TaskCheckpoint, TaskStatus,
+};
 #[cfg(feature = "self-improvement")]
 use crate::cognitive::metrics::{MetricsStore, PerformanceSnapshot};
 #[cfg(feature = "resilience")]
 use crate::self_healing::ErrorOccurrence;
 
 impl Agent {
+    /// The default checkpoint manager with the configured retention policy
+    pub(super) fn configured_checkpoint_manager(config: &Config) -> Result<CheckpointManager> {
+        let retention = RetentionPolicy::from_config(&config.checkpoint)?;
+        Ok(CheckpointManager::default_path()
+            .context("Failed to initialize checkpoint manager")?
+            .with_retention(retention))
+    }
+
     /// Resume a task from a checkpoint
     pub async fn resume(config: Config, task_id: &str) -> Result<Self> {
-        let checkpoint_manager =
-            CheckpointManager::default_path().context("Failed to initialize checkpoint manager")?;
+        let checkpoint_manager = Self::configured_checkpoint_manager(&config)?;
 
         let checkpoint = checkpoint_manager
             .load(task_id)
@@ -44,34 +53,35 @@ impl Agent {
         // Build all restored state in temporary variables first, then commit
         // atomically to the agent. This prevents partial state if any step fails.
         let restored_messages = checkpoint.messages.clone();
-        let mut restored_loop = AgentLoop::new(config.agent.max_iterations);
-
-        // Restore exact loop progress when available.
-        // Older checkpoints may not have an iteration value, so keep fallback logic.
-        if checkpoint.current_iteration > 0 {
-            restored_loop.restore_progress(checkpoint.current_step, checkpoint.current_iteration);
+        let restored_note = checkpoint.pinned_note.clone();
+        let restored_outputs = checkpoint.tool_outputs.clone();
+        let restored_loop = Self::restored_loop(&checkpoint, config.agent.max_iterations);
+        // A --focus given on resume replaces the saved scope
+        let focus_from_cli = !config.focus.is_empty() && config.focus != checkpoint.focus;
+        let restored_focus = if config.focus.is_empty() {
+            checkpoint.focus.clone()
         } else {
-            // Backward-compatible restore for legacy checkpoints.
-            for _ in 0..checkpoint.current_step {
-                restored_loop.next_state(); // consumes one iteration
-                restored_loop.increment_step();
-            }
-            restored_loop.set_state(AgentState::Executing {
-                step: checkpoint.current_step,
-            });
-        }
+            config.focus.clone()
+        };
 
         let checkpoint_tool_calls = checkpoint.tool_calls.len();
 
         // Create the agent and commit all restored state at once
         let mut agent = Self::new(config).await?;
         agent.messages = restored_messages;
+        agent.pinned_note = restored_note;
+        agent.tool_outputs.restore(restored_outputs);
+        agent.focus = restored_focus;
+        agent.apply_focus();
         agent.loop_control = restored_loop;
         agent.current_checkpoint = Some(checkpoint);
         agent.checkpoint_manager = Some(checkpoint_manager);
         agent.last_checkpoint_tool_calls = checkpoint_tool_calls;
         agent.last_checkpoint_persisted_at = Instant::now();
         agent.checkpoint_persisted_once = true;
+        if focus_from_cli {
+            agent.set_focus(agent.focus.clone())?;
+        }
 
         // Set cognitive state to Do phase since we're resuming execution
         agent.cognitive_state.set_phase(CyclePhase::Do);
@@ -81,6 +91,67 @@ impl Agent {
         Ok(agent)
     }
 
+    /// Rebuild loop progress (step and iteration counters) from a checkpoint.
+    fn restored_loop(checkpoint: &TaskCheckpoint, max_iterations: usize) -> AgentLoop {
+        let mut restored_loop = AgentLoop::new(max_iterations);
+
+        // Restore exact loop progress when available.
+        // Older checkpoints may not have an iteration value, so keep fallback logic.
+        if checkpoint.current_iteration > 0 {
+            restored_loop.restore_progress(checkpoint.current_step, checkpoint.current_iteration);
+        } else {
+            // Backward-compatible restore for legacy checkpoints.
+            for _ in 0..checkpoint.current_step {
+                restored_loop.next_state(); // consumes one iteration
+                restored_loop.increment_step();
+            }
+            restored_loop.set_state(AgentState::Executing {
+                step: checkpoint.current_step,
+            });
+        }
+        restored_loop
+    }
+
+    /// Fork a saved task at `at_step` and switch this session onto the fork.
+    ///
+    /// The parent checkpoint is left untouched; further progress is recorded
+    /// against the new task ID. Returns the new task ID.
+    pub(super) fn fork_and_switch(&mut self, task_id: &str, at_step: usize) -> Result<String> {
+        let manager = match self.checkpoint_manager.take() {
+            Some(manager) => manager,
+            None => Self::configured_checkpoint_manager(&self.config)?,
+        };
+        let result = manager
+            .fork(task_id, at_step)
+            .and_then(|new_id| Ok((manager.load(&new_id)?, new_id)));
+        self.checkpoint_manager = Some(manager);
+        let (checkpoint, new_id) = result?;
+
+        self.messages = checkpoint.messages.clone();
+        self.pinned_note = checkpoint.pinned_note.clone();
+        self.tool_outputs.restore(checkpoint.tool_outputs.clone());
+        self.focus = checkpoint.focus.clone();
+        self.apply_focus();
+        self.memory.clear();
+        for msg in &self.messages {
+            if msg.role != "system" {
+                self.memory.add_message(msg);
+            }
+        }
+        self.loop_control = Self::restored_loop(&checkpoint, self.config.agent.max_iterations);
+        self.last_checkpoint_tool_calls = checkpoint.tool_calls.len();
+        self.last_checkpoint_persisted_at = Instant::now();
+        self.checkpoint_persisted_once = true;
+        self.current_checkpoint = Some(checkpoint);
+        self.cognitive_state.set_phase(CyclePhase::Do);
+
+        info!(
+            "Forked task {} at step {} into {}",
+            task_id, at_step, new_id
+        );
+        Ok(new_id)
+    }
+
     /// Convert current state to a checkpoint
     pub fn to_checkpoint(&self, task_id: &str, task_description: &str) -> TaskCheckpoint {
         let mut checkpoint = if let Some(ref existing) = self.current_checkpoint {
@@ -91,7 +162,20 @@ impl Agent {
 
         checkpoint.set_step(self.loop_control.current_step());
         checkpoint.set_iteration(self.loop_control.current_iteration());
-        checkpoint.set_messages(self.messages.clone());
+        // Reasoning is journaled per step only when `store_reasoning` is on
+        checkpoint.set_messages(
+            self.messages
+                .iter()
+                .cloned()
+                .map(|mut m| {
+                    m.reasoning_content = None;
+                    m
+                })
+                .collect(),
+        );
+        checkpoint.pinned_note = self.pinned_note.clone();
+        checkpoint.focus = self.focus.clone();
+        checkpoint.tool_outputs = self.tool_outputs.snapshot();
         checkpoint.set_estimated_tokens(self.memory.total_tokens());
 
         // Capture git state
@@ -104,31 +188,87 @@ impl Agent {
 
     /// Save current state to checkpoint
     pub(super) fn save_checkpoint(&mut self, task_description: &str) -> Result<()> {
-        if let Some(ref manager) = self.checkpoint_manager {
+        if self.checkpoint_manager.is_some() {
             if !self.should_persist_checkpoint() {
                 debug!("Checkpoint skipped by continuous-work policy");
                 return Ok(());
             }
-
-            let task_id = self
-                .current_checkpoint
-                .as_ref()
-                .map(|c| c.task_id.clone())
-                .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
-
-            let checkpoint = self.to_checkpoint(&task_id, task_description);
-            manager.save(&checkpoint)?;
-            self.last_checkpoint_tool_calls = checkpoint.tool_calls.len();
-            self.last_checkpoint_persisted_at = Instant::now();
-            self.checkpoint_persisted_once = true;
-            self.current_checkpoint = Some(checkpoint);
-            #[cfg(feature = "resilience")]
-            self.record_self_healing_checkpoint(task_description);
-            debug!("Checkpoint saved for task: {}", task_id);
+            self.write_checkpoint(task_description)?;
         }
         Ok(())
     }
 
+    /// Save a checkpoint now, bypassing the continuous-work interval, and
+    /// bookmark its step with an optional label.
+    ///
+    /// Returns the task ID and bookmarked step, which `/fork` accepts.
+    pub(super) fn checkpoint_now(&mut self, label: Option<String>) -> Result<(String, usize)> {
+        if self.checkpoint_manager.is_none() {
+            self.checkpoint_manager = Some(Self::configured_checkpoint_manager(&self.config)?);
+        }
+        let task_description = self
+            .current_checkpoint
+            .as_ref()
+            .map(|c| c.task_description.clone())
+            .or_else(|| {
+                self.messages
+                    .iter()
+                    .find(|m| m.role == "user")
+                    .map(|m| m.content.text().chars().take(200).collect())
+            })
+            .unwrap_or_else(|| "Interactive session".to_string());
+
+        let task_id = self
+            .current_checkpoint
+            .as_ref()
+            .map(|c| c.task_id.clone())
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        let mut checkpoint = self.to_checkpoint(&task_id, &task_description);
+        checkpoint.add_bookmark(label);
+        self.current_checkpoint = Some(checkpoint);
+
+        let checkpoint = self.write_checkpoint(&task_description)?;
+        Ok((checkpoint.task_id.clone(), checkpoint.current_step))
+    }
+
+    /// `/checkpoint [label]`: save now and report how to get back here
+    pub(super) fn print_checkpoint_now(&mut self, label: Option<String>) {
+        match self.checkpoint_now(label) {
+            Ok((task_id, step)) => {
+                println!(
+                    "{} Checkpoint saved: {} at step {}",
+                    "🔖".bright_green(),
+                    task_id.bright_white(),
+                    step
+                );
+                println!("   Return to it with /fork {} {}", task_id.dimmed(), step);
+            }
+            Err(e) => println!("{} Checkpoint failed: {}", "✗".bright_red(), e),
+        }
+    }
+
+    /// Persist the current state
+    fn write_checkpoint(&mut self, task_description: &str) -> Result<&TaskCheckpoint> {
+        let Some(ref manager) = self.checkpoint_manager else {
+            anyhow::bail!("Checkpointing is unavailable");
+        };
+        let task_id = self
+            .current_checkpoint
+            .as_ref()
+            .map(|c| c.task_id.clone())
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+        let checkpoint = self.to_checkpoint(&task_id, task_description);
+        manager.save(&checkpoint)?;
+        self.last_checkpoint_tool_calls = checkpoint.tool_calls.len();
+        self.last_checkpoint_persisted_at = Instant::now();
+        self.checkpoint_persisted_once = true;
+        #[cfg(feature = "resilience")]
+        self.record_self_healing_checkpoint(task_description);
+        debug!("Checkpoint saved for task: {}", task_id);
+        Ok(self.current_checkpoint.insert(checkpoint))
+    }
+
     pub(super) fn should_persist_checkpoint(&self) -> bool {
         if !self.config.continuous_work.enabled {
             return true;
@@ -138,8 +278,8 @@ impl Agent {
             return true;
         }
 
-        let tools_interval = self.config.continuous_work.checkpoint_interval_tools;
-        let secs_interval = self.config.continuous_work.checkpoint_interval_secs;
+        let tools_interval = self.config.checkpoint_interval_tools();
+        let secs_interval = self.config.checkpoint_interval_secs();
 
         if tools_interval == 0 && secs_interval == 0 {
             return true;
@@ -296,6 +436,12 @@ impl Agent {
             );
         }
 
+        self.save_learning_state()
+    }
+
+    /// Persist the self-improvement engine and global episodic memory so
+    /// later sessions start from what this one learned.
+    pub(super) fn save_learning_state(&self) -> Result<()> {
         // Save global episodic memory — offloaded to a background thread
         // to avoid blocking the Tokio executor on synchronous filesystem I/O.
         let data_dir = dirs::data_local_dir()
@@ -312,6 +458,12 @@ impl Agent {
         } else {
             info!("Saved self-improvement engine state");
         }
+        if let Err(e) = self
+            .intelligence
+            .save(&data_dir.join("task_difficulty.json"))
+        {
+            warn!("Failed to save task difficulty calibration: {}", e);
+        }
 
         let bg_data_dir = data_dir.clone();
         std::thread::spawn(move || {
@@ -333,7 +485,31 @@ impl Agent {
     }
 
     /// Mark current task as failed
+    /// Save the task as `Paused` so it can be resumed later, regardless of the
+    /// continuous-work save interval.
+    pub(super) fn pause_checkpoint(&mut self, task_description: &str) -> Result<()> {
+        let task_id = self
+            .current_checkpoint
+            .as_ref()
+            .map(|c| c.task_id.clone())
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        let mut checkpoint = self.to_checkpoint(&task_id, task_description);
+        checkpoint.set_status(TaskStatus::Paused);
+        if let Some(ref manager) = self.checkpoint_manager {
+            manager.save(&checkpoint)?;
+            self.last_checkpoint_tool_calls = checkpoint.tool_calls.len();
+            self.last_checkpoint_persisted_at = Instant::now();
+            self.checkpoint_persisted_once = true;
+        }
+        self.current_checkpoint = Some(checkpoint);
+        Ok(())
+    }
+
     pub(super) fn fail_checkpoint(&mut self, reason: &str) -> Result<()> {
+        self.last_failure = Some(StepFailure {
+            step: self.loop_control.current_step(),
+            reason: reason.to_string(),
+        });
         if let Some(plan) = self.cognitive_state.active_tactical_plan.as_mut() {
             plan.status = crate::cognitive::StepStatus::Failed;
         }
@@ -411,6 +587,20 @@ impl Agent {
             return false;
         }
 
+        // Restoring a checkpoint would bring the dropped context straight back
+        if execution
+            .actions_executed
+            .iter()
+            .any(|a| a == "reduce_context")
+        {
+            let dropped = self.reduce_context_for_error(error);
+            info!(
+                "Self-healing dropped {} messages to retry a repeated failure with focused context",
+                dropped
+            );
+            return true;
+        }
+
         let restored = self.restore_from_self_healing_checkpoint();
         if restored {
             info!(
@@ -1231,6 +1421,7 @@ mod tests {
             new_memory_entries: vec![],
             new_tool_calls: vec![],
             new_errors: vec![],
+            new_reasoning: vec![],
             updated_tokens: None,
             git_checkpoint: None,
         };
@@ -1258,6 +1449,7 @@ mod tests {
             new_memory_entries: vec![],
             new_tool_calls: vec![],
             new_errors: vec![],
+            new_reasoning: vec![],
             updated_tokens: None,
             git_checkpoint: None,
         };
@@ -1304,6 +1496,7 @@ mod tests {
                 error: "applied error".to_string(),
                 recovered: true,
             }],
+            new_reasoning: vec![],
             updated_tokens: Some(9999),
             git_checkpoint: Some(GitCheckpointInfo {
                 branch: "dev".to_string(),
@@ -1349,6 +1542,7 @@ mod tests {
             new_memory_entries: vec![],
             new_tool_calls: vec![],
             new_errors: vec![],
+            new_reasoning: vec![],
             updated_tokens: None, // should not change
             git_checkpoint: None,
         };
@@ -1381,6 +1575,7 @@ mod tests {
             new_memory_entries: vec![],
             new_tool_calls: vec![],
             new_errors: vec![],
+            new_reasoning: vec![],
             updated_tokens: Some(15000),
             git_checkpoint: None,
         };
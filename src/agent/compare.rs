@@ -0,0 +1,396 @@
+//! `/compare`: the same prompt or task on two models
+//!
+//! By default each model answers one turn: the current conversation plus
+//! the task, sent to both at once through their own [`ApiClient`]s. With
+//! `--full` each model runs the whole task instead, one after the other in
+//! its own throwaway git worktree (tools resolve paths against the process
+//! working directory, so two runs cannot share it at the same time); the
+//! worktrees are kept for inspection.
+//!
+//! Outputs are shown side by side with time, tokens and estimated cost, and
+//! every comparison, with the user's verdict if they give one, is appended
+//! to the analytics log behind `selfware analytics leaderboard`.
+
+use std::time::{Duration, Instant};
+
+use colored::*;
+
+use super::*;
+use crate::devops::worktree::TaskWorktree;
+use crate::observability::analytics::{ComparisonRun, ModelComparison, ModelComparisonStore};
+
+/// Parsed `/compare [--full] <modelA> <modelB> <task>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct CompareArgs {
+    pub model_a: String,
+    pub model_b: String,
+    pub task: String,
+    pub full: bool,
+}
+
+/// Parse the arguments after `/compare`; `--full` may come anywhere before
+/// the task
+pub(super) fn parse_compare_args(args: &str) -> Option<CompareArgs> {
+    let mut full = false;
+    let mut models = Vec::new();
+    let mut rest = args.trim_start();
+    while models.len() < 2 {
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let word = &rest[..end];
+        if word.is_empty() {
+            return None;
+        }
+        if word == "--full" {
+            full = true;
+        } else {
+            models.push(word.to_string());
+        }
+        rest = rest[end..].trim_start();
+    }
+    if let Some(after) = rest.strip_prefix("--full") {
+        if after.is_empty() || after.starts_with(char::is_whitespace) {
+            full = true;
+            rest = after.trim_start();
+        }
+    }
+    let task = rest.trim();
+    if task.is_empty() {
+        return None;
+    }
+    let model_b = models.pop()?;
+    let model_a = models.pop()?;
+    Some(CompareArgs {
+        model_a,
+        model_b,
+        task: task.to_string(),
+        full,
+    })
+}
+
+/// What one model produced
+#[derive(Debug, Clone)]
+pub(super) struct ModelRun {
+    pub model: String,
+    pub output: String,
+    pub duration: Duration,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub success: bool,
+}
+
+impl ModelRun {
+    fn failed(model: &str, duration: Duration, err: &anyhow::Error) -> Self {
+        Self {
+            model: model.to_string(),
+            output: format!("Error: {:#}", err),
+            duration,
+            prompt_tokens: 0,
+            completion_tokens: 0,
+            success: false,
+        }
+    }
+
+    fn cost_usd(&self) -> f64 {
+        output::estimate_cost_usd(self.prompt_tokens, self.completion_tokens)
+    }
+
+    fn record(&self) -> ComparisonRun {
+        ComparisonRun {
+            model: self.model.clone(),
+            duration_ms: self.duration.as_millis() as u64,
+            prompt_tokens: self.prompt_tokens,
+            completion_tokens: self.completion_tokens,
+            cost_usd: self.cost_usd(),
+            success: self.success,
+        }
+    }
+}
+
+/// One turn on `client`, with native tool calls listed after the text
+async fn run_turn(
+    client: ApiClient,
+    model: String,
+    messages: Vec<Message>,
+    tools: Option<Vec<crate::api::types::ToolDefinition>>,
+    thinking: ThinkingMode,
+) -> ModelRun {
+    let started = Instant::now();
+    let response = match client.chat(messages, tools, thinking).await {
+        Ok(response) => response,
+        Err(e) => return ModelRun::failed(&model, started.elapsed(), &e),
+    };
+    let duration = started.elapsed();
+    let mut text = String::new();
+    if let Some(choice) = response.choices.first() {
+        text.push_str(choice.message.content.text().trim());
+        for call in choice.message.tool_calls.iter().flatten() {
+            text.push_str(&format!(
+                "\n-> {}({})",
+                call.function.name, call.function.arguments
+            ));
+        }
+    }
+    ModelRun {
+        model,
+        output: text.trim().to_string(),
+        duration,
+        prompt_tokens: response.usage.prompt_tokens as u64,
+        completion_tokens: response.usage.completion_tokens as u64,
+        success: true,
+    }
+}
+
+impl Agent {
+    /// Send the conversation plus `task` to both models at once
+    pub(super) async fn compare_turn(&self, args: &CompareArgs) -> Result<[ModelRun; 2]> {
+        let mut messages = self.messages.clone();
+        messages.push(Message::user(args.task.clone()));
+        let tools = self.api_tools();
+        let thinking = self.thinking_mode();
+
+        let (config_a, _) = self.config_for_model(&args.model_a)?;
+        let (config_b, _) = self.config_for_model(&args.model_b)?;
+        let client_a = ApiClient::new(&config_a)?;
+        let client_b = ApiClient::new(&config_b)?;
+
+        let (a, b) = tokio::join!(
+            run_turn(
+                client_a,
+                config_a.model,
+                messages.clone(),
+                tools.clone(),
+                thinking
+            ),
+            run_turn(client_b, config_b.model, messages, tools, thinking),
+        );
+        Ok([a, b])
+    }
+
+    /// Run `task` to completion on both models, each in its own worktree of
+    /// HEAD. The worktrees are left in place for inspection.
+    pub(super) async fn compare_full(&self, args: &CompareArgs) -> Result<[ModelRun; 2]> {
+        let a = self.run_in_worktree(&args.model_a, &args.task).await?;
+        let b = self.run_in_worktree(&args.model_b, &args.task).await?;
+        Ok([a, b])
+    }
+
+    async fn run_in_worktree(&self, model: &str, task: &str) -> Result<ModelRun> {
+        let (config, _) = self.config_for_model(model)?;
+        let mut worktree =
+            TaskWorktree::create(&std::env::current_dir()?, &format!("compare-{}", model))?;
+        println!(
+            "\n{} {} in {} on branch {}\n",
+            "⚖".bright_cyan(),
+            config.model.bright_white(),
+            worktree.path().display(),
+            worktree.branch()
+        );
+        worktree.enter()?;
+
+        let (prompt_before, completion_before) = output::get_total_tokens();
+        let started = Instant::now();
+        let run = async {
+            let mut agent = Agent::new(config.clone()).await?;
+            agent.run_task(task).await
+        }
+        .await;
+        let duration = started.elapsed();
+        let (prompt_after, completion_after) = output::get_total_tokens();
+        worktree.leave();
+
+        let mut run = match run {
+            Ok(result) => {
+                let mut text = format!("{:?} after {} steps", result.status, result.steps);
+                if !result.files_changed.is_empty() {
+                    text.push_str(&format!("\nChanged: {}", result.files_changed.join(", ")));
+                }
+                for error in result.errors.iter().filter(|e| !e.recovered) {
+                    text.push_str(&format!("\nError: {}", error.message));
+                }
+                ModelRun {
+                    model: config.model.clone(),
+                    output: text,
+                    duration,
+                    prompt_tokens: 0,
+                    completion_tokens: 0,
+                    success: result.succeeded(),
+                }
+            }
+            Err(e) => ModelRun::failed(&config.model, duration, &e),
+        };
+        run.prompt_tokens = prompt_after.saturating_sub(prompt_before);
+        run.completion_tokens = completion_after.saturating_sub(completion_before);
+        run.output.push_str(&format!(
+            "\nWorktree: {} (branch {})",
+            worktree.path().display(),
+            worktree.branch()
+        ));
+        Ok(run)
+    }
+}
+
+/// Two outputs in columns with their stats underneath, wrapped to `width`
+pub(super) fn render_side_by_side(runs: &[ModelRun; 2], width: usize) -> String {
+    let column = (width.saturating_sub(3) / 2).max(20);
+    let left = wrap(&runs[0].output, column);
+    let right = wrap(&runs[1].output, column);
+    let rule = "─".repeat(column);
+
+    let mut out = String::new();
+    out.push_str(&format!(
+        "{:<column$} │ {}\n",
+        truncate(&runs[0].model, column),
+        truncate(&runs[1].model, column)
+    ));
+    out.push_str(&format!("{}─┼─{}\n", rule, rule));
+    for i in 0..left.len().max(right.len()) {
+        let l = left.get(i).map(String::as_str).unwrap_or("");
+        let r = right.get(i).map(String::as_str).unwrap_or("");
+        let pad = column.saturating_sub(l.chars().count());
+        out.push_str(&format!("{}{} │ {}\n", l, " ".repeat(pad), r));
+    }
+    out.push_str(&format!("{}─┼─{}\n", rule, rule));
+    let stats = |run: &ModelRun| {
+        format!(
+            "{:.1}s, {} + {} tokens, ~${:.4}",
+            run.duration.as_secs_f64(),
+            run.prompt_tokens,
+            run.completion_tokens,
+            run.cost_usd()
+        )
+    };
+    let l = truncate(&stats(&runs[0]), column);
+    out.push_str(&format!("{:<column$} │ {}\n", l, stats(&runs[1])));
+    out
+}
+
+/// Word-wrap `text` to lines of at most `width` characters
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let mut word = word.to_string();
+            while word.chars().count() > width {
+                if !line.is_empty() {
+                    lines.push(std::mem::take(&mut line));
+                }
+                let head: String = word.chars().take(width).collect();
+                word = word.chars().skip(width).collect();
+                lines.push(head);
+            }
+            let needed =
+                line.chars().count() + word.chars().count() + usize::from(!line.is_empty());
+            if needed > width && !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&word);
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+fn truncate(text: &str, width: usize) -> String {
+    text.chars().take(width).collect()
+}
+
+/// Read a verdict: `1`/`2` (or a model name) picks a winner, `t` is a
+/// tie, anything else is no vote
+pub(super) fn parse_vote(answer: &str, runs: &[ModelRun; 2]) -> Option<Option<String>> {
+    let answer = answer.trim();
+    match answer.to_lowercase().as_str() {
+        "1" | "a" => Some(Some(runs[0].model.clone())),
+        "2" | "b" => Some(Some(runs[1].model.clone())),
+        "t" | "tie" | "=" => Some(None),
+        _ => runs
+            .iter()
+            .find(|r| !answer.is_empty() && r.model == answer)
+            .map(|r| Some(r.model.clone())),
+    }
+}
+
+/// Save a comparison and the user's verdict: the winning model, or `None`
+/// for a tie, or no vote at all
+pub(super) fn record_comparison(
+    args: &CompareArgs,
+    runs: &[ModelRun; 2],
+    vote: Option<Option<String>>,
+) -> Result<()> {
+    let mut comparison = ModelComparison::new(
+        &args.task,
+        args.full,
+        runs.iter().map(ModelRun::record).collect(),
+    );
+    if let Some(winner) = vote {
+        comparison.voted = true;
+        comparison.winner = winner;
+    }
+    ModelComparisonStore::new().record(&comparison)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_compare_args() {
+        assert_eq!(
+            parse_compare_args("qwen-7b llama-8b fix the parser"),
+            Some(CompareArgs {
+                model_a: "qwen-7b".to_string(),
+                model_b: "llama-8b".to_string(),
+                task: "fix the parser".to_string(),
+                full: false,
+            })
+        );
+        let full = parse_compare_args("--full a b  write tests --full-coverage").unwrap();
+        assert!(full.full);
+        assert_eq!(full.task, "write tests --full-coverage");
+        assert!(parse_compare_args("a b --full do it").unwrap().full);
+        assert_eq!(parse_compare_args("a b"), None);
+        assert_eq!(parse_compare_args("a"), None);
+    }
+
+    #[test]
+    fn test_parse_vote() {
+        let run = |model: &str| ModelRun {
+            model: model.to_string(),
+            output: String::new(),
+            duration: Duration::ZERO,
+            prompt_tokens: 0,
+            completion_tokens: 0,
+            success: true,
+        };
+        let runs = [run("qwen"), run("llama")];
+        assert_eq!(parse_vote("2", &runs), Some(Some("llama".to_string())));
+        assert_eq!(parse_vote("qwen\n", &runs), Some(Some("qwen".to_string())));
+        assert_eq!(parse_vote("t", &runs), Some(None));
+        assert_eq!(parse_vote("", &runs), None);
+    }
+
+    #[test]
+    fn test_render_side_by_side() {
+        let run = |model: &str, output: &str| ModelRun {
+            model: model.to_string(),
+            output: output.to_string(),
+            duration: Duration::from_millis(1500),
+            prompt_tokens: 100,
+            completion_tokens: 20,
+            success: true,
+        };
+        let runs = [
+            run("left", "one two three four five six"),
+            run("right", "short"),
+        ];
+        let text = render_side_by_side(&runs, 43);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], format!("{:<20} │ right", "left"));
+        assert_eq!(lines[2], "one two three four   │ short");
+        assert_eq!(lines[3], format!("{:<20} │ ", "five six"));
+        assert!(lines[5].starts_with("1.5s, 100 + 20 token │ 1.5s, 100 + 20 tokens, ~$"));
+    }
+}
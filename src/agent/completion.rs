@@ -0,0 +1,153 @@
+//! Explicit completion and "need a human" markers.
+//!
+//! By default a reply without tool calls ends the task. With
+//! `agent.completion_marker` set, such a reply only completes the task when
+//! it carries the marker; otherwise the model is asked to keep working or
+//! say it is done. `agent.blocked_marker` lets the model pause the task and
+//! ask the user instead of guessing or looping.
+
+use crate::config::AgentConfig;
+
+/// What a reply without tool calls says about the task.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum CompletionSignal {
+    /// No completion marker is configured; the reply ends the task.
+    Implicit,
+    /// The reply carries the completion marker.
+    Complete,
+    /// A completion marker is configured but the reply lacks it.
+    Unmarked,
+    /// The reply carries the blocked marker; the text is what the model
+    /// needs from the user.
+    Blocked(String),
+}
+
+fn marker(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|m| !m.is_empty())
+}
+
+/// Classify a reply that made no tool calls. The blocked marker wins when
+/// both appear, since completing a task the model is stuck on is worse.
+pub(super) fn detect(content: &str, config: &AgentConfig) -> CompletionSignal {
+    if let Some(blocked) = marker(&config.blocked_marker) {
+        if content.contains(blocked) {
+            let request = strip_markers(content, config);
+            return CompletionSignal::Blocked(if request.is_empty() {
+                "The agent needs input to continue.".to_string()
+            } else {
+                request
+            });
+        }
+    }
+    match marker(&config.completion_marker) {
+        None => CompletionSignal::Implicit,
+        Some(complete) if content.contains(complete) => CompletionSignal::Complete,
+        Some(_) => CompletionSignal::Unmarked,
+    }
+}
+
+/// The reply with any markers removed, for showing to the user.
+pub(super) fn strip_markers(content: &str, config: &AgentConfig) -> String {
+    let mut text = content.to_string();
+    for m in [&config.completion_marker, &config.blocked_marker]
+        .into_iter()
+        .filter_map(marker)
+    {
+        text = text.replace(m, "");
+    }
+    text.trim().to_string()
+}
+
+/// System prompt section describing the configured markers.
+pub(super) fn prompt_section(config: &AgentConfig) -> Option<String> {
+    let complete = marker(&config.completion_marker);
+    let blocked = marker(&config.blocked_marker);
+    if complete.is_none() && blocked.is_none() {
+        return None;
+    }
+    let mut section = String::from("\n\n## Ending a Task\n");
+    if let Some(complete) = complete {
+        section.push_str(&format!(
+            "- When the task is done and verified, reply without tool calls and include {} \
+             in your final message. Replies without it do not end the task.\n",
+            complete
+        ));
+    }
+    if let Some(blocked) = blocked {
+        section.push_str(&format!(
+            "- If you cannot continue without a decision or information only the user has, \
+             reply without tool calls, say exactly what you need, and include {}. \
+             The task pauses until the user answers.\n",
+            blocked
+        ));
+    }
+    Some(section)
+}
+
+/// Sent back when a reply without tool calls lacks the completion marker.
+pub(super) fn unmarked_reply_prompt(config: &AgentConfig) -> String {
+    format!(
+        "You replied without calling a tool, but the task is not marked complete. \
+         If the task is done and verified, reply with {}. Otherwise continue working \
+         with the appropriate tools.",
+        marker(&config.completion_marker).unwrap_or_default()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(complete: Option<&str>, blocked: Option<&str>) -> AgentConfig {
+        AgentConfig {
+            completion_marker: complete.map(String::from),
+            blocked_marker: blocked.map(String::from),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_detect_completion_signals() {
+        let none = config(None, None);
+        assert_eq!(detect("All done.", &none), CompletionSignal::Implicit);
+        assert!(prompt_section(&none).is_none());
+
+        let markers = config(Some("<task_complete/>"), Some("<need_human/>"));
+        assert_eq!(
+            detect("Tests pass. <task_complete/>", &markers),
+            CompletionSignal::Complete
+        );
+        assert_eq!(
+            detect("I think that's it.", &markers),
+            CompletionSignal::Unmarked
+        );
+        assert_eq!(
+            detect(
+                "Which database should I target? <need_human/> <task_complete/>",
+                &markers
+            ),
+            CompletionSignal::Blocked("Which database should I target?".to_string())
+        );
+        assert_eq!(
+            detect("<need_human/>", &markers),
+            CompletionSignal::Blocked("The agent needs input to continue.".to_string())
+        );
+
+        let section = prompt_section(&markers).unwrap();
+        assert!(section.contains("<task_complete/>"));
+        assert!(section.contains("<need_human/>"));
+    }
+
+    #[test]
+    fn test_blank_markers_are_ignored() {
+        let blank = config(Some("  "), Some(""));
+        assert_eq!(detect("Done.", &blank), CompletionSignal::Implicit);
+        assert_eq!(
+            strip_markers(
+                "Done. <task_complete/>",
+                &config(Some("<task_complete/>"), None)
+            ),
+            "Done."
+        );
+    }
+}
@@ -49,6 +49,7 @@ impl ContextCompressor {
             .sum()
     }
 
+    #[tracing::instrument(name = "context.compress", skip_all)]
     pub async fn compress(&self, client: &ApiClient, messages: &[Message]) -> Result<Vec<Message>> {
         if messages.len() <= self.min_messages_to_keep + 1 {
             warn!("Too few messages to compress, returning as-is");
@@ -127,6 +128,66 @@ impl ContextCompressor {
         Ok(compressed)
     }
 
+    /// Ask the model for a short list of the decisions and constraints
+    /// established so far, merged with any previously pinned note.
+    pub async fn extract_decisions(
+        &self,
+        client: &ApiClient,
+        messages: &[Message],
+        existing: Option<&str>,
+    ) -> Result<String> {
+        let transcript = messages
+            .iter()
+            .skip(1)
+            .map(|m| {
+                let content = if m.content.chars().count() > 500 {
+                    format!(
+                        "{}...[truncated]",
+                        m.content.chars().take(500).collect::<String>()
+                    )
+                } else {
+                    m.content.text().to_string()
+                };
+                format!("{}: {}", m.role, content)
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        let previous = existing
+            .map(|note| {
+                format!(
+                    "Previously pinned note (keep what still holds):\n{}\n\n",
+                    note
+                )
+            })
+            .unwrap_or_default();
+
+        let request = vec![
+            Message::system("You record the durable decisions of a coding session. Reply with a terse bullet list of decisions made, constraints agreed, and approaches ruled out (with the reason). No preamble, no routine progress, at most 15 bullets."),
+            Message::user(format!(
+                "{}Conversation:\n{}\n\nList the decisions and constraints so far.",
+                previous, transcript
+            )),
+        ];
+
+        let response = tokio::time::timeout(
+            std::time::Duration::from_secs(120),
+            client.chat(request, None, ThinkingMode::Disabled),
+        )
+        .await
+        .map_err(|_| anyhow::anyhow!("Decision extraction API call timed out after 120s"))??;
+
+        let note = response
+            .choices
+            .first()
+            .map(|c| c.message.content.text().trim().to_string())
+            .unwrap_or_default();
+        if note.is_empty() {
+            anyhow::bail!("Decision extraction returned an empty response");
+        }
+        info!("Extracted pinned decisions: {} chars", note.len());
+        Ok(note)
+    }
+
     pub fn hard_compress(&self, messages: &[Message]) -> Vec<Message> {
         let mut result = Vec::new();
         if let Some(first) = messages.first() {
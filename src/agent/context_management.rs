@@ -1,9 +1,77 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use colored::*;
 use regex::Regex;
 use serde_json::Value;
 
 use super::*;
+use crate::observability::carbon_tracker::{GridIntensity, SessionFootprint};
+
+/// Result of a `/model` hot-swap.
+#[derive(Debug, Clone)]
+pub struct ModelSwitch {
+    pub previous: String,
+    pub model: String,
+    pub endpoint: String,
+    pub context_length: usize,
+    /// Estimated tokens in the conversation carried over to the new model
+    pub current_tokens: usize,
+}
+
+/// Render a pinned note as the section appended to the system prompt.
+fn pinned_section(note: &str) -> String {
+    format!("\n\n## Pinned Decisions and Constraints\n\n{}", note)
+}
+
+/// The command an editor setting like `code --wait` names: its program and
+/// leading arguments, split the way a shell would.
+fn editor_command(editor: &str) -> Result<std::process::Command> {
+    let words = shlex::split(editor).unwrap_or_default();
+    let (program, args) = words
+        .split_first()
+        .with_context(|| format!("Cannot run editor '{}'", editor))?;
+    let mut command = std::process::Command::new(program);
+    command.args(args);
+    Ok(command)
+}
+
+/// Header line that marks a user message as file content from `/ctx load`.
+pub(super) const FILE_CONTENT_MARKER: &str = "// FILE: ";
+
+/// Estimated tokens for one message: text plus a fixed per-message overhead
+/// and a flat estimate per image.
+pub(super) fn message_tokens(message: &Message) -> usize {
+    crate::token_count::estimate_tokens_with_overhead(&message.content.text_all(), 4)
+        + message.content.image_count() * crate::tokens::DEFAULT_IMAGE_TOKEN_ESTIMATE
+}
+
+/// Estimated tokens per bucket for the messages sent on the next request.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TokenBreakdown {
+    pub system: usize,
+    pub user: usize,
+    pub assistant: usize,
+    pub tool: usize,
+    /// User messages holding file content loaded with `/ctx load`
+    pub files: usize,
+}
+
+impl TokenBreakdown {
+    /// Tokens in user, assistant and tool messages, excluding loaded files.
+    pub fn conversation(&self) -> usize {
+        self.user + self.assistant + self.tool
+    }
+
+    pub fn total(&self) -> usize {
+        self.system + self.files + self.conversation()
+    }
+}
+
+impl ModelSwitch {
+    /// Whether the carried-over conversation no longer fits the new window.
+    pub fn exceeds_window(&self) -> bool {
+        self.current_tokens > self.context_length
+    }
+}
 
 impl Agent {
     // =========================================================================
@@ -13,28 +81,121 @@ impl Agent {
     /// Trim the message history so total estimated tokens stay within
     /// `max_context_tokens`. Removes the oldest non-system messages first.
     pub(super) fn trim_message_history(&mut self) {
-        let total: usize = self
-            .messages
-            .iter()
-            .map(|m| {
-                crate::token_count::estimate_tokens_with_overhead(&m.content.text_all(), 4)
-                    + m.content.image_count() * crate::tokens::DEFAULT_IMAGE_TOKEN_ESTIMATE
-            })
-            .sum();
-        if total <= self.max_context_tokens {
+        let Some(keep) = self.trim_keep_mask() else {
             return;
+        };
+        self.rag.forget_inserted();
+
+        // Retain only the messages we decided to keep (single O(N) pass).
+        let mut idx = 0;
+        self.messages.retain(|_| {
+            let k = keep[idx];
+            idx += 1;
+            k
+        });
+    }
+
+    /// Shrink the context after the backend rejected a request as too long.
+    /// The token estimate evidently undercounts for this model, so the
+    /// budget is lowered below what was sent before trimming to it.
+    /// Returns the estimated tokens dropped.
+    pub(super) fn shrink_context_after_overflow(&mut self) -> usize {
+        const MIN_BUDGET: usize = 4_000;
+        let before = self.estimate_messages_tokens();
+        self.max_context_tokens = self.max_context_tokens.min(before * 3 / 4).max(MIN_BUDGET);
+        self.trim_message_history();
+        if !self.messages.iter().any(|m| m.role != "system") {
+            self.messages.push(Message::user(
+                "[Earlier context was dropped to fit the model's context window; \
+                 continue the task]",
+            ));
+        }
+        before.saturating_sub(self.estimate_messages_tokens())
+    }
+
+    /// Shed context a repeating failure does not need before retrying it:
+    /// loaded files the error does not mention and all but the most recent
+    /// turns, keeping system messages and the task itself. Ends with a
+    /// prompt naming the error. Returns the number of messages dropped.
+    #[cfg(feature = "resilience")]
+    pub(super) fn reduce_context_for_error(&mut self, error: &str) -> usize {
+        const KEEP_RECENT: usize = 6;
+
+        let mentioned = |path: &str| {
+            error.contains(path)
+                || std::path::Path::new(path)
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| error.contains(n))
+        };
+
+        let mut keep = vec![true; self.messages.len()];
+        let mut conversation = Vec::new();
+        for (i, m) in self.messages.iter().enumerate() {
+            if m.role == "system" {
+                continue;
+            }
+            let loaded_path = (m.role == "user")
+                .then(|| {
+                    m.content
+                        .text()
+                        .lines()
+                        .find_map(|l| l.strip_prefix(FILE_CONTENT_MARKER))
+                })
+                .flatten();
+            match loaded_path {
+                Some(path) => keep[i] = mentioned(path.trim()),
+                None => conversation.push(i),
+            }
         }
+        self.context_files.retain(|p| mentioned(p));
+
+        // The first user message is the task; past it only recent turns
+        // survive, never starting on a tool result whose call was dropped.
+        let task = conversation
+            .iter()
+            .copied()
+            .find(|&i| self.messages[i].role == "user");
+        let mut recent_from = conversation.len().saturating_sub(KEEP_RECENT);
+        while recent_from < conversation.len()
+            && self.messages[conversation[recent_from]].role == "tool"
+        {
+            recent_from += 1;
+        }
+        for &i in &conversation[..recent_from] {
+            if Some(i) != task {
+                keep[i] = false;
+            }
+        }
+
+        let before = self.messages.len();
+        let mut idx = 0;
+        self.messages.retain(|_| {
+            let k = keep[idx];
+            idx += 1;
+            k
+        });
+        self.rag.forget_inserted();
+        let dropped = before - self.messages.len();
+
+        self.messages.push(Message::user(format!(
+            "The same error keeps recurring:\n\n{}\n\nUnrelated earlier context was \
+             dropped. Focus on fixing this specific error before anything else, and \
+             try a different approach from the attempts that produced it.",
+            error
+        )));
+        dropped
+    }
 
+    /// Which messages survive trimming, or `None` when everything fits.
+    pub(super) fn trim_keep_mask(&self) -> Option<Vec<bool>> {
         // Collect per-message token counts once (O(N)) instead of recomputing
         // every iteration.
-        let token_counts: Vec<usize> = self
-            .messages
-            .iter()
-            .map(|m| {
-                crate::token_count::estimate_tokens_with_overhead(&m.content.text_all(), 4)
-                    + m.content.image_count() * crate::tokens::DEFAULT_IMAGE_TOKEN_ESTIMATE
-            })
-            .collect();
+        let token_counts: Vec<usize> = self.messages.iter().map(message_tokens).collect();
+        let total: usize = token_counts.iter().sum();
+        if total <= self.max_context_tokens {
+            return None;
+        }
 
         // Walk non-system messages oldest-first and mark them for removal until
         // the total fits within budget.
@@ -49,28 +210,35 @@ impl Agent {
                 remaining -= tokens;
             }
         }
+        Some(keep)
+    }
 
-        // Retain only the messages we decided to keep (single O(N) pass).
-        let mut idx = 0;
-        self.messages.retain(|_| {
-            let k = keep[idx];
-            idx += 1;
-            k
-        });
+    /// Estimated tokens by role for the messages the next request will send,
+    /// i.e. after trimming. File content loaded with `/ctx load` is counted
+    /// separately from the conversation.
+    pub(super) fn token_breakdown(&self) -> TokenBreakdown {
+        let keep = self.trim_keep_mask();
+        let mut breakdown = TokenBreakdown::default();
+        for (i, message) in self.messages.iter().enumerate() {
+            if keep.as_ref().is_some_and(|keep| !keep[i]) {
+                continue;
+            }
+            let tokens = message_tokens(message);
+            let bucket = match message.role.as_str() {
+                "system" => &mut breakdown.system,
+                "user" if message.content.contains(FILE_CONTENT_MARKER) => &mut breakdown.files,
+                "user" => &mut breakdown.user,
+                "assistant" => &mut breakdown.assistant,
+                _ => &mut breakdown.tool,
+            };
+            *bucket += tokens;
+        }
+        breakdown
     }
 
     /// Estimate total tokens from accumulated messages (the actual context sent to API)
     pub(super) fn estimate_messages_tokens(&self) -> usize {
-        self.messages
-            .iter()
-            .map(|m| {
-                let text_tokens =
-                    crate::token_count::estimate_tokens_with_overhead(&m.content.text_all(), 4);
-                let image_tokens =
-                    m.content.image_count() * crate::tokens::DEFAULT_IMAGE_TOKEN_ESTIMATE;
-                text_tokens + image_tokens
-            })
-            .sum()
+        self.messages.iter().map(message_tokens).sum()
     }
 
     /// Get the best estimate of total tokens used
@@ -84,6 +252,21 @@ impl Agent {
         api_tokens.max(msg_tokens).max(mem_tokens)
     }
 
+    /// Estimated energy and CO2 for the tokens processed this session plus
+    /// the compute of processes tools spawned. Reads as zero when
+    /// `carbon.enabled` is off.
+    pub(super) fn session_footprint(&self) -> SessionFootprint {
+        let carbon = &self.config.carbon;
+        if !carbon.enabled {
+            return SessionFootprint::default();
+        }
+        SessionFootprint::from_tokens(
+            self.total_tokens_used() as u64,
+            carbon.wh_per_1k_tokens,
+            GridIntensity::Custom(carbon.grams_per_kwh),
+        ) + self.carbon.compute_footprint()
+    }
+
     pub(super) fn context_usage_pct(&self) -> f64 {
         let tokens = self.total_tokens_used();
         let window = self.memory.context_window();
@@ -93,6 +276,39 @@ impl Agent {
         (tokens as f64 / window as f64 * 100.0).min(100.0)
     }
 
+    /// Snapshot of the status bar and `/stats` figures plus plan progress,
+    /// for the live dashboard
+    pub(super) fn run_stats(&self) -> crate::observability::dashboard::RunStats {
+        use crate::cognitive::state::StepStatus;
+
+        let (prompt_tokens, completion_tokens) = output::get_total_tokens();
+        let steps = self
+            .cognitive_state
+            .active_operational_plan
+            .as_ref()
+            .map(|plan| plan.steps.as_slice())
+            .unwrap_or_default();
+        crate::observability::dashboard::RunStats {
+            context_tokens: self.total_tokens_used(),
+            context_window: self.memory.context_window(),
+            prompt_tokens,
+            completion_tokens,
+            cost_usd: output::estimate_cost_usd(prompt_tokens, completion_tokens),
+            footprint: self.session_footprint(),
+            phase: self.cognitive_state.cycle_phase.as_str().to_string(),
+            step: self.loop_control.current_step(),
+            plan_done: steps
+                .iter()
+                .filter(|s| matches!(s.status, StepStatus::Completed | StepStatus::Skipped))
+                .count(),
+            plan_total: steps.len(),
+            plan_current: steps
+                .iter()
+                .find(|s| s.status == StepStatus::InProgress)
+                .map(|s| s.description.clone()),
+        }
+    }
+
     /// Print a Qwen Code-style status bar line before the prompt
     ///
     /// Layout: `  ? for shortcuts                            45.2% context used`
@@ -122,6 +338,9 @@ impl Agent {
 
         // Get cost from actual API usage
         let cost = tokens as f64 * 0.000003; // rough estimate
+        let footprint = self
+            .session_footprint()
+            .label(crate::ui::style::is_ascii_mode());
 
         // Model name
         let model_name = &self.config.model;
@@ -144,12 +363,27 @@ impl Agent {
             .map(|(w, _)| w as usize)
             .unwrap_or(80);
 
+        // Only shown while the API circuit breaker is tripped
+        let circuit = self.circuit_label().map(|l| format!(" {}", l));
+        let circuit = circuit.unwrap_or_default();
+        // Only shown while host memory/CPU is under pressure
+        let sample = self.pressure.last();
+        let pressure = sample
+            .and_then(|sample| sample.label())
+            .map(|l| format!(" ⚠ {}", l))
+            .unwrap_or_default();
+        let pressure_colored = if sample.is_some_and(|sample| sample.pause) {
+            pressure.bright_red()
+        } else {
+            pressure.bright_yellow()
+        };
+
         // Left side: mode + hint
         let left = format!("[{}] ? for shortcuts", mode);
         // Right side: bar + percentage + tokens + cost
         let right = format!(
-            "{} {:.1}% ({:.1}k/{:.0}k) ${:.2} [{}]",
-            bar, pct, k_tokens, k_window, cost, short_model
+            "{} {:.1}% ({:.1}k/{:.0}k) ${:.2} {} [{}]{}{}",
+            bar, pct, k_tokens, k_window, cost, footprint, short_model, circuit, pressure
         );
 
         // Pad middle with spaces
@@ -167,7 +401,7 @@ impl Agent {
         };
 
         println!(
-            " {} {}{}  {} {:.1}% ({:.1}k/{:.0}k) {} [{}]",
+            " {} {}{}  {} {:.1}% ({:.1}k/{:.0}k) {} {} [{}]{}{}",
             mode_colored,
             "? for shortcuts".dimmed(),
             " ".repeat(padding),
@@ -176,10 +410,28 @@ impl Agent {
             k_tokens,
             k_window,
             format!("${:.2}", cost).dimmed(),
+            footprint.dimmed(),
             short_model.dimmed(),
+            circuit.bright_red(),
+            pressure_colored,
         );
     }
 
+    /// Short description of the API circuit breaker, or `None` while it is closed.
+    pub(super) fn circuit_label(&self) -> Option<String> {
+        use crate::supervision::circuit_breaker::CircuitState;
+
+        let circuit = self.client.circuit_state();
+        match circuit.state {
+            CircuitState::Closed => None,
+            CircuitState::Open => Some(format!(
+                "⚡ API circuit open (retry in {}s)",
+                circuit.retry_in.unwrap_or_default().as_secs()
+            )),
+            CircuitState::HalfOpen => Some("⚡ API circuit half-open".to_string()),
+        }
+    }
+
     /// Show compact startup context line (Claude Code style)
     pub(super) fn show_startup_context(&self) {
         let tokens = self.total_tokens_used();
@@ -500,6 +752,7 @@ impl Agent {
         self.messages.retain(|m| m.role == "system");
         self.memory.clear();
         self.context_files.clear();
+        self.rag.forget_inserted();
     }
 
     /// Load files matching pattern into context
@@ -671,7 +924,7 @@ impl Agent {
         // Remove only messages that contain file content (// FILE: headers)
         // Keep all conversation messages intact
         self.messages
-            .retain(|m| !(m.role == "user" && m.content.contains("// FILE: ")));
+            .retain(|m| !(m.role == "user" && m.content.contains(FILE_CONTENT_MARKER)));
 
         let mut loaded = 0;
         for path_str in &files {
@@ -776,6 +1029,12 @@ impl Agent {
     /// Expand @file references in input (e.g., "@src/main.rs" becomes file content)
     /// Also supports @directory/ to include a directory tree (max depth 3)
     /// Returns the expanded input and the list of files that were included
+    ///
+    /// When the references together exceed `max_reference_tokens`, the
+    /// smaller ones stay verbatim and the largest are cut down to the
+    /// sections most relevant to the rest of the message; their entries in
+    /// the returned list say how much was kept.
+    #[tracing::instrument(name = "context.references", skip_all)]
     pub(super) fn expand_file_references(&self, input: &str) -> (String, Vec<String>) {
         use std::fs;
         use std::sync::LazyLock;
@@ -786,9 +1045,7 @@ impl Agent {
                 .expect("Invalid file reference regex")
         });
 
-        let mut expanded = input.to_string();
-        let mut included_files = Vec::new();
-
+        let mut references = Vec::new();
         for caps in FILE_REF_RE.captures_iter(input) {
             let Some(full_match) = caps.get(0).map(|m| m.as_str()) else {
                 continue;
@@ -796,53 +1053,120 @@ impl Agent {
             let Some(file_path) = caps.get(1).map(|m| m.as_str()) else {
                 continue;
             };
+            // URLs are expanded by expand_url_references
+            if file_path.starts_with("http://") || file_path.starts_with("https://") {
+                continue;
+            }
             let path = std::path::Path::new(file_path);
 
-            if path.is_dir() {
-                // Directory reference: include tree listing + file contents (max depth 3)
-                let mut dir_content = format!("Directory tree for {}:\n```\n", file_path);
-                let mut file_count = 0;
-                for entry in walkdir::WalkDir::new(file_path)
+            let body = if path.is_dir() {
+                // Directory reference: include tree listing (max depth 3)
+                let files = walkdir::WalkDir::new(file_path)
                     .max_depth(3)
                     .into_iter()
                     .filter_map(|e| e.ok())
+                    .filter(|entry| entry.file_type().is_file())
+                    .map(|entry| entry.path().display().to_string())
+                    .filter(|display| {
+                        !(display.contains("/target/")
+                            || display.contains("\\target\\")
+                            || display.contains("/.git/")
+                            || display.contains("\\.git\\")
+                            || display.contains("/node_modules/")
+                            || display.contains("\\node_modules\\"))
+                    })
+                    .collect();
+                ReferenceBody::Directory(files)
+            } else if let Ok(content) = fs::read_to_string(file_path) {
+                ReferenceBody::File(content)
+            } else {
+                continue;
+            };
+            references.push(FileReference {
+                full_match: full_match.to_string(),
+                path: file_path.to_string(),
+                tokens: body.tokens(),
+                body,
+            });
+        }
+
+        let query = FILE_REF_RE.replace_all(input, " ");
+        let shares = reference_shares(&references, self.config.max_reference_tokens);
+        let mut expanded = input.to_string();
+        let mut included_files = Vec::new();
+        for (reference, share) in references.iter().zip(shares) {
+            let (block, label) = reference.render(share, &query);
+            expanded = expanded.replacen(&reference.full_match, &block, 1);
+            included_files.push(label);
+        }
+
+        (expanded, included_files)
+    }
+
+    /// Expand @https://... references in `input` into the page's readable
+    /// text, applied to `expanded` (the input after `expand_file_references`).
+    /// Fetches follow the browser tools' target rules, are redacted and
+    /// capped, and are cached for the session. A reference that is blocked
+    /// or fails stays as literal text with a warning.
+    /// Returns the expanded input and the list of URLs that were included
+    pub(super) async fn expand_url_references(
+        &mut self,
+        input: &str,
+        expanded: String,
+    ) -> (String, Vec<String>) {
+        use std::sync::LazyLock;
+
+        static URL_REF_RE: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(r#"@(https?://[^\s<>"'`]+)"#).expect("Invalid URL reference regex")
+        });
+        const URL_FETCH_TIMEOUT_SECS: u64 = 20;
+        const MAX_URL_CHARS: usize = 20_000;
+
+        let mut expanded = expanded;
+        let mut included_urls = Vec::new();
+
+        for caps in URL_REF_RE.captures_iter(input) {
+            let Some(raw) = caps.get(1).map(|m| m.as_str()) else {
+                continue;
+            };
+            // Sentence punctuation after a URL is not part of it
+            let url = raw.trim_end_matches(['.', ',', ';', ':', '!', '?', ')', ']']);
+            let reference = format!("@{}", url);
+
+            let text = match self.url_cache.get(url) {
+                Some(text) => text.clone(),
+                None => match crate::tools::browser::fetch_page_text(url, URL_FETCH_TIMEOUT_SECS)
+                    .await
                 {
-                    let entry_path = entry.path();
-                    let display = entry_path.display().to_string();
-                    if display.contains("/target/")
-                        || display.contains("\\target\\")
-                        || display.contains("/.git/")
-                        || display.contains("\\.git\\")
-                        || display.contains("/node_modules/")
-                        || display.contains("\\node_modules\\")
-                    {
-                        continue;
+                    Ok(text) => {
+                        let text = crate::safety::redact::redact_secrets(&text);
+                        let text = if text.chars().count() > MAX_URL_CHARS {
+                            let kept: String = text.chars().take(MAX_URL_CHARS).collect();
+                            format!("{}\n[truncated at {} chars]", kept, MAX_URL_CHARS)
+                        } else {
+                            text.into_owned()
+                        };
+                        self.url_cache.insert(url.to_string(), text.clone());
+                        text
                     }
-                    if entry.file_type().is_file() {
-                        dir_content.push_str(&format!("  {}\n", display));
-                        file_count += 1;
+                    Err(e) => {
+                        println!("{} Could not include {}: {}", "⚠".bright_yellow(), url, e);
+                        continue;
                     }
-                }
-                dir_content.push_str("```\n");
-                expanded = expanded.replacen(full_match, &dir_content, 1);
-                included_files.push(format!(
-                    "{}/ ({} files)",
-                    file_path.trim_end_matches('/'),
-                    file_count
-                ));
-            } else if let Ok(content) = fs::read_to_string(file_path) {
-                let file_block = format!(
-                    "\n```{} ({})\n{}\n```\n",
-                    file_path,
-                    Self::format_file_size(content.len()),
-                    content.trim()
-                );
-                expanded = expanded.replacen(full_match, &file_block, 1);
-                included_files.push(file_path.to_string());
-            }
+                },
+            };
+
+            let url_block = format!(
+                "\n```text {} ({})\n{}\n```\n",
+                url,
+                Self::format_file_size(text.len()),
+                text.trim()
+            );
+            expanded = expanded.replacen(&reference, &url_block, 1);
+            included_urls.push(url.to_string());
         }
 
-        (expanded, included_files)
+        (expanded, included_urls)
     }
 
     /// Format file size for display
@@ -937,6 +1261,29 @@ impl Agent {
             "  {}│{}                                                                    {}│{}",
             patina, reset, patina, reset
         );
+        let breakdown = self.token_breakdown();
+        println!(
+            "  {}│{}  {bold}{}◈ NEXT REQUEST{}{:<43}    {}│{}",
+            patina, reset, rust, reset, "", patina, reset
+        );
+        for (label, value) in [
+            ("Total", breakdown.total()),
+            ("System", breakdown.system),
+            ("Files Loaded", breakdown.files),
+            ("Conversation", breakdown.conversation()),
+            ("  User", breakdown.user),
+            ("  Assistant", breakdown.assistant),
+            ("  Tool", breakdown.tool),
+        ] {
+            println!(
+                "  {}│{}     {:<15} {:>8} tokens                             {}│{}",
+                patina, reset, label, value, patina, reset
+            );
+        }
+        println!(
+            "  {}│{}                                                                    {}│{}",
+            patina, reset, patina, reset
+        );
         println!(
             "  {}│{}  {bold}{}⊡ MEMORY{}{:<49}    {}│{}",
             patina, reset, sand, reset, "", patina, reset
@@ -965,6 +1312,77 @@ impl Agent {
             "  {}│{}                                                                    {}│{}",
             patina, reset, patina, reset
         );
+        let footprint = self.session_footprint();
+        println!(
+            "  {}│{}  {bold}{}◇ FOOTPRINT{}{:<46}    {}│{}",
+            patina, reset, sand, reset, "", patina, reset
+        );
+        println!(
+            "  {}│{}     Energy          {:>8.2} Wh                                 {}│{}",
+            patina, reset, footprint.energy_wh, patina, reset
+        );
+        println!(
+            "  {}│{}     CO2             {:>8.2} g                                  {}│{}",
+            patina, reset, footprint.co2e_grams, patina, reset
+        );
+        if self.config.carbon.enabled {
+            for tool in self.carbon.compute_by_tool().iter().take(5) {
+                // "~" marks wall-clock estimates where child CPU time was unavailable
+                let secs = format!(
+                    "{}{:.1}s",
+                    if tool.low_confidence { "~" } else { "" },
+                    tool.cpu_secs
+                );
+                println!(
+                    "  {}│{}       {:<14.14}{:>8.2} Wh  {:>8}  ×{:<6}              {}│{}",
+                    patina, reset, tool.tool, tool.energy_wh, secs, tool.calls, patina, reset
+                );
+            }
+        }
+        println!(
+            "  {}│{}                                                                    {}│{}",
+            patina, reset, patina, reset
+        );
+        let circuit = self.client.circuit_state();
+        println!(
+            "  {}│{}  {bold}{}◆ API CIRCUIT{}{:<43}    {}│{}",
+            patina, reset, rust, reset, "", patina, reset
+        );
+        println!(
+            "  {}│{}     State           {:>8}                                    {}│{}",
+            patina, reset, circuit.state, patina, reset
+        );
+        println!(
+            "  {}│{}     Failures        {:>8}  (threshold: {}, total: {:<6})       {}│{}",
+            patina,
+            reset,
+            circuit.failure_count,
+            circuit.failure_threshold,
+            circuit.total_failures,
+            patina,
+            reset
+        );
+        if let Some(retry_in) = circuit.retry_in {
+            println!(
+                "  {}│{}     Retry In        {:>7}s  (/reset-circuit to retry now)      {}│{}",
+                patina,
+                reset,
+                retry_in.as_secs(),
+                patina,
+                reset
+            );
+        }
+        if let Some(err) = &circuit.last_error {
+            let err: String = err.chars().take(41).collect();
+            println!(
+                "  {}│{}     Last Error      {:<41}   {}│{}",
+                patina, reset, err, patina, reset
+            );
+        }
+        println!(
+            "  {}│{}                                                                    {}│{}",
+            patina, reset, patina, reset
+        );
         println!(
             "  {}│{}  {bold}{}≋ MODE{}{:<50}    {}│{}",
             patina, reset, worn, reset, "", patina, reset
@@ -990,6 +1408,104 @@ impl Agent {
         println!();
     }
 
+    /// Whether the current model's profile lists vision among its modalities
+    pub(super) fn model_supports_vision(&self) -> bool {
+        self.config
+            .profile_for_switch(&self.config.model)
+            .supports_vision()
+    }
+
+    /// This session's config pointed at another model, resolved through the
+    /// configured model profiles
+    pub(super) fn config_for_model(
+        &self,
+        name: &str,
+    ) -> Result<(Config, crate::config::ModelProfile)> {
+        let profile = self.config.profile_for_switch(name);
+
+        let mut config = self.config.clone();
+        config.endpoint = profile.endpoint.clone();
+        config.model = profile.model.clone();
+        config.api_key = profile.api_key.clone();
+        config.max_tokens = profile.max_tokens;
+        config.temperature = profile.temperature;
+        config.validate()?;
+        Ok((config, profile))
+    }
+
+    /// Rebuild the API client for another model, keeping the conversation.
+    ///
+    /// The target is resolved through the configured model profiles, so a
+    /// profile with its own endpoint or API key is honoured. The context
+    /// budget follows the new model's window.
+    pub(super) fn switch_model(&mut self, name: &str) -> Result<ModelSwitch> {
+        let (config, profile) = self.config_for_model(name)?;
+
+        let client = ApiClient::new(&config)?;
+        self.tools
+            .register(crate::tools::fim::FileFimEdit::new(std::sync::Arc::new(
+                client.clone(),
+            )));
+
+        let previous = std::mem::replace(&mut self.config, config).model;
+        self.client = client;
+        self.compressor = ContextCompressor::new(profile.max_tokens);
+        self.max_context_tokens = profile.context_length;
+        crate::telemetry::record_model_switch(&previous, &profile.model);
+
+        Ok(ModelSwitch {
+            previous,
+            model: profile.model,
+            endpoint: profile.endpoint,
+            context_length: profile.context_length,
+            current_tokens: self.compressor.estimate_tokens(&self.messages),
+        })
+    }
+
+    /// Switch to the model difficulty routing picked for the next task, or
+    /// back to the session's own model when none is configured for it.
+    pub(super) fn route_model(&mut self, recommended: Option<&str>) {
+        let target = match (recommended, &self.routed_from) {
+            (Some(model), _) => model.to_string(),
+            (None, Some(original)) => original.clone(),
+            (None, None) => return,
+        };
+        if self.config.profile_for_switch(&target).model == self.config.model {
+            if recommended.is_none() {
+                self.routed_from = None;
+            }
+            return;
+        }
+
+        let original = self
+            .routed_from
+            .clone()
+            .unwrap_or_else(|| self.config.model.clone());
+        match self.switch_model(&target) {
+            Ok(switch) if switch.exceeds_window() => {
+                tracing::warn!(
+                    "Not routing to {}: conversation exceeds its {}-token window",
+                    switch.model,
+                    switch.context_length
+                );
+                if let Err(e) = self.switch_model(&switch.previous) {
+                    tracing::warn!("Failed to switch back to {}: {}", switch.previous, e);
+                }
+            }
+            Ok(switch) => {
+                if !output::is_compact() {
+                    println!(
+                        "{} Using {} for this task",
+                        "🧭".bright_cyan(),
+                        switch.model.bright_white()
+                    );
+                }
+                self.routed_from = recommended.map(|_| original);
+            }
+            Err(e) => tracing::warn!("Failed to route to model {}: {}", target, e),
+        }
+    }
+
     /// Compress context to reduce token usage
     pub(super) async fn compress_context(&mut self) -> Result<usize> {
         let before = self.compressor.estimate_tokens(&self.messages);
@@ -1008,6 +1524,7 @@ impl Agent {
             .compressor
             .compress(&self.client, &self.messages)
             .await?;
+        self.rag.forget_inserted();
 
         let after = self.compressor.estimate_tokens(&self.messages);
         let saved = before.saturating_sub(after);
@@ -1028,6 +1545,90 @@ impl Agent {
         Ok(saved)
     }
 
+    /// Pin the decisions made so far, then compress the conversation
+    /// regardless of the compression threshold.
+    pub(super) async fn compact_context(&mut self) -> Result<usize> {
+        let before = self.compressor.estimate_tokens(&self.messages);
+
+        println!(
+            "{} Pinning decisions and constraints...",
+            "📌".bright_cyan()
+        );
+        let note = self
+            .compressor
+            .extract_decisions(&self.client, &self.messages, self.pinned_note.as_deref())
+            .await?;
+        self.set_pinned_note(Some(note));
+
+        println!("{} Compressing context...", "🗜️".bright_cyan());
+        self.messages = self
+            .compressor
+            .compress(&self.client, &self.messages)
+            .await?;
+        self.rag.forget_inserted();
+        self.trim_message_history();
+
+        let after = self.compressor.estimate_tokens(&self.messages);
+        println!(
+            "{} Compacted: {} → {} tokens, decisions pinned",
+            "✓".bright_green(),
+            before.to_string().bright_yellow(),
+            after.to_string().bright_green()
+        );
+        Ok(before.saturating_sub(after))
+    }
+
+    /// Replace the pinned decisions section at the end of the system prompt.
+    ///
+    /// The note lives inside the system message so that compression (which
+    /// keeps the first message) and trimming (which keeps system messages)
+    /// never drop it. `None` or an empty note unpins.
+    pub(super) fn set_pinned_note(&mut self, note: Option<String>) {
+        let note = note.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+        if let Some(system) = self.messages.iter_mut().find(|m| m.role == "system") {
+            let current = system.content.text();
+            let base = self
+                .pinned_note
+                .as_deref()
+                .and_then(|old| current.strip_suffix(pinned_section(old).as_str()))
+                .unwrap_or(current);
+            let updated = match &note {
+                Some(n) => format!("{}{}", base, pinned_section(n)),
+                None => base.to_string(),
+            };
+            system.content = crate::api::types::MessageContent::from_text(updated);
+        }
+        self.pinned_note = note;
+    }
+
+    /// Open the pinned note in `$VISUAL`/`$EDITOR` and save the result.
+    /// Returns whether a note remains pinned afterwards.
+    pub(super) fn edit_pinned_note(&mut self) -> Result<bool> {
+        use std::io::Write;
+
+        let mut file = tempfile::Builder::new()
+            .prefix("selfware_pinned_")
+            .suffix(".md")
+            .tempfile()?;
+        file.write_all(self.pinned_note.as_deref().unwrap_or_default().as_bytes())?;
+        file.flush()?;
+
+        let editor = std::env::var("VISUAL")
+            .or_else(|_| std::env::var("EDITOR"))
+            .unwrap_or_else(|_| "vi".to_string());
+        let status = editor_command(&editor)?
+            .arg(file.path())
+            .status()
+            .with_context(|| format!("Failed to launch editor '{}'", editor))?;
+        if !status.success() {
+            anyhow::bail!("Editor exited with {}; pinned note unchanged", status);
+        }
+
+        let edited = std::fs::read_to_string(file.path())?;
+        self.set_pinned_note(Some(edited));
+        Ok(self.pinned_note.is_some())
+    }
+
     /// Enhance cargo check/clippy errors with analyzer suggestions
     pub(super) fn enhance_cargo_errors(&self, result_str: &str) -> String {
         // Try to parse the result and extract errors
@@ -1065,6 +1666,189 @@ impl Agent {
     }
 }
 
+/// An `@path` reference found in user input
+struct FileReference {
+    /// The reference as written, including the `@`
+    full_match: String,
+    path: String,
+    body: ReferenceBody,
+    /// Estimated tokens of the body inserted verbatim
+    tokens: usize,
+}
+
+enum ReferenceBody {
+    File(String),
+    /// Files listed for a directory reference
+    Directory(Vec<String>),
+}
+
+impl ReferenceBody {
+    fn tokens(&self) -> usize {
+        match self {
+            Self::File(content) => crate::token_count::estimate_content_tokens(content),
+            Self::Directory(files) => {
+                crate::token_count::estimate_content_tokens(&files.join("\n"))
+            }
+        }
+    }
+}
+
+impl FileReference {
+    /// The text that replaces the reference and its entry in the list of
+    /// included files. With a `share`, the body is cut down to fit it.
+    fn render(&self, share: Option<usize>, query: &str) -> (String, String) {
+        match (&self.body, share) {
+            (ReferenceBody::File(content), None) => (
+                format!(
+                    "\n```{} ({})\n{}\n```\n",
+                    self.path,
+                    Agent::format_file_size(content.len()),
+                    content.trim()
+                ),
+                self.path.clone(),
+            ),
+            (ReferenceBody::File(content), Some(budget)) => {
+                let (excerpt, kept, total) = excerpt_relevant(&self.path, content, query, budget);
+                (
+                    format!(
+                        "\n```{} ({}, excerpt: {} of {} lines most relevant to this message; \
+                         file_read has the rest)\n{}\n```\n",
+                        self.path,
+                        Agent::format_file_size(content.len()),
+                        kept,
+                        total,
+                        excerpt.trim_end()
+                    ),
+                    format!("{} (excerpt: {}/{} lines)", self.path, kept, total),
+                )
+            }
+            (ReferenceBody::Directory(files), share) => {
+                let mut listed = 0;
+                let mut used = 0;
+                for file in files {
+                    let cost = crate::token_count::estimate_content_tokens(file) + 1;
+                    if share.is_some_and(|budget| used + cost > budget) {
+                        break;
+                    }
+                    used += cost;
+                    listed += 1;
+                }
+                let mut block = format!("Directory tree for {}:\n```\n", self.path);
+                for file in &files[..listed] {
+                    block.push_str(&format!("  {}\n", file));
+                }
+                if listed < files.len() {
+                    block.push_str(&format!("  … {} more files\n", files.len() - listed));
+                }
+                block.push_str("```\n");
+                let dir = self.path.trim_end_matches('/');
+                let label = if listed < files.len() {
+                    format!("{}/ ({} of {} files listed)", dir, listed, files.len())
+                } else {
+                    format!("{}/ ({} files)", dir, files.len())
+                };
+                (block, label)
+            }
+        }
+    }
+}
+
+/// Token share for each reference: `None` keeps it verbatim. Within the
+/// budget everything is verbatim; past it, references are taken smallest
+/// first and any larger than an even split of what is left gets that split.
+fn reference_shares(references: &[FileReference], budget: usize) -> Vec<Option<usize>> {
+    let mut shares = vec![None; references.len()];
+    let total: usize = references.iter().map(|r| r.tokens).sum();
+    if budget == 0 || total <= budget {
+        return shares;
+    }
+    let mut order: Vec<usize> = (0..references.len()).collect();
+    order.sort_by_key(|&i| references[i].tokens);
+    let mut remaining = budget;
+    for (n, &i) in order.iter().enumerate() {
+        let share = remaining / (order.len() - n);
+        let tokens = references[i].tokens;
+        if tokens <= share {
+            remaining -= tokens;
+        } else {
+            shares[i] = Some(share);
+            remaining -= share;
+        }
+    }
+    shares
+}
+
+/// The chunks of `content` that best match `query` (BM25), in file order,
+/// within `budget` tokens. Skipped spans are marked. Without matching terms
+/// the start of the file is kept. Returns the excerpt and the lines kept
+/// out of the total.
+fn excerpt_relevant(
+    path: &str,
+    content: &str,
+    query: &str,
+    budget: usize,
+) -> (String, usize, usize) {
+    use crate::token_count::estimate_content_tokens;
+
+    let lines: Vec<&str> = content.lines().collect();
+    let chunks =
+        crate::vector_store::CodeChunker::new(1200).chunk(content, std::path::Path::new(path));
+    let mut index = crate::bm25::BM25Index::new();
+    for (i, chunk) in chunks.iter().enumerate() {
+        index.add(i.to_string(), chunk.content.clone());
+    }
+    let ranked: Vec<usize> = index
+        .search(query, chunks.len())
+        .into_iter()
+        .filter_map(|r| r.id.parse().ok())
+        .collect();
+    let unranked = (0..chunks.len()).filter(|i| !ranked.contains(i));
+
+    let mut keep = vec![false; lines.len()];
+    let mut used = 0;
+    for i in ranked.iter().copied().chain(unranked) {
+        let chunk = &chunks[i];
+        let cost = estimate_content_tokens(&chunk.content);
+        if used + cost > budget {
+            continue;
+        }
+        used += cost;
+        let start = chunk.metadata.start_line.saturating_sub(1);
+        let end = chunk.metadata.end_line.min(lines.len());
+        for kept in keep.iter_mut().take(end).skip(start) {
+            *kept = true;
+        }
+    }
+    // Even the smallest chunk is too big: keep whole lines from the top
+    if used == 0 {
+        for (i, line) in lines.iter().enumerate() {
+            used += estimate_content_tokens(line) + 1;
+            if used > budget {
+                break;
+            }
+            keep[i] = true;
+        }
+    }
+
+    let mut out = Vec::new();
+    let mut gap_start = None;
+    for (i, line) in lines.iter().enumerate() {
+        if keep[i] {
+            if let Some(start) = gap_start.take() {
+                out.push(format!("… lines {}-{} omitted …", start + 1, i));
+            }
+            out.push((*line).to_string());
+        } else if gap_start.is_none() {
+            gap_start = Some(i);
+        }
+    }
+    if let Some(start) = gap_start {
+        out.push(format!("… lines {}-{} omitted …", start + 1, lines.len()));
+    }
+    let kept = keep.iter().filter(|k| **k).count();
+    (out.join("\n"), kept, lines.len())
+}
+
 // =========================================================================
 // Tests
 // =========================================================================
@@ -1375,6 +2159,59 @@ mod tests {
         server.stop().await;
     }
 
+    #[tokio::test]
+    async fn test_expand_file_references_keeps_relevant_sections_over_budget() {
+        let server = MockLlmServer::builder().with_response("ok").build().await;
+        let mut agent = make_test_agent(&server).await;
+        agent.config.max_reference_tokens = 600;
+
+        let dir = tempfile::tempdir().expect("failed to create temp dir");
+        let small = dir.path().join("small.txt");
+        std::fs::write(&small, "small note").unwrap();
+        // Many unrelated functions with one about retries in the middle
+        let mut big = String::new();
+        for i in 0..80 {
+            if i == 40 {
+                big.push_str(
+                    "fn retry_backoff(attempt: u32) -> u64 {\n    \
+                     // exponential retry backoff\n    100 * 2u64.pow(attempt)\n}\n\n",
+                );
+            }
+            big.push_str(&format!(
+                "fn helper_{i}(value: usize) -> usize {{\n    value * {i} + {i}\n}}\n\n"
+            ));
+        }
+        let large = dir.path().join("large.rs");
+        std::fs::write(&large, &big).unwrap();
+
+        let input = format!(
+            "why is the retry backoff wrong? @{} @{}",
+            small.display(),
+            large.display()
+        );
+        let (expanded, files) = agent.expand_file_references(&input);
+
+        // The small reference is untouched, the large one is an excerpt
+        assert!(expanded.contains("small note"));
+        assert_eq!(files[0], small.display().to_string());
+        assert!(
+            files[1].starts_with(&format!("{} (excerpt: ", large.display())),
+            "{:?}",
+            files
+        );
+        assert!(expanded.contains("fn retry_backoff"), "{}", expanded);
+        assert!(expanded.contains("omitted …"), "{}", expanded);
+        assert!(!expanded.contains("fn helper_79("), "{}", expanded);
+
+        // A budget of 0 turns the limit off
+        agent.config.max_reference_tokens = 0;
+        let (expanded, files) = agent.expand_file_references(&input);
+        assert!(expanded.contains("fn helper_79("));
+        assert_eq!(files[1], large.display().to_string());
+
+        server.stop().await;
+    }
+
     // =====================================================================
     // clear_context  (lightweight Agent state test)
     // =====================================================================
@@ -1403,6 +2240,48 @@ mod tests {
         server.stop().await;
     }
 
+    #[cfg(feature = "resilience")]
+    #[tokio::test]
+    async fn test_reduce_context_for_error_keeps_task_recent_and_referenced_files() {
+        let server = MockLlmServer::builder().with_response("ok").build().await;
+        let mut agent = make_test_agent(&server).await;
+
+        let file = |path: &str| {
+            Message::user(format!(
+                "\n// ====\n{}{}\n// ====\nfn x() {{}}",
+                FILE_CONTENT_MARKER, path
+            ))
+        };
+        agent.messages.push(Message::user("fix the parser"));
+        agent.messages.push(file("src/parser.rs"));
+        agent.messages.push(file("src/unrelated.rs"));
+        agent.context_files = vec!["src/parser.rs".into(), "src/unrelated.rs".into()];
+        for i in 0..10 {
+            agent
+                .messages
+                .push(Message::assistant(format!("attempt {}", i)));
+        }
+
+        let dropped = agent.reduce_context_for_error("error[E0308] in src/parser.rs:12");
+
+        assert_eq!(dropped, 5);
+        assert_eq!(agent.context_files, vec!["src/parser.rs".to_string()]);
+        let texts: Vec<&str> = agent.messages.iter().map(|m| m.content.text()).collect();
+        assert!(texts.contains(&"fix the parser"));
+        assert!(texts
+            .iter()
+            .any(|t| t.contains("src/parser.rs") && t.contains("fn x")));
+        assert!(!texts.iter().any(|t| t.contains("src/unrelated.rs")));
+        assert!(!texts.contains(&"attempt 3"));
+        assert!(texts.contains(&"attempt 4"));
+        assert!(texts
+            .last()
+            .unwrap()
+            .contains("error[E0308] in src/parser.rs:12"));
+
+        server.stop().await;
+    }
+
     // =====================================================================
     // estimate_messages_tokens
     // =====================================================================
@@ -2021,6 +2900,49 @@ mod tests {
         assert_eq!(result_mb, "1.5MB");
     }
 
+    // =====================================================================
+    // expand_url_references
+    // =====================================================================
+
+    #[tokio::test]
+    async fn test_expand_url_references_leaves_blocked_url_literal() {
+        let server = MockLlmServer::builder().with_response("ok").build().await;
+        let mut agent = make_test_agent(&server).await;
+
+        // The mock server is on localhost, which the browser rules block
+        let input = format!("summarize @{}/v1/models please", server.url());
+        let (expanded, files) = agent.expand_file_references(&input);
+        assert_eq!(expanded, input);
+        let (expanded, urls) = agent.expand_url_references(&input, expanded).await;
+        assert_eq!(expanded, input, "a blocked URL should stay as typed");
+        assert!(files.is_empty() && urls.is_empty());
+        assert!(agent.url_cache.is_empty(), "failures are not cached");
+
+        server.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_expand_url_references_uses_session_cache() {
+        let server = MockLlmServer::builder().with_response("ok").build().await;
+        let mut agent = make_test_agent(&server).await;
+        agent.url_cache.insert(
+            "https://example.com/issue/7".to_string(),
+            "Crash when the config is empty".to_string(),
+        );
+
+        let input = "fix @https://example.com/issue/7.";
+        let (expanded, urls) = agent.expand_url_references(input, input.to_string()).await;
+        assert!(expanded.contains("```text https://example.com/issue/7"));
+        assert!(expanded.contains("Crash when the config is empty"));
+        assert!(
+            expanded.ends_with("```\n."),
+            "trailing period stays outside"
+        );
+        assert_eq!(urls, ["https://example.com/issue/7"]);
+
+        server.stop().await;
+    }
+
     // =====================================================================
     // expand_file_references — directory reference
     // =====================================================================
@@ -2421,4 +3343,235 @@ mod tests {
 
         server.stop().await;
     }
+
+    // =====================================================================
+    // session_footprint
+    // =====================================================================
+
+    #[tokio::test]
+    async fn test_session_footprint_zero_when_disabled() {
+        let server = MockLlmServer::builder().with_response("ok").build().await;
+        let mut agent = make_test_agent(&server).await;
+        agent.messages.push(Message::user("x".repeat(4000)));
+
+        let enabled = agent.session_footprint();
+        assert!(enabled.energy_wh > 0.0);
+        assert!(enabled.co2e_grams > 0.0);
+
+        agent.config.carbon.enabled = false;
+        let disabled = agent.session_footprint();
+        assert_eq!(disabled.energy_wh, 0.0);
+        assert_eq!(disabled.co2e_grams, 0.0);
+
+        server.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_session_footprint_includes_tool_compute() {
+        let server = MockLlmServer::builder().with_response("ok").build().await;
+        let mut agent = make_test_agent(&server).await;
+        let tokens_only = agent.session_footprint();
+
+        agent.config.carbon.cpu_watts = 36.0;
+        agent.carbon = crate::observability::carbon_tracker::CarbonTracker::new()
+            .with_cpu_watts(agent.config.carbon.cpu_watts);
+        agent.carbon.record_compute("cargo_test", 100.0);
+        let with_compute = agent.session_footprint();
+        assert!((with_compute.energy_wh - tokens_only.energy_wh - 1.0).abs() < 1e-9);
+        assert!(with_compute.co2e_grams > tokens_only.co2e_grams);
+
+        agent.config.carbon.enabled = false;
+        assert_eq!(agent.session_footprint(), SessionFootprint::default());
+
+        server.stop().await;
+    }
+
+    // =====================================================================
+    // pinned decisions (/compact)
+    // =====================================================================
+
+    #[test]
+    fn test_editor_command_splits_arguments() {
+        let command = editor_command("code --wait").unwrap();
+        assert_eq!(command.get_program(), "code");
+        assert_eq!(command.get_args().collect::<Vec<_>>(), ["--wait"]);
+
+        let command = editor_command("'/opt/My Editor/edit' -n").unwrap();
+        assert_eq!(command.get_program(), "/opt/My Editor/edit");
+        assert_eq!(command.get_args().collect::<Vec<_>>(), ["-n"]);
+
+        assert!(editor_command("  ").is_err());
+        assert!(editor_command("vim 'unterminated").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_set_pinned_note_replaces_section() {
+        let server = MockLlmServer::builder().with_response("ok").build().await;
+        let mut agent = make_test_agent(&server).await;
+        let original = agent.messages[0].content.text().to_string();
+
+        agent.set_pinned_note(Some("- first".to_string()));
+        agent.set_pinned_note(Some("- second".to_string()));
+        let prompt = agent.messages[0].content.text();
+        assert!(prompt.ends_with("## Pinned Decisions and Constraints\n\n- second"));
+        assert!(!prompt.contains("- first"));
+
+        agent.set_pinned_note(Some("   ".to_string()));
+        assert!(agent.pinned_note.is_none());
+        assert_eq!(agent.messages[0].content.text(), original);
+
+        server.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_compact_context_pins_note_that_survives_compression() {
+        let server = MockLlmServer::builder()
+            .with_response("- Use SQLite, not Postgres (no server available)")
+            .with_response("Earlier work summarized.")
+            .build()
+            .await;
+        let mut agent = make_test_agent(&server).await;
+        for i in 0..12 {
+            agent
+                .messages
+                .push(Message::user(format!("question {}", i)));
+            agent
+                .messages
+                .push(Message::assistant(format!("answer {}", i)));
+        }
+
+        agent.compact_context().await.unwrap();
+
+        assert_eq!(
+            agent.pinned_note.as_deref(),
+            Some("- Use SQLite, not Postgres (no server available)")
+        );
+        assert!(agent.messages[0].content.text().contains("Use SQLite"));
+        assert!(agent.messages.len() < 25);
+
+        // Trimming keeps system messages, so the note stays put
+        agent.max_context_tokens = 1;
+        agent.trim_message_history();
+        assert!(agent.messages[0].content.text().contains("Use SQLite"));
+
+        let checkpoint = agent.to_checkpoint("t", "task");
+        assert_eq!(checkpoint.pinned_note, agent.pinned_note);
+
+        server.stop().await;
+    }
+
+    // =====================================================================
+    // switch_model
+    // =====================================================================
+
+    #[tokio::test]
+    async fn test_switch_model_keeps_conversation_and_uses_profile() {
+        let server = MockLlmServer::builder().with_response("ok").build().await;
+        let mut agent = make_test_agent(&server).await;
+        agent.config.models.insert(
+            "fast".to_string(),
+            crate::config::ModelProfile {
+                endpoint: format!("{}/v1", server.url()),
+                model: "fast-model".to_string(),
+                api_key: None,
+                max_tokens: 2048,
+                temperature: 0.2,
+                modalities: vec!["text".to_string()],
+                context_length: 8192,
+            },
+        );
+        // A short system prompt, so the fit does not depend on how many
+        // tools are described in the real one
+        agent.messages = vec![Message::system("You are a coding agent.")];
+        agent.messages.push(Message::user("keep me"));
+        let before = agent.messages.len();
+
+        let switch = agent.switch_model("fast").unwrap();
+        assert_eq!(switch.previous, "mock-model");
+        assert_eq!(switch.model, "fast-model");
+        assert_eq!(agent.config.model, "fast-model");
+        assert_eq!(agent.max_context_tokens, 8192);
+        assert_eq!(agent.messages.len(), before);
+        assert!(!switch.exceeds_window());
+
+        server.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_switch_model_large_window_holds_full_system_prompt() {
+        let server = MockLlmServer::builder().with_response("ok").build().await;
+        let mut agent = make_test_agent(&server).await;
+        agent.config.models.insert(
+            "long".to_string(),
+            crate::config::ModelProfile {
+                endpoint: format!("{}/v1", server.url()),
+                model: "long-model".to_string(),
+                api_key: None,
+                max_tokens: 2048,
+                temperature: 0.2,
+                modalities: vec!["text".to_string()],
+                context_length: 32768,
+            },
+        );
+        agent.messages.push(Message::user("keep me"));
+
+        let switch = agent.switch_model("long").unwrap();
+        assert_eq!(agent.max_context_tokens, 32768);
+        assert!(!switch.exceeds_window());
+
+        server.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_switch_model_reports_window_overflow() {
+        let server = MockLlmServer::builder().with_response("ok").build().await;
+        let mut agent = make_test_agent(&server).await;
+        agent.config.models.insert(
+            "tiny".to_string(),
+            crate::config::ModelProfile {
+                endpoint: format!("{}/v1", server.url()),
+                model: "tiny-model".to_string(),
+                api_key: None,
+                max_tokens: 256,
+                temperature: 1.0,
+                modalities: vec!["text".to_string()],
+                context_length: 16,
+            },
+        );
+        agent.messages.push(Message::user("pad ".repeat(200)));
+
+        let switch = agent.switch_model("tiny").unwrap();
+        assert!(switch.exceeds_window());
+        assert!(switch.current_tokens > 16);
+
+        server.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_token_breakdown_buckets_and_matches_trimmed_request() {
+        let server = MockLlmServer::builder().build().await;
+        let mut agent = make_test_agent(&server).await;
+        agent
+            .messages
+            .push(Message::user("// FILE: src/lib.rs\nfn main() {}"));
+        agent.messages.push(Message::user("please refactor"));
+        agent.messages.push(Message::assistant("sure"));
+        agent.messages.push(Message::tool("ok", "call_1"));
+
+        let breakdown = agent.token_breakdown();
+        assert!(breakdown.system > 0);
+        assert!(breakdown.files > 0);
+        assert!(breakdown.user > 0 && breakdown.assistant > 0 && breakdown.tool > 0);
+        assert_eq!(breakdown.total(), agent.estimate_messages_tokens());
+
+        // Over budget: the breakdown reflects what survives trimming
+        agent.max_context_tokens = breakdown.system + breakdown.tool;
+        let trimmed = agent.token_breakdown();
+        agent.trim_message_history();
+        assert_eq!(trimmed.total(), agent.estimate_messages_tokens());
+        assert_eq!(trimmed.files, 0);
+        assert!(trimmed.total() < breakdown.total());
+
+        server.stop().await;
+    }
 }
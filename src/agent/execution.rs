@@ -4,14 +4,15 @@ use anyhow::{Context, Result};
 use chrono::Utc;
 use colored::*;
 use serde_json::Value;
-use tracing::{debug, info, warn};
+use tracing::{debug, info, warn, Instrument};
 
+use super::completion::{self, CompletionSignal};
 use super::*;
-use crate::api::ThinkingMode;
 use crate::checkpoint::ToolCallLog;
 use crate::cognitive::self_improvement::Outcome;
 use crate::cognitive::CyclePhase;
-use crate::errors::AgentError;
+use crate::config::ToolErrorPolicy;
+use crate::errors::{is_context_overflow_error, AgentError};
 use crate::tool_parser::parse_tool_calls;
 
 /// Try to extract a `base64_png` field from a JSON tool result string.
@@ -37,6 +38,18 @@ fn build_image_result_summary(result: &str) -> String {
     }
 }
 
+/// Tools whose work happens in child processes (builds, installs, shell
+/// commands), charged by wall clock when child CPU time is unavailable
+fn spawns_processes(name: &str) -> bool {
+    name.starts_with("cargo_")
+        || name.starts_with("npm_")
+        || name.starts_with("pip_")
+        || matches!(
+            name,
+            "shell_exec" | "yarn_install" | "container_build" | "compose_up"
+        )
+}
+
 struct AssistantStepResponse {
     content: String,
     reasoning_content: Option<String>,
@@ -67,7 +80,22 @@ impl Agent {
     /// Internal execution logic
     /// If `use_last_message` is true, process tool calls from the last assistant message
     async fn execute_step_internal(&mut self, use_last_message: bool) -> Result<bool> {
-        let response = self.get_assistant_step_response(use_last_message).await?;
+        let response = match self.get_assistant_step_response(use_last_message).await {
+            // Shrink and retry once; sending the same request again would
+            // overflow again
+            Err(e) if !use_last_message && is_context_overflow_error(&e) => {
+                let dropped = self.shrink_context_after_overflow();
+                warn!(
+                    "Request exceeded the context window; dropped ~{} tokens and retrying",
+                    dropped
+                );
+                output::context_overflow_recovered(dropped);
+                self.get_assistant_step_response(false)
+                    .await
+                    .context("Request still exceeded the context window after trimming")?
+            }
+            response => response?,
+        };
         let content = response.content;
         let tool_calls = self.collect_tool_calls(
             &content,
@@ -82,7 +110,30 @@ impl Agent {
             return Ok(false);
         }
 
-        if self.maybe_prompt_for_action(&content, tool_calls.is_empty(), use_last_message) {
+        let signal = if tool_calls.is_empty() {
+            completion::detect(&content, &self.config.agent)
+        } else {
+            CompletionSignal::Implicit
+        };
+        if let CompletionSignal::Blocked(request) = signal {
+            self.pause_for_user(request);
+            self.last_assistant_response = content;
+            return Ok(false);
+        }
+
+        // An explicit completion marker is not second-guessed as intent without action
+        if signal != CompletionSignal::Complete
+            && self.maybe_prompt_for_action(&content, tool_calls.is_empty(), use_last_message)
+        {
+            return Ok(false);
+        }
+
+        if signal == CompletionSignal::Unmarked {
+            info!("Reply without tool calls lacks the completion marker");
+            self.messages
+                .push(Message::user(completion::unmarked_reply_prompt(
+                    &self.config.agent,
+                )));
             return Ok(false);
         }
 
@@ -93,6 +144,12 @@ impl Agent {
                 self.messages.push(Message::user(gate_msg));
                 return Ok(false);
             }
+            if let Some(leftover_msg) = self.check_leftover_code() {
+                info!("Completion blocked by leftover code");
+                self.messages.push(Message::user(leftover_msg));
+                return Ok(false);
+            }
+            let content = completion::strip_markers(&content, &self.config.agent);
             output::final_answer(&content);
             self.last_assistant_response = content;
             return Ok(true);
@@ -109,6 +166,16 @@ impl Agent {
         Ok(false)
     }
 
+    /// Pause the task on the model's request for input only the user has.
+    fn pause_for_user(&mut self, request: String) {
+        info!("Model asked for user input: {}", request);
+        self.emit_event(AgentEvent::Status {
+            message: "Waiting for your input".to_string(),
+        });
+        self.loop_control
+            .set_state(AgentState::Paused { reason: request });
+    }
+
     /// Detect malformed tool call attempts and push a correction message.
     /// Returns `true` if malformed markers were found and a correction was injected.
     fn detect_and_correct_malformed_tools(
@@ -191,6 +258,22 @@ impl Agent {
         None
     }
 
+    /// Report debug or placeholder code the agent added (per the configured
+    /// deny patterns) before accepting completion. Each violation blocks
+    /// once; completing again without removing it acknowledges it.
+    fn check_leftover_code(&mut self) -> Option<String> {
+        let violations = self.verification_gate.unacknowledged_violations();
+        if violations.is_empty() {
+            return None;
+        }
+        let list: Vec<String> = violations.iter().map(|v| format!("- {}", v)).collect();
+        Some(format!(
+            "You added debug or placeholder code that should not be left behind:\n{}\n\
+             Remove it and re-verify. If a line is intentional, say why and complete again.",
+            list.join("\n")
+        ))
+    }
+
     /// Track tool calls and detect repetition loops.
     /// Returns `Some(message)` if the same tool+args has been called too many times recently.
     fn detect_repetition(&mut self, tool_calls: &[CollectedToolCall]) -> Option<String> {
@@ -255,11 +338,20 @@ impl Agent {
         true
     }
 
+    /// Run a step's tool calls one at a time, in the order the model emitted
+    /// them, appending each result before the next call starts. Every call
+    /// gets its result message; under the strict `agent.on_tool_error` policy
+    /// a failed call then fails the step.
     async fn execute_tool_batch(&mut self, tool_calls: Vec<CollectedToolCall>) -> Result<()> {
+        self.last_step_tools.clear();
+        self.last_step_calls.clear();
+        let mut first_failure: Option<String> = None;
         for (name, args_str, tool_call_id) in tool_calls {
             if self.is_cancelled() {
                 break;
             }
+            self.last_step_calls
+                .push(Self::describe_call(&name, &args_str));
 
             let start_time = std::time::Instant::now();
             if let Some(warning) = self
@@ -295,6 +387,7 @@ impl Agent {
                     duration_ms,
                     Some(error_msg.clone()),
                 );
+                self.last_step_tools.push((name.clone(), false));
                 self.self_improvement.record_error(
                     &error_msg,
                     "safety",
@@ -308,6 +401,8 @@ impl Agent {
             if !self.confirm_tool_execution(&name, &args_str, &call_id, use_native_fc)? {
                 continue;
             }
+            // Time the call from here so waiting on the user is not counted
+            let start_time = std::time::Instant::now();
 
             self.emit_event(AgentEvent::ToolStarted { name: name.clone() });
 
@@ -341,6 +436,16 @@ impl Agent {
                 spinner.stop_success(&summary);
             } else {
                 spinner.stop_error(&summary);
+                if first_failure.is_none() {
+                    let reason: String = result
+                        .lines()
+                        .next()
+                        .unwrap_or("")
+                        .chars()
+                        .take(300)
+                        .collect();
+                    first_failure = Some(format!("Tool '{}' failed: {}", name, reason));
+                }
             }
 
             // Store for progressive disclosure via /last
@@ -372,6 +477,7 @@ impl Agent {
                 duration_ms,
                 tool_error.clone(),
             );
+            self.last_step_tools.push((name.clone(), success));
             if let Some(error_text) = tool_error {
                 self.self_improvement.record_error(
                     &error_text,
@@ -383,6 +489,18 @@ impl Agent {
             }
 
             // Track file operations for context management
+            if success && matches!(name.as_str(), "apply_patch" | "symbol_rename") {
+                let paths = if name == "apply_patch" {
+                    crate::tools::patch::patch_targets(&args)
+                } else {
+                    crate::tools::refactor::renamed_files(&result)
+                };
+                for path in paths {
+                    if self.stale_files.len() < 500 {
+                        self.stale_files.insert(path.to_string_lossy().to_string());
+                    }
+                }
+            }
             if success {
                 if let Some(path) = args.get("path").and_then(|v| v.as_str()) {
                     let path_str = path.to_string();
@@ -412,7 +530,13 @@ impl Agent {
             self.push_tool_result_message(use_native_fc, &call_id, &name, success, &result);
         }
 
-        Ok(())
+        self.emit_run_stats();
+        match first_failure {
+            Some(failure) if self.config.agent.on_tool_error == ToolErrorPolicy::Strict => {
+                Err(anyhow::anyhow!(failure))
+            }
+            _ => Ok(()),
+        }
     }
 
     fn build_tool_call_context(
@@ -434,6 +558,7 @@ impl Agent {
         (call_id, use_native_fc, fake_call)
     }
 
+    #[tracing::instrument(name = "tool.confirm", skip_all)]
     fn confirm_tool_execution(
         &mut self,
         name: &str,
@@ -441,7 +566,22 @@ impl Agent {
         call_id: &str,
         use_native_fc: bool,
     ) -> Result<bool> {
-        if !self.needs_confirmation(name) {
+        // Edits to selfware's own source always ask, whatever the mode
+        let self_edit = self.safety.self_edit_target(name, args_str);
+        // Runaway rewrites ask even in auto-edit; YOLO only records them
+        let large_edit = self.safety.large_edit(name, args_str);
+        let autonomous = matches!(
+            self.execution_mode(),
+            crate::config::ExecutionMode::Yolo | crate::config::ExecutionMode::Daemon
+        );
+        if let Some(edit) = &large_edit {
+            warn!("Large edit by {}: {}", name, edit);
+            if autonomous {
+                self.audit_large_edit(name, args_str, edit);
+            }
+        }
+        let forced = self_edit.is_some() || (large_edit.is_some() && !autonomous);
+        if !self.call_needs_confirmation(name, args_str) && !forced {
             return Ok(true);
         }
 
@@ -461,22 +601,52 @@ impl Agent {
             .into());
         }
 
+        if let Some(target) = &self_edit {
+            println!(
+                "{} {} is part of selfware's own source; a bad edit can break this session",
+                "⚠️".bright_red(),
+                target.display()
+            );
+        }
+        if let Some(edit) = &large_edit {
+            println!("{} Large edit: {}", "⚠️".bright_yellow(), edit);
+        }
+        if name == "cargo_add" {
+            println!(
+                "{} Adding a dependency pulls third-party code into the build",
+                "⚠️".bright_yellow()
+            );
+        }
         println!(
             "{} Tool: {} Args: {}",
             "⚠️".bright_yellow(),
             name.bright_cyan(),
             args_display.bright_white()
         );
-        print!(
-            "{}",
-            "Execute? [y/N/s(bypass permissions)]: ".bright_yellow()
-        );
-        io::stdout().flush().ok();
+        loop {
+            print!(
+                "{}",
+                "Execute? [y/N/s(bypass permissions)/c [label](checkpoint first)]: "
+                    .bright_yellow()
+            );
+            io::stdout().flush().ok();
 
-        let mut response = String::new();
-        if io::stdin().read_line(&mut response).is_ok() {
-            let response = response.trim().to_lowercase();
-            match response.as_str() {
+            let mut response = String::new();
+            if io::stdin().read_line(&mut response).is_err() {
+                break;
+            }
+            let response = response.trim();
+            // Snapshot before deciding, then ask again
+            if let Some(label) = response
+                .strip_prefix("/checkpoint")
+                .or_else(|| response.strip_prefix("c ").map(|l| l.trim_start()))
+                .or_else(|| (response == "c").then_some(""))
+            {
+                let label = label.trim();
+                self.print_checkpoint_now((!label.is_empty()).then(|| label.to_string()));
+                continue;
+            }
+            match response.to_lowercase().as_str() {
                 "y" | "yes" => return Ok(true),
                 "s" | "skip" => {
                     self.set_execution_mode(crate::config::ExecutionMode::Yolo);
@@ -486,7 +656,7 @@ impl Agent {
                     );
                     return Ok(true);
                 }
-                _ => {}
+                _ => break,
             }
         }
 
@@ -506,6 +676,25 @@ impl Agent {
         Ok(false)
     }
 
+    /// Note a large edit that YOLO mode let through in the configured audit log
+    fn audit_large_edit(
+        &self,
+        name: &str,
+        args_str: &str,
+        edit: &crate::safety::checker::LargeEdit,
+    ) {
+        #[cfg(feature = "execution-modes")]
+        if let Some(path) = &self.config.yolo.audit_log_path {
+            let args = serde_json::from_str(args_str).unwrap_or(Value::Null);
+            let reason = format!("large edit: {}", edit);
+            if let Err(e) = crate::safety::yolo::record_flagged(path, name, &args, &reason) {
+                warn!("Failed to write audit log {}: {}", path.display(), e);
+            }
+        }
+        #[cfg(not(feature = "execution-modes"))]
+        let _ = (name, args_str, edit);
+    }
+
     fn parse_tool_args(
         &mut self,
         name: &str,
@@ -552,7 +741,11 @@ impl Agent {
         start_time: std::time::Instant,
     ) -> Result<(bool, String, String)> {
         let Some(tool) = self.tools.get(name) else {
-            let err = format!("Unknown tool: {}", name);
+            let err = if self.tools.is_disabled(name) {
+                format!("Tool '{}' is disabled for this task", name)
+            } else {
+                format!("Unknown tool: {}", name)
+            };
             self.log_tool_call(name, args_str, &err, false, start_time, false);
             return Ok((false, err.clone(), err));
         };
@@ -561,33 +754,133 @@ impl Agent {
         // Use tokio::fs to avoid blocking the async runtime thread.
         if matches!(name, "file_edit" | "file_write" | "file_delete") {
             if let Some(path) = args.get("path").and_then(|v| v.as_str()) {
-                if let Ok(content) = tokio::fs::read_to_string(path).await {
-                    use crate::session::edit_history::{EditAction, FileSnapshot};
-                    let snapshot = FileSnapshot::new(std::path::PathBuf::from(path), content);
-                    let action = EditAction::FileEdit {
-                        path: std::path::PathBuf::from(path),
-                        tool: name.to_string(),
-                    };
-                    self.edit_history.create_checkpoint(action);
-                    self.edit_history.add_file_to_current(snapshot);
+                // Existence is checked on its own: a file that exists but is
+                // not UTF-8 text must not be mistaken for a new one
+                let exists = !matches!(
+                    tokio::fs::symlink_metadata(path).await,
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound
+                );
+                let content = if exists {
+                    tokio::fs::read_to_string(path).await.ok()
+                } else {
+                    None
+                };
+                // file_write refuses to replace an existing file without
+                // `overwrite`; only an intended overwrite gets a checkpoint
+                let refused = name == "file_write"
+                    && exists
+                    && !args
+                        .get("overwrite")
+                        .and_then(|v| v.as_bool())
+                        .unwrap_or(false);
+                if name != "file_delete" && !refused {
+                    // A file that doesn't exist yet has no pre-existing lines
+                    let baseline = content.as_deref().unwrap_or_default();
+                    self.verification_gate.record_baseline(path, baseline);
                 }
+                match content {
+                    _ if refused => {}
+                    Some(content) => {
+                        use crate::session::edit_history::{EditAction, FileSnapshot};
+                        let snapshot = FileSnapshot::new(std::path::PathBuf::from(path), content);
+                        let action = EditAction::FileEdit {
+                            path: std::path::PathBuf::from(path),
+                            tool: name.to_string(),
+                        };
+                        self.edit_history.create_checkpoint(action);
+                        self.edit_history.add_file_to_current(snapshot);
+                    }
+                    None if exists => {
+                        warn!(
+                            "{} is not readable as text; /rewind cannot restore it",
+                            path
+                        );
+                    }
+                    None if name == "file_write" => {
+                        // Lets /rewind remove files a turn created
+                        self.edit_history.create_checkpoint(
+                            crate::session::edit_history::EditAction::FileCreate {
+                                path: std::path::PathBuf::from(path),
+                            },
+                        );
+                    }
+                    None => {}
+                }
+            }
+        }
+        // A patch or rename is one undo unit covering every file it touches
+        let multi_file_targets = match name {
+            "apply_patch" => crate::tools::patch::patch_targets(args),
+            "symbol_rename" => crate::tools::refactor::rename_targets(args),
+            _ => Vec::new(),
+        };
+        if !multi_file_targets.is_empty() {
+            use crate::session::edit_history::{EditAction, FileSnapshot};
+            self.edit_history
+                .create_checkpoint(EditAction::MultiFileEdit {
+                    paths: multi_file_targets.clone(),
+                    tool: name.to_string(),
+                });
+            for path in &multi_file_targets {
+                let content = tokio::fs::read_to_string(path).await;
+                self.verification_gate.record_baseline(
+                    &path.display().to_string(),
+                    content.as_deref().unwrap_or_default(),
+                );
+                if let Ok(content) = content {
+                    self.edit_history
+                        .add_file_to_current(FileSnapshot::new(path.clone(), content));
+                }
+            }
+        }
+        // /undo reverts a dependency change like an edit of its manifest
+        if matches!(name, "cargo_add" | "cargo_remove") {
+            use crate::session::edit_history::{EditAction, FileSnapshot};
+            let manifest = std::path::PathBuf::from(crate::tools::cargo::manifest_arg(args));
+            if let Ok(content) = tokio::fs::read_to_string(&manifest).await {
+                self.edit_history.create_checkpoint(EditAction::FileEdit {
+                    path: manifest.clone(),
+                    tool: name.to_string(),
+                });
+                self.edit_history
+                    .add_file_to_current(FileSnapshot::new(manifest, content));
             }
         }
 
         let timeout_secs = self.config.agent.step_timeout_secs.max(1);
+        let cancel = crate::tools::CancellationToken::from_flag(self.cancel_token());
+        let span = crate::telemetry::tool_call_span(name);
+        let exec_start = std::time::Instant::now();
+        let compute = crate::observability::carbon_tracker::ComputeProbe::start();
         let execution = tokio::time::timeout(
             std::time::Duration::from_secs(timeout_secs),
-            tool.execute(args.clone()),
+            tool.execute_cancellable(args.clone(), &cancel),
         )
-        .await;
+        .instrument(span.clone())
+        .await
+        .map(|result| result.and_then(|output| crate::tools::validate_output(tool, output)));
+        let exec_ms = exec_start.elapsed().as_millis() as u64;
+        self.record_tool_compute(name, compute.finish());
 
         match execution {
             Ok(Ok(result)) => {
                 let elapsed = start_time.elapsed().as_millis() as u64;
                 let result_str = serde_json::to_string(&result)?;
+                span.record(
+                    "result_tokens",
+                    crate::token_count::estimate_content_tokens(&result_str),
+                );
+                crate::telemetry::record_span_outcome(&span, "ok", elapsed, None);
+                self.record_tool_timing(name, exec_ms, true, result_str.len());
                 let summary =
                     output::semantic_summary(name, args, Some(&result_str), true, elapsed);
-                self.log_tool_call(name, args_str, &result_str, true, start_time, true);
+                // Test counts would not survive truncation of the full result
+                if name == "cargo_test" {
+                    let digest = crate::tools::cargo::test_digest(&result).to_string();
+                    self.log_tool_call(name, args_str, &digest, true, start_time, false);
+                } else {
+                    self.log_tool_call(name, args_str, &result_str, true, start_time, true);
+                }
 
                 // Record successful tool usage for learning
                 self.self_improvement.record_tool(
@@ -598,7 +891,9 @@ impl Agent {
                     None,
                 );
 
-                let verification_result = self.maybe_verify_file_change(name, args).await;
+                let verification_result = self
+                    .maybe_verify_file_change(name, args, &multi_file_targets)
+                    .await;
                 let enhanced_result = self.maybe_enhance_tool_result(name, &result_str);
                 let final_result = match verification_result {
                     Some(ver_msg) => format!("{}{}", enhanced_result, ver_msg),
@@ -608,6 +903,13 @@ impl Agent {
             }
             Ok(Err(e)) => {
                 let elapsed = start_time.elapsed().as_millis() as u64;
+                crate::telemetry::record_span_outcome(
+                    &span,
+                    "error",
+                    elapsed,
+                    Some(&e.to_string()),
+                );
+                self.record_tool_timing(name, exec_ms, false, e.to_string().len());
                 let summary =
                     output::semantic_summary(name, args, Some(&e.to_string()), false, elapsed);
                 self.log_tool_call(name, args_str, &e.to_string(), false, start_time, false);
@@ -629,6 +931,8 @@ impl Agent {
             Err(_) => {
                 let elapsed = start_time.elapsed().as_millis() as u64;
                 let err = format!("Tool '{}' timed out after {}s", name, timeout_secs);
+                crate::telemetry::record_span_outcome(&span, "timeout", elapsed, Some(&err));
+                self.record_tool_timing(name, exec_ms, false, 0);
                 let summary = output::semantic_summary(name, args, Some(&err), false, elapsed);
                 self.log_tool_call(name, args_str, &err, false, start_time, false);
                 self.cognitive_state.episodic_memory.what_failed(name, &err);
@@ -644,21 +948,39 @@ impl Agent {
         }
     }
 
-    async fn maybe_verify_file_change(&mut self, tool_name: &str, args: &Value) -> Option<String> {
-        if !matches!(tool_name, "file_edit" | "file_write") {
-            return None;
-        }
-
-        let path = args.get("path").and_then(|v| v.as_str())?;
+    /// Run the verification gate after a tool changed files. `targets` are
+    /// the files a multi-file tool edited.
+    async fn maybe_verify_file_change(
+        &mut self,
+        tool_name: &str,
+        args: &Value,
+        targets: &[std::path::PathBuf],
+    ) -> Option<String> {
+        let dependency_change = matches!(tool_name, "cargo_add" | "cargo_remove");
+        let files: Vec<String> = match tool_name {
+            "file_edit" | "file_write" => {
+                vec![args.get("path").and_then(|v| v.as_str())?.to_string()]
+            }
+            "symbol_rename" if !targets.is_empty() => {
+                targets.iter().map(|p| p.display().to_string()).collect()
+            }
+            _ if dependency_change => vec![crate::tools::cargo::manifest_arg(args)],
+            _ => return None,
+        };
+        let path = files.join(", ");
         info!("Running verification after {} on {}", tool_name, path);
         self.cognitive_state.set_phase(CyclePhase::Verify);
         let spinner = crate::ui::spinner::TerminalSpinner::start("Verifying...");
 
-        match self
-            .verification_gate
-            .verify_change(&[path.to_string()], &format!("{}:{}", tool_name, path))
-            .await
-        {
+        let trigger = format!("{}:{}", tool_name, path);
+        let verification = if dependency_change {
+            self.verification_gate
+                .verify_dependency_change(&path, &trigger)
+                .await
+        } else {
+            self.verification_gate.verify_change(&files, &trigger).await
+        };
+        match verification {
             Ok(report) => {
                 if report.overall_passed {
                     spinner.stop_success("Verification passed");
@@ -692,18 +1014,58 @@ impl Agent {
     }
 
     fn maybe_enhance_tool_result(&self, name: &str, result_str: &str) -> String {
-        if name == "cargo_check" && result_str.contains("\"success\":false") {
+        let compile_failed = match name {
+            "cargo_check" => result_str.contains("\"success\":false"),
+            "cargo_test" => result_str.contains("\"outcome\":\"compile_error\""),
+            _ => false,
+        };
+        if compile_failed {
             self.enhance_cargo_errors(result_str)
         } else {
             result_str.to_string()
         }
     }
 
+    /// Elide the middle of a result over `max_tool_result_tokens`, keeping
+    /// the full text for `tool_output`
+    fn fit_tool_result<'a>(
+        &self,
+        call_id: &str,
+        tool_name: &str,
+        result: &'a str,
+    ) -> std::borrow::Cow<'a, str> {
+        let max_tokens = self.config.agent.max_tool_result_tokens;
+        // tool_output pages on its own; eliding it again would loop
+        if max_tokens == 0 || tool_name == "tool_output" {
+            return result.into();
+        }
+        let note = format!("tool_output call_id \"{}\" reads them", call_id);
+        let Some(elided) = crate::token_count::elide_middle(
+            result,
+            max_tokens,
+            self.config.agent.tool_result_head_fraction,
+            &note,
+        ) else {
+            return result.into();
+        };
+        debug!(
+            "Elided {} chars of {} result {} to fit {} tokens",
+            elided.chars, tool_name, call_id, max_tokens
+        );
+        self.tool_outputs
+            .insert(crate::tools::tool_output::RetainedOutput {
+                call_id: call_id.to_string(),
+                output: result.to_string(),
+                elided_from: elided.start,
+            });
+        elided.text.into()
+    }
+
     fn push_tool_result_message(
         &mut self,
         use_native_fc: bool,
         call_id: &str,
-        _tool_name: &str,
+        tool_name: &str,
         success: bool,
         result: &str,
     ) {
@@ -730,6 +1092,46 @@ impl Agent {
             }
         }
 
+        // Artifacts: images are shown to a vision model, anything else is
+        // described in the result text
+        let mut images = Vec::new();
+        let described;
+        let result = match serde_json::from_str::<Value>(result) {
+            Ok(mut value)
+                if success && value.get(crate::tools::artifact::ARTIFACTS_KEY).is_some() =>
+            {
+                images = self.present_artifacts(&mut value);
+                described = value.to_string();
+                described.as_str()
+            }
+            _ => result,
+        };
+
+        let result = self.fit_tool_result(call_id, tool_name, result);
+        if !images.is_empty() {
+            let text = if use_native_fc {
+                result.to_string()
+            } else {
+                format!("<tool_result>{}</tool_result>", result)
+            };
+            let content = images.iter().fold(
+                crate::api::types::MessageContent::from_text(text),
+                |content, (mime_type, data)| content.with_image_data(mime_type, data),
+            );
+            if use_native_fc {
+                self.messages.push(crate::api::types::Message {
+                    role: "tool".to_string(),
+                    content,
+                    reasoning_content: None,
+                    tool_calls: None,
+                    tool_call_id: Some(call_id.to_string()),
+                    name: None,
+                });
+            } else {
+                self.messages.push(Message::user_multimodal(content));
+            }
+            return;
+        }
         if use_native_fc {
             let result_json = if success {
                 result.to_string()
@@ -747,6 +1149,65 @@ impl Agent {
         }
     }
 
+    /// Replace the artifact list of a tool result with what the model gets:
+    /// each entry is marked `attached` or carries a `description`. Returns
+    /// the (MIME type, base64) of images to attach, which is empty unless
+    /// the current model supports vision.
+    fn present_artifacts(&self, result: &mut Value) -> Vec<(String, String)> {
+        use crate::tools::artifact::{self, ARTIFACTS_KEY};
+
+        let vision = self.model_supports_vision();
+        let mut images = Vec::new();
+        let mut entries = Vec::new();
+        for artifact in artifact::artifacts(result) {
+            let mut entry = serde_json::json!({
+                "path": artifact.path,
+                "mime_type": artifact.mime_type,
+            });
+            let image = vision.then(|| artifact.image_base64()).and_then(|data| {
+                data.map_err(|e| debug!("Not attaching artifact: {}", e))
+                    .ok()
+            });
+            match image {
+                Some(data) => {
+                    entry["attached"] = Value::Bool(true);
+                    images.push((artifact.mime_type.clone(), data));
+                }
+                None => entry["description"] = Value::String(artifact::describe(&artifact)),
+            }
+            entries.push(entry);
+        }
+        result[ARTIFACTS_KEY] = Value::Array(entries);
+        images
+    }
+
+    /// Persist how long a tool took, for `selfware analytics tool-stats`
+    /// Charge the CPU time of the processes a tool spawned to the session
+    /// footprint. Where child CPU time can't be read, only tools that run
+    /// processes are charged, by wall clock.
+    fn record_tool_compute(
+        &mut self,
+        name: &str,
+        sample: crate::observability::carbon_tracker::ComputeSample,
+    ) {
+        if !self.config.carbon.enabled || (!sample.measured && !spawns_processes(name)) {
+            return;
+        }
+        self.carbon.record_sample(name, sample);
+    }
+
+    fn record_tool_timing(&self, name: &str, duration_ms: u64, success: bool, result_bytes: usize) {
+        let timing = crate::observability::analytics::ToolTiming::new(
+            name,
+            duration_ms,
+            success,
+            result_bytes,
+        );
+        if let Err(e) = self.tool_timings.record(&timing) {
+            debug!("Failed to record tool timing: {}", e);
+        }
+    }
+
     fn log_tool_call(
         &mut self,
         tool_name: &str,
@@ -773,6 +1234,18 @@ impl Agent {
         }
     }
 
+    /// Journal the model's reasoning for the current step when
+    /// `store_reasoning` is enabled
+    fn log_reasoning(&mut self, reasoning: Option<&str>) {
+        if !self.config.store_reasoning {
+            return;
+        }
+        let step = self.loop_control.current_step();
+        if let (Some(text), Some(checkpoint)) = (reasoning, self.current_checkpoint.as_mut()) {
+            checkpoint.log_reasoning(step, text);
+        }
+    }
+
     async fn get_assistant_step_response(
         &mut self,
         use_last_message: bool,
@@ -800,6 +1273,14 @@ impl Agent {
             });
         }
 
+        // Keep long-running sessions from growing memory without bound
+        if self.memory.needs_consolidation() {
+            match self.memory.consolidate(&self.client).await {
+                Ok(saved) => info!("Consolidated memory, ~{} tokens saved", saved),
+                Err(e) => warn!("Memory consolidation failed: {}", e),
+            }
+        }
+
         // Hard-truncate message history to stay within context window before
         // any API call.  This prevents exceeding the model's context limit when
         // compression is skipped or fails.
@@ -816,10 +1297,13 @@ impl Agent {
                     self.messages = self.compressor.hard_compress(&self.messages);
                 }
             }
+            self.rag.forget_inserted();
         }
 
         let mut request_messages = self.messages.clone();
-        if let Some(learning_hint) = self.build_learning_hint(self.learning_context()) {
+        let learning_hint = self.build_learning_hint(self.learning_context());
+        self.record_decision_inputs(learning_hint.as_deref());
+        if let Some(learning_hint) = learning_hint {
             // Merge into existing system message to maintain OpenAI message ordering
             // (system messages must precede all user/assistant/tool messages)
             if let Some(first) = request_messages.first_mut() {
@@ -838,7 +1322,7 @@ impl Agent {
                 .chat_streaming(
                     request_messages.clone(),
                     self.api_tools(),
-                    ThinkingMode::Enabled,
+                    self.thinking_mode(),
                 )
                 .await
             {
@@ -852,6 +1336,9 @@ impl Agent {
                     }
                     (content, reasoning)
                 }
+                Err(stream_err) if is_context_overflow_error(&stream_err) => {
+                    return Err(stream_err);
+                }
                 Err(stream_err) => {
                     warn!(
                         "Streaming request failed ({}); retrying this step with non-streaming API",
@@ -860,7 +1347,7 @@ impl Agent {
 
                     let response = self
                         .client
-                        .chat(request_messages, self.api_tools(), ThinkingMode::Enabled)
+                        .chat(request_messages, self.api_tools(), self.thinking_mode())
                         .await
                         .with_context(|| {
                             format!(
@@ -906,7 +1393,7 @@ impl Agent {
         } else {
             let response = self
                 .client
-                .chat(request_messages, self.api_tools(), ThinkingMode::Enabled)
+                .chat(request_messages, self.api_tools(), self.thinking_mode())
                 .await?;
 
             let choice = response
@@ -951,6 +1438,7 @@ impl Agent {
             (content, reasoning)
         };
 
+        self.log_reasoning(reasoning.as_deref());
         self.messages.push(Message {
             role: "assistant".to_string(),
             content: content.clone().into(),
@@ -1069,7 +1557,9 @@ impl Agent {
         debug!("Sending planning request to model...");
         self.trim_message_history();
         let mut request_messages = self.messages.clone();
-        if let Some(learning_hint) = self.build_learning_hint(self.learning_context()) {
+        let learning_hint = self.build_learning_hint(self.learning_context());
+        self.record_decision_inputs(learning_hint.as_deref());
+        if let Some(learning_hint) = learning_hint {
             // Merge into existing system message to maintain OpenAI message ordering
             if let Some(first) = request_messages.first_mut() {
                 if first.role == "system" {
@@ -1083,7 +1573,7 @@ impl Agent {
         }
         let response = self
             .client
-            .chat(request_messages, self.api_tools(), ThinkingMode::Enabled)
+            .chat(request_messages, self.api_tools(), self.thinking_mode())
             .await?;
 
         let choice = response
@@ -1136,6 +1626,7 @@ impl Agent {
             (parsed, None)
         };
 
+        self.log_reasoning(assistant_msg.reasoning_content.as_deref());
         self.messages.push(Message {
             role: "assistant".to_string(),
             content: content.clone(),
@@ -2027,6 +2518,45 @@ mod tests {
         server.stop().await;
     }
 
+    #[tokio::test]
+    async fn test_push_result_elides_oversized_output() {
+        let server = MockLlmServer::builder().with_response("done").build().await;
+        let mut config = test_config(format!("{}/v1", server.url()));
+        config.agent.max_tool_result_tokens = 200;
+        let mut agent = Agent::new(config).await.unwrap();
+
+        let result: String = (0..2000).map(|i| format!("line {}\n", i)).collect();
+        agent.push_tool_result_message(true, "call_big", "shell_exec", true, &result);
+
+        let text = agent.messages.last().unwrap().content.text();
+        assert!(text.starts_with("line 0\n"));
+        assert!(text.contains("line 1999"));
+        assert!(text.contains("lines elided"));
+        assert!(text.contains("call_big"));
+        assert!(text.len() < result.len() / 4);
+
+        // The full text stays retrievable through tool_output
+        let retained = agent.tool_outputs.get("call_big").unwrap();
+        assert_eq!(retained.output, result);
+        let page = agent
+            .tools
+            .get("tool_output")
+            .unwrap()
+            .execute(serde_json::json!({"call_id": "call_big", "max_chars": 20}))
+            .await
+            .unwrap();
+        let elided = &result[retained.elided_from..];
+        assert_eq!(page["content"], elided[..20]);
+        assert!(!text.contains(&elided[..20]));
+
+        // Small results pass through untouched
+        agent.push_tool_result_message(true, "call_small", "shell_exec", true, "ok");
+        assert_eq!(agent.messages.last().unwrap().content.text(), "ok");
+        assert!(agent.tool_outputs.get("call_small").is_none());
+
+        server.stop().await;
+    }
+
     // =========================================================================
     // image promotion tests
     // =========================================================================
@@ -2073,6 +2603,42 @@ mod tests {
         server.stop().await;
     }
 
+    #[tokio::test]
+    async fn test_push_result_artifacts_follow_model_vision() {
+        let server = MockLlmServer::builder().with_response("done").build().await;
+        let dir = tempfile::tempdir().unwrap();
+        let png = dir.path().join("shot.png");
+        xcap::image::RgbaImage::new(4, 3).save(&png).unwrap();
+        let mut result = serde_json::json!({"success": true, "output_path": png});
+        crate::tools::artifact::attach(
+            &mut result,
+            [crate::tools::artifact::Artifact::from_path(&png)],
+        );
+        let result = result.to_string();
+
+        // A text-only model gets a description
+        let config = test_config(format!("{}/v1", server.url()));
+        let mut agent = Agent::new(config.clone()).await.unwrap();
+        agent.push_tool_result_message(true, "call_a", "browser_screenshot", true, &result);
+        let msg = agent.messages.last().unwrap();
+        assert!(!msg.content.has_images());
+        assert!(msg.content.text().contains("4x3 pixels"));
+
+        // A vision model sees the image
+        let mut config = config;
+        let mut profile = config.profile_for_switch(&config.model);
+        profile.modalities = vec!["text".to_string(), "vision".to_string()];
+        config.models.insert("main".to_string(), profile);
+        let mut agent = Agent::new(config).await.unwrap();
+        agent.push_tool_result_message(false, "call_b", "browser_screenshot", true, &result);
+        let msg = agent.messages.last().unwrap();
+        assert_eq!(msg.content.image_count(), 1);
+        assert!(msg.content.text().contains("\"attached\":true"));
+        assert!(!msg.content.text().contains("pixels"));
+
+        server.stop().await;
+    }
+
     #[test]
     fn test_try_extract_base64_png() {
         assert_eq!(
@@ -2127,6 +2693,39 @@ mod tests {
         server.stop().await;
     }
 
+    #[tokio::test]
+    async fn test_log_reasoning_only_when_enabled() {
+        let server = MockLlmServer::builder().with_response("done").build().await;
+        let config = test_config(format!("{}/v1", server.url()));
+        let mut agent = Agent::new(config).await.unwrap();
+        agent.current_checkpoint = Some(crate::checkpoint::TaskCheckpoint::new(
+            "test".to_string(),
+            "test desc".to_string(),
+        ));
+        agent.messages.push(Message {
+            reasoning_content: Some("the fixture is stale".to_string()),
+            ..Message::assistant("Updating the fixture")
+        });
+
+        agent.log_reasoning(Some("the fixture is stale"));
+        assert!(agent
+            .current_checkpoint
+            .as_ref()
+            .unwrap()
+            .reasoning
+            .is_empty());
+
+        agent.config.store_reasoning = true;
+        agent.log_reasoning(Some("the fixture is stale"));
+        let cp = agent.to_checkpoint("test", "test desc");
+        assert_eq!(cp.reasoning.len(), 1);
+        assert_eq!(cp.reasoning[0].text, "the fixture is stale");
+        // The journal keeps reasoning once, in the capped per-step log
+        assert!(cp.messages.iter().all(|m| m.reasoning_content.is_none()));
+
+        server.stop().await;
+    }
+
     #[tokio::test]
     async fn test_log_tool_call_truncates_when_requested() {
         let server = MockLlmServer::builder().with_response("done").build().await;
@@ -2255,6 +2854,25 @@ mod tests {
         server.stop().await;
     }
 
+    #[tokio::test]
+    async fn test_enhance_cargo_test_only_on_compile_error() {
+        let server = MockLlmServer::builder().with_response("done").build().await;
+        let config = test_config(format!("{}/v1", server.url()));
+        let agent = Agent::new(config).await.unwrap();
+
+        let failing = r#"{"success":false,"outcome":"tests_failed","errors":[]}"#;
+        assert_eq!(
+            agent.maybe_enhance_tool_result("cargo_test", failing),
+            failing
+        );
+
+        let broken = r#"{"success":false,"outcome":"compile_error","errors":[{"code":"E0425","message":"cannot find value `x` in this scope","file":"src/lib.rs","line":3,"column":5}]}"#;
+        let result = agent.maybe_enhance_tool_result("cargo_test", broken);
+        assert!(result.contains("<error_analysis>"));
+
+        server.stop().await;
+    }
+
     // =========================================================================
     // collect_tool_calls tests (via agent instance)
     // =========================================================================
@@ -2525,6 +3143,29 @@ mod tests {
         server.stop().await;
     }
 
+    #[tokio::test]
+    async fn test_leftover_code_blocks_completion_once() {
+        let server = MockLlmServer::builder().with_response("done").build().await;
+        let config = test_config(format!("{}/v1", server.url()));
+        let mut agent = Agent::new(config).await.unwrap();
+        assert!(agent.check_leftover_code().is_none());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lib.rs");
+        std::fs::write(&path, "fn a() {}\nfn b() { dbg!(2); }\n").unwrap();
+        let path = path.display().to_string();
+        agent
+            .verification_gate
+            .record_baseline(&path, "fn a() {}\n");
+
+        let msg = agent.check_leftover_code().unwrap();
+        assert!(msg.contains(&format!("{}:2: fn b() {{ dbg!(2); }}", path)));
+        // Completing again keeps the line
+        assert!(agent.check_leftover_code().is_none());
+
+        server.stop().await;
+    }
+
     // =========================================================================
     // maybe_verify_file_change tests
     // =========================================================================
@@ -2536,7 +3177,9 @@ mod tests {
         let mut agent = Agent::new(config).await.unwrap();
 
         let args = serde_json::json!({"command": "ls"});
-        let result = agent.maybe_verify_file_change("shell_exec", &args).await;
+        let result = agent
+            .maybe_verify_file_change("shell_exec", &args, &[])
+            .await;
         assert!(result.is_none());
 
         server.stop().await;
@@ -2549,7 +3192,9 @@ mod tests {
         let mut agent = Agent::new(config).await.unwrap();
 
         let args = serde_json::json!({"path": "test.rs"});
-        let result = agent.maybe_verify_file_change("file_read", &args).await;
+        let result = agent
+            .maybe_verify_file_change("file_read", &args, &[])
+            .await;
         assert!(result.is_none());
 
         server.stop().await;
@@ -2562,7 +3207,9 @@ mod tests {
         let mut agent = Agent::new(config).await.unwrap();
 
         let args = serde_json::json!({"old_str": "x", "new_str": "y"});
-        let result = agent.maybe_verify_file_change("file_edit", &args).await;
+        let result = agent
+            .maybe_verify_file_change("file_edit", &args, &[])
+            .await;
         assert!(result.is_none());
 
         server.stop().await;
@@ -2816,6 +3463,248 @@ mod tests {
         server.stop().await;
     }
 
+    #[tokio::test]
+    async fn test_tool_batch_runs_and_records_in_emission_order() {
+        let server = MockLlmServer::builder().with_response("done").build().await;
+        let mut config = test_config(format!("{}/v1", server.url()));
+        config.agent.native_function_calling = true;
+        let mut agent = Agent::new(config).await.unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        let path_str = path.display().to_string();
+        let initial_len = agent.messages.len();
+
+        // Each call depends on the one before it in the same turn
+        let calls: Vec<CollectedToolCall> = vec![
+            (
+                "file_write".to_string(),
+                serde_json::json!({"path": path_str, "content": "first draft"}).to_string(),
+                Some("call_0".to_string()),
+            ),
+            (
+                "file_edit".to_string(),
+                serde_json::json!({"path": path_str, "old_str": "first", "new_str": "second"})
+                    .to_string(),
+                Some("call_1".to_string()),
+            ),
+            (
+                "file_read".to_string(),
+                serde_json::json!({"path": path_str}).to_string(),
+                Some("call_2".to_string()),
+            ),
+        ];
+        agent.execute_tool_batch(calls).await.unwrap();
+
+        let results: Vec<&Message> = agent.messages[initial_len..]
+            .iter()
+            .filter(|m| m.role == "tool")
+            .collect();
+        let ids: Vec<&str> = results
+            .iter()
+            .filter_map(|m| m.tool_call_id.as_deref())
+            .collect();
+        assert_eq!(ids, ["call_0", "call_1", "call_2"]);
+        assert!(results[2].content.text().contains("second draft"));
+        let names: Vec<&str> = agent
+            .last_step_tools
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        assert_eq!(names, ["file_write", "file_edit", "file_read"]);
+        assert!(agent.last_step_tools.iter().all(|(_, success)| *success));
+
+        server.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_only_intended_overwrite_is_checkpointed() {
+        let server = MockLlmServer::builder().with_response("done").build().await;
+        let config = test_config(format!("{}/v1", server.url()));
+        let mut agent = Agent::new(config).await.unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lib.rs");
+        std::fs::write(&path, "original").unwrap();
+        let path_str = path.display().to_string();
+        let start = std::time::Instant::now();
+
+        let args = serde_json::json!({"path": path_str, "content": "replaced"});
+        let (success, _, _) = agent
+            .execute_single_tool("file_write", &args.to_string(), &args, start)
+            .await
+            .unwrap();
+        assert!(!success);
+        assert!(agent.edit_history.is_empty());
+
+        let args = serde_json::json!({"path": path_str, "content": "replaced", "overwrite": true});
+        let (success, _, _) = agent
+            .execute_single_tool("file_write", &args.to_string(), &args, start)
+            .await
+            .unwrap();
+        assert!(success);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "replaced");
+        let checkpoint = agent.edit_history.current_checkpoint().unwrap();
+        assert_eq!(checkpoint.files[&path].content, "original");
+
+        server.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_non_utf8_file_is_not_checkpointed_as_created() {
+        let server = MockLlmServer::builder().with_response("done").build().await;
+        let config = test_config(format!("{}/v1", server.url()));
+        let mut agent = Agent::new(config).await.unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("blob.bin");
+        std::fs::write(&path, [0xff, 0xfe, 0x00]).unwrap();
+        let start = std::time::Instant::now();
+
+        let args = serde_json::json!({"path": path.display().to_string(), "content": "text"});
+        let (success, _, _) = agent
+            .execute_single_tool("file_write", &args.to_string(), &args, start)
+            .await
+            .unwrap();
+        assert!(!success);
+        assert!(agent.edit_history.is_empty());
+        assert_eq!(std::fs::read(&path).unwrap(), [0xff, 0xfe, 0x00]);
+
+        server.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_cargo_add_needs_confirmation_outside_yolo() {
+        let server = MockLlmServer::builder().with_response("done").build().await;
+        let mut config = test_config(format!("{}/v1", server.url()));
+        config.execution_mode = crate::config::ExecutionMode::Normal;
+        let mut agent = Agent::new(config).await.unwrap();
+
+        assert!(agent.needs_confirmation("cargo_add"));
+        agent.set_execution_mode(crate::config::ExecutionMode::AutoEdit);
+        assert!(agent.needs_confirmation("cargo_add"));
+        agent.set_execution_mode(crate::config::ExecutionMode::Yolo);
+        assert!(!agent.needs_confirmation("cargo_add"));
+
+        server.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_branch_tools_confirm_only_when_stashing() {
+        let server = MockLlmServer::builder().with_response("done").build().await;
+        let mut config = test_config(format!("{}/v1", server.url()));
+        config.execution_mode = crate::config::ExecutionMode::Normal;
+        let mut agent = Agent::new(config).await.unwrap();
+
+        assert!(!agent.call_needs_confirmation("git_branch_create", r#"{"name": "feature/x"}"#));
+        assert!(!agent.call_needs_confirmation("git_checkout", r#"{"ref": "main"}"#));
+        assert!(agent.call_needs_confirmation("git_checkout", r#"{"ref": "main", "stash": true}"#));
+        agent.set_execution_mode(crate::config::ExecutionMode::Yolo);
+        assert!(!agent.call_needs_confirmation("git_checkout", r#"{"ref": "main", "stash": true}"#));
+
+        server.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_large_edit_asks_in_auto_edit_and_is_audited_in_yolo() {
+        let server = MockLlmServer::builder().with_response("done").build().await;
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("big.rs");
+        let original: String = (0..60).map(|i| format!("fn f{}() {{}}\n", i)).collect();
+        std::fs::write(&file, &original).unwrap();
+        let audit = dir.path().join("audit.jsonl");
+
+        let mut config = test_config(format!("{}/v1", server.url()));
+        config.yolo.audit_log_path = Some(audit.clone());
+        let mut agent = Agent::new(config).await.unwrap();
+        let rewrite = serde_json::json!({
+            "path": file.to_string_lossy(),
+            "content": "fn main() {}\n",
+        })
+        .to_string();
+
+        // YOLO goes ahead but leaves a note in the audit log
+        assert!(agent
+            .confirm_tool_execution("file_write", &rewrite, "call_1", false)
+            .unwrap());
+        #[cfg(feature = "execution-modes")]
+        {
+            let logged = std::fs::read_to_string(&audit).unwrap();
+            assert!(logged.contains("Flagged"), "{}", logged);
+            assert!(logged.contains("large edit"), "{}", logged);
+        }
+
+        // Auto-edit would normally approve file_write without asking
+        agent.set_execution_mode(crate::config::ExecutionMode::AutoEdit);
+        let tweak = serde_json::json!({
+            "path": file.to_string_lossy(),
+            "content": original.replacen("fn f0", "fn first", 1),
+        })
+        .to_string();
+        assert!(agent
+            .confirm_tool_execution("file_write", &tweak, "call_2", false)
+            .unwrap());
+        if !agent.is_interactive() {
+            let err = agent
+                .confirm_tool_execution("file_write", &rewrite, "call_3", false)
+                .unwrap_err();
+            assert!(err.to_string().contains("file_write"), "{}", err);
+        }
+
+        server.stop().await;
+    }
+
+    /// Claims to print JSON but passes through whatever the command wrote
+    struct JsonReportTool;
+
+    #[async_trait::async_trait]
+    impl crate::tools::Tool for JsonReportTool {
+        fn name(&self) -> &str {
+            "json_report"
+        }
+        fn description(&self) -> &str {
+            "Print the project report as JSON"
+        }
+        fn schema(&self) -> Value {
+            serde_json::json!({"type": "object", "properties": {}})
+        }
+        fn output_schema(&self) -> Option<Value> {
+            Some(serde_json::json!({
+                "type": "object",
+                "required": ["stdout"],
+                "properties": {
+                    "stdout": {"type": "string", "contentMediaType": "application/json"}
+                }
+            }))
+        }
+        async fn execute(&self, _args: Value) -> anyhow::Result<Value> {
+            Ok(serde_json::json!({"stdout": "error: unknown flag --json\nUsage: report [-v]"}))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_single_tool_invalid_output_is_a_clean_error() {
+        let server = MockLlmServer::builder().with_response("done").build().await;
+        let config = test_config(format!("{}/v1", server.url()));
+        let mut agent = Agent::new(config).await.unwrap();
+        agent.tools.register(JsonReportTool);
+
+        let args = serde_json::json!({});
+        let (success, result_str, _) = agent
+            .execute_single_tool("json_report", "{}", &args, std::time::Instant::now())
+            .await
+            .unwrap();
+
+        assert!(!success);
+        assert!(
+            result_str.starts_with(
+                "Tool 'json_report' returned output that does not match its output schema: \
+                 $.stdout: expected JSON text, got \"error: unknown flag --json"
+            ),
+            "{}",
+            result_str
+        );
+
+        server.stop().await;
+    }
+
     // =========================================================================
     // plan tests (via mock server)
     // =========================================================================
@@ -3028,6 +3917,68 @@ mod tests {
         server.stop().await;
     }
 
+    #[tokio::test]
+    #[cfg_attr(
+        target_os = "windows",
+        ignore = "mock TCP server unreliable on Windows CI"
+    )]
+    async fn test_step_recovers_from_context_overflow() {
+        // Recorded from vLLM's OpenAI-compatible server
+        let overflow = r#"{"object":"error","message":"This model's maximum context length is 32768 tokens. However, you requested 33871 tokens (25679 in the messages, 8192 in the completion). Please reduce the length of the messages or completion.","type":"BadRequestError","param":null,"code":400}"#;
+        let server = MockLlmServer::builder()
+            .with_error(400, overflow)
+            .with_response("Done after trimming.")
+            .build()
+            .await;
+
+        let config = test_config(format!("{}/v1", server.url()));
+        let mut agent = Agent::new(config).await.unwrap();
+        for i in 0..40 {
+            agent.messages.push(Message::user(format!(
+                "older message {} {}",
+                i,
+                "x".repeat(2000)
+            )));
+        }
+        agent.messages.push(Message::user("Finish the task"));
+        let before = agent.messages.len();
+
+        let result = agent.execute_step_internal(false).await;
+        assert!(result.unwrap(), "step should complete after one retry");
+        assert!(agent.messages.len() < before);
+        assert!(agent
+            .messages
+            .iter()
+            .any(|m| m.content.text() == "Finish the task"));
+        assert!(agent.max_context_tokens < 100_000);
+
+        server.stop().await;
+    }
+
+    #[tokio::test]
+    #[cfg_attr(
+        target_os = "windows",
+        ignore = "mock TCP server unreliable on Windows CI"
+    )]
+    async fn test_step_gives_up_after_second_overflow() {
+        let overflow = r#"{"error":{"code":400,"message":"the request exceeds the available context size, try increasing it","type":"exceed_context_size_error"}}"#;
+        let server = MockLlmServer::builder()
+            .with_error(400, overflow)
+            .with_error(400, overflow)
+            .with_response("unreachable")
+            .build()
+            .await;
+
+        let config = test_config(format!("{}/v1", server.url()));
+        let mut agent = Agent::new(config).await.unwrap();
+        agent.messages.push(Message::user("Finish the task"));
+
+        let err = agent.execute_step_internal(false).await.unwrap_err();
+        assert!(crate::errors::is_context_overflow_error(&err));
+
+        server.stop().await;
+    }
+
     #[tokio::test]
     #[cfg_attr(
         target_os = "windows",
@@ -0,0 +1,212 @@
+//! `/explain`: why the agent took its most recent step
+//!
+//! Each request to the model records what went into it besides the
+//! conversation: the learning hint merged into the system prompt, the tool
+//! results from the step before, and the RAG chunks in context. `/explain`
+//! prints that next to the plan step and cognitive state, and what the
+//! model did with it. Only the latest step is kept.
+
+use super::*;
+
+/// Inputs to the most recent model request
+#[derive(Debug, Clone, Default)]
+pub(super) struct DecisionTrace {
+    /// Loop step the request was made in
+    pub step: usize,
+    /// Learning hint merged into the system prompt, if any
+    pub learning_hint: Option<String>,
+    /// Tool calls whose results the model had just received
+    pub inputs: Vec<String>,
+    /// RAG chunks (`file:start-end`) inserted into the context so far
+    pub rag_chunks: Vec<String>,
+}
+
+/// Longest excerpt of the model's reply shown as its reasoning
+const REPLY_EXCERPT_CHARS: usize = 300;
+
+impl Agent {
+    /// One-line description of a tool call, e.g. "Reading src/main.rs"
+    pub(super) fn describe_call(name: &str, args_str: &str) -> String {
+        let args = serde_json::from_str(args_str).unwrap_or(serde_json::Value::Null);
+        let activity = output::tool_activity_message(name, &args);
+        format!("{} ({})", activity.trim_end_matches("..."), name)
+    }
+
+    /// Snapshot what the next request adds to the conversation. The calls
+    /// of the previous step become its inputs, so afterwards
+    /// `last_step_calls` only holds calls the model makes in reply.
+    pub(super) fn record_decision_inputs(&mut self, learning_hint: Option<&str>) {
+        self.decision_trace = Some(DecisionTrace {
+            step: self.loop_control.current_step(),
+            learning_hint: learning_hint.map(str::to_string),
+            inputs: std::mem::take(&mut self.last_step_calls),
+            rag_chunks: self.rag.inserted_chunks(),
+        });
+    }
+
+    /// Text for `/explain`, or `None` before the first request
+    pub(super) fn explain_last_step(&self) -> Option<String> {
+        use crate::cognitive::state::StepStatus;
+
+        let trace = self.decision_trace.as_ref()?;
+        let mut out = vec![format!("Step {}", trace.step + 1)];
+
+        out.push("\nDecision:".to_string());
+        if self.last_step_calls.is_empty() {
+            out.push("  replied without calling tools".to_string());
+        } else {
+            let statuses = (self.last_step_tools.len() == self.last_step_calls.len())
+                .then_some(&self.last_step_tools);
+            for (i, call) in self.last_step_calls.iter().enumerate() {
+                let mark = match statuses.map(|s| s[i].1) {
+                    Some(true) => "✓",
+                    Some(false) => "✗",
+                    None => "•",
+                };
+                out.push(format!("  {} {}", mark, call));
+            }
+        }
+        let reply = self.last_assistant_response.trim();
+        if !reply.is_empty() {
+            let excerpt: String = reply.chars().take(REPLY_EXCERPT_CHARS).collect();
+            let ellipsis = if reply.chars().count() > REPLY_EXCERPT_CHARS {
+                "…"
+            } else {
+                ""
+            };
+            out.push(format!(
+                "  Model said: {}{}",
+                excerpt.replace('\n', " "),
+                ellipsis
+            ));
+        }
+
+        out.push("\nPlan:".to_string());
+        out.push(format!(
+            "  Phase: {}",
+            self.cognitive_state.cycle_phase.as_str()
+        ));
+        if let Some(plan) = &self.cognitive_state.active_tactical_plan {
+            out.push(format!("  Goal: {}", plan.description));
+        }
+        let plan_step = self
+            .cognitive_state
+            .active_operational_plan
+            .as_ref()
+            .and_then(|plan| {
+                plan.steps
+                    .iter()
+                    .find(|s| s.status == StepStatus::InProgress)
+                    .or_else(|| {
+                        plan.steps
+                            .iter()
+                            .rev()
+                            .find(|s| s.status != StepStatus::Pending)
+                    })
+            });
+        if let Some(step) = plan_step {
+            out.push(format!(
+                "  Step {} ({:?}): {}",
+                step.index, step.status, step.description
+            ));
+            if let Some(notes) = &step.notes {
+                out.push(format!("    {}", notes));
+            }
+        }
+        let memory = &self.cognitive_state.working_memory;
+        if let Some(hypothesis) = &memory.active_hypothesis {
+            out.push(format!("  Hypothesis: {}", hypothesis));
+        }
+        if let Some(approach) = memory.approach_stack.last() {
+            out.push(format!("  Approach: {}", approach.description));
+        }
+
+        out.push("\nLearning hints injected:".to_string());
+        match &trace.learning_hint {
+            Some(hint) => out.extend(
+                hint.lines()
+                    .filter(|l| !l.trim().is_empty())
+                    .map(|l| format!("  {}", l)),
+            ),
+            None => out.push("  none".to_string()),
+        }
+
+        out.push("\nContext it acted on:".to_string());
+        if trace.inputs.is_empty() {
+            out.push("  no tool results since the previous reply".to_string());
+        } else {
+            out.extend(trace.inputs.iter().map(|i| format!("  ← {}", i)));
+        }
+        if !trace.rag_chunks.is_empty() {
+            out.push(format!("  RAG chunks: {}", trace.rag_chunks.join(", ")));
+        }
+        if !self.context_files.is_empty() {
+            out.push(format!(
+                "  Files loaded with /ctx: {}",
+                self.context_files.len()
+            ));
+        }
+
+        out.push("\nToken, cost and timing figures: /stats".to_string());
+        Some(out.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_explain_shows_inputs_hint_and_decision() {
+        let server = crate::testing::mock_api::MockLlmServer::builder()
+            .with_response("done")
+            .build()
+            .await;
+        let config = crate::config::Config {
+            endpoint: format!("{}/v1", server.url()),
+            model: "mock".to_string(),
+            ..Default::default()
+        };
+        let mut agent = Agent::new(config).await.unwrap();
+        assert!(agent.explain_last_step().is_none());
+
+        agent.last_step_calls = vec![Agent::describe_call(
+            "file_read",
+            r#"{"path": "src/lexer.rs"}"#,
+        )];
+        agent.record_decision_inputs(Some("Prefer previously effective tools: file_edit."));
+        assert!(agent.last_step_calls.is_empty());
+
+        agent.last_step_calls = vec![Agent::describe_call(
+            "file_edit",
+            r#"{"path": "src/lexer.rs"}"#,
+        )];
+        agent.last_step_tools = vec![("file_edit".to_string(), true)];
+        agent.last_assistant_response = "Fixing the off-by-one in the lexer".to_string();
+
+        let text = agent.explain_last_step().unwrap();
+        assert!(
+            text.contains("✓ Editing src/lexer.rs (file_edit)"),
+            "{}",
+            text
+        );
+        assert!(
+            text.contains("← Reading src/lexer.rs (file_read)"),
+            "{}",
+            text
+        );
+        assert!(
+            text.contains("Prefer previously effective tools"),
+            "{}",
+            text
+        );
+        assert!(
+            text.contains("Model said: Fixing the off-by-one"),
+            "{}",
+            text
+        );
+        assert!(text.contains("/stats"));
+
+        server.stop().await;
+    }
+}
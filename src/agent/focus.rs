@@ -0,0 +1,120 @@
+//! `/focus` and `--focus`: scope a task to a set of paths
+//!
+//! While a focus is set, the safety checker rejects edits outside it,
+//! `rag_search` ranks chunks from focused files first, and the focused files
+//! are loaded into the context when the focus is set. The focus is saved in
+//! the task checkpoint, so a resumed task keeps its scope.
+
+use std::path::Path;
+
+use colored::Colorize;
+
+use super::*;
+
+/// Share of the context window focused files may fill when loaded upfront
+const FOCUS_CONTEXT_SHARE: usize = 4;
+
+impl Agent {
+    /// Scope edits and retrieval to `paths` (files or directories) and load
+    /// their files into the context. Returns the number of files loaded.
+    pub(super) fn set_focus(&mut self, paths: Vec<String>) -> Result<usize> {
+        let mut focus: Vec<String> = Vec::new();
+        for path in paths {
+            if !Path::new(&path).exists() {
+                anyhow::bail!("Focus path not found: {}", path);
+            }
+            if !focus.contains(&path) {
+                focus.push(path);
+            }
+        }
+        if focus.is_empty() {
+            anyhow::bail!("Usage: /focus <paths...> | /focus clear");
+        }
+        self.focus = focus;
+        self.apply_focus();
+        self.load_focus_files()
+    }
+
+    /// Lift the focus; edits and retrieval go back to normal
+    pub(super) fn clear_focus(&mut self) {
+        self.focus.clear();
+        self.apply_focus();
+    }
+
+    /// Paths the current task is scoped to, as given
+    pub fn focus(&self) -> &[String] {
+        &self.focus
+    }
+
+    /// Hand the focus set to the safety checker and the RAG index
+    pub(super) fn apply_focus(&mut self) {
+        self.safety.set_focus(&self.focus);
+        self.rag.set_focus(self.safety.focus().to_vec());
+    }
+
+    /// Add a note naming the focus and the content of its files to the
+    /// conversation, stopping once they would fill a quarter of the window
+    fn load_focus_files(&mut self) -> Result<usize> {
+        let budget = self.memory.context_window() / FOCUS_CONTEXT_SHARE;
+        let note = Message::user(format!(
+            "Focus for this task: only these paths may be changed, and edits \
+             elsewhere will be rejected:\n- {}",
+            self.focus.join("\n- ")
+        ));
+        self.memory.add_message(&note);
+        self.messages.push(note);
+
+        let mut files = Vec::new();
+        for path in &self.focus {
+            for entry in walkdir::WalkDir::new(path)
+                .sort_by_file_name()
+                .into_iter()
+                .filter_entry(|e| {
+                    !matches!(
+                        e.file_name().to_str(),
+                        Some("target" | "node_modules" | ".git" | "__pycache__")
+                    )
+                })
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_type().is_file())
+            {
+                files.push(entry.path().display().to_string());
+            }
+        }
+
+        let mut loaded = 0;
+        let mut tokens = 0;
+        for path_str in files {
+            if self
+                .messages
+                .iter()
+                .any(|m| m.role == "user" && m.content.contains(&format!("// FILE: {}", path_str)))
+            {
+                continue;
+            }
+            let Ok(content) = std::fs::read_to_string(&path_str) else {
+                continue;
+            };
+            let full_content = format!("\n// ═══════════════════════════════════════════\n// FILE: {}\n// ═══════════════════════════════════════════\n{}", path_str, content);
+            let file_tokens = crate::token_count::estimate_tokens_with_overhead(&full_content, 4);
+            if budget > 0 && tokens + file_tokens > budget {
+                println!(
+                    "{} Focused files exceed a quarter of the context window; \
+                     loaded {} upfront, the rest stay reachable with file_read",
+                    "⚠️".bright_yellow(),
+                    loaded
+                );
+                break;
+            }
+            tokens += file_tokens;
+            if !self.context_files.contains(&path_str) {
+                self.context_files.push(path_str);
+            }
+            let msg = Message::user(full_content);
+            self.memory.add_message(&msg);
+            self.messages.push(msg);
+            loaded += 1;
+        }
+        Ok(loaded)
+    }
+}
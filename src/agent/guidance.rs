@@ -0,0 +1,254 @@
+//! Project guidance ingested from convention files such as `AGENTS.md`.
+//!
+//! Many repositories describe their conventions in a markdown file at the
+//! root. [`load`] finds the configured files, joins them under per-file
+//! headers and caps the result at a token budget. The agent prepends the
+//! block to its system prompt on startup and swaps it out on `/reload`.
+
+use super::Agent;
+use crate::api::types::MessageContent;
+use crate::token_count::estimate_content_tokens;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+const TRUNCATION_NOTE: &str = "\n[... truncated to fit the project guidance budget ...]";
+
+/// Guidance assembled from the convention files found at the repository root.
+#[derive(Debug, Clone, Default)]
+pub struct ProjectGuidance {
+    /// Files whose content was included, in prompt order.
+    pub files: Vec<PathBuf>,
+    /// The block prepended to the system prompt.
+    pub text: String,
+    /// Estimated tokens in `text`.
+    pub tokens: usize,
+    /// True when a file was cut short or skipped to stay within the budget.
+    pub truncated: bool,
+}
+
+/// Walk up from `start` to the nearest directory containing `.git`.
+/// Falls back to `start` itself outside a repository.
+pub fn repo_root(start: &Path) -> PathBuf {
+    start
+        .ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .unwrap_or(start)
+        .to_path_buf()
+}
+
+/// Read `filenames` from `root` in order and build the guidance block.
+///
+/// Returns `None` when the budget is zero or none of the files exist.
+pub fn load(root: &Path, filenames: &[String], max_tokens: usize) -> Option<ProjectGuidance> {
+    if max_tokens == 0 {
+        return None;
+    }
+
+    let mut guidance = ProjectGuidance {
+        text: "# Project Guidance\n\nConventions from this repository's own documentation. \
+               Follow them unless the user says otherwise.\n"
+            .to_string(),
+        ..Default::default()
+    };
+    let mut seen = Vec::new();
+
+    for name in filenames {
+        let path = root.join(name);
+        let Ok(canonical) = path.canonicalize() else {
+            continue;
+        };
+        // Case-insensitive filesystems resolve AGENTS.md and agents.md to one file
+        if seen.contains(&canonical) {
+            continue;
+        }
+        seen.push(canonical);
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            warn!("Could not read project guidance file {}", path.display());
+            continue;
+        };
+        let content = content.trim();
+        if content.is_empty() {
+            continue;
+        }
+
+        let section = format!("\n## {}\n\n{}\n", name, content);
+        let used = estimate_content_tokens(&guidance.text);
+        let remaining = max_tokens.saturating_sub(used);
+        let tokens = estimate_content_tokens(&section);
+        if tokens <= remaining {
+            guidance.text.push_str(&section);
+            guidance.files.push(path);
+            continue;
+        }
+
+        guidance.truncated = true;
+        let cut = truncate_to_tokens(&section, remaining);
+        if cut.trim().is_empty() {
+            warn!(
+                "Project guidance budget ({} tokens) exhausted, skipping {}",
+                max_tokens,
+                path.display()
+            );
+            break;
+        }
+        warn!(
+            "Project guidance file {} is ~{} tokens and was truncated to fit the {} token budget",
+            path.display(),
+            tokens,
+            max_tokens
+        );
+        guidance.text.push_str(cut);
+        guidance.text.push_str(TRUNCATION_NOTE);
+        guidance.text.push('\n');
+        guidance.files.push(path);
+        break;
+    }
+
+    if guidance.files.is_empty() {
+        return None;
+    }
+    guidance.tokens = estimate_content_tokens(&guidance.text);
+    Some(guidance)
+}
+
+/// Cut `text` to roughly `max_tokens`, ending on a line boundary and never
+/// inside a multibyte character.
+fn truncate_to_tokens(text: &str, max_tokens: usize) -> &str {
+    let tokens = estimate_content_tokens(text).max(1);
+    let budget = max_tokens.saturating_sub(estimate_content_tokens(TRUNCATION_NOTE));
+    let mut end = text.floor_char_boundary(text.len() * budget / tokens);
+    while end > 0 && estimate_content_tokens(&text[..end]) > budget {
+        end = text.floor_char_boundary(end * 9 / 10);
+    }
+    match text[..end].rfind('\n') {
+        Some(newline) => &text[..newline],
+        None => &text[..end],
+    }
+}
+
+impl Agent {
+    /// Re-read the guidance files and swap the block at the top of the system
+    /// prompt. Returns the new guidance, or `None` when no files were found.
+    pub(super) fn reload_project_guidance(&mut self) -> Option<ProjectGuidance> {
+        let cwd = std::env::current_dir().unwrap_or_else(|_| ".".into());
+        let guidance = load(
+            &repo_root(&cwd),
+            &self.config.agent.guidance_files,
+            self.config.agent.guidance_max_tokens,
+        );
+        self.set_project_guidance(guidance.as_ref());
+        guidance
+    }
+
+    /// Replace the previously prepended guidance block (if any) with `guidance`.
+    fn set_project_guidance(&mut self, guidance: Option<&ProjectGuidance>) {
+        let prefix = guidance
+            .map(|g| format!("{}\n", g.text))
+            .unwrap_or_default();
+        if let Some(system) = self.messages.iter_mut().find(|m| m.role == "system") {
+            let current = system.content.text();
+            let base = current
+                .strip_prefix(self.project_guidance.as_str())
+                .unwrap_or(current);
+            system.content = MessageContent::from_text(format!("{}{}", prefix, base));
+        }
+        self.project_guidance = prefix;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_load_concatenates_in_configured_order() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("AGENTS.md"), "Use anyhow.").unwrap();
+        std::fs::write(dir.path().join("CLAUDE.md"), "Run clippy.").unwrap();
+
+        let guidance = load(dir.path(), &names(&["CLAUDE.md", "AGENTS.md"]), 4000).unwrap();
+        let claude = guidance.text.find("## CLAUDE.md").unwrap();
+        let agents = guidance.text.find("## AGENTS.md").unwrap();
+        assert!(claude < agents);
+        assert!(guidance.text.contains("Use anyhow."));
+        assert_eq!(guidance.files.len(), 2);
+        assert!(!guidance.truncated);
+    }
+
+    #[test]
+    fn test_load_none_without_files_or_budget() {
+        let dir = TempDir::new().unwrap();
+        assert!(load(dir.path(), &names(&["AGENTS.md"]), 4000).is_none());
+
+        std::fs::write(dir.path().join("AGENTS.md"), "rules").unwrap();
+        assert!(load(dir.path(), &names(&["AGENTS.md"]), 0).is_none());
+    }
+
+    #[test]
+    fn test_load_truncates_to_budget() {
+        let dir = TempDir::new().unwrap();
+        let long: String = (0..2000)
+            .map(|i| format!("- rule number {} about naming\n", i))
+            .collect();
+        std::fs::write(dir.path().join("AGENTS.md"), &long).unwrap();
+        std::fs::write(dir.path().join("CLAUDE.md"), "never reached").unwrap();
+
+        let guidance = load(dir.path(), &names(&["AGENTS.md", "CLAUDE.md"]), 300).unwrap();
+        assert!(guidance.truncated);
+        assert!(guidance.tokens <= 300);
+        assert!(guidance.text.contains("truncated to fit"));
+        assert!(!guidance.text.contains("never reached"));
+    }
+
+    #[test]
+    fn test_truncate_respects_char_boundaries() {
+        let text = "日本語のルール\n".repeat(200);
+        let cut = truncate_to_tokens(&text, 50);
+        assert!(cut.len() < text.len());
+        assert!(cut.ends_with("ルール"));
+    }
+
+    #[tokio::test]
+    async fn test_set_project_guidance_replaces_previous_block() {
+        use crate::testing::mock_api::MockLlmServer;
+
+        let server = MockLlmServer::builder().build().await;
+        let config = crate::config::Config {
+            endpoint: format!("{}/v1", server.url()),
+            ..Default::default()
+        };
+        let mut agent = Agent::new(config).await.unwrap();
+        let original = agent.messages[0].content.text().to_string();
+        let dir = TempDir::new().unwrap();
+
+        std::fs::write(dir.path().join("AGENTS.md"), "first version").unwrap();
+        let first = load(dir.path(), &names(&["AGENTS.md"]), 4000);
+        agent.set_project_guidance(first.as_ref());
+        std::fs::write(dir.path().join("AGENTS.md"), "second version").unwrap();
+        let second = load(dir.path(), &names(&["AGENTS.md"]), 4000);
+        agent.set_project_guidance(second.as_ref());
+
+        let prompt = agent.messages[0].content.text();
+        assert!(prompt.starts_with("# Project Guidance"));
+        assert!(prompt.contains("second version"));
+        assert!(!prompt.contains("first version"));
+        assert!(prompt.ends_with(&original));
+
+        agent.set_project_guidance(None);
+        assert_eq!(agent.messages[0].content.text(), original);
+    }
+
+    #[test]
+    fn test_repo_root_finds_git_dir() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+        let nested = dir.path().join("a/b");
+        std::fs::create_dir_all(&nested).unwrap();
+        assert_eq!(repo_root(&nested), dir.path());
+    }
+}
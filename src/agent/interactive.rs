@@ -1,9 +1,16 @@
-use anyhow::Result;
+use crate::api::types::{ContentBlock, ImageUrl, MessageContent};
+use crate::cognitive::load::LoadLevel;
+use crate::session::chat_store::{ChatSummary, ContextSnapshot};
+use crate::tools::artifact::{self, Artifact};
+use anyhow::{bail, Result};
 use colored::*;
 use std::time::Instant;
 
 use super::*;
 
+/// Most images `/image` queues for one message
+const MAX_PENDING_IMAGES: usize = 4;
+
 /// Truncate a string at a char boundary, avoiding panics on multi-byte UTF-8.
 fn safe_truncate(s: &str, max_bytes: usize) -> &str {
     if s.len() <= max_bytes {
@@ -16,6 +23,38 @@ fn safe_truncate(s: &str, max_bytes: usize) -> &str {
     &s[..end]
 }
 
+/// Parse `/fork` arguments into a task ID and optional step.
+///
+/// Accepts `/fork` (current task), `/fork <step>` (current task at a step),
+/// `/fork <task_id>` and `/fork <task_id> <step>`.
+fn parse_fork_args(args: &str, current_task: Option<&str>) -> Option<(String, Option<usize>)> {
+    let parts: Vec<&str> = args.split_whitespace().collect();
+    match parts.as_slice() {
+        [] => current_task.map(|id| (id.to_string(), None)),
+        [single] => match (single.parse::<usize>(), current_task) {
+            (Ok(step), Some(id)) => Some((id.to_string(), Some(step))),
+            _ => Some((single.to_string(), None)),
+        },
+        [id, step] => step.parse().ok().map(|step| (id.to_string(), Some(step))),
+        _ => None,
+    }
+}
+
+/// Parse the arguments of `/feedback good|bad [note]`.
+fn parse_feedback_args(args: &str) -> Option<(bool, Option<&str>)> {
+    let args = args.trim();
+    let (verdict, note) = match args.split_once(char::is_whitespace) {
+        Some((verdict, note)) => (verdict, Some(note.trim())),
+        None => (args, None),
+    };
+    let positive = match verdict.to_lowercase().as_str() {
+        "good" => true,
+        "bad" => false,
+        _ => return None,
+    };
+    Some((positive, note.filter(|n| !n.is_empty())))
+}
+
 impl Agent {
     pub async fn interactive(&mut self) -> Result<()> {
         use std::io::IsTerminal;
@@ -87,6 +126,13 @@ impl Agent {
                 println!("\n{}", "Shutdown requested, exiting...".bright_yellow());
                 break;
             }
+            if self.runtime_limit_reached() {
+                break;
+            }
+            // "Save and quit" from the Ctrl+C pause menu
+            if self.quit_requested {
+                break;
+            }
 
             // Auto-refresh stale files before prompting
             let refreshed = self.refresh_stale_context_files().await;
@@ -100,6 +146,8 @@ impl Agent {
             }
 
             // Print status bar and update prompt with context usage before each input
+            self.pressure.sample();
+            self.show_wellness_reminder();
             self.print_status_bar();
             let ctx_pct = self.context_usage_pct();
             let step = self.loop_control.current_step();
@@ -110,6 +158,7 @@ impl Agent {
                     consecutive_errors = 0;
                     last_ctrl_c = None;
                     self.reset_cancellation();
+                    self.wellness.record_activity();
                     line
                 }
                 Ok(ReadlineResult::Interrupt) => {
@@ -266,10 +315,22 @@ impl Agent {
                     "│  {} /ctx copy          Copy sources to clip         │",
                     "📋".bright_white()
                 );
+                println!(
+                    "│  {} /reload            Re-read AGENTS.md guidance   │",
+                    "📜".bright_white()
+                );
                 println!(
                     "│  {} /compress          Compress context             │",
                     "🗜️ ".bright_white()
                 );
+                println!(
+                    "│  {} /compact [show|edit] Pin decisions + compress   │",
+                    "📌".bright_white()
+                );
+                println!(
+                    "│  {} /focus <paths|clear> Scope edits to paths       │",
+                    "🎯".bright_white()
+                );
                 println!(
                     "{}",
                     "├─────────────────────────────────────────────────┤".bright_cyan()
@@ -302,22 +363,42 @@ impl Agent {
                     "│  {} /undo              Undo last file edit          │",
                     "↩ ".bright_white()
                 );
+                println!(
+                    "│  {} /rewind            Take back the last turn      │",
+                    "⏪".bright_white()
+                );
                 println!(
                     "│  {} /cost              Token usage & cost           │",
                     "💰".bright_white()
                 );
                 println!(
-                    "│  {} /model             Model configuration          │",
+                    "│  {} /feedback good|bad Correct the last step        │",
+                    "👍".bright_white()
+                );
+                println!(
+                    "│  {} /explain           Why the last step happened   │",
+                    "🔍".bright_white()
+                );
+                println!(
+                    "│  {} /reset-circuit     Retry API now, skip cooldown │",
+                    "⚡".bright_white()
+                );
+                println!(
+                    "│  {} /model [name]      Show or switch model         │",
                     "🤖".bright_white()
                 );
                 println!(
-                    "│  {} /compact           Toggle compact mode          │",
+                    "│  {} /compact output    Compact mode (was /compact)  │",
                     "📦".bright_white()
                 );
                 println!(
                     "│  {} /verbose           Toggle verbose mode          │",
                     "📢".bright_white()
                 );
+                println!(
+                    "│  {} /brief [off]       Keep replies short           │",
+                    "✂ ".bright_white()
+                );
                 println!(
                     "│  {} /config            Show current config          │",
                     "⚙ ".bright_white()
@@ -334,10 +415,22 @@ impl Agent {
                     "│  {} /review <file>     Review code file             │",
                     "👁️ ".bright_white()
                 );
+                println!(
+                    "│  {} /garden [path]     Explore files as a garden    │",
+                    "🌳".bright_white()
+                );
+                println!(
+                    "│  {} /retry [hint]      Retry the last failed step   │",
+                    "🔁".bright_white()
+                );
                 println!(
                     "│  {} /plan <task>       Create task plan             │",
                     "📝".bright_white()
                 );
+                println!(
+                    "│  {} /plan export [f]   Export plan (.md or .mmd)    │",
+                    "🗺️ ".bright_white()
+                );
                 println!(
                     "│  {} /swarm <task>      Run task with dev swarm      │",
                     "🐝".bright_white()
@@ -366,6 +459,14 @@ impl Agent {
                     "│  {} /restore           List/restore checkpoints     │",
                     "⏪".bright_white()
                 );
+                println!(
+                    "│  {} /checkpoint [l]    Save and label state now     │",
+                    "🔖".bright_white()
+                );
+                println!(
+                    "│  {} /fork [id] [n]     Fork checkpoint as new task  │",
+                    "⑂ ".bright_white()
+                );
                 println!(
                     "│  {} /chat save <n>     Save chat session            │",
                     "💾".bright_white()
@@ -410,9 +511,10 @@ impl Agent {
                     "{}",
                     "├──────────────────────────────────────────────────────┤".bright_cyan()
                 );
-                println!("│  Ctrl+C        Interrupt running task               │");
-                println!("│  Ctrl+C ×2     Exit (double-tap at prompt)          │");
+                println!("│  Ctrl+C        Pause running task (menu)            │");
+                println!("│  Ctrl+C ×2     Abort running task / exit at prompt  │");
                 println!("│  Ctrl+J        Insert newline (multi-line)          │");
+                println!("│  ```           Compose block, send on closing ```   │");
                 println!("│  Ctrl+Y        Toggle YOLO mode                     │");
                 println!("│  Shift+Tab     Toggle Auto-Edit mode                │");
                 println!("│  Ctrl+X        Open external editor ($EDITOR)       │");
@@ -472,10 +574,9 @@ impl Agent {
                 continue;
             }
 
-            if input == "/tools" {
-                for tool in self.tools.list() {
-                    println!("  - {}: {}", tool.name(), tool.description());
-                }
+            if input == "/tools" || input.starts_with("/tools ") {
+                let name = input.strip_prefix("/tools").map(str::trim);
+                print!("{}", self.render_tools(name.filter(|n| !n.is_empty())));
                 continue;
             }
 
@@ -536,6 +637,38 @@ impl Agent {
                 continue;
             }
 
+            if input == "/reload" {
+                match self.reload_project_guidance() {
+                    Some(guidance) => {
+                        let names: Vec<String> = guidance
+                            .files
+                            .iter()
+                            .filter_map(|p| p.file_name())
+                            .map(|n| n.to_string_lossy().into_owned())
+                            .collect();
+                        println!(
+                            "{} Reloaded project guidance from {} (~{} tokens)",
+                            "🔄".bright_green(),
+                            names.join(", ").bright_white(),
+                            guidance.tokens
+                        );
+                        if guidance.truncated {
+                            println!(
+                                "{} Guidance exceeds agent.guidance_max_tokens ({}) and was truncated",
+                                "⚠️".bright_yellow(),
+                                self.config.agent.guidance_max_tokens
+                            );
+                        }
+                    }
+                    None => println!(
+                        "{} No project guidance files found ({})",
+                        "ℹ".bright_cyan(),
+                        self.config.agent.guidance_files.join(", ")
+                    ),
+                }
+                continue;
+            }
+
             if input == "/context copy" || input == "/ctx copy" {
                 match self.copy_sources_to_clipboard().await {
                     Ok(size) => {
@@ -546,6 +679,41 @@ impl Agent {
                 continue;
             }
 
+            if input == "/focus" {
+                if self.focus.is_empty() {
+                    println!(
+                        "{} No focus set. Use /focus <paths...> to scope edits",
+                        "ℹ".bright_cyan()
+                    );
+                } else {
+                    println!("{} Focus:", "🎯".bright_cyan());
+                    for path in &self.focus {
+                        println!("   - {}", path.bright_white());
+                    }
+                }
+                continue;
+            }
+
+            if input == "/focus clear" {
+                self.clear_focus();
+                println!("{} Focus cleared", "🎯".bright_green());
+                continue;
+            }
+
+            if let Some(paths) = input.strip_prefix("/focus ") {
+                let paths = paths.split_whitespace().map(str::to_string).collect();
+                match self.set_focus(paths) {
+                    Ok(count) => println!(
+                        "{} Focus set to {}; loaded {} files into context",
+                        "🎯".bright_green(),
+                        self.focus.join(", ").bright_white(),
+                        count
+                    ),
+                    Err(e) => println!("{} {}", "❌".bright_red(), e),
+                }
+                continue;
+            }
+
             // === New slash commands ===
 
             if input == "/diff" {
@@ -615,6 +783,49 @@ impl Agent {
                 continue;
             }
 
+            if input == "/rewind" {
+                let Some(plan) = self.rewind_plan() else {
+                    println!("{} Nothing to rewind", "ℹ".bright_yellow());
+                    continue;
+                };
+                let prompt: String = plan.prompt.chars().take(60).collect();
+                println!(
+                    "{} Rewind the last turn: \"{}\"",
+                    "⏪".bright_cyan(),
+                    prompt
+                );
+                println!(
+                    "  {} message(s) removed from the conversation",
+                    plan.messages
+                );
+                for path in &plan.restore {
+                    println!("  restore {}", path.display().to_string().bright_white());
+                }
+                for path in &plan.remove {
+                    println!("  delete  {}", path.display().to_string().bright_white());
+                }
+                print!("{}", "Rewind? [y/N]: ".bright_yellow());
+                std::io::Write::flush(&mut std::io::stdout()).ok();
+                let mut answer = String::new();
+                if std::io::stdin().read_line(&mut answer).is_err()
+                    || !matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+                {
+                    println!("{} Rewind cancelled", "⏭️".bright_yellow());
+                    continue;
+                }
+                match self.rewind_turn() {
+                    Ok(plan) => println!(
+                        "{} Rewound: {} message(s), {} file(s) restored, {} removed",
+                        "⏪".bright_green(),
+                        plan.messages,
+                        plan.restore.len(),
+                        plan.remove.len()
+                    ),
+                    Err(e) => println!("{} {:#}", "❌".bright_red(), e),
+                }
+                continue;
+            }
+
             if input == "/cost" {
                 let (prompt, completion) = output::get_total_tokens();
                 let total = prompt + completion;
@@ -626,7 +837,7 @@ impl Agent {
                     completion.to_string().bright_white()
                 );
                 println!("  Total:      {:>10}", total.to_string().bright_cyan());
-                let est_cost = (prompt as f64 * 3.0 + completion as f64 * 15.0) / 1_000_000.0;
+                let est_cost = output::estimate_cost_usd(prompt, completion);
                 if est_cost > 0.001 {
                     println!(
                         "  Est. cost:  {:>10}",
@@ -637,6 +848,118 @@ impl Agent {
                 continue;
             }
 
+            if input == "/feedback" || input.starts_with("/feedback ") {
+                let args = input.strip_prefix("/feedback").unwrap_or_default();
+                let Some((positive, note)) = parse_feedback_args(args) else {
+                    println!("{} Usage: /feedback good|bad [note]", "ℹ".bright_yellow());
+                    continue;
+                };
+                match self.record_user_feedback(positive, note) {
+                    Ok(feedback) => {
+                        let verdict = if feedback.positive {
+                            "good".bright_green()
+                        } else {
+                            "bad".bright_red()
+                        };
+                        println!(
+                            "{} Recorded last step as {} (task type: {}, weight x{})",
+                            "✓".bright_green(),
+                            verdict,
+                            feedback.task_type,
+                            crate::cognitive::self_improvement::FEEDBACK_WEIGHT
+                        );
+                        for (tool, succeeded) in &feedback.tools {
+                            let automatic = if *succeeded { "success" } else { "failure" };
+                            println!(
+                                "  {} {} {}",
+                                "•".dimmed(),
+                                tool.bright_white(),
+                                format!("(automatic: {})", automatic).dimmed()
+                            );
+                        }
+                        if let Some(note) = &feedback.note {
+                            println!("  {} Saved note as a lesson: {}", "•".dimmed(), note);
+                        }
+                        if let Err(e) = self.save_learning_state() {
+                            println!("{} Could not persist feedback: {}", "⚠️".bright_yellow(), e);
+                        }
+                    }
+                    Err(e) => println!("{} {}", "ℹ".bright_yellow(), e),
+                }
+                continue;
+            }
+
+            if input == "/reset-circuit" {
+                let before = self.client.circuit_state();
+                if self.client.reset_circuit().await {
+                    println!(
+                        "{} API circuit breaker reset ({} → closed)",
+                        "✓".bright_green(),
+                        before.state
+                    );
+                } else {
+                    println!(
+                        "{} API circuit breaker is already closed",
+                        "ℹ".bright_cyan()
+                    );
+                }
+                continue;
+            }
+
+            if let Some(name) = input.strip_prefix("/model ").map(str::trim) {
+                if name.is_empty() {
+                    println!("Usage: /model <name>");
+                    continue;
+                }
+                let switch = match self.switch_model(name) {
+                    Ok(switch) => switch,
+                    Err(e) => {
+                        println!("{} Model switch failed: {}", "❌".bright_red(), e);
+                        continue;
+                    }
+                };
+                self.routed_from = None;
+                println!(
+                    "{} Switched model: {} → {} ({})",
+                    "🤖".bright_cyan(),
+                    switch.previous.bright_black(),
+                    switch.model.bright_white(),
+                    switch.endpoint.bright_black()
+                );
+                if switch.exceeds_window() {
+                    println!(
+                        "{} Conversation is ~{} tokens but {} has a {}-token window",
+                        "⚠️".bright_yellow(),
+                        switch.current_tokens,
+                        switch.model,
+                        switch.context_length
+                    );
+                    print!("Compress conversation now? [Y/n] ");
+                    std::io::Write::flush(&mut std::io::stdout())?;
+                    let mut confirm = String::new();
+                    std::io::stdin().read_line(&mut confirm)?;
+                    let confirm = confirm.trim().to_lowercase();
+                    if confirm != "n" && confirm != "no" {
+                        match self.compressor.compress(&self.client, &self.messages).await {
+                            Ok(compressed) => {
+                                self.messages = compressed;
+                                self.rag.forget_inserted();
+                                self.trim_message_history();
+                                println!(
+                                    "{} Context now ~{} tokens",
+                                    "✓".bright_green(),
+                                    self.compressor.estimate_tokens(&self.messages)
+                                );
+                            }
+                            Err(e) => {
+                                println!("{} Compression error: {}", "❌".bright_red(), e)
+                            }
+                        }
+                    }
+                }
+                continue;
+            }
+
             if input == "/model" {
                 println!();
                 println!("  {} Model Configuration", "🤖".bright_cyan());
@@ -671,6 +994,45 @@ impl Agent {
             }
 
             if input == "/compact" {
+                match self.compact_context().await {
+                    Ok(_) => {
+                        if let Some(note) = &self.pinned_note {
+                            println!("{}", note.dimmed());
+                        }
+                    }
+                    Err(e) => println!("{} Compaction error: {}", "❌".bright_red(), e),
+                }
+                continue;
+            }
+
+            if input == "/compact show" {
+                match &self.pinned_note {
+                    Some(note) => {
+                        println!("{} Pinned decisions and constraints:", "📌".bright_cyan());
+                        println!("{}", note);
+                    }
+                    None => println!(
+                        "{} Nothing pinned yet. Run /compact to pin decisions.",
+                        "ℹ".bright_cyan()
+                    ),
+                }
+                continue;
+            }
+
+            if input == "/compact edit" {
+                match self.edit_pinned_note() {
+                    Ok(true) => println!("{} Pinned note updated", "✓".bright_green()),
+                    Ok(false) => println!("{} Pinned note cleared", "✓".bright_green()),
+                    Err(e) => println!("{} {}", "❌".bright_red(), e),
+                }
+                continue;
+            }
+
+            if input == "/compact output" {
+                // An explicit choice replaces the load heuristic and /brief
+                self.config.ui.cognitive_load.adaptive = false;
+                self.load_signals.brief_requested = false;
+                self.update_cognitive_load();
                 let new_compact = !output::is_compact();
                 output::init(
                     new_compact,
@@ -689,7 +1051,25 @@ impl Agent {
                 continue;
             }
 
+            if input == "/brief" || input == "/brief off" {
+                self.load_signals.brief_requested = input == "/brief";
+                self.update_cognitive_load();
+                println!(
+                    "{} Brief replies: {}",
+                    "⚙".bright_cyan(),
+                    if self.load_signals.brief_requested {
+                        "ON".bright_green()
+                    } else {
+                        "OFF".bright_red()
+                    }
+                );
+                continue;
+            }
+
             if input == "/verbose" {
+                self.config.ui.cognitive_load.adaptive = false;
+                self.load_signals.brief_requested = false;
+                self.update_cognitive_load();
                 let new_verbose = !output::is_verbose();
                 output::init(
                     output::is_compact(),
@@ -754,6 +1134,21 @@ impl Agent {
                 continue;
             }
 
+            if input == "/explain" {
+                match self.explain_last_step() {
+                    Some(text) => {
+                        println!();
+                        println!("  {} Last step", "🔍".bright_cyan());
+                        for line in text.lines() {
+                            println!("  {}", line);
+                        }
+                        println!();
+                    }
+                    None => println!("{} No step to explain yet.", "i".bright_yellow()),
+                }
+                continue;
+            }
+
             if input == "/config" {
                 println!();
                 println!("  {} Current Configuration", "⚙".bright_cyan());
@@ -843,6 +1238,66 @@ impl Agent {
                 continue;
             }
 
+            if input == "/retry" || input.starts_with("/retry ") {
+                let hint = input["/retry".len()..].trim();
+                let hint = (!hint.is_empty()).then_some(hint);
+                match self.retry_last_failure(hint).await {
+                    Ok(true) => self.after_task_run().await,
+                    Ok(false) => println!("{} Nothing to retry", "ℹ".bright_yellow()),
+                    Err(e) => println!("{} Retry failed: {}", "❌".bright_red(), e),
+                }
+                continue;
+            }
+
+            if input == "/garden" || input.starts_with("/garden ") {
+                let path = input["/garden".len()..].trim();
+                let path = if path.is_empty() { "." } else { path };
+                let garden = match crate::ui::garden::build_garden_from_path(path) {
+                    Ok(garden) => garden,
+                    Err(e) => {
+                        println!("{} Error building garden: {}", "❌".bright_red(), e);
+                        continue;
+                    }
+                };
+                #[cfg(feature = "tui")]
+                {
+                    let explored = tokio::task::spawn_blocking(move || {
+                        crate::ui::tui::run_garden_explorer(&garden)
+                    })
+                    .await
+                    .map_err(anyhow::Error::from)
+                    .and_then(|outcome| outcome);
+                    match explored {
+                        Ok(Some(file)) => match self.analyze(&file).await {
+                            Ok(_) => self.after_task_run().await,
+                            Err(e) => println!("{} Error analyzing: {}", "❌".bright_red(), e),
+                        },
+                        Ok(None) => {}
+                        Err(e) => println!("{} Garden explorer failed: {}", "❌".bright_red(), e),
+                    }
+                }
+                #[cfg(not(feature = "tui"))]
+                println!("{}", garden.render());
+                continue;
+            }
+
+            if input == "/plan export" || input.starts_with("/plan export ") {
+                let path = input
+                    .strip_prefix("/plan export")
+                    .map(str::trim)
+                    .unwrap_or("");
+                let path = if path.is_empty() {
+                    plan_export::DEFAULT_PLAN_EXPORT
+                } else {
+                    path
+                };
+                match self.export_plan(std::path::Path::new(path)) {
+                    Ok(()) => println!("{} Plan written to {}", "📝".bright_green(), path),
+                    Err(e) => println!("{} {:#}", "❌".bright_red(), e),
+                }
+                continue;
+            }
+
             if input.starts_with("/plan ") {
                 let Some(task) = input.strip_prefix("/plan ").map(str::trim) else {
                     println!("{} Usage: /plan <task>", "ℹ".bright_yellow());
@@ -1076,6 +1531,44 @@ impl Agent {
                 continue;
             }
 
+            // /checkpoint - Save and bookmark the current state right now
+            if input == "/checkpoint" || input.starts_with("/checkpoint ") {
+                let label = input.strip_prefix("/checkpoint").unwrap_or_default().trim();
+                self.print_checkpoint_now((!label.is_empty()).then(|| label.to_string()));
+                continue;
+            }
+
+            // /fork - Branch a journal checkpoint into a new task and continue there
+            if input == "/fork" || input.starts_with("/fork ") {
+                let args = input.strip_prefix("/fork").unwrap_or_default();
+                let current = self.current_checkpoint.as_ref().map(|c| c.task_id.clone());
+                let Some((task_id, step)) = parse_fork_args(args, current.as_deref()) else {
+                    println!("{} Usage: /fork [task_id] [step]", "ℹ".bright_yellow());
+                    continue;
+                };
+                let step = match step {
+                    Some(step) => step,
+                    None => match Agent::task_status(&task_id) {
+                        Ok(checkpoint) => checkpoint.current_step,
+                        Err(e) => {
+                            println!("{} Fork failed: {}", "✗".bright_red(), e);
+                            continue;
+                        }
+                    },
+                };
+                match self.fork_and_switch(&task_id, step) {
+                    Ok(new_id) => println!(
+                        "{} Forked {} at step {} into {} (now active)",
+                        "⑂".bright_green(),
+                        task_id.dimmed(),
+                        step,
+                        new_id.bright_white()
+                    ),
+                    Err(e) => println!("{} Fork failed: {}", "✗".bright_red(), e),
+                }
+                continue;
+            }
+
             // /chat commands
             if input.starts_with("/chat save ") {
                 let Some(name) = input.strip_prefix("/chat save ").map(str::trim) else {
@@ -1104,24 +1597,13 @@ impl Agent {
                 if name.is_empty() {
                     println!("{} Usage: /chat resume <name>", "ℹ".bright_yellow());
                 } else {
-                    match self.chat_store.load(name) {
+                    match self.resume_chat(name) {
                         Ok(chat) => {
-                            self.messages = chat.messages;
-
-                            // Restore memory system from recovered messages so that
-                            // memory stats, token counts, and context are consistent.
-                            self.memory.clear();
-                            for msg in &self.messages {
-                                if msg.role != "system" {
-                                    self.memory.add_message(msg);
-                                }
-                            }
-
                             println!(
                                 "{} Resumed chat '{}' ({} messages, model: {})",
                                 "▶".bright_green(),
                                 name,
-                                self.messages.len(),
+                                chat.message_count,
                                 chat.model.bright_white()
                             );
                         }
@@ -1173,6 +1655,109 @@ impl Agent {
                 continue;
             }
 
+            // /save, /load, /saved - named context snapshots
+            if input == "/save" || input.starts_with("/save ") {
+                let name = input["/save".len()..].trim();
+                if name.is_empty() {
+                    println!("{} Usage: /save <name>", "ℹ".bright_yellow());
+                    continue;
+                }
+                match self.save_snapshot(name) {
+                    Ok(()) => println!(
+                        "{} Snapshot '{}' saved ({} messages, {} files, mode: {})",
+                        "💾".bright_green(),
+                        name,
+                        self.messages.len(),
+                        self.context_files.len(),
+                        self.execution_mode()
+                    ),
+                    Err(e) => println!("{} Save failed: {:#}", "✗".bright_red(), e),
+                }
+                continue;
+            }
+
+            if input == "/load" || input.starts_with("/load ") {
+                let name = input["/load".len()..].trim();
+                if name.is_empty() {
+                    println!("{} Usage: /load <name>", "ℹ".bright_yellow());
+                    continue;
+                }
+                let snapshot = match self.chat_store.load_snapshot(name) {
+                    Ok(snapshot) => snapshot,
+                    Err(e) => {
+                        println!("{} Load failed: {:#}", "✗".bright_red(), e);
+                        continue;
+                    }
+                };
+                let (tokens, window) = self.snapshot_fit(&snapshot);
+                println!(
+                    "{} Snapshot '{}': {} messages, {} files, mode: {}, ~{} tokens (saved {} with {})",
+                    "📂".bright_cyan(),
+                    snapshot.name,
+                    snapshot.messages.len(),
+                    snapshot.context_files.len(),
+                    snapshot.execution_mode,
+                    tokens,
+                    snapshot.saved_at.format("%Y-%m-%d %H:%M"),
+                    snapshot.model
+                );
+                if tokens > window {
+                    println!(
+                        "{} ~{} tokens exceeds the {}-token window of {}; older messages will be compressed or trimmed",
+                        "⚠".bright_yellow(),
+                        tokens,
+                        window,
+                        self.config.model
+                    );
+                }
+                print!(
+                    "{}",
+                    "Replace the current context with it? [y/N]: ".bright_yellow()
+                );
+                std::io::Write::flush(&mut std::io::stdout()).ok();
+                let mut answer = String::new();
+                if std::io::stdin().read_line(&mut answer).is_err()
+                    || !matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+                {
+                    println!("{} Load cancelled", "⏭️".bright_yellow());
+                    continue;
+                }
+                self.restore_snapshot(snapshot);
+                println!("{} Loaded snapshot '{}'", "▶".bright_green(), name);
+                continue;
+            }
+
+            if input == "/saved" {
+                match self.chat_store.list_snapshots() {
+                    Ok(snapshots) if snapshots.is_empty() => {
+                        println!("{} No saved snapshots", "ℹ".bright_yellow());
+                    }
+                    Ok(snapshots) => {
+                        println!();
+                        println!("  {} Saved Snapshots", "📂".bright_cyan());
+                        for snapshot in &snapshots {
+                            println!(
+                                "  {} {} ({} msgs, {} files, {}, {}, {})",
+                                "●".bright_cyan(),
+                                snapshot.name.bright_white(),
+                                snapshot.message_count,
+                                snapshot.file_count,
+                                snapshot.execution_mode,
+                                snapshot.model.dimmed(),
+                                snapshot
+                                    .saved_at
+                                    .format("%Y-%m-%d %H:%M")
+                                    .to_string()
+                                    .dimmed()
+                            );
+                        }
+                        println!();
+                    }
+                    Err(e) => println!("{} Error listing snapshots: {}", "✗".bright_red(), e),
+                }
+                continue;
+            }
+
             if input == "/chat" {
                 println!();
                 println!("  {} Chat Commands", "💬".bright_cyan());
@@ -1221,6 +1806,127 @@ impl Agent {
                 continue;
             }
 
+            if input == "/compare" || input.starts_with("/compare ") {
+                let Some(args) = super::compare::parse_compare_args(&input["/compare".len()..])
+                else {
+                    println!(
+                        "{} Usage: /compare [--full] <modelA> <modelB> <task>",
+                        "ℹ".bright_yellow()
+                    );
+                    continue;
+                };
+                if args.full {
+                    print!(
+                        "{}",
+                        "Run the whole task on both models, each in its own git worktree? This can use many tokens. [y/N]: "
+                            .bright_yellow()
+                    );
+                    std::io::Write::flush(&mut std::io::stdout()).ok();
+                    let mut answer = String::new();
+                    if std::io::stdin().read_line(&mut answer).is_err()
+                        || !matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+                    {
+                        println!("{} Comparison cancelled", "⏭️".bright_yellow());
+                        continue;
+                    }
+                }
+                println!(
+                    "{} Comparing {} and {}...",
+                    "⚖".bright_cyan(),
+                    args.model_a.bright_white(),
+                    args.model_b.bright_white()
+                );
+                let result = if args.full {
+                    self.compare_full(&args).await
+                } else {
+                    self.compare_turn(&args).await
+                };
+                let runs = match result {
+                    Ok(runs) => runs,
+                    Err(e) => {
+                        println!("{} {:#}", "✗".bright_red(), e);
+                        continue;
+                    }
+                };
+                let width = crossterm::terminal::size()
+                    .map(|(w, _)| w as usize)
+                    .unwrap_or(80);
+                println!("\n{}", super::compare::render_side_by_side(&runs, width));
+                print!(
+                    "{}",
+                    "Which was better? [1/2/t(ie), Enter to skip]: ".bright_yellow()
+                );
+                std::io::Write::flush(&mut std::io::stdout()).ok();
+                let mut answer = String::new();
+                let vote = match std::io::stdin().read_line(&mut answer) {
+                    Ok(_) => super::compare::parse_vote(&answer, &runs),
+                    Err(_) => None,
+                };
+                if let Err(e) = super::compare::record_comparison(&args, &runs, vote) {
+                    tracing::warn!("Failed to record model comparison: {}", e);
+                }
+                continue;
+            }
+
+            if input == "/tokens" || input.starts_with("/tokens ") {
+                #[cfg(feature = "tokens")]
+                {
+                    let arg = input["/tokens".len()..].trim();
+                    let limit = match arg {
+                        "" => None,
+                        "all" => Some(usize::MAX),
+                        n => match n.parse::<usize>() {
+                            Ok(n) if n > 0 => Some(n),
+                            _ => {
+                                println!("{} Usage: /tokens [all|<count>]", "ℹ".bright_cyan());
+                                continue;
+                            }
+                        },
+                    };
+                    self.show_token_distribution(limit);
+                }
+                #[cfg(not(feature = "tokens"))]
+                println!(
+                    "{} /tokens needs a build with the 'tokens' feature",
+                    "⚠️".bright_yellow()
+                );
+                continue;
+            }
+
+            if input == "/wellness" || input.starts_with("/wellness ") {
+                let arg = input["/wellness".len()..].trim();
+                match self.wellness_command(arg) {
+                    Ok(message) => println!("{} {}", "ℹ".bright_cyan(), message),
+                    Err(e) => println!("{} {:#}", "✗".bright_red(), e),
+                }
+                continue;
+            }
+
+            if input == "/image" || input.starts_with("/image ") {
+                let source = input["/image".len()..].trim();
+                if source.is_empty() {
+                    println!(
+                        "{} Usage: /image <path|url> (goes with your next message; {} queued)",
+                        "ℹ".bright_yellow(),
+                        self.pending_images.len()
+                    );
+                    continue;
+                }
+                match self.attach_image(source) {
+                    Ok(ContentBlock::ImageUrl { .. }) => println!(
+                        "{} Image attached; it goes with your next message",
+                        "📎".bright_green()
+                    ),
+                    Ok(ContentBlock::Text { .. }) => println!(
+                        "{} {} cannot view images; a text description goes with your next message",
+                        "ℹ".bright_yellow(),
+                        self.config.model
+                    ),
+                    Err(e) => println!("{} {:#}", "✗".bright_red(), e),
+                }
+                continue;
+            }
+
             if input.starts_with("/theme ") {
                 let Some(name) = input.strip_prefix("/theme ").map(str::trim) else {
                     println!("{} Usage: /theme <name>", "ℹ".bright_yellow());
@@ -1246,11 +1952,14 @@ impl Agent {
                 continue;
             }
 
-            // Expand @file references in input (Qwen Code style)
-            let (expanded_input, included_files) = self.expand_file_references(input);
+            // Expand @file and @https://... references in input (Qwen Code style)
+            let (expanded_input, mut included_files) = self.expand_file_references(input);
+            let (expanded_input, included_urls) =
+                self.expand_url_references(input, expanded_input).await;
+            included_files.extend(included_urls);
             if !included_files.is_empty() {
                 println!(
-                    "{} Included {} file(s):",
+                    "{} Included {} reference(s):",
                     "📎".bright_cyan(),
                     included_files.len()
                 );
@@ -1306,6 +2015,7 @@ impl Agent {
                 }
             }
 
+            self.observe_user_input(&expanded_input);
             match self.run_task_with_queue(&expanded_input).await {
                 Ok(_) => {}
                 Err(e) => println!("{} Error: {}", "❌".bright_red(), e),
@@ -1315,10 +2025,200 @@ impl Agent {
         Ok(())
     }
 
+    /// Replace the conversation with a saved chat
+    pub fn resume_chat(&mut self, name: &str) -> Result<ChatSummary> {
+        let chat = self.chat_store.load(name)?;
+        let summary = ChatSummary {
+            name: chat.name,
+            saved_at: chat.saved_at,
+            model: chat.model,
+            message_count: chat.messages.len(),
+        };
+        self.messages = chat.messages;
+
+        // Restore memory system from recovered messages so that
+        // memory stats, token counts, and context are consistent.
+        self.memory.clear();
+        for msg in &self.messages {
+            if msg.role != "system" {
+                self.memory.add_message(msg);
+            }
+        }
+        Ok(summary)
+    }
+
+    /// Save the conversation, loaded context files and execution mode as a
+    /// named snapshot
+    pub fn save_snapshot(&self, name: &str) -> Result<()> {
+        self.chat_store.save_snapshot(&ContextSnapshot {
+            name: name.to_string(),
+            saved_at: chrono::Utc::now(),
+            model: self.config.model.clone(),
+            execution_mode: self.execution_mode(),
+            context_files: self.context_files.clone(),
+            messages: self.messages.clone(),
+        })
+    }
+
+    /// Estimated tokens of a snapshot's messages and the current model's
+    /// context window
+    pub(super) fn snapshot_fit(&self, snapshot: &ContextSnapshot) -> (usize, usize) {
+        let tokens = snapshot
+            .messages
+            .iter()
+            .map(super::context_management::message_tokens)
+            .sum();
+        (tokens, self.memory.context_window())
+    }
+
+    /// Replace the conversation, context files and execution mode with a
+    /// snapshot's. Turns recorded for `/rewind` belong to the replaced
+    /// conversation and are dropped.
+    pub fn restore_snapshot(&mut self, snapshot: ContextSnapshot) {
+        self.messages = snapshot.messages;
+        self.memory.clear();
+        for msg in &self.messages {
+            if msg.role != "system" {
+                self.memory.add_message(msg);
+            }
+        }
+        self.context_files = snapshot.context_files;
+        self.stale_files.clear();
+        self.turns.clear();
+        self.set_execution_mode(snapshot.execution_mode);
+    }
+
+    /// Print a break reminder at the prompt when one is due, unless output
+    /// is quiet
+    fn show_wellness_reminder(&mut self) {
+        let accessible = crate::ui::accessibility::is_accessible();
+        if let Some(reminder) = self.wellness.due_reminder(accessible) {
+            if !output::is_quiet() {
+                println!("{}\n", reminder.bright_green());
+            }
+        }
+    }
+
+    /// `/wellness [on|off|<minutes>]`: show active time, or change and save
+    /// the reminder setting
+    pub(crate) fn wellness_command(&mut self, arg: &str) -> Result<String> {
+        use crate::ui::accessibility::wellness::format_minutes;
+        match arg {
+            "" => {
+                let prefs = self.wellness.prefs();
+                let setting = if prefs.enabled {
+                    format!("break reminders every {} minutes", prefs.interval_minutes)
+                } else {
+                    "break reminders off".to_string()
+                };
+                Ok(format!(
+                    "Active {} this session, {} since your last break; {}",
+                    format_minutes(self.wellness.active_time()),
+                    format_minutes(self.wellness.stretch()),
+                    setting
+                ))
+            }
+            "on" => {
+                self.wellness.set_enabled(true)?;
+                Ok(format!(
+                    "Break reminders on, every {} minutes",
+                    self.wellness.prefs().interval_minutes
+                ))
+            }
+            "off" => {
+                self.wellness.set_enabled(false)?;
+                Ok("Break reminders off".to_string())
+            }
+            minutes => {
+                let minutes: u64 = minutes
+                    .trim_end_matches('m')
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("Usage: /wellness [on|off|<minutes>]"))?;
+                self.wellness.set_interval(minutes)?;
+                Ok(format!("Break reminders every {} minutes", minutes))
+            }
+        }
+    }
+
+    /// Queue an image for the next user message. Vision models get the
+    /// image itself, within the attachment size limit; others get a text
+    /// description instead. Returns the queued block.
+    pub(super) fn attach_image(&mut self, source: &str) -> Result<ContentBlock> {
+        if self.pending_images.len() >= MAX_PENDING_IMAGES {
+            bail!(
+                "At most {} images can go with one message",
+                MAX_PENDING_IMAGES
+            );
+        }
+        let vision = self.model_supports_vision();
+        let block = if source.starts_with("http://") || source.starts_with("https://") {
+            if vision {
+                ContentBlock::ImageUrl {
+                    image_url: ImageUrl {
+                        url: source.to_string(),
+                        detail: None,
+                    },
+                }
+            } else {
+                ContentBlock::Text {
+                    text: format!("[Image {}: remote image, not shown to this model]", source),
+                }
+            }
+        } else {
+            let image = Artifact::from_path(source);
+            if !image.path.is_file() {
+                bail!("No such file: {}", source);
+            }
+            if !(image.is_image() || image.mime_type == "image/svg+xml") {
+                bail!("Not an image: {} ({})", source, image.mime_type);
+            }
+            // SVG is text: quoted in the description even for vision models
+            let attach = vision && image.is_image();
+            match attach.then(|| image.image_base64()).transpose()? {
+                Some(data) => ContentBlock::ImageUrl {
+                    image_url: ImageUrl {
+                        url: format!("data:{};base64,{}", image.mime_type, data),
+                        detail: None,
+                    },
+                },
+                None => ContentBlock::Text {
+                    text: format!("[Image {}]", artifact::describe(&image)),
+                },
+            }
+        };
+        self.pending_images.push(block.clone());
+        Ok(block)
+    }
+
+    /// User message for `text` carrying the images queued by `/image`.
+    /// Descriptions are appended to the text.
+    pub(super) fn user_message_with_images(&mut self, text: String) -> Message {
+        if self.pending_images.is_empty() {
+            return Message::user(text);
+        }
+        let mut text = text;
+        let mut urls = Vec::new();
+        for block in std::mem::take(&mut self.pending_images) {
+            match block {
+                ContentBlock::Text { text: description } => {
+                    text.push_str("\n\n");
+                    text.push_str(&description);
+                }
+                ContentBlock::ImageUrl { image_url } => urls.push(image_url.url),
+            }
+        }
+        let content = urls.into_iter().fold(
+            MessageContent::from_text(text),
+            MessageContent::with_image_url,
+        );
+        Message::user_multimodal(content)
+    }
+
     async fn run_task_with_queue(&mut self, task: &str) -> Result<()> {
         let result = self.run_task(task).await;
         self.after_task_run().await;
-        result
+        self.load_signals.mark_ready();
+        result.map(|_| ())
     }
 
     async fn run_swarm_with_queue(&mut self, task: &str) -> Result<()> {
@@ -1328,7 +2228,8 @@ impl Agent {
     }
 
     async fn after_task_run(&mut self) {
-        let interrupted = self.is_cancelled();
+        self.wellness.record_activity();
+        let interrupted = self.is_cancelled() || self.quit_requested;
         self.reset_cancellation();
         if !interrupted {
             self.drain_pending_messages().await;
@@ -1353,7 +2254,7 @@ impl Agent {
                 println!("{} Error: {}", "❌".bright_red(), e);
             }
 
-            let interrupted = self.is_cancelled();
+            let interrupted = self.is_cancelled() || self.quit_requested;
             self.reset_cancellation();
             if interrupted {
                 break;
@@ -1373,6 +2274,40 @@ impl Agent {
         self.pending_messages.push_back(msg.to_string());
     }
 
+    /// Record a message typed by the user and re-estimate their cognitive
+    /// load, announcing when output switches to or from brief mode.
+    fn observe_user_input(&mut self, input: &str) {
+        self.load_signals.record_input(input);
+        let before = self.cognitive_load;
+        let after = self.update_cognitive_load();
+        if before == after || self.load_signals.brief_requested {
+            return;
+        }
+        if after == LoadLevel::High {
+            println!(
+                "{} Keeping output brief (use {} for full output)",
+                "⚙".bright_cyan(),
+                "/verbose".bright_white()
+            );
+        } else if before == LoadLevel::High {
+            println!("{} Back to full output", "⚙".bright_cyan());
+        }
+    }
+
+    /// Re-estimate cognitive load and adapt output to it. `/brief` always
+    /// counts; the typing heuristic only while `ui.cognitive_load.adaptive`.
+    fn update_cognitive_load(&mut self) -> LoadLevel {
+        let settings = &self.config.ui.cognitive_load;
+        let level = if settings.adaptive || self.load_signals.brief_requested {
+            settings.estimator().estimate(&self.load_signals)
+        } else {
+            LoadLevel::Normal
+        };
+        self.cognitive_load = level;
+        output::set_cognitive_load(level);
+        level
+    }
+
     /// Copy text to clipboard using system clipboard tools.
     /// Runs blocking clipboard I/O on a dedicated thread to avoid
     /// stalling the async runtime.
@@ -1433,6 +2368,19 @@ impl Agent {
         .map_err(|e| anyhow::anyhow!("Clipboard task failed: {}", e))?
     }
 
+    /// Whether `--max-runtime` has elapsed, announcing the end of the session if so.
+    fn runtime_limit_reached(&self) -> bool {
+        let Some(used) = self.loop_control.runtime_exceeded() else {
+            return false;
+        };
+        println!(
+            "\n{} Max runtime reached after {}, ending session.",
+            "⏱️".bright_yellow(),
+            crate::ui::animations::format_duration(used)
+        );
+        true
+    }
+
     /// Basic interactive mode (fallback when reedline unavailable)
     async fn interactive_basic(&mut self) -> Result<()> {
         use std::io::{self, Write};
@@ -1445,6 +2393,9 @@ impl Agent {
         let is_tty = std::io::stdin().is_terminal();
 
         loop {
+            if self.runtime_limit_reached() {
+                break;
+            }
             if is_tty {
                 print!("🦊 ❯ ");
                 io::stdout().flush()?;
@@ -1479,10 +2430,11 @@ impl Agent {
                 println!("  /status         - Show agent status");
                 println!("  /memory         - Show memory statistics");
                 println!("  /clear          - Clear conversation history");
-                println!("  /tools          - List available tools");
+                println!("  /tools [name]   - List tools, or show one tool's schema");
                 println!("  /analyze <path> - Analyze codebase at path");
                 println!("  /review <file>  - Review code in file");
                 println!("  /plan <task>    - Create a plan for a task");
+                println!("  /plan export [file] - Save the plan (.md or .mmd)");
                 println!("  /swarm <task>   - Run task with dev swarm");
                 println!("  /queue <msg>    - Queue a message");
                 println!("  /queue list     - Show queued messages");
@@ -1508,10 +2460,9 @@ impl Agent {
                 continue;
             }
 
-            if input == "/tools" {
-                for tool in self.tools.list() {
-                    println!("  - {}: {}", tool.name(), tool.description());
-                }
+            if input == "/tools" || input.starts_with("/tools ") {
+                let name = input.strip_prefix("/tools").map(str::trim);
+                print!("{}", self.render_tools(name.filter(|n| !n.is_empty())));
                 continue;
             }
 
@@ -1552,6 +2503,23 @@ impl Agent {
                 continue;
             }
 
+            if input == "/plan export" || input.starts_with("/plan export ") {
+                let path = input
+                    .strip_prefix("/plan export")
+                    .map(str::trim)
+                    .unwrap_or("");
+                let path = if path.is_empty() {
+                    plan_export::DEFAULT_PLAN_EXPORT
+                } else {
+                    path
+                };
+                match self.export_plan(std::path::Path::new(path)) {
+                    Ok(()) => println!("{} Plan written to {}", "📝".bright_green(), path),
+                    Err(e) => println!("{} {:#}", "❌".bright_red(), e),
+                }
+                continue;
+            }
+
             if input.starts_with("/plan ") {
                 let Some(task) = input.strip_prefix("/plan ").map(str::trim) else {
                     println!("{} Usage: /plan <task>", "ℹ".bright_yellow());
@@ -1715,6 +2683,7 @@ impl Agent {
                 }
             }
 
+            self.observe_user_input(input);
             match self.run_task_with_queue(input).await {
                 Ok(_) => {}
                 Err(e) => println!("{} Error: {}", "❌".bright_red(), e),
@@ -1751,6 +2720,53 @@ mod tests {
         assert_eq!(Agent::format_file_size(2 * 1024 * 1024), "2.0MB");
     }
 
+    #[test]
+    fn test_parse_feedback_args() {
+        assert_eq!(parse_feedback_args(" good"), Some((true, None)));
+        assert_eq!(parse_feedback_args(" BAD"), Some((false, None)));
+        assert_eq!(
+            parse_feedback_args(" bad  renamed the wrong fn "),
+            Some((false, Some("renamed the wrong fn")))
+        );
+        assert_eq!(parse_feedback_args(""), None);
+        assert_eq!(parse_feedback_args(" meh"), None);
+    }
+
+    // ── parse_fork_args tests ──
+
+    #[test]
+    fn fork_args_default_to_current_task() {
+        assert_eq!(
+            parse_fork_args("", Some("task-1")),
+            Some(("task-1".to_string(), None))
+        );
+        assert_eq!(parse_fork_args("", None), None);
+    }
+
+    #[test]
+    fn fork_args_single_number_is_step_of_current_task() {
+        assert_eq!(
+            parse_fork_args(" 3", Some("task-1")),
+            Some(("task-1".to_string(), Some(3)))
+        );
+        // Without a current task a lone argument is treated as an ID
+        assert_eq!(parse_fork_args("3", None), Some(("3".to_string(), None)));
+    }
+
+    #[test]
+    fn fork_args_explicit_id_and_step() {
+        assert_eq!(
+            parse_fork_args("abc-123", Some("task-1")),
+            Some(("abc-123".to_string(), None))
+        );
+        assert_eq!(
+            parse_fork_args("abc-123 7", None),
+            Some(("abc-123".to_string(), Some(7)))
+        );
+        assert_eq!(parse_fork_args("abc-123 seven", None), None);
+        assert_eq!(parse_fork_args("a b c", None), None);
+    }
+
     // ── Slash command matching patterns ──
     // These tests verify the string-matching logic used in the interactive loop
     // to route slash commands, extracted as pure assertions.
@@ -1770,10 +2786,13 @@ mod tests {
             "/diff",
             "/git",
             "/undo",
+            "/rewind",
             "/cost",
             "/model",
             "/last",
+            "/explain",
             "/compact",
+            "/focus",
             "/verbose",
             "/config",
             "/memory",
@@ -1784,6 +2803,13 @@ mod tests {
             "/queue",
             "/swarm",
             "/chat",
+            "/save",
+            "/load",
+            "/saved",
+            "/image",
+            "/wellness",
+            "/compare",
+            "/tokens",
         ];
         for cmd in &commands {
             assert!(
@@ -1815,6 +2841,10 @@ mod tests {
         let arg = input.strip_prefix("/analyze ").map(str::trim);
         assert_eq!(arg, Some("./src"));
 
+        let input = "/plan export docs/plan.mmd";
+        let arg = input.strip_prefix("/plan export").map(str::trim);
+        assert_eq!(arg, Some("docs/plan.mmd"));
+
         let input = "/plan implement auth flow";
         let arg = input.strip_prefix("/plan ").map(str::trim);
         assert_eq!(arg, Some("implement auth flow"));
@@ -0,0 +1,213 @@
+//! Ctrl+C while a task runs
+//!
+//! At a terminal the first Ctrl+C asks the loop to pause at the next step
+//! boundary, where a checkpoint is saved and a menu offers to resume,
+//! change mode, save and quit, or abort. A second Ctrl+C within
+//! [`ABORT_WINDOW`] aborts straight away. With piped input there is nobody
+//! to answer a menu, so the first Ctrl+C aborts.
+
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use colored::*;
+
+use super::task_result::TaskOutcome;
+use super::*;
+
+/// A second Ctrl+C within this long of the first aborts the task
+pub(super) const ABORT_WINDOW: Duration = Duration::from_millis(1500);
+
+/// Values of the shared pause state
+pub(super) const RUNNING: u8 = 0;
+/// Ctrl+C asked for a pause at the next step boundary
+pub(super) const PAUSE_REQUESTED: u8 = 1;
+/// The pause menu is waiting for an answer
+pub(super) const MENU_OPEN: u8 = 2;
+
+/// What to do with a task paused by Ctrl+C
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum InterruptChoice {
+    Resume,
+    /// Cycle the execution mode and show the menu again
+    ChangeMode,
+    SaveAndQuit,
+    Abort,
+}
+
+impl InterruptChoice {
+    /// Menu key for a choice; Enter resumes
+    pub(super) fn from_key(key: &str) -> Option<Self> {
+        match key.trim().to_ascii_lowercase().as_str() {
+            "" | "r" | "resume" => Some(Self::Resume),
+            "m" | "mode" => Some(Self::ChangeMode),
+            "s" | "save" | "q" | "quit" => Some(Self::SaveAndQuit),
+            "a" | "abort" => Some(Self::Abort),
+            _ => None,
+        }
+    }
+}
+
+/// Turn Ctrl+C presses into pause and abort requests until dropped
+pub(super) async fn watch_ctrl_c(pause: Arc<AtomicU8>, cancel: Arc<AtomicBool>, interactive: bool) {
+    let mut first_press: Option<Instant> = None;
+    while tokio::signal::ctrl_c().await.is_ok() {
+        let menu_open = pause.load(Ordering::Relaxed) == MENU_OPEN;
+        let again = first_press.is_some_and(|t| t.elapsed() < ABORT_WINDOW);
+        if !interactive || again || menu_open {
+            cancel.store(true, Ordering::Relaxed);
+            if menu_open {
+                println!("\n🦊 Aborting. Press Enter to stop the task.");
+            } else {
+                println!("\n🦊 Received shutdown signal. Gracefully stopping agent and saving checkpoint...");
+            }
+            return;
+        }
+        first_press = Some(Instant::now());
+        pause.store(PAUSE_REQUESTED, Ordering::Relaxed);
+        println!(
+            "\n{} Pausing after the current step. Press Ctrl+C again to abort now.",
+            "⏸".bright_yellow()
+        );
+    }
+}
+
+/// Ctrl+C watcher for one task run; stops watching when dropped
+pub(super) struct InterruptWatch(tokio::task::JoinHandle<()>);
+
+impl Drop for InterruptWatch {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+impl Agent {
+    /// Start turning Ctrl+C into pause/abort requests for this task
+    pub(super) fn watch_interrupts(&self) -> InterruptWatch {
+        self.pause_state.store(RUNNING, Ordering::Relaxed);
+        InterruptWatch(tokio::spawn(watch_ctrl_c(
+            Arc::clone(&self.pause_state),
+            self.cancel_token(),
+            self.is_interactive(),
+        )))
+    }
+
+    /// Handle a pending Ctrl+C pause at a step boundary. Returns the outcome
+    /// when the task should stop here; an abort is left to the caller's
+    /// cancellation check.
+    pub(super) fn handle_pause_request(&mut self, task_description: &str) -> Option<TaskOutcome> {
+        if self
+            .pause_state
+            .compare_exchange(
+                PAUSE_REQUESTED,
+                MENU_OPEN,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            )
+            .is_err()
+        {
+            return None;
+        }
+        if let Err(e) = self.save_checkpoint(task_description) {
+            warn!("Failed to save checkpoint: {}", e);
+        }
+        let outcome = self.run_pause_menu(task_description);
+        self.pause_state.store(RUNNING, Ordering::Relaxed);
+        outcome
+    }
+
+    fn run_pause_menu(&mut self, task_description: &str) -> Option<TaskOutcome> {
+        loop {
+            match self.ask_interrupt_choice() {
+                InterruptChoice::Resume => {
+                    println!("{} Resuming", "▶".bright_green());
+                    return None;
+                }
+                InterruptChoice::ChangeMode => {
+                    let mode = self.cycle_execution_mode();
+                    println!("{} Mode: {:?}", "🔄".bright_cyan(), mode);
+                }
+                InterruptChoice::SaveAndQuit => {
+                    self.record_task_outcome(
+                        task_description,
+                        Outcome::Partial,
+                        Some("Paused by user"),
+                    );
+                    self.save_paused(task_description);
+                    self.quit_requested = true;
+                    return Some(TaskOutcome::Paused);
+                }
+                InterruptChoice::Abort => {
+                    self.cancelled.store(true, Ordering::Relaxed);
+                    return None;
+                }
+            }
+        }
+    }
+
+    /// Show the pause menu and read a choice. Ctrl+C here, or losing the
+    /// terminal, aborts.
+    fn ask_interrupt_choice(&self) -> InterruptChoice {
+        use std::io::Write;
+
+        println!(
+            "\n{} Task paused (step {}, checkpoint saved)",
+            "⏸".bright_yellow(),
+            self.loop_control.current_step() + 1
+        );
+        println!(
+            "   [r] resume  [m] change mode (now {:?})  [s] save and quit  [a] abort",
+            self.execution_mode()
+        );
+        loop {
+            print!("{}", "   choice (Enter resumes) > ".dimmed());
+            std::io::stdout().flush().ok();
+            if self.is_cancelled() {
+                return InterruptChoice::Abort;
+            }
+            let mut answer = String::new();
+            match std::io::stdin().read_line(&mut answer) {
+                Ok(0) | Err(_) => return InterruptChoice::Abort,
+                Ok(_) => {}
+            }
+            // A Ctrl+C while the menu was waiting counts as abort
+            if self.is_cancelled() {
+                return InterruptChoice::Abort;
+            }
+            match InterruptChoice::from_key(&answer) {
+                Some(choice) => return choice,
+                None => println!("   Unknown choice '{}'", answer.trim()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interrupt_choice_keys() {
+        assert_eq!(
+            InterruptChoice::from_key("\n"),
+            Some(InterruptChoice::Resume)
+        );
+        assert_eq!(
+            InterruptChoice::from_key("R"),
+            Some(InterruptChoice::Resume)
+        );
+        assert_eq!(
+            InterruptChoice::from_key("m"),
+            Some(InterruptChoice::ChangeMode)
+        );
+        assert_eq!(
+            InterruptChoice::from_key("quit"),
+            Some(InterruptChoice::SaveAndQuit)
+        );
+        assert_eq!(
+            InterruptChoice::from_key(" a "),
+            Some(InterruptChoice::Abort)
+        );
+        assert_eq!(InterruptChoice::from_key("x"), None);
+    }
+}
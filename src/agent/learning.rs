@@ -1,7 +1,19 @@
 use tracing::info;
 
+use crate::cognitive::intelligence::TaskContext;
+
 use super::*;
 
+/// What `/feedback` recorded, echoed back to the user.
+#[derive(Debug, Clone)]
+pub(super) struct UserFeedback {
+    pub positive: bool,
+    /// Tools from the last step and their automatic outcome
+    pub tools: Vec<(String, bool)>,
+    pub task_type: &'static str,
+    pub note: Option<String>,
+}
+
 impl Agent {
     pub(super) fn infer_task_type(task: &str) -> &'static str {
         let task_lower = task.to_lowercase();
@@ -59,6 +71,123 @@ impl Agent {
         self.self_improvement.start_session(session_id);
     }
 
+    /// Estimate how hard `task` is before running it, and switch to the
+    /// model configured for that difficulty in `agent.difficulty_models`.
+    pub(super) fn assess_task(&mut self, task: &str) -> &Assessment {
+        let task_type = Self::infer_task_type(task);
+        let context = TaskContext {
+            task_type,
+            files_in_context: self.context_files.len(),
+            success_rate: self.self_improvement.task_success_rate(task_type),
+            base_thinking_budget: Self::thinking_budget_for(&self.config.agent, task_type),
+            models: Some(&self.config.agent.difficulty_models),
+        };
+        let assessment = self.intelligence.assess(task, &context);
+        self.route_model(assessment.recommended_model.as_deref());
+        let assessment = self.task_assessment.insert(assessment);
+
+        crate::telemetry::record_thinking_budget(
+            task_type,
+            self.config
+                .agent
+                .thinking_budget
+                .or(assessment.thinking_budget),
+        );
+        assessment
+    }
+
+    /// Record the user's verdict on the last step. It outweighs the automatic
+    /// outcome signals for the step's tools and the task prompt, and a note
+    /// is kept as a lesson so it reaches future prompts.
+    pub(super) fn record_user_feedback(
+        &mut self,
+        positive: bool,
+        note: Option<&str>,
+    ) -> Result<UserFeedback> {
+        if self.last_step_tools.is_empty() && self.last_assistant_response.is_empty() {
+            anyhow::bail!("Nothing to give feedback on yet");
+        }
+
+        let outcome = if positive {
+            Outcome::Success
+        } else {
+            Outcome::Failure
+        };
+        let context = self.learning_context().to_string();
+        let task_type = Self::infer_task_type(&context);
+        let tool_names: Vec<String> = self
+            .last_step_tools
+            .iter()
+            .map(|(name, _)| name.clone())
+            .collect();
+        self.self_improvement
+            .record_feedback(&tool_names, &context, &context, task_type, outcome);
+
+        let note = note.map(str::trim).filter(|n| !n.is_empty());
+        if let Some(note) = note {
+            let subject = if tool_names.is_empty() {
+                "the last response".to_string()
+            } else {
+                tool_names.join(", ")
+            };
+            self.cognitive_state
+                .episodic_memory
+                .record_lesson(crate::cognitive::Lesson {
+                    category: if positive {
+                        crate::cognitive::LessonCategory::Preference
+                    } else {
+                        crate::cognitive::LessonCategory::Warning
+                    },
+                    content: format!(
+                        "User marked {} as {}: {}",
+                        subject,
+                        if positive { "good" } else { "bad" },
+                        note
+                    ),
+                    context,
+                    tags: vec!["user_feedback".to_string()],
+                    timestamp: chrono::Utc::now(),
+                });
+        }
+
+        Ok(UserFeedback {
+            positive,
+            tools: self.last_step_tools.clone(),
+            task_type,
+            note: note.map(str::to_string),
+        })
+    }
+
+    /// Thinking budget for a task type: `--thinking-budget` wins, then the
+    /// `thinking_budgets` map. `None` means think without a cap.
+    pub(super) fn thinking_budget_for(
+        agent: &crate::config::AgentConfig,
+        task_type: &str,
+    ) -> Option<u32> {
+        agent
+            .thinking_budget
+            .or_else(|| agent.thinking_budgets.get(task_type).copied())
+    }
+
+    /// Thinking mode for planning and execution requests of the current task.
+    /// `--thinking-budget` wins, then the task's difficulty assessment, then
+    /// the task type's budget. Backends that ignore the `thinking` parameter
+    /// behave as before.
+    pub(super) fn thinking_mode(&self) -> ThinkingMode {
+        let budget = match (&self.config.agent.thinking_budget, &self.task_assessment) {
+            (None, Some(assessment)) => assessment.thinking_budget,
+            _ => {
+                let task_type = Self::infer_task_type(self.learning_context());
+                Self::thinking_budget_for(&self.config.agent, task_type)
+            }
+        };
+        match budget {
+            None => ThinkingMode::Enabled,
+            Some(0) => ThinkingMode::Disabled,
+            Some(tokens) => ThinkingMode::Budget(tokens as usize),
+        }
+    }
+
     pub(super) fn record_task_outcome(
         &mut self,
         task_prompt: &str,
@@ -73,6 +202,12 @@ impl Agent {
             Self::outcome_quality(outcome),
         );
         self.self_improvement.record_task(outcome.is_positive());
+        if outcome == Outcome::Success {
+            if let Some(assessment) = self.task_assessment.take() {
+                self.intelligence
+                    .calibrate(&assessment, self.loop_control.current_step());
+            }
+        }
 
         if let Some(err) = error {
             self.self_improvement.record_error(
@@ -131,14 +266,57 @@ impl Agent {
             }
         }
 
-        if hints.is_empty() {
-            None
-        } else {
-            Some(format!(
+        let mut sections = Vec::new();
+        if !hints.is_empty() {
+            sections.push(format!(
                 "Self-improvement guidance from prior outcomes:\n- {}",
                 hints.join("\n- ")
-            ))
+            ));
+        }
+        let lessons = self.context_file_lessons();
+        if !lessons.is_empty() {
+            sections.push(format!(
+                "Lessons from earlier work on the files in context:\n- {}",
+                lessons.join("\n- ")
+            ));
+        }
+        if self.cognitive_load == crate::cognitive::load::LoadLevel::High {
+            sections.push(
+                "The user is moving fast: keep replies short, skip preamble and recaps, \
+                 and make reasonable assumptions instead of asking clarifying questions."
+                    .to_string(),
+            );
+        }
+
+        if sections.is_empty() {
+            None
+        } else {
+            Some(sections.join("\n\n"))
+        }
+    }
+
+    /// Lessons that mention the files loaded into context. The system prompt
+    /// already carries the most recent lessons as a general fallback, so any
+    /// lesson it contains is left out here.
+    pub(super) fn context_file_lessons(&self) -> Vec<String> {
+        const MAX_FILE_LESSONS: usize = 5;
+
+        if self.context_files.is_empty() {
+            return Vec::new();
         }
+        let system_prompt = self
+            .messages
+            .first()
+            .filter(|m| m.role == "system")
+            .map(|m| m.content.text().to_string())
+            .unwrap_or_default();
+        self.cognitive_state
+            .episodic_memory
+            .relevant_to(&self.context_files, MAX_FILE_LESSONS + 10)
+            .into_iter()
+            .filter(|lesson| !system_prompt.contains(lesson.as_str()))
+            .take(MAX_FILE_LESSONS)
+            .collect()
     }
 
     /// Reflect on a completed step: record lessons, update learner, inject hints
@@ -243,6 +421,21 @@ mod tests {
     use super::*;
     use crate::cognitive::self_improvement::Outcome;
 
+    #[test]
+    fn test_thinking_budget_for_uses_map_then_override() {
+        let mut agent = crate::config::AgentConfig::default();
+        assert_eq!(Agent::thinking_budget_for(&agent, "refactor"), Some(16384));
+        assert_eq!(
+            Agent::thinking_budget_for(&agent, "documentation"),
+            Some(2048)
+        );
+        assert_eq!(Agent::thinking_budget_for(&agent, "general"), None);
+
+        agent.thinking_budget = Some(0);
+        assert_eq!(Agent::thinking_budget_for(&agent, "refactor"), Some(0));
+        assert_eq!(Agent::thinking_budget_for(&agent, "general"), Some(0));
+    }
+
     // =========================================================================
     // infer_task_type — exhaustive branch coverage + edge cases
     // =========================================================================
@@ -476,6 +669,69 @@ mod tests {
         server.stop().await;
     }
 
+    #[tokio::test]
+    async fn test_build_learning_hint_asks_for_terse_replies_under_high_load() {
+        let server = crate::testing::mock_api::MockLlmServer::builder()
+            .with_response("done")
+            .build()
+            .await;
+        let config = crate::config::Config {
+            endpoint: format!("{}/v1", server.url()),
+            ..Default::default()
+        };
+        let mut agent = Agent::new(config).await.unwrap();
+
+        let terse = |hint: Option<String>| hint.is_some_and(|h| h.contains("moving fast"));
+        assert!(!terse(agent.build_learning_hint("fix the build")));
+        agent.cognitive_load = crate::cognitive::load::LoadLevel::High;
+        assert!(terse(agent.build_learning_hint("fix the build")));
+
+        server.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_assess_task_routes_hard_tasks_to_configured_model() {
+        let server = crate::testing::mock_api::MockLlmServer::builder()
+            .with_response("done")
+            .build()
+            .await;
+        let mut config = crate::config::Config {
+            endpoint: format!("{}/v1", server.url()),
+            model: "small".to_string(),
+            ..Default::default()
+        };
+        config
+            .agent
+            .difficulty_models
+            .insert("hard".to_string(), "big".to_string());
+        let mut agent = Agent::new(config).await.unwrap();
+        // Ignore calibration persisted on this machine by earlier runs
+        agent.intelligence = crate::cognitive::intelligence::Intelligence::new();
+
+        let hard = "Redesign the entire storage architecture to fix the race condition \
+                    and improve performance across src/store/mod.rs and src/api/mod.rs";
+        let assessment = agent.assess_task(hard).clone();
+        assert_eq!(
+            assessment.difficulty,
+            crate::cognitive::intelligence::Difficulty::Hard
+        );
+        assert_eq!(agent.config.model, "big");
+        assert_eq!(agent.routed_from.as_deref(), Some("small"));
+        assert!(matches!(agent.thinking_mode(), ThinkingMode::Budget(_)));
+
+        agent.assess_task("fix the typo in README.md");
+        assert_eq!(agent.config.model, "small");
+        assert_eq!(agent.routed_from, None);
+
+        // A successful task calibrates the estimate for its type
+        agent.assess_task(hard);
+        agent.record_task_outcome(hard, Outcome::Success, None);
+        assert!(agent.task_assessment.is_none());
+        assert_ne!(agent.intelligence.ratio(&assessment.task_type), 1.0);
+
+        server.stop().await;
+    }
+
     #[tokio::test]
     async fn test_build_learning_hint_returns_string_or_none() {
         let server = crate::testing::mock_api::MockLlmServer::builder()
@@ -513,6 +769,92 @@ mod tests {
         server.stop().await;
     }
 
+    #[tokio::test]
+    async fn test_record_user_feedback_overrides_last_step() {
+        let server = crate::testing::mock_api::MockLlmServer::builder()
+            .with_response("done")
+            .build()
+            .await;
+        let config = crate::config::Config {
+            endpoint: format!("{}/v1", server.url()),
+            ..Default::default()
+        };
+        let mut agent = Agent::new(config).await.unwrap();
+        agent.self_improvement = SelfImprovementEngine::new();
+        assert!(agent.record_user_feedback(true, None).is_err());
+
+        agent.current_task_context = "fix the tokenizer".to_string();
+        agent.self_improvement.record_tool(
+            "file_edit",
+            "fix the tokenizer",
+            Outcome::Success,
+            5,
+            None,
+        );
+        agent.last_step_tools = vec![("file_edit".to_string(), true)];
+
+        let feedback = agent
+            .record_user_feedback(false, Some(" broke the public API "))
+            .unwrap();
+        assert!(!feedback.positive);
+        assert_eq!(feedback.task_type, "bug_fix");
+        assert_eq!(feedback.note.as_deref(), Some("broke the public API"));
+
+        let (_, score) = agent.self_improvement.best_tools_for("fix the tokenizer")[0].clone();
+        assert!(score < 0.5);
+        let lesson = agent
+            .cognitive_state
+            .episodic_memory
+            .lessons
+            .last()
+            .unwrap();
+        assert_eq!(
+            lesson.content,
+            "User marked file_edit as bad: broke the public API"
+        );
+
+        server.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_build_learning_hint_includes_lessons_for_context_files() {
+        let server = crate::testing::mock_api::MockLlmServer::builder()
+            .with_response("done")
+            .build()
+            .await;
+        let config = crate::config::Config {
+            endpoint: format!("{}/v1", server.url()),
+            ..Default::default()
+        };
+        let mut agent = Agent::new(config).await.unwrap();
+        agent.cognitive_state.episodic_memory = Default::default();
+        agent
+            .cognitive_state
+            .episodic_memory
+            .what_failed("file_edit", "file_edit on src/lexer.rs failed verification");
+        agent
+            .cognitive_state
+            .episodic_memory
+            .what_failed("file_edit", "file_edit on src/other.rs failed verification");
+
+        assert!(agent.context_file_lessons().is_empty());
+
+        agent.context_files.push("./src/lexer.rs".to_string());
+        let hint = agent.build_learning_hint("Fix the lexer").unwrap();
+        assert!(hint.contains("files in context"));
+        assert!(hint.contains("src/lexer.rs failed verification"));
+        assert!(!hint.contains("src/other.rs"));
+
+        // Lessons already in the system prompt are not repeated
+        let lesson = agent.context_file_lessons().remove(0);
+        if let Some(system) = agent.messages.first_mut() {
+            system.content = format!("{}\n- {}", system.content.text(), lesson).into();
+        }
+        assert!(agent.context_file_lessons().is_empty());
+
+        server.stop().await;
+    }
+
     // =========================================================================
     // learning_context
     // =========================================================================
@@ -1,10 +1,29 @@
+use std::time::{Duration, Instant};
+
 #[derive(Debug, Clone)]
 pub enum AgentState {
     Planning,
-    Executing { step: usize },
-    ErrorRecovery { error: String },
+    Executing {
+        step: usize,
+    },
+    ErrorRecovery {
+        error: String,
+    },
     Completed,
-    Failed { reason: String },
+    /// Waiting on the user; `reason` is what the model asked for.
+    Paused {
+        reason: String,
+    },
+    Failed {
+        reason: String,
+    },
+}
+
+/// The step a task was on when it ended in `Failed`, kept for `/retry`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepFailure {
+    pub step: usize,
+    pub reason: String,
 }
 
 pub struct AgentLoop {
@@ -12,6 +31,8 @@ pub struct AgentLoop {
     max_iterations: usize,
     current_step: usize,
     iteration: usize,
+    /// Wall-clock budget (`--max-runtime`): when it started and how long it allows.
+    runtime_limit: Option<(Instant, Duration)>,
 }
 
 impl AgentLoop {
@@ -21,6 +42,7 @@ impl AgentLoop {
             max_iterations,
             current_step: 0,
             iteration: 0,
+            runtime_limit: None,
         }
     }
 
@@ -65,11 +87,14 @@ impl AgentLoop {
         self.state = state;
     }
 
+    /// Count a finished step. A step that paused the task stays paused.
     pub fn increment_step(&mut self) {
         self.current_step += 1;
-        self.state = AgentState::Executing {
-            step: self.current_step,
-        };
+        if !matches!(self.state, AgentState::Paused { .. }) {
+            self.state = AgentState::Executing {
+                step: self.current_step,
+            };
+        }
     }
 
     pub fn current_step(&self) -> usize {
@@ -87,6 +112,23 @@ impl AgentLoop {
         self.state = AgentState::Executing { step };
     }
 
+    /// Start a wall-clock budget now. Unlike the iteration count it spans every
+    /// task in the session, so `reset_for_task` leaves it alone.
+    pub fn set_runtime_limit(&mut self, limit: Duration) {
+        self.runtime_limit = Some((Instant::now(), limit));
+    }
+
+    pub fn runtime_limit(&self) -> Option<Duration> {
+        self.runtime_limit.map(|(_, limit)| limit)
+    }
+
+    /// Time used so far, once the runtime budget is spent.
+    pub fn runtime_exceeded(&self) -> Option<Duration> {
+        let (started, limit) = self.runtime_limit?;
+        let used = started.elapsed();
+        (used >= limit).then_some(used)
+    }
+
     /// Reset loop state for a new task, preserving max_iterations.
     ///
     /// Without this, queued tasks share the iteration counter from the previous
@@ -151,6 +193,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_runtime_limit_survives_task_reset() {
+        let mut loop_ctrl = AgentLoop::new(100);
+        assert!(loop_ctrl.runtime_exceeded().is_none());
+
+        loop_ctrl.set_runtime_limit(Duration::from_secs(3600));
+        assert!(loop_ctrl.runtime_exceeded().is_none());
+
+        loop_ctrl.set_runtime_limit(Duration::ZERO);
+        loop_ctrl.reset_for_task();
+        assert_eq!(loop_ctrl.runtime_limit(), Some(Duration::ZERO));
+        assert!(loop_ctrl.runtime_exceeded().is_some());
+    }
+
     #[test]
     fn test_agent_state_error_recovery() {
         let mut loop_ctrl = AgentLoop::new(100);
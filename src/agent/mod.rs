@@ -1,4 +1,4 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use std::collections::{HashSet, VecDeque};
 use std::sync::{
     atomic::{AtomicBool, Ordering},
@@ -11,6 +11,7 @@ use crate::analyzer::ErrorAnalyzer;
 use crate::api::types::{Message, ToolCall};
 use crate::api::{ApiClient, StreamChunk, ThinkingMode};
 use crate::checkpoint::{CheckpointManager, TaskCheckpoint};
+use crate::cognitive::intelligence::{Assessment, Intelligence};
 use crate::cognitive::self_improvement::{Outcome, SelfImprovementEngine};
 use crate::cognitive::{CognitiveState, CyclePhase};
 use crate::config::Config;
@@ -27,27 +28,59 @@ use crate::tools::ToolRegistry;
 use crate::verification::{VerificationConfig, VerificationGate};
 
 mod checkpointing;
+mod compare;
+mod completion;
 pub mod context;
 mod context_management;
 mod execution;
+mod explain;
+mod focus;
+pub mod guidance;
 mod interactive;
+mod interrupt;
 pub mod last_tool;
 mod learning;
 pub mod loop_control;
+mod plan_export;
 pub mod planning;
+mod rewind;
 mod streaming;
+pub mod task_result;
 mod task_runner;
+pub mod task_summary;
+#[cfg(feature = "tokens")]
+mod token_view;
+mod tool_catalog;
 pub mod tui_events;
 
 use crate::errors::is_confirmation_error;
 use context::ContextCompressor;
-use loop_control::{AgentLoop, AgentState};
+use loop_control::{AgentLoop, AgentState, StepFailure};
 use planning::Planner;
 use tui_events::{AgentEvent, EventEmitter, NoopEmitter};
 
 /// Upper bound for queued interactive messages to avoid unbounded memory growth.
 pub(crate) const MAX_PENDING_MESSAGES: usize = 100;
 
+/// Tools that only read, and so never need confirmation.
+const READ_ONLY_TOOLS: [&str; 10] = [
+    "file_read",
+    "directory_tree",
+    "glob_find",
+    "grep_search",
+    "symbol_search",
+    "find_cycles",
+    "panic_risk",
+    "git_status",
+    "git_diff",
+    "git_blame",
+];
+
+/// Tools that change state without risking work: a new branch points at an
+/// existing commit, and `git_checkout` refuses to leave a dirty tree unless
+/// asked to stash (see [`Agent::call_needs_confirmation`]).
+const NON_DESTRUCTIVE_TOOLS: [&str; 2] = ["git_branch_create", "git_checkout"];
+
 /// Core agent that orchestrates LLM reasoning with tool execution.
 ///
 /// The agent maintains conversation state, manages tool calls through a safety
@@ -89,14 +122,25 @@ pub struct Agent {
     events: Arc<dyn EventEmitter>,
     /// Edit history for undo support
     edit_history: EditHistory,
+    /// Turns of the conversation that `/rewind` can take back, oldest first
+    turns: Vec<rewind::Turn>,
     /// Last assistant response content (for /copy command)
     last_assistant_response: String,
     /// Chat session store for save/resume/list/delete
     chat_store: ChatStore,
     /// Cancellation token set by Ctrl+C while a task is running
     cancelled: Arc<AtomicBool>,
+    /// Ctrl+C pause request/menu state, see `interrupt`
+    pause_state: Arc<std::sync::atomic::AtomicU8>,
+    /// "Save and quit" was chosen at the pause menu
+    quit_requested: bool,
     /// Messages queued for sequential execution
     pending_messages: VecDeque<String>,
+    /// Images from `/image` for the next user message: `ImageUrl` blocks,
+    /// or `Text` descriptions when the model cannot view images
+    pending_images: Vec<crate::api::types::ContentBlock>,
+    /// Active session time and break reminders
+    wellness: crate::ui::accessibility::wellness::Wellness,
     /// Maximum total estimated tokens for the message history.
     /// When exceeded, oldest non-system messages are removed.
     max_context_tokens: usize,
@@ -105,6 +149,44 @@ pub struct Agent {
     self_healing: SelfHealingEngine,
     /// Recent tool call signatures for repetition detection (name, args_hash)
     recent_tool_calls: VecDeque<(String, u64)>,
+    /// Tools run by the most recent tool step and whether each succeeded, for `/feedback`
+    last_step_tools: Vec<(String, bool)>,
+    /// Descriptions of the calls the model made in reply to the last request
+    last_step_calls: Vec<String>,
+    /// What went into the last request, for `/explain`
+    decision_trace: Option<explain::DecisionTrace>,
+    /// How the user has been typing, for estimating their cognitive load
+    load_signals: crate::cognitive::load::SessionSignals,
+    /// Latest cognitive load estimate
+    cognitive_load: crate::cognitive::load::LoadLevel,
+    /// Task difficulty estimator, calibrated by finished tasks
+    intelligence: Intelligence,
+    /// Difficulty assessment of the running task
+    task_assessment: Option<Assessment>,
+    /// Model in use before difficulty routing switched away from it
+    routed_from: Option<String>,
+    /// Project guidance block currently prepended to the system prompt
+    project_guidance: String,
+    /// Decisions pinned by `/compact`, appended to the system prompt
+    pinned_note: Option<String>,
+    /// Paths the task is scoped to (`/focus`, `--focus`); empty when unscoped
+    focus: Vec<String>,
+    /// Step and reason of the last task failure, consumed by `/retry`
+    last_failure: Option<StepFailure>,
+    /// Full text of tool results elided to fit `max_tool_result_tokens`
+    tool_outputs: Arc<crate::tools::tool_output::ToolOutputStore>,
+    /// Index behind `rag_search` and the chunks it already inserted
+    rag: Arc<crate::tools::rag::RagState>,
+    /// Readable text of URLs included with `@https://...`, keyed by URL
+    url_cache: std::collections::HashMap<String, String>,
+    /// Host memory/CPU readings, taken before each loop iteration
+    pressure: crate::resource::PressureSampler,
+    /// Per-tool execution times, persisted for `selfware analytics tool-stats`
+    tool_timings: crate::observability::analytics::ToolTimingStore,
+    /// Hooks fired when a task completes, fails or pauses
+    notifier: crate::observability::notifications::Notifier,
+    /// CPU time of the processes each tool spawned, for the session footprint
+    carbon: crate::observability::carbon_tracker::CarbonTracker,
 }
 
 impl Agent {
@@ -114,10 +196,36 @@ impl Agent {
         tools.register(crate::tools::fim::FileFimEdit::new(std::sync::Arc::new(
             client.clone(),
         )));
+        // File tools open their capability roots from this agent's allowed paths
+        tools.register(
+            crate::tools::file::FileRead::with_safety_config(config.safety.clone())
+                .with_max_read_bytes(config.agent.max_read_bytes),
+        );
+        tools.register(crate::tools::file::FileWrite::with_safety_config(
+            config.safety.clone(),
+        ));
+        tools.register(crate::tools::file::FileEdit::with_safety_config(
+            config.safety.clone(),
+        ));
+        let tool_outputs = Arc::new(crate::tools::tool_output::ToolOutputStore::new());
+        tools.register(crate::tools::tool_output::ToolOutput::new(
+            tool_outputs.clone(),
+        ));
+        let rag = Arc::new(crate::tools::rag::RagState::new(guidance::repo_root(
+            &std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from(".")),
+        )));
+        tools.register(crate::tools::rag::RagSearch::new(rag.clone()));
+        tools.register(crate::tools::rag::RagReindex::new(rag.clone()));
+        tools
+            .set_filter(crate::tools::ToolFilter::new(&config.tools)?)
+            .context("Invalid tool allow/deny list")?;
         let memory = AgentMemory::new(&config)?;
-        let safety = SafetyChecker::new(&config.safety);
+        let safety =
+            SafetyChecker::new(&config.safety).with_self_edit_allowed(config.allow_self_edit);
         // Publish the user-loaded safety config so file tools honour allowed_paths etc.
         init_safety_config(&config.safety);
+        crate::safety::redact::init_redaction(&config.redaction)
+            .context("Invalid redaction allowlist")?;
         let loop_control = AgentLoop::new(config.agent.max_iterations);
         let compressor = ContextCompressor::new(config.max_tokens);
 
@@ -161,6 +269,22 @@ impl Agent {
             SelfImprovementEngine::new()
         };
 
+        let difficulty_path = dirs::data_local_dir()
+            .unwrap_or_else(|| std::path::PathBuf::from("."))
+            .join("selfware")
+            .join("task_difficulty.json");
+        let intelligence = if difficulty_path.exists() {
+            Intelligence::load(&difficulty_path).unwrap_or_else(|e| {
+                warn!(
+                    "Failed to load task difficulty calibration: {}, starting fresh",
+                    e
+                );
+                Intelligence::new()
+            })
+        } else {
+            Intelligence::new()
+        };
+
         // Choose between native function calling or XML-based tool parsing
         let mut system_prompt = if config.agent.native_function_calling {
             // Native function calling: simple prompt, tools passed via API
@@ -180,6 +304,7 @@ You have access to tools for file operations, git, cargo, shell commands, and mo
 - NEVER skip verification after file_edit or file_write
 - NEVER declare complete without a successful cargo_check
 - When editing files, include 3-5 lines of context for unique matches
+- Run commands with shell_exec's program and args, e.g. {"program": "cargo", "args": ["test", "-p", "foo"]}; use its command string only for pipes or redirects
 - You have a large budget. Do NOT rush. Be thorough and methodical.
 - When the task is complete, respond with a summary of what was done."#
                 .to_string()
@@ -232,7 +357,7 @@ To call a tool, use this EXACT XML structure:
 
 <tool>
 <name>shell_exec</name>
-<arguments>{{"command": "cargo build"}}</arguments>
+<arguments>{{"program": "cargo", "args": ["build"]}}</arguments>
 </tool>
 
 ### WRONG formats (DO NOT USE):
@@ -253,6 +378,7 @@ To call a tool, use this EXACT XML structure:
 - Arguments must be valid JSON inside <arguments>...</arguments>
 - Each <tool>...</tool> block is executed separately
 - Wait for tool results before proceeding
+- Run commands with shell_exec's program and args; use its command string only for pipes or redirects
 - NEVER skip verification after file_edit or file_write
 - NEVER declare complete without a successful cargo_check
 - You have a large budget. Do NOT rush. Be thorough and methodical.
@@ -261,7 +387,8 @@ To call a tool, use this EXACT XML structure:
             )
         };
 
-        // Inject past lessons to avoid repeating mistakes
+        // Inject past lessons to avoid repeating mistakes. Lessons about files
+        // loaded into context are added per request by build_learning_hint.
         let recent_lessons = cognitive_state.episodic_memory.recent_lessons(10);
         if !recent_lessons.is_empty() {
             system_prompt.push_str("\n\n## Global Lessons Learned\nDo not repeat past mistakes. Consider these lessons:\n");
@@ -279,14 +406,63 @@ To call a tool, use this EXACT XML structure:
             }
         }
 
+        if let Some(section) = completion::prompt_section(&config.agent) {
+            system_prompt.push_str(&section);
+        }
+        if let Some(enabled) = tools.restricted_names() {
+            system_prompt.push_str(&format!(
+                "\n\n## Tools For This Task\nOnly these tools are enabled: {}. Any other tool \
+                 is disabled and calls to it will fail; skip workflow steps that need one and \
+                 say so in your summary.\n",
+                enabled.join(", ")
+            ));
+        }
+
+        // Prepend repository conventions (AGENTS.md and friends) as project guidance
+        let cwd = std::env::current_dir().unwrap_or_else(|_| ".".into());
+        let project_guidance = guidance::load(
+            &guidance::repo_root(&cwd),
+            &config.agent.guidance_files,
+            config.agent.guidance_max_tokens,
+        )
+        .map(|g| {
+            info!(
+                "Loaded project guidance from {} file(s), ~{} tokens",
+                g.files.len(),
+                g.tokens
+            );
+            format!("{}\n", g.text)
+        })
+        .unwrap_or_default();
+        system_prompt.insert_str(0, &project_guidance);
+
         let messages = vec![Message::system(system_prompt)];
 
         // Initialize checkpoint manager if configured
-        let checkpoint_manager = CheckpointManager::default_path().ok();
+        let checkpoint_manager = Self::configured_checkpoint_manager(&config).ok();
 
         // Initialize verification gate with project root
         let project_root = std::env::current_dir().unwrap_or_else(|_| ".".into());
-        let verification_gate = VerificationGate::new(&project_root, VerificationConfig::fast());
+        let verification_config = VerificationConfig {
+            deny_patterns: config
+                .agent
+                .deny_patterns
+                .iter()
+                .filter_map(|p| match regex::Regex::new(p) {
+                    Ok(re) => Some(re),
+                    Err(e) => {
+                        warn!("Ignoring invalid deny pattern '{}': {}", p, e);
+                        None
+                    }
+                })
+                .collect(),
+            ..VerificationConfig::fast()
+        };
+        let verification_gate = VerificationGate::new(&project_root, verification_config);
+        let pressure = crate::resource::PressureSampler::new(
+            &config.resources.memory,
+            &config.resources.pause,
+        );
 
         // Initialize error analyzer
         let error_analyzer = ErrorAnalyzer::new();
@@ -295,7 +471,7 @@ To call a tool, use this EXACT XML structure:
         let self_healing = SelfHealingEngine::new(SelfHealingConfig {
             enabled: config.continuous_work.auto_recovery,
             max_healing_attempts: config.continuous_work.max_recovery_attempts,
-            checkpoint_interval_secs: config.continuous_work.checkpoint_interval_secs,
+            checkpoint_interval_secs: config.checkpoint_interval_secs(),
             ..Default::default()
         });
 
@@ -304,7 +480,17 @@ To call a tool, use this EXACT XML structure:
 
         info!("Agent initialized with cognitive state, verification gate, and error analyzer");
 
-        Ok(Self {
+        let notifier = crate::observability::notifications::Notifier::new(&config.notifications);
+        let carbon = crate::observability::carbon_tracker::CarbonTracker::new()
+            .with_calculator(
+                crate::observability::carbon_tracker::EmissionCalculator::new().with_intensity(
+                    crate::observability::carbon_tracker::GridIntensity::Custom(
+                        config.carbon.grams_per_kwh,
+                    ),
+                ),
+            )
+            .with_cpu_watts(config.carbon.cpu_watts);
+        let mut agent = Self {
             client,
             tools,
             memory,
@@ -327,15 +513,45 @@ To call a tool, use this EXACT XML structure:
             checkpoint_persisted_once: false,
             events: Arc::new(NoopEmitter),
             edit_history,
+            turns: Vec::new(),
             last_assistant_response: String::new(),
             chat_store,
             cancelled: Arc::new(AtomicBool::new(false)),
+            pause_state: Arc::new(std::sync::atomic::AtomicU8::new(0)),
+            quit_requested: false,
             pending_messages: VecDeque::new(),
+            pending_images: Vec::new(),
+            wellness: crate::ui::accessibility::wellness::Wellness::load(),
             max_context_tokens: 100_000,
             #[cfg(feature = "resilience")]
             self_healing,
             recent_tool_calls: VecDeque::new(),
-        })
+            last_step_tools: Vec::new(),
+            last_step_calls: Vec::new(),
+            decision_trace: None,
+            load_signals: crate::cognitive::load::SessionSignals::new(),
+            cognitive_load: crate::cognitive::load::LoadLevel::Normal,
+            intelligence,
+            task_assessment: None,
+            routed_from: None,
+            project_guidance,
+            pinned_note: None,
+            focus: Vec::new(),
+            last_failure: None,
+            tool_outputs,
+            rag,
+            url_cache: std::collections::HashMap::new(),
+            pressure,
+            tool_timings: crate::observability::analytics::ToolTimingStore::new(),
+            notifier,
+            carbon,
+        };
+        if !agent.config.focus.is_empty() {
+            agent
+                .set_focus(agent.config.focus.clone())
+                .context("Invalid --focus")?;
+        }
+        Ok(agent)
     }
 
     /// Set the TUI event sender for real-time updates
@@ -348,11 +564,23 @@ To call a tool, use this EXACT XML structure:
         self
     }
 
+    /// Stop tasks at the next step boundary once `limit` of wall-clock time has
+    /// passed, counted from now and across every task in the session.
+    pub fn with_max_runtime(mut self, limit: std::time::Duration) -> Self {
+        self.loop_control.set_runtime_limit(limit);
+        self
+    }
+
     /// Emit an event to the TUI / event listener (no-op when no emitter is configured).
     fn emit_event(&self, event: AgentEvent) {
         self.events.emit(event);
     }
 
+    /// Send the live dashboard fresh context, usage and plan figures
+    fn emit_run_stats(&self) {
+        self.emit_event(AgentEvent::Stats(self.run_stats()));
+    }
+
     /// Get tools for API calls - returns Some(tools) if native function calling is enabled
     fn api_tools(&self) -> Option<Vec<crate::api::types::ToolDefinition>> {
         if self.config.agent.native_function_calling {
@@ -392,22 +620,13 @@ To call a tool, use this EXACT XML structure:
     /// 1. Read-only tools never need confirmation
     /// 2. Yolo / Daemon mode never asks
     /// 3. Tools in `safety.require_confirmation` config always ask (except Yolo/Daemon)
-    /// 4. Mode-specific rules (AutoEdit auto-approves file ops, Normal asks for everything)
+    /// 4. Non-destructive tools never ask
+    /// 5. Mode-specific rules (AutoEdit auto-approves file ops, Normal asks for everything)
     pub fn needs_confirmation(&self, tool_name: &str) -> bool {
         use crate::config::ExecutionMode;
 
         // Read-only tools never need confirmation
-        let safe_tools = [
-            "file_read",
-            "directory_tree",
-            "glob_find",
-            "grep_search",
-            "symbol_search",
-            "git_status",
-            "git_diff",
-        ];
-
-        if safe_tools.contains(&tool_name) {
+        if READ_ONLY_TOOLS.contains(&tool_name) {
             return false;
         }
 
@@ -430,20 +649,48 @@ To call a tool, use this EXACT XML structure:
             return true;
         }
 
+        if NON_DESTRUCTIVE_TOOLS.contains(&tool_name) {
+            return false;
+        }
+
         match self.config.execution_mode {
             ExecutionMode::Yolo | ExecutionMode::Daemon => false, // Already handled above
             ExecutionMode::AutoEdit => {
                 // Auto-approve file operations, ask for destructive operations
                 !matches!(
                     tool_name,
-                    "file_write" | "file_edit" | "directory_tree" | "glob_find"
+                    "file_write"
+                        | "file_edit"
+                        | "apply_patch"
+                        | "symbol_rename"
+                        | "directory_tree"
+                        | "glob_find"
                 )
             }
             ExecutionMode::Normal => {
                 // Ask for all tools except safe ones
-                !safe_tools.contains(&tool_name)
+                !READ_ONLY_TOOLS.contains(&tool_name)
+            }
+        }
+    }
+
+    /// [`needs_confirmation`](Self::needs_confirmation) for one call, where
+    /// the arguments change the risk. Stashing before a checkout moves the
+    /// user's uncommitted work, so it asks like any other write.
+    pub fn call_needs_confirmation(&self, tool_name: &str, args_str: &str) -> bool {
+        if tool_name == "git_checkout" {
+            let stash = serde_json::from_str::<serde_json::Value>(args_str)
+                .ok()
+                .and_then(|args| args.get("stash").and_then(|v| v.as_bool()))
+                .unwrap_or(false);
+            if stash {
+                return !matches!(
+                    self.config.execution_mode,
+                    crate::config::ExecutionMode::Yolo | crate::config::ExecutionMode::Daemon
+                );
             }
         }
+        self.needs_confirmation(tool_name)
     }
 
     /// Check if running in non-interactive mode (piped stdin)
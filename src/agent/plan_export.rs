@@ -0,0 +1,111 @@
+//! `/plan export`: write the agent's current plan for reviewers
+//!
+//! The plan the agent tracks in its cognitive state is converted to a
+//! [`Plan`] and written as Markdown with an embedded Mermaid flowchart, or
+//! as a bare Mermaid flowchart when the file ends in `.mmd`/`.mermaid`.
+
+use std::path::Path;
+
+use super::*;
+use crate::cognitive::state::StepStatus;
+use crate::planning::{GoalStatus, Plan, PlanStep};
+
+/// Where `/plan export` writes without a path
+pub(super) const DEFAULT_PLAN_EXPORT: &str = "plan.md";
+
+impl Agent {
+    /// The active operational plan, or `None` before the agent has one
+    pub(super) fn current_plan(&self) -> Option<Plan> {
+        let operational = self.cognitive_state.active_operational_plan.as_ref()?;
+        if operational.steps.is_empty() {
+            return None;
+        }
+        let name = self
+            .cognitive_state
+            .active_tactical_plan
+            .as_ref()
+            .map(|plan| plan.description.clone())
+            .unwrap_or_else(|| "Current plan".to_string());
+        let mut plan = Plan::new(&operational.task_id, name, &operational.task_id);
+        for step in &operational.steps {
+            let mut exported = PlanStep::new(step.index.to_string(), &step.description);
+            exported.status = match step.status {
+                StepStatus::Pending => GoalStatus::Pending,
+                StepStatus::InProgress => GoalStatus::Active,
+                StepStatus::Completed => GoalStatus::Achieved,
+                StepStatus::Failed => GoalStatus::Failed,
+                StepStatus::Skipped => GoalStatus::Abandoned,
+            };
+            if step.status == StepStatus::Failed {
+                exported.error = step.notes.clone();
+            }
+            plan.add_step(exported);
+        }
+        Some(plan)
+    }
+
+    /// Write the current plan to `path`, as Mermaid for `.mmd`/`.mermaid`
+    /// files and as Markdown otherwise
+    pub(super) fn export_plan(&self, path: &Path) -> Result<()> {
+        let Some(plan) = self.current_plan() else {
+            anyhow::bail!("No plan yet; run a task or /plan <task> first");
+        };
+        let mermaid_only = matches!(
+            path.extension().and_then(|e| e.to_str()),
+            Some("mmd" | "mermaid")
+        );
+        let text = if mermaid_only {
+            plan.to_mermaid() + "\n"
+        } else {
+            plan.to_markdown()
+        };
+        std::fs::write(path, text)
+            .with_context(|| format!("Failed to write plan to {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_export_plan_as_markdown_and_mermaid() {
+        let server = crate::testing::mock_api::MockLlmServer::builder()
+            .with_response("done")
+            .build()
+            .await;
+        let config = crate::config::Config {
+            endpoint: format!("{}/v1", server.url()),
+            model: "mock".to_string(),
+            ..Default::default()
+        };
+        let mut agent = Agent::new(config).await.unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        assert!(agent.export_plan(&dir.path().join("plan.md")).is_err());
+
+        agent.cognitive_state.set_operational_plan(
+            "task-1",
+            vec![
+                "Read lexer.rs".to_string(),
+                "Fix the \"EOF\" case".to_string(),
+            ],
+        );
+        agent.cognitive_state.complete_operational_step(1, None);
+
+        let markdown = dir.path().join("plan.md");
+        agent.export_plan(&markdown).unwrap();
+        let text = std::fs::read_to_string(&markdown).unwrap();
+        assert!(text.contains("- [x] 1. Read lexer.rs"), "{}", text);
+        assert!(text.contains("```mermaid"), "{}", text);
+
+        let mermaid = dir.path().join("plan.mmd");
+        agent.export_plan(&mermaid).unwrap();
+        let text = std::fs::read_to_string(&mermaid).unwrap();
+        assert!(text.starts_with("flowchart TD"), "{}", text);
+        assert!(text.contains("#quot;EOF#quot;"), "{}", text);
+        assert!(text.contains("s0 --> s1"), "{}", text);
+        assert!(text.contains("class s0 done"), "{}", text);
+
+        server.stop().await;
+    }
+}
@@ -1,7 +1,120 @@
 /// Planner generates structured prompts for task planning
 pub struct Planner;
 
+/// Most clarifying questions asked before planning
+pub const MAX_CLARIFYING_QUESTIONS: usize = 2;
+
+/// Tasks longer than this are taken as specified enough
+const DETAILED_TASK_WORDS: usize = 25;
+
+/// Words that point at something the task never names
+const VAGUE_REFERENTS: &[&str] = &[
+    "it",
+    "this",
+    "that",
+    "these",
+    "those",
+    "things",
+    "stuff",
+    "the bug",
+    "the issue",
+    "the problem",
+    "the error",
+];
+
+/// Changes with no natural end point
+const OPEN_ENDED_ACTIONS: &[&str] = &[
+    "improve",
+    "clean up",
+    "cleanup",
+    "refactor",
+    "optimize",
+    "optimise",
+    "polish",
+    "modernize",
+    "tidy",
+    "make better",
+    "make it better",
+];
+
+/// Objects that leave an open-ended change unbounded
+const BROAD_OBJECTS: &[&str] = &[
+    "everything",
+    "all",
+    "it",
+    "this",
+    "things",
+    "stuff",
+    "the code",
+    "the codebase",
+    "the project",
+    "the app",
+    "the repo",
+];
+
+/// What about a task is unclear
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ambiguity {
+    /// No file, symbol or component is named
+    MissingTarget,
+    /// No criterion says when the change is done
+    UndefinedScope,
+}
+
+/// A question to put to the user before planning
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Question {
+    pub kind: Ambiguity,
+    pub text: String,
+    /// What the agent proceeds on when nobody can answer
+    pub assumption: String,
+}
+
+/// What the agent already knows beyond the task text
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PlanningContext<'a> {
+    /// Files loaded into the conversation
+    pub files: &'a [String],
+    /// Whether earlier turns could explain references like "it"
+    pub has_history: bool,
+}
+
 impl Planner {
+    /// Questions worth asking before planning `task`, or `None` when it is
+    /// clear enough to start. Deliberately conservative: anything naming a
+    /// file, symbol or concrete component passes, as does any detailed task.
+    pub fn needs_clarification(task: &str, context: &PlanningContext) -> Option<Vec<Question>> {
+        let words = words(task);
+        if words.is_empty() || words.len() > DETAILED_TASK_WORDS || names_target(task) {
+            return None;
+        }
+        let text = format!(" {} ", words.join(" "));
+        let mentions =
+            |phrases: &[&str]| phrases.iter().any(|p| text.contains(&format!(" {} ", p)));
+
+        let mut questions = Vec::new();
+        if context.files.is_empty() && !context.has_history && mentions(VAGUE_REFERENTS) {
+            questions.push(Question {
+                kind: Ambiguity::MissingTarget,
+                text: "Which files or components should I work on?".to_string(),
+                assumption: "The target is not named; locate the relevant code by searching \
+                             the project and state which files were chosen."
+                    .to_string(),
+            });
+        }
+        if mentions(OPEN_ENDED_ACTIONS) && (words.len() <= 2 || mentions(BROAD_OBJECTS)) {
+            questions.push(Question {
+                kind: Ambiguity::UndefinedScope,
+                text: "What should be different when this is done?".to_string(),
+                assumption: "The scope is open-ended; keep changes small and \
+                             behaviour-preserving, limited to the clearest problems."
+                    .to_string(),
+            });
+        }
+        questions.truncate(MAX_CLARIFYING_QUESTIONS);
+        (!questions.is_empty()).then_some(questions)
+    }
+
     /// Create a planning prompt with task and context
     pub fn create_plan(task: &str, context: &str) -> String {
         format!(
@@ -37,6 +150,33 @@ Be thorough but concise.
         )
     }
 
+    /// Create a prompt for analyzing only the files changed since a ref
+    pub fn analyze_changes_prompt(path: &str, since: &str, files: &[String]) -> String {
+        format!(
+            r#"
+Analyze the changes to the codebase at {} since {}. Only these files changed
+(paths relative to {}); do not survey the rest of the tree:
+{}
+
+Provide:
+1. What changed and why it matters
+2. How the changed files fit into the existing architecture
+3. New or changed dependencies and entry points
+4. Risks, regressions or follow-up work the changes suggest
+
+Be thorough but concise.
+"#,
+            path,
+            since,
+            path,
+            files
+                .iter()
+                .map(|f| format!("- {}", f))
+                .collect::<Vec<_>>()
+                .join("\n")
+        )
+    }
+
     /// Create a prompt for code review
     pub fn review_prompt(file_path: &str, content: &str) -> String {
         format!(
@@ -59,6 +199,37 @@ Identify:
     }
 }
 
+/// Lowercased words of `text`, keeping apostrophes inside words
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .map(|w| w.trim_matches('\'').to_lowercase())
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+/// Whether `task` names something concrete: a path, a file with an
+/// extension, a code identifier, a quoted name or an issue number
+fn names_target(task: &str) -> bool {
+    if task.contains('`') || task.contains("::") || task.contains('"') || task.contains('#') {
+        return true;
+    }
+    task.split_whitespace().any(|token| {
+        let token = token.trim_matches(|c: char| !c.is_alphanumeric() && c != '_' && c != '/');
+        let path_like = token.contains('/') && token.len() > 1;
+        let file_like = token
+            .rsplit_once('.')
+            .is_some_and(|(stem, ext)| !stem.is_empty() && (1..=5).contains(&ext.len()));
+        let snake_case = token.contains('_') && token.chars().any(char::is_alphabetic);
+        let camel_case = token
+            .chars()
+            .skip(1)
+            .zip(token.chars())
+            .any(|(c, prev)| c.is_uppercase() && prev.is_lowercase());
+        let call = token.ends_with("()");
+        path_like || file_like || snake_case || camel_case || call
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -108,6 +279,54 @@ mod tests {
         assert!(plan.contains("Context with 'special' chars"));
     }
 
+    #[test]
+    fn test_needs_clarification_flags_vague_tasks() {
+        let none = PlanningContext::default();
+        let questions = Planner::needs_clarification("fix it", &none).unwrap();
+        assert_eq!(questions.len(), 1);
+        assert_eq!(questions[0].kind, Ambiguity::MissingTarget);
+
+        let questions = Planner::needs_clarification("Clean up the codebase", &none).unwrap();
+        assert_eq!(questions[0].kind, Ambiguity::UndefinedScope);
+
+        let questions = Planner::needs_clarification("improve this", &none).unwrap();
+        assert_eq!(questions.len(), MAX_CLARIFYING_QUESTIONS);
+        assert!(questions.iter().all(|q| !q.assumption.is_empty()));
+    }
+
+    #[test]
+    fn test_needs_clarification_passes_clear_tasks() {
+        let none = PlanningContext::default();
+        for task in [
+            "Fix the bug in src/parser.rs",
+            "refactor parse_config to return a Result",
+            "Improve the error message when ConfigLoader fails",
+            "fix the failing test in `api::client`",
+            "Add a --json flag to the status command",
+            "Fix the login page crash when the password is empty",
+            "fix issue #42",
+            "",
+        ] {
+            assert!(
+                Planner::needs_clarification(task, &none).is_none(),
+                "flagged: {task}"
+            );
+        }
+
+        // Earlier turns or loaded files explain what "it" is
+        let files = vec!["src/main.rs".to_string()];
+        let with_files = PlanningContext {
+            files: &files,
+            has_history: false,
+        };
+        assert!(Planner::needs_clarification("fix it", &with_files).is_none());
+        let with_history = PlanningContext {
+            files: &[],
+            has_history: true,
+        };
+        assert!(Planner::needs_clarification("now fix that", &with_history).is_none());
+    }
+
     #[test]
     fn test_analyze_prompt_includes_path() {
         let prompt = Planner::analyze_prompt("./src");
@@ -116,6 +335,15 @@ mod tests {
         assert!(prompt.contains("Dependencies"));
     }
 
+    #[test]
+    fn test_analyze_changes_prompt_lists_only_changed_files() {
+        let files = vec!["src/api/mod.rs".to_string(), "Cargo.toml".to_string()];
+        let prompt = Planner::analyze_changes_prompt(".", "main", &files);
+        assert!(prompt.contains("since main"));
+        assert!(prompt.contains("- src/api/mod.rs\n- Cargo.toml"));
+        assert!(prompt.contains("do not survey the rest of the tree"));
+    }
+
     #[test]
     fn test_review_prompt_includes_content() {
         let prompt = Planner::review_prompt("src/main.rs", "fn main() {}");
@@ -0,0 +1,198 @@
+//! `/rewind`: take back the last turn
+//!
+//! A turn is one user message and everything the agent did in reply. Each
+//! turn remembers its prompt and the first edit-history checkpoint made
+//! during it, so one `/rewind` drops the turn's messages, puts edited files
+//! back as they were before it, removes files it created, and resets the
+//! loop step. The system prompt is never removed.
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use super::*;
+use crate::session::edit_history::{EditAction, EditCheckpointId};
+
+/// Where a turn started, for `/rewind`
+#[derive(Debug, Clone)]
+pub(super) struct Turn {
+    /// The user message that opened the turn
+    prompt: String,
+    /// First edit-history checkpoint that belongs to the turn
+    first_edit: EditCheckpointId,
+}
+
+/// What rewinding the last turn undoes
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(super) struct RewindPlan {
+    pub prompt: String,
+    /// Messages that will be dropped
+    pub messages: usize,
+    /// Files put back to their content before the turn
+    pub restore: Vec<PathBuf>,
+    /// Files the turn created, to be deleted
+    pub remove: Vec<PathBuf>,
+}
+
+impl Agent {
+    /// Start a turn for the user message just pushed
+    pub(super) fn begin_turn(&mut self, prompt: &str) {
+        self.turns.push(Turn {
+            prompt: prompt.to_string(),
+            first_edit: self.edit_history.next_id(),
+        });
+    }
+
+    /// Index of the first message of `turn`. If trimming dropped its
+    /// opening message, every non-system message left belongs to it.
+    fn turn_start(&self, turn: &Turn) -> usize {
+        let floor = self
+            .messages
+            .iter()
+            .take_while(|m| m.role == "system")
+            .count();
+        self.messages
+            .iter()
+            .rposition(|m| m.role == "user" && m.content.text() == turn.prompt)
+            .unwrap_or(floor)
+            .max(floor)
+    }
+
+    /// What `/rewind` would undo, or `None` when no turn is left
+    pub(super) fn rewind_plan(&self) -> Option<RewindPlan> {
+        let turn = self.turns.last()?;
+        let mut plan = RewindPlan {
+            prompt: turn.prompt.clone(),
+            messages: self.messages.len() - self.turn_start(turn),
+            ..Default::default()
+        };
+        // The oldest checkpoint for a path holds its content before the turn
+        let mut seen = HashSet::new();
+        for checkpoint in self
+            .edit_history
+            .all()
+            .iter()
+            .filter(|c| c.id.0 >= turn.first_edit.0)
+        {
+            if let EditAction::FileCreate { path } = &checkpoint.action {
+                if seen.insert(path.clone()) {
+                    plan.remove.push(path.clone());
+                }
+            }
+            for path in checkpoint.files.keys() {
+                if seen.insert(path.clone()) {
+                    plan.restore.push(path.clone());
+                }
+            }
+        }
+        Some(plan)
+    }
+
+    /// Undo the last turn: drop its messages, restore and remove the files
+    /// it touched, and reset the loop step
+    pub(super) fn rewind_turn(&mut self) -> Result<RewindPlan> {
+        let Some(plan) = self.rewind_plan() else {
+            anyhow::bail!("Nothing to rewind; only the system prompt is left");
+        };
+        let turn = self.turns.pop().expect("rewind_plan found a turn");
+
+        let checkpoints = self.edit_history.take_since(turn.first_edit);
+        for path in &plan.restore {
+            let snapshot = checkpoints
+                .iter()
+                .find_map(|c| c.files.get(path))
+                .expect("planned paths come from these checkpoints");
+            std::fs::write(path, &snapshot.content)
+                .with_context(|| format!("Failed to restore {}", path.display()))?;
+        }
+        for path in &plan.remove {
+            match std::fs::remove_file(path) {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => {
+                    return Err(e).with_context(|| format!("Failed to remove {}", path.display()))
+                }
+            }
+        }
+
+        let start = self.turn_start(&turn);
+        self.messages.truncate(start);
+        self.loop_control.reset_for_task();
+        self.last_failure = None;
+        Ok(plan)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_rewind_drops_turn_messages_and_file_changes() {
+        use crate::session::edit_history::FileSnapshot;
+
+        let server = crate::testing::mock_api::MockLlmServer::builder()
+            .with_response("done")
+            .build()
+            .await;
+        let config = crate::config::Config {
+            endpoint: format!("{}/v1", server.url()),
+            model: "mock".to_string(),
+            ..Default::default()
+        };
+        let mut agent = Agent::new(config).await.unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let edited = dir.path().join("lib.rs");
+        let created = dir.path().join("new.rs");
+        let system_messages = agent.messages.len();
+
+        assert!(agent.rewind_plan().is_none());
+        assert!(agent.rewind_turn().is_err());
+
+        // Turn one stays
+        agent.messages.push(Message::user("first"));
+        agent.begin_turn("first");
+        agent.messages.push(Message::assistant("ok"));
+
+        // Turn two edits one file and creates another
+        agent.messages.push(Message::user("second"));
+        agent.begin_turn("second");
+        std::fs::write(&edited, "before\n").unwrap();
+        agent.edit_history.create_checkpoint(EditAction::FileEdit {
+            path: edited.clone(),
+            tool: "file_edit".to_string(),
+        });
+        agent
+            .edit_history
+            .add_file_to_current(FileSnapshot::new(edited.clone(), "before\n".to_string()));
+        std::fs::write(&edited, "after\n").unwrap();
+        agent
+            .edit_history
+            .create_checkpoint(EditAction::FileCreate {
+                path: created.clone(),
+            });
+        std::fs::write(&created, "fn new() {}\n").unwrap();
+        agent.messages.push(Message::assistant("edited"));
+        agent
+            .messages
+            .push(Message::user("<tool_result>ok</tool_result>"));
+
+        let plan = agent.rewind_plan().unwrap();
+        assert_eq!(plan.prompt, "second");
+        assert_eq!(plan.messages, 3);
+        assert_eq!(plan.restore, vec![edited.clone()]);
+        assert_eq!(plan.remove, vec![created.clone()]);
+
+        agent.rewind_turn().unwrap();
+        assert_eq!(std::fs::read_to_string(&edited).unwrap(), "before\n");
+        assert!(!created.exists());
+        assert_eq!(agent.messages.len(), system_messages + 2);
+        assert_eq!(agent.messages.last().unwrap().content.text(), "ok");
+
+        // The system prompt survives rewinding everything
+        agent.rewind_turn().unwrap();
+        assert_eq!(agent.messages.len(), system_messages);
+        assert!(agent.rewind_turn().is_err());
+
+        server.stop().await;
+    }
+}
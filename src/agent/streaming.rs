@@ -33,6 +33,7 @@ impl Agent {
 
     /// Chat with streaming, displaying output as it arrives
     /// Returns (content, reasoning, tool_calls) tuple
+    #[tracing::instrument(name = "api.stream", skip_all)]
     pub(super) async fn chat_streaming(
         &self,
         messages: Vec<Message>,
@@ -50,14 +51,24 @@ impl Agent {
         let stream = self.client.chat_stream(messages, tools, thinking).await?;
 
         let mut rx = stream.into_channel().await;
-        let mut content = String::new();
-        let mut reasoning = String::new();
-        let mut tool_calls: Vec<ToolCall> = Vec::new();
-        let mut in_reasoning = false;
-        let mut display_buf = String::new();
-        let mut in_tool_tag = false;
-
-        while let Some(chunk_result) = rx.recv().await {
+        let mut turn = StreamTurn::new(
+            !output::is_compact(),
+            colored::control::SHOULD_COLORIZE.should_colorize(),
+        );
+
+        let cancel = crate::tools::CancellationToken::from_flag(self.cancel_token());
+        loop {
+            // Dropping the receiver on cancel closes the channel, which makes
+            // the reader task drop the HTTP response.
+            let chunk_result = tokio::select! {
+                chunk = rx.recv() => match chunk {
+                    Some(chunk) => chunk,
+                    None => break,
+                },
+                _ = cancel.cancelled() => {
+                    return Err(crate::errors::AgentError::Cancelled.into());
+                }
+            };
             let chunk = chunk_result?;
 
             // Rotate loading phrase every 3 seconds while spinner is active
@@ -68,86 +79,20 @@ impl Agent {
                 }
             }
 
-            match chunk {
+            let shown = match chunk {
                 StreamChunk::Content(text) => {
                     // Stop spinner on first content
-                    if let Some(s) = spinner.take() {
-                        drop(s);
-                    }
-                    if in_reasoning {
-                        // Finished reasoning, now showing content
-                        in_reasoning = false;
-                        if !output::is_compact() {
-                            println!(); // End reasoning line
-                        }
-                    }
-                    // Always accumulate full content for parsing
-                    content.push_str(&text);
-
-                    // Filter out <tool_call> XML blocks from display
-                    // Buffer content and only print text outside tool_call tags
-                    display_buf.push_str(&text);
-
-                    // Process display buffer: suppress tool_call blocks
-                    loop {
-                        if in_tool_tag {
-                            // We're inside a <tool_call> - look for closing tag
-                            if let Some(end_pos) = display_buf.find("</tool_call>") {
-                                let end = end_pos + "</tool_call>".len();
-                                // Extract the tool call text to show a clean summary
-                                let tool_xml = &display_buf[..end];
-                                if let Some(fname) = Self::extract_tool_name(tool_xml) {
-                                    print!("  {} {}...", "🔧".dimmed(), fname.bright_cyan());
-                                    io::stdout().flush().ok();
-                                }
-                                display_buf = display_buf[end..].to_string();
-                                in_tool_tag = false;
-                            } else {
-                                break; // Wait for more data
-                            }
-                        } else {
-                            // Look for start of <tool_call>
-                            if let Some(start_pos) = display_buf.find("<tool_call>") {
-                                // Print everything before the tag
-                                let before = &display_buf[..start_pos];
-                                if !before.is_empty() {
-                                    print!("{}", before);
-                                    io::stdout().flush().ok();
-                                }
-                                display_buf = display_buf[start_pos..].to_string();
-                                in_tool_tag = true;
-                            } else if display_buf.contains('<') && !display_buf.contains('>') {
-                                // Partial tag at end - buffer it
-                                break;
-                            } else {
-                                // No tags - print everything
-                                if !display_buf.is_empty() {
-                                    print!("{}", display_buf);
-                                    io::stdout().flush().ok();
-                                }
-                                display_buf.clear();
-                                break;
-                            }
-                        }
-                    }
+                    spinner.take();
+                    turn.content(&text)
                 }
                 StreamChunk::Reasoning(text) => {
                     // Stop spinner on first reasoning
-                    if let Some(s) = spinner.take() {
-                        drop(s);
-                    }
-                    if !output::is_compact() {
-                        if !in_reasoning {
-                            in_reasoning = true;
-                            output::thinking_prefix();
-                        }
-                        output::thinking(&text, true);
-                        io::stdout().flush().ok();
-                    }
-                    reasoning.push_str(&text);
+                    spinner.take();
+                    turn.reasoning(&text)
                 }
                 StreamChunk::ToolCall(call) => {
-                    tool_calls.push(call);
+                    turn.tool_calls.push(call);
+                    continue;
                 }
                 StreamChunk::Usage(u) => {
                     debug!(
@@ -161,42 +106,273 @@ impl Agent {
                         prompt_tokens: u.prompt_tokens as u64,
                         completion_tokens: u.completion_tokens as u64,
                     });
+                    continue;
                 }
                 StreamChunk::Done => break,
+            };
+            if !shown.is_empty() {
+                print!("{}", shown);
+                io::stdout().flush().ok();
             }
         }
 
-        // Flush any remaining display buffer (non-tool-call text)
-        if !display_buf.is_empty() && !in_tool_tag {
-            print!("{}", display_buf);
-            io::stdout().flush().ok();
-        }
-
-        // Ensure we end with a newline if we printed content
-        if !content.is_empty() || !reasoning.is_empty() {
-            println!();
-        }
+        print!("{}", turn.finish());
+        io::stdout().flush().ok();
 
         Ok((
-            content,
-            if reasoning.is_empty() {
+            turn.content,
+            if turn.reasoning.is_empty() {
                 None
             } else {
-                Some(reasoning)
+                Some(turn.reasoning)
             },
-            if tool_calls.is_empty() {
+            if turn.tool_calls.is_empty() {
                 None
             } else {
-                Some(tool_calls)
+                Some(turn.tool_calls)
             },
         ))
     }
 }
 
+/// Which kind of streamed text was printed last
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Segment {
+    None,
+    Content,
+    Reasoning,
+}
+
+/// One streamed reply: the accumulated content, reasoning and tool calls,
+/// and the terminal text to print as chunks arrive.
+///
+/// Backends may interleave `reasoning_content` and `content` deltas within a
+/// turn, so the display keys strictly on the chunk variant and each kind has
+/// its own buffer. Content held back (a partial tag or markdown line) stays
+/// in the content buffer while reasoning is printed, and each switch between
+/// the two starts a new line.
+pub(super) struct StreamTurn {
+    pub(super) content: String,
+    pub(super) reasoning: String,
+    pub(super) tool_calls: Vec<ToolCall>,
+    /// Content not yet printed, so `<tool_call>` blocks can be suppressed
+    display_buf: String,
+    in_tool_tag: bool,
+    markdown: output::MarkdownStreamRenderer,
+    segment: Segment,
+    /// Whether the last printed text ended its line
+    at_line_start: bool,
+    show_reasoning: bool,
+    color: bool,
+}
+
+impl StreamTurn {
+    pub(super) fn new(show_reasoning: bool, color: bool) -> Self {
+        Self {
+            content: String::new(),
+            reasoning: String::new(),
+            tool_calls: Vec::new(),
+            display_buf: String::new(),
+            in_tool_tag: false,
+            markdown: output::MarkdownStreamRenderer::with_color(color),
+            segment: Segment::None,
+            at_line_start: true,
+            show_reasoning,
+            color,
+        }
+    }
+
+    /// Add a content delta, returning what to print
+    pub(super) fn content(&mut self, text: &str) -> String {
+        // Always accumulate full content for parsing
+        self.content.push_str(text);
+        self.display_buf.push_str(text);
+
+        // Print text outside <tool_call> blocks, summarising the blocks
+        let mut visible = String::new();
+        loop {
+            if self.in_tool_tag {
+                let Some(end_pos) = self.display_buf.find("</tool_call>") else {
+                    break; // Wait for more data
+                };
+                let end = end_pos + "</tool_call>".len();
+                if let Some(fname) = Agent::extract_tool_name(&self.display_buf[..end]) {
+                    visible.push_str(&self.markdown.finish());
+                    visible.push_str(&format!(
+                        "  {} {}...",
+                        self.paint("🔧", |t| t.dimmed()),
+                        self.paint(&fname, |t| t.bright_cyan())
+                    ));
+                }
+                self.display_buf.drain(..end);
+                self.in_tool_tag = false;
+            } else if let Some(start_pos) = self.display_buf.find("<tool_call>") {
+                visible.push_str(&self.markdown.push(&self.display_buf[..start_pos]));
+                self.display_buf.drain(..start_pos);
+                self.in_tool_tag = true;
+            } else if self.display_buf.contains('<') && !self.display_buf.contains('>') {
+                break; // Partial tag at end - buffer it
+            } else {
+                visible.push_str(&self.markdown.push(&self.display_buf));
+                self.display_buf.clear();
+                break;
+            }
+        }
+        self.emit(Segment::Content, visible)
+    }
+
+    /// Add a reasoning delta, returning what to print
+    pub(super) fn reasoning(&mut self, text: &str) -> String {
+        self.reasoning.push_str(text);
+        if !self.show_reasoning || text.is_empty() {
+            return String::new();
+        }
+        let styled = if output::is_verbose() {
+            self.paint(text, |t| t.bright_black())
+        } else {
+            self.paint(text, |t| t.dimmed())
+        };
+        self.emit(Segment::Reasoning, styled)
+    }
+
+    /// Flush held-back content at the end of the stream, returning what to print
+    pub(super) fn finish(&mut self) -> String {
+        let mut rest = String::new();
+        if !self.in_tool_tag {
+            rest.push_str(&self.markdown.push(&self.display_buf));
+        }
+        self.display_buf.clear();
+        // Held-back markdown, including an unclosed code block, as plain text
+        rest.push_str(&self.markdown.finish());
+        let mut out = self.emit(Segment::Content, rest);
+        if !self.content.is_empty() || !self.reasoning.is_empty() {
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Text for `segment`, starting a new line when switching segments
+    fn emit(&mut self, segment: Segment, text: String) -> String {
+        if text.is_empty() {
+            return text;
+        }
+        let mut out = String::new();
+        if segment != self.segment {
+            if !self.at_line_start {
+                out.push('\n');
+            }
+            if segment == Segment::Reasoning {
+                out.push_str(&self.paint("Thinking:", |t| t.dimmed()));
+                out.push(' ');
+            }
+            self.segment = segment;
+        }
+        out.push_str(&text);
+        self.at_line_start = ends_line(&out);
+        out
+    }
+
+    fn paint(&self, text: &str, style: impl Fn(&str) -> ColoredString) -> String {
+        if self.color {
+            style(text).to_string()
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+/// Whether printed text ends its line, ignoring trailing style resets
+fn ends_line(text: &str) -> bool {
+    let mut text = text;
+    while let Some(pos) = text.rfind("\x1b[") {
+        let tail = &text[pos..];
+        if tail.ends_with('m') && !tail.contains('\n') {
+            text = &text[..pos];
+        } else {
+            break;
+        }
+    }
+    text.ends_with('\n')
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    // =========================================================================
+    // StreamTurn: interleaved content and reasoning
+    // =========================================================================
+
+    fn feed(turn: &mut StreamTurn, chunks: &[StreamChunk]) -> String {
+        let mut printed = String::new();
+        for chunk in chunks {
+            match chunk {
+                StreamChunk::Content(text) => printed.push_str(&turn.content(text)),
+                StreamChunk::Reasoning(text) => printed.push_str(&turn.reasoning(text)),
+                _ => {}
+            }
+        }
+        printed.push_str(&turn.finish());
+        printed
+    }
+
+    fn interleaved() -> Vec<StreamChunk> {
+        vec![
+            StreamChunk::Reasoning("Let me ".into()),
+            StreamChunk::Content("Hello ".into()),
+            StreamChunk::Reasoning("think.".into()),
+            StreamChunk::Content("world <".into()),
+            StreamChunk::Reasoning(" Still".into()),
+            StreamChunk::Content("b>".into()),
+            StreamChunk::Content("<tool_call><function=file_read>{}</function></tool_call>".into()),
+            StreamChunk::Reasoning(" thinking.".into()),
+            StreamChunk::Content("!\n".into()),
+        ]
+    }
+
+    #[test]
+    fn test_stream_turn_separates_interleaved_reasoning() {
+        let mut turn = StreamTurn::new(true, false);
+        let printed = feed(&mut turn, &interleaved());
+
+        assert_eq!(
+            turn.content,
+            "Hello world <b><tool_call><function=file_read>{}</function></tool_call>!\n"
+        );
+        assert_eq!(turn.reasoning, "Let me think. Still thinking.");
+        // "world <" waits for its tag to close, so " Still" continues the
+        // reasoning line instead of interrupting the content
+        assert_eq!(
+            printed,
+            "Thinking: Let me \n\
+             Hello \n\
+             Thinking: think. Still\n\
+             world <b>  🔧 file_read...\n\
+             Thinking:  thinking.\n\
+             !\n\n"
+        );
+        // Every printed line is wholly reasoning or wholly content
+        for line in printed.lines() {
+            if line.starts_with("Thinking:") {
+                assert!(!line.contains("Hello") && !line.contains("world"), "{line}");
+            } else {
+                assert!(
+                    !line.contains("think") && !line.contains("Let me"),
+                    "{line}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_stream_turn_hides_reasoning_in_compact_mode() {
+        let mut turn = StreamTurn::new(false, false);
+        let printed = feed(&mut turn, &interleaved());
+
+        assert_eq!(turn.reasoning, "Let me think. Still thinking.");
+        assert_eq!(printed, "Hello world <b>  🔧 file_read...!\n\n");
+    }
+
     // =========================================================================
     // extract_tool_name tests: <function=name> pattern
     // =========================================================================
@@ -0,0 +1,251 @@
+//! Structured summary of a finished task.
+//!
+//! [`Agent::run_task`] returns a [`TaskResult`] so that callers scripting the
+//! agent (`selfware --json run ...`) get the outcome as data instead of
+//! scraping the human-oriented terminal output.
+
+use super::task_summary::TaskSummary;
+use super::Agent;
+use crate::output;
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+/// Tools whose successful calls change files on disk.
+const FILE_MUTATING_TOOLS: &[&str] = &[
+    "file_write",
+    "file_edit",
+    "file_delete",
+    "apply_patch",
+    "symbol_rename",
+];
+
+/// How a task run ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskOutcome {
+    Completed,
+    Failed,
+    /// Stopped by Ctrl+C.
+    Interrupted,
+    /// Ran out of iterations before the model signalled completion.
+    Incomplete,
+    /// Stopped at a step boundary after `--max-runtime` elapsed.
+    TimedOut,
+    /// Paused because the model asked for input only the user has.
+    Paused,
+}
+
+/// An error logged while the task ran.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TaskError {
+    pub step: usize,
+    pub message: String,
+    /// False when the error ended the task.
+    pub recovered: bool,
+}
+
+/// Token usage reported by the API for this session.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TokenUsage {
+    pub prompt: u64,
+    pub completion: u64,
+    pub total: u64,
+    pub estimated_cost_usd: f64,
+}
+
+/// What a task run did, for `--json` output and other programmatic callers.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TaskResult {
+    pub task_id: Option<String>,
+    pub status: TaskOutcome,
+    pub steps: usize,
+    pub files_changed: Vec<String>,
+    pub errors: Vec<TaskError>,
+    pub tokens: TokenUsage,
+    /// Written when the task completes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<TaskSummary>,
+}
+
+impl TaskResult {
+    pub fn succeeded(&self) -> bool {
+        self.status == TaskOutcome::Completed
+    }
+
+    /// Why a `Failed` run failed: the last error it did not recover from
+    pub fn failure(&self) -> Option<&str> {
+        if self.status != TaskOutcome::Failed {
+            return None;
+        }
+        let last = self.errors.iter().rev().find(|e| !e.recovered);
+        Some(last.map_or("unknown error", |e| e.message.as_str()))
+    }
+
+    /// `Err` for a `Failed` run, for callers that report failure through
+    /// `Result` and the exit status
+    pub fn into_result(self) -> anyhow::Result<Self> {
+        match self.failure() {
+            Some(reason) => anyhow::bail!("Agent failed: {}", reason),
+            None => Ok(self),
+        }
+    }
+}
+
+impl Agent {
+    /// Summarise the current task from its checkpoint and the session counters.
+    pub fn task_result(&self, status: TaskOutcome) -> TaskResult {
+        let (prompt, completion) = output::get_total_tokens();
+        let mut files_changed = BTreeSet::new();
+        let mut errors = Vec::new();
+
+        if let Some(checkpoint) = &self.current_checkpoint {
+            for call in checkpoint.tool_calls.iter().filter(|c| c.success) {
+                if !FILE_MUTATING_TOOLS.contains(&call.tool_name.as_str()) {
+                    continue;
+                }
+                let Ok(args) = serde_json::from_str::<serde_json::Value>(&call.arguments) else {
+                    continue;
+                };
+                if call.tool_name == "apply_patch" {
+                    for path in crate::tools::patch::patch_targets(&args) {
+                        files_changed.insert(path.display().to_string());
+                    }
+                } else if call.tool_name == "symbol_rename" {
+                    let result = call.result.as_deref().unwrap_or_default();
+                    for path in crate::tools::refactor::renamed_files(result) {
+                        files_changed.insert(path.display().to_string());
+                    }
+                } else if let Some(path) = args.get("path").and_then(|v| v.as_str()) {
+                    files_changed.insert(path.to_string());
+                }
+            }
+            errors = checkpoint
+                .errors
+                .iter()
+                .map(|e| TaskError {
+                    step: e.step,
+                    message: e.error.clone(),
+                    recovered: e.recovered,
+                })
+                .collect();
+        }
+        // Without a checkpoint the reason is only in `last_failure`
+        if let (TaskOutcome::Failed, Some(failure)) = (status, &self.last_failure) {
+            if !errors
+                .iter()
+                .any(|e| !e.recovered && e.message == failure.reason)
+            {
+                errors.push(TaskError {
+                    step: failure.step,
+                    message: failure.reason.clone(),
+                    recovered: false,
+                });
+            }
+        }
+
+        TaskResult {
+            task_id: self.current_checkpoint.as_ref().map(|c| c.task_id.clone()),
+            status,
+            steps: self.loop_control.current_step(),
+            files_changed: files_changed.into_iter().collect(),
+            errors,
+            tokens: TokenUsage {
+                prompt,
+                completion,
+                total: prompt + completion,
+                estimated_cost_usd: output::estimate_cost_usd(prompt, completion),
+            },
+            summary: self
+                .current_checkpoint
+                .as_ref()
+                .and_then(|c| c.summary.clone()),
+        }
+    }
+
+    /// Result for a run that returned `err`, recording the error unless the
+    /// checkpoint already logged it.
+    pub fn failed_task_result(&self, err: &anyhow::Error) -> TaskResult {
+        let mut result = self.task_result(TaskOutcome::Failed);
+        let message = format!("{:#}", err);
+        let logged = result
+            .errors
+            .iter()
+            .any(|e| !e.recovered && message.contains(&e.message));
+        if !logged {
+            result.errors.push(TaskError {
+                step: result.steps,
+                message,
+                recovered: false,
+            });
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checkpoint::{TaskCheckpoint, ToolCallLog};
+    use crate::testing::mock_api::MockLlmServer;
+
+    fn logged_call(tool: &str, arguments: serde_json::Value, success: bool) -> ToolCallLog {
+        ToolCallLog {
+            timestamp: chrono::Utc::now(),
+            tool_name: tool.to_string(),
+            arguments: arguments.to_string(),
+            result: None,
+            success,
+            duration_ms: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_task_result_collects_changed_files_and_errors() {
+        let server = MockLlmServer::builder().build().await;
+        let config = crate::config::Config {
+            endpoint: format!("{}/v1", server.url()),
+            ..Default::default()
+        };
+        let mut agent = Agent::new(config).await.unwrap();
+
+        let mut checkpoint = TaskCheckpoint::new("task-1".into(), "demo".into());
+        let patch = "--- a/src/b.rs\n+++ b/src/b.rs\n@@ -1 +1 @@\n-old\n+new\n";
+        checkpoint.log_tool_call(logged_call(
+            "file_write",
+            serde_json::json!({"path": "src/a.rs", "content": "x"}),
+            true,
+        ));
+        checkpoint.log_tool_call(logged_call(
+            "apply_patch",
+            serde_json::json!({"patch": patch}),
+            true,
+        ));
+        checkpoint.log_tool_call(logged_call(
+            "file_edit",
+            serde_json::json!({"path": "src/failed.rs"}),
+            false,
+        ));
+        checkpoint.log_tool_call(logged_call(
+            "file_read",
+            serde_json::json!({"path": "src/read.rs"}),
+            true,
+        ));
+        checkpoint.log_error(2, "tests failed".into(), true);
+        agent.current_checkpoint = Some(checkpoint);
+
+        let result = agent.task_result(TaskOutcome::Completed);
+        assert_eq!(result.task_id.as_deref(), Some("task-1"));
+        assert_eq!(result.files_changed, vec!["src/a.rs", "src/b.rs"]);
+        assert_eq!(result.errors.len(), 1);
+        assert!(result.succeeded());
+
+        let failed = agent.failed_task_result(&anyhow::anyhow!("API unreachable"));
+        assert_eq!(failed.status, TaskOutcome::Failed);
+        assert_eq!(failed.errors.last().unwrap().message, "API unreachable");
+
+        let json = serde_json::to_value(&failed).unwrap();
+        assert_eq!(json["status"], "failed");
+        assert!(json["tokens"]["total"].is_u64());
+        server.stop().await;
+    }
+}
@@ -1,33 +1,142 @@
 use anyhow::{Context, Result};
 use colored::*;
-use tracing::warn;
+use tracing::{warn, Instrument};
 
 use super::*;
 
+use super::planning::PlanningContext;
+use super::task_result::{TaskOutcome, TaskResult};
 use super::tui_events::AgentEvent;
+use crate::orchestration::swarm::{
+    AgentRole, BlackboardArtifact, Changelist, DesignDoc, ReviewNotes, SwarmBlackboard, TestReport,
+};
+use crate::ui::animations::format_duration;
 
 impl Agent {
-    pub async fn run_task(&mut self, task: &str) -> Result<()> {
-        // Reset loop state so queued tasks don't inherit the previous
-        // task's iteration counter and hit the max-iterations limit.
-        self.loop_control.reset_for_task();
-        let task_description = task.to_string();
+    /// Run `task` until the model signals completion, the iteration budget
+    /// runs out or the user interrupts, and summarise what happened. A task
+    /// the agent gives up on ends as [`TaskOutcome::Failed`];
+    /// [`TaskResult::into_result`] turns that into an error.
+    pub async fn run_task(&mut self, task: &str) -> Result<TaskResult> {
+        let started = Instant::now();
+        match self.execute_task(task).await {
+            Ok(outcome) => {
+                if outcome == TaskOutcome::Completed {
+                    self.record_task_summary().await;
+                }
+                let result = self.task_result(outcome);
+                self.notify_task_end(outcome, started.elapsed(), None).await;
+                Ok(result)
+            }
+            Err(e) => {
+                let reason = format!("{:#}", e);
+                self.notify_task_end(TaskOutcome::Failed, started.elapsed(), Some(&reason))
+                    .await;
+                Err(e)
+            }
+        }
+    }
 
-        let cancel_token = self.cancel_token();
-        let ctrl_c_handle = tokio::spawn(async move {
-            if let Ok(()) = tokio::signal::ctrl_c().await {
-                cancel_token.store(true, std::sync::atomic::Ordering::Relaxed);
-                println!("\n🦊 Received shutdown signal. Gracefully stopping agent and saving checkpoint...");
+    /// Fire the `[notifications]` hooks for a task that just ended
+    async fn notify_task_end(
+        &self,
+        outcome: TaskOutcome,
+        elapsed: std::time::Duration,
+        error: Option<&str>,
+    ) {
+        use crate::config::NotificationEvent;
+        use crate::observability::notifications::TaskNotification;
+
+        let event = match outcome {
+            TaskOutcome::Completed => NotificationEvent::Completed,
+            TaskOutcome::Failed | TaskOutcome::Incomplete => NotificationEvent::Failed,
+            TaskOutcome::Interrupted | TaskOutcome::TimedOut | TaskOutcome::Paused => {
+                NotificationEvent::Paused
             }
-        });
+        };
+        let summary = match (error, &self.last_failure) {
+            (Some(error), _) => error,
+            (None, Some(failure)) if event == NotificationEvent::Failed => &failure.reason,
+            _ => self.last_assistant_response.as_str(),
+        };
+        let status = serde_json::to_value(outcome)
+            .ok()
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_default();
+        let task_id = self
+            .current_checkpoint
+            .as_ref()
+            .map(|c| c.task_id.as_str())
+            .unwrap_or_default();
+        let notification = TaskNotification::new(task_id, event, &status, elapsed, summary);
+        self.notifier.notify(&notification).await;
+    }
 
-        struct AbortOnDrop(tokio::task::JoinHandle<()>);
-        impl Drop for AbortOnDrop {
-            fn drop(&mut self) {
-                self.0.abort();
+    /// Settle an ambiguous task before planning. At a terminal the user is
+    /// asked; otherwise the task proceeds on stated assumptions, which are
+    /// recorded in the checkpoint. Returns the task message to send.
+    fn clarify_task(&mut self, task: &str) -> String {
+        let context = PlanningContext {
+            files: &self.context_files,
+            has_history: self.messages.iter().any(|m| m.role == "user"),
+        };
+        let Some(questions) = Planner::needs_clarification(task, &context) else {
+            return task.to_string();
+        };
+
+        if self.is_interactive() {
+            use std::io::Write;
+            let mut answers = Vec::new();
+            for question in &questions {
+                println!("{} {}", "❓".bright_yellow(), question.text.bright_white());
+                print!("{}", "   (Enter to let me decide) > ".dimmed());
+                std::io::stdout().flush().ok();
+                let mut answer = String::new();
+                if std::io::stdin().read_line(&mut answer).is_err() {
+                    break;
+                }
+                let answer = answer.trim();
+                let answer = if answer.is_empty() {
+                    question.assumption.as_str()
+                } else {
+                    answer
+                };
+                answers.push(format!("- {} {}", question.text, answer));
+            }
+            if !answers.is_empty() {
+                return format!("{}\n\nClarifications:\n{}", task, answers.join("\n"));
             }
         }
-        let _ctrl_c_guard = AbortOnDrop(ctrl_c_handle);
+
+        let assumptions: Vec<String> = questions.into_iter().map(|q| q.assumption).collect();
+        println!(
+            "{} Task is ambiguous; proceeding on assumptions:",
+            "📝".bright_yellow()
+        );
+        for assumption in &assumptions {
+            println!("   - {}", assumption.dimmed());
+        }
+        info!("Proceeding on assumptions: {:?}", assumptions);
+        let message = format!(
+            "{}\n\nAssumptions (state them in your plan):\n- {}",
+            task,
+            assumptions.join("\n- ")
+        );
+        if let Some(checkpoint) = self.current_checkpoint.as_mut() {
+            checkpoint.set_assumptions(assumptions);
+        }
+        message
+    }
+
+    async fn execute_task(&mut self, task: &str) -> Result<TaskOutcome> {
+        // Reset loop state so queued tasks don't inherit the previous
+        // task's iteration counter and hit the max-iterations limit.
+        self.loop_control.reset_for_task();
+        self.last_failure = None;
+        self.verification_gate.reset_baselines();
+        let task_description = task.to_string();
+
+        let _interrupt_watch = self.watch_interrupts();
 
         self.emit_event(AgentEvent::Started);
         self.emit_event(AgentEvent::Status {
@@ -38,7 +147,8 @@ impl Agent {
         println!("Task: {}", task.bright_white());
 
         // Initialize checkpoint if not resuming
-        if self.current_checkpoint.is_none() {
+        let resuming = self.current_checkpoint.is_some();
+        if !resuming {
             let task_id = uuid::Uuid::new_v4().to_string();
             self.current_checkpoint = Some(TaskCheckpoint::new(task_id, task.to_string()));
         }
@@ -48,6 +158,15 @@ impl Agent {
             .map(|c| c.task_id.clone())
             .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
         self.start_learning_session(&learning_session_id, &task_description);
+        let assessment = self.assess_task(&task_description);
+        if output::is_verbose() {
+            println!(
+                "{} Difficulty: {} (~{:.0} steps)",
+                "🧭".bright_cyan(),
+                assessment.difficulty,
+                assessment.expected_steps
+            );
+        }
         self.cognitive_state.upsert_strategic_goal(
             "strategic-agent-reliability",
             "Improve long-term autonomous task reliability and production readiness",
@@ -68,7 +187,13 @@ impl Agent {
             ],
         );
 
-        let msg = Message::user(task);
+        let task_message = if resuming {
+            task.to_string()
+        } else {
+            self.clarify_task(task)
+        };
+        self.begin_turn(&task_message);
+        let msg = self.user_message_with_images(task_message);
         self.memory.add_message(&msg);
         self.messages.push(msg);
 
@@ -90,6 +215,10 @@ impl Agent {
             // the token budget.
             self.trim_message_history();
 
+            if let Some(outcome) = self.handle_pause_request(&task_description) {
+                return Ok(outcome);
+            }
+
             if self.is_cancelled() {
                 println!("{}", "\n⚡ Interrupted".bright_yellow());
                 self.messages
@@ -99,12 +228,19 @@ impl Agent {
                     Outcome::Abandoned,
                     Some("Task interrupted by user"),
                 );
-                return Ok(());
+                return Ok(TaskOutcome::Interrupted);
+            }
+
+            if let Some(used) = self.loop_control.runtime_exceeded() {
+                self.stop_at_runtime_limit(&task_description, used);
+                return Ok(TaskOutcome::TimedOut);
             }
 
+            self.wait_out_resource_pressure(&task_description).await;
+
             match state {
                 AgentState::Planning => {
-                    let _span = enter_agent_step("Planning", 0);
+                    let span = enter_agent_step("Planning", 0);
                     record_state_transition("Start", "Planning");
                     output::phase_transition("Start", "Planning");
 
@@ -116,7 +252,7 @@ impl Agent {
                     self.cognitive_state.set_phase(CyclePhase::Plan);
 
                     // Plan returns true if the response contains tool calls
-                    let has_tool_calls = match self.plan().await {
+                    let has_tool_calls = match self.plan().instrument(span.clone()).await {
                         Ok(has_tool_calls) => has_tool_calls,
                         Err(e) => {
                             self.emit_event(AgentEvent::Error {
@@ -168,7 +304,7 @@ impl Agent {
                                     if let Err(e) = self.complete_checkpoint() {
                                         warn!("Failed to save completed checkpoint: {}", e);
                                     }
-                                    return Ok(());
+                                    return Ok(TaskOutcome::Completed);
                                 }
                                 #[cfg(feature = "resilience")]
                                 {
@@ -213,7 +349,7 @@ impl Agent {
                     }
                 }
                 AgentState::Executing { step } => {
-                    let _span = enter_agent_step("Executing", step);
+                    let span = enter_agent_step("Executing", step);
                     output::step_start(step + 1, "Executing");
                     if let Some(task_id) =
                         self.current_checkpoint.as_ref().map(|c| c.task_id.clone())
@@ -224,6 +360,7 @@ impl Agent {
                             &format!("Execution step {}", step + 1),
                         );
                     }
+                    self.emit_run_stats();
                     // Inject periodic progress/budget-awareness messages
                     if let Some(progress_msg) = self.build_progress_injection(step) {
                         self.messages.push(Message::system(progress_msg));
@@ -231,7 +368,11 @@ impl Agent {
                     // Update progress based on step
                     let step_progress = ((step + 1) as f64 * 0.1).min(0.9);
                     progress.update_progress(step_progress);
-                    match self.execute_step_with_logging(&task_description).await {
+                    match self
+                        .execute_step_with_logging(&task_description)
+                        .instrument(span.clone())
+                        .await
+                    {
                         Ok(completed) => {
                             if self.is_cancelled() {
                                 continue;
@@ -249,7 +390,7 @@ impl Agent {
                                 if let Err(e) = self.complete_checkpoint() {
                                     warn!("Failed to save completed checkpoint: {}", e);
                                 }
-                                return Ok(());
+                                return Ok(TaskOutcome::Completed);
                             }
                             self.loop_control.increment_step();
                             self.reflect_on_step(step + 1).await;
@@ -354,7 +495,12 @@ impl Agent {
                     if let Err(e) = self.complete_checkpoint() {
                         warn!("Failed to save completed checkpoint: {}", e);
                     }
-                    return Ok(());
+                    return Ok(TaskOutcome::Completed);
+                }
+                AgentState::Paused { reason } => {
+                    if !self.resolve_pause(&task_description, &reason) {
+                        return Ok(TaskOutcome::Paused);
+                    }
                 }
                 AgentState::Failed { reason } => {
                     record_state_transition("Executing", "Failed");
@@ -369,7 +515,7 @@ impl Agent {
                     if let Err(e) = self.fail_checkpoint(&reason) {
                         warn!("Failed to save failed checkpoint: {}", e);
                     }
-                    anyhow::bail!("Agent failed: {}", reason);
+                    return Ok(TaskOutcome::Failed);
                 }
             }
 
@@ -382,13 +528,146 @@ impl Agent {
             Outcome::Partial,
             Some("Execution stopped before completion"),
         );
-        Ok(())
+        Ok(TaskOutcome::Incomplete)
+    }
+
+    /// End the task at a step boundary because `--max-runtime` ran out,
+    /// leaving a paused checkpoint to resume from.
+    fn stop_at_runtime_limit(&mut self, task_description: &str, used: std::time::Duration) {
+        let limit = self.loop_control.runtime_limit().unwrap_or_default();
+        println!(
+            "{} Max runtime of {} reached after {}; stopping before step {}.",
+            "\n⏱️".bright_yellow(),
+            format_duration(limit),
+            format_duration(used),
+            self.loop_control.current_step() + 1
+        );
+        self.messages
+            .push(Message::user("[Task stopped: max runtime reached]"));
+        self.record_task_outcome(
+            task_description,
+            Outcome::Partial,
+            Some("Max runtime exceeded"),
+        );
+        self.save_paused(task_description);
+    }
+
+    /// Sample host memory/CPU and, while it is past the `[resources.pause]`
+    /// thresholds, checkpoint and wait instead of starting another step that
+    /// could get the process OOM-killed mid-edit.
+    async fn wait_out_resource_pressure(&mut self, task_description: &str) {
+        let mut sample = self.pressure.sample();
+        if !sample.pause {
+            return;
+        }
+        let config = self.pressure.config().clone();
+        let started = std::time::Instant::now();
+        warn!(
+            memory = sample.memory_ratio,
+            cpu = sample.cpu_ratio,
+            "Host resources critical, pausing agent loop"
+        );
+        if let Err(e) = self.save_checkpoint(task_description) {
+            warn!("Failed to save checkpoint: {}", e);
+        }
+        while sample.pause && !self.is_cancelled() {
+            if config.max_pause_secs > 0 && started.elapsed().as_secs() >= config.max_pause_secs {
+                println!(
+                    "{} Resources still critical after {}; continuing anyway.",
+                    "⚠️".bright_yellow(),
+                    format_duration(started.elapsed())
+                );
+                return;
+            }
+            let message = format!(
+                "Resources critical ({}); pausing {}s before the next step",
+                sample.label().unwrap_or_default(),
+                config.pause_secs
+            );
+            println!("{} {}", "⏸".bright_yellow(), message);
+            self.emit_event(AgentEvent::Status { message });
+            for _ in 0..config.pause_secs {
+                if self.is_cancelled() {
+                    return;
+                }
+                tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+            }
+            sample = self.pressure.sample();
+        }
+        if !sample.pause {
+            println!(
+                "{} Resources recovered after {}; resuming.",
+                "▶".bright_green(),
+                format_duration(started.elapsed())
+            );
+        }
+    }
+
+    /// Handle a task paused for input. At a terminal the user can answer and
+    /// the task carries on; returns false when it stays paused.
+    fn resolve_pause(&mut self, task_description: &str, request: &str) -> bool {
+        println!(
+            "{} {}",
+            "\n🙋 Input needed:".bright_yellow(),
+            request.bright_white()
+        );
+        if let Some(answer) = self.ask_for_input() {
+            record_state_transition("Paused", "Executing");
+            self.messages.push(Message::user(answer));
+            self.loop_control.set_state(AgentState::Executing {
+                step: self.loop_control.current_step(),
+            });
+            return true;
+        }
+
+        record_state_transition("Executing", "Paused");
+        self.messages
+            .push(Message::user("[Task paused: waiting for user input]"));
+        self.record_task_outcome(
+            task_description,
+            Outcome::Partial,
+            Some("Paused for user input"),
+        );
+        self.save_paused(task_description);
+        false
+    }
+
+    /// Read an answer at a terminal; `None` without one or on an empty line.
+    fn ask_for_input(&self) -> Option<String> {
+        use std::io::Write;
+        if !self.is_interactive() {
+            return None;
+        }
+        print!("{}", "   (answer, or Enter to pause) > ".dimmed());
+        std::io::stdout().flush().ok();
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer).ok()?;
+        let answer = answer.trim();
+        (!answer.is_empty()).then(|| answer.to_string())
+    }
+
+    /// Save the task as paused and say how to pick it up again.
+    pub(super) fn save_paused(&mut self, task_description: &str) {
+        match self.pause_checkpoint(task_description) {
+            Ok(()) => {
+                if let Some(checkpoint) = &self.current_checkpoint {
+                    println!(
+                        "   Progress saved. Continue with: selfware resume {}",
+                        checkpoint.task_id
+                    );
+                }
+            }
+            Err(e) => warn!("Failed to save paused checkpoint: {}", e),
+        }
     }
 
     pub(super) async fn run_swarm_task(&mut self, task: &str) -> Result<()> {
-        use crate::orchestration::swarm::{create_dev_swarm, AgentRole, SwarmTask};
+        use crate::orchestration::swarm::{create_dev_swarm, Swarm, SwarmTask};
 
         let mut swarm = create_dev_swarm();
+        let trust_path = Swarm::default_trust_path();
+        swarm.load_trust(&trust_path);
+        let blackboard = swarm.blackboard();
         let mut agents = swarm.list_agents();
         agents.sort_by_key(|a| std::cmp::Reverse(a.role.priority()));
 
@@ -399,10 +678,11 @@ impl Agent {
         );
         for agent in &agents {
             println!(
-                "  {} {} ({})",
+                "  {} {} ({}, trust {:.0}%)",
                 "→".bright_black(),
                 agent.name.bright_white(),
-                agent.role.name().dimmed()
+                agent.role.name().dimmed(),
+                agent.trust_score * 100.0
             );
         }
 
@@ -448,12 +728,13 @@ impl Agent {
 
         // Process tasks from the swarm queue in priority order.
         // Each phase uses the specialist agent's system prompt to guide
-        // the LLM, then records the result back into the swarm.
+        // the LLM, reads its inputs from the blackboard, then records the
+        // result back into the swarm and its artifact onto the blackboard.
         let mut phase_num = 0usize;
-        while let Some(sub_task) = swarm.next_task() {
+        while let Some(mut sub_task) = swarm.next_task() {
             phase_num += 1;
             let task_id = sub_task.id.clone();
-            let assigned = swarm.assign_task(&task_id);
+            let assigned = swarm.assign_by_trust(&mut sub_task);
 
             // Determine the lead agent for this sub-task
             let lead_agent_prompt = if let Some(agent_id) = assigned.first() {
@@ -470,11 +751,14 @@ impl Agent {
                     .unwrap_or_default()
             };
 
-            let role_name = sub_task
-                .required_roles
+            let role = sub_task.required_roles.first().copied().unwrap_or_default();
+            let role_name = role.name();
+            let author = assigned
                 .first()
-                .map(|r| r.name())
-                .unwrap_or("General");
+                .and_then(|id| swarm.get_agent(id))
+                .or_else(|| swarm.agents_by_role(role).first().copied())
+                .map(|a| a.name.clone())
+                .unwrap_or_else(|| role_name.to_string());
 
             println!(
                 "\n{} Phase {}/{}: {} ({})",
@@ -485,27 +769,75 @@ impl Agent {
                 role_name.bright_yellow()
             );
 
-            // Build a role-specific prompt that includes specialist guidance
-            let role_prompt = format!(
-                "{}\n\n\
-                 You are acting as the {} in a development swarm.\n\
-                 Previous phases have already contributed to the conversation context.\n\
-                 Focus specifically on your role's responsibilities.\n\
-                 After completing your work, verify with cargo_check if you made code changes.\n\n\
-                 Task: {}",
-                lead_agent_prompt, role_name, sub_task.description
+            // Inputs come from the blackboard, so a retry sees exactly what
+            // the first attempt saw
+            let inputs = blackboard
+                .read()
+                .unwrap_or_else(|e| e.into_inner())
+                .context_for(role);
+            let role_prompt = swarm_phase_prompt(
+                &lead_agent_prompt,
+                role,
+                &sub_task.description,
+                inputs.as_deref(),
             );
 
-            let result = self.run_task(&role_prompt).await;
+            let mut attempt = 0;
+            let result = loop {
+                attempt += 1;
+                let result = self.run_task(&role_prompt).await;
+                let settled = match &result {
+                    Ok(r) => r.succeeded() || r.status == TaskOutcome::Interrupted,
+                    Err(_) => self.is_cancelled(),
+                };
+                if settled || attempt > SWARM_PHASE_RETRIES {
+                    break result;
+                }
+                println!(
+                    "{} {} phase failed; retrying with the same inputs",
+                    "🔁".bright_yellow(),
+                    role_name
+                );
+            };
 
             // Record completion back in the swarm
             let (success, result_msg) = match &result {
-                Ok(()) => (true, "Phase completed successfully".to_string()),
+                Ok(r) if r.succeeded() => (true, "Phase completed successfully".to_string()),
+                Ok(r) => (false, format!("Phase ended as {:?}", r.status)),
                 Err(e) => (false, e.to_string()),
             };
 
+            // An interrupted phase says nothing about how reliable its
+            // agents are, so it leaves their trust untouched
+            let interrupted = matches!(&result, Ok(r) if r.status == TaskOutcome::Interrupted);
             for agent_id in &assigned {
-                swarm.complete_task(&task_id, agent_id, &result_msg);
+                if interrupted {
+                    if let Some(agent) = swarm.get_agent_mut(agent_id) {
+                        agent.set_idle();
+                    }
+                } else {
+                    swarm.complete_task(&task_id, agent_id, &result_msg, success);
+                }
+            }
+
+            if let Ok(task_result) = &result {
+                let written = record_phase_artifact(
+                    &mut blackboard.write().unwrap_or_else(|e| e.into_inner()),
+                    role,
+                    &author,
+                    task_result,
+                    &self.last_assistant_response,
+                );
+                match written {
+                    Ok(Some(kind)) => println!(
+                        "{} {} wrote {} to the blackboard",
+                        "📋".bright_cyan(),
+                        author,
+                        kind
+                    ),
+                    Ok(None) => {}
+                    Err(e) => warn!("Failed to record {} artifact: {}", role_name, e),
+                }
             }
 
             if !success {
@@ -516,6 +848,10 @@ impl Agent {
             }
         }
 
+        if let Err(e) = swarm.save_trust(&trust_path) {
+            warn!("Failed to save swarm trust: {}", e);
+        }
+
         // Print swarm statistics
         let stats = swarm.stats();
         println!(
@@ -524,6 +860,19 @@ impl Agent {
             stats.total_agents,
             stats.average_trust * 100.0
         );
+        let mut agents = swarm.list_agents();
+        agents.sort_by_key(|a| std::cmp::Reverse(a.role.priority()));
+        for agent in agents {
+            println!(
+                "  {} {} ({}): trust {:.0}%, {}/{} phases passed",
+                "→".bright_black(),
+                agent.name.bright_white(),
+                agent.role.name().dimmed(),
+                agent.trust_score * 100.0,
+                agent.tasks_completed,
+                agent.tasks_completed + agent.tasks_failed
+            );
+        }
 
         Ok(())
     }
@@ -578,7 +927,7 @@ impl Agent {
         ))
     }
 
-    pub async fn analyze(&mut self, path: &str) -> Result<()> {
+    pub async fn analyze(&mut self, path: &str) -> Result<TaskResult> {
         let task = Planner::analyze_prompt(path);
         self.run_task(&task).await
     }
@@ -591,7 +940,7 @@ impl Agent {
             .with_context(|| format!("Failed to read file: {}", file_path))?;
 
         let task = Planner::review_prompt(file_path, &content);
-        self.run_task(&task).await
+        self.run_task(&task).await?.into_result().map(|_| ())
     }
 
     /// Get memory statistics
@@ -617,6 +966,13 @@ impl Agent {
         manager.load(task_id)
     }
 
+    /// Fork a saved task at a step into a new task, returning the new ID
+    pub fn fork_task(task_id: &str, at_step: usize) -> Result<String> {
+        let manager =
+            CheckpointManager::default_path().context("Failed to initialize checkpoint manager")?;
+        manager.fork(task_id, at_step)
+    }
+
     /// Delete a saved task
     pub fn delete_task(task_id: &str) -> Result<()> {
         let manager =
@@ -624,6 +980,39 @@ impl Agent {
         manager.delete(task_id)
     }
 
+    /// Re-enter `Executing` for the step the last task failed on, keeping the
+    /// conversation as is and adding the user's `hint` as guidance.
+    ///
+    /// The retry starts with a fresh iteration budget and does not go through
+    /// error recovery, so it never counts toward recovery backoff. Returns
+    /// `Ok(false)` when there is no failure to retry.
+    pub async fn retry_last_failure(&mut self, hint: Option<&str>) -> Result<bool> {
+        let Some(failure) = self.last_failure.take() else {
+            return Ok(false);
+        };
+
+        let mut guidance = format!(
+            "[Retry requested by user] Step {} failed: {}",
+            failure.step + 1,
+            failure.reason
+        );
+        if let Some(hint) = hint.map(str::trim).filter(|h| !h.is_empty()) {
+            guidance.push_str(&format!("\nGuidance from the user: {}", hint));
+        }
+        guidance.push_str("\nRetry this step, building on the work so far.");
+        let msg = Message::user(guidance);
+        self.memory.add_message(&msg);
+        self.messages.push(msg);
+
+        if let Some(ref mut checkpoint) = self.current_checkpoint {
+            checkpoint.set_status(crate::checkpoint::TaskStatus::InProgress);
+        }
+        record_state_transition("Failed", "Executing");
+        self.loop_control.restore_progress(failure.step, 0);
+        self.continue_execution().await?;
+        Ok(true)
+    }
+
     /// Continue execution from current state (for resuming tasks)
     pub async fn continue_execution(&mut self) -> Result<()> {
         let task_description = self
@@ -660,6 +1049,8 @@ impl Agent {
         #[cfg(feature = "resilience")]
         let mut recovery_attempts = 0u32;
 
+        let _interrupt_watch = self.watch_interrupts();
+
         while let Some(state) = self.loop_control.next_state() {
             // Inject a system warning when approaching the iteration limit so
             // the LLM can wrap up gracefully instead of being cut off.
@@ -671,6 +1062,10 @@ impl Agent {
             // the token budget.
             self.trim_message_history();
 
+            if self.handle_pause_request(&task_description).is_some() {
+                return Ok(());
+            }
+
             if self.is_cancelled() {
                 println!("{}", "\n⚡ Interrupted".bright_yellow());
                 self.messages
@@ -683,14 +1078,19 @@ impl Agent {
                 return Ok(());
             }
 
+            if let Some(used) = self.loop_control.runtime_exceeded() {
+                self.stop_at_runtime_limit(&task_description, used);
+                return Ok(());
+            }
+
             match state {
                 AgentState::Planning => {
-                    let _span = enter_agent_step("Planning", 0);
+                    let span = enter_agent_step("Planning", 0);
                     record_state_transition("Resume", "Planning");
                     println!("{}", "📋 Planning...".bright_yellow());
                     self.cognitive_state.set_phase(CyclePhase::Plan);
 
-                    if let Err(e) = self.plan().await {
+                    if let Err(e) = self.plan().instrument(span.clone()).await {
                         self.record_task_outcome(
                             &task_description,
                             Outcome::Failure,
@@ -710,7 +1110,7 @@ impl Agent {
                     }
                 }
                 AgentState::Executing { step } => {
-                    let _span = enter_agent_step("Executing", step);
+                    let span = enter_agent_step("Executing", step);
                     println!(
                         "{} Executing...",
                         format!("📝 Step {}", step + 1).bright_blue()
@@ -724,7 +1124,12 @@ impl Agent {
                             &format!("Execution step {}", step + 1),
                         );
                     }
-                    match self.execute_step_with_logging(&task_description).await {
+                    self.emit_run_stats();
+                    match self
+                        .execute_step_with_logging(&task_description)
+                        .instrument(span.clone())
+                        .await
+                    {
                         Ok(completed) => {
                             if self.is_cancelled() {
                                 continue;
@@ -834,6 +1239,11 @@ impl Agent {
                     }
                     return Ok(());
                 }
+                AgentState::Paused { reason } => {
+                    if !self.resolve_pause(&task_description, &reason) {
+                        return Ok(());
+                    }
+                }
                 AgentState::Failed { reason } => {
                     record_state_transition("Executing", "Failed");
                     println!("{} {}", "❌ Task failed:".bright_red(), reason);
@@ -857,6 +1267,93 @@ impl Agent {
     }
 }
 
+/// Extra attempts a failed swarm phase gets before the swarm moves on
+const SWARM_PHASE_RETRIES: usize = 1;
+
+/// Prompt for one swarm phase, with the blackboard artifacts it reads
+fn swarm_phase_prompt(
+    lead_agent_prompt: &str,
+    role: AgentRole,
+    description: &str,
+    inputs: Option<&str>,
+) -> String {
+    let mut prompt = format!(
+        "{}\n\nYou are acting as the {} in a development swarm.\n",
+        lead_agent_prompt,
+        role.name()
+    );
+    if let Some(inputs) = inputs {
+        prompt.push_str(&format!(
+            "Earlier phases left these artifacts on the swarm blackboard. \
+             Treat them as your inputs:\n\n{}\n\n",
+            inputs
+        ));
+    }
+    prompt.push_str(
+        "Focus specifically on your role's responsibilities.\n\
+         After completing your work, verify with cargo_check if you made code changes.\n",
+    );
+    let deliverable = match role {
+        AgentRole::Architect => Some("a design doc: the approach, the files to change and why"),
+        AgentRole::Coder => Some("a summary of the changes you made"),
+        AgentRole::Tester => Some("a test report: what you ran and whether it passed"),
+        AgentRole::Reviewer => Some("your review notes"),
+        _ => None,
+    };
+    if let Some(deliverable) = deliverable {
+        prompt.push_str(&format!(
+            "End your final answer with {}; it is handed to the next phase.\n",
+            deliverable
+        ));
+    }
+    prompt.push_str(&format!("\nTask: {}", description));
+    prompt
+}
+
+/// Write what a finished phase produced to the blackboard, returning the
+/// artifact kind. Only a successful phase hands work on, except the Tester,
+/// whose failures are exactly what the Reviewer needs to see.
+fn record_phase_artifact(
+    blackboard: &mut SwarmBlackboard,
+    role: AgentRole,
+    author: &str,
+    result: &TaskResult,
+    response: &str,
+) -> Result<Option<&'static str>> {
+    if !result.succeeded() && role != AgentRole::Tester {
+        return Ok(None);
+    }
+    let summary = response.to_string();
+    let kind = match role {
+        AgentRole::Architect => {
+            blackboard.put(&DesignDoc { content: summary }, author)?;
+            DesignDoc::KIND
+        }
+        AgentRole::Coder => {
+            let changes = Changelist {
+                files: result.files_changed.clone(),
+                summary,
+            };
+            blackboard.put(&changes, author)?;
+            Changelist::KIND
+        }
+        AgentRole::Tester => {
+            let report = TestReport {
+                passed: result.succeeded(),
+                summary,
+            };
+            blackboard.put(&report, author)?;
+            TestReport::KIND
+        }
+        AgentRole::Reviewer => {
+            blackboard.put(&ReviewNotes { content: summary }, author)?;
+            ReviewNotes::KIND
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(kind))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1369,6 +1866,101 @@ mod tests {
         server.stop().await;
     }
 
+    #[tokio::test]
+    #[cfg_attr(
+        target_os = "windows",
+        ignore = "mock TCP server unreliable on Windows CI"
+    )]
+    async fn test_run_task_returns_result_for_checkpoint() {
+        let server = MockLlmServer::builder()
+            .with_response("Plan.")
+            .with_response("Done.")
+            .build()
+            .await;
+        let config = mock_agent_config(format!("{}/v1", server.url()), false);
+        let mut agent = Agent::new(config).await.unwrap();
+        let result = agent.run_task("Summarise the repo").await.unwrap();
+        assert_eq!(
+            result.task_id,
+            agent.current_checkpoint.as_ref().map(|c| c.task_id.clone())
+        );
+        assert_ne!(result.status, TaskOutcome::Failed);
+        assert!(result.files_changed.is_empty());
+        server.stop().await;
+    }
+
+    #[tokio::test]
+    #[cfg_attr(
+        target_os = "windows",
+        ignore = "mock TCP server unreliable on Windows CI"
+    )]
+    async fn test_run_task_stops_when_max_runtime_elapsed() {
+        let server = MockLlmServer::builder()
+            .with_response("Plan.")
+            .build()
+            .await;
+        let config = mock_agent_config(format!("{}/v1", server.url()), false);
+        let mut agent = Agent::new(config)
+            .await
+            .unwrap()
+            .with_max_runtime(std::time::Duration::ZERO);
+        let result = agent.run_task("Refactor everything").await.unwrap();
+        assert_eq!(result.status, TaskOutcome::TimedOut);
+        assert_eq!(
+            agent.current_checkpoint.as_ref().unwrap().status,
+            crate::checkpoint::TaskStatus::Paused
+        );
+        server.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_run_task_follows_completion_markers() {
+        let server = MockLlmServer::builder()
+            .with_response("Plan.")
+            .with_response("I think that's everything.")
+            .with_response("Tests pass. <task_complete/>")
+            .build()
+            .await;
+        let mut config = mock_agent_config(format!("{}/v1", server.url()), false);
+        config.agent.completion_marker = Some("<task_complete/>".to_string());
+        let mut agent = Agent::new(config).await.unwrap();
+        let result = agent
+            .run_task("Add a --dry-run flag to src/cli.rs")
+            .await
+            .unwrap();
+        assert_eq!(result.status, TaskOutcome::Completed);
+        assert!(agent
+            .messages
+            .iter()
+            .any(|m| m.role == "user" && m.content.text().contains("not marked complete")));
+        assert_eq!(agent.last_assistant_response, "Tests pass.");
+        server.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_run_task_pauses_on_blocked_marker() {
+        let server = MockLlmServer::builder()
+            .with_response("Plan.")
+            .with_response("Which region should the bucket live in? <need_human/>")
+            .build()
+            .await;
+        let mut config = mock_agent_config(format!("{}/v1", server.url()), false);
+        config.agent.blocked_marker = Some("<need_human/>".to_string());
+        let mut agent = Agent::new(config).await.unwrap();
+
+        // Test runs have no terminal on stdin, so the task stays paused
+        let result = agent
+            .run_task("Add a --dry-run flag to src/cli.rs")
+            .await
+            .unwrap();
+        assert_eq!(result.status, TaskOutcome::Paused);
+        assert_eq!(
+            agent.current_checkpoint.as_ref().unwrap().status,
+            crate::checkpoint::TaskStatus::Paused
+        );
+        server.stop().await;
+    }
+
     #[tokio::test]
     #[cfg_attr(
         target_os = "windows",
@@ -1667,6 +2259,76 @@ mod tests {
         server.stop().await;
     }
 
+    #[tokio::test]
+    #[cfg_attr(
+        target_os = "windows",
+        ignore = "mock TCP server unreliable on Windows CI"
+    )]
+    async fn test_run_task_reports_failure_as_failed_outcome() {
+        let server = MockLlmServer::builder().build().await;
+        let mut config = mock_agent_config(format!("{}/v1", server.url()), false);
+        config.agent.max_iterations = 0;
+        let mut agent = Agent::new(config).await.unwrap();
+
+        let result = agent.run_task("Do anything").await.unwrap();
+        assert_eq!(result.status, TaskOutcome::Failed);
+        assert_eq!(result.failure(), Some("Max iterations exceeded"));
+        let err = result.into_result().unwrap_err();
+        assert_eq!(err.to_string(), "Agent failed: Max iterations exceeded");
+        server.stop().await;
+    }
+
+    #[tokio::test]
+    #[cfg_attr(
+        target_os = "windows",
+        ignore = "mock TCP server unreliable on Windows CI"
+    )]
+    async fn test_retry_without_failure_does_nothing() {
+        let server = MockLlmServer::builder().build().await;
+        let config = mock_agent_config(format!("{}/v1", server.url()), false);
+        let mut agent = Agent::new(config).await.unwrap();
+        let before = agent.messages.len();
+        assert!(!agent.retry_last_failure(Some("try again")).await.unwrap());
+        assert_eq!(agent.messages.len(), before);
+        server.stop().await;
+    }
+
+    #[tokio::test]
+    #[cfg_attr(
+        target_os = "windows",
+        ignore = "mock TCP server unreliable on Windows CI"
+    )]
+    async fn test_retry_resumes_failed_step_with_hint() {
+        let server = MockLlmServer::builder()
+            .with_response("Fixed it.")
+            .build()
+            .await;
+        let config = mock_agent_config(format!("{}/v1", server.url()), false);
+        let mut agent = Agent::new(config).await.unwrap();
+        agent.current_checkpoint = Some(TaskCheckpoint::new(
+            "retry-1".to_string(),
+            "Fix the build".to_string(),
+        ));
+        agent.loop_control.restore_progress(3, 8);
+        agent.fail_checkpoint("Max iterations exceeded").unwrap();
+        assert_eq!(agent.last_failure.as_ref().unwrap().step, 3);
+
+        assert!(agent
+            .retry_last_failure(Some("use the nightly toolchain"))
+            .await
+            .unwrap());
+        assert!(agent.last_failure.is_none());
+        assert_eq!(agent.loop_control.current_step(), 3);
+        let retry = agent
+            .messages
+            .iter()
+            .find(|m| m.content.text().contains("[Retry requested by user]"))
+            .unwrap();
+        assert!(retry.content.text().contains("Step 4 failed"));
+        assert!(retry.content.text().contains("use the nightly toolchain"));
+        server.stop().await;
+    }
+
     #[tokio::test]
     #[cfg_attr(
         target_os = "windows",
@@ -2019,4 +2681,96 @@ mod tests {
         });
         assert!(check(&cp));
     }
+
+    fn phase_result(status: TaskOutcome, files: &[&str]) -> TaskResult {
+        TaskResult {
+            task_id: None,
+            status,
+            steps: 1,
+            files_changed: files.iter().map(|f| f.to_string()).collect(),
+            errors: Vec::new(),
+            tokens: Default::default(),
+            summary: None,
+        }
+    }
+
+    #[test]
+    fn test_swarm_phases_hand_off_through_blackboard() {
+        let mut board = SwarmBlackboard::new();
+        let done = phase_result(TaskOutcome::Completed, &["src/lib.rs"]);
+        let failed = phase_result(TaskOutcome::Failed, &[]);
+
+        let kind = record_phase_artifact(
+            &mut board,
+            AgentRole::Architect,
+            "Archie",
+            &done,
+            "Add a cache",
+        )
+        .unwrap();
+        assert_eq!(kind, Some(DesignDoc::KIND));
+        record_phase_artifact(
+            &mut board,
+            AgentRole::Coder,
+            "Cody",
+            &done,
+            "Cached lookups",
+        )
+        .unwrap();
+        assert_eq!(
+            board.get::<Changelist>().unwrap().files,
+            vec!["src/lib.rs".to_string()]
+        );
+
+        // A failed Coder leaves nothing; a failed Tester still reports
+        assert_eq!(
+            record_phase_artifact(&mut board, AgentRole::Coder, "Cody", &failed, "oops").unwrap(),
+            None
+        );
+        assert_eq!(board.history(Changelist::KIND).len(), 1);
+        record_phase_artifact(
+            &mut board,
+            AgentRole::Tester,
+            "Tessa",
+            &failed,
+            "2 failures",
+        )
+        .unwrap();
+        assert!(!board.get::<TestReport>().unwrap().passed);
+
+        let inputs = board.context_for(AgentRole::Reviewer);
+        let prompt = swarm_phase_prompt(
+            "You review.",
+            AgentRole::Reviewer,
+            "Review",
+            inputs.as_deref(),
+        );
+        assert!(prompt.contains("Add a cache"));
+        assert!(prompt.contains("- src/lib.rs"));
+        assert!(prompt.contains("Result: failed"));
+        assert!(prompt.contains("review notes"));
+        assert!(prompt.ends_with("Task: Review"));
+
+        let first = swarm_phase_prompt("You design.", AgentRole::Architect, "Design", None);
+        assert!(!first.contains("blackboard"));
+    }
+
+    #[tokio::test]
+    async fn test_clarify_task_records_assumptions_without_terminal() {
+        let server = MockLlmServer::builder().build().await;
+        let config = mock_agent_config(format!("{}/v1", server.url()), false);
+        let mut agent = Agent::new(config).await.unwrap();
+        agent.current_checkpoint = Some(TaskCheckpoint::new("t".into(), "fix it".into()));
+
+        // Test runs have no terminal on stdin, so nobody is asked
+        let message = agent.clarify_task("fix it");
+        assert!(message.starts_with("fix it\n\nAssumptions"));
+        let checkpoint = agent.current_checkpoint.as_ref().unwrap();
+        assert_eq!(checkpoint.assumptions.len(), 1);
+        assert!(message.contains(&checkpoint.assumptions[0]));
+
+        let clear = "Add a --dry-run flag to src/cli.rs";
+        assert_eq!(agent.clarify_task(clear), clear);
+        server.stop().await;
+    }
 }
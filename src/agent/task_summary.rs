@@ -0,0 +1,593 @@
+//! Summary written when a task completes
+//!
+//! What was asked, which files changed and by how many lines, the commands
+//! and tests that ran, and what is left to do. Everything except the prose
+//! overview is read from the checkpoint's tool log. The overview is the
+//! opening of the final reply, or with `agent.summary_overview` set, one
+//! short model request. [`TaskSummary::to_markdown`] renders it for a pull
+//! request description.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::Path;
+use tracing::debug;
+
+use super::*;
+
+/// Longest overview taken from the final reply when the model can't write one
+const FALLBACK_OVERVIEW_CHARS: usize = 600;
+
+/// Longest excerpt of the final reply sent with the overview request
+const REPLY_EXCERPT_CHARS: usize = 2000;
+
+/// Lines one file gained and lost over the task
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileChange {
+    pub path: String,
+    pub added: usize,
+    pub removed: usize,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub deleted: bool,
+}
+
+/// Last run of one test command
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestRun {
+    pub command: String,
+    /// `cargo_test` outcome: passed, tests_failed, compile_error or cargo_error
+    pub outcome: String,
+    pub passed: usize,
+    pub failed: usize,
+    pub ignored: usize,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failed_tests: Vec<String>,
+}
+
+impl TestRun {
+    pub fn succeeded(&self) -> bool {
+        self.outcome == "passed"
+    }
+}
+
+/// What a completed task did, stored in its checkpoint
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TaskSummary {
+    /// The task as the user gave it
+    pub request: String,
+    /// Prose overview written by the model
+    pub overview: String,
+    pub files: Vec<FileChange>,
+    /// Shell and cargo commands, in the order first run
+    pub commands: Vec<String>,
+    pub tests: Vec<TestRun>,
+    pub follow_ups: Vec<String>,
+}
+
+impl TaskSummary {
+    /// Everything but the overview, from the successful calls in the
+    /// checkpoint's tool log. Paths under `root` are shown relative to it.
+    pub fn from_checkpoint(checkpoint: &TaskCheckpoint, root: &Path) -> Self {
+        let mut files: BTreeMap<String, FileChange> = BTreeMap::new();
+        let mut commands: Vec<String> = Vec::new();
+        let mut tests: Vec<TestRun> = Vec::new();
+
+        for call in checkpoint.tool_calls.iter().filter(|c| c.success) {
+            let args: Value = serde_json::from_str(&call.arguments).unwrap_or(Value::Null);
+            let arg = |key: &str| args.get(key).and_then(|v| v.as_str());
+            match call.tool_name.as_str() {
+                "file_edit" => {
+                    if let (Some(path), Some(old), Some(new)) =
+                        (arg("path"), arg("old_str"), arg("new_str"))
+                    {
+                        let (added, removed) = count_changes(old, new);
+                        let file = file_entry(&mut files, Path::new(path), root);
+                        file.added += added;
+                        file.removed += removed;
+                    }
+                }
+                "file_write" => {
+                    if let (Some(path), Some(content)) = (arg("path"), arg("content")) {
+                        let file = file_entry(&mut files, Path::new(path), root);
+                        file.added += content.lines().count();
+                        file.deleted = false;
+                    }
+                }
+                "file_delete" => {
+                    if let Some(path) = arg("path") {
+                        file_entry(&mut files, Path::new(path), root).deleted = true;
+                    }
+                }
+                "apply_patch" => {
+                    for (path, added, removed) in crate::tools::patch::line_changes(&args) {
+                        let file = file_entry(&mut files, &path, root);
+                        file.added += added;
+                        file.removed += removed;
+                    }
+                }
+                "symbol_rename" => {
+                    let result = call.result.as_deref().unwrap_or_default();
+                    for path in crate::tools::refactor::renamed_files(result) {
+                        file_entry(&mut files, &path, root);
+                    }
+                }
+                _ => {}
+            }
+
+            let Some(command) = command_of(&call.tool_name, &args) else {
+                continue;
+            };
+            if call.tool_name == "cargo_test" {
+                if let Some(run) = test_run(&command, call.result.as_deref()) {
+                    match tests.iter_mut().find(|t| t.command == command) {
+                        Some(previous) => *previous = run,
+                        None => tests.push(run),
+                    }
+                }
+            }
+            if !commands.contains(&command) {
+                commands.push(command);
+            }
+        }
+
+        let mut follow_ups = Vec::new();
+        for run in tests.iter().filter(|t| !t.succeeded()) {
+            let detail = if run.failed_tests.is_empty() {
+                run.outcome.replace('_', " ")
+            } else {
+                format!("failing: {}", run.failed_tests.join(", "))
+            };
+            follow_ups.push(format!("`{}` did not pass ({})", run.command, detail));
+        }
+        if tests.is_empty() && !files.is_empty() {
+            follow_ups.push("No tests were run after the changes".to_string());
+        }
+        for error in checkpoint.errors.iter().filter(|e| !e.recovered) {
+            follow_ups.push(format!(
+                "Unresolved error at step {}: {}",
+                error.step, error.error
+            ));
+        }
+        for assumption in &checkpoint.assumptions {
+            follow_ups.push(format!("Confirm assumption: {}", assumption));
+        }
+
+        Self {
+            request: checkpoint.task_description.clone(),
+            overview: String::new(),
+            files: files.into_values().collect(),
+            commands,
+            tests,
+            follow_ups,
+        }
+    }
+
+    /// Markdown for a pull request description
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::from("## Summary\n\n");
+        if !self.overview.is_empty() {
+            let _ = writeln!(out, "{}\n", self.overview.trim());
+        }
+        if !self.request.is_empty() {
+            let _ = writeln!(out, "Requested: {}\n", self.request.trim());
+        }
+
+        out.push_str("## Changes\n\n");
+        if self.files.is_empty() {
+            out.push_str("No files changed.\n");
+        }
+        for file in &self.files {
+            if file.deleted {
+                let _ = writeln!(out, "- `{}` (deleted)", file.path);
+            } else {
+                let _ = writeln!(out, "- `{}` (+{} −{})", file.path, file.added, file.removed);
+            }
+        }
+        if self.files.len() > 1 {
+            let added: usize = self.files.iter().map(|f| f.added).sum();
+            let removed: usize = self.files.iter().map(|f| f.removed).sum();
+            let _ = writeln!(
+                out,
+                "\n{} files changed, +{} −{}",
+                self.files.len(),
+                added,
+                removed
+            );
+        }
+
+        if !self.commands.is_empty() {
+            out.push_str("\n## Commands run\n\n```sh\n");
+            for command in &self.commands {
+                let _ = writeln!(out, "{}", command);
+            }
+            out.push_str("```\n");
+        }
+
+        if !self.tests.is_empty() {
+            out.push_str("\n## Tests\n\n");
+            for run in &self.tests {
+                let mark = if run.succeeded() { "✅" } else { "❌" };
+                let _ = writeln!(
+                    out,
+                    "- {} `{}`: {} passed, {} failed, {} ignored",
+                    mark, run.command, run.passed, run.failed, run.ignored
+                );
+            }
+        }
+
+        if !self.follow_ups.is_empty() {
+            out.push_str("\n## Follow-ups\n\n");
+            for follow_up in &self.follow_ups {
+                let _ = writeln!(out, "- {}", follow_up);
+            }
+        }
+        out
+    }
+}
+
+impl Agent {
+    /// Summarise the current task from its checkpoint. The overview is the
+    /// opening of the final reply unless `agent.summary_overview` asks the
+    /// model for one, and falls back to it when that request fails.
+    pub async fn summarize_task(&self) -> TaskSummary {
+        let root = std::env::current_dir().unwrap_or_default();
+        let mut summary = self
+            .current_checkpoint
+            .as_ref()
+            .map(|checkpoint| TaskSummary::from_checkpoint(checkpoint, &root))
+            .unwrap_or_default();
+        summary.overview = if self.config.agent.summary_overview {
+            match self.summary_overview(&summary).await {
+                Ok(overview) => overview,
+                Err(e) => {
+                    debug!("Falling back to the final reply for the overview: {:#}", e);
+                    fallback_overview(&self.last_assistant_response)
+                }
+            }
+        } else {
+            fallback_overview(&self.last_assistant_response)
+        };
+        summary
+    }
+
+    async fn summary_overview(&self, summary: &TaskSummary) -> Result<String> {
+        let reply: String = self
+            .last_assistant_response
+            .chars()
+            .take(REPLY_EXCERPT_CHARS)
+            .collect();
+        let request = vec![
+            Message::system("You write the overview paragraph of a pull request description. Reply with two to four plain sentences saying what changed and why. No headings, no lists, no file-by-file detail."),
+            Message::user(format!(
+                "{}\nFinal reply of the agent that did the work:\n{}",
+                summary.to_markdown(),
+                reply
+            )),
+        ];
+        let response = tokio::time::timeout(
+            std::time::Duration::from_secs(60),
+            self.client.chat(request, None, ThinkingMode::Disabled),
+        )
+        .await
+        .map_err(|_| anyhow::anyhow!("Task summary request timed out after 60s"))??;
+        let overview = response
+            .choices
+            .first()
+            .map(|c| c.message.content.text().trim().to_string())
+            .unwrap_or_default();
+        if overview.is_empty() {
+            anyhow::bail!("Task summary request returned an empty response");
+        }
+        Ok(overview)
+    }
+
+    /// Summarise a completed task, print it (unless `--quiet` or `--json`)
+    /// and keep it in the checkpoint
+    pub(super) async fn record_task_summary(&mut self) {
+        let summary = self.summarize_task().await;
+        if !crate::output::is_quiet() {
+            println!("\n{}", summary.to_markdown());
+        }
+        let Some(checkpoint) = self.current_checkpoint.as_mut() else {
+            return;
+        };
+        checkpoint.summary = Some(summary);
+        if let Some(ref manager) = self.checkpoint_manager {
+            if let Err(e) = manager.save(checkpoint) {
+                warn!("Failed to save task summary: {}", e);
+            }
+        }
+    }
+}
+
+/// Map entry for `path`, shown relative to `root`
+fn file_entry<'a>(
+    files: &'a mut BTreeMap<String, FileChange>,
+    path: &Path,
+    root: &Path,
+) -> &'a mut FileChange {
+    let path = path
+        .strip_prefix(root)
+        .unwrap_or(path)
+        .display()
+        .to_string();
+    files.entry(path.clone()).or_insert_with(|| FileChange {
+        path,
+        ..Default::default()
+    })
+}
+
+/// Lines added and removed going from `old` to `new`
+fn count_changes(old: &str, new: &str) -> (usize, usize) {
+    similar::TextDiff::from_lines(old, new)
+        .iter_all_changes()
+        .fold((0, 0), |(added, removed), change| match change.tag() {
+            similar::ChangeTag::Insert => (added + 1, removed),
+            similar::ChangeTag::Delete => (added, removed + 1),
+            similar::ChangeTag::Equal => (added, removed),
+        })
+}
+
+/// Command line a shell or cargo tool call ran
+fn command_of(tool: &str, args: &Value) -> Option<String> {
+    if tool == "shell_exec" {
+        return crate::tools::shell::command_line(args);
+    }
+    let subcommand = tool.strip_prefix("cargo_")?;
+    let arg = |key: &str| args.get(key).and_then(|v| v.as_str());
+    let mut command = format!("cargo {}", subcommand);
+    if let Some(package) = arg("package") {
+        let _ = write!(command, " -p {}", package);
+    }
+    if let Some(name) = arg("crate") {
+        let _ = write!(command, " {}", name);
+    }
+    if let Some(test) = arg("test_name") {
+        let _ = write!(command, " {}", test);
+    }
+    Some(command)
+}
+
+/// Counts from a logged `cargo_test` result (see `tools::cargo::test_digest`)
+fn test_run(command: &str, result: Option<&str>) -> Option<TestRun> {
+    let result: Value = serde_json::from_str(result?).ok()?;
+    let count = |key: &str| result["summary"][key].as_u64().unwrap_or(0) as usize;
+    Some(TestRun {
+        command: command.to_string(),
+        outcome: result["outcome"].as_str()?.to_string(),
+        passed: count("passed"),
+        failed: count("failed"),
+        ignored: count("ignored"),
+        failed_tests: result["failed_tests"]
+            .as_array()
+            .map(|names| {
+                names
+                    .iter()
+                    .filter_map(|n| n.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default(),
+    })
+}
+
+/// First paragraph of the final reply, shortened
+fn fallback_overview(reply: &str) -> String {
+    let paragraph = reply.trim().split("\n\n").next().unwrap_or_default().trim();
+    if paragraph.chars().count() > FALLBACK_OVERVIEW_CHARS {
+        let cut: String = paragraph
+            .chars()
+            .take(FALLBACK_OVERVIEW_CHARS - 1)
+            .collect();
+        format!("{}…", cut)
+    } else {
+        paragraph.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checkpoint::ToolCallLog;
+    use crate::testing::mock_api::MockLlmServer;
+
+    fn logged(tool: &str, arguments: Value, result: Option<Value>, success: bool) -> ToolCallLog {
+        ToolCallLog {
+            timestamp: chrono::Utc::now(),
+            tool_name: tool.to_string(),
+            arguments: arguments.to_string(),
+            result: result.map(|r| r.to_string()),
+            success,
+            duration_ms: None,
+        }
+    }
+
+    fn sample_checkpoint() -> TaskCheckpoint {
+        let mut checkpoint = TaskCheckpoint::new("task-1".into(), "Fix the parser".into());
+        let patch = "--- a/src/b.rs\n+++ b/src/b.rs\n@@ -1,2 +1,3 @@\n keep\n-old\n+new\n+more\n";
+        let calls = [
+            logged(
+                "file_edit",
+                serde_json::json!({"path": "/repo/src/a.rs", "old_str": "a\nb\n", "new_str": "a\nc\nd\n"}),
+                None,
+                true,
+            ),
+            logged(
+                "apply_patch",
+                serde_json::json!({"patch": patch}),
+                None,
+                true,
+            ),
+            logged(
+                "file_write",
+                serde_json::json!({"path": "src/skipped.rs", "content": "x"}),
+                None,
+                false,
+            ),
+            logged(
+                "shell_exec",
+                serde_json::json!({"program": "git", "args": ["status"]}),
+                None,
+                true,
+            ),
+            logged(
+                "cargo_test",
+                serde_json::json!({"package": "core"}),
+                Some(serde_json::json!({
+                    "outcome": "tests_failed",
+                    "summary": {"passed": 3, "failed": 1, "ignored": 0, "total": 4},
+                    "failed_tests": ["parser::tests::bad"],
+                })),
+                true,
+            ),
+            logged(
+                "cargo_test",
+                serde_json::json!({"package": "core"}),
+                Some(serde_json::json!({
+                    "outcome": "passed",
+                    "summary": {"passed": 4, "failed": 0, "ignored": 1, "total": 5},
+                    "failed_tests": [],
+                })),
+                true,
+            ),
+        ];
+        for call in calls {
+            checkpoint.log_tool_call(call);
+        }
+        checkpoint.log_error(3, "timeout".into(), false);
+        checkpoint
+            .assumptions
+            .push("Only the v2 format matters".into());
+        checkpoint
+    }
+
+    #[test]
+    fn test_summary_from_checkpoint() {
+        let summary = TaskSummary::from_checkpoint(&sample_checkpoint(), Path::new("/repo"));
+        assert_eq!(summary.request, "Fix the parser");
+        assert_eq!(
+            summary.files,
+            vec![
+                FileChange {
+                    path: "src/a.rs".into(),
+                    added: 2,
+                    removed: 1,
+                    deleted: false
+                },
+                FileChange {
+                    path: "src/b.rs".into(),
+                    added: 2,
+                    removed: 1,
+                    deleted: false
+                },
+            ]
+        );
+        assert_eq!(summary.commands, vec!["git status", "cargo test -p core"]);
+        assert_eq!(summary.tests.len(), 1);
+        assert!(summary.tests[0].succeeded());
+        assert_eq!(summary.tests[0].passed, 4);
+        assert_eq!(
+            summary.follow_ups,
+            vec![
+                "Unresolved error at step 3: timeout",
+                "Confirm assumption: Only the v2 format matters"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_summary_markdown() {
+        let mut summary = TaskSummary::from_checkpoint(&sample_checkpoint(), Path::new("/repo"));
+        summary.overview = "Fixes the parser.".into();
+        let md = summary.to_markdown();
+        assert!(md.starts_with("## Summary\n\nFixes the parser.\n\nRequested: Fix the parser\n"));
+        assert!(md.contains("- `src/a.rs` (+2 −1)\n"));
+        assert!(md.contains("2 files changed, +4 −2"));
+        assert!(md.contains("```sh\ngit status\ncargo test -p core\n```"));
+        assert!(md.contains("- ✅ `cargo test -p core`: 4 passed, 0 failed, 1 ignored"));
+        assert!(md.contains("## Follow-ups\n\n- Unresolved error at step 3: timeout\n"));
+    }
+
+    #[test]
+    fn test_failing_tests_and_untested_changes_are_follow_ups() {
+        let mut checkpoint = TaskCheckpoint::new("task-2".into(), "demo".into());
+        checkpoint.log_tool_call(logged(
+            "file_delete",
+            serde_json::json!({"path": "old.rs"}),
+            None,
+            true,
+        ));
+        let summary = TaskSummary::from_checkpoint(&checkpoint, Path::new("/repo"));
+        assert!(summary.files[0].deleted);
+        assert_eq!(
+            summary.follow_ups,
+            vec!["No tests were run after the changes"]
+        );
+
+        checkpoint.log_tool_call(logged(
+            "cargo_test",
+            serde_json::json!({}),
+            Some(serde_json::json!({
+                "outcome": "tests_failed",
+                "summary": {"passed": 1, "failed": 1, "ignored": 0, "total": 2},
+                "failed_tests": ["a::bad"],
+            })),
+            true,
+        ));
+        let summary = TaskSummary::from_checkpoint(&checkpoint, Path::new("/repo"));
+        assert_eq!(
+            summary.follow_ups,
+            vec!["`cargo test` did not pass (failing: a::bad)"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_summarize_task_asks_model_for_overview_only() {
+        let server = MockLlmServer::builder()
+            .with_response("Fixed the parser so v2 files load.")
+            .build()
+            .await;
+        let mut config = crate::config::Config {
+            endpoint: format!("{}/v1", server.url()),
+            ..Default::default()
+        };
+        config.agent.summary_overview = true;
+        let mut agent = Agent::new(config).await.unwrap();
+        agent.current_checkpoint = Some(sample_checkpoint());
+
+        let summary = agent.summarize_task().await;
+        assert_eq!(summary.overview, "Fixed the parser so v2 files load.");
+        assert_eq!(summary.commands.len(), 2);
+        server.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_summarize_task_uses_final_reply_by_default() {
+        let server = MockLlmServer::builder()
+            .with_response("This must not be requested.")
+            .build()
+            .await;
+        let config = crate::config::Config {
+            endpoint: format!("{}/v1", server.url()),
+            ..Default::default()
+        };
+        let mut agent = Agent::new(config).await.unwrap();
+        agent.current_checkpoint = Some(sample_checkpoint());
+        agent.last_assistant_response = "Parser fixed.\n\nDetails follow.".into();
+
+        let summary = agent.summarize_task().await;
+        assert_eq!(summary.overview, "Parser fixed.");
+        assert_eq!(summary.commands.len(), 2);
+        server.stop().await;
+    }
+
+    #[test]
+    fn test_fallback_overview_takes_first_paragraph() {
+        assert_eq!(fallback_overview("Done.\n\nDetails"), "Done.");
+        let long = "x".repeat(1000);
+        assert_eq!(
+            fallback_overview(&long).chars().count(),
+            FALLBACK_OVERVIEW_CHARS
+        );
+    }
+}
@@ -143,6 +143,8 @@ fn mock_agent_config(endpoint: String, streaming: bool) -> Config {
         // where backslash separators and UNC prefix paths can confuse glob matching.
         safety: crate::config::SafetyConfig {
             allowed_paths: vec!["./**".to_string(), "/**".to_string()],
+            // Tests run inside selfware's own checkout
+            guard_self_source: false,
             ..Default::default()
         },
         execution_mode: crate::config::ExecutionMode::Yolo,
@@ -195,6 +197,106 @@ async fn test_agent_run_task_e2e_tool_workflow_with_mock_api() {
     server.stop().await;
 }
 
+// Self-healing recovery of the rejected call blocks in place, which needs
+// the multi-threaded runtime
+#[tokio::test(flavor = "multi_thread")]
+#[cfg_attr(
+    target_os = "windows",
+    ignore = "mock TCP server unreliable under heavy parallelism on Windows CI"
+)]
+async fn test_agent_run_task_rejects_disabled_tool() {
+    let server = MockLlmServer::builder()
+        .with_response(
+            r#"<tool>
+<name>shell_exec</name>
+<arguments>{"command":"echo hi"}</arguments>
+</tool>"#,
+        )
+        .with_response("Could not run the shell; done.")
+        .build()
+        .await;
+
+    let mut config = mock_agent_config(format!("{}/v1", server.url()), false);
+    config.tools.allow = vec!["file_read".into(), "grep_*".into()];
+    let mut agent = Agent::new(config).await.unwrap();
+    let prompt = &agent.messages[0].content;
+    assert!(prompt.contains("Only these tools are enabled: file_read, grep_search."));
+    assert!(!prompt.contains(r#"<tool name="shell_exec">"#));
+
+    agent.run_task("Run a shell command").await.unwrap();
+    assert!(agent.messages.iter().any(|m| m
+        .content
+        .contains("Tool 'shell_exec' is disabled for this task")));
+
+    server.stop().await;
+}
+
+/// Run a task whose only tool call fails, under `policy`, and return the agent
+async fn run_task_with_failing_tool(policy: crate::config::ToolErrorPolicy) -> Agent {
+    let server = MockLlmServer::builder()
+        .with_response(
+            r#"<tool>
+<name>file_read</name>
+<arguments>{"path":"./no-such-file-for-policy-test.rs"}</arguments>
+</tool>"#,
+        )
+        .with_response("The file is missing; done.")
+        .build()
+        .await;
+
+    let mut config = mock_agent_config(format!("{}/v1", server.url()), false);
+    config.agent.on_tool_error = policy;
+    let mut agent = Agent::new(config).await.unwrap();
+    agent.run_task("Read the file").await.unwrap();
+    server.stop().await;
+    agent
+}
+
+/// Whether the failed call sent the agent through error recovery
+fn recovered_from_tool_error(agent: &Agent) -> bool {
+    agent.current_checkpoint.as_ref().is_some_and(|checkpoint| {
+        checkpoint
+            .errors
+            .iter()
+            .any(|e| e.recovered && e.error.contains("Tool 'file_read' failed"))
+    })
+}
+
+// Strict recovery runs self-healing, which blocks in place and so needs the
+// multi-threaded runtime
+#[tokio::test(flavor = "multi_thread")]
+#[cfg_attr(
+    target_os = "windows",
+    ignore = "mock TCP server unreliable under heavy parallelism on Windows CI"
+)]
+async fn test_agent_run_task_strict_tool_error_policy_recovers() {
+    let agent = run_task_with_failing_tool(crate::config::ToolErrorPolicy::Strict).await;
+
+    assert!(agent
+        .messages
+        .iter()
+        .any(|m| m.content.contains("<tool_result>")));
+    assert!(recovered_from_tool_error(&agent));
+    assert!(agent.last_assistant_response.contains("done"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[cfg_attr(
+    target_os = "windows",
+    ignore = "mock TCP server unreliable under heavy parallelism on Windows CI"
+)]
+async fn test_agent_run_task_lenient_tool_error_policy_continues() {
+    let agent = run_task_with_failing_tool(crate::config::ToolErrorPolicy::Lenient).await;
+
+    // The model sees the error as the tool result, without a recovery turn
+    assert!(agent
+        .messages
+        .iter()
+        .any(|m| m.content.contains("<tool_result>")));
+    assert!(!recovered_from_tool_error(&agent));
+    assert!(agent.last_assistant_response.contains("done"));
+}
+
 #[tokio::test]
 #[cfg_attr(
     target_os = "windows",
@@ -222,6 +324,40 @@ async fn test_agent_run_task_streaming_fallback_to_non_streaming() {
     server.stop().await;
 }
 
+#[tokio::test]
+async fn test_render_tools_groups_and_marks_access() {
+    let mut config = mock_agent_config("http://127.0.0.1:9/v1".to_string(), false);
+    config.execution_mode = crate::config::ExecutionMode::Normal;
+    config.tools.deny = vec!["shell_exec".into()];
+    let agent = Agent::new(config).await.unwrap();
+
+    let ansi = regex::Regex::new("\x1b\\[[0-9;]*m").unwrap();
+    let render = |name| ansi.replace_all(&agent.render_tools(name), "").into_owned();
+    let listing = render(None);
+    assert!(listing.contains("normal mode"));
+    for heading in ["\nFile\n", "\nGit\n", "\nSearch\n", "\nDevOps\n"] {
+        assert!(listing.contains(heading), "missing {heading:?}");
+    }
+    let file_read = listing
+        .lines()
+        .find(|l| l.trim_start().starts_with("file_read "))
+        .unwrap();
+    assert!(file_read.ends_with("[read-only]"));
+    let file_write = listing
+        .lines()
+        .find(|l| l.trim_start().starts_with("file_write "))
+        .unwrap();
+    assert!(file_write.ends_with("[confirm]"));
+    assert!(listing.contains("path*: string"));
+    assert!(!listing.contains("shell_exec"));
+
+    let detail = render(Some("grep_search"));
+    assert!(detail.starts_with("grep_search (Search, read-only)"));
+    assert!(detail.contains("\"properties\""));
+    assert!(render(Some("shell_exec")).contains("disabled for this task"));
+    assert!(render(Some("no_such_tool")).contains("No tool named"));
+}
+
 #[test]
 fn test_tool_call_parsing_xml_format() {
     let content = r#"
@@ -824,3 +960,212 @@ fn test_trim_message_history_preserves_system_only() {
     assert_eq!(msgs.len(), 1);
     assert_eq!(msgs[0].role, "system");
 }
+
+#[tokio::test]
+async fn test_checkpoint_now_bypasses_interval_and_bookmarks() {
+    let server = MockLlmServer::builder().with_response("done").build().await;
+    let mut config = mock_agent_config(format!("{}/v1", server.url()), false);
+    config.continuous_work.enabled = true;
+    config.continuous_work.checkpoint_interval_tools = 1000;
+    config.continuous_work.checkpoint_interval_secs = 0;
+    let mut agent = Agent::new(config).await.unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    agent.checkpoint_manager = Some(CheckpointManager::new(dir.path().to_path_buf()).unwrap());
+    agent.current_checkpoint = Some(TaskCheckpoint::new(
+        "task-now".to_string(),
+        "Migrate the schema".to_string(),
+    ));
+    agent.checkpoint_persisted_once = true;
+    assert!(!agent.should_persist_checkpoint());
+
+    let (task_id, step) = agent
+        .checkpoint_now(Some("before migration".to_string()))
+        .unwrap();
+    assert_eq!(task_id, "task-now");
+
+    let saved = agent
+        .checkpoint_manager
+        .as_ref()
+        .unwrap()
+        .load("task-now")
+        .unwrap();
+    assert_eq!(saved.bookmarks.len(), 1);
+    assert_eq!(saved.bookmarks[0].step, step);
+    assert_eq!(
+        saved.bookmarks[0].label.as_deref(),
+        Some("before migration")
+    );
+
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn test_focus_loads_files_and_restricts_edits() {
+    let dir = tempfile::tempdir().unwrap();
+    let focused = dir.path().join("focused.rs");
+    std::fs::write(&focused, "fn focused() {}\n").unwrap();
+    let focused = focused.display().to_string();
+    let other = dir.path().join("other.rs").display().to_string();
+
+    let server = MockLlmServer::builder().with_response("ok").build().await;
+    let mut agent = Agent::new(mock_agent_config(format!("{}/v1", server.url()), false))
+        .await
+        .unwrap();
+    assert!(agent.set_focus(vec![other.clone()]).is_err());
+    assert!(agent.focus().is_empty());
+
+    assert_eq!(agent.set_focus(vec![focused.clone()]).unwrap(), 1);
+    assert!(agent
+        .messages
+        .iter()
+        .any(|m| m.content.contains("fn focused()")));
+    let write = |path: &str| crate::api::types::ToolCall {
+        id: "call_1".into(),
+        call_type: "function".into(),
+        function: crate::api::types::ToolFunction {
+            name: "file_write".into(),
+            arguments: serde_json::json!({"path": path, "content": "x"}).to_string(),
+        },
+    };
+    assert!(agent.safety.check_tool_call(&write(&other)).is_err());
+
+    let checkpoint = agent.to_checkpoint("task", "scoped task");
+    assert_eq!(checkpoint.focus, vec![focused.clone()]);
+
+    agent.clear_focus();
+    assert!(agent.safety.check_tool_call(&write(&other)).is_ok());
+    assert!(agent.to_checkpoint("task", "scoped task").focus.is_empty());
+    server.stop().await;
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_agent_run_task_fires_completion_notification() {
+    use crate::config::{NotificationEvent, NotificationHook, NotificationKind};
+
+    let server = MockLlmServer::builder()
+        .with_response("Renamed the function; done.")
+        .build()
+        .await;
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("notification.json");
+    let mut config = mock_agent_config(format!("{}/v1", server.url()), false);
+    config.notifications.hooks = vec![NotificationHook {
+        kind: NotificationKind::Command,
+        url: None,
+        command: Some(format!("cat > {}", out.display())),
+        on: vec![NotificationEvent::Completed],
+        template: None,
+    }];
+    let mut agent = Agent::new(config).await.unwrap();
+    let result = agent.run_task("Rename the function").await.unwrap();
+
+    let payload: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&out).unwrap()).unwrap();
+    assert_eq!(payload["event"], "completed");
+    assert_eq!(payload["status"], "completed");
+    assert_eq!(payload["task_id"], result.task_id.unwrap().as_str());
+    assert_eq!(payload["summary"], agent.last_assistant_response.trim());
+
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn test_restore_snapshot_replaces_live_state() {
+    use crate::session::chat_store::ContextSnapshot;
+
+    let server = MockLlmServer::builder().with_response("ok").build().await;
+    let mut agent = Agent::new(mock_agent_config(format!("{}/v1", server.url()), false))
+        .await
+        .unwrap();
+    agent.context_files = vec!["old.rs".to_string()];
+    agent.stale_files.insert("old.rs".to_string());
+    agent.begin_turn("old prompt");
+
+    let snapshot = ContextSnapshot {
+        name: "work".to_string(),
+        saved_at: chrono::Utc::now(),
+        model: "other-model".to_string(),
+        execution_mode: ExecutionMode::AutoEdit,
+        context_files: vec!["src/lib.rs".to_string()],
+        messages: vec![
+            Message::system("prompt"),
+            Message::user("load the parser"),
+            Message::assistant("loaded"),
+        ],
+    };
+    let (tokens, window) = agent.snapshot_fit(&snapshot);
+    assert!(tokens > 0 && tokens < window);
+
+    agent.restore_snapshot(snapshot);
+    assert_eq!(agent.messages.len(), 3);
+    assert_eq!(agent.messages[1].content.text(), "load the parser");
+    assert_eq!(agent.context_files, vec!["src/lib.rs"]);
+    assert!(agent.stale_files.is_empty());
+    assert!(agent.rewind_plan().is_none());
+    assert_eq!(agent.execution_mode(), ExecutionMode::AutoEdit);
+
+    let oversized = ContextSnapshot {
+        name: "big".to_string(),
+        saved_at: chrono::Utc::now(),
+        model: "mock-model".to_string(),
+        execution_mode: ExecutionMode::Normal,
+        context_files: Vec::new(),
+        messages: vec![Message::user("word ".repeat(window))],
+    };
+    let (tokens, window) = agent.snapshot_fit(&oversized);
+    assert!(tokens > window);
+
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn test_image_command_queues_for_next_message() {
+    let server = MockLlmServer::builder().with_response("ok").build().await;
+    let dir = tempfile::tempdir().unwrap();
+    let png = dir.path().join("ui.png");
+    xcap::image::RgbaImage::new(8, 6).save(&png).unwrap();
+    let png = png.display().to_string();
+
+    // Text-only model: the image is described in the message text
+    let config = mock_agent_config(format!("{}/v1", server.url()), false);
+    let mut agent = Agent::new(config.clone()).await.unwrap();
+    assert!(agent.attach_image("/nonexistent/ui.png").is_err());
+    assert!(agent
+        .attach_image(&dir.path().join("x.txt").display().to_string())
+        .is_err());
+    agent.attach_image(&png).unwrap();
+    let msg = agent.user_message_with_images("fix the layout".to_string());
+    assert!(!msg.content.has_images());
+    assert!(msg.content.text().starts_with("fix the layout"));
+    assert!(msg.content.text().contains("8x6 pixels"));
+    assert!(agent.pending_images.is_empty());
+
+    // Vision model: local and remote images become image parts
+    let mut config = config;
+    let mut profile = config.profile_for_switch(&config.model);
+    profile.modalities = vec!["text".to_string(), "vision".to_string()];
+    config.models.insert("main".to_string(), profile);
+    let mut agent = Agent::new(config).await.unwrap();
+    agent.attach_image(&png).unwrap();
+    agent.attach_image("https://example.com/ui.png").unwrap();
+    let msg = agent.user_message_with_images("fix the layout".to_string());
+    assert_eq!(msg.content.image_count(), 2);
+    assert_eq!(msg.content.text(), "fix the layout");
+    let json = serde_json::to_value(&msg).unwrap();
+    assert!(json["content"][1]["image_url"]["url"]
+        .as_str()
+        .unwrap()
+        .starts_with("data:image/png;base64,"));
+    assert_eq!(
+        json["content"][2]["image_url"]["url"],
+        "https://example.com/ui.png"
+    );
+
+    // The payload is bounded
+    for _ in 0..4 {
+        agent.attach_image(&png).unwrap();
+    }
+    assert!(agent.attach_image(&png).is_err());
+    server.stop().await;
+}
@@ -0,0 +1,274 @@
+//! Token distribution of the context (`/tokens`, `selfware tokens --context`)
+//!
+//! Where `/ctx` sums tokens by role, this lists the individual messages and
+//! loaded files the next request will send, largest first, as a bar chart.
+//! Any item over `ui.token_hog_share` of the context window is flagged.
+
+use colored::*;
+use std::collections::HashMap;
+
+use super::context_management::{message_tokens, FILE_CONTENT_MARKER};
+use super::*;
+
+/// Longest snippet of a message shown in its label
+const SNIPPET_CHARS: usize = 40;
+
+/// Items listed by default; the rest are summed into one line
+const DEFAULT_LIMIT: usize = 20;
+
+/// What a [`TokenItem`] is, for its color
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenItemKind {
+    System,
+    File,
+    User,
+    Assistant,
+    Tool,
+    ToolDefinitions,
+}
+
+/// One message, loaded file or tool schema in the context
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenItem {
+    pub label: String,
+    pub tokens: usize,
+    pub kind: TokenItemKind,
+}
+
+/// First line of `text`, shortened to [`SNIPPET_CHARS`]
+fn snippet(text: &str) -> String {
+    let line = text
+        .lines()
+        .find(|l| !l.trim().is_empty())
+        .unwrap_or("")
+        .trim();
+    if line.chars().count() > SNIPPET_CHARS {
+        let cut: String = line.chars().take(SNIPPET_CHARS - 1).collect();
+        format!("{}…", cut)
+    } else {
+        line.to_string()
+    }
+}
+
+impl Agent {
+    /// Every item the next request sends, largest first. Trimmed messages
+    /// are left out, as in `/ctx`.
+    pub(super) fn token_items(&self) -> Vec<TokenItem> {
+        let keep = self.trim_keep_mask();
+        let mut tool_names: HashMap<&str, &str> = HashMap::new();
+        let (mut user_n, mut assistant_n) = (0, 0);
+        let mut items = Vec::new();
+        for (i, message) in self.messages.iter().enumerate() {
+            for call in message.tool_calls.iter().flatten() {
+                tool_names.insert(&call.id, &call.function.name);
+            }
+            let (label, kind) = match message.role.as_str() {
+                "system" => ("system prompt".to_string(), TokenItemKind::System),
+                "user" if message.content.contains(FILE_CONTENT_MARKER) => {
+                    let text = message.content.text();
+                    let path = text
+                        .lines()
+                        .find_map(|l| l.strip_prefix(FILE_CONTENT_MARKER))
+                        .unwrap_or("?");
+                    (format!("file {}", path), TokenItemKind::File)
+                }
+                "user" => {
+                    user_n += 1;
+                    (
+                        format!("user #{}: {}", user_n, snippet(message.content.text())),
+                        TokenItemKind::User,
+                    )
+                }
+                "assistant" => {
+                    assistant_n += 1;
+                    let calls = message.tool_calls.as_ref().map_or(0, Vec::len);
+                    let label = if calls > 0 {
+                        format!("assistant #{} ({} tool calls)", assistant_n, calls)
+                    } else {
+                        format!("assistant #{}", assistant_n)
+                    };
+                    (label, TokenItemKind::Assistant)
+                }
+                _ => {
+                    let name = message
+                        .name
+                        .as_deref()
+                        .or_else(|| {
+                            message
+                                .tool_call_id
+                                .as_deref()
+                                .and_then(|id| tool_names.get(id).copied())
+                        })
+                        .unwrap_or("tool");
+                    (format!("tool result ({})", name), TokenItemKind::Tool)
+                }
+            };
+            if keep.as_ref().is_some_and(|keep| !keep[i]) {
+                continue;
+            }
+            items.push(TokenItem {
+                label,
+                tokens: message_tokens(message),
+                kind,
+            });
+        }
+        if let Some(tools) = self.api_tools() {
+            let schema = serde_json::to_string(&tools).unwrap_or_default();
+            items.push(TokenItem {
+                label: format!("tool definitions ({} tools)", tools.len()),
+                tokens: crate::token_count::estimate_content_tokens(&schema),
+                kind: TokenItemKind::ToolDefinitions,
+            });
+        }
+        items.sort_by_key(|item| std::cmp::Reverse(item.tokens));
+        items
+    }
+
+    /// Print the token distribution of the next request, listing `limit`
+    /// items or [`DEFAULT_LIMIT`].
+    pub fn show_token_distribution(&self, limit: Option<usize>) {
+        let items = self.token_items();
+        let window = self.memory.context_window();
+        print!(
+            "{}",
+            render_token_bars(
+                &items,
+                window,
+                self.config.ui.token_hog_share,
+                limit.unwrap_or(DEFAULT_LIMIT),
+                colored::control::SHOULD_COLORIZE.should_colorize(),
+            )
+        );
+    }
+}
+
+/// Render `items` (sorted largest first) as a bar list. Bars are scaled to
+/// the largest item; percentages are of `window`. Items over `hog_share` of
+/// the window get a `⚠` marker.
+pub fn render_token_bars(
+    items: &[TokenItem],
+    window: usize,
+    hog_share: f64,
+    limit: usize,
+    color: bool,
+) -> String {
+    const BAR_WIDTH: usize = 30;
+    const LABEL_WIDTH: usize = 46;
+
+    let total: usize = items.iter().map(|i| i.tokens).sum();
+    let largest = items.first().map_or(0, |i| i.tokens).max(1);
+    let pct = |tokens: usize| {
+        if window > 0 {
+            tokens as f64 / window as f64 * 100.0
+        } else {
+            0.0
+        }
+    };
+    let hog_limit = (window as f64 * hog_share) as usize;
+
+    let mut out = format!(
+        "\nToken distribution: {} items, {} tokens ({:.1}% of {} window, {} tokenizer)\n\n",
+        items.len(),
+        total,
+        pct(total),
+        window,
+        crate::token_count::tokenizer_name()
+    );
+    for item in items.iter().take(limit) {
+        let filled = (item.tokens * BAR_WIDTH).div_ceil(largest).min(BAR_WIDTH);
+        let bar = format!("{}{}", "█".repeat(filled), " ".repeat(BAR_WIDTH - filled));
+        let hog = window > 0 && item.tokens > hog_limit;
+        let mut label: String = item.label.chars().take(LABEL_WIDTH).collect();
+        let pad = LABEL_WIDTH.saturating_sub(label.chars().count());
+        label.push_str(&" ".repeat(pad));
+        let marker = if hog { " ⚠" } else { "" };
+        let line = if color {
+            let bar = match item.kind {
+                TokenItemKind::System => bar.bright_blue(),
+                TokenItemKind::File => bar.bright_magenta(),
+                TokenItemKind::User => bar.bright_green(),
+                TokenItemKind::Assistant => bar.bright_cyan(),
+                TokenItemKind::Tool | TokenItemKind::ToolDefinitions => bar.yellow(),
+            };
+            let bar = if hog { bar.on_red() } else { bar };
+            format!(
+                "  {} {} {:>8} {:>5.1}%{}",
+                label,
+                bar,
+                item.tokens,
+                pct(item.tokens),
+                marker.bright_red().bold()
+            )
+        } else {
+            format!(
+                "  {} {} {:>8} {:>5.1}%{}",
+                label,
+                bar,
+                item.tokens,
+                pct(item.tokens),
+                marker
+            )
+        };
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    if items.len() > limit {
+        let rest: usize = items[limit..].iter().map(|i| i.tokens).sum();
+        out.push_str(&format!(
+            "  … {} more items, {} tokens ({:.1}%)\n",
+            items.len() - limit,
+            rest,
+            pct(rest)
+        ));
+    }
+    let hogs = items
+        .iter()
+        .filter(|i| window > 0 && i.tokens > hog_limit)
+        .count();
+    if hogs > 0 {
+        out.push_str(&format!(
+            "\n  ⚠ {} item(s) over {:.0}% of the window (ui.token_hog_share)\n",
+            hogs,
+            hog_share * 100.0
+        ));
+    }
+    out.push('\n');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(label: &str, tokens: usize, kind: TokenItemKind) -> TokenItem {
+        TokenItem {
+            label: label.to_string(),
+            tokens,
+            kind,
+        }
+    }
+
+    #[test]
+    fn test_render_token_bars_scales_and_flags_hogs() {
+        let items = vec![
+            item("file src/big.rs", 3000, TokenItemKind::File),
+            item("system prompt", 1500, TokenItemKind::System),
+            item("user #1: hi", 10, TokenItemKind::User),
+        ];
+        let out = render_token_bars(&items, 10_000, 0.2, 2, false);
+        let lines: Vec<&str> = out.lines().collect();
+        assert!(lines[1].contains("3 items, 4510 tokens (45.1% of 10000 window"));
+        assert!(lines[3].contains(&"█".repeat(30)) && lines[3].ends_with("30.0% ⚠"));
+        assert!(lines[4].contains(&format!("{} ", "█".repeat(15))));
+        assert!(!lines[4].contains('⚠'));
+        assert!(out.contains("… 1 more items, 10 tokens"));
+        assert!(out.contains("1 item(s) over 20% of the window"));
+    }
+
+    #[test]
+    fn test_snippet_takes_first_line() {
+        assert_eq!(snippet("\n  fix the parser\nmore"), "fix the parser");
+        let long = "x".repeat(100);
+        assert_eq!(snippet(&long).chars().count(), SNIPPET_CHARS);
+    }
+}
@@ -0,0 +1,209 @@
+//! The `/tools` listing: every enabled tool grouped by category, with a
+//! one-line description, its parameters, and whether it is read-only or asks
+//! for confirmation under the current execution mode. `/tools <name>` shows
+//! one tool's full description and JSON schema.
+
+use colored::*;
+use serde_json::Value;
+
+use super::{Agent, READ_ONLY_TOOLS};
+
+/// Category headings, in display order
+const CATEGORIES: [&str; 6] = ["File", "Git", "Search", "Shell", "DevOps", "Other"];
+
+/// Category a tool is listed under. Tools registered outside the built-in
+/// set land in "Other".
+fn category(name: &str) -> &'static str {
+    match name {
+        n if n.starts_with("file_") => "File",
+        "directory_tree" | "apply_patch" | "symbol_rename" | "tool_output" => "File",
+        n if n.starts_with("git_") => "Git",
+        "grep_search" | "glob_find" | "symbol_search" | "find_cycles" | "panic_risk" => "Search",
+        n if n.starts_with("rag_") || n.starts_with("knowledge_") => "Search",
+        "shell_exec" | "port_check" => "Shell",
+        n if n.starts_with("process_") => "Shell",
+        n if ["cargo_", "npm_", "pip_", "yarn_", "container_", "compose_"]
+            .iter()
+            .any(|p| n.starts_with(p)) =>
+        {
+            "DevOps"
+        }
+        _ => "Other",
+    }
+}
+
+/// First line of a description, without a trailing period
+fn summary_line(description: &str) -> &str {
+    let line = description.lines().next().unwrap_or_default().trim();
+    let line = match line.find(". ") {
+        Some(end) => &line[..end],
+        None => line,
+    };
+    line.trim_end_matches('.')
+}
+
+/// Parameters from a JSON schema as `name*: type, name: type`, required
+/// ones first and marked with `*`
+fn param_summary(schema: &Value) -> String {
+    let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+        return String::new();
+    };
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|r| r.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    let mut params: Vec<(bool, String)> = properties
+        .iter()
+        .map(|(name, prop)| {
+            let is_required = required.contains(&name.as_str());
+            let ty = prop.get("type").and_then(Value::as_str).unwrap_or("any");
+            let marker = if is_required { "*" } else { "" };
+            (is_required, format!("{}{}: {}", name, marker, ty))
+        })
+        .collect();
+    // Stable sort keeps schema order within each group
+    params.sort_by_key(|(is_required, _)| !is_required);
+    params
+        .into_iter()
+        .map(|(_, p)| p)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl Agent {
+    /// Text for `/tools`, or `/tools <name>` when `name` is given
+    pub(super) fn render_tools(&self, name: Option<&str>) -> String {
+        match name {
+            Some(name) => self.render_tool_detail(name),
+            None => self.render_tool_list(),
+        }
+    }
+
+    /// Short access label for a tool under the current mode
+    fn tool_access(&self, name: &str) -> Option<&'static str> {
+        if READ_ONLY_TOOLS.contains(&name) {
+            Some("read-only")
+        } else if self.needs_confirmation(name) {
+            Some("confirm")
+        } else {
+            None
+        }
+    }
+
+    fn render_tool_list(&self) -> String {
+        let mut tools = self.tools.list();
+        tools.sort_by_key(|t| t.name().to_string());
+        let width = tools.iter().map(|t| t.name().len()).max().unwrap_or(0);
+
+        let mut out = format!(
+            "{} {} tools, {} mode ({} = required parameter)\n",
+            "🔧".bright_cyan(),
+            tools.len(),
+            self.mode_label(),
+            "*".bright_yellow()
+        );
+        for heading in CATEGORIES {
+            let in_category: Vec<_> = tools
+                .iter()
+                .filter(|t| category(t.name()) == heading)
+                .collect();
+            if in_category.is_empty() {
+                continue;
+            }
+            out.push_str(&format!("\n{}\n", heading.bright_white().bold()));
+            for tool in in_category {
+                let access = match self.tool_access(tool.name()) {
+                    Some("read-only") => "[read-only]".bright_green().to_string(),
+                    Some(label) => format!("[{}]", label).bright_yellow().to_string(),
+                    None => String::new(),
+                };
+                out.push_str(&format!(
+                    "  {}  {} {}\n",
+                    format!("{:<width$}", tool.name(), width = width).bright_cyan(),
+                    summary_line(tool.description()),
+                    access
+                ));
+                let params = param_summary(&tool.schema());
+                if !params.is_empty() {
+                    out.push_str(&format!(
+                        "  {:<width$}  {}\n",
+                        "",
+                        params.dimmed(),
+                        width = width
+                    ));
+                }
+            }
+        }
+        out.push_str(&format!(
+            "\n{}\n",
+            "Use /tools <name> for a tool's full schema.".dimmed()
+        ));
+        out
+    }
+
+    fn render_tool_detail(&self, name: &str) -> String {
+        let Some(tool) = self.tools.get(name) else {
+            return if self.tools.is_disabled(name) {
+                format!(
+                    "{} Tool '{}' is disabled for this task",
+                    "ℹ".bright_yellow(),
+                    name
+                )
+            } else {
+                format!("{} No tool named '{}'", "ℹ".bright_yellow(), name)
+            };
+        };
+        let access = self
+            .tool_access(name)
+            .unwrap_or("runs without confirmation");
+        let schema = serde_json::to_string_pretty(&tool.schema()).unwrap_or_default();
+        format!(
+            "{} ({}, {})\n{}\n\n{}\n",
+            tool.name().bright_cyan().bold(),
+            category(name),
+            access,
+            tool.description(),
+            schema
+        )
+    }
+
+    fn mode_label(&self) -> &'static str {
+        match self.execution_mode() {
+            crate::config::ExecutionMode::Normal => "normal",
+            crate::config::ExecutionMode::AutoEdit => "auto-edit",
+            crate::config::ExecutionMode::Yolo => "YOLO",
+            crate::config::ExecutionMode::Daemon => "daemon",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_category_and_param_summary() {
+        assert_eq!(category("file_read"), "File");
+        assert_eq!(category("git_diff"), "Git");
+        assert_eq!(category("rag_search"), "Search");
+        assert_eq!(category("process_start"), "Shell");
+        assert_eq!(category("container_run"), "DevOps");
+        assert_eq!(category("browser_fetch"), "Other");
+
+        let schema = serde_json::json!({
+            "type": "object",
+            "required": ["path"],
+            "properties": {
+                "max_depth": {"type": "integer"},
+                "path": {"type": "string"}
+            }
+        });
+        assert_eq!(param_summary(&schema), "path*: string, max_depth: integer");
+        assert_eq!(param_summary(&serde_json::json!({})), "");
+        assert_eq!(
+            summary_line("Read a file. Supports offsets.\nMore detail"),
+            "Read a file"
+        );
+    }
+}
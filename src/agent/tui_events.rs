@@ -1,3 +1,4 @@
+use crate::observability::dashboard::RunStats;
 #[cfg(feature = "tui")]
 use crate::ui::tui::TuiEvent;
 
@@ -30,6 +31,8 @@ pub enum AgentEvent {
         success: bool,
         duration_ms: u64,
     },
+    /// Context, usage and plan progress, sent at step boundaries
+    Stats(RunStats),
 }
 
 /// Trait for emitting real-time events during agent execution.
@@ -84,6 +87,7 @@ impl EventEmitter for TuiEmitter {
                 success,
                 duration_ms,
             },
+            AgentEvent::Stats(stats) => TuiEvent::RunStats(stats),
         };
         let _ = self.tx.send(tui_event);
     }
@@ -5,9 +5,16 @@
 //! - Prioritization (type errors before unused warnings)
 //! - Automatic fix suggestions for common patterns
 //! - Grouping of related errors
+//!
+//! It also reports panic risks in Rust sources: `unwrap()`, `expect()`,
+//! indexing and arithmetic that can panic, ranked by how hot the code is.
 
+use super::code_graph::{CodeGraph, GraphBuilder};
+use super::references::{code_only, outline, OutlineItem};
+use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
 /// Type for raw error input to batch analysis
 pub type RawError<'a> = (Option<&'a str>, &'a str, &'a str, Option<u32>, Option<u32>);
@@ -506,6 +513,602 @@ fn extract_between(message: &str, start_marker: &str, end_marker: &str) -> Optio
     Some(message[start..end].to_string())
 }
 
+// ============================================================================
+// Panic risk
+// ============================================================================
+
+/// Construct that can panic at runtime
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PanicKind {
+    /// `.unwrap()` on an `Option` or `Result`
+    Unwrap,
+    /// `.expect(..)` on an `Option` or `Result`
+    Expect,
+    /// Indexing or slicing with `[]`
+    Index,
+    /// Integer arithmetic that can overflow or divide by zero
+    Arithmetic,
+}
+
+impl PanicKind {
+    /// How likely the construct is to panic in practice
+    pub fn weight(&self) -> f32 {
+        match self {
+            PanicKind::Unwrap => 1.0,
+            PanicKind::Expect => 0.8,
+            PanicKind::Index => 0.7,
+            PanicKind::Arithmetic => 0.4,
+        }
+    }
+
+    /// Safer pattern to use instead
+    pub fn suggestion(&self) -> &'static str {
+        match self {
+            PanicKind::Unwrap => {
+                "Propagate with `?` (adding `.context(..)`), or handle the None/Err case with \
+                 `match`, `if let` or `unwrap_or_else`"
+            }
+            PanicKind::Expect => {
+                "Return the error with `?` and `.context(..)` unless the invariant truly cannot fail"
+            }
+            PanicKind::Index => {
+                "Use `.get(i)` / `.get(a..b)` and handle `None`, or check the bounds first"
+            }
+            PanicKind::Arithmetic => {
+                "Use `checked_add`/`checked_sub`/`checked_mul`/`checked_div` (or the \
+                 `saturating_`/`wrapping_` forms) where overflow or a zero divisor is possible"
+            }
+        }
+    }
+}
+
+/// One place that can panic
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PanicRisk {
+    pub file: PathBuf,
+    /// 1-indexed line
+    pub line: usize,
+    pub kind: PanicKind,
+    /// The line, trimmed
+    pub code: String,
+    /// Enclosing function, `Type::method` inside an `impl`
+    pub function: Option<String>,
+    /// In test code, where panicking is fine
+    pub in_test: bool,
+    /// The enclosing function is part of the public API
+    pub public: bool,
+    /// Functions calling the enclosing one, from the crate's call graph
+    pub callers: usize,
+    /// Ranking score: kind weight, raised for public and often-called
+    /// functions; 0 in test code
+    pub hotness: f32,
+    pub suggestion: String,
+}
+
+/// Panic risks across a set of files, hottest first with test code last
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PanicRiskReport {
+    pub files_scanned: usize,
+    pub findings: Vec<PanicRisk>,
+}
+
+impl PanicRiskReport {
+    /// Findings outside test code
+    pub fn production(&self) -> impl Iterator<Item = &PanicRisk> {
+        self.findings.iter().filter(|f| !f.in_test)
+    }
+
+    /// Number of findings per kind outside test code
+    pub fn counts(&self) -> HashMap<PanicKind, usize> {
+        let mut counts = HashMap::new();
+        for finding in self.production() {
+            *counts.entry(finding.kind).or_insert(0) += 1;
+        }
+        counts
+    }
+}
+
+/// A file as seen by the panic-risk pass
+struct Source<'a> {
+    path: &'a PathBuf,
+    content: &'a str,
+    /// `content` with comments and literals blanked
+    masked: String,
+    fns: Vec<FnSpan>,
+    /// Line ranges of test items
+    tests: Vec<(usize, usize)>,
+}
+
+/// A function as seen by the panic-risk pass
+struct FnSpan {
+    name: String,
+    /// Type of the enclosing `impl` or `trait`
+    owner: Option<String>,
+    qualified: String,
+    lines: (usize, usize),
+    body: std::ops::Range<usize>,
+    public: bool,
+    in_test: bool,
+}
+
+/// Report panic risks in Rust sources given as (path, content).
+///
+/// The pass is lexical: comments and literals are ignored, and callers are
+/// counted over all given files with calls resolved by name, so a call
+/// through a method name several types define is not counted.
+pub fn panic_risk(files: &[(PathBuf, String)]) -> PanicRiskReport {
+    let sources: Vec<Source<'_>> = files
+        .iter()
+        .filter(|(path, _)| path.extension().is_some_and(|e| e == "rs"))
+        .map(|(path, content)| {
+            let items = outline(path, content);
+            let masked = code_only(content);
+            Source {
+                path,
+                content,
+                fns: fn_spans(content, &items),
+                tests: test_lines(content, &masked, &items),
+                masked,
+            }
+        })
+        .collect();
+    let graph = call_graph(&sources);
+
+    let mut findings = Vec::new();
+    for Source {
+        path,
+        content,
+        masked,
+        fns,
+        tests,
+    } in &sources
+    {
+        let test_file = is_test_path(path);
+        for (index, (code, original)) in masked.lines().zip(content.lines()).enumerate() {
+            let line = index + 1;
+            let kinds = panic_kinds(code);
+            if kinds.is_empty() {
+                continue;
+            }
+            let function = fns
+                .iter()
+                .filter(|f| f.lines.0 <= line && line <= f.lines.1)
+                .min_by_key(|f| f.lines.1 - f.lines.0);
+            let in_test = test_file
+                || tests.iter().any(|&(a, b)| a <= line && line <= b)
+                || function.is_some_and(|f| f.in_test);
+            let public = function.is_some_and(|f| f.public);
+            let callers = function
+                .and_then(|f| graph.get_node(&f.qualified))
+                .map_or(0, |node| graph.node_metrics(&node.id).in_degree);
+            for kind in kinds {
+                let hotness = if in_test {
+                    0.0
+                } else {
+                    kind.weight()
+                        * if public { 2.0 } else { 1.0 }
+                        * (1.0 + (callers as f32).ln_1p())
+                };
+                findings.push(PanicRisk {
+                    file: (*path).clone(),
+                    line,
+                    kind,
+                    code: original.trim().to_string(),
+                    function: function.map(|f| f.qualified.clone()),
+                    in_test,
+                    public,
+                    callers,
+                    hotness,
+                    suggestion: kind.suggestion().to_string(),
+                });
+            }
+        }
+    }
+    findings.sort_by(|a, b| {
+        b.hotness
+            .total_cmp(&a.hotness)
+            .then_with(|| a.file.cmp(&b.file))
+            .then_with(|| a.line.cmp(&b.line))
+    });
+    PanicRiskReport {
+        files_scanned: sources.len(),
+        findings,
+    }
+}
+
+/// Report panic risks in the Rust files under `root`, skipping `target`
+/// and hidden directories
+pub fn panic_risk_in(root: &Path) -> Result<PanicRiskReport> {
+    let mut files = Vec::new();
+    let walker = walkdir::WalkDir::new(root).into_iter().filter_entry(|e| {
+        e.depth() == 0
+            || !(e.file_name() == "target" || e.file_name().to_string_lossy().starts_with('.'))
+    });
+    for entry in walker {
+        let entry = entry?;
+        let path = entry.path();
+        if !entry.file_type().is_file() || path.extension().is_none_or(|e| e != "rs") {
+            continue;
+        }
+        let Ok(content) = std::fs::read_to_string(path) else {
+            continue;
+        };
+        let relative = path.strip_prefix(root).unwrap_or(path).to_path_buf();
+        files.push((relative, content));
+    }
+    files.sort();
+    Ok(panic_risk(&files))
+}
+
+/// Panic-prone constructs on one line of code with literals and comments
+/// blanked, each kind at most once
+fn panic_kinds(code: &str) -> Vec<PanicKind> {
+    let trimmed = code.trim_start();
+    let mut kinds = Vec::new();
+    if trimmed.is_empty() || trimmed.starts_with('#') {
+        return kinds;
+    }
+    // A literal regex that does not compile fails on every run, so tests
+    // catch it long before users do
+    let literal_regex = code.contains("Regex::new(");
+    if code.contains(".unwrap()") && !literal_regex {
+        kinds.push(PanicKind::Unwrap);
+    }
+    if has_expect(code) && !literal_regex {
+        kinds.push(PanicKind::Expect);
+    }
+    if has_index(code) {
+        kinds.push(PanicKind::Index);
+    }
+    // Constants are evaluated at compile time
+    let item = trimmed.trim_start_matches("pub ");
+    if !(item.starts_with("const ") || item.starts_with("static ")) && has_arithmetic(code) {
+        kinds.push(PanicKind::Arithmetic);
+    }
+    kinds
+}
+
+/// Whether `code` calls `.expect(..)`, leaving out fallible methods of the
+/// same name such as a parser's `self.expect(Token::Comma)?`
+fn has_expect(code: &str) -> bool {
+    code.match_indices(".expect(").any(|(i, m)| {
+        let args = &code[i + m.len()..];
+        let mut depth = 1;
+        let close = args.bytes().position(|b| {
+            match b {
+                b'(' => depth += 1,
+                b')' => depth -= 1,
+                _ => {}
+            }
+            depth == 0
+        });
+        close.is_none_or(|close| !args[close + 1..].starts_with('?'))
+    })
+}
+
+/// Words that can precede `[` without indexing, e.g. `for x in [a, b]`
+const NON_INDEX_WORDS: &[&str] = &[
+    "in", "for", "as", "let", "return", "mut", "else", "break", "move", "match", "if", "while",
+    "yield",
+];
+
+/// Whether `code` indexes or slices with `[]`
+fn has_index(code: &str) -> bool {
+    let bytes = code.as_bytes();
+    code.match_indices('[').any(|(open, _)| {
+        let before = code[..open].trim_end();
+        let Some(&prev) = before.as_bytes().last() else {
+            return false;
+        };
+        let after_expr = prev == b')' || prev == b']' || is_word_byte(prev);
+        if !after_expr || NON_INDEX_WORDS.contains(&last_word(before)) {
+            return false;
+        }
+        // `x[..]` takes the whole slice, and a blank key was a string
+        // literal: `json["key"]` yields `Null` rather than panicking
+        let close = bytes[open..]
+            .iter()
+            .position(|&b| b == b']')
+            .map(|n| open + n);
+        close.is_none_or(|close| !matches!(code[open + 1..close].trim(), ".." | ""))
+    })
+}
+
+/// Whether `code` has integer arithmetic that can overflow or divide by zero
+fn has_arithmetic(code: &str) -> bool {
+    if code.contains("f32") || code.contains("f64") || has_float_literal(code) {
+        return false;
+    }
+    let bytes = code.as_bytes();
+    (0..bytes.len()).any(|i| {
+        let op = bytes[i];
+        if !matches!(op, b'+' | b'-' | b'*' | b'/' | b'%') {
+            return false;
+        }
+        let before = code[..i].trim_end();
+        let Some(&prev) = before.as_bytes().last() else {
+            return false;
+        };
+        // Binary use only: unary minus, derefs and `->` have no operand before
+        if !(prev == b')' || prev == b']' || is_word_byte(prev)) {
+            return false;
+        }
+        let rest = &code[i + 1..];
+        // Compound assignment: `a -= b`
+        let after = rest.strip_prefix('=').unwrap_or(rest).trim_start();
+        let Some(&next) = after.as_bytes().first() else {
+            return false;
+        };
+        if !(next == b'(' || is_word_byte(next)) {
+            return false;
+        }
+        let left = last_word(before);
+        let right = first_word(after);
+        // `T: Clone + Send` and `dyn Fn() + Send` are bounds, not sums
+        if op == b'+' && (starts_uppercase(left) || starts_uppercase(right)) {
+            return false;
+        }
+        let literal = |w: &str| w.bytes().next().is_some_and(|b| b.is_ascii_digit());
+        // Adding a literal, as in `count += 1`, would need a counter at the
+        // end of its range
+        if literal(right) && (op == b'+' || literal(left)) {
+            return false;
+        }
+        // Dividing by a non-zero literal cannot panic
+        if matches!(op, b'/' | b'%') && literal(right) {
+            return right.bytes().all(|b| b == b'0' || b == b'_');
+        }
+        true
+    })
+}
+
+fn is_word_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
+fn last_word(text: &str) -> &str {
+    let start = text
+        .bytes()
+        .rposition(|b| !is_word_byte(b))
+        .map_or(0, |i| i + 1);
+    &text[start..]
+}
+
+fn first_word(text: &str) -> &str {
+    let end = text
+        .bytes()
+        .position(|b| !is_word_byte(b))
+        .unwrap_or(text.len());
+    &text[..end]
+}
+
+fn starts_uppercase(word: &str) -> bool {
+    word.bytes().next().is_some_and(|b| b.is_ascii_uppercase())
+}
+
+/// Whether `code` contains a literal such as `1.5` or `2e3`
+fn has_float_literal(code: &str) -> bool {
+    let bytes = code.as_bytes();
+    bytes.windows(3).enumerate().any(|(i, w)| {
+        let starts_number = i == 0 || !is_word_byte(bytes[i - 1]) || bytes[i - 1].is_ascii_digit();
+        starts_number && w[0].is_ascii_digit() && w[1] == b'.' && w[2].is_ascii_digit()
+    })
+}
+
+/// Files that only hold tests: under `tests/` or `benches/`, or a
+/// `tests.rs` / `*_test(s).rs` module
+fn is_test_path(path: &Path) -> bool {
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+    stem == "tests"
+        || stem == "test"
+        || stem.ends_with("_test")
+        || stem.ends_with("_tests")
+        || path
+            .components()
+            .any(|c| c.as_os_str() == "tests" || c.as_os_str() == "benches")
+}
+
+/// Line ranges of test code: items marked `#[test]` or `#[cfg(test)]`,
+/// and `#[cfg(test)]` inline modules, which the outline flattens
+fn test_lines(content: &str, masked: &str, items: &[OutlineItem]) -> Vec<(usize, usize)> {
+    fn collect(content: &str, items: &[OutlineItem], out: &mut Vec<(usize, usize)>) {
+        for item in items {
+            if item.is_test || is_cfg_test(content, item) {
+                out.push(item.lines);
+            } else {
+                collect(content, &item.children, out);
+            }
+        }
+    }
+    let mut lines = Vec::new();
+    collect(content, items, &mut lines);
+
+    let line_at = |offset: usize| masked[..offset].matches('\n').count() + 1;
+    let mut pending = None;
+    let mut offset = 0;
+    for line in masked.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("#[cfg(test)]") {
+            pending.get_or_insert(offset);
+        } else if !trimmed.starts_with('#') && !trimmed.trim().is_empty() {
+            let item = trimmed.strip_prefix("pub ").unwrap_or(trimmed);
+            if let (Some(start), true) = (pending, item.starts_with("mod ")) {
+                if let Some(open) = line.find('{') {
+                    let close = matching_brace(masked, offset + open);
+                    lines.push((line_at(start), line_at(close)));
+                }
+            }
+            pending = None;
+        }
+        offset += line.len();
+    }
+    lines
+}
+
+/// Offset of the `}` closing the `{` at `open`, or the end of `code`
+fn matching_brace(code: &str, open: usize) -> usize {
+    let mut depth = 0;
+    for (i, b) in code.bytes().enumerate().skip(open) {
+        match b {
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return i;
+                }
+            }
+            _ => {}
+        }
+    }
+    code.len()
+}
+
+/// Whether the item's outer attributes include `#[cfg(test)]`
+fn is_cfg_test(content: &str, item: &OutlineItem) -> bool {
+    content[item.span.clone()]
+        .lines()
+        .map(str::trim_start)
+        .take_while(|l| l.is_empty() || l.starts_with('#') || l.starts_with("//"))
+        .any(|l| l.starts_with('#') && l.contains("cfg(test)"))
+}
+
+/// Functions and methods of a file
+fn fn_spans(content: &str, items: &[OutlineItem]) -> Vec<FnSpan> {
+    let mut spans = Vec::new();
+    for item in items {
+        if item.keyword == "fn" {
+            spans.push(fn_span(content, item, None, false));
+        }
+        if item.keyword == "impl" || item.keyword == "trait" {
+            // Methods of trait impls are as public as the trait
+            let trait_impl = item.keyword == "impl" && item.name.contains(" for ");
+            for child in item.children.iter().filter(|c| c.keyword == "fn") {
+                let mut span = fn_span(content, child, Some(item.self_type()), trait_impl);
+                span.in_test |= item.is_test;
+                spans.push(span);
+            }
+        }
+    }
+    spans
+}
+
+fn fn_span(content: &str, item: &OutlineItem, owner: Option<&str>, public: bool) -> FnSpan {
+    let text = &content[item.span.clone()];
+    // The header is the first line that is not an attribute or a comment
+    let header = text
+        .lines()
+        .map(str::trim_start)
+        .find(|l| !(l.is_empty() || l.starts_with('#') || l.starts_with("//")))
+        .unwrap_or("");
+    FnSpan {
+        name: item.name.clone(),
+        owner: owner.map(str::to_string),
+        qualified: match owner {
+            Some(owner) => format!("{}::{}", owner, item.name),
+            None => item.name.clone(),
+        },
+        lines: item.lines,
+        body: item.span.clone(),
+        public: public || header.starts_with("pub "),
+        in_test: item.is_test,
+    }
+}
+
+/// Call graph over the functions of all sources.
+///
+/// `Type::f(..)` and `Self::f(..)` resolve to that type's function, bare
+/// `f(..)` to a free function, `module::f(..)` to a free function in a file
+/// named after the module, and `.f(..)` to the caller's own method or,
+/// failing that, to the only method of that name. Calls through ambiguous
+/// method names are dropped so that `new` or `get` do not collect every
+/// call site in the crate.
+fn call_graph(sources: &[Source<'_>]) -> CodeGraph {
+    let mut builder = GraphBuilder::new("calls");
+    let mut known = HashSet::new();
+    let mut methods: HashMap<&str, HashSet<&str>> = HashMap::new();
+    let mut module_fns = HashSet::new();
+    for source in sources {
+        let file = source.path.display().to_string();
+        let module = match source.path.file_stem().and_then(|s| s.to_str()) {
+            Some("mod") => source
+                .path
+                .parent()
+                .and_then(|p| p.file_name())
+                .and_then(|n| n.to_str()),
+            stem => stem,
+        };
+        for f in &source.fns {
+            if let (None, Some(module)) = (&f.owner, module) {
+                module_fns.insert(format!("{}::{}", module, f.name));
+            }
+            if known.insert(f.qualified.as_str()) {
+                builder.add_function(&f.qualified, Some(&file), Some(f.lines.0 as u32));
+            }
+            if f.owner.is_some() {
+                methods
+                    .entry(f.name.as_str())
+                    .or_default()
+                    .insert(f.qualified.as_str());
+            }
+        }
+    }
+    for source in sources {
+        let test_file = is_test_path(source.path);
+        for f in &source.fns {
+            // Calls from tests do not make a function hot
+            let line = f.lines.0;
+            if test_file || f.in_test || source.tests.iter().any(|&(a, b)| a <= line && line <= b) {
+                continue;
+            }
+            let body = &source.masked[f.body.clone()];
+            let mut called = HashSet::new();
+            for (i, _) in body.match_indices('(') {
+                let before = body[..i].trim_end();
+                let name = last_word(before);
+                if name.is_empty() {
+                    continue;
+                }
+                let head = before[..before.len() - name.len()].trim_end();
+                let callee = if let Some(path) = head.strip_suffix("::") {
+                    match last_word(path) {
+                        "Self" => f.owner.as_ref().map(|o| format!("{}::{}", o, name)),
+                        ty if starts_uppercase(ty) => Some(format!("{}::{}", ty, name)),
+                        // `std::fs::write` is not a call to a local `write`
+                        module if module_fns.contains(&format!("{}::{}", module, name)) => {
+                            Some(name.to_string())
+                        }
+                        _ => None,
+                    }
+                } else if head.ends_with('.') {
+                    let own = f.owner.as_ref().map(|o| format!("{}::{}", o, name));
+                    match own {
+                        Some(own) if known.contains(own.as_str()) => Some(own),
+                        _ => methods
+                            .get(name)
+                            .filter(|m| m.len() == 1)
+                            .and_then(|m| m.iter().next())
+                            .map(|m| m.to_string()),
+                    }
+                } else if last_word(head) == "fn" {
+                    None
+                } else {
+                    Some(name.to_string())
+                };
+                if let Some(callee) = callee {
+                    if callee != f.qualified && known.contains(callee.as_str()) {
+                        called.insert(callee);
+                    }
+                }
+            }
+            for callee in called {
+                builder.add_call(&f.qualified, &callee);
+            }
+        }
+    }
+    builder.build()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -982,4 +1585,82 @@ mod tests {
         assert_eq!(error.line, Some(42));
         assert_eq!(error.column, Some(10));
     }
+
+    #[test]
+    fn test_panic_risk_ranks_hot_production_code_first() {
+        let lib = r#"
+pub fn parse(input: &str) -> u32 {
+    let first = input.split(',').next().unwrap();
+    let bytes = input.as_bytes();
+    let _ = bytes[0];
+    first.parse().expect("number")
+}
+
+fn helper(items: &[u32], total: u32, count: u32) -> u32 {
+    let _ = json["key"];
+    let _ = &items[..];
+    let _ = total / 2 + 1.5f64 as u32;
+    let _ = self.expect(Token::Comma)?;
+    total - count
+}
+
+fn caller_a() { helper(&[], 1, 1); parse("1"); }
+fn caller_b() { helper(&[], 2, 1); }
+
+#[cfg(test)]
+mod tests {
+    fn fixture() {
+        let value = "1".parse::<u32>().unwrap();
+        helper(&[], value, value);
+    }
+}
+"#;
+        let files = vec![(PathBuf::from("src/lib.rs"), lib.to_string())];
+        let report = panic_risk(&files);
+        assert_eq!(report.files_scanned, 1);
+
+        let kinds: Vec<(usize, PanicKind)> =
+            report.production().map(|f| (f.line, f.kind)).collect();
+        assert_eq!(kinds.len(), 4, "{:?}", kinds);
+        // The public function comes first, then the private one
+        assert!(kinds[..3].contains(&(3, PanicKind::Unwrap)));
+        assert!(kinds[..3].contains(&(5, PanicKind::Index)));
+        assert!(kinds[..3].contains(&(6, PanicKind::Expect)));
+        assert_eq!(kinds[3], (14, PanicKind::Arithmetic));
+
+        let unwrap = &report.findings[0];
+        assert_eq!(unwrap.file, PathBuf::from("src/lib.rs"));
+        assert_eq!(unwrap.kind, PanicKind::Unwrap);
+        assert_eq!(unwrap.function.as_deref(), Some("parse"));
+        assert!(unwrap.public);
+        assert_eq!(unwrap.callers, 1);
+        assert!(unwrap.suggestion.contains('?'));
+
+        // Calls from the test module do not count
+        let sub = report.production().find(|f| f.line == 14).unwrap();
+        assert_eq!(sub.callers, 2);
+        assert!(!sub.public);
+        assert!(sub.suggestion.contains("checked_sub"));
+
+        let test_unwrap = report.findings.last().unwrap();
+        assert!(test_unwrap.in_test);
+        assert_eq!(test_unwrap.line, 23);
+        assert_eq!(test_unwrap.hotness, 0.0);
+        assert_eq!(report.counts()[&PanicKind::Unwrap], 1);
+    }
+
+    #[test]
+    fn test_panic_kinds_arithmetic_heuristics() {
+        assert!(has_arithmetic("let n = a - b;"));
+        assert!(has_arithmetic("total *= factor;"));
+        assert!(has_arithmetic("let avg = sum / count;"));
+        assert!(!has_arithmetic("count += 1;"));
+        assert!(!has_arithmetic("let half = n / 2;"));
+        assert!(!has_arithmetic("let x = y * 0.5;"));
+        assert!(!has_arithmetic("fn f<T: Clone + Send>(x: &T) -> T {"));
+        assert!(!has_arithmetic("let p = -offset;"));
+        assert!(has_index("let x = v[i];"));
+        assert!(!has_index("for x in [a, b] {"));
+        assert!(!has_index("let v: Vec<[u8; 4]> = vec![[0; 4]];"));
+    }
 }
@@ -3,27 +3,39 @@
 //! A fast, reliable ranking function for text search without requiring embeddings.
 //! Used for code search, symbol lookup, and as a fallback/complement to vector search.
 
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::Path;
+
+/// On-disk format version written by [`BM25Index::save`]
+const FORMAT_VERSION: u32 = 1;
 
 /// BM25 search index for fast text retrieval
-#[derive(Debug, Clone)]
+///
+/// Document frequencies and the total corpus length are maintained
+/// incrementally on every add, update and remove, so IDF is derived at query
+/// time and edits never require a pass over the whole corpus.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BM25Index {
+    /// Format version, checked on load
+    version: u32,
     /// Documents stored as (doc_id, tokens)
     documents: Vec<Document>,
-    /// Inverse document frequency for each term
-    idf: HashMap<String, f32>,
-    /// Average document length
-    avgdl: f32,
+    /// Number of documents containing each term
+    #[serde(skip)]
+    doc_freq: HashMap<String, u32>,
+    /// Sum of all document lengths
+    #[serde(skip)]
+    total_length: u64,
     /// Term saturation parameter (typically 1.2-2.0)
     k1: f32,
     /// Length normalization parameter (typically 0.75)
     b: f32,
-    /// Whether the index needs rebuilding
-    dirty: bool,
 }
 
 /// A document in the index
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct Document {
     /// Unique identifier
     id: String,
@@ -35,6 +47,26 @@ struct Document {
     length: u32,
 }
 
+impl Document {
+    fn new(id: String, text: String) -> Self {
+        let tokens = BM25Index::tokenize(&text);
+        let length = tokens.len() as u32;
+
+        // Build term frequency map
+        let mut term_freqs: HashMap<String, u32> = HashMap::new();
+        for token in tokens {
+            *term_freqs.entry(token).or_insert(0) += 1;
+        }
+
+        Self {
+            id,
+            text,
+            term_freqs,
+            length,
+        }
+    }
+}
+
 /// Search result with score
 #[derive(Debug, Clone)]
 pub struct BM25Result {
@@ -46,6 +78,17 @@ pub struct BM25Result {
     pub score: f32,
 }
 
+/// Size of a BM25 index
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BM25Stats {
+    /// Number of indexed documents
+    pub documents: usize,
+    /// Number of distinct terms
+    pub terms: usize,
+    /// Average document length in tokens
+    pub avg_doc_length: f32,
+}
+
 impl Default for BM25Index {
     fn default() -> Self {
         Self::new()
@@ -65,12 +108,12 @@ impl BM25Index {
     /// - `b`: Length normalization (0.0-1.0, higher = more penalty for long documents)
     pub fn with_params(k1: f32, b: f32) -> Self {
         Self {
+            version: FORMAT_VERSION,
             documents: Vec::new(),
-            idf: HashMap::new(),
-            avgdl: 0.0,
+            doc_freq: HashMap::new(),
+            total_length: 0,
             k1,
             b,
-            dirty: false,
         }
     }
 
@@ -80,56 +123,37 @@ impl BM25Index {
     /// - `id`: Unique document identifier
     /// - `text`: Document text to index
     pub fn add(&mut self, id: impl Into<String>, text: impl Into<String>) {
-        let id = id.into();
-        let text = text.into();
-
-        // Upsert: remove any existing document with the same ID
-        self.remove_all(&id);
-
-        let tokens = Self::tokenize(&text);
-        let length = tokens.len() as u32;
-
-        // Build term frequency map
-        let mut term_freqs: HashMap<String, u32> = HashMap::new();
-        for token in tokens {
-            *term_freqs.entry(token).or_insert(0) += 1;
-        }
-
-        self.documents.push(Document {
-            id,
-            text,
-            term_freqs,
-            length,
-        });
-        self.dirty = true;
+        self.update_document(id, text);
     }
 
     /// Add multiple documents at once (more efficient than individual adds)
     pub fn add_batch(&mut self, docs: impl IntoIterator<Item = (String, String)>) {
         for (id, text) in docs {
-            let tokens = Self::tokenize(&text);
-            let length = tokens.len() as u32;
+            self.insert(Document::new(id, text));
+        }
+    }
 
-            let mut term_freqs: HashMap<String, u32> = HashMap::new();
-            for token in tokens {
-                *term_freqs.entry(token).or_insert(0) += 1;
-            }
+    /// Index `text` under `id`, replacing any existing document with that ID.
+    ///
+    /// Only the terms of the old and new text are touched. Returns true if a
+    /// document was replaced.
+    pub fn update_document(&mut self, id: impl Into<String>, text: impl Into<String>) -> bool {
+        let id = id.into();
+        let replaced = self.remove_all(&id) > 0;
+        self.insert(Document::new(id, text.into()));
+        replaced
+    }
 
-            self.documents.push(Document {
-                id,
-                text,
-                term_freqs,
-                length,
-            });
-        }
-        self.dirty = true;
+    /// Remove every document with `id`, returning true if any was indexed
+    pub fn remove_document(&mut self, id: &str) -> bool {
+        self.remove_all(id) > 0
     }
 
     /// Remove first document matching ID (returns true if found)
     pub fn remove(&mut self, id: &str) -> bool {
         if let Some(pos) = self.documents.iter().position(|d| d.id == id) {
-            self.documents.remove(pos);
-            self.dirty = true;
+            let doc = self.documents.remove(pos);
+            self.uncount(&doc);
             true
         } else {
             false
@@ -138,57 +162,124 @@ impl BM25Index {
 
     /// Remove ALL documents matching ID (handles duplicates)
     pub fn remove_all(&mut self, id: &str) -> usize {
-        let before = self.documents.len();
-        self.documents.retain(|d| d.id != id);
-        let removed = before - self.documents.len();
-        if removed > 0 {
-            self.dirty = true;
+        let mut removed = Vec::new();
+        let mut kept = Vec::with_capacity(self.documents.len());
+        for doc in self.documents.drain(..) {
+            if doc.id == id {
+                removed.push(doc);
+            } else {
+                kept.push(doc);
+            }
         }
-        removed
+        self.documents = kept;
+        for doc in &removed {
+            self.uncount(doc);
+        }
+        removed.len()
     }
 
     /// Clear all documents
     pub fn clear(&mut self) {
         self.documents.clear();
-        self.idf.clear();
-        self.avgdl = 0.0;
-        self.dirty = false;
+        self.doc_freq.clear();
+        self.total_length = 0;
     }
 
-    /// Rebuild the index (compute IDF values)
-    /// Called automatically before search if dirty
+    /// Recount document frequencies and corpus length from the stored
+    /// documents. Only needed after deserializing; edits keep them current.
     pub fn rebuild(&mut self) {
-        if self.documents.is_empty() {
-            self.idf.clear();
-            self.avgdl = 0.0;
-            self.dirty = false;
-            return;
+        self.doc_freq.clear();
+        self.total_length = 0;
+        let documents = std::mem::take(&mut self.documents);
+        for doc in &documents {
+            self.count(doc);
         }
+        self.documents = documents;
+    }
 
-        let n = self.documents.len() as f32;
+    /// Write the index to `path` atomically (temp file + rename)
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let json = serde_json::to_string(self).context("Failed to serialize BM25 index")?;
+        let tmp = path.with_extension(format!("tmp.{}", std::process::id()));
+        std::fs::write(&tmp, json).with_context(|| format!("Failed to write {}", tmp.display()))?;
+        if let Err(e) = std::fs::rename(&tmp, path) {
+            let _ = std::fs::remove_file(&tmp);
+            return Err(e).context("Failed to atomically save BM25 index");
+        }
+        Ok(())
+    }
+
+    /// Load an index written by [`BM25Index::save`]
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read BM25 index {}", path.display()))?;
+        let mut index: Self = serde_json::from_str(&json)
+            .with_context(|| format!("Invalid BM25 index {}", path.display()))?;
+        if index.version != FORMAT_VERSION {
+            anyhow::bail!(
+                "Unsupported BM25 index version {} in {} (expected {})",
+                index.version,
+                path.display(),
+                FORMAT_VERSION
+            );
+        }
+        index.rebuild();
+        Ok(index)
+    }
+
+    /// Document count, distinct term count and average document length
+    pub fn stats(&self) -> BM25Stats {
+        BM25Stats {
+            documents: self.documents.len(),
+            terms: self.doc_freq.len(),
+            avg_doc_length: self.avgdl(),
+        }
+    }
 
-        // Compute average document length
-        let total_length: u32 = self.documents.iter().map(|d| d.length).sum();
-        self.avgdl = total_length as f32 / n;
+    fn insert(&mut self, doc: Document) {
+        self.count(&doc);
+        self.documents.push(doc);
+    }
+
+    fn count(&mut self, doc: &Document) {
+        self.total_length += u64::from(doc.length);
+        for term in doc.term_freqs.keys() {
+            *self.doc_freq.entry(term.clone()).or_insert(0) += 1;
+        }
+    }
 
-        // Compute document frequency for each term
-        let mut doc_freq: HashMap<String, u32> = HashMap::new();
-        for doc in &self.documents {
-            for term in doc.term_freqs.keys() {
-                *doc_freq.entry(term.clone()).or_insert(0) += 1;
+    fn uncount(&mut self, doc: &Document) {
+        self.total_length = self.total_length.saturating_sub(u64::from(doc.length));
+        for term in doc.term_freqs.keys() {
+            if let Some(df) = self.doc_freq.get_mut(term) {
+                *df = df.saturating_sub(1);
+                if *df == 0 {
+                    self.doc_freq.remove(term);
+                }
             }
         }
+    }
 
-        // Compute IDF for each term
-        // IDF = ln((N - df + 0.5) / (df + 0.5) + 1)
-        self.idf.clear();
-        for (term, df) in doc_freq {
-            let df = df as f32;
-            let idf = ((n - df + 0.5) / (df + 0.5) + 1.0).ln();
-            self.idf.insert(term, idf);
+    /// Average document length
+    fn avgdl(&self) -> f32 {
+        if self.documents.is_empty() {
+            0.0
+        } else {
+            self.total_length as f32 / self.documents.len() as f32
         }
+    }
 
-        self.dirty = false;
+    /// Inverse document frequency of a term, or None if no document has it
+    ///
+    /// IDF = ln((N - df + 0.5) / (df + 0.5) + 1)
+    fn idf(&self, term: &str) -> Option<f32> {
+        let df = *self.doc_freq.get(term)? as f32;
+        let n = self.documents.len() as f32;
+        Some(((n - df + 0.5) / (df + 0.5) + 1.0).ln())
     }
 
     /// Search the index and return ranked results
@@ -200,10 +291,11 @@ impl BM25Index {
     /// # Returns
     /// Vector of results sorted by score (descending)
     pub fn search(&mut self, query: &str, limit: usize) -> Vec<BM25Result> {
-        if self.dirty {
-            self.rebuild();
-        }
+        self.search_immutable(query, limit)
+    }
 
+    /// Search without modifying self
+    pub fn search_immutable(&self, query: &str, limit: usize) -> Vec<BM25Result> {
         if self.documents.is_empty() {
             return Vec::new();
         }
@@ -213,56 +305,26 @@ impl BM25Index {
             return Vec::new();
         }
 
-        // Score each document
-        let mut scores: Vec<(usize, f32)> = self
-            .documents
+        // Resolve IDF once per query term
+        let weighted: Vec<(&str, f32)> = query_tokens
             .iter()
-            .enumerate()
-            .map(|(i, doc)| (i, self.score_document(doc, &query_tokens)))
-            .filter(|(_, score)| *score > 0.0)
+            .filter_map(|t| Some((t.as_str(), self.idf(t)?)))
             .collect();
+        let avgdl = self.avgdl();
 
-        // Sort by score descending
-        scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-
-        // Return top results
-        scores
-            .into_iter()
-            .take(limit)
-            .map(|(i, score)| {
-                let doc = &self.documents[i];
-                BM25Result {
-                    id: doc.id.clone(),
-                    text: doc.text.clone(),
-                    score,
-                }
-            })
-            .collect()
-    }
-
-    /// Search without modifying self (requires index to be up-to-date)
-    pub fn search_immutable(&self, query: &str, limit: usize) -> Vec<BM25Result> {
-        // Immutable search should still provide best-effort results even if the
-        // mutable index is marked dirty.
-        if self.documents.is_empty() {
-            return Vec::new();
-        }
-
-        let query_tokens = Self::tokenize(query);
-        if query_tokens.is_empty() {
-            return Vec::new();
-        }
-
+        // Score each document
         let mut scores: Vec<(usize, f32)> = self
             .documents
             .iter()
             .enumerate()
-            .map(|(i, doc)| (i, self.score_document(doc, &query_tokens)))
+            .map(|(i, doc)| (i, self.score_document(doc, &weighted, avgdl)))
             .filter(|(_, score)| *score > 0.0)
             .collect();
 
+        // Sort by score descending
         scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
 
+        // Return top results
         scores
             .into_iter()
             .take(limit)
@@ -277,11 +339,10 @@ impl BM25Index {
             .collect()
     }
 
-    /// Compute BM25 score for a document given query tokens
-    fn score_document(&self, doc: &Document, query_tokens: &[String]) -> f32 {
+    /// Compute BM25 score for a document given IDF-weighted query tokens
+    fn score_document(&self, doc: &Document, query: &[(&str, f32)], avgdl: f32) -> f32 {
         let mut score = 0.0;
         let dl = doc.length as f32;
-        let avgdl = self.avgdl;
 
         // Guard against division by zero when document length or average
         // document length is zero.
@@ -289,18 +350,16 @@ impl BM25Index {
             return 0.0;
         }
 
-        for token in query_tokens {
-            if let Some(&idf) = self.idf.get(token) {
-                let tf = *doc.term_freqs.get(token).unwrap_or(&0) as f32;
-                if tf > 0.0 {
-                    // BM25 scoring formula
-                    let numerator = tf * (self.k1 + 1.0);
-                    let denominator = tf + self.k1 * (1.0 - self.b + self.b * (dl / avgdl));
-                    if denominator <= 0.0 {
-                        continue;
-                    }
-                    score += idf * (numerator / denominator);
+        for &(token, idf) in query {
+            let tf = *doc.term_freqs.get(token).unwrap_or(&0) as f32;
+            if tf > 0.0 {
+                // BM25 scoring formula
+                let numerator = tf * (self.k1 + 1.0);
+                let denominator = tf + self.k1 * (1.0 - self.b + self.b * (dl / avgdl));
+                if denominator <= 0.0 {
+                    continue;
                 }
+                score += idf * (numerator / denominator);
             }
         }
 
@@ -406,7 +465,7 @@ impl BM25Index {
 
     /// Get all unique terms in the index
     pub fn terms(&self) -> Vec<&str> {
-        self.idf.keys().map(|s| s.as_str()).collect()
+        self.doc_freq.keys().map(|s| s.as_str()).collect()
     }
 
     /// Check if a document ID exists
@@ -667,7 +726,7 @@ mod tests {
             term_freqs: term_freqs.clone(),
             length: tokens.len() as u32,
         });
-        index.dirty = true;
+        index.rebuild();
 
         assert_eq!(index.len(), 2);
         let removed = index.remove_all("dup");
@@ -701,9 +760,8 @@ mod tests {
         assert_eq!(results[0].id, "rare_doc");
 
         // Rare term should have higher IDF
-        index.rebuild();
-        let rare_idf = index.idf.get("rare").unwrap_or(&0.0);
-        let common_idf = index.idf.get("common").unwrap_or(&0.0);
+        let rare_idf = index.idf("rare").unwrap_or(0.0);
+        let common_idf = index.idf("common").unwrap_or(0.0);
         assert!(rare_idf > common_idf);
     }
 
@@ -720,4 +778,92 @@ mod tests {
         assert_eq!(index.k1, 1.5);
         assert_eq!(index.b, 0.75);
     }
+
+    #[test]
+    fn test_bm25_update_and_remove_keep_scores_exact() {
+        let mut index = BM25Index::new();
+        index.add("a", "parse config file");
+        index.add("b", "render widget tree");
+        index.add("c", "parse widget tree");
+
+        // Editing a document must score exactly like a fresh build of the
+        // same corpus, for both its new and its old terms.
+        assert!(index.update_document("b", "parse config value"));
+        let mut fresh = BM25Index::new();
+        fresh.add("a", "parse config file");
+        fresh.add("b", "parse config value");
+        fresh.add("c", "parse widget tree");
+        for query in ["config", "parse", "widget", "render"] {
+            // Updated documents move to the end, so compare ties by id
+            let mut got: Vec<(String, f32)> = index
+                .search(query, 10)
+                .into_iter()
+                .map(|r| (r.id, r.score))
+                .collect();
+            let mut want: Vec<(String, f32)> = fresh
+                .search(query, 10)
+                .into_iter()
+                .map(|r| (r.id, r.score))
+                .collect();
+            got.sort_by(|x, y| x.0.cmp(&y.0));
+            want.sort_by(|x, y| x.0.cmp(&y.0));
+            assert_eq!(got, want, "query {query}");
+        }
+        assert!(index.search("render", 10).is_empty());
+        assert!(!index.update_document("d", "render loop"));
+        assert_eq!(index.search("render", 10)[0].id, "d");
+
+        // Removing the only holder of a term drops the term; removing a
+        // holder of a shared term makes it rarer and so raises its IDF.
+        assert!(index.remove_document("d"));
+        assert!(!index.remove_document("d"));
+        assert!(!index.terms().contains(&"render"));
+        let before = index.idf("parse").unwrap();
+        assert!(index.remove_document("a"));
+        let after = index.idf("parse").unwrap();
+        assert!(after > before, "{after} <= {before}");
+        assert_eq!(
+            index.stats(),
+            BM25Stats {
+                documents: 2,
+                terms: 5,
+                avg_doc_length: 3.0,
+            }
+        );
+
+        index.clear();
+        assert_eq!(index.stats().terms, 0);
+        assert!(index.search("parse", 10).is_empty());
+    }
+
+    #[test]
+    fn test_bm25_save_load_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("index").join("bm25.json");
+
+        let mut index = BM25Index::with_params(1.2, 0.5);
+        index.add("getUser", "fn get_user(id: UserId) -> User");
+        index.add("setUser", "fn set_user(user: User)");
+        index.save(&path).unwrap();
+
+        let mut loaded = BM25Index::load(&path).unwrap();
+        assert_eq!(loaded.stats(), index.stats());
+        assert_eq!((loaded.k1, loaded.b), (1.2, 0.5));
+        let scores = |i: &mut BM25Index| -> Vec<(String, f32)> {
+            i.search("user id", 10)
+                .into_iter()
+                .map(|r| (r.id, r.score))
+                .collect()
+        };
+        assert_eq!(scores(&mut loaded), scores(&mut index));
+
+        // A loaded index keeps updating incrementally
+        loaded.update_document("setUser", "fn clear_cache()");
+        assert_eq!(loaded.search("user", 10).len(), 1);
+        assert_eq!(loaded.get("setUser"), Some("fn clear_cache()"));
+
+        std::fs::write(&path, "not json").unwrap();
+        assert!(BM25Index::load(&path).is_err());
+        assert!(BM25Index::load(&dir.path().join("missing.json")).is_err());
+    }
 }
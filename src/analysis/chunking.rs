@@ -0,0 +1,418 @@
+//! Chunking source files for retrieval
+//!
+//! Fixed-size windows cut functions in half, which hurts retrieval. Rust
+//! files are instead chunked along item boundaries from
+//! [`references::outline`](super::references::outline): one chunk per
+//! function, struct, enum, trait or impl, headed by its module path and
+//! carrying the symbol name and line range. Impl and trait blocks too large
+//! for one chunk are split into their members. Code between items (imports,
+//! module declarations) forms its own chunks. Oversized leaves and files in
+//! other languages fall back to fixed-size windows.
+
+use super::references::{outline, OutlineItem};
+use super::vector_store::{ChunkMetadata, ChunkType, CodeChunk, CodeChunker};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Splits a file into chunks for indexing
+pub trait Chunker: Send + Sync {
+    /// Chunks of `content`, the text of `file_path`, in file order
+    fn chunk(&self, content: &str, file_path: &Path) -> Vec<CodeChunk>;
+}
+
+/// Windows of whole lines up to a size, for languages without an outline
+#[derive(Debug, Clone)]
+pub struct FixedChunker {
+    /// Maximum chunk size in characters
+    pub max_chunk_size: usize,
+    /// Overlap between chunks
+    pub overlap: usize,
+}
+
+impl Default for FixedChunker {
+    fn default() -> Self {
+        let defaults = CodeChunker::default();
+        Self {
+            max_chunk_size: defaults.max_chunk_size,
+            overlap: defaults.overlap,
+        }
+    }
+}
+
+impl FixedChunker {
+    /// Create a chunker producing windows of at most `max_chunk_size` characters
+    pub fn new(max_chunk_size: usize) -> Self {
+        Self {
+            max_chunk_size,
+            ..Default::default()
+        }
+    }
+}
+
+impl Chunker for FixedChunker {
+    fn chunk(&self, content: &str, file_path: &Path) -> Vec<CodeChunk> {
+        CodeChunker {
+            max_chunk_size: self.max_chunk_size,
+            min_chunk_size: 0,
+            overlap: self.overlap,
+        }
+        .chunk_fixed_size(content, file_path, language(file_path))
+    }
+}
+
+impl Chunker for CodeChunker {
+    fn chunk(&self, content: &str, file_path: &Path) -> Vec<CodeChunk> {
+        CodeChunker::chunk(self, content, file_path)
+    }
+}
+
+/// One chunk per item for Rust, [`FixedChunker`] for everything else
+#[derive(Debug, Clone)]
+pub struct CodeAwareChunker {
+    /// Items larger than this (in characters) are split
+    pub max_chunk_size: usize,
+    /// Code between items shorter than this is dropped
+    pub min_chunk_size: usize,
+    /// Used for files without an outline
+    pub fallback: FixedChunker,
+}
+
+impl Default for CodeAwareChunker {
+    fn default() -> Self {
+        let defaults = CodeChunker::default();
+        Self {
+            max_chunk_size: defaults.max_chunk_size,
+            min_chunk_size: defaults.min_chunk_size,
+            fallback: FixedChunker::default(),
+        }
+    }
+}
+
+/// The file being chunked, shared by all its chunks
+struct Source<'a> {
+    text: &'a str,
+    path: Arc<Path>,
+    language: Arc<str>,
+}
+
+impl Source<'_> {
+    fn line(&self, byte: usize) -> usize {
+        self.text[..byte].matches('\n').count() + 1
+    }
+}
+
+impl CodeAwareChunker {
+    /// Create a chunker splitting items larger than `max_chunk_size` characters
+    pub fn new(max_chunk_size: usize) -> Self {
+        Self {
+            max_chunk_size,
+            fallback: FixedChunker::new(max_chunk_size),
+            ..Default::default()
+        }
+    }
+
+    fn push_item(
+        &self,
+        source: &Source,
+        item: &OutlineItem,
+        parent: Option<&OutlineItem>,
+        chunks: &mut Vec<CodeChunk>,
+    ) {
+        let size = item.span.len();
+        if size > self.max_chunk_size && !item.children.is_empty() {
+            for (n, child) in item.children.iter().enumerate() {
+                // The block's own header goes with its first member
+                let start = if n == 0 {
+                    item.span.start
+                } else {
+                    child.span.start
+                };
+                let child = OutlineItem {
+                    span: start..child.span.end,
+                    ..child.clone()
+                };
+                self.push_item(source, &child, Some(item), chunks);
+            }
+            return;
+        }
+
+        let (header, symbol) = match parent {
+            Some(parent) => (
+                format!("// {} ({} {})", item.module, parent.keyword, parent.name),
+                format!("{}::{}", parent.self_type(), item.name),
+            ),
+            None if item.keyword == "impl" => {
+                (format!("// {}", item.module), item.self_type().to_string())
+            }
+            None => (format!("// {}", item.module), item.name.clone()),
+        };
+        self.push_span(
+            source,
+            item.span.clone(),
+            &header,
+            Some(&symbol),
+            chunk_type(item),
+            chunks,
+        );
+    }
+
+    /// Code between items, dropped when it is only whitespace or too short
+    fn push_gap(
+        &self,
+        source: &Source,
+        span: std::ops::Range<usize>,
+        module: &str,
+        chunks: &mut Vec<CodeChunk>,
+    ) {
+        let gap = &source.text[span.clone()];
+        let trimmed = gap.trim();
+        if trimmed.len() < self.min_chunk_size.max(1) {
+            return;
+        }
+        let start = span.start + (gap.len() - gap.trim_start().len());
+        let end = start + trimmed.len();
+        let chunk_type = if trimmed.lines().any(|line| {
+            let line = line.trim_start();
+            line.starts_with("use ") || line.starts_with("pub use ")
+        }) {
+            ChunkType::Import
+        } else {
+            ChunkType::CodeBlock
+        };
+        self.push_span(
+            source,
+            start..end,
+            &format!("// {}", module),
+            None,
+            chunk_type,
+            chunks,
+        );
+    }
+
+    /// `span` as one chunk, or as windows of whole lines if it is too large
+    fn push_span(
+        &self,
+        source: &Source,
+        span: std::ops::Range<usize>,
+        header: &str,
+        symbol: Option<&str>,
+        chunk_type: ChunkType,
+        chunks: &mut Vec<CodeChunk>,
+    ) {
+        let mut start = span.start;
+        while start < span.end {
+            let mut end = span.end;
+            if end - start > self.max_chunk_size {
+                // Cut after the last line that fits, or the first line if none does
+                let window = &source.text[start..span.end];
+                let mut cut = self.max_chunk_size;
+                while !window.is_char_boundary(cut) {
+                    cut -= 1;
+                }
+                end = window[..cut]
+                    .rfind('\n')
+                    .or_else(|| window.find('\n'))
+                    .map_or(span.end, |n| start + n + 1);
+            }
+            let body = source.text[start..end].trim_end();
+            if body.trim().is_empty() {
+                start = end;
+                continue;
+            }
+            let content = format!("{}\n{}", header, body);
+            let mut metadata = ChunkMetadata::new(
+                source.path.clone(),
+                source.line(start),
+                source.line(start + body.len()),
+                chunk_type,
+                source.language.clone(),
+                &content,
+            );
+            if let Some(symbol) = symbol {
+                metadata = metadata.with_symbol(symbol);
+            }
+            chunks.push(CodeChunk::new(content, metadata));
+            start = end;
+        }
+    }
+}
+
+impl Chunker for CodeAwareChunker {
+    fn chunk(&self, content: &str, file_path: &Path) -> Vec<CodeChunk> {
+        if file_path.extension().and_then(|e| e.to_str()) != Some("rs") {
+            return self.fallback.chunk(content, file_path);
+        }
+        let items = outline(file_path, content);
+        let Some(first) = items.first() else {
+            return self.fallback.chunk(content, file_path);
+        };
+        let source = Source {
+            text: content,
+            path: Arc::from(file_path),
+            language: Arc::from(language(file_path)),
+        };
+
+        let mut chunks = Vec::new();
+        let mut cursor = 0;
+        let mut module = first.module.clone();
+        for item in &items {
+            self.push_gap(&source, cursor..item.span.start, &module, &mut chunks);
+            self.push_item(&source, item, None, &mut chunks);
+            cursor = item.span.end;
+            module.clone_from(&item.module);
+        }
+        self.push_gap(&source, cursor..content.len(), &module, &mut chunks);
+        chunks
+    }
+}
+
+fn chunk_type(item: &OutlineItem) -> ChunkType {
+    match item.keyword.as_str() {
+        "fn" if item.is_test => ChunkType::Test,
+        "fn" => ChunkType::Function,
+        "struct" | "union" => ChunkType::Struct,
+        "enum" => ChunkType::Enum,
+        "trait" => ChunkType::Trait,
+        "impl" => ChunkType::Impl,
+        "const" | "static" => ChunkType::Constant,
+        _ => ChunkType::CodeBlock,
+    }
+}
+
+/// Language identifier from the file extension
+fn language(path: &Path) -> &str {
+    match path.extension().and_then(|e| e.to_str()).unwrap_or("") {
+        "rs" => "rust",
+        "py" => "python",
+        "js" | "jsx" => "javascript",
+        "ts" | "tsx" => "typescript",
+        ext => ext,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"//! Geometry helpers
+
+use std::fmt;
+use std::ops::Add;
+
+/// A point in the plane.
+#[derive(Debug, Clone, Copy)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // "{" in a string must not end the item
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+/// Distance from the origin, in taxicab metric.
+pub fn manhattan(p: Point) -> i32 {
+    let dx = p.x.abs();
+    let dy = p.y.abs();
+    dx + dy
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn origin_is_zero() {
+        assert_eq!(manhattan(Point { x: 0, y: 0 }), 0);
+    }
+}
+"#;
+
+    fn boundaries(chunks: &[CodeChunk]) -> Vec<(usize, usize, Option<&str>)> {
+        chunks
+            .iter()
+            .map(|c| {
+                (
+                    c.metadata.start_line,
+                    c.metadata.end_line,
+                    c.metadata.symbol_name.as_deref(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_code_aware_chunks_follow_items_where_fixed_windows_split_them() {
+        let path = Path::new("src/geometry.rs");
+        let chunker = CodeAwareChunker {
+            min_chunk_size: 10,
+            ..CodeAwareChunker::new(2000)
+        };
+        let chunks = chunker.chunk(SAMPLE, path);
+        assert_eq!(
+            boundaries(&chunks),
+            vec![
+                (1, 4, None),
+                (6, 11, Some("Point")),
+                (13, 18, Some("Point")),
+                (20, 25, Some("manhattan")),
+                (27, 29, None),
+                (31, 34, Some("origin_is_zero")),
+            ]
+        );
+        let types: Vec<ChunkType> = chunks.iter().map(|c| c.metadata.chunk_type).collect();
+        assert_eq!(
+            types,
+            vec![
+                ChunkType::Import,
+                ChunkType::Struct,
+                ChunkType::Impl,
+                ChunkType::Function,
+                ChunkType::Import,
+                ChunkType::Test,
+            ]
+        );
+        // Each chunk is headed by its module path and starts at its docs
+        assert!(chunks[1]
+            .content
+            .starts_with("// crate::geometry\n/// A point in the plane.\n#[derive"));
+        assert!(chunks[5]
+            .content
+            .starts_with("// crate::geometry::tests\n    #[test]"));
+
+        // Windows of a similar size cut the impl after its header line and
+        // `manhattan` between its doc comment and body
+        let fixed = FixedChunker {
+            max_chunk_size: 250,
+            overlap: 0,
+        }
+        .chunk(SAMPLE, path);
+        assert_eq!(
+            boundaries(&fixed),
+            vec![(1, 13, None), (14, 20, None), (21, 35, None)]
+        );
+    }
+
+    #[test]
+    fn test_oversized_impl_splits_into_members_and_other_files_use_windows() {
+        let source = "impl Stack {\n    /// Push\n    pub fn push(&mut self, v: u8) {\n        self.0.push(v);\n    }\n\n    pub fn pop(&mut self) -> Option<u8> {\n        self.0.pop()\n    }\n}\n";
+        let chunker = CodeAwareChunker::new(100);
+        let chunks = chunker.chunk(source, Path::new("/work/app/src/stack.rs"));
+        assert_eq!(
+            boundaries(&chunks),
+            vec![(1, 5, Some("Stack::push")), (7, 9, Some("Stack::pop"))]
+        );
+        assert!(chunks[1]
+            .content
+            .starts_with("// crate::stack (impl Stack)\n    pub fn pop"));
+
+        let text = "line one\nline two\nline three\n";
+        let chunks = chunker.chunk(text, Path::new("notes.txt"));
+        assert!(!chunks.is_empty());
+        assert_eq!(&*chunks[0].metadata.language, "txt");
+        assert!(chunks.iter().all(|c| c.metadata.symbol_name.is_none()));
+    }
+}
@@ -10,7 +10,7 @@
 //! - Architecture diagram generation
 //! - Multiple output formats (DOT, Mermaid, ASCII)
 
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::io::{self, Write as IoWrite};
 use std::sync::atomic::{AtomicU64, Ordering};
 
@@ -515,6 +515,132 @@ impl CodeGraph {
         rec_stack.remove(node_id);
     }
 
+    /// Strongly connected components that form a cycle: more than one node,
+    /// or one node with an edge to itself. Uses Tarjan's algorithm, so it is
+    /// linear in the size of the graph. Members are node IDs ordered by
+    /// qualified name, and components are ordered by their first member.
+    pub fn cycle_components(&self) -> Vec<Vec<String>> {
+        let key = |id: &str| {
+            let name = self.nodes.get(id).map_or("", |n| n.qualified_name.as_str());
+            (name.to_string(), id.to_string())
+        };
+        let mut order: Vec<&str> = self.nodes.keys().map(String::as_str).collect();
+        order.sort_by_key(|id| key(id));
+        let successors: HashMap<&str, Vec<&str>> = order
+            .iter()
+            .map(|&id| {
+                let mut targets: Vec<&str> = self
+                    .outgoing_edges(id)
+                    .into_iter()
+                    .map(|e| e.target.as_str())
+                    .filter(|t| self.nodes.contains_key(*t))
+                    .collect();
+                targets.sort_by_key(|t| key(t));
+                targets.dedup();
+                (id, targets)
+            })
+            .collect();
+
+        let mut index: HashMap<&str, usize> = HashMap::new();
+        let mut lowlink: HashMap<&str, usize> = HashMap::new();
+        let mut stack: Vec<&str> = Vec::new();
+        let mut on_stack: HashSet<&str> = HashSet::new();
+        let mut components = Vec::new();
+
+        for &root in &order {
+            if index.contains_key(root) {
+                continue;
+            }
+            // Explicit call stack of (node, next successor), so deep graphs
+            // cannot overflow the thread's stack
+            let mut calls: Vec<(&str, usize)> = Vec::new();
+            index.insert(root, index.len());
+            lowlink.insert(root, index[root]);
+            stack.push(root);
+            on_stack.insert(root);
+            calls.push((root, 0));
+
+            while let Some((node, next)) = calls.last_mut() {
+                let node = *node;
+                if let Some(&target) = successors[node].get(*next) {
+                    *next += 1;
+                    if !index.contains_key(target) {
+                        index.insert(target, index.len());
+                        lowlink.insert(target, index[target]);
+                        stack.push(target);
+                        on_stack.insert(target);
+                        calls.push((target, 0));
+                    } else if on_stack.contains(target) {
+                        let low = lowlink[node].min(index[target]);
+                        lowlink.insert(node, low);
+                    }
+                    continue;
+                }
+                calls.pop();
+                if let Some(&(parent, _)) = calls.last() {
+                    let low = lowlink[parent].min(lowlink[node]);
+                    lowlink.insert(parent, low);
+                }
+                if lowlink[node] == index[node] {
+                    let mut component = Vec::new();
+                    while let Some(member) = stack.pop() {
+                        on_stack.remove(member);
+                        component.push(member);
+                        if member == node {
+                            break;
+                        }
+                    }
+                    if component.len() > 1 || successors[node].contains(&node) {
+                        component.sort_by_key(|id| key(id));
+                        components.push(component.into_iter().map(str::to_string).collect());
+                    }
+                }
+            }
+        }
+
+        components.sort_by_key(|c: &Vec<String>| key(&c[0]));
+        components
+    }
+
+    /// Each dependency cycle with the edges forming it and a suggested
+    /// break point: the edge with the lowest weight (e.g. fewest
+    /// references), ties going to the first edge by name.
+    pub fn dependency_cycles(&self) -> Vec<DependencyCycle> {
+        let name = |id: &str| {
+            self.nodes
+                .get(id)
+                .map_or_else(|| id.to_string(), |n| n.qualified_name.clone())
+        };
+        self.cycle_components()
+            .into_iter()
+            .filter_map(|component| {
+                let members: HashSet<&String> = component.iter().collect();
+                // Parallel edges between two nodes are merged
+                let mut weights: BTreeMap<(String, String), f32> = BTreeMap::new();
+                for edge in &self.edges {
+                    if members.contains(&edge.source) && members.contains(&edge.target) {
+                        *weights
+                            .entry((name(&edge.source), name(&edge.target)))
+                            .or_insert(0.0) += edge.weight;
+                    }
+                }
+                let edges: Vec<CycleEdge> = weights
+                    .into_iter()
+                    .map(|((from, to), weight)| CycleEdge { from, to, weight })
+                    .collect();
+                let break_at = edges
+                    .iter()
+                    .min_by(|a, b| a.weight.total_cmp(&b.weight))?
+                    .clone();
+                Some(DependencyCycle {
+                    members: component.iter().map(|id| name(id)).collect(),
+                    edges,
+                    break_at,
+                })
+            })
+            .collect()
+    }
+
     /// Calculate metrics for a node
     pub fn node_metrics(&self, node_id: &str) -> NodeMetrics {
         let in_degree = self.incoming_edges(node_id).len();
@@ -592,6 +718,25 @@ pub struct NodeMetrics {
     pub centrality: f32,
 }
 
+/// An edge inside a dependency cycle, by qualified names
+#[derive(Debug, Clone, PartialEq)]
+pub struct CycleEdge {
+    pub from: String,
+    pub to: String,
+    pub weight: f32,
+}
+
+/// A set of nodes that depend on each other
+#[derive(Debug, Clone, PartialEq)]
+pub struct DependencyCycle {
+    /// Qualified names, sorted
+    pub members: Vec<String>,
+    /// Edges between members, sorted by source then target
+    pub edges: Vec<CycleEdge>,
+    /// The weakest edge; removing it is the cheapest way to break the cycle
+    pub break_at: CycleEdge,
+}
+
 /// Output format for graph rendering
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OutputFormat {
@@ -1241,6 +1386,48 @@ mod tests {
         assert!(!cycles.is_empty());
     }
 
+    #[test]
+    fn test_dependency_cycles_are_canonical_with_weakest_edge() {
+        let mut graph = CodeGraph::new("test");
+        for name in ["e", "d", "c", "b", "a", "solo"] {
+            graph.add_node(GraphNode::new(name, NodeType::Module));
+        }
+        let mut link = |from: &str, to: &str, weight: f32| {
+            let (from, to) = (graph.name_index[from].clone(), graph.name_index[to].clone());
+            graph.add_edge(GraphEdge::new(&from, &to, EdgeType::Imports).with_weight(weight));
+        };
+        link("c", "d", 4.0);
+        link("d", "c", 2.0);
+        link("a", "b", 3.0);
+        link("b", "c", 1.0);
+        link("b", "a", 1.0);
+        link("b", "a", 5.0);
+        link("e", "e", 1.0);
+
+        let cycles = graph.dependency_cycles();
+        let members: Vec<_> = cycles.iter().map(|c| c.members.clone()).collect();
+        assert_eq!(members, [vec!["a", "b"], vec!["c", "d"], vec!["e"]]);
+        // Parallel edges merge, so b -> a weighs 6 and a -> b is weakest
+        assert_eq!(cycles[0].edges.len(), 2);
+        assert_eq!(cycles[0].break_at.from, "a");
+        assert_eq!(cycles[0].break_at.weight, 3.0);
+        assert_eq!(cycles[1].break_at.from, "d");
+        assert_eq!(graph.dependency_cycles(), cycles);
+
+        // A long chain closing on itself stays one component
+        let mut chain = CodeGraph::new("chain");
+        let ids: Vec<String> = (0..5000)
+            .map(|i| chain.add_node(GraphNode::new(&format!("m{i:04}"), NodeType::Module)))
+            .collect();
+        for pair in ids.windows(2) {
+            chain.add_edge(GraphEdge::new(&pair[0], &pair[1], EdgeType::Imports));
+        }
+        chain.add_edge(GraphEdge::new(&ids[4999], &ids[0], EdgeType::Imports));
+        let components = chain.cycle_components();
+        assert_eq!(components.len(), 1);
+        assert_eq!(components[0].len(), 5000);
+    }
+
     #[test]
     fn test_code_graph_node_metrics() {
         let mut graph = CodeGraph::new("test");
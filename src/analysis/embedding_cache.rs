@@ -0,0 +1,275 @@
+//! Persistent cache of chunk embeddings
+//!
+//! Re-indexing mostly sees chunks that were embedded before. The cache keys
+//! vectors on the SHA-256 of the chunk text for one embedding model, keeps
+//! the most recently used ones up to a fixed capacity, and persists them so
+//! later sessions start warm. Vectors from different models are not
+//! comparable, so the first lookup for a new model empties the cache.
+//!
+//! Wrap a backend with [`EmbeddingBackend::cached`](super::vector_store::EmbeddingBackend::cached)
+//! to route its embeds through a cache.
+
+use anyhow::{Context, Result};
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use tracing::{debug, warn};
+
+/// Entries kept unless a capacity is given: about 30 MB of 384-dim vectors
+pub const DEFAULT_CAPACITY: usize = 20_000;
+
+type ChunkHash = [u8; 32];
+
+/// On-disk form, least recently used entry first
+#[derive(Serialize, Deserialize)]
+struct Snapshot {
+    model: Option<String>,
+    entries: Vec<(ChunkHash, Vec<f32>)>,
+}
+
+struct Inner {
+    /// Model the cached vectors came from
+    model: Option<String>,
+    entries: LruCache<ChunkHash, Vec<f32>>,
+}
+
+/// Hit/miss counters for an [`EmbeddingCache`]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct EmbeddingCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+    pub capacity: usize,
+}
+
+impl EmbeddingCacheStats {
+    /// Fraction of lookups served from the cache
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
+}
+
+/// Bounded LRU cache of embeddings keyed on `(model, sha256(chunk))`
+pub struct EmbeddingCache {
+    inner: Mutex<Inner>,
+    path: Option<PathBuf>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    /// Whether there are entries not yet written to `path`
+    dirty: AtomicBool,
+}
+
+impl EmbeddingCache {
+    /// In-memory cache holding at most `capacity` vectors
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                model: None,
+                entries: LruCache::new(NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN)),
+            }),
+            path: None,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            dirty: AtomicBool::new(false),
+        }
+    }
+
+    /// Cache persisted at `path`, loading whatever an earlier session saved.
+    /// An unreadable file is logged and replaced on the next save.
+    pub fn open(path: impl Into<PathBuf>, capacity: usize) -> Self {
+        let mut cache = Self::new(capacity);
+        let path = path.into();
+        match Self::load(&path) {
+            Ok(Some(snapshot)) => {
+                let inner = cache.inner.get_mut().unwrap_or_else(|e| e.into_inner());
+                inner.model = snapshot.model;
+                for (hash, vector) in snapshot.entries {
+                    inner.entries.put(hash, vector);
+                }
+                debug!(
+                    "Loaded {} cached embeddings from {}",
+                    inner.entries.len(),
+                    path.display()
+                );
+            }
+            Ok(None) => {}
+            Err(e) => warn!("Ignoring embedding cache {}: {:#}", path.display(), e),
+        }
+        cache.path = Some(path);
+        cache
+    }
+
+    /// `~/.local/share/selfware/embeddings.cache` or the platform equivalent
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::data_local_dir().map(|dir| dir.join("selfware").join("embeddings.cache"))
+    }
+
+    fn load(path: &Path) -> Result<Option<Snapshot>> {
+        let data = match std::fs::read(path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).context("Failed to read embedding cache"),
+        };
+        let (snapshot, _): (Snapshot, usize) =
+            bincode::serde::decode_from_slice(&data, bincode::config::standard())
+                .context("Corrupt embedding cache")?;
+        Ok(Some(snapshot))
+    }
+
+    fn hash(text: &str) -> ChunkHash {
+        Sha256::digest(text.as_bytes()).into()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Drop every entry if they came from a model other than `model`
+    fn switch_model(inner: &mut Inner, model: &str) -> bool {
+        if inner.model.as_deref() == Some(model) {
+            return false;
+        }
+        if !inner.entries.is_empty() {
+            debug!(
+                "Embedding model changed from {:?} to {}; clearing {} cached vectors",
+                inner.model,
+                model,
+                inner.entries.len()
+            );
+        }
+        inner.entries.clear();
+        inner.model = Some(model.to_string());
+        true
+    }
+
+    /// Cached vector for `text` embedded by `model`
+    pub fn get(&self, model: &str, text: &str) -> Option<Vec<f32>> {
+        let mut inner = self.lock();
+        if Self::switch_model(&mut inner, model) {
+            self.dirty.store(true, Ordering::Relaxed);
+        }
+        let found = inner.entries.get(&Self::hash(text)).cloned();
+        let counter = if found.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        found
+    }
+
+    /// Remember the vector `model` produced for `text`
+    pub fn insert(&self, model: &str, text: &str, vector: Vec<f32>) {
+        let mut inner = self.lock();
+        Self::switch_model(&mut inner, model);
+        inner.entries.put(Self::hash(text), vector);
+        self.dirty.store(true, Ordering::Relaxed);
+    }
+
+    pub fn stats(&self) -> EmbeddingCacheStats {
+        let inner = self.lock();
+        EmbeddingCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: inner.entries.len(),
+            capacity: inner.entries.cap().get(),
+        }
+    }
+
+    /// Write the cache to its path (temp file + rename). A no-op for
+    /// in-memory caches and when nothing changed since the last save.
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if !self.dirty.swap(false, Ordering::Relaxed) {
+            return Ok(());
+        }
+        let snapshot = {
+            let inner = self.lock();
+            Snapshot {
+                model: inner.model.clone(),
+                entries: inner
+                    .entries
+                    .iter()
+                    .rev()
+                    .map(|(hash, vector)| (*hash, vector.clone()))
+                    .collect(),
+            }
+        };
+        let data = bincode::serde::encode_to_vec(&snapshot, bincode::config::standard())?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension(format!("tmp.{}", std::process::id()));
+        std::fs::write(&tmp, &data)?;
+        if let Err(e) = std::fs::rename(&tmp, path) {
+            let _ = std::fs::remove_file(&tmp);
+            self.dirty.store(true, Ordering::Relaxed);
+            return Err(e).context("Failed to atomically save embedding cache");
+        }
+        Ok(())
+    }
+}
+
+impl Drop for EmbeddingCache {
+    fn drop(&mut self) {
+        if let Err(e) = self.save() {
+            warn!("Failed to save embedding cache: {:#}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lru_eviction_and_stats() {
+        let cache = EmbeddingCache::new(2);
+        cache.insert("m", "a", vec![1.0]);
+        cache.insert("m", "b", vec![2.0]);
+        assert_eq!(cache.get("m", "a"), Some(vec![1.0]));
+        cache.insert("m", "c", vec![3.0]);
+
+        // "b" was least recently used
+        assert_eq!(cache.get("m", "b"), None);
+        assert_eq!(cache.get("m", "c"), Some(vec![3.0]));
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses), (2, 1));
+        assert_eq!((stats.entries, stats.capacity), (2, 2));
+        assert!((stats.hit_rate() - 2.0 / 3.0).abs() < 1e-9);
+
+        // Another model's vectors are never served
+        assert_eq!(cache.get("other", "a"), None);
+        assert_eq!(cache.stats().entries, 0);
+    }
+
+    #[test]
+    fn test_persists_across_opens() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("embeddings.cache");
+        {
+            let cache = EmbeddingCache::open(&path, 10);
+            cache.insert("m", "fn main() {}", vec![0.5, 0.25]);
+        }
+        assert!(path.exists());
+
+        let cache = EmbeddingCache::open(&path, 10);
+        assert_eq!(cache.get("m", "fn main() {}"), Some(vec![0.5, 0.25]));
+
+        std::fs::write(&path, b"not a cache").unwrap();
+        let cache = EmbeddingCache::open(&path, 10);
+        assert_eq!(cache.stats().entries, 0);
+    }
+}
@@ -0,0 +1,234 @@
+//! Per-file insight for the interactive garden
+//!
+//! A quick, local look at one file: the symbols it defines (as code graph
+//! nodes) and the technical debt visible in its source. Nothing here calls
+//! the model, so it is cheap enough to run on every selection.
+
+use super::code_graph::{GraphNode, NodeType};
+use super::tech_debt::{DebtItem, DebtMetrics, DebtSeverity, DebtType};
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+/// Lines between two definitions after which a function is flagged as long
+const LONG_FUNCTION_LINES: usize = 100;
+
+/// Lines after which a whole file is flagged as oversized
+const LARGE_FILE_LINES: usize = 1000;
+
+/// Comment markers that record known debt, most severe first
+const DEBT_MARKERS: &[(&str, DebtSeverity)] = &[
+    ("FIXME", DebtSeverity::Medium),
+    ("HACK", DebtSeverity::Medium),
+    ("XXX", DebtSeverity::Medium),
+    ("TODO", DebtSeverity::Low),
+];
+
+/// Symbols and debt found in a single file
+#[derive(Debug, Clone)]
+pub struct FileInsight {
+    pub path: PathBuf,
+    pub lines: usize,
+    pub symbols: Vec<GraphNode>,
+    pub debt: Vec<DebtItem>,
+    pub metrics: DebtMetrics,
+}
+
+impl FileInsight {
+    /// Read and inspect `path`.
+    pub fn inspect(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Ok(Self::from_source(path, &content))
+    }
+
+    /// Inspect already-loaded source text.
+    pub fn from_source(path: &Path, content: &str) -> Self {
+        let path_str = path.display().to_string();
+        let lines: Vec<&str> = content.lines().collect();
+
+        let symbols = if path.extension().is_some_and(|ext| ext == "rs") {
+            rust_symbols(&lines, &path_str)
+        } else {
+            Vec::new()
+        };
+
+        let mut debt = marker_debt(&lines, path);
+        debt.extend(long_function_debt(&symbols, lines.len(), path));
+        if lines.len() > LARGE_FILE_LINES {
+            debt.push(
+                DebtItem::new(DebtType::Architecture, "Large file")
+                    .with_description(format!(
+                        "{} lines; consider splitting into smaller modules",
+                        lines.len()
+                    ))
+                    .with_file(path),
+            );
+        }
+
+        let metrics = DebtMetrics::calculate(&debt);
+        Self {
+            path: path.to_path_buf(),
+            lines: lines.len(),
+            symbols,
+            debt,
+            metrics,
+        }
+    }
+}
+
+fn rust_symbols(lines: &[&str], path: &str) -> Vec<GraphNode> {
+    let Ok(patterns) = crate::tools::search::build_symbol_patterns("all", "") else {
+        return Vec::new();
+    };
+
+    let mut symbols = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("//") {
+            continue;
+        }
+        // Patterns are ordered so `pub const fn` is reported as a function
+        let found = patterns.iter().find_map(|(regex, kind)| {
+            let caps = regex.captures(trimmed)?;
+            let name = caps.get(1).or_else(|| caps.get(2))?.as_str();
+            Some((name, *kind))
+        });
+        let Some((name, kind)) = found else {
+            continue;
+        };
+        let mut node = GraphNode::new(name, node_type(kind))
+            .in_file(path)
+            .at_line((index + 1) as u32);
+        if trimmed.starts_with("pub") {
+            node = node.with_visibility("pub");
+        }
+        symbols.push(node);
+    }
+    symbols
+}
+
+fn node_type(kind: &str) -> NodeType {
+    match kind {
+        "function" => NodeType::Function,
+        "struct" => NodeType::Struct,
+        "enum" => NodeType::Enum,
+        "trait" => NodeType::Trait,
+        "impl" => NodeType::Impl,
+        "const" => NodeType::Const,
+        "type" => NodeType::TypeAlias,
+        _ => NodeType::Module,
+    }
+}
+
+fn marker_debt(lines: &[&str], path: &Path) -> Vec<DebtItem> {
+    let mut debt = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        let Some(comment) = line.find("//").or_else(|| line.find('#')) else {
+            continue;
+        };
+        let comment = &line[comment..];
+        let Some((marker, severity)) = DEBT_MARKERS
+            .iter()
+            .find(|(marker, _)| comment.contains(marker))
+        else {
+            continue;
+        };
+        let note = comment
+            .split_once(marker)
+            .map(|(_, rest)| rest.trim_start_matches([':', ' ', ')']).trim())
+            .unwrap_or_default();
+        debt.push(
+            DebtItem::new(
+                DebtType::CodeStyle,
+                format!("{} at line {}", marker, index + 1),
+            )
+            .with_severity(*severity)
+            .with_description(note)
+            .with_file(path),
+        );
+    }
+    debt
+}
+
+fn long_function_debt(symbols: &[GraphNode], total_lines: usize, path: &Path) -> Vec<DebtItem> {
+    let starts: Vec<usize> = symbols
+        .iter()
+        .filter_map(|s| s.line_number.map(|l| l as usize))
+        .collect();
+
+    symbols
+        .iter()
+        .enumerate()
+        .filter(|(_, symbol)| symbol.node_type == NodeType::Function)
+        .filter_map(|(i, symbol)| {
+            let start = symbol.line_number? as usize;
+            let end = starts.get(i + 1).copied().unwrap_or(total_lines + 1);
+            let span = end.saturating_sub(start);
+            (span > LONG_FUNCTION_LINES).then(|| {
+                DebtItem::new(
+                    DebtType::Complexity,
+                    format!("Long function `{}`", symbol.name),
+                )
+                .with_description(format!("~{} lines starting at line {}", span, start))
+                .with_file(path)
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rust_symbols_and_markers() {
+        let source = "pub struct Garden;\n\
+                      // TODO: water the plants\n\
+                      impl Garden {\n\
+                          pub async fn tend(&self) {}\n\
+                      }\n\
+                      fn helper() {} // FIXME avoid allocation\n";
+        let insight = FileInsight::from_source(Path::new("src/garden.rs"), source);
+
+        let names: Vec<_> = insight
+            .symbols
+            .iter()
+            .map(|s| (s.name.as_str(), s.node_type, s.line_number))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("Garden", NodeType::Struct, Some(1)),
+                ("Garden", NodeType::Impl, Some(3)),
+                ("tend", NodeType::Function, Some(4)),
+                ("helper", NodeType::Function, Some(6)),
+            ]
+        );
+        assert_eq!(insight.symbols[0].visibility.as_deref(), Some("pub"));
+
+        assert_eq!(insight.debt.len(), 2);
+        assert_eq!(insight.debt[0].title, "TODO at line 2");
+        assert_eq!(insight.debt[0].description, "water the plants");
+        assert_eq!(insight.debt[1].severity, DebtSeverity::Medium);
+        assert_eq!(insight.metrics.total_items, 2);
+    }
+
+    #[test]
+    fn test_long_function_and_large_file_debt() {
+        let mut source = String::from("fn long() {\n");
+        source.push_str(&"    step();\n".repeat(1100));
+        source.push_str("}\nfn short() {}\n");
+        let insight = FileInsight::from_source(Path::new("big.rs"), &source);
+
+        let titles: Vec<_> = insight.debt.iter().map(|d| d.title.as_str()).collect();
+        assert_eq!(titles, vec!["Long function `long`", "Large file"]);
+    }
+
+    #[test]
+    fn test_non_rust_files_have_markers_but_no_symbols() {
+        let insight =
+            FileInsight::from_source(Path::new("run.py"), "def go():\n    pass  # HACK\n");
+        assert!(insight.symbols.is_empty());
+        assert_eq!(insight.debt.len(), 1);
+    }
+}
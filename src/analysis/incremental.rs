@@ -0,0 +1,217 @@
+//! Incremental `selfware analyze --since <ref>`
+//!
+//! Instead of surveying the whole tree, the analysis is limited to files
+//! changed since a git ref: committed changes, uncommitted edits and new
+//! untracked files. After each analyze the commit it saw is remembered per
+//! repository under the data directory, so `--since last` picks up where the
+//! previous run stopped.
+
+use anyhow::{bail, Context, Result};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// `--since` value meaning "the commit the previous analyze saw"
+pub const SINCE_LAST: &str = "last";
+
+/// Files changed under the analyzed path since a base commit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangedFiles {
+    /// The ref as given, e.g. `main` or `last`
+    pub since: String,
+    /// Commit the ref resolved to
+    pub base: String,
+    /// Changed files, relative to the analyzed path
+    pub files: Vec<String>,
+}
+
+/// Last commit analyzed in each repository, keyed by repository root
+pub struct AnalyzeState {
+    path: PathBuf,
+}
+
+impl AnalyzeState {
+    /// State file under the local data directory
+    pub fn new() -> Self {
+        let path = dirs::data_local_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("selfware")
+            .join("analyze")
+            .join("last_analyzed.json");
+        Self { path }
+    }
+
+    /// State file at a custom path
+    pub fn with_path(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    fn load(&self) -> BTreeMap<String, String> {
+        std::fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    /// Commit the previous analyze of the repository containing `dir` saw
+    pub fn last(&self, dir: &Path) -> Option<String> {
+        let root = repo_root(dir).ok()?;
+        self.load().remove(&root)
+    }
+
+    /// Remember the current HEAD of the repository containing `dir`
+    pub fn record(&self, dir: &Path) -> Result<()> {
+        let root = repo_root(dir)?;
+        let head = git(dir, &["rev-parse", "HEAD"])?;
+        let mut state = self.load();
+        state.insert(root, head);
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(&state)?)
+            .with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+}
+
+impl Default for AnalyzeState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn repo_root(dir: &Path) -> Result<String> {
+    git(dir, &["rev-parse", "--show-toplevel"]).context("not a git repository")
+}
+
+/// Files under `dir` changed since `since` (a git ref, or [`SINCE_LAST`]).
+/// Fails when `dir` is not in a git repository, the ref is unknown, or no
+/// previous analyze was recorded for `last`.
+pub fn changed_since(dir: &Path, since: &str, state: &AnalyzeState) -> Result<ChangedFiles> {
+    repo_root(dir)?;
+    let reference = if since == SINCE_LAST {
+        state
+            .last(dir)
+            .context("no previous analyze is recorded for this repository")?
+    } else {
+        since.to_string()
+    };
+    let base = git(
+        dir,
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{}^{{commit}}", reference),
+        ],
+    )
+    .with_context(|| format!("unknown git ref '{}'", reference))?;
+
+    // Committed and uncommitted changes against the base, minus deletions
+    let diff = git(
+        dir,
+        &[
+            "diff",
+            "--name-only",
+            "--relative",
+            "--diff-filter=ACMRT",
+            &base,
+            "--",
+            ".",
+        ],
+    )?;
+    let untracked = git(
+        dir,
+        &["ls-files", "--others", "--exclude-standard", "--", "."],
+    )?;
+    let mut files: Vec<String> = diff
+        .lines()
+        .chain(untracked.lines())
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect();
+    files.sort();
+    files.dedup();
+
+    Ok(ChangedFiles {
+        since: since.to_string(),
+        base,
+        files,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let run = |args: &[&str]| git(dir.path(), args).unwrap();
+        run(&["init", "-q"]);
+        run(&["config", "user.email", "dev@example.com"]);
+        run(&["config", "user.name", "Dev"]);
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/lib.rs"), "pub fn a() {}\n").unwrap();
+        std::fs::write(dir.path().join("README.md"), "# demo\n").unwrap();
+        run(&["add", "."]);
+        run(&["commit", "-qm", "init"]);
+        dir
+    }
+
+    #[test]
+    fn test_changed_since_lists_committed_edited_and_new_files() {
+        let dir = repo();
+        let first = git(dir.path(), &["rev-parse", "HEAD"]).unwrap();
+        std::fs::write(dir.path().join("src/lib.rs"), "pub fn b() {}\n").unwrap();
+        git(dir.path(), &["commit", "-qam", "edit"]).unwrap();
+        std::fs::write(dir.path().join("README.md"), "# changed\n").unwrap();
+        std::fs::write(dir.path().join("src/new.rs"), "pub fn c() {}\n").unwrap();
+
+        let state = AnalyzeState::with_path(PathBuf::from("unused.json"));
+        let changed = changed_since(dir.path(), &first, &state).unwrap();
+        assert_eq!(changed.base, first);
+        assert_eq!(changed.files, ["README.md", "src/lib.rs", "src/new.rs"]);
+
+        // Paths are relative to the analyzed directory, which scopes the set
+        let scoped = changed_since(&dir.path().join("src"), &first, &state).unwrap();
+        assert_eq!(scoped.files, ["lib.rs", "new.rs"]);
+    }
+
+    #[test]
+    fn test_since_last_uses_recorded_commit_and_unknown_refs_fail() {
+        let dir = repo();
+        let state_dir = tempfile::tempdir().unwrap();
+        let state = AnalyzeState::with_path(state_dir.path().join("state.json"));
+        assert!(changed_since(dir.path(), SINCE_LAST, &state).is_err());
+
+        state.record(dir.path()).unwrap();
+        let unchanged = changed_since(dir.path(), SINCE_LAST, &state).unwrap();
+        assert!(unchanged.files.is_empty());
+
+        std::fs::write(dir.path().join("src/lib.rs"), "pub fn z() {}\n").unwrap();
+        let changed = changed_since(dir.path(), SINCE_LAST, &state).unwrap();
+        assert_eq!(changed.files, ["src/lib.rs"]);
+
+        let err = changed_since(dir.path(), "no-such-branch", &state).unwrap_err();
+        assert!(err.to_string().contains("unknown git ref"), "{err}");
+        let plain = tempfile::tempdir().unwrap();
+        assert!(changed_since(plain.path(), "HEAD", &state).is_err());
+    }
+}
@@ -5,10 +5,21 @@
 //! - Error analysis
 //! - BM25 search
 //! - Vector storage
+//! - Embedding cache (with the `cache` feature)
 //! - Technical debt tracking
+//! - Per-file symbol and debt insight
+//! - Changed-file sets for incremental analyze
+//! - Symbol references for safe renames
+//! - Code-aware chunking for retrieval
 
 pub mod analyzer;
 pub mod bm25;
+pub mod chunking;
 pub mod code_graph;
+#[cfg(feature = "cache")]
+pub mod embedding_cache;
+pub mod file_insight;
+pub mod incremental;
+pub mod references;
 pub mod tech_debt;
 pub mod vector_store;
@@ -0,0 +1,1692 @@
+//! Rust symbol references
+//!
+//! A lexical index of a crate, precise enough to rename a top-level item.
+//! Each source file is tokenized (strings, comments and lifetimes dropped)
+//! and split into module scopes, inline `mod` blocks included. `use`
+//! declarations, glob imports and `pub use` re-exports are followed to find
+//! every scope where the item's name resolves to the item. The result is a
+//! [`CodeGraph`] of the item and the modules referencing it, plus the exact
+//! spans to rewrite.
+//!
+//! Only names that resolve to the item are touched. The same text defined in
+//! another module, a field or method access, a macro, or a local binding that
+//! shadows the name is left alone.
+
+use super::code_graph::{CodeGraph, EdgeType, GraphEdge, GraphNode, NodeType};
+use anyhow::{bail, Context, Result};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Keywords that introduce a named item
+const ITEM_KEYWORDS: &[&str] = &[
+    "fn", "struct", "enum", "trait", "type", "const", "static", "union", "mod",
+];
+
+/// Items in the value namespace, which local bindings can shadow
+const VALUE_KEYWORDS: &[&str] = &["fn", "const", "static"];
+
+/// Reserved words that cannot be used as a new name
+const RESERVED: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while", "abstract", "become", "box", "do", "final", "macro",
+    "override", "priv", "try", "typeof", "unsized", "virtual", "yield", "_",
+];
+
+/// Directories outside `src` whose files are separate crates using this one
+const EXTERNAL_DIRS: &[&str] = &["tests", "examples", "benches"];
+
+/// Module path, e.g. `["crate", "tools", "patch"]`
+type ModulePath = Vec<String>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Ident,
+    Punct,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Token {
+    kind: Kind,
+    start: usize,
+    end: usize,
+    /// Index into the file's scopes
+    scope: usize,
+    /// Bracket nesting; an opening bracket has its outer depth
+    depth: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Scope {
+    path: ModulePath,
+    /// Depth of the scope's items
+    depth: usize,
+}
+
+/// One leaf of a `use` tree
+#[derive(Debug, Clone)]
+struct UseItem {
+    file: usize,
+    scope: usize,
+    path: Vec<String>,
+    /// Token of the last segment; `None` for globs
+    last: Option<usize>,
+    alias: Option<String>,
+    glob: bool,
+}
+
+impl UseItem {
+    /// Name the item binds in its scope
+    fn bound_name(&self) -> Option<&str> {
+        if self.glob {
+            return None;
+        }
+        match self.alias.as_deref() {
+            Some(alias) => Some(alias),
+            None => match self.path.last().map(String::as_str) {
+                Some("self") => self.path.iter().rev().nth(1).map(String::as_str),
+                last => last,
+            },
+        }
+    }
+}
+
+struct SourceFile {
+    /// Relative to the crate root
+    path: PathBuf,
+    text: String,
+    tokens: Vec<Token>,
+    scopes: Vec<Scope>,
+    /// Tokens inside `use` declarations
+    use_tokens: HashSet<usize>,
+}
+
+impl SourceFile {
+    fn parse(path: PathBuf, text: String, module: ModulePath) -> Self {
+        let mut tokens = Vec::new();
+        let mut scopes = vec![Scope {
+            path: module,
+            depth: 0,
+        }];
+        let mut stack = vec![0];
+        let mut depth = 0usize;
+        let raw = lex(&text);
+        for (index, &(kind, start, end)) in raw.iter().enumerate() {
+            let word = &text[start..end];
+            let token_depth = match word {
+                "(" | "[" | "{" => {
+                    depth += 1;
+                    depth - 1
+                }
+                ")" | "]" | "}" => {
+                    depth = depth.saturating_sub(1);
+                    while stack.len() > 1 && scopes[stack[stack.len() - 1]].depth > depth {
+                        stack.pop();
+                    }
+                    depth
+                }
+                _ => depth,
+            };
+            let scope = stack[stack.len() - 1];
+            tokens.push(Token {
+                kind,
+                start,
+                end,
+                scope,
+                depth: token_depth,
+            });
+            // `mod name {` opens a scope for the block
+            if word == "{" && index >= 2 && &text[raw[index - 2].1..raw[index - 2].2] == "mod" {
+                let (name_kind, name_start, name_end) = raw[index - 1];
+                if name_kind == Kind::Ident {
+                    let mut path = scopes[scope].path.clone();
+                    path.push(text[name_start..name_end].to_string());
+                    scopes.push(Scope { path, depth });
+                    stack.push(scopes.len() - 1);
+                }
+            }
+        }
+        Self {
+            path,
+            text,
+            tokens,
+            scopes,
+            use_tokens: HashSet::new(),
+        }
+    }
+
+    fn text(&self, index: usize) -> &str {
+        self.tokens
+            .get(index)
+            .map_or("", |t| &self.text[t.start..t.end])
+    }
+
+    fn prev(&self, index: usize) -> &str {
+        index.checked_sub(1).map_or("", |i| self.text(i))
+    }
+
+    fn is_ident(&self, index: usize) -> bool {
+        self.tokens
+            .get(index)
+            .is_some_and(|t| t.kind == Kind::Ident)
+    }
+
+    fn line(&self, index: usize) -> usize {
+        self.text[..self.tokens[index].start].matches('\n').count() + 1
+    }
+
+    fn scope_path(&self, index: usize) -> &ModulePath {
+        &self.scopes[self.tokens[index].scope].path
+    }
+
+    /// Whether the token sits directly in its module, not inside an item
+    fn at_item_level(&self, index: usize) -> bool {
+        let token = &self.tokens[index];
+        token.depth == self.scopes[token.scope].depth
+    }
+
+    /// Token closing the bracket opened at `open`
+    fn matching_close(&self, open: usize) -> usize {
+        let depth = self.tokens[open].depth;
+        (open + 1..self.tokens.len())
+            .find(|&i| self.tokens[i].depth == depth && matches!(self.text(i), ")" | "]" | "}"))
+            .unwrap_or(self.tokens.len())
+    }
+
+    /// First token after `from` that leaves nesting `depth`
+    fn block_end(&self, from: usize, depth: usize) -> usize {
+        (from + 1..self.tokens.len())
+            .find(|&i| self.tokens[i].depth < depth)
+            .unwrap_or(self.tokens.len())
+    }
+
+    /// First token at or after `from` at `depth` whose text is `word`
+    fn find_at_depth(&self, from: usize, depth: usize, word: &str) -> Option<usize> {
+        (from..self.tokens.len())
+            .take_while(|&i| self.tokens[i].depth >= depth)
+            .find(|&i| self.tokens[i].depth == depth && self.text(i) == word)
+    }
+
+    /// Item-level definitions of `name`, with their keyword
+    fn definitions<'a>(&'a self, name: &'a str) -> impl Iterator<Item = (usize, &'a str)> + 'a {
+        (1..self.tokens.len()).filter_map(move |i| {
+            let keyword = self.prev(i);
+            (self.text(i) == name
+                && self.is_ident(i)
+                && ITEM_KEYWORDS.contains(&keyword)
+                && self.at_item_level(i))
+            .then_some((i, keyword))
+        })
+    }
+
+    /// Every `use` leaf in the file
+    fn use_items(&mut self, file: usize) -> Vec<UseItem> {
+        let mut items = Vec::new();
+        for i in 0..self.tokens.len() {
+            if self.text(i) != "use"
+                || !matches!(self.prev(i), "" | ";" | "{" | "}" | "]" | ")" | "pub")
+            {
+                continue;
+            }
+            let depth = self.tokens[i].depth;
+            let Some(end) = self.find_at_depth(i + 1, depth, ";") else {
+                continue;
+            };
+            self.use_tokens.extend(i..=end);
+            let mut pos = i + 1;
+            self.parse_use_tree(file, &mut pos, end, Vec::new(), &mut items);
+        }
+        items
+    }
+
+    fn parse_use_tree(
+        &self,
+        file: usize,
+        pos: &mut usize,
+        end: usize,
+        mut path: Vec<String>,
+        items: &mut Vec<UseItem>,
+    ) {
+        let scope = self.tokens.get(*pos).map_or(0, |t| t.scope);
+        while *pos < end {
+            match self.text(*pos) {
+                "{" => {
+                    *pos += 1;
+                    while *pos < end && self.text(*pos) != "}" {
+                        let before = *pos;
+                        self.parse_use_tree(file, pos, end, path.clone(), items);
+                        if self.text(*pos) == "," {
+                            *pos += 1;
+                        }
+                        if *pos == before {
+                            *pos += 1;
+                        }
+                    }
+                    *pos += 1;
+                    return;
+                }
+                "*" => {
+                    *pos += 1;
+                    items.push(UseItem {
+                        file,
+                        scope,
+                        path,
+                        last: None,
+                        alias: None,
+                        glob: true,
+                    });
+                    return;
+                }
+                "::" => {
+                    // A leading `::` names an external crate
+                    if path.is_empty() {
+                        path.push("::".to_string());
+                    }
+                    *pos += 1;
+                }
+                word if self.is_ident(*pos) => {
+                    path.push(word.to_string());
+                    let last = *pos;
+                    *pos += 1;
+                    if self.text(*pos) == "::" {
+                        *pos += 1;
+                        continue;
+                    }
+                    let alias = (self.text(*pos) == "as").then(|| {
+                        *pos += 2;
+                        self.text(*pos - 1).to_string()
+                    });
+                    items.push(UseItem {
+                        file,
+                        scope,
+                        path,
+                        last: Some(last),
+                        alias,
+                        glob: false,
+                    });
+                    return;
+                }
+                _ => return,
+            }
+        }
+    }
+
+    /// Token ranges where a local binding shadows `name`, and the binding
+    /// tokens themselves. Covers `let`, `if let`/`while let`, `for`, function
+    /// parameters and closure parameters.
+    fn shadows(&self, name: &str) -> (Vec<(usize, usize)>, HashSet<usize>) {
+        let mut ranges = Vec::new();
+        let mut bindings = HashSet::new();
+        let binds =
+            |i: usize| self.text(i) == name && !matches!(self.text(i + 1), "::" | "(" | "{" | "!");
+        for i in 0..self.tokens.len() {
+            let depth = self.tokens[i].depth;
+            match self.text(i) {
+                "let" => {
+                    let conditional = matches!(self.prev(i), "if" | "while" | "&");
+                    let stop = (i + 1..self.tokens.len())
+                        .find(|&j| {
+                            self.tokens[j].depth <= depth
+                                && matches!(self.text(j), "=" | ";" | ":" | "else" | "{" | "}")
+                        })
+                        .unwrap_or(self.tokens.len());
+                    let Some(binding) = (i + 1..stop).find(|&j| binds(j)) else {
+                        continue;
+                    };
+                    bindings.insert(binding);
+                    if conditional {
+                        if let Some(open) = self.find_at_depth(stop, depth, "{") {
+                            ranges.push((open, self.matching_close(open)));
+                        }
+                    } else if let Some(semi) = self.find_at_depth(stop, depth, ";") {
+                        // The initializer still sees the outer name
+                        ranges.push((semi, self.block_end(semi, depth)));
+                    }
+                }
+                // Not `impl Trait for Type` or `for<'a>`
+                "for"
+                    if !(matches!(self.prev(i), ">" | "<") || (i > 0 && self.is_ident(i - 1))) =>
+                {
+                    let Some(keyword_in) = self.find_at_depth(i + 1, depth, "in") else {
+                        continue;
+                    };
+                    let Some(binding) = (i + 1..keyword_in).find(|&j| binds(j)) else {
+                        continue;
+                    };
+                    bindings.insert(binding);
+                    if let Some(open) = self.find_at_depth(keyword_in, depth, "{") {
+                        ranges.push((open, self.matching_close(open)));
+                    }
+                }
+                "fn" if self.is_ident(i + 1) => {
+                    let Some(open) = self.fn_params(i + 2) else {
+                        continue;
+                    };
+                    let close = self.matching_close(open);
+                    let Some(binding) = self.param_binding(open, close, name) else {
+                        continue;
+                    };
+                    bindings.insert(binding);
+                    let body = (close + 1..self.tokens.len()).find(|&j| {
+                        self.tokens[j].depth == depth && matches!(self.text(j), "{" | ";")
+                    });
+                    if let Some(body) = body.filter(|&j| self.text(j) == "{") {
+                        ranges.push((body, self.matching_close(body)));
+                    }
+                }
+                "|" if self.closure_start(i) => {
+                    let Some(close) = self.find_at_depth(i + 1, depth, "|") else {
+                        continue;
+                    };
+                    let Some(binding) = self.param_binding(i, close, name) else {
+                        continue;
+                    };
+                    bindings.insert(binding);
+                    let end = (close + 1..self.tokens.len())
+                        .find(|&j| {
+                            self.tokens[j].depth < depth
+                                || (self.tokens[j].depth == depth
+                                    && matches!(self.text(j), "," | ";"))
+                        })
+                        .unwrap_or(self.tokens.len());
+                    ranges.push((close, end));
+                }
+                _ => {}
+            }
+        }
+        (ranges, bindings)
+    }
+
+    /// The `(` opening a function's parameters, skipping generics
+    fn fn_params(&self, from: usize) -> Option<usize> {
+        let mut angle = 0usize;
+        for i in from..self.tokens.len() {
+            match self.text(i) {
+                "<" => angle += 1,
+                ">" if self.prev(i) != "-" => angle = angle.saturating_sub(1),
+                "(" if angle == 0 => return Some(i),
+                "{" | ";" => return None,
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// A parameter between `open` and `close` named `name`, ignoring types
+    fn param_binding(&self, open: usize, close: usize, name: &str) -> Option<usize> {
+        let depth = self.tokens[open].depth + usize::from(self.text(open) == "(");
+        let mut in_type = false;
+        for i in open + 1..close {
+            let token = &self.tokens[i];
+            if token.depth == depth {
+                match self.text(i) {
+                    ":" => in_type = true,
+                    "," => in_type = false,
+                    _ => {}
+                }
+            }
+            if !in_type
+                && self.text(i) == name
+                && matches!(self.text(i + 1), ":" | "," | ")" | "|" | "}" | "@")
+            {
+                return Some(i);
+            }
+        }
+        None
+    }
+
+    /// Whether the `|` at `index` starts a closure's parameter list
+    fn closure_start(&self, index: usize) -> bool {
+        match self.prev(index) {
+            "(" | "," | "=" | "{" | ";" | "[" | "move" | "return" => true,
+            ">" => index >= 2 && self.text(index - 2) == "=",
+            _ => false,
+        }
+    }
+}
+
+/// An item of a Rust source file, as found by [`outline`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutlineItem {
+    /// Introducing keyword: `fn`, `struct`, `impl`, `macro_rules`, ...
+    pub keyword: String,
+    /// Item name; for `impl` blocks the header, e.g. `Display for Point<T>`
+    pub name: String,
+    /// Module the item is declared in, e.g. `crate::tools::rag`
+    pub module: String,
+    /// Byte range, including leading attributes and doc comments
+    pub span: std::ops::Range<usize>,
+    /// First and last line (1-indexed)
+    pub lines: (usize, usize),
+    /// Attributes mark the item as a test
+    pub is_test: bool,
+    /// Items of an `impl` or `trait` body
+    pub children: Vec<OutlineItem>,
+}
+
+impl OutlineItem {
+    /// Graph node kind of the item
+    pub fn node_type(&self) -> NodeType {
+        match self.keyword.as_str() {
+            "impl" => NodeType::Impl,
+            "mod" => NodeType::Module,
+            "macro_rules" => NodeType::Macro,
+            keyword => node_type(keyword),
+        }
+    }
+
+    /// Type an `impl` block is for, without generics; the name otherwise
+    pub fn self_type(&self) -> &str {
+        let mut name = self.name.rsplit(" for ").next().unwrap_or(&self.name);
+        name = name.trim_start_matches('&');
+        if name.starts_with('\'') {
+            name = name.split_once(' ').map_or(name, |(_, rest)| rest);
+        }
+        for prefix in ["mut ", "dyn "] {
+            name = name.strip_prefix(prefix).unwrap_or(name);
+        }
+        name.split(['<', ' ']).next().unwrap_or(name)
+    }
+}
+
+/// Items of a Rust file in source order, inline `mod` blocks flattened into
+/// their items. `path` locates the file under `src/` for module paths.
+pub fn outline(path: &Path, text: &str) -> Vec<OutlineItem> {
+    let components: Vec<_> = path.components().collect();
+    let relative: PathBuf = match components.iter().rposition(|c| c.as_os_str() == "src") {
+        Some(src) => components[src..].iter().collect(),
+        None => path.to_path_buf(),
+    };
+    let file = SourceFile::parse(
+        relative.clone(),
+        text.to_string(),
+        module_path(&relative, true),
+    );
+    file.items(0, file.tokens.len(), &|i| file.at_item_level(i))
+}
+
+impl SourceFile {
+    /// Items among tokens `from..to` whose keyword passes `level`
+    fn items(&self, from: usize, to: usize, level: &dyn Fn(usize) -> bool) -> Vec<OutlineItem> {
+        let mut items = Vec::new();
+        let mut floor = from;
+        let mut i = from;
+        while i < to {
+            let word = self.text(i);
+            let next = self.text(i + 1);
+            let is_item = level(i)
+                && self.tokens[i].kind == Kind::Ident
+                && match word {
+                    "impl" => true,
+                    "macro_rules" => next == "!",
+                    // `const fn`, `const _` and friends are modifiers
+                    "const" | "static" | "type" | "fn" | "struct" | "enum" | "union" | "trait" => {
+                        self.is_ident(i + 1) && next != "fn" && next != "unsafe"
+                    }
+                    // Inline modules are flattened; `mod x;` is left to the gaps
+                    _ => false,
+                };
+            if !is_item {
+                i += 1;
+                continue;
+            }
+            let end = self.item_end(i, word).min(to.saturating_sub(1));
+            let start = self.item_start(i, floor);
+            let start_byte = self.leading_comments(self.tokens[start].start, floor);
+            let end_byte = self.tokens[end].end;
+            let name = match word {
+                "impl" => self.impl_header(i, end),
+                "macro_rules" => self.text(i + 2).to_string(),
+                "static" if next == "mut" => self.text(i + 2).to_string(),
+                _ => next.to_string(),
+            };
+            let children = if matches!(word, "impl" | "trait") {
+                let depth = self.tokens[i].depth + 1;
+                let open =
+                    (i..end).find(|&t| self.tokens[t].depth == depth - 1 && self.text(t) == "{");
+                open.map_or_else(Vec::new, |open| {
+                    self.items(open + 1, end, &|t| self.tokens[t].depth == depth)
+                })
+            } else {
+                Vec::new()
+            };
+            items.push(OutlineItem {
+                keyword: word.to_string(),
+                name,
+                module: self.scope_path(i).join("::"),
+                span: start_byte..end_byte,
+                lines: (
+                    self.text[..start_byte].matches('\n').count() + 1,
+                    self.text[..end_byte].matches('\n').count() + 1,
+                ),
+                // `#[test]`, `#[tokio::test]`, but not `#[cfg(test)]`
+                is_test: (start..i).any(|t| {
+                    matches!(self.text(t), "test" | "bench") && matches!(self.prev(t), "[" | "::")
+                }),
+                children,
+            });
+            floor = end + 1;
+            i = end + 1;
+        }
+        items
+    }
+
+    /// Last token of the item introduced at `keyword`
+    fn item_end(&self, keyword: usize, word: &str) -> usize {
+        let depth = self.tokens[keyword].depth;
+        let last = self.tokens.len().saturating_sub(1);
+        for i in keyword + 1..self.tokens.len() {
+            let token = &self.tokens[i];
+            if token.depth < depth {
+                return i.saturating_sub(1);
+            }
+            if token.depth > depth {
+                continue;
+            }
+            match self.text(i) {
+                ";" => return i,
+                // A const or static initializer can contain braces
+                "{" if !matches!(word, "const" | "static" | "type") => {
+                    return self.matching_close(i).min(last);
+                }
+                "(" | "[" if word == "macro_rules" => {
+                    let close = self.matching_close(i).min(last);
+                    return if self.text(close + 1) == ";" {
+                        close + 1
+                    } else {
+                        close
+                    };
+                }
+                _ => {}
+            }
+        }
+        last
+    }
+
+    /// First token of the item: visibility, qualifiers and outer attributes
+    /// before `keyword`, not reaching back to `floor`
+    fn item_start(&self, keyword: usize, floor: usize) -> usize {
+        let mut start = keyword;
+        while start > floor {
+            let prev = start - 1;
+            match self.text(prev) {
+                "pub" | "async" | "unsafe" | "const" | "extern" | "default" => start = prev,
+                ")" if self.tokens[prev].depth == self.tokens[keyword].depth => {
+                    let open = (floor..prev)
+                        .rev()
+                        .find(|&t| {
+                            self.tokens[t].depth == self.tokens[prev].depth && self.text(t) == "("
+                        })
+                        .unwrap_or(prev);
+                    if open > floor && self.text(open - 1) == "pub" {
+                        start = open - 1;
+                    } else {
+                        break;
+                    }
+                }
+                "]" => {
+                    let open = (floor..prev)
+                        .rev()
+                        .find(|&t| {
+                            self.tokens[t].depth == self.tokens[prev].depth && self.text(t) == "["
+                        })
+                        .unwrap_or(prev);
+                    if open > floor && self.text(open - 1) == "#" {
+                        start = open - 1;
+                    } else {
+                        break;
+                    }
+                }
+                _ => break,
+            }
+        }
+        start
+    }
+
+    /// Start of the line comments directly above byte `start`, or `start`
+    fn leading_comments(&self, start: usize, floor: usize) -> usize {
+        let floor_byte = floor.checked_sub(1).map_or(0, |t| self.tokens[t].end);
+        let line_start = |at: usize| self.text[..at].rfind('\n').map_or(0, |n| n + 1);
+        let mut begin = line_start(start);
+        if !self.text[begin..start].trim().is_empty() {
+            return start;
+        }
+        while begin > floor_byte {
+            let above = line_start(begin - 1);
+            let line = self.text[above..begin].trim();
+            if above < floor_byte || !line.starts_with("//") || line.starts_with("//!") {
+                break;
+            }
+            begin = above;
+        }
+        begin
+    }
+
+    /// `impl` header without generics or `where` clause, spaces normalized
+    fn impl_header(&self, keyword: usize, end: usize) -> String {
+        let depth = self.tokens[keyword].depth;
+        let open = (keyword + 1..=end)
+            .find(|&t| self.tokens[t].depth == depth && matches!(self.text(t), "{" | ";"))
+            .unwrap_or(end);
+        let (Some(first), Some(last)) = (
+            self.tokens.get(keyword + 1),
+            open.checked_sub(1).and_then(|t| self.tokens.get(t)),
+        ) else {
+            return String::new();
+        };
+        if first.start >= last.end {
+            return String::new();
+        }
+        let header = self.text[first.start..last.end]
+            .split_whitespace()
+            .collect::<Vec<_>>();
+        let mut header = header.join(" ");
+        if let Some(at) = header.find(" where ") {
+            header.truncate(at);
+        }
+        if header.starts_with('<') {
+            let mut angle = 0i32;
+            for (at, c) in header.char_indices() {
+                match c {
+                    '<' => angle += 1,
+                    '>' => {
+                        angle -= 1;
+                        if angle == 0 {
+                            header = header[at + 1..].trim_start().to_string();
+                            break;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        header
+    }
+}
+
+/// Tokens of `src` as (kind, start, end). Comments, string and character
+/// literals, lifetimes and numbers are skipped.
+fn lex(src: &str) -> Vec<(Kind, usize, usize)> {
+    let bytes = src.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
+        if c.is_ascii_whitespace() {
+            i += 1;
+        } else if src[i..].starts_with("//") {
+            i = src[i..].find('\n').map_or(bytes.len(), |n| i + n);
+        } else if src[i..].starts_with("/*") {
+            i = skip_block_comment(bytes, i);
+        } else if c == b'"' {
+            i = skip_string(bytes, i + 1);
+        } else if c == b'\'' {
+            i = skip_quote(src, i);
+        } else if is_ident_start(c) {
+            let start = i;
+            if src[i..].starts_with("r#") && bytes.get(i + 2).is_some_and(|b| is_ident_start(*b)) {
+                i += 2;
+            }
+            while i < bytes.len() && is_ident_continue(bytes[i]) {
+                i += 1;
+            }
+            match (&src[start..i], bytes.get(i)) {
+                ("b" | "c", Some(b'"')) => i = skip_string(bytes, i + 1),
+                ("b", Some(b'\'')) => i = skip_quote(src, i),
+                ("r" | "br" | "cr", Some(b'"' | b'#')) => match skip_raw_string(bytes, i) {
+                    Some(end) => i = end,
+                    None => tokens.push((Kind::Ident, start, i)),
+                },
+                _ => tokens.push((Kind::Ident, start, i)),
+            }
+        } else if c.is_ascii_digit() {
+            i += 1;
+            while i < bytes.len()
+                && (is_ident_continue(bytes[i])
+                    || (bytes[i] == b'.' && bytes.get(i + 1).is_some_and(u8::is_ascii_digit)))
+            {
+                i += 1;
+            }
+        } else if src[i..].starts_with("::") {
+            tokens.push((Kind::Punct, i, i + 2));
+            i += 2;
+        } else {
+            let len = src[i..].chars().next().map_or(1, char::len_utf8);
+            tokens.push((Kind::Punct, i, i + len));
+            i += len;
+        }
+    }
+    tokens
+}
+
+/// `src` with comments, string and character literals and lifetimes
+/// blanked to spaces. Lines and byte offsets are unchanged, so matches in
+/// the result point into `src`.
+pub fn code_only(src: &str) -> String {
+    let bytes = src.as_bytes();
+    let mut out = bytes.to_vec();
+    let mut blank = |from: usize, to: usize| {
+        for b in &mut out[from..to.min(bytes.len())] {
+            if *b != b'\n' {
+                *b = b' ';
+            }
+        }
+    };
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
+        let start = i;
+        if src[i..].starts_with("//") {
+            i = src[i..].find('\n').map_or(bytes.len(), |n| i + n);
+            blank(start, i);
+        } else if src[i..].starts_with("/*") {
+            i = skip_block_comment(bytes, i);
+            blank(start, i);
+        } else if c == b'"' {
+            i = skip_string(bytes, i + 1);
+            blank(start, i);
+        } else if c == b'\'' {
+            i = skip_quote(src, i);
+            blank(start, i);
+        } else if is_ident_start(c) {
+            while i < bytes.len() && is_ident_continue(bytes[i]) {
+                i += 1;
+            }
+            let end = match (&src[start..i], bytes.get(i)) {
+                ("b" | "c", Some(b'"')) => Some(skip_string(bytes, i + 1)),
+                ("b", Some(b'\'')) => Some(skip_quote(src, i)),
+                ("r" | "br" | "cr", Some(b'"' | b'#')) => skip_raw_string(bytes, i),
+                _ => None,
+            };
+            if let Some(end) = end {
+                blank(start, end);
+                i = end;
+            }
+        } else {
+            i += src[i..].chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    // Only whole characters were replaced, so the bytes are still UTF-8
+    String::from_utf8(out).unwrap_or_else(|_| src.to_string())
+}
+
+fn is_ident_start(c: u8) -> bool {
+    c.is_ascii_alphabetic() || c == b'_' || c >= 0x80
+}
+
+fn is_ident_continue(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || c == b'_' || c >= 0x80
+}
+
+fn skip_block_comment(bytes: &[u8], mut i: usize) -> usize {
+    let mut nesting = 0;
+    while i < bytes.len() {
+        if bytes[i..].starts_with(b"/*") {
+            nesting += 1;
+            i += 2;
+        } else if bytes[i..].starts_with(b"*/") {
+            nesting -= 1;
+            i += 2;
+            if nesting == 0 {
+                break;
+            }
+        } else {
+            i += 1;
+        }
+    }
+    i
+}
+
+/// Index after the closing quote of a string whose body starts at `i`
+fn skip_string(bytes: &[u8], mut i: usize) -> usize {
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'"' => return i + 1,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+/// Skip a raw string whose `#`s or opening quote start at `i`
+fn skip_raw_string(bytes: &[u8], i: usize) -> Option<usize> {
+    let hashes = bytes[i..].iter().take_while(|&&b| b == b'#').count();
+    if bytes.get(i + hashes) != Some(&b'"') {
+        return None;
+    }
+    let mut j = i + hashes + 1;
+    while j < bytes.len() {
+        if bytes[j] == b'"'
+            && bytes[j + 1..]
+                .iter()
+                .take(hashes)
+                .filter(|&&b| b == b'#')
+                .count()
+                == hashes
+        {
+            return Some(j + 1 + hashes);
+        }
+        j += 1;
+    }
+    Some(bytes.len())
+}
+
+/// Skip a character literal or a lifetime starting at the `'` at `i`
+fn skip_quote(src: &str, i: usize) -> usize {
+    let bytes = src.as_bytes();
+    let mut chars = src[i + 1..].chars();
+    match chars.next() {
+        Some('\\') => {
+            let escaped = i + 2 + chars.next().map_or(0, char::len_utf8);
+            src[escaped..]
+                .find('\'')
+                .map_or(src.len(), |n| escaped + n + 1)
+        }
+        Some(c) if bytes.get(i + 1 + c.len_utf8()) == Some(&b'\'') => i + 2 + c.len_utf8(),
+        Some(_) => {
+            let mut j = i + 1;
+            while j < bytes.len() && is_ident_continue(bytes[j]) {
+                j += 1;
+            }
+            j
+        }
+        None => src.len(),
+    }
+}
+
+fn node_type(keyword: &str) -> NodeType {
+    match keyword {
+        "fn" => NodeType::Function,
+        "struct" | "union" => NodeType::Struct,
+        "enum" => NodeType::Enum,
+        "trait" => NodeType::Trait,
+        "type" => NodeType::TypeAlias,
+        _ => NodeType::Const,
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+        && !RESERVED.contains(&name)
+}
+
+/// Source files of the crate at `root`, each with its module path. Files
+/// outside the library (tests, examples, binaries) are their own roots.
+fn crate_files(root: &Path) -> Result<Vec<(PathBuf, ModulePath)>> {
+    let src = root.join("src");
+    if !src.is_dir() {
+        bail!("No src directory in {}", root.display());
+    }
+    let has_lib = src.join("lib.rs").is_file();
+    let mut files = Vec::new();
+    for dir in std::iter::once("src").chain(EXTERNAL_DIRS.iter().copied()) {
+        let walker = walkdir::WalkDir::new(root.join(dir))
+            .into_iter()
+            .filter_entry(|e| e.file_name() != "target");
+        for entry in walker.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if !entry.file_type().is_file() || path.extension().is_none_or(|ext| ext != "rs") {
+                continue;
+            }
+            let relative = path.strip_prefix(root).unwrap_or(path).to_path_buf();
+            files.push((relative.clone(), module_path(&relative, has_lib)));
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn module_path(relative: &Path, has_lib: bool) -> ModulePath {
+    let own_root = || vec![relative.display().to_string()];
+    let Ok(inner) = relative.strip_prefix("src") else {
+        return own_root();
+    };
+    let parts: Vec<String> = inner
+        .with_extension("")
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+    match parts.first().map(String::as_str) {
+        Some("bin") => return own_root(),
+        Some("main") if parts.len() == 1 && has_lib => return own_root(),
+        _ => {}
+    }
+    let mut path = vec!["crate".to_string()];
+    path.extend(parts);
+    if (path.len() == 2 && matches!(path[1].as_str(), "lib" | "main"))
+        || path.last().is_some_and(|p| p == "mod")
+    {
+        path.pop();
+    }
+    path
+}
+
+/// Library name from the manifest, as written in paths
+fn crate_name(root: &Path) -> Option<String> {
+    let manifest: toml::Value =
+        toml::from_str(&std::fs::read_to_string(root.join("Cargo.toml")).ok()?).ok()?;
+    let name = manifest
+        .get("lib")
+        .and_then(|lib| lib.get("name"))
+        .or_else(|| manifest.get("package")?.get("name"))?
+        .as_str()?;
+    Some(name.replace('-', "_"))
+}
+
+/// The item being renamed and every span that refers to it
+pub struct RenamePlan {
+    pub old_path: String,
+    pub new_name: String,
+    /// The item, and each module defining or referencing it, with edges
+    /// from the modules to the item
+    pub graph: CodeGraph,
+    /// Where the item is defined, as `file:line`
+    pub definition: String,
+    /// Original text and byte spans to replace, per file
+    edits: BTreeMap<PathBuf, (String, Vec<(usize, usize)>)>,
+}
+
+impl RenamePlan {
+    /// Plan renaming the item at `old_path` (e.g. `crate::tools::patch::Hunk`
+    /// or `tools::patch::Hunk`) to `new_name` in the crate at `root`.
+    pub fn build(root: &Path, old_path: &str, new_name: &str) -> Result<Self> {
+        let index = Index::load(root)?;
+        index.plan(old_path, new_name)
+    }
+
+    /// Files to edit, relative to the crate root, from the graph's edges
+    pub fn files(&self) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = self
+            .graph
+            .nodes
+            .values()
+            .filter(|node| node.node_type == NodeType::Module)
+            .filter_map(|node| node.file_path.as_ref().map(PathBuf::from))
+            .collect();
+        files.sort();
+        files.dedup();
+        files
+    }
+
+    /// Number of spans renamed, the definition included
+    pub fn references(&self) -> usize {
+        self.edits.values().map(|(_, spans)| spans.len()).sum()
+    }
+
+    /// Each file's path with its text before and after the rename
+    pub fn rewrite(&self) -> Vec<(PathBuf, String, String)> {
+        self.edits
+            .iter()
+            .map(|(path, (text, spans))| {
+                let mut renamed = String::with_capacity(text.len());
+                let mut at = 0;
+                for &(start, end) in spans {
+                    renamed.push_str(&text[at..start]);
+                    renamed.push_str(&self.new_name);
+                    at = end;
+                }
+                renamed.push_str(&text[at..]);
+                (path.clone(), text.clone(), renamed)
+            })
+            .collect()
+    }
+}
+
+/// Module dependency graph of the library at `root`: a node per file
+/// module and an `Imports` edge to each module it uses, weighted by the
+/// number of `use` leaves and qualified paths that reference it. Inline
+/// modules such as `mod tests` count toward their file's module. Edges
+/// between a module and its own ancestors or submodules are left out unless
+/// `include_nested` is set, since a parent re-exporting a child that uses
+/// `super::` is ordinary Rust rather than a cycle to break.
+pub fn module_graph(root: &Path, include_nested: bool) -> Result<CodeGraph> {
+    Ok(Index::load(root)?.module_graph(include_nested))
+}
+
+struct Index {
+    files: Vec<SourceFile>,
+    uses: Vec<UseItem>,
+    modules: HashSet<ModulePath>,
+    /// Modules imported under another name, per scope
+    module_aliases: HashMap<ModulePath, HashMap<String, ModulePath>>,
+    crate_name: Option<String>,
+}
+
+impl Index {
+    fn load(root: &Path) -> Result<Self> {
+        let mut files = Vec::new();
+        let mut uses = Vec::new();
+        for (path, module) in crate_files(root)? {
+            let text = std::fs::read_to_string(root.join(&path))
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            let mut file = SourceFile::parse(path, text, module);
+            uses.extend(file.use_items(files.len()));
+            files.push(file);
+        }
+        let modules = files
+            .iter()
+            .flat_map(|f| f.scopes.iter().map(|s| s.path.clone()))
+            .collect();
+        let mut index = Self {
+            files,
+            uses,
+            modules,
+            module_aliases: HashMap::new(),
+            crate_name: crate_name(root),
+        };
+        // Twice, so an import through an imported module resolves
+        for _ in 0..2 {
+            let mut aliases: HashMap<ModulePath, HashMap<String, ModulePath>> = HashMap::new();
+            for item in index.uses.iter().filter(|u| !u.glob) {
+                let scope = index.scope(item.file, item.scope);
+                let target = match item.path.last().map(String::as_str) {
+                    Some("self") => &item.path[..item.path.len() - 1],
+                    _ => &item.path[..],
+                };
+                if let (Some(module), Some(name)) =
+                    (index.resolve(scope, target), item.bound_name())
+                {
+                    aliases
+                        .entry(scope.clone())
+                        .or_default()
+                        .insert(name.to_string(), module);
+                }
+            }
+            index.module_aliases = aliases;
+        }
+        Ok(index)
+    }
+
+    fn scope(&self, file: usize, scope: usize) -> &ModulePath {
+        &self.files[file].scopes[scope].path
+    }
+
+    /// The module `segments` names from `scope`, if it is in this crate
+    fn resolve(&self, scope: &ModulePath, segments: &[String]) -> Option<ModulePath> {
+        let (first, rest) = match segments.split_first() {
+            Some(split) => split,
+            None => return Some(scope.clone()),
+        };
+        let mut module = match first.as_str() {
+            "crate" => vec![scope[0].clone()],
+            "self" => scope.clone(),
+            "super" => {
+                let mut parent = scope.clone();
+                parent.pop();
+                parent
+            }
+            name if Some(name) == self.crate_name.as_deref() => vec!["crate".to_string()],
+            name => {
+                let mut child = scope.clone();
+                child.push(name.to_string());
+                if self.modules.contains(&child) {
+                    child
+                } else {
+                    self.module_aliases.get(scope)?.get(name)?.clone()
+                }
+            }
+        };
+        for segment in rest {
+            match segment.as_str() {
+                "self" => {}
+                "super" => {
+                    module.pop();
+                }
+                name => module.push(name.to_string()),
+            }
+        }
+        (!module.is_empty() && self.modules.contains(&module)).then_some(module)
+    }
+
+    /// The module a path refers to, or the module of the item it names
+    fn target_module(&self, scope: &ModulePath, segments: &[String]) -> Option<ModulePath> {
+        (1..=segments.len())
+            .rev()
+            .find_map(|n| self.resolve(scope, &segments[..n]))
+    }
+
+    fn module_graph(&self, include_nested: bool) -> CodeGraph {
+        let library = |file: &SourceFile| file.scopes[0].path[0] == "crate";
+        // Inline modules belong to their file's module
+        let file_module: HashMap<&ModulePath, &ModulePath> = self
+            .files
+            .iter()
+            .filter(|f| library(f))
+            .flat_map(|f| f.scopes.iter().map(move |s| (&s.path, &f.scopes[0].path)))
+            .collect();
+
+        let mut weights: BTreeMap<(&ModulePath, &ModulePath), usize> = BTreeMap::new();
+        let mut count = |from: &'_ ModulePath, target: Option<ModulePath>| {
+            let Some(to) = target.and_then(|t| file_module.get(&t).copied()) else {
+                return;
+            };
+            let from = file_module[from];
+            let nested = to.starts_with(from) || from.starts_with(to);
+            if from != to && (include_nested || !nested) {
+                *weights.entry((from, to)).or_default() += 1;
+            }
+        };
+        for item in &self.uses {
+            let file = &self.files[item.file];
+            if library(file) {
+                let scope = self.scope(item.file, item.scope);
+                count(scope, self.target_module(scope, &item.path));
+            }
+        }
+        for file in self.files.iter().filter(|f| library(f)) {
+            for i in 0..file.tokens.len() {
+                // The first segment of a qualified path outside `use`
+                if !file.is_ident(i)
+                    || file.text(i + 1) != "::"
+                    || file.prev(i) == "::"
+                    || file.use_tokens.contains(&i)
+                {
+                    continue;
+                }
+                let mut segments = vec![file.text(i).to_string()];
+                let mut j = i;
+                while file.text(j + 1) == "::" && file.is_ident(j + 2) {
+                    segments.push(file.text(j + 2).to_string());
+                    j += 2;
+                }
+                let scope = file.scope_path(i);
+                count(scope, self.target_module(scope, &segments));
+            }
+        }
+
+        let mut graph = CodeGraph::new("module dependencies");
+        let mut ids = HashMap::new();
+        for file in self.files.iter().filter(|f| library(f)) {
+            let module = &file.scopes[0].path;
+            let id = graph.add_node(
+                GraphNode::new(&module.join("::"), NodeType::Module)
+                    .in_file(&file.path.display().to_string()),
+            );
+            ids.insert(module, id);
+        }
+        for ((from, to), references) in weights {
+            graph.add_edge(
+                GraphEdge::new(&ids[from], &ids[to], EdgeType::Imports)
+                    .with_weight(references as f32),
+            );
+        }
+        graph
+    }
+
+    fn plan(&self, old_path: &str, new_name: &str) -> Result<RenamePlan> {
+        if !is_identifier(new_name) {
+            bail!("'{}' is not a valid Rust identifier", new_name);
+        }
+        let mut segments: Vec<String> =
+            old_path.split("::").map(|s| s.trim().to_string()).collect();
+        let name = segments
+            .pop()
+            .filter(|n| !n.is_empty())
+            .context("old_path is empty")?;
+        if name == new_name {
+            bail!("'{}' already has that name", old_path);
+        }
+        match segments.first().map(String::as_str) {
+            Some("crate") => {}
+            Some(first) if Some(first) == self.crate_name.as_deref() => {
+                segments[0] = "crate".into()
+            }
+            _ => segments.insert(0, "crate".into()),
+        }
+        let module = segments;
+        if !self.modules.contains(&module) {
+            bail!("No module '{}' in this crate", module.join("::"));
+        }
+
+        let mut definitions = Vec::new();
+        let mut keyword = "";
+        for (f, file) in self.files.iter().enumerate() {
+            for (i, kw) in file.definitions(&name) {
+                if *file.scope_path(i) == module {
+                    definitions.push((f, i));
+                    keyword = kw;
+                }
+            }
+        }
+        let Some(&(def_file, def_token)) = definitions.first() else {
+            bail!("No item '{}' is defined in '{}'", name, module.join("::"));
+        };
+        if keyword == "mod" {
+            bail!(
+                "Renaming modules is not supported; '{}' is a module",
+                old_path
+            );
+        }
+        let is_value = VALUE_KEYWORDS.contains(&keyword);
+
+        // Scopes defining a different item with the same name
+        let mut blocked: HashSet<ModulePath> = HashSet::new();
+        for file in &self.files {
+            for (i, _) in file.definitions(&name) {
+                if *file.scope_path(i) != module {
+                    blocked.insert(file.scope_path(i).clone());
+                }
+            }
+        }
+
+        // Scopes where the bare name resolves to the item
+        let mut in_scope: HashSet<ModulePath> = HashSet::from([module.clone()]);
+        let mut use_sites: HashSet<(usize, usize)> = HashSet::new();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for item in &self.uses {
+                let scope = self.scope(item.file, item.scope);
+                if item.glob {
+                    let source = self.resolve(scope, &item.path);
+                    if source.is_some_and(|s| in_scope.contains(&s))
+                        && !blocked.contains(scope)
+                        && in_scope.insert(scope.clone())
+                    {
+                        changed = true;
+                    }
+                    continue;
+                }
+                let (Some(last), Some((leaf, prefix))) = (item.last, item.path.split_last()) else {
+                    continue;
+                };
+                if *leaf != name
+                    || !self
+                        .resolve(scope, prefix)
+                        .is_some_and(|m| in_scope.contains(&m))
+                {
+                    continue;
+                }
+                use_sites.insert((item.file, last));
+                if item.alias.as_deref().unwrap_or(&name) == name && in_scope.insert(scope.clone())
+                {
+                    changed = true;
+                }
+            }
+        }
+
+        let mut sites: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        for &(f, i) in definitions.iter().chain(use_sites.iter()) {
+            sites.entry(f).or_default().push(i);
+        }
+        let mut bare_sites = Vec::new();
+        for (f, file) in self.files.iter().enumerate() {
+            if !file.text.contains(name.as_str()) {
+                continue;
+            }
+            let (shadowed, bindings) = if is_value {
+                file.shadows(&name)
+            } else {
+                Default::default()
+            };
+            for i in 0..file.tokens.len() {
+                if file.text(i) != name
+                    || !file.is_ident(i)
+                    || file.use_tokens.contains(&i)
+                    || definitions.contains(&(f, i))
+                    || bindings.contains(&i)
+                    || matches!(file.prev(i), "." | "$")
+                    || ITEM_KEYWORDS.contains(&file.prev(i))
+                    || file.text(i + 1) == "!"
+                {
+                    continue;
+                }
+                if file.prev(i) == "::" {
+                    let mut first = i;
+                    let mut prefix = Vec::new();
+                    while first >= 2 && file.text(first - 1) == "::" && file.is_ident(first - 2) {
+                        prefix.insert(0, file.text(first - 2).to_string());
+                        first -= 2;
+                    }
+                    // `<T as Trait>::name`, `::std::...` and turbofish paths
+                    if prefix.is_empty() || file.prev(first) == "::" {
+                        continue;
+                    }
+                    if self
+                        .resolve(file.scope_path(i), &prefix)
+                        .is_some_and(|m| in_scope.contains(&m))
+                    {
+                        sites.entry(f).or_default().push(i);
+                    }
+                    continue;
+                }
+                let scope = file.scope_path(i);
+                if !in_scope.contains(scope) || blocked.contains(scope) {
+                    continue;
+                }
+                if is_value
+                    && (file.text(i + 1) == ":"
+                        || shadowed.iter().any(|&(start, end)| start < i && i <= end))
+                {
+                    continue;
+                }
+                sites.entry(f).or_default().push(i);
+                bare_sites.push((f, i));
+            }
+        }
+
+        self.check_collisions(new_name, &in_scope, &bare_sites, is_value)?;
+
+        let qualified = format!("{}::{}", module.join("::"), name);
+        let def = &self.files[def_file];
+        let mut graph = CodeGraph::new(&format!("references to {}", qualified));
+        let item_id = graph.add_node(
+            GraphNode::new(&name, node_type(keyword))
+                .with_qualified_name(&qualified)
+                .in_file(&def.path.display().to_string())
+                .at_line(def.line(def_token) as u32),
+        );
+        let mut edits = BTreeMap::new();
+        let mut linked = HashSet::new();
+        for (&f, tokens) in &mut sites {
+            let file = &self.files[f];
+            tokens.sort_unstable();
+            tokens.dedup();
+            for &i in tokens.iter() {
+                let scope = file.scope_path(i);
+                if !linked.insert(scope.clone()) {
+                    continue;
+                }
+                let module_id = graph.add_node(
+                    GraphNode::new(&scope.join("::"), NodeType::Module)
+                        .in_file(&file.path.display().to_string()),
+                );
+                let edge = if *scope == module {
+                    EdgeType::Contains
+                } else {
+                    EdgeType::References
+                };
+                graph.add_edge(GraphEdge::new(&module_id, &item_id, edge));
+            }
+            let spans = tokens
+                .iter()
+                .map(|&i| (file.tokens[i].start, file.tokens[i].end))
+                .collect();
+            edits.insert(file.path.clone(), (file.text.clone(), spans));
+        }
+
+        Ok(RenamePlan {
+            old_path: old_path.to_string(),
+            new_name: new_name.to_string(),
+            graph,
+            definition: format!("{}:{}", def.path.display(), def.line(def_token)),
+            edits,
+        })
+    }
+
+    /// Fail when `new_name` is already taken where the item is in scope, or
+    /// a local binding of `new_name` would capture a renamed reference
+    fn check_collisions(
+        &self,
+        new_name: &str,
+        in_scope: &HashSet<ModulePath>,
+        bare_sites: &[(usize, usize)],
+        is_value: bool,
+    ) -> Result<()> {
+        for file in &self.files {
+            if let Some((i, _)) = file
+                .definitions(new_name)
+                .find(|&(i, _)| in_scope.contains(file.scope_path(i)))
+            {
+                bail!(
+                    "'{}' is already defined in '{}' ({}:{})",
+                    new_name,
+                    file.scope_path(i).join("::"),
+                    file.path.display(),
+                    file.line(i)
+                );
+            }
+        }
+        if let Some(item) = self.uses.iter().find(|u| {
+            u.bound_name() == Some(new_name) && in_scope.contains(self.scope(u.file, u.scope))
+        }) {
+            let file = &self.files[item.file];
+            bail!(
+                "'{}' is already imported in '{}' ({})",
+                new_name,
+                self.scope(item.file, item.scope).join("::"),
+                file.path.display()
+            );
+        }
+        if !is_value {
+            return Ok(());
+        }
+        let mut shadows: HashMap<usize, Vec<(usize, usize)>> = HashMap::new();
+        for &(f, i) in bare_sites {
+            let file = &self.files[f];
+            let ranges = shadows.entry(f).or_insert_with(|| file.shadows(new_name).0);
+            if ranges.iter().any(|&(start, end)| start < i && i <= end) {
+                bail!(
+                    "A local '{}' would capture the renamed reference at {}:{}",
+                    new_name,
+                    file.path.display(),
+                    file.line(i)
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    fn demo_crate() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(root, "Cargo.toml", "[package]\nname = \"demo-app\"\n");
+        write(
+            root,
+            "src/lib.rs",
+            "pub mod net;\npub mod util;\npub use util::retry;\n",
+        );
+        write(
+            root,
+            "src/util.rs",
+            r#"/// Calls `retry` until it works
+pub fn retry(attempts: u32) -> u32 {
+    attempts
+}
+
+pub fn twice() -> u32 {
+    retry(1) + self::retry(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retries() {
+        let count = 2;
+        assert_eq!(retry(count), 2);
+    }
+}
+"#,
+        );
+        write(
+            root,
+            "src/net/mod.rs",
+            r#"use crate::util::retry;
+use crate::util as helpers;
+
+pub struct Client {
+    retry: u32,
+}
+
+impl Client {
+    pub fn retry(&self) -> u32 {
+        let n = retry(self.retry);
+        let retry = |x: u32| x + 1;
+        retry(n) + helpers::retry(0) + super::util::retry(0)
+    }
+}
+
+pub fn message() -> &'static str {
+    "retry later"
+}
+"#,
+        );
+        write(
+            root,
+            "src/other.rs",
+            "fn retry() {}\n\npub fn go() {\n    retry();\n}\n",
+        );
+        write(
+            root,
+            "tests/api.rs",
+            "use demo_app::retry;\n\n#[test]\nfn t() {\n    retry(3);\n}\n",
+        );
+        dir
+    }
+
+    fn renamed(plan: &RenamePlan, path: &str) -> String {
+        plan.rewrite()
+            .into_iter()
+            .find(|(p, _, _)| p == Path::new(path))
+            .map(|(_, _, new)| new)
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_rename_follows_imports_and_skips_unrelated_names() {
+        let dir = demo_crate();
+        let plan = RenamePlan::build(dir.path(), "util::retry", "retry_op").unwrap();
+        assert_eq!(plan.definition, "src/util.rs:2");
+        assert_eq!(
+            plan.files(),
+            [
+                "src/lib.rs",
+                "src/net/mod.rs",
+                "src/util.rs",
+                "tests/api.rs"
+            ]
+            .map(PathBuf::from)
+        );
+
+        let util = renamed(&plan, "src/util.rs");
+        assert!(util.contains("pub fn retry_op(attempts: u32)"));
+        assert!(util.contains("retry_op(1) + self::retry_op(1)"));
+        assert!(util.contains("assert_eq!(retry_op(count), 2)"));
+        // Comments are not code
+        assert!(util.contains("/// Calls `retry` until it works"));
+
+        let net = renamed(&plan, "src/net/mod.rs");
+        assert!(net.contains("use crate::util::retry_op;"));
+        // Field, method and string keep their names
+        assert!(net.contains("    retry: u32,"));
+        assert!(net.contains("pub fn retry(&self)"));
+        assert!(net.contains("retry_op(self.retry)"));
+        assert!(net.contains("\"retry later\""));
+        // The local closure shadows the import; qualified paths still resolve
+        assert!(net.contains("let retry = |x: u32| x + 1;"));
+        assert!(net.contains("retry(n) + helpers::retry_op(0) + super::util::retry_op(0)"));
+
+        assert_eq!(
+            renamed(&plan, "src/lib.rs"),
+            "pub mod net;\npub mod util;\npub use util::retry_op;\n"
+        );
+        assert!(renamed(&plan, "tests/api.rs").contains("use demo_app::retry_op;"));
+        assert!(renamed(&plan, "tests/api.rs").contains("    retry_op(3);"));
+        // A private item of the same name elsewhere is unrelated
+        assert!(!plan.files().contains(&PathBuf::from("src/other.rs")));
+        assert_eq!(plan.graph.node_count(), 6);
+    }
+
+    #[test]
+    fn test_rename_rejects_collisions_and_unknown_items() {
+        let dir = demo_crate();
+        let err = |path: &str, new: &str| {
+            RenamePlan::build(dir.path(), path, new)
+                .err()
+                .map(|e| e.to_string())
+                .unwrap_or_default()
+        };
+        assert!(err("util::retry", "twice").contains("already defined in 'crate::util'"));
+        assert!(err("util::retry", "fn").contains("not a valid Rust identifier"));
+        assert!(err("util::missing", "x").contains("No item 'missing'"));
+        assert!(err("nope::retry", "x").contains("No module 'crate::nope'"));
+        assert!(err("crate::net", "web").contains("Renaming modules is not supported"));
+        // `count` is a local where the test calls `retry`
+        assert!(err("util::retry", "count").contains("would capture"));
+
+        let plan = RenamePlan::build(dir.path(), "crate::net::Client", "HttpClient").unwrap();
+        assert_eq!(plan.references(), 2);
+        assert!(renamed(&plan, "src/net/mod.rs").contains("impl HttpClient {"));
+    }
+
+    #[test]
+    fn test_outline_spans_items_with_docs_attributes_and_members() {
+        let src = r#"use std::fmt;
+
+/// Doc for `LIMIT`
+pub(crate) const LIMIT: usize = { 1 + 2 };
+
+#[derive(Debug)]
+pub struct Pair<T>(T, T);
+
+pub const unsafe fn raw() {}
+
+impl<T: fmt::Debug> fmt::Display for Pair<T> where T: Clone {
+    // "}" in a comment
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "}}")
+    }
+}
+
+macro_rules! twice {
+    ($e:expr) => { $e * 2 };
+}
+
+mod inner {
+    #[tokio::test]
+    async fn probe() {}
+}
+"#;
+        let items = outline(Path::new("/repo/src/pair.rs"), src);
+        let summary: Vec<(&str, &str, &str, (usize, usize))> = items
+            .iter()
+            .map(|i| {
+                (
+                    i.keyword.as_str(),
+                    i.name.as_str(),
+                    i.module.as_str(),
+                    i.lines,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("const", "LIMIT", "crate::pair", (3, 4)),
+                ("struct", "Pair", "crate::pair", (6, 7)),
+                ("fn", "raw", "crate::pair", (9, 9)),
+                ("impl", "fmt::Display for Pair<T>", "crate::pair", (11, 16)),
+                ("macro_rules", "twice", "crate::pair", (18, 20)),
+                ("fn", "probe", "crate::pair::inner", (23, 24)),
+            ]
+        );
+        assert!(src[items[0].span.clone()].starts_with("/// Doc"));
+        assert!(src[items[2].span.clone()].starts_with("pub const unsafe fn"));
+        assert_eq!(items[3].self_type(), "Pair");
+        assert_eq!(items[3].node_type(), NodeType::Impl);
+        let members = &items[3].children;
+        assert_eq!(members.len(), 1);
+        assert_eq!(
+            (members[0].name.as_str(), members[0].lines),
+            ("fmt", (12, 15))
+        );
+        assert!(items[5].is_test && !items[0].is_test);
+    }
+}
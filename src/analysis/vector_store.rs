@@ -23,6 +23,10 @@ use std::sync::{Arc, RwLock};
 use std::time::{SystemTime, UNIX_EPOCH};
 use tracing::warn;
 
+use super::chunking::{Chunker, CodeAwareChunker};
+#[cfg(feature = "cache")]
+use super::embedding_cache::{EmbeddingCache, EmbeddingCacheStats};
+
 // ---------------------------------------------------------------------------
 // Serde helpers for Arc<Path> and Arc<str>
 // ---------------------------------------------------------------------------
@@ -1245,14 +1249,67 @@ pub enum EmbeddingBackend {
     Mock(MockEmbeddingProvider),
     /// TF-IDF based embedding provider (no external dependencies)
     TfIdf(TfIdfEmbeddingProvider),
+    /// Another backend with its embeddings served from a cache when possible
+    #[cfg(feature = "cache")]
+    Cached(CachedEmbedding),
+}
+
+/// A backend whose embeddings go through an [`EmbeddingCache`]
+#[cfg(feature = "cache")]
+pub struct CachedEmbedding {
+    inner: Box<EmbeddingBackend>,
+    cache: Arc<EmbeddingCache>,
 }
 
 impl EmbeddingBackend {
+    /// Route this backend's embeds through `cache`. Backends without a
+    /// stable [`model_id`](Self::model_id) bypass it.
+    #[cfg(feature = "cache")]
+    pub fn cached(self, cache: Arc<EmbeddingCache>) -> Self {
+        Self::Cached(CachedEmbedding {
+            inner: Box::new(self),
+            cache,
+        })
+    }
+
+    /// Identifies the model when its vectors depend only on the text, so
+    /// they can be cached and compared across sessions. TF-IDF vectors
+    /// depend on the vocabulary built up so far and have none.
+    pub fn model_id(&self) -> Option<String> {
+        match self {
+            Self::Mock(p) => Some(format!("mock-{}", p.dimension())),
+            Self::TfIdf(_) => None,
+            #[cfg(feature = "cache")]
+            Self::Cached(c) => c.inner.model_id(),
+        }
+    }
+
+    /// Hit/miss counts when embeds go through a cache
+    #[cfg(feature = "cache")]
+    pub fn cache_stats(&self) -> Option<EmbeddingCacheStats> {
+        match self {
+            Self::Cached(c) => Some(c.cache.stats()),
+            _ => None,
+        }
+    }
+
     /// Generate embedding for text
     pub async fn embed(&self, text: &str) -> Result<Vec<f32>> {
         match self {
             Self::Mock(p) => p.embed(text).await,
             Self::TfIdf(p) => p.embed(text).await,
+            #[cfg(feature = "cache")]
+            Self::Cached(c) => {
+                let Some(model) = c.inner.model_id() else {
+                    return Box::pin(c.inner.embed(text)).await;
+                };
+                if let Some(vector) = c.cache.get(&model, text) {
+                    return Ok(vector);
+                }
+                let vector = Box::pin(c.inner.embed(text)).await?;
+                c.cache.insert(&model, text, vector.clone());
+                Ok(vector)
+            }
         }
     }
 
@@ -1261,6 +1318,28 @@ impl EmbeddingBackend {
         match self {
             Self::Mock(p) => p.embed_batch(texts).await,
             Self::TfIdf(p) => p.embed_batch(texts).await,
+            #[cfg(feature = "cache")]
+            Self::Cached(c) => {
+                let Some(model) = c.inner.model_id() else {
+                    return Box::pin(c.inner.embed_batch(texts)).await;
+                };
+                let mut vectors: Vec<Option<Vec<f32>>> =
+                    texts.iter().map(|text| c.cache.get(&model, text)).collect();
+                let missing: Vec<usize> =
+                    (0..texts.len()).filter(|&i| vectors[i].is_none()).collect();
+                if !missing.is_empty() {
+                    let batch: Vec<String> = missing.iter().map(|&i| texts[i].clone()).collect();
+                    let embedded = Box::pin(c.inner.embed_batch(&batch)).await?;
+                    for (i, vector) in missing.into_iter().zip(embedded) {
+                        c.cache.insert(&model, &texts[i], vector.clone());
+                        vectors[i] = Some(vector);
+                    }
+                }
+                vectors
+                    .into_iter()
+                    .map(|v| v.ok_or_else(|| anyhow!("Embedding backend returned too few vectors")))
+                    .collect()
+            }
         }
     }
 
@@ -1269,6 +1348,8 @@ impl EmbeddingBackend {
         match self {
             Self::Mock(p) => p.dimension(),
             Self::TfIdf(p) => p.dimension(),
+            #[cfg(feature = "cache")]
+            Self::Cached(c) => c.inner.dimension(),
         }
     }
 }
@@ -1283,8 +1364,8 @@ pub struct VectorStore {
     provider: Arc<EmbeddingBackend>,
     /// Storage path for persistence
     storage_path: Option<PathBuf>,
-    /// Code chunker
-    chunker: CodeChunker,
+    /// Splits files into chunks for indexing
+    chunker: Box<dyn Chunker>,
 }
 
 impl VectorStore {
@@ -1295,7 +1376,7 @@ impl VectorStore {
             indices: HashMap::new(),
             provider,
             storage_path: None,
-            chunker: CodeChunker::default(),
+            chunker: Box::new(CodeAwareChunker::default()),
         }
     }
 
@@ -1306,8 +1387,8 @@ impl VectorStore {
     }
 
     /// Set chunker
-    pub fn with_chunker(mut self, chunker: CodeChunker) -> Self {
-        self.chunker = chunker;
+    pub fn with_chunker(mut self, chunker: impl Chunker + 'static) -> Self {
+        self.chunker = Box::new(chunker);
         self
     }
 
@@ -2575,4 +2656,43 @@ pub fn calculate_product(a: i32, b: i32) -> i32 {
         let index = store.indices.get("project").unwrap();
         assert_eq!(index.check_health(), IndexHealth::Healthy);
     }
+
+    #[cfg(feature = "cache")]
+    #[tokio::test]
+    async fn test_cached_backend_reuses_embeddings() {
+        let cache = Arc::new(EmbeddingCache::new(100));
+        let provider = Arc::new(
+            EmbeddingBackend::Mock(MockEmbeddingProvider::default()).cached(cache.clone()),
+        );
+        let mut store = VectorStore::new(provider.clone());
+
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("lib.rs");
+        std::fs::write(&file_path, "pub fn one() {}\n\npub fn two() {}\n").unwrap();
+        store.collection("project", CollectionScope::Project);
+        store.index_file("project", &file_path).await.unwrap();
+        let first = cache.stats();
+        assert_eq!(first.hits, 0);
+        assert!(first.entries > 0);
+
+        // Re-indexing unchanged content is served from the cache
+        store.rebuild_index("project").await.unwrap();
+        let second = provider.cache_stats().unwrap();
+        assert_eq!(second.hits, first.entries as u64);
+        assert_eq!(second.misses, first.misses);
+
+        let direct = MockEmbeddingProvider::default().embed("x").await.unwrap();
+        assert_eq!(provider.embed("x").await.unwrap(), direct);
+        assert_eq!(
+            provider.embed_batch(&["x".into()]).await.unwrap(),
+            vec![direct]
+        );
+
+        // Backends without a stable model id bypass the cache
+        let tfidf =
+            EmbeddingBackend::TfIdf(TfIdfEmbeddingProvider::default()).cached(cache.clone());
+        let before = cache.stats();
+        tfidf.embed("pub fn one() {}").await.unwrap();
+        assert_eq!(cache.stats(), before);
+    }
 }
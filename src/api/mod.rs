@@ -4,14 +4,16 @@ use futures::StreamExt;
 use reqwest::Client;
 use std::time::Duration;
 use tokio::sync::mpsc;
-use tracing::{debug, warn};
+use tracing::{debug, warn, Instrument};
 
+pub mod provider;
 pub mod types;
 
 use crate::errors::ApiError;
 use crate::supervision::circuit_breaker::{
-    CircuitBreaker, CircuitBreakerConfig, CircuitBreakerError,
+    CircuitBreaker, CircuitBreakerConfig, CircuitBreakerError, CircuitBreakerMetrics,
 };
+use provider::{GenericAdapter, ProviderAdapter, StreamFormat};
 use std::sync::Arc;
 use types::*;
 
@@ -79,6 +81,7 @@ pub trait LlmClient: Send + Sync {
 pub struct StreamingResponse {
     response: reqwest::Response,
     chunk_timeout: Duration,
+    adapter: Arc<dyn ProviderAdapter>,
 }
 
 impl std::fmt::Debug for StreamingResponse {
@@ -95,9 +98,16 @@ impl StreamingResponse {
         Self {
             response,
             chunk_timeout,
+            adapter: Arc::new(GenericAdapter),
         }
     }
 
+    /// Normalize each chunk with `adapter` before it is parsed
+    fn with_adapter(mut self, adapter: Arc<dyn ProviderAdapter>) -> Self {
+        self.adapter = adapter;
+        self
+    }
+
     /// Process the stream and send chunks through a channel
     pub async fn into_channel(self) -> mpsc::Receiver<Result<StreamChunk>> {
         let (tx, rx) = mpsc::channel(32);
@@ -107,9 +117,24 @@ impl StreamingResponse {
             let mut buffer = String::new();
             let mut accumulator = ToolCallAccumulator::new();
             let chunk_timeout = self.chunk_timeout;
+            let adapter = self.adapter;
+            let format = adapter.stream_format();
+            let delimiter = match format {
+                StreamFormat::Sse => "\n\n",
+                StreamFormat::Ndjson => "\n",
+            };
+            let parse = |event: &str, accumulator: &mut ToolCallAccumulator| match format {
+                StreamFormat::Sse => parse_sse_event(event, accumulator, adapter.as_ref()),
+                StreamFormat::Ndjson => parse_ndjson_line(event, accumulator),
+            };
 
             loop {
-                let chunk_opt = match tokio::time::timeout(chunk_timeout, stream.next()).await {
+                let next = tokio::select! {
+                    next = tokio::time::timeout(chunk_timeout, stream.next()) => next,
+                    // Receiver dropped (e.g. on cancellation): stop reading
+                    _ = tx.closed() => return,
+                };
+                let chunk_opt = match next {
                     Ok(Some(result)) => Some(result),
                     Ok(None) => None, // Stream ended
                     Err(_elapsed) => {
@@ -141,12 +166,12 @@ impl StreamingResponse {
                     Ok(bytes) => {
                         buffer.push_str(&String::from_utf8_lossy(&bytes));
 
-                        // Process complete SSE events
-                        while let Some(pos) = buffer.find("\n\n") {
+                        // Process complete SSE events or NDJSON lines
+                        while let Some(pos) = buffer.find(delimiter) {
                             let event = buffer[..pos].to_string();
-                            buffer = buffer[pos + 2..].to_string();
+                            buffer = buffer[pos + delimiter.len()..].to_string();
 
-                            for chunk in parse_sse_event(&event, &mut accumulator) {
+                            for chunk in parse(&event, &mut accumulator) {
                                 if tx.send(Ok(chunk)).await.is_err() {
                                     warn!(
                                         "Streaming receiver dropped while forwarding parsed stream chunk"
@@ -179,10 +204,10 @@ impl StreamingResponse {
                 }
             }
 
-            // Flush trailing buffer (data without final \n\n)
+            // Flush trailing buffer (data without a final delimiter)
             let remaining = buffer.trim().to_string();
             if !remaining.is_empty() {
-                for chunk in parse_sse_event(&remaining, &mut accumulator) {
+                for chunk in parse(&remaining, &mut accumulator) {
                     if tx.send(Ok(chunk)).await.is_err() {
                         warn!("Streaming receiver dropped while sending trailing buffered chunk");
                         return;
@@ -283,12 +308,19 @@ pub enum StreamChunk {
 /// 1. First delta: `index`, `id`, `type`, `function.name`, partial `function.arguments`
 /// 2. Subsequent deltas: same `index`, only `function.arguments` chunk
 ///
-/// The accumulator buffers these per-index and emits a complete `ToolCall` when
-/// a new index appears or when `flush()` is called at stream end.
+/// The accumulator buffers these per-index and emits complete `ToolCall`s when
+/// `flush()` is called at stream end.
+///
+/// Calls are always emitted in ascending `index` order, whatever order their
+/// deltas arrive in, so the agent executes them (and appends their results)
+/// in the order the model emitted them. A later call may depend on an
+/// earlier one, such as an edit after a read of the same file.
 #[derive(Default)]
 struct ToolCallAccumulator {
-    /// In-progress tool calls keyed by index
-    pending: std::collections::HashMap<usize, (String, String, String, String)>, // (id, type, name, args)
+    /// In-progress tool calls keyed (and so ordered) by index
+    pending: std::collections::BTreeMap<usize, (String, String, String, String)>, // (id, type, name, args)
+    /// Whole tool calls numbered so far (NDJSON streams carry no index)
+    seen: usize,
 }
 
 impl ToolCallAccumulator {
@@ -346,11 +378,10 @@ impl ToolCallAccumulator {
         None
     }
 
-    /// Flush all pending tool calls, returning completed ToolCall objects.
+    /// Flush all pending tool calls, returning completed ToolCall objects in
+    /// ascending index order.
     fn flush(&mut self) -> Vec<types::ToolCall> {
-        let mut calls: Vec<_> = self.pending.drain().collect();
-        calls.sort_by_key(|(idx, _)| *idx);
-        calls
+        std::mem::take(&mut self.pending)
             .into_iter()
             .map(|(_, (id, call_type, name, args))| types::ToolCall {
                 id,
@@ -370,7 +401,11 @@ impl ToolCallAccumulator {
 /// arrive in the same JSON payload). The accumulator buffers incremental tool call
 /// deltas; call `accumulator.flush()` at stream end to emit any remaining calls.
 // Streaming infrastructure (used by chat_streaming)
-fn parse_sse_event(event: &str, accumulator: &mut ToolCallAccumulator) -> Vec<StreamChunk> {
+fn parse_sse_event(
+    event: &str,
+    accumulator: &mut ToolCallAccumulator,
+    adapter: &dyn ProviderAdapter,
+) -> Vec<StreamChunk> {
     let mut chunks = Vec::new();
 
     for line in event.lines() {
@@ -384,7 +419,8 @@ fn parse_sse_event(event: &str, accumulator: &mut ToolCallAccumulator) -> Vec<St
                 return chunks;
             }
 
-            if let Ok(json) = serde_json::from_str::<serde_json::Value>(data) {
+            if let Ok(mut json) = serde_json::from_str::<serde_json::Value>(data) {
+                adapter.adapt_response(&mut json);
                 let choice = json.get("choices").and_then(|c| c.get(0));
                 let delta = choice.and_then(|c| c.get("delta"));
 
@@ -443,6 +479,72 @@ fn parse_sse_event(event: &str, accumulator: &mut ToolCallAccumulator) -> Vec<St
     chunks
 }
 
+/// Parse one line of an Ollama `/api/chat` stream.
+///
+/// Every line is a complete JSON object: `message.content` and
+/// `message.thinking` carry the text deltas, tool calls arrive whole in
+/// `message.tool_calls`, and the last line has `done: true` along with the
+/// prompt (`prompt_eval_count`) and completion (`eval_count`) token counts.
+/// Tool calls go through the accumulator only to be numbered across lines.
+fn parse_ndjson_line(line: &str, accumulator: &mut ToolCallAccumulator) -> Vec<StreamChunk> {
+    let mut chunks = Vec::new();
+    let Ok(json) = serde_json::from_str::<serde_json::Value>(line.trim()) else {
+        return chunks;
+    };
+    if let Some(error) = json.get("error").and_then(|e| e.as_str()) {
+        warn!("Ollama stream error: {}", error);
+        return chunks;
+    }
+    let message = json.get("message");
+
+    if let Some(thinking) = message
+        .and_then(|m| m.get("thinking"))
+        .and_then(|t| t.as_str())
+    {
+        if !thinking.is_empty() {
+            chunks.push(StreamChunk::Reasoning(thinking.to_string()));
+        }
+    }
+    if let Some(content) = message
+        .and_then(|m| m.get("content"))
+        .and_then(|c| c.as_str())
+    {
+        if !content.is_empty() {
+            chunks.push(StreamChunk::Content(content.to_string()));
+        }
+    }
+    if let Some(calls) = message
+        .and_then(|m| m.get("tool_calls"))
+        .and_then(|tc| tc.as_array())
+    {
+        for call in calls {
+            let mut delta = provider::from_native_tool_call(call, accumulator.seen);
+            delta["index"] = serde_json::json!(accumulator.seen);
+            accumulator.seen += 1;
+            accumulator.process_delta(&delta);
+        }
+    }
+
+    if json.get("done").and_then(|d| d.as_bool()) == Some(true) {
+        for call in accumulator.flush() {
+            chunks.push(StreamChunk::ToolCall(call));
+        }
+        let prompt = json.get("prompt_eval_count").and_then(|c| c.as_u64());
+        let completion = json.get("eval_count").and_then(|c| c.as_u64());
+        if prompt.is_some() || completion.is_some() {
+            let prompt_tokens = prompt.unwrap_or(0) as usize;
+            let completion_tokens = completion.unwrap_or(0) as usize;
+            chunks.push(StreamChunk::Usage(Usage {
+                prompt_tokens,
+                completion_tokens,
+                total_tokens: prompt_tokens + completion_tokens,
+            }));
+        }
+        chunks.push(StreamChunk::Done);
+    }
+    chunks
+}
+
 /// Retry configuration for API calls
 #[derive(Clone, Debug)]
 pub struct RetryConfig {
@@ -452,6 +554,8 @@ pub struct RetryConfig {
     pub initial_delay_ms: u64,
     /// Maximum delay between retries
     pub max_delay_ms: u64,
+    /// Initial delay after a 429 that carries no `Retry-After` (doubles each time)
+    pub rate_limit_delay_ms: u64,
     /// HTTP status codes that should trigger a retry
     pub retryable_status_codes: Vec<u16>,
 }
@@ -462,6 +566,7 @@ impl Default for RetryConfig {
             max_retries: 3,
             initial_delay_ms: 1000,
             max_delay_ms: 30000,
+            rate_limit_delay_ms: 5000,
             retryable_status_codes: vec![429, 500, 502, 503, 504],
         }
     }
@@ -473,11 +578,124 @@ impl RetryConfig {
             max_retries: settings.max_retries,
             initial_delay_ms: settings.base_delay_ms,
             max_delay_ms: settings.max_delay_ms,
+            rate_limit_delay_ms: settings.rate_limit_base_delay_ms,
             retryable_status_codes: vec![429, 500, 502, 503, 504],
         }
     }
 }
 
+/// Longest `Retry-After` wait we will honour.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(300);
+
+/// How the delay before a retry was chosen, for logging.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum BackoffStrategy {
+    /// The server's `Retry-After` header.
+    RetryAfter,
+    /// Rate limited (429) without `Retry-After`: longer exponential backoff.
+    RateLimit,
+    /// Transient server or network failure: standard exponential backoff.
+    Transient,
+}
+
+impl std::fmt::Display for BackoffStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BackoffStrategy::RetryAfter => write!(f, "honouring Retry-After"),
+            BackoffStrategy::RateLimit => write!(f, "rate-limit backoff"),
+            BackoffStrategy::Transient => write!(f, "exponential backoff"),
+        }
+    }
+}
+
+/// Per-request retry delays. Rate limits and transient failures back off on
+/// separate schedules so a 429 storm does not inherit the short 5xx delays.
+struct Backoff<'a> {
+    config: &'a RetryConfig,
+    rate_limited: u32,
+    transient: u32,
+}
+
+impl<'a> Backoff<'a> {
+    fn new(config: &'a RetryConfig) -> Self {
+        Self {
+            config,
+            rate_limited: 0,
+            transient: 0,
+        }
+    }
+
+    /// Delay before retrying a failed attempt. `status` is `None` for network
+    /// errors; `retry_after` is the parsed header, if the response had one.
+    fn next_delay(
+        &mut self,
+        status: Option<u16>,
+        retry_after: Option<Duration>,
+    ) -> (Duration, BackoffStrategy) {
+        let retry_after = retry_after.map(|d| d.min(MAX_RETRY_AFTER));
+        if status == Some(429) {
+            if let Some(wait) = retry_after {
+                return (wait, BackoffStrategy::RetryAfter);
+            }
+            let delay = self.exponential(self.config.rate_limit_delay_ms, self.rate_limited);
+            self.rate_limited += 1;
+            return (delay, BackoffStrategy::RateLimit);
+        }
+
+        let delay = self.exponential(self.config.initial_delay_ms, self.transient);
+        self.transient += 1;
+        // Other statuses (e.g. 503) may also send Retry-After; treat it as a floor
+        match retry_after {
+            Some(wait) if wait > delay => (wait, BackoffStrategy::RetryAfter),
+            _ => (delay, BackoffStrategy::Transient),
+        }
+    }
+
+    /// `base * 2^n` with +-5% jitter, capped at `max_delay_ms`.
+    fn exponential(&self, base_ms: u64, n: u32) -> Duration {
+        let delay_ms = base_ms
+            .saturating_mul(1u64 << n.min(32))
+            .min(self.config.max_delay_ms);
+        // Use signed arithmetic to avoid u64 overflow
+        let jitter = (delay_ms as f64 * 0.1 * (rand_jitter() - 0.5)) as i64;
+        let delay_ms = (delay_ms as i64).saturating_add(jitter).max(1) as u64;
+        Duration::from_millis(delay_ms.min(self.config.max_delay_ms))
+    }
+}
+
+/// Parse a `Retry-After` header: either delay-seconds or an HTTP-date
+/// (`Wed, 21 Oct 2015 07:28:00 GMT`). A date in the past means "now".
+fn parse_retry_after(value: &str, now: chrono::DateTime<chrono::Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    Some(
+        date.with_timezone(&chrono::Utc)
+            .signed_duration_since(now)
+            .to_std()
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+/// Model and sampling settings one chat request goes out with
+struct ChatTarget<'a> {
+    model: &'a str,
+    temperature: f32,
+    max_tokens: usize,
+}
+
+impl<'a> ChatTarget<'a> {
+    fn default_model(config: &'a crate::config::Config) -> Self {
+        Self {
+            model: &config.model,
+            temperature: config.temperature,
+            max_tokens: config.max_tokens,
+        }
+    }
+}
+
 /// HTTP client for OpenAI-compatible chat completion APIs.
 ///
 /// Supports both synchronous and streaming requests, native tool calling,
@@ -489,6 +707,7 @@ pub struct ApiClient {
     base_url: String,
     retry_config: RetryConfig,
     circuit_breaker: Arc<CircuitBreaker>,
+    adapter: Arc<dyn ProviderAdapter>,
 }
 
 impl ApiClient {
@@ -522,6 +741,7 @@ impl ApiClient {
             config: config.clone(),
             retry_config: RetryConfig::from_settings(&config.retry),
             circuit_breaker: Arc::new(CircuitBreaker::new(CircuitBreakerConfig::default())),
+            adapter: provider::adapter_for(config.provider),
         })
     }
 
@@ -532,6 +752,33 @@ impl ApiClient {
         self
     }
 
+    /// Circuit breaker state and failure counts for this client's endpoint.
+    pub fn circuit_state(&self) -> CircuitBreakerMetrics {
+        self.circuit_breaker.metrics()
+    }
+
+    /// Close the circuit breaker immediately instead of waiting for the
+    /// cooldown, e.g. after restarting a local server. Returns false if it
+    /// was already closed.
+    pub async fn reset_circuit(&self) -> bool {
+        self.circuit_breaker.reset().await
+    }
+
+    fn circuit_open_error(&self) -> anyhow::Error {
+        let state = self.circuit_state();
+        let mut message = String::from("Circuit breaker is open - API is unavailable");
+        if let Some(retry_in) = state.retry_in {
+            message.push_str(&format!(
+                " (retrying in {}s, or run /reset-circuit)",
+                retry_in.as_secs().max(1)
+            ));
+        }
+        if let Some(err) = state.last_error {
+            message.push_str(&format!(". Last error: {}", err));
+        }
+        anyhow::anyhow!(message)
+    }
+
     /// Send a completion request (e.g. for FIM)
     pub async fn completion(
         &self,
@@ -543,9 +790,7 @@ impl ApiClient {
             .call(|| self.completion_inner(prompt, max_tokens, stop.clone()))
             .await
             .map_err(|e| match e {
-                CircuitBreakerError::CircuitOpen => {
-                    anyhow::anyhow!("Circuit breaker is open - API is unavailable")
-                }
+                CircuitBreakerError::CircuitOpen => self.circuit_open_error(),
                 CircuitBreakerError::OperationFailed(err) => err,
             })
     }
@@ -581,11 +826,7 @@ impl ApiClient {
         if !response.status().is_success() {
             let status = response.status();
             let text = response.text().await.unwrap_or_default();
-            return Err(ApiError::HttpStatus {
-                status: status.as_u16(),
-                message: text,
-            }
-            .into());
+            return Err(ApiError::from_status(status.as_u16(), text).into());
         }
 
         let resp: types::CompletionResponse = response.json().await?;
@@ -598,34 +839,42 @@ impl ApiClient {
         tools: Option<Vec<ToolDefinition>>,
         thinking: ThinkingMode,
     ) -> Result<ChatResponse> {
-        let mut messages = messages;
-        if let ThinkingMode::Disabled = thinking {
-            let sys_msg = crate::api::types::Message::system("CRITICAL INSTRUCTION: DO NOT use <think> blocks or any thinking process in your response. Output your final response directly and immediately.");
-            messages.insert(0, sys_msg);
-        }
+        let body = self.chat_body(
+            ChatTarget::default_model(&self.config),
+            messages,
+            tools,
+            thinking,
+            false,
+        );
+        self.send_with_retry(&body).await
+    }
 
+    /// Build a chat completion body and let the provider adapter reshape it
+    fn chat_body(
+        &self,
+        target: ChatTarget<'_>,
+        mut messages: Vec<Message>,
+        tools: Option<Vec<ToolDefinition>>,
+        thinking: ThinkingMode,
+        stream: bool,
+    ) -> serde_json::Value {
+        self.adapter.adapt_messages(&mut messages, thinking);
         canonicalize_message_order(&mut messages);
 
         let mut body = serde_json::json!({
-            "model": self.config.model,
+            "model": target.model,
             "messages": messages,
-            "temperature": self.config.temperature,
-            "max_tokens": self.config.max_tokens,
-            "stream": false,
+            "temperature": target.temperature,
+            "max_tokens": target.max_tokens,
+            "stream": stream,
         });
 
         if let Some(ref tools) = tools {
             body["tools"] = serde_json::json!(tools);
         }
 
-        if let ThinkingMode::Budget(tokens) = thinking {
-            body["thinking"] = serde_json::json!({
-                "type": "enabled",
-                "budget_tokens": tokens
-            });
-        }
-
-        self.send_with_retry(&body).await
+        self.adapter.adapt_request(&mut body, thinking);
+        body
     }
 
     /// Stream a chat completion response
@@ -636,15 +885,28 @@ impl ApiClient {
         tools: Option<Vec<ToolDefinition>>,
         thinking: ThinkingMode,
     ) -> Result<StreamingResponse> {
-        self.circuit_breaker
+        // Covers the request up to the response headers; tokens arrive later
+        // on the stream
+        let span = crate::telemetry::api_request_span(&self.config.model, true);
+        let start = std::time::Instant::now();
+        let result = self
+            .circuit_breaker
             .call(|| self.chat_stream_inner(messages.clone(), tools.clone(), thinking))
+            .instrument(span.clone())
             .await
             .map_err(|e| match e {
-                CircuitBreakerError::CircuitOpen => {
-                    anyhow::anyhow!("Circuit breaker is open - API is unavailable")
-                }
+                CircuitBreakerError::CircuitOpen => self.circuit_open_error(),
                 CircuitBreakerError::OperationFailed(err) => err,
-            })
+            });
+
+        let elapsed = start.elapsed().as_millis() as u64;
+        match &result {
+            Ok(_) => crate::telemetry::record_span_outcome(&span, "ok", elapsed, None),
+            Err(e) => {
+                crate::telemetry::record_span_outcome(&span, "error", elapsed, Some(&e.to_string()))
+            }
+        }
+        result
     }
 
     async fn chat_stream_inner(
@@ -653,34 +915,15 @@ impl ApiClient {
         tools: Option<Vec<ToolDefinition>>,
         thinking: ThinkingMode,
     ) -> Result<StreamingResponse> {
-        let mut messages = messages;
-        if let ThinkingMode::Disabled = thinking {
-            let sys_msg = crate::api::types::Message::system("CRITICAL INSTRUCTION: DO NOT use <think> blocks or any thinking process in your response. Output your final response directly and immediately.");
-            messages.insert(0, sys_msg);
-        }
-
-        canonicalize_message_order(&mut messages);
-
-        let mut body = serde_json::json!({
-            "model": self.config.model,
-            "messages": messages,
-            "temperature": self.config.temperature,
-            "max_tokens": self.config.max_tokens,
-            "stream": true,
-        });
-
-        if let Some(ref tools) = tools {
-            body["tools"] = serde_json::json!(tools);
-        }
-
-        if let ThinkingMode::Budget(tokens) = thinking {
-            body["thinking"] = serde_json::json!({
-                "type": "enabled",
-                "budget_tokens": tokens
-            });
-        }
+        let body = self.chat_body(
+            ChatTarget::default_model(&self.config),
+            messages,
+            tools,
+            thinking,
+            true,
+        );
 
-        let url = format!("{}/chat/completions", self.base_url);
+        let url = self.adapter.chat_url(&self.base_url);
         debug!("Starting streaming request to {}", url);
 
         let mut request = self
@@ -701,19 +944,15 @@ impl ApiClient {
         if !response.status().is_success() {
             let status = response.status();
             let text = response.text().await.unwrap_or_default();
-            return Err(ApiError::HttpStatus {
-                status: status.as_u16(),
-                message: text,
-            }
-            .into());
+            return Err(ApiError::from_status(status.as_u16(), text).into());
         }
 
         // Use configurable per-step timeout for stream inactivity instead of a fixed constant.
         let stream_chunk_timeout_secs = self.config.agent.step_timeout_secs.max(30);
-        Ok(StreamingResponse::new(
-            response,
-            Duration::from_secs(stream_chunk_timeout_secs),
-        ))
+        Ok(
+            StreamingResponse::new(response, Duration::from_secs(stream_chunk_timeout_secs))
+                .with_adapter(self.adapter.clone()),
+        )
     }
 
     /// Send request with exponential backoff retry logic, wrapped in a circuit breaker
@@ -722,9 +961,7 @@ impl ApiClient {
             .call(|| self.send_with_retry_inner(body))
             .await
             .map_err(|e| match e {
-                CircuitBreakerError::CircuitOpen => {
-                    anyhow::anyhow!("Circuit breaker is open - API is unavailable")
-                }
+                CircuitBreakerError::CircuitOpen => self.circuit_open_error(),
                 CircuitBreakerError::OperationFailed(err) => err,
             })
     }
@@ -743,26 +980,59 @@ impl ApiClient {
         endpoint: &str,
         api_key: Option<&crate::config::RedactedString>,
     ) -> Result<ChatResponse> {
-        let url = format!("{}/chat/completions", endpoint);
+        let model = body.get("model").and_then(|m| m.as_str()).unwrap_or("");
+        let span = crate::telemetry::api_request_span(model, false);
+        let start = std::time::Instant::now();
+        let result = self
+            .send_request_attempts(body, endpoint, api_key)
+            .instrument(span.clone())
+            .await;
+
+        let elapsed = start.elapsed().as_millis() as u64;
+        match &result {
+            Ok(response) => {
+                span.record("prompt_tokens", response.usage.prompt_tokens);
+                span.record("completion_tokens", response.usage.completion_tokens);
+                crate::telemetry::record_span_outcome(&span, "ok", elapsed, None);
+            }
+            Err(e) => {
+                crate::telemetry::record_span_outcome(
+                    &span,
+                    "error",
+                    elapsed,
+                    Some(&e.to_string()),
+                );
+            }
+        }
+        result
+    }
+
+    async fn send_request_attempts(
+        &self,
+        body: &serde_json::Value,
+        endpoint: &str,
+        api_key: Option<&crate::config::RedactedString>,
+    ) -> Result<ChatResponse> {
+        let url = self.adapter.chat_url(endpoint);
         let mut last_error: Option<anyhow::Error> = None;
-        let mut delay_ms = self.retry_config.initial_delay_ms;
+        let mut backoff = Backoff::new(&self.retry_config);
+        let mut next_delay = (Duration::ZERO, BackoffStrategy::Transient);
 
         for attempt in 0..=self.retry_config.max_retries {
             if attempt > 0 {
+                let (delay, strategy) = next_delay;
                 warn!(
-                    "Retry attempt {}/{} after {}ms delay",
-                    attempt, self.retry_config.max_retries, delay_ms
+                    "Retry attempt {}/{} after {}ms delay ({})",
+                    attempt,
+                    self.retry_config.max_retries,
+                    delay.as_millis(),
+                    strategy
                 );
-                tokio::time::sleep(Duration::from_millis(delay_ms)).await;
-                // Exponential backoff with jitter
-                delay_ms = (delay_ms * 2).min(self.retry_config.max_delay_ms);
-                // Add jitter (+-10%) -- use signed arithmetic to avoid u64 overflow
-                let jitter = (delay_ms as f64 * 0.1 * (rand_jitter() - 0.5)) as i64;
-                delay_ms = (delay_ms as i64).saturating_add(jitter).max(1) as u64;
-                delay_ms = delay_ms.min(self.retry_config.max_delay_ms);
+                tokio::time::sleep(delay).await;
             }
 
             debug!("Sending request to {} (attempt {})", url, attempt + 1);
+            tracing::Span::current().record("attempts", attempt + 1);
 
             let mut request = self
                 .client
@@ -793,7 +1063,10 @@ impl ApiClient {
                             eprintln!("=== RAW API RESPONSE ===\n{}\n=== END RAW ===", body_text);
                         }
 
-                        let chat_response: ChatResponse = serde_json::from_str(&body_text)
+                        let mut json: serde_json::Value = serde_json::from_str(&body_text)
+                            .context("Failed to parse response JSON")?;
+                        self.adapter.adapt_response(&mut json);
+                        let chat_response: ChatResponse = serde_json::from_value(json)
                             .context("Failed to parse response JSON")?;
                         return Ok(chat_response);
                     }
@@ -804,16 +1077,18 @@ impl ApiClient {
                         .retryable_status_codes
                         .contains(&status.as_u16())
                     {
-                        // Parse Retry-After header before consuming the body.
-                        // Supports numeric seconds format; capped at 300s.
-                        let retry_after_secs = response
+                        // Parse Retry-After header before consuming the body
+                        let retry_after = response
                             .headers()
                             .get("retry-after")
                             .and_then(|v| v.to_str().ok())
-                            .and_then(|s| s.trim().parse::<u64>().ok())
-                            .map(|s| s.min(300));
+                            .and_then(|s| parse_retry_after(s, chrono::Utc::now()));
 
                         let error_text = response.text().await.unwrap_or_default();
+                        // Resending an oversized request fails the same way
+                        if ApiError::is_context_overflow_message(&error_text) {
+                            return Err(ApiError::ContextLengthExceeded(error_text).into());
+                        }
                         warn!("Retryable error ({}): {}", status, error_text);
                         last_error = Some(
                             ApiError::HttpStatus {
@@ -823,30 +1098,21 @@ impl ApiClient {
                             .into(),
                         );
 
-                        // Honour Retry-After as minimum wait for 429/503
-                        if let Some(retry_secs) = retry_after_secs {
-                            let retry_ms = retry_secs * 1000;
-                            if retry_ms > delay_ms {
-                                delay_ms = retry_ms;
-                            }
-                        }
+                        next_delay = backoff.next_delay(Some(status.as_u16()), retry_after);
                         continue;
                     }
 
                     // Non-retryable error
                     let status_code = status.as_u16();
                     let error_text = response.text().await.unwrap_or_default();
-                    return Err(ApiError::HttpStatus {
-                        status: status_code,
-                        message: error_text,
-                    }
-                    .into());
+                    return Err(ApiError::from_status(status_code, error_text).into());
                 }
                 Err(e) => {
                     // Network errors are generally retryable
                     if e.is_timeout() || e.is_connect() {
                         warn!("Network error (retrying): {}", e);
                         last_error = Some(ApiError::Network(e.to_string()).into());
+                        next_delay = backoff.next_delay(None, None);
                         continue;
                     }
                     // Other errors (e.g., invalid URL) are not retryable
@@ -864,6 +1130,7 @@ impl ApiClient {
     /// Send a chat completion to an alternate model described by a `ModelProfile`.
     ///
     /// Images are automatically stripped if the profile does not support vision.
+    /// The request is shaped for `Config.provider`, like the default model's.
     pub async fn chat_with_profile(
         &self,
         messages: Vec<Message>,
@@ -877,24 +1144,12 @@ impl ApiClient {
             messages
         };
 
-        let mut body = serde_json::json!({
-            "model": profile.model,
-            "messages": messages,
-            "temperature": profile.temperature,
-            "max_tokens": profile.max_tokens,
-            "stream": false,
-        });
-
-        if let Some(ref tools) = tools {
-            body["tools"] = serde_json::json!(tools);
-        }
-
-        if let ThinkingMode::Budget(tokens) = thinking {
-            body["thinking"] = serde_json::json!({
-                "type": "enabled",
-                "budget_tokens": tokens
-            });
-        }
+        let target = ChatTarget {
+            model: &profile.model,
+            temperature: profile.temperature,
+            max_tokens: profile.max_tokens,
+        };
+        let body = self.chat_body(target, messages, tools, thinking, false);
 
         self.send_request_with_retry(&body, &profile.endpoint, profile.api_key.as_ref())
             .await
@@ -939,7 +1194,6 @@ pub enum ThinkingMode {
     /// Thinking disabled for faster responses
     Disabled,
     /// Thinking with a specific token budget
-    #[allow(dead_code)] // For models supporting thinking budget
     Budget(usize),
 }
 
@@ -1043,7 +1297,7 @@ mod tests {
     fn test_parse_sse_event_done() {
         let mut acc = ToolCallAccumulator::new();
         let event = "data: [DONE]";
-        let results = parse_sse_event(event, &mut acc);
+        let results = parse_sse_event(event, &mut acc, &GenericAdapter);
         assert_eq!(results.len(), 1);
         assert!(matches!(results[0], StreamChunk::Done));
     }
@@ -1052,7 +1306,7 @@ mod tests {
     fn test_parse_sse_event_content() {
         let mut acc = ToolCallAccumulator::new();
         let event = r#"data: {"choices":[{"delta":{"content":"Hello"}}]}"#;
-        let results = parse_sse_event(event, &mut acc);
+        let results = parse_sse_event(event, &mut acc, &GenericAdapter);
         assert_eq!(results.len(), 1);
         assert!(matches!(&results[0], StreamChunk::Content(t) if t == "Hello"));
     }
@@ -1061,7 +1315,7 @@ mod tests {
     fn test_parse_sse_event_reasoning() {
         let mut acc = ToolCallAccumulator::new();
         let event = r#"data: {"choices":[{"delta":{"reasoning_content":"Thinking about it"}}]}"#;
-        let results = parse_sse_event(event, &mut acc);
+        let results = parse_sse_event(event, &mut acc, &GenericAdapter);
         assert_eq!(results.len(), 1);
         assert!(matches!(&results[0], StreamChunk::Reasoning(_)));
     }
@@ -1071,7 +1325,7 @@ mod tests {
         let mut acc = ToolCallAccumulator::new();
         let event =
             r#"data: {"usage":{"prompt_tokens":10,"completion_tokens":5,"total_tokens":15}}"#;
-        let results = parse_sse_event(event, &mut acc);
+        let results = parse_sse_event(event, &mut acc, &GenericAdapter);
         assert_eq!(results.len(), 1);
         assert!(matches!(&results[0], StreamChunk::Usage(_)));
     }
@@ -1080,7 +1334,7 @@ mod tests {
     fn test_parse_sse_event_empty_content() {
         let mut acc = ToolCallAccumulator::new();
         let event = r#"data: {"choices":[{"delta":{"content":""}}]}"#;
-        let results = parse_sse_event(event, &mut acc);
+        let results = parse_sse_event(event, &mut acc, &GenericAdapter);
         assert!(results.is_empty());
     }
 
@@ -1088,7 +1342,7 @@ mod tests {
     fn test_parse_sse_event_no_data_prefix() {
         let mut acc = ToolCallAccumulator::new();
         let event = "not a data line";
-        let results = parse_sse_event(event, &mut acc);
+        let results = parse_sse_event(event, &mut acc, &GenericAdapter);
         assert!(results.is_empty());
     }
 
@@ -1096,7 +1350,7 @@ mod tests {
     fn test_parse_sse_event_invalid_json() {
         let mut acc = ToolCallAccumulator::new();
         let event = "data: {invalid json}";
-        let results = parse_sse_event(event, &mut acc);
+        let results = parse_sse_event(event, &mut acc, &GenericAdapter);
         assert!(results.is_empty());
     }
 
@@ -1104,7 +1358,7 @@ mod tests {
     fn test_parse_sse_event_multiline() {
         let mut acc = ToolCallAccumulator::new();
         let event = "event: message\ndata: [DONE]";
-        let results = parse_sse_event(event, &mut acc);
+        let results = parse_sse_event(event, &mut acc, &GenericAdapter);
         assert_eq!(results.len(), 1);
         assert!(matches!(results[0], StreamChunk::Done));
     }
@@ -1126,6 +1380,7 @@ mod tests {
             max_retries: 9,
             base_delay_ms: 250,
             max_delay_ms: 12000,
+            rate_limit_base_delay_ms: 250,
         };
         let config = RetryConfig::from_settings(&settings);
 
@@ -1171,6 +1426,7 @@ mod tests {
             max_retries: 5,
             initial_delay_ms: 500,
             max_delay_ms: 60000,
+            rate_limit_delay_ms: 500,
             retryable_status_codes: vec![429, 503],
         };
         assert_eq!(config.max_retries, 5);
@@ -1220,6 +1476,7 @@ mod tests {
             max_retries: 10,
             initial_delay_ms: 10000,
             max_delay_ms: 15000,
+            rate_limit_delay_ms: 10000,
             retryable_status_codes: vec![500],
         };
 
@@ -1234,6 +1491,72 @@ mod tests {
         assert_eq!(delay_ms, 15000);
     }
 
+    #[test]
+    fn test_backoff_rate_limit_uses_longer_schedule() {
+        let config = RetryConfig {
+            max_retries: 5,
+            initial_delay_ms: 100,
+            max_delay_ms: 60000,
+            rate_limit_delay_ms: 4000,
+            retryable_status_codes: vec![429, 500],
+        };
+        let mut backoff = Backoff::new(&config);
+        let within = |d: Duration, ms: u64| d.as_millis().abs_diff(ms as u128) <= ms as u128 / 20;
+
+        let (delay, strategy) = backoff.next_delay(Some(500), None);
+        assert_eq!(strategy, BackoffStrategy::Transient);
+        assert!(within(delay, 100), "{:?}", delay);
+
+        let (first, strategy) = backoff.next_delay(Some(429), None);
+        assert_eq!(strategy, BackoffStrategy::RateLimit);
+        assert!(within(first, 4000), "{:?}", first);
+        let (second, _) = backoff.next_delay(Some(429), None);
+        assert!(within(second, 8000), "{:?}", second);
+
+        // The 5xx schedule is independent of the 429 one
+        let (delay, _) = backoff.next_delay(Some(503), None);
+        assert!(within(delay, 200), "{:?}", delay);
+    }
+
+    #[test]
+    fn test_backoff_honours_retry_after() {
+        let config = RetryConfig::default();
+        let mut backoff = Backoff::new(&config);
+
+        // 429 uses Retry-After instead of the computed delay, even when shorter
+        let (delay, strategy) = backoff.next_delay(Some(429), Some(Duration::from_secs(2)));
+        assert_eq!(strategy, BackoffStrategy::RetryAfter);
+        assert_eq!(delay, Duration::from_secs(2));
+
+        let (delay, _) = backoff.next_delay(Some(429), Some(Duration::from_secs(3600)));
+        assert_eq!(delay, MAX_RETRY_AFTER);
+
+        // For 5xx it is only a floor
+        let (delay, strategy) = backoff.next_delay(Some(503), Some(Duration::ZERO));
+        assert_eq!(strategy, BackoffStrategy::Transient);
+        assert!(delay > Duration::ZERO);
+    }
+
+    #[test]
+    fn test_parse_retry_after_seconds_and_http_date() {
+        let now = chrono::DateTime::parse_from_rfc2822("Wed, 21 Oct 2015 07:28:00 GMT")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        assert_eq!(
+            parse_retry_after(" 120 ", now),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:30 GMT", now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:00:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
     // ============================================
     // Request Construction Tests
     // ============================================
@@ -1511,7 +1834,7 @@ mod tests {
         // Test parsing SSE event with complete tool call delta
         let mut acc = ToolCallAccumulator::new();
         let event = r#"data: {"choices":[{"delta":{"tool_calls":[{"index":0,"id":"call_123","type":"function","function":{"name":"file_read","arguments":"{\"path\":\"/test\"}"}}]}}]}"#;
-        let results = parse_sse_event(event, &mut acc);
+        let results = parse_sse_event(event, &mut acc, &GenericAdapter);
         // Tool call is buffered in accumulator, not emitted yet
         assert!(results.is_empty());
 
@@ -1530,17 +1853,17 @@ mod tests {
 
         // First chunk: id, name, partial args
         let event1 = r#"data: {"choices":[{"delta":{"tool_calls":[{"index":0,"id":"call_456","type":"function","function":{"name":"file_write","arguments":"{\"path\":"}}]}}]}"#;
-        let r1 = parse_sse_event(event1, &mut acc);
+        let r1 = parse_sse_event(event1, &mut acc, &GenericAdapter);
         assert!(r1.is_empty());
 
         // Second chunk: more args
         let event2 = r#"data: {"choices":[{"delta":{"tool_calls":[{"index":0,"function":{"arguments":"\"/tmp/test\","}}]}}]}"#;
-        let r2 = parse_sse_event(event2, &mut acc);
+        let r2 = parse_sse_event(event2, &mut acc, &GenericAdapter);
         assert!(r2.is_empty());
 
         // Third chunk: finish args
         let event3 = r#"data: {"choices":[{"delta":{"tool_calls":[{"index":0,"function":{"arguments":"\"content\":\"hello\"}"}}]}}]}"#;
-        let r3 = parse_sse_event(event3, &mut acc);
+        let r3 = parse_sse_event(event3, &mut acc, &GenericAdapter);
         assert!(r3.is_empty());
 
         // Flush to get the completed tool call with assembled arguments
@@ -1560,10 +1883,10 @@ mod tests {
         let mut acc = ToolCallAccumulator::new();
 
         let event1 = r#"data: {"choices":[{"delta":{"tool_calls":[{"index":0,"id":"call_789","type":"function","function":{"name":"git_status","arguments":"{}"}}]}}]}"#;
-        parse_sse_event(event1, &mut acc);
+        parse_sse_event(event1, &mut acc, &GenericAdapter);
 
         let done_event = "data: [DONE]";
-        let results = parse_sse_event(done_event, &mut acc);
+        let results = parse_sse_event(done_event, &mut acc, &GenericAdapter);
         // Should have the flushed tool call + Done
         assert_eq!(results.len(), 2);
         assert!(matches!(&results[0], StreamChunk::ToolCall(tc) if tc.id == "call_789"));
@@ -1575,7 +1898,7 @@ mod tests {
         // Test SSE event with finish_reason but no content
         let mut acc = ToolCallAccumulator::new();
         let event = r#"data: {"choices":[{"delta":{},"finish_reason":"stop"}]}"#;
-        let results = parse_sse_event(event, &mut acc);
+        let results = parse_sse_event(event, &mut acc, &GenericAdapter);
         // Should return empty since there's no content or reasoning
         assert!(results.is_empty());
     }
@@ -1626,15 +1949,47 @@ mod tests {
         let mut acc = ToolCallAccumulator::new();
 
         let event1 = r#"data: {"choices":[{"delta":{"tool_calls":[{"index":0,"id":"call_finish","type":"function","function":{"name":"git_status","arguments":"{}"}}]}}]}"#;
-        let r1 = parse_sse_event(event1, &mut acc);
+        let r1 = parse_sse_event(event1, &mut acc, &GenericAdapter);
         assert!(r1.is_empty());
 
         let finish = r#"data: {"choices":[{"delta":{},"finish_reason":"tool_calls"}]}"#;
-        let r2 = parse_sse_event(finish, &mut acc);
+        let r2 = parse_sse_event(finish, &mut acc, &GenericAdapter);
         assert_eq!(r2.len(), 1);
         assert!(matches!(&r2[0], StreamChunk::ToolCall(tc) if tc.id == "call_finish"));
     }
 
+    #[test]
+    fn test_tool_calls_emitted_in_index_order_regardless_of_arrival() {
+        let mut acc = ToolCallAccumulator::new();
+
+        // Three calls whose deltas arrive out of index order and interleaved
+        let events = [
+            r#"data: {"choices":[{"delta":{"tool_calls":[{"index":2,"id":"call_c","type":"function","function":{"name":"cargo_check","arguments":"{"}}]}}]}"#,
+            r#"data: {"choices":[{"delta":{"tool_calls":[{"index":1,"id":"call_b","type":"function","function":{"name":"file_edit","arguments":"{\"path\":"}}]}}]}"#,
+            r#"data: {"choices":[{"delta":{"tool_calls":[{"index":0,"id":"call_a","type":"function","function":{"name":"file_read","arguments":"{\"path\":\"a.rs\"}"}}]}}]}"#,
+            r#"data: {"choices":[{"delta":{"tool_calls":[{"index":2,"function":{"arguments":"}"}}]}}]}"#,
+            r#"data: {"choices":[{"delta":{"tool_calls":[{"index":1,"function":{"arguments":"\"a.rs\"}"}}]}}]}"#,
+            "data: [DONE]",
+        ];
+        let chunks: Vec<StreamChunk> = events
+            .iter()
+            .flat_map(|event| parse_sse_event(event, &mut acc, &GenericAdapter))
+            .collect();
+
+        let calls: Vec<&types::ToolCall> = chunks
+            .iter()
+            .filter_map(|chunk| match chunk {
+                StreamChunk::ToolCall(call) => Some(call),
+                _ => None,
+            })
+            .collect();
+        let ids: Vec<&str> = calls.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, ["call_a", "call_b", "call_c"]);
+        assert_eq!(calls[1].function.arguments, "{\"path\":\"a.rs\"}");
+        assert_eq!(calls[2].function.arguments, "{}");
+        assert!(matches!(chunks.last(), Some(StreamChunk::Done)));
+    }
+
     // ============================================
     // API URL Construction Tests
     // ============================================
@@ -1694,12 +2049,12 @@ mod tests {
         assert_eq!(events.len(), 2);
 
         // First event
-        let results1 = parse_sse_event(events[0], &mut acc);
+        let results1 = parse_sse_event(events[0], &mut acc, &GenericAdapter);
         assert_eq!(results1.len(), 1);
         assert!(matches!(&results1[0], StreamChunk::Content(t) if t == "Hello"));
 
         // Second event
-        let results2 = parse_sse_event(events[1], &mut acc);
+        let results2 = parse_sse_event(events[1], &mut acc, &GenericAdapter);
         assert_eq!(results2.len(), 1);
         assert!(matches!(&results2[0], StreamChunk::Content(t) if t == " world"));
     }
@@ -1710,7 +2065,7 @@ mod tests {
         // Test SSE event with extra whitespace
         let event = "  data: [DONE]  ";
         // The parser strips "data: " prefix, should handle this
-        let results = parse_sse_event(event.trim(), &mut acc);
+        let results = parse_sse_event(event.trim(), &mut acc, &GenericAdapter);
         assert_eq!(results.len(), 1);
         assert!(matches!(results[0], StreamChunk::Done));
     }
@@ -1722,6 +2077,7 @@ mod tests {
             max_retries: 3,
             initial_delay_ms: 1000,
             max_delay_ms: 30000,
+            rate_limit_delay_ms: 1000,
             retryable_status_codes: vec![],
         };
 
@@ -1736,6 +2092,7 @@ mod tests {
             max_retries: 0,
             initial_delay_ms: 1000,
             max_delay_ms: 30000,
+            rate_limit_delay_ms: 1000,
             retryable_status_codes: vec![500],
         };
 
@@ -1750,6 +2107,7 @@ mod tests {
             max_retries: 3,
             initial_delay_ms: 0,
             max_delay_ms: 0,
+            rate_limit_delay_ms: 0,
             retryable_status_codes: vec![500],
         };
 
@@ -1979,7 +2337,7 @@ mod tests {
     fn test_parse_sse_content_and_usage_same_event() {
         let mut acc = ToolCallAccumulator::new();
         let event = r#"data: {"choices":[{"delta":{"content":"hi"}}],"usage":{"prompt_tokens":5,"completion_tokens":2,"total_tokens":7}}"#;
-        let results = parse_sse_event(event, &mut acc);
+        let results = parse_sse_event(event, &mut acc, &GenericAdapter);
         assert_eq!(results.len(), 2);
         assert!(matches!(&results[0], StreamChunk::Content(t) if t == "hi"));
         assert!(matches!(&results[1], StreamChunk::Usage(u) if u.total_tokens == 7));
@@ -1990,7 +2348,7 @@ mod tests {
         let mut acc = ToolCallAccumulator::new();
         let event =
             r#"data: {"choices":[{"delta":{"content":"answer","reasoning_content":"thinking"}}]}"#;
-        let results = parse_sse_event(event, &mut acc);
+        let results = parse_sse_event(event, &mut acc, &GenericAdapter);
         assert_eq!(results.len(), 2);
         assert!(matches!(&results[0], StreamChunk::Content(t) if t == "answer"));
         assert!(matches!(&results[1], StreamChunk::Reasoning(t) if t == "thinking"));
@@ -2000,7 +2358,7 @@ mod tests {
     fn test_parse_sse_empty_reasoning_not_emitted() {
         let mut acc = ToolCallAccumulator::new();
         let event = r#"data: {"choices":[{"delta":{"reasoning_content":""}}]}"#;
-        let results = parse_sse_event(event, &mut acc);
+        let results = parse_sse_event(event, &mut acc, &GenericAdapter);
         assert!(results.is_empty());
     }
 
@@ -2008,7 +2366,7 @@ mod tests {
     fn test_parse_sse_multiple_tool_call_deltas_in_one_event() {
         let mut acc = ToolCallAccumulator::new();
         let event = r#"data: {"choices":[{"delta":{"tool_calls":[{"index":0,"id":"c1","type":"function","function":{"name":"fn1","arguments":"{}"}},{"index":1,"id":"c2","type":"function","function":{"name":"fn2","arguments":"{}"}}]}}]}"#;
-        let results = parse_sse_event(event, &mut acc);
+        let results = parse_sse_event(event, &mut acc, &GenericAdapter);
         // Tool calls are buffered, not emitted
         assert!(results.is_empty());
 
@@ -2024,12 +2382,12 @@ mod tests {
 
         // First event: buffer a tool call
         let event1 = r#"data: {"choices":[{"delta":{"tool_calls":[{"index":0,"id":"c_fin","type":"function","function":{"name":"read","arguments":"{}"}}]}}]}"#;
-        let r1 = parse_sse_event(event1, &mut acc);
+        let r1 = parse_sse_event(event1, &mut acc, &GenericAdapter);
         assert!(r1.is_empty());
 
         // Second event with finish_reason: should flush
         let event2 = r#"data: {"choices":[{"delta":{},"finish_reason":"tool_calls"}]}"#;
-        let r2 = parse_sse_event(event2, &mut acc);
+        let r2 = parse_sse_event(event2, &mut acc, &GenericAdapter);
         assert_eq!(r2.len(), 1);
         assert!(matches!(&r2[0], StreamChunk::ToolCall(tc) if tc.id == "c_fin"));
     }
@@ -2050,7 +2408,7 @@ mod tests {
         acc.process_delta(&d0);
         acc.process_delta(&d1);
 
-        let results = parse_sse_event("data: [DONE]", &mut acc);
+        let results = parse_sse_event("data: [DONE]", &mut acc, &GenericAdapter);
         // Should be: ToolCall(a), ToolCall(b), Done
         assert_eq!(results.len(), 3);
         assert!(matches!(&results[0], StreamChunk::ToolCall(tc) if tc.id == "a"));
@@ -2063,7 +2421,7 @@ mod tests {
         let mut acc = ToolCallAccumulator::new();
         // Valid JSON but no choices key
         let event = r#"data: {"id":"chatcmpl-123","model":"test"}"#;
-        let results = parse_sse_event(event, &mut acc);
+        let results = parse_sse_event(event, &mut acc, &GenericAdapter);
         assert!(results.is_empty());
     }
 
@@ -2072,7 +2430,7 @@ mod tests {
         let mut acc = ToolCallAccumulator::new();
         // Usage field is present but cannot deserialize to Usage struct
         let event = r#"data: {"usage":{"invalid":"fields"}}"#;
-        let results = parse_sse_event(event, &mut acc);
+        let results = parse_sse_event(event, &mut acc, &GenericAdapter);
         assert!(results.is_empty());
     }
 
@@ -2082,7 +2440,7 @@ mod tests {
         // Multiple data: lines in one SSE event (spec says to concatenate)
         // Our implementation processes each line independently
         let event = "data: {\"choices\":[{\"delta\":{\"content\":\"A\"}}]}\ndata: {\"choices\":[{\"delta\":{\"content\":\"B\"}}]}";
-        let results = parse_sse_event(event, &mut acc);
+        let results = parse_sse_event(event, &mut acc, &GenericAdapter);
         assert_eq!(results.len(), 2);
         assert!(matches!(&results[0], StreamChunk::Content(t) if t == "A"));
         assert!(matches!(&results[1], StreamChunk::Content(t) if t == "B"));
@@ -2092,7 +2450,7 @@ mod tests {
     fn test_parse_sse_event_choices_empty_array() {
         let mut acc = ToolCallAccumulator::new();
         let event = r#"data: {"choices":[]}"#;
-        let results = parse_sse_event(event, &mut acc);
+        let results = parse_sse_event(event, &mut acc, &GenericAdapter);
         assert!(results.is_empty());
     }
 
@@ -2100,7 +2458,7 @@ mod tests {
     fn test_parse_sse_event_choices_no_delta() {
         let mut acc = ToolCallAccumulator::new();
         let event = r#"data: {"choices":[{"index":0}]}"#;
-        let results = parse_sse_event(event, &mut acc);
+        let results = parse_sse_event(event, &mut acc, &GenericAdapter);
         assert!(results.is_empty());
     }
 
@@ -2127,6 +2485,42 @@ mod tests {
         assert_eq!(client.base_url, "https://api.example.com/v1");
     }
 
+    #[test]
+    fn test_api_client_shapes_body_for_configured_provider() {
+        let messages = vec![Message::system("sys"), Message::user("hi")];
+        let generic = ApiClient::new(&crate::config::Config::default()).unwrap();
+        let body = generic.chat_body(
+            ChatTarget::default_model(&generic.config),
+            messages.clone(),
+            None,
+            ThinkingMode::Disabled,
+            true,
+        );
+        // The no-thinking instruction is merged into the single system message
+        assert_eq!(body["messages"].as_array().unwrap().len(), 2);
+        assert!(body["messages"][0]["content"]
+            .as_str()
+            .unwrap()
+            .contains("CRITICAL INSTRUCTION"));
+        assert_eq!(body["stream"], true);
+
+        let config = crate::config::Config {
+            provider: crate::config::Provider::Vllm,
+            ..Default::default()
+        };
+        let vllm = ApiClient::new(&config).unwrap();
+        let body = vllm.chat_body(
+            ChatTarget::default_model(&vllm.config),
+            messages,
+            None,
+            ThinkingMode::Disabled,
+            false,
+        );
+        assert_eq!(body["chat_template_kwargs"]["enable_thinking"], false);
+        assert_eq!(body["messages"][0]["content"], "sys");
+        assert_eq!(body["max_tokens"], config.max_tokens);
+    }
+
     #[test]
     fn test_api_client_new_respects_step_timeout() {
         let mut config = crate::config::Config::default();
@@ -2152,6 +2546,7 @@ mod tests {
             max_retries: 10,
             initial_delay_ms: 200,
             max_delay_ms: 5000,
+            rate_limit_delay_ms: 200,
             retryable_status_codes: vec![429],
         };
         let client = client.with_retry_config(custom_retry);
@@ -2168,6 +2563,7 @@ mod tests {
             max_retries: 7,
             base_delay_ms: 500,
             max_delay_ms: 10000,
+            rate_limit_base_delay_ms: 500,
         };
         let client = ApiClient::new(&config).unwrap();
         assert_eq!(client.retry_config.max_retries, 7);
@@ -2520,6 +2916,7 @@ mod tests {
             max_retries: 3,
             base_delay_ms: 10, // Very short delay for tests
             max_delay_ms: 50,
+            rate_limit_base_delay_ms: 10,
         };
 
         let client = ApiClient::new(&config).unwrap();
@@ -2563,6 +2960,7 @@ mod tests {
             max_retries: 1,
             base_delay_ms: 10,
             max_delay_ms: 20,
+            rate_limit_base_delay_ms: 10,
         };
 
         let client = ApiClient::new(&config).unwrap();
@@ -2728,6 +3126,72 @@ mod tests {
         let _ = server.await;
     }
 
+    #[tokio::test]
+    async fn test_streaming_response_parses_ollama_ndjson() {
+        use tokio::io::AsyncWriteExt;
+        use tokio::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            drain_http_request(&mut socket).await;
+            // Recorded from `ollama serve` 0.9 with qwen3 and think: true
+            let body = concat!(
+                r#"{"model":"qwen3","created_at":"2025-06-01T12:00:00.1Z","message":{"role":"assistant","content":"","thinking":"Read it"},"done":false}"#,
+                "\n",
+                r#"{"model":"qwen3","created_at":"2025-06-01T12:00:00.2Z","message":{"role":"assistant","content":"Hello"},"done":false}"#,
+                "\n",
+                r#"{"model":"qwen3","created_at":"2025-06-01T12:00:00.3Z","message":{"role":"assistant","content":" world"},"done":false}"#,
+                "\n",
+                r#"{"model":"qwen3","created_at":"2025-06-01T12:00:00.4Z","message":{"role":"assistant","content":"","tool_calls":[{"function":{"name":"file_read","arguments":{"path":"a.rs"}}}]},"done":false}"#,
+                "\n",
+                r#"{"model":"qwen3","created_at":"2025-06-01T12:00:00.5Z","message":{"role":"assistant","content":""},"done_reason":"stop","done":true,"total_duration":912000000,"prompt_eval_count":26,"eval_count":9}"#,
+                "\n",
+            );
+            // Split mid-line so framing has to buffer across reads
+            let (first, second) = body.split_at(body.len() / 2);
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/x-ndjson\r\nTransfer-Encoding: chunked\r\nConnection: close\r\n\r\n{:X}\r\n{}\r\n{:X}\r\n{}\r\n0\r\n\r\n",
+                first.len(),
+                first,
+                second.len(),
+                second
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+        });
+
+        let response = reqwest::get(format!("http://{}", addr)).await.unwrap();
+        let stream = StreamingResponse::new(response, Duration::from_secs(5))
+            .with_adapter(provider::adapter_for(crate::config::Provider::OllamaNative));
+        let mut rx = stream.into_channel().await;
+        let mut chunks = Vec::new();
+        while let Some(chunk) = rx.recv().await {
+            chunks.push(chunk.unwrap());
+        }
+
+        assert_eq!(chunks.len(), 6, "{:?}", chunks);
+        assert!(matches!(&chunks[0], StreamChunk::Reasoning(t) if t == "Read it"));
+        assert!(matches!(&chunks[1], StreamChunk::Content(t) if t == "Hello"));
+        assert!(matches!(&chunks[2], StreamChunk::Content(t) if t == " world"));
+        match &chunks[3] {
+            StreamChunk::ToolCall(call) => {
+                assert_eq!(call.id, "call_0");
+                assert_eq!(call.function.name, "file_read");
+                assert_eq!(call.function.arguments, r#"{"path":"a.rs"}"#);
+            }
+            other => panic!("expected a tool call, got {:?}", other),
+        }
+        assert!(matches!(
+            &chunks[4],
+            StreamChunk::Usage(u) if u.prompt_tokens == 26 && u.completion_tokens == 9 && u.total_tokens == 35
+        ));
+        assert!(matches!(&chunks[5], StreamChunk::Done));
+
+        let _ = server.await;
+    }
+
     #[tokio::test]
     async fn test_streaming_response_collect_with_reasoning() {
         use tokio::io::AsyncWriteExt;
@@ -3053,6 +3517,7 @@ mod tests {
             max_retries: 2,
             base_delay_ms: 10,
             max_delay_ms: 5000,
+            rate_limit_base_delay_ms: 10,
         };
 
         let client = ApiClient::new(&config).unwrap();
@@ -0,0 +1,589 @@
+//! Per-provider request and response adaptation
+//!
+//! OpenAI-compatible servers agree on the core of a chat completion but
+//! differ at the edges: how thinking is switched on or off, what the token
+//! limit is called, and where reasoning text comes back. [`ApiClient`]
+//! builds one OpenAI-shaped body and hands it to the [`ProviderAdapter`]
+//! picked by `Config.provider`, and runs every response (and every stream
+//! chunk) through the same adapter before parsing it.
+//!
+//! Ollama's native `/api/chat` is the one non-OpenAI endpoint: its adapter
+//! translates the body both ways and streams newline-delimited JSON instead
+//! of server-sent events (see [`StreamFormat`]).
+//!
+//! [`ApiClient`]: super::ApiClient
+
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use super::types::Message;
+use super::ThinkingMode;
+use crate::config::Provider;
+
+/// Instruction that keeps models without a thinking switch from reasoning
+const NO_THINKING_INSTRUCTION: &str = "CRITICAL INSTRUCTION: DO NOT use <think> blocks or any thinking process in your response. Output your final response directly and immediately.";
+
+/// Anthropic rejects thinking budgets below this
+const ANTHROPIC_MIN_THINKING_BUDGET: usize = 1024;
+
+/// How a streamed response is framed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamFormat {
+    /// Server-sent events of OpenAI chunks, separated by a blank line
+    Sse,
+    /// One Ollama `/api/chat` JSON object per line
+    Ndjson,
+}
+
+/// Shapes requests for, and normalizes responses from, one kind of server
+pub trait ProviderAdapter: Send + Sync {
+    /// Chat endpoint URL under the configured `endpoint`
+    fn chat_url(&self, endpoint: &str) -> String {
+        format!("{}/chat/completions", endpoint)
+    }
+
+    /// Framing of streamed responses
+    fn stream_format(&self) -> StreamFormat {
+        StreamFormat::Sse
+    }
+
+    /// Adjust the conversation before it is serialized
+    fn adapt_messages(&self, _messages: &mut Vec<Message>, _thinking: ThinkingMode) {}
+
+    /// Adjust the OpenAI-shaped request body
+    fn adapt_request(&self, _body: &mut Value, _thinking: ThinkingMode) {}
+
+    /// Bring a response body or stream chunk into the OpenAI shape
+    fn adapt_response(&self, _body: &mut Value) {}
+}
+
+/// The adapter for `provider`
+pub fn adapter_for(provider: Provider) -> Arc<dyn ProviderAdapter> {
+    match provider {
+        Provider::Generic => Arc::new(GenericAdapter),
+        Provider::OpenAi => Arc::new(OpenAiAdapter),
+        Provider::LlamaCpp => Arc::new(LlamaCppAdapter),
+        Provider::Vllm => Arc::new(VllmAdapter),
+        Provider::Ollama => Arc::new(OllamaAdapter),
+        Provider::OllamaNative => Arc::new(OllamaNativeAdapter),
+        Provider::AnthropicCompat => Arc::new(AnthropicCompatAdapter),
+    }
+}
+
+/// Any OpenAI-compatible server: thinking is turned off by instruction and
+/// budgeted with the `thinking` extension
+pub struct GenericAdapter;
+
+impl ProviderAdapter for GenericAdapter {
+    fn adapt_messages(&self, messages: &mut Vec<Message>, thinking: ThinkingMode) {
+        instruct_no_thinking(messages, thinking);
+    }
+
+    fn adapt_request(&self, body: &mut Value, thinking: ThinkingMode) {
+        if let ThinkingMode::Budget(tokens) = thinking {
+            body["thinking"] = json!({"type": "enabled", "budget_tokens": tokens});
+        }
+    }
+}
+
+/// OpenAI proper: no `thinking` field, and `max_tokens` is
+/// `max_completion_tokens` (reasoning models reject the old name)
+pub struct OpenAiAdapter;
+
+impl ProviderAdapter for OpenAiAdapter {
+    fn adapt_request(&self, body: &mut Value, _thinking: ThinkingMode) {
+        if let Some(limit) = body.as_object_mut().and_then(|b| b.remove("max_tokens")) {
+            body["max_completion_tokens"] = limit;
+        }
+    }
+}
+
+/// llama.cpp: thinking is a chat template switch
+pub struct LlamaCppAdapter;
+
+impl ProviderAdapter for LlamaCppAdapter {
+    fn adapt_request(&self, body: &mut Value, thinking: ThinkingMode) {
+        set_enable_thinking(body, thinking);
+    }
+}
+
+/// vLLM: thinking is a chat template switch, and newer releases return
+/// reasoning as `reasoning`
+pub struct VllmAdapter;
+
+impl ProviderAdapter for VllmAdapter {
+    fn adapt_request(&self, body: &mut Value, thinking: ThinkingMode) {
+        set_enable_thinking(body, thinking);
+    }
+
+    fn adapt_response(&self, body: &mut Value) {
+        rename_reasoning(body);
+    }
+}
+
+/// Ollama: no thinking switch on the OpenAI endpoint, and reasoning comes
+/// back as `reasoning`
+pub struct OllamaAdapter;
+
+impl ProviderAdapter for OllamaAdapter {
+    fn adapt_messages(&self, messages: &mut Vec<Message>, thinking: ThinkingMode) {
+        instruct_no_thinking(messages, thinking);
+    }
+
+    fn adapt_response(&self, body: &mut Value) {
+        rename_reasoning(body);
+    }
+}
+
+/// Ollama's native `/api/chat`: sampling settings live under `options`,
+/// thinking is a `think` flag, tool call arguments are objects, and streams
+/// are NDJSON
+pub struct OllamaNativeAdapter;
+
+impl ProviderAdapter for OllamaNativeAdapter {
+    /// `endpoint` may be the server root or, copied from an OpenAI-style
+    /// setup, end in `/v1` or `/api`
+    fn chat_url(&self, endpoint: &str) -> String {
+        let root = endpoint.trim_end_matches('/');
+        let root = root
+            .strip_suffix("/v1")
+            .or_else(|| root.strip_suffix("/api"))
+            .unwrap_or(root);
+        format!("{}/api/chat", root)
+    }
+
+    fn stream_format(&self) -> StreamFormat {
+        StreamFormat::Ndjson
+    }
+
+    fn adapt_request(&self, body: &mut Value, thinking: ThinkingMode) {
+        let Some(obj) = body.as_object_mut() else {
+            return;
+        };
+        let mut options = serde_json::Map::new();
+        if let Some(temperature) = obj.remove("temperature") {
+            options.insert("temperature".to_string(), temperature);
+        }
+        if let Some(limit) = obj.remove("max_tokens") {
+            options.insert("num_predict".to_string(), limit);
+        }
+        obj.insert("options".to_string(), Value::Object(options));
+        obj.insert(
+            "think".to_string(),
+            json!(thinking != ThinkingMode::Disabled),
+        );
+        if let Some(messages) = obj.get_mut("messages").and_then(Value::as_array_mut) {
+            messages.iter_mut().for_each(to_native_message);
+        }
+    }
+
+    fn adapt_response(&self, body: &mut Value) {
+        if body.get("message").is_none() || body.get("choices").is_some() {
+            return;
+        }
+        let message = &body["message"];
+        let tool_calls: Vec<Value> = message
+            .get("tool_calls")
+            .and_then(Value::as_array)
+            .map(|calls| {
+                calls
+                    .iter()
+                    .enumerate()
+                    .map(|(i, call)| from_native_tool_call(call, i))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let mut openai_message = json!({
+            "role": message.get("role").cloned().unwrap_or_else(|| json!("assistant")),
+            "content": message.get("content").cloned().unwrap_or_else(|| json!("")),
+        });
+        if let Some(thinking) = message.get("thinking").filter(|t| t.is_string()) {
+            openai_message["reasoning_content"] = thinking.clone();
+        }
+        if !tool_calls.is_empty() {
+            openai_message["tool_calls"] = Value::Array(tool_calls);
+        }
+        let prompt = body["prompt_eval_count"].as_u64().unwrap_or(0);
+        let completion = body["eval_count"].as_u64().unwrap_or(0);
+        let created = body["created_at"]
+            .as_str()
+            .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+            .map(|t| t.timestamp().max(0) as u64)
+            .unwrap_or(0);
+        *body = json!({
+            "id": "ollama",
+            "object": "chat.completion",
+            "created": created,
+            "model": body.get("model").cloned().unwrap_or_else(|| json!("")),
+            "choices": [{
+                "index": 0,
+                "message": openai_message,
+                "finish_reason": body.get("done_reason").cloned().unwrap_or(Value::Null),
+            }],
+            "usage": {
+                "prompt_tokens": prompt,
+                "completion_tokens": completion,
+                "total_tokens": prompt + completion,
+            },
+        });
+    }
+}
+
+/// Anthropic's OpenAI-compatible endpoint: thinking only with a budget of
+/// at least 1024 tokens
+pub struct AnthropicCompatAdapter;
+
+impl ProviderAdapter for AnthropicCompatAdapter {
+    fn adapt_request(&self, body: &mut Value, thinking: ThinkingMode) {
+        if let ThinkingMode::Budget(tokens) = thinking {
+            body["thinking"] = json!({
+                "type": "enabled",
+                "budget_tokens": tokens.max(ANTHROPIC_MIN_THINKING_BUDGET),
+            });
+        }
+    }
+}
+
+fn instruct_no_thinking(messages: &mut Vec<Message>, thinking: ThinkingMode) {
+    if thinking == ThinkingMode::Disabled {
+        messages.insert(0, Message::system(NO_THINKING_INSTRUCTION));
+    }
+}
+
+fn set_enable_thinking(body: &mut Value, thinking: ThinkingMode) {
+    body["chat_template_kwargs"] = json!({
+        "enable_thinking": thinking != ThinkingMode::Disabled,
+    });
+}
+
+/// Move `reasoning` to `reasoning_content` in each choice's message and
+/// stream delta
+fn rename_reasoning(body: &mut Value) {
+    let Some(choices) = body.get_mut("choices").and_then(Value::as_array_mut) else {
+        return;
+    };
+    for choice in choices {
+        for key in ["message", "delta"] {
+            let Some(part) = choice.get_mut(key).and_then(Value::as_object_mut) else {
+                continue;
+            };
+            if part.contains_key("reasoning_content") {
+                continue;
+            }
+            if let Some(reasoning) = part.remove("reasoning") {
+                part.insert("reasoning_content".to_string(), reasoning);
+            }
+        }
+    }
+}
+
+/// Rewrite one serialized [`Message`] into the `/api/chat` shape: text
+/// content with images alongside, `thinking` for reasoning, and tool call
+/// arguments as objects
+fn to_native_message(message: &mut Value) {
+    let Some(msg) = message.as_object_mut() else {
+        return;
+    };
+    if let Some(Value::Array(blocks)) = msg.get("content").cloned() {
+        let mut texts = Vec::new();
+        let mut images = Vec::new();
+        for block in &blocks {
+            if let Some(text) = block.get("text").and_then(Value::as_str) {
+                texts.push(text);
+            } else if let Some(url) = block.pointer("/image_url/url").and_then(Value::as_str) {
+                // Ollama takes bare base64, not data URLs
+                let data = url.split_once(";base64,").map_or(url, |(_, data)| data);
+                images.push(json!(data));
+            }
+        }
+        msg.insert("content".to_string(), json!(texts.join("\n")));
+        if !images.is_empty() {
+            msg.insert("images".to_string(), Value::Array(images));
+        }
+    }
+    if let Some(reasoning) = msg.remove("reasoning_content") {
+        msg.insert("thinking".to_string(), reasoning);
+    }
+    msg.remove("tool_call_id");
+    if msg.get("role").and_then(Value::as_str) == Some("tool") {
+        if let Some(name) = msg.remove("name") {
+            msg.insert("tool_name".to_string(), name);
+        }
+    }
+    if let Some(calls) = msg.get_mut("tool_calls").and_then(Value::as_array_mut) {
+        for call in calls {
+            let function = &call["function"];
+            let arguments = match &function["arguments"] {
+                Value::String(raw) => serde_json::from_str(raw).unwrap_or_else(|_| json!({})),
+                other => other.clone(),
+            };
+            *call = json!({
+                "function": {"name": function["name"].clone(), "arguments": arguments},
+            });
+        }
+    }
+}
+
+/// One `/api/chat` tool call as an OpenAI tool call. Ollama sends no ids,
+/// so the call's position stands in for one.
+pub(super) fn from_native_tool_call(call: &Value, index: usize) -> Value {
+    let function = &call["function"];
+    let arguments = match &function["arguments"] {
+        Value::String(raw) => raw.clone(),
+        Value::Null => "{}".to_string(),
+        other => other.to_string(),
+    };
+    json!({
+        "id": call
+            .get("id")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("call_{}", index)),
+        "type": "function",
+        "function": {"name": function["name"].clone(), "arguments": arguments},
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::types::ChatResponse;
+
+    /// The body `ApiClient` builds before adaptation
+    fn request(thinking: ThinkingMode, provider: Provider) -> Value {
+        let adapter = adapter_for(provider);
+        let mut messages = vec![Message::system("You are terse."), Message::user("hi")];
+        adapter.adapt_messages(&mut messages, thinking);
+        let mut body = json!({
+            "model": "m",
+            "messages": messages,
+            "temperature": 0.2,
+            "max_tokens": 512,
+            "stream": false,
+        });
+        adapter.adapt_request(&mut body, thinking);
+        body
+    }
+
+    fn response(provider: Provider, mut body: Value) -> ChatResponse {
+        adapter_for(provider).adapt_response(&mut body);
+        serde_json::from_value(body).unwrap()
+    }
+
+    fn completion(message: Value) -> Value {
+        json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "created": 1_700_000_000,
+            "model": "m",
+            "choices": [{"index": 0, "message": message, "finish_reason": "stop"}],
+            "usage": {"prompt_tokens": 12, "completion_tokens": 3, "total_tokens": 15},
+        })
+    }
+
+    #[test]
+    fn test_generic_keeps_the_existing_request_shape() {
+        let off = request(ThinkingMode::Disabled, Provider::Generic);
+        assert_eq!(off["messages"].as_array().unwrap().len(), 3);
+        assert!(off["messages"][0]["content"]
+            .as_str()
+            .unwrap()
+            .starts_with("CRITICAL INSTRUCTION"));
+        assert!(off.get("thinking").is_none());
+
+        let budget = request(ThinkingMode::Budget(256), Provider::Generic);
+        assert_eq!(
+            budget["thinking"],
+            json!({"type": "enabled", "budget_tokens": 256})
+        );
+        assert_eq!(budget["max_tokens"], 512);
+    }
+
+    #[test]
+    fn test_openai_renames_token_limit_and_drops_thinking() {
+        let body = request(ThinkingMode::Budget(256), Provider::OpenAi);
+        assert_eq!(body["max_completion_tokens"], 512);
+        assert!(body.get("max_tokens").is_none());
+        assert!(body.get("thinking").is_none());
+        assert_eq!(
+            request(ThinkingMode::Disabled, Provider::OpenAi)["messages"]
+                .as_array()
+                .unwrap()
+                .len(),
+            2
+        );
+
+        let parsed = response(
+            Provider::OpenAi,
+            completion(json!({"role": "assistant", "content": "hello"})),
+        );
+        assert_eq!(parsed.choices[0].message.content.text(), "hello");
+    }
+
+    #[test]
+    fn test_llama_cpp_and_vllm_use_the_template_switch() {
+        for provider in [Provider::LlamaCpp, Provider::Vllm] {
+            let off = request(ThinkingMode::Disabled, provider);
+            assert_eq!(off["chat_template_kwargs"]["enable_thinking"], false);
+            assert_eq!(off["messages"].as_array().unwrap().len(), 2);
+            assert!(off.get("thinking").is_none());
+            let on = request(ThinkingMode::Enabled, provider);
+            assert_eq!(on["chat_template_kwargs"]["enable_thinking"], true);
+        }
+
+        // llama-server with --reasoning-format deepseek
+        let parsed = response(
+            Provider::LlamaCpp,
+            completion(json!({
+                "role": "assistant",
+                "content": "4",
+                "reasoning_content": "2 + 2",
+            })),
+        );
+        assert_eq!(
+            parsed.choices[0].message.reasoning_content.as_deref(),
+            Some("2 + 2")
+        );
+
+        // vLLM 0.10+ names the field `reasoning`
+        let parsed = response(
+            Provider::Vllm,
+            completion(json!({"role": "assistant", "content": "4", "reasoning": "2 + 2"})),
+        );
+        assert_eq!(
+            parsed.choices[0].message.reasoning_content.as_deref(),
+            Some("2 + 2")
+        );
+    }
+
+    #[test]
+    fn test_ollama_normalizes_reasoning_in_messages_and_deltas() {
+        let off = request(ThinkingMode::Disabled, Provider::Ollama);
+        assert_eq!(off["messages"].as_array().unwrap().len(), 3);
+        assert!(off.get("chat_template_kwargs").is_none());
+
+        let parsed = response(
+            Provider::Ollama,
+            completion(json!({"role": "assistant", "content": "", "reasoning": "hmm"})),
+        );
+        assert_eq!(
+            parsed.choices[0].message.reasoning_content.as_deref(),
+            Some("hmm")
+        );
+
+        let mut chunk = json!({
+            "id": "chatcmpl-2",
+            "object": "chat.completion.chunk",
+            "choices": [{"index": 0, "delta": {"role": "assistant", "reasoning": "Let"}}],
+        });
+        adapter_for(Provider::Ollama).adapt_response(&mut chunk);
+        assert_eq!(chunk["choices"][0]["delta"]["reasoning_content"], "Let");
+        assert!(chunk["choices"][0]["delta"].get("reasoning").is_none());
+    }
+
+    #[test]
+    fn test_ollama_native_translates_request_and_response() {
+        let adapter = adapter_for(Provider::OllamaNative);
+        for endpoint in [
+            "http://localhost:11434",
+            "http://localhost:11434/",
+            "http://localhost:11434/v1",
+            "http://localhost:11434/api",
+        ] {
+            assert_eq!(
+                adapter.chat_url(endpoint),
+                "http://localhost:11434/api/chat"
+            );
+        }
+        assert_eq!(adapter.stream_format(), StreamFormat::Ndjson);
+        assert_eq!(
+            adapter_for(Provider::Ollama).stream_format(),
+            StreamFormat::Sse
+        );
+
+        let body = request(ThinkingMode::Disabled, Provider::OllamaNative);
+        assert_eq!(
+            body["options"],
+            json!({"temperature": 0.2, "num_predict": 512})
+        );
+        assert_eq!(body["think"], false);
+        assert!(body.get("max_tokens").is_none());
+        assert_eq!(body["messages"].as_array().unwrap().len(), 2);
+        assert_eq!(
+            request(ThinkingMode::Budget(64), Provider::OllamaNative)["think"],
+            true
+        );
+
+        let mut call = Message::assistant("");
+        call.tool_calls = Some(vec![crate::api::types::ToolCall {
+            id: "call_0".into(),
+            call_type: "function".into(),
+            function: crate::api::types::ToolFunction {
+                name: "file_read".into(),
+                arguments: r#"{"path":"a.rs"}"#.into(),
+            },
+        }]);
+        let mut result = Message::tool("fn main() {}", "call_0");
+        result.name = Some("file_read".into());
+        let mut image = serde_json::to_value(Message::user("look")).unwrap();
+        image["content"] = json!([
+            {"type": "text", "text": "look"},
+            {"type": "image_url", "image_url": {"url": "data:image/png;base64,iVBOR"}},
+        ]);
+        let mut messages = [
+            serde_json::to_value(call).unwrap(),
+            serde_json::to_value(result).unwrap(),
+            image,
+        ];
+        messages.iter_mut().for_each(to_native_message);
+        assert_eq!(
+            messages[0]["tool_calls"][0],
+            json!({"function": {"name": "file_read", "arguments": {"path": "a.rs"}}})
+        );
+        assert_eq!(messages[1]["tool_name"], "file_read");
+        assert!(messages[1].get("tool_call_id").is_none());
+        assert_eq!(messages[2]["content"], "look");
+        assert_eq!(messages[2]["images"], json!(["iVBOR"]));
+
+        let parsed = response(
+            Provider::OllamaNative,
+            json!({
+                "model": "qwen3",
+                "created_at": "2025-06-01T12:00:00Z",
+                "message": {
+                    "role": "assistant",
+                    "content": "",
+                    "thinking": "need the file",
+                    "tool_calls": [{"function": {"name": "file_read", "arguments": {"path": "a.rs"}}}],
+                },
+                "done": true,
+                "done_reason": "stop",
+                "prompt_eval_count": 30,
+                "eval_count": 7,
+            }),
+        );
+        let message = &parsed.choices[0].message;
+        assert_eq!(message.reasoning_content.as_deref(), Some("need the file"));
+        let calls = message.tool_calls.as_ref().unwrap();
+        assert_eq!(calls[0].id, "call_0");
+        assert_eq!(calls[0].function.arguments, r#"{"path":"a.rs"}"#);
+        assert_eq!(parsed.usage.total_tokens, 37);
+        assert_eq!(parsed.created, 1_748_779_200);
+    }
+
+    #[test]
+    fn test_anthropic_sends_thinking_only_with_a_valid_budget() {
+        let body = request(ThinkingMode::Budget(256), Provider::AnthropicCompat);
+        assert_eq!(
+            body["thinking"],
+            json!({"type": "enabled", "budget_tokens": 1024})
+        );
+        assert!(request(ThinkingMode::Enabled, Provider::AnthropicCompat)
+            .get("thinking")
+            .is_none());
+        let off = request(ThinkingMode::Disabled, Provider::AnthropicCompat);
+        assert_eq!(off["messages"].as_array().unwrap().len(), 2);
+        assert_eq!(off["max_tokens"], 512);
+    }
+}
@@ -114,15 +114,27 @@ impl MessageContent {
         }
     }
 
-    /// Convert to `Blocks` (if not already) and append an image.
+    /// Convert to `Blocks` (if not already) and append a PNG image.
     pub fn with_image(self, base64_png: &str) -> Self {
+        self.with_image_data("image/png", base64_png)
+    }
+
+    /// Convert to `Blocks` (if not already) and append an image of the
+    /// given MIME type.
+    pub fn with_image_data(self, mime_type: &str, base64: &str) -> Self {
+        self.with_image_url(format!("data:{};base64,{}", mime_type, base64))
+    }
+
+    /// Convert to `Blocks` (if not already) and append an image by URL,
+    /// either remote or a `data:` URI.
+    pub fn with_image_url(self, url: impl Into<String>) -> Self {
         let mut blocks = match self {
             Self::Text(s) => vec![ContentBlock::Text { text: s }],
             Self::Blocks(b) => b,
         };
         blocks.push(ContentBlock::ImageUrl {
             image_url: ImageUrl {
-                url: format!("data:image/png;base64,{}", base64_png),
+                url: url.into(),
                 detail: None,
             },
         });
@@ -681,6 +693,37 @@ mod tests {
         assert!(parsed.content.has_images());
     }
 
+    #[test]
+    fn test_multimodal_message_wire_shape() {
+        // OpenAI-style content parts: base64 data URI and remote URL
+        let content = MessageContent::from_text("Fix the layout")
+            .with_image_data("image/jpeg", "/9j/4AAQ")
+            .with_image_url("https://example.com/ui.png");
+        let msg = Message::user_multimodal(content);
+        assert_eq!(
+            serde_json::to_value(&msg).unwrap(),
+            serde_json::json!({
+                "role": "user",
+                "content": [
+                    {"type": "text", "text": "Fix the layout"},
+                    {"type": "image_url", "image_url": {"url": "data:image/jpeg;base64,/9j/4AAQ"}},
+                    {"type": "image_url", "image_url": {"url": "https://example.com/ui.png"}}
+                ]
+            })
+        );
+
+        let json = r#"{"type": "image_url", "image_url": {"url": "https://example.com/a.png", "detail": "low"}}"#;
+        let block: ContentBlock = serde_json::from_str(json).unwrap();
+        let ContentBlock::ImageUrl { image_url } = &block else {
+            panic!("expected an image block");
+        };
+        assert_eq!(image_url.detail.as_deref(), Some("low"));
+        assert_eq!(
+            serde_json::to_value(&block).unwrap()["image_url"]["detail"],
+            "low"
+        );
+    }
+
     #[test]
     fn test_message_content_backward_compat_deserialization() {
         // Plain string JSON deserializes as Text variant
@@ -4,19 +4,26 @@
 
 #[cfg(feature = "tui")]
 use std::sync::mpsc;
+use std::time::Duration;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
 use tracing::warn;
 
 // Use library exports instead of redeclaring modules
 // This avoids duplicate compilation and maintains consistency
+use crate::agent::planning::Planner;
+use crate::agent::task_result::TaskOutcome;
 use crate::agent::Agent;
 use crate::checkpoint;
-use crate::config::{Config, ExecutionMode};
+use crate::config::{Config, ConfigSource, ConfigSources, ExecutionMode, ToolErrorPolicy};
+use crate::devops::worktree::TaskWorktree;
 use crate::multiagent;
+use crate::observability::analytics::{ModelComparisonStore, TimePeriod, ToolTimingStore};
+use crate::orchestration::batch;
 use crate::output;
-use crate::telemetry::init_tracing;
+use crate::session::local_first::{KeySource, LocalFirst};
+use crate::telemetry::init_tracing_with_config;
 use crate::ui;
 use crate::ui::components::{
     render_header, render_task_complete, render_task_start, WorkshopContext,
@@ -99,6 +106,44 @@ struct Cli {
     /// Use ASCII-only output (no emoji or extended Unicode)
     #[arg(long)]
     ascii: bool,
+
+    /// Plain-text output for screen readers (implies --ascii and --no-color)
+    #[arg(long)]
+    accessible: bool,
+
+    /// Print a JSON result on stdout for `run`, `analyze`, `batch` and `-p`; other output goes to stderr
+    #[arg(long)]
+    json: bool,
+
+    /// Thinking token budget for every task, overriding agent.thinking_budgets (0 disables thinking)
+    #[arg(long, value_name = "TOKENS")]
+    thinking_budget: Option<u32>,
+
+    /// Only offer these tools to the model, comma-separated (globs like `git_*` work)
+    #[arg(long, value_name = "TOOLS", value_delimiter = ',')]
+    allow_tools: Vec<String>,
+
+    /// Never offer or run these tools, comma-separated (globs like `git_*` work)
+    #[arg(long, value_name = "TOOLS", value_delimiter = ',')]
+    deny_tools: Vec<String>,
+
+    /// Hand tool errors back to the model and keep going, even if the config sets
+    /// `on_tool_error = "strict"`
+    #[arg(long)]
+    continue_on_tool_error: bool,
+
+    /// Turn off secret redaction for this run (trusted local sessions only)
+    #[arg(long)]
+    no_redact: bool,
+
+    /// Permit edits to selfware's own source tree; each edit still asks for confirmation
+    #[arg(long)]
+    allow_self_edit: bool,
+
+    /// Only allow edits to these files or directories, comma-separated; they are also
+    /// loaded into context and ranked first by rag_search
+    #[arg(long, value_name = "PATHS", value_delimiter = ',')]
+    focus: Vec<String>,
 }
 
 /// Color theme for terminal output
@@ -115,7 +160,7 @@ pub enum Theme {
     HighContrast,
 }
 
-/// Output format for CLI (currently only affects `status` command)
+/// Output format for the `status` command (`--json` also selects JSON)
 #[derive(Debug, Clone, Copy, Default, clap::ValueEnum)]
 pub enum OutputFormat {
     /// Human-readable text (default)
@@ -135,6 +180,122 @@ enum DemoScenarioKind {
     TokenChallenge,
 }
 
+/// Document format for `selfware journal export`
+#[derive(Debug, Clone, Copy, Default, clap::ValueEnum)]
+enum ExportFormat {
+    /// Markdown narrative (default)
+    #[default]
+    Md,
+}
+
+/// Graph format for `selfware kg export`
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum GraphFormat {
+    /// GraphViz DOT
+    Dot,
+    /// Mermaid flowchart
+    Mermaid,
+}
+
+/// Time window for `selfware analytics tool-stats`
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum StatsPeriod {
+    Hour,
+    Day,
+    Week,
+    Month,
+    All,
+}
+
+impl From<StatsPeriod> for TimePeriod {
+    fn from(period: StatsPeriod) -> Self {
+        match period {
+            StatsPeriod::Hour => TimePeriod::Hour,
+            StatsPeriod::Day => TimePeriod::Day,
+            StatsPeriod::Week => TimePeriod::Week,
+            StatsPeriod::Month => TimePeriod::Month,
+            StatsPeriod::All => TimePeriod::All,
+        }
+    }
+}
+
+#[derive(Subcommand, Clone)]
+enum AnalyticsAction {
+    /// Latency (p50/p95) and failure rate per tool, most total time first
+    ToolStats {
+        /// Only count calls made within this period
+        #[arg(long, value_enum, default_value = "all")]
+        period: StatsPeriod,
+
+        /// Forget all recorded tool timings
+        #[arg(long)]
+        reset: bool,
+    },
+
+    /// Models ranked by `/compare` verdicts, with average time and cost
+    Leaderboard {
+        /// Forget all recorded comparisons
+        #[arg(long)]
+        reset: bool,
+    },
+}
+
+#[derive(Subcommand, Clone)]
+enum KgAction {
+    /// Export the knowledge graph for GraphViz or Mermaid
+    Export {
+        /// Output format
+        #[arg(long, value_enum)]
+        format: GraphFormat,
+
+        /// Saved graph (JSON) to export instead of one built from the
+        /// project's Rust sources
+        #[arg(long, value_name = "PATH")]
+        graph: Option<std::path::PathBuf>,
+
+        /// Only export the entities around this one (ID or name)
+        #[arg(long, value_name = "ENTITY")]
+        subgraph: Option<String>,
+
+        /// How many relations away from --subgraph to include
+        #[arg(long, default_value_t = 2, requires = "subgraph")]
+        depth: usize,
+
+        /// Write to this file instead of stdout
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<std::path::PathBuf>,
+    },
+}
+
+#[derive(Subcommand, Clone)]
+enum JournalAction {
+    /// Remove old finished entries (in-progress entries are always kept)
+    Prune {
+        /// Keep at most this many finished entries (overrides checkpoint.keep_last)
+        #[arg(long)]
+        keep_last: Option<usize>,
+
+        /// Remove finished entries older than this, e.g. 30d (overrides checkpoint.keep_for)
+        #[arg(long, value_name = "AGE")]
+        keep_for: Option<String>,
+
+        /// List what would be removed without removing it
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Write an entry up as a readable narrative: goal, plan, steps with
+    /// reasoning and diffs, and outcome
+    Export {
+        /// Entry ID
+        task_id: String,
+
+        /// Document format
+        #[arg(long, value_enum, default_value_t = ExportFormat::Md)]
+        format: ExportFormat,
+    },
+}
+
 #[derive(Subcommand, Clone)]
 enum Commands {
     /// Interactive setup wizard for first-time configuration
@@ -146,7 +307,11 @@ enum Commands {
 
     /// Open your workshop for an interactive session
     #[command(alias = "c")]
-    Chat,
+    Chat {
+        /// End the session once this much time has passed (e.g. 45m, 2h, 1h30m)
+        #[arg(long, value_name = "DURATION", value_parser = parse_runtime)]
+        max_runtime: Option<Duration>,
+    },
 
     /// Multi-agent chat with concurrent streams
     #[command(alias = "m")]
@@ -161,6 +326,49 @@ enum Commands {
     Run {
         /// What shall we tend to?
         task: String,
+
+        /// Stop at the next step once this much time has passed (e.g. 45m, 2h, 1h30m)
+        #[arg(long, value_name = "DURATION", value_parser = parse_runtime)]
+        max_runtime: Option<Duration>,
+
+        /// Work in a throwaway git worktree of HEAD, then merge, keep or discard it
+        #[arg(long)]
+        worktree: bool,
+
+        /// Break down where the run's time went: model, each tool, context work, confirmations
+        #[arg(long)]
+        profile: bool,
+
+        /// Also write the profile as folded stacks for flamegraph tools (implies --profile)
+        #[arg(long, value_name = "FILE")]
+        profile_folded: Option<std::path::PathBuf>,
+
+        /// Show a live dashboard of context, usage and plan progress while it runs
+        #[cfg(feature = "tui")]
+        #[arg(long)]
+        watch: bool,
+    },
+
+    /// Tend to a list of tasks unattended, one per line (or a YAML list)
+    Batch {
+        /// Task file (.txt with one task per line, or .yaml/.yml)
+        file: String,
+
+        /// Stop at the first task that does not complete
+        #[arg(long)]
+        fail_fast: bool,
+
+        /// Skip tasks whose journal entry is already completed
+        #[arg(long = "continue")]
+        skip_completed: bool,
+
+        /// Where to write the JSON report (defaults to <file>.report.json)
+        #[arg(long, value_name = "FILE")]
+        report: Option<String>,
+
+        /// Run every task in one throwaway git worktree of HEAD
+        #[arg(long)]
+        worktree: bool,
     },
 
     /// Survey your garden (analyze codebase)
@@ -169,6 +377,11 @@ enum Commands {
         /// Path to survey
         #[arg(default_value = ".")]
         path: String,
+
+        /// Only survey files changed since this git ref; `last` means since
+        /// the previous analyze of this repository
+        #[arg(long, value_name = "REF")]
+        since: Option<String>,
     },
 
     /// View your garden as a living ecosystem
@@ -203,14 +416,53 @@ enum Commands {
         task_id: String,
     },
 
+    /// Fork a journal entry at a step into a new entry
+    Fork {
+        /// Journal entry ID to fork from
+        task_id: String,
+
+        /// Step to fork at (defaults to the entry's latest step)
+        #[arg(long)]
+        step: Option<usize>,
+    },
+
     /// Browse your journal entries
     #[command(alias = "j")]
-    Journal,
+    Journal {
+        #[command(subcommand)]
+        action: Option<JournalAction>,
+    },
+
+    /// Search saved chats for a message
+    Search {
+        /// Words to look for
+        query: String,
+
+        /// Maximum number of chats to show
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+
+        /// Resume the Nth hit (1-based) in an interactive session
+        #[arg(long, value_name = "N")]
+        open: Option<usize>,
+    },
+
+    /// Migrate stored session data
+    Migrate {
+        /// Encrypt existing plaintext journals and saved chats
+        #[arg(long)]
+        encrypt: bool,
+    },
 
     /// View a specific journal entry
     JournalEntry {
         /// Entry ID
         task_id: String,
+
+        /// Also show the model's reasoning for each step (needs
+        /// `store_reasoning = true` when the task ran)
+        #[arg(long)]
+        verbose: bool,
     },
 
     /// Remove a journal entry
@@ -219,6 +471,18 @@ enum Commands {
         task_id: String,
     },
 
+    /// Inspect the knowledge graph of the project
+    Kg {
+        #[command(subcommand)]
+        action: KgAction,
+    },
+
+    /// Usage analytics across sessions
+    Analytics {
+        #[command(subcommand)]
+        action: AnalyticsAction,
+    },
+
     /// Show workshop status and statistics
     Status {
         /// Output format for machine consumption
@@ -226,6 +490,37 @@ enum Commands {
         output_format: OutputFormat,
     },
 
+    /// Show which messages and files use the most context tokens
+    #[cfg(feature = "tokens")]
+    Tokens {
+        /// Break down the context the next request would send
+        #[arg(long)]
+        context: bool,
+
+        /// Load a saved chat first
+        #[arg(long, conflicts_with = "task")]
+        chat: Option<String>,
+
+        /// Load a checkpointed task first
+        #[arg(long)]
+        task: Option<String>,
+
+        /// List every item instead of the largest 20
+        #[arg(long)]
+        all: bool,
+    },
+
+    /// Inspect the effective configuration
+    Config {
+        /// Print every setting with the layer it came from
+        #[arg(long)]
+        show: bool,
+
+        /// Validate the configuration and list every problem found
+        #[arg(long)]
+        check: bool,
+    },
+
     /// Self-improve: analyze and edit the selfware codebase
     #[cfg(feature = "self-improvement")]
     Improve {
@@ -283,18 +578,18 @@ enum Commands {
 }
 
 pub async fn run() -> Result<()> {
-    // Initialize telemetry
-    init_tracing();
-
     let cli = Cli::parse();
+    let quiet = cli.quiet || cli.json;
+    crate::output::set_quiet(quiet);
+    crate::ui::accessibility::set_accessible(cli.accessible);
 
     // Apply --no-color early to disable all color output
-    if cli.no_color || std::env::var("NO_COLOR").is_ok() {
+    if cli.no_color || cli.accessible || std::env::var("NO_COLOR").is_ok() {
         colored::control::set_override(false);
     }
 
     // Apply --ascii mode (or SELFWARE_ASCII env var) for terminals without emoji support
-    if cli.ascii || std::env::var("SELFWARE_ASCII").is_ok() {
+    if cli.ascii || cli.accessible || std::env::var("SELFWARE_ASCII").is_ok() {
         crate::ui::style::set_ascii_mode(true);
     }
 
@@ -303,7 +598,7 @@ pub async fn run() -> Result<()> {
         std::env::set_current_dir(workdir)
             .map_err(|e| anyhow::anyhow!("Cannot enter garden '{}': {}", workdir, e))?;
 
-        if !cli.quiet {
+        if !quiet {
             println!(
                 "{} Entering garden: {}",
                 Glyphs::sprout(),
@@ -346,9 +641,30 @@ pub async fn run() -> Result<()> {
         }
     });
 
-    let mut config = Config::load(config_path.as_deref())?;
+    // `config --check` reports problems that would otherwise stop the load
+    if let Some(Commands::Config { check: true, .. }) = &cli.command {
+        return check_config(config_path.as_deref(), quiet, cli.json);
+    }
+
+    // Defaults < user config < project config < env vars, then CLI flags below
+    let (mut config, mut sources) = Config::layered(config_path.as_deref())?;
+
+    // The profiler is a tracing layer, so it has to be on before tracing starts
+    if let Some(Commands::Run {
+        profile,
+        profile_folded,
+        ..
+    }) = &cli.command
+    {
+        if *profile || profile_folded.is_some() {
+            crate::observability::profile::enable();
+        }
+    }
+
+    // Initialize telemetry once the OTLP endpoint (if any) is known
+    init_tracing_with_config(&config.telemetry);
 
-    // Resolve execution mode: explicit CLI flags > --mode > env var (from Config::load)
+    // Resolve execution mode: explicit CLI flags > --mode > env var (from Config::layered)
     let exec_mode = if cli.daemon {
         ExecutionMode::Daemon
     } else if cli.yolo {
@@ -358,10 +674,46 @@ pub async fn run() -> Result<()> {
     } else {
         config.execution_mode // Preserve SELFWARE_MODE env var / default
     };
+    if cli.daemon || cli.yolo || cli.mode.is_some() {
+        sources.set("execution_mode", ConfigSource::Cli);
+    }
 
     // Apply execution mode to config
     config.execution_mode = exec_mode;
 
+    // A configured key that cannot be loaded must stop startup rather than
+    // silently fall back to writing plaintext
+    if let Some(source) = KeySource::from_config(&config.encryption)? {
+        LocalFirst::new().with_encryption(source)?.install()?;
+    }
+
+    if let Some(budget) = cli.thinking_budget {
+        config.agent.thinking_budget = Some(budget);
+        sources.set("agent.thinking_budget", ConfigSource::Cli);
+    }
+
+    // Tool lists on the command line replace the configured ones
+    if !cli.allow_tools.is_empty() {
+        config.tools.allow = cli.allow_tools;
+        sources.set("tools.allow", ConfigSource::Cli);
+    }
+    if !cli.deny_tools.is_empty() {
+        config.tools.deny = cli.deny_tools;
+        sources.set("tools.deny", ConfigSource::Cli);
+    }
+    if cli.continue_on_tool_error {
+        config.agent.on_tool_error = ToolErrorPolicy::Lenient;
+        sources.set("agent.on_tool_error", ConfigSource::Cli);
+    }
+
+    if cli.no_redact {
+        config.redaction.enabled = false;
+        sources.set("redaction.enabled", ConfigSource::Cli);
+    }
+    crate::safety::redact::init_redaction(&config.redaction)?;
+    config.allow_self_edit = cli.allow_self_edit;
+    config.focus = cli.focus.clone();
+
     if config.execution_mode == ExecutionMode::Daemon {
         let addr = "127.0.0.1:9090".parse().unwrap();
         if let Err(e) = crate::telemetry::start_prometheus_exporter(addr) {
@@ -389,9 +741,22 @@ pub async fn run() -> Result<()> {
             Theme::HighContrast => ThemeId::HighContrast,
         };
         theme::set_theme(theme_id);
+        if let Some(value) = clap::ValueEnum::to_possible_value(&cli.theme) {
+            config.ui.theme = value.get_name().to_string();
+        }
+        sources.set("ui.theme", ConfigSource::Cli);
     }
 
     // CLI flags override config for compact/verbose/show_tokens
+    for (flag, key) in [
+        (cli.compact, "ui.compact_mode"),
+        (cli.verbose, "ui.verbose_mode"),
+        (cli.show_tokens, "ui.show_tokens"),
+    ] {
+        if flag {
+            sources.set(key, ConfigSource::Cli);
+        }
+    }
     let compact = cli.compact || config.ui.compact_mode;
     let verbose = cli.verbose || config.ui.verbose_mode;
     let show_tokens = cli.show_tokens || config.ui.show_tokens;
@@ -399,6 +764,13 @@ pub async fn run() -> Result<()> {
     config.compact_mode = compact;
     config.verbose_mode = verbose;
     config.show_tokens = show_tokens;
+    config.ui.compact_mode = compact;
+    config.ui.verbose_mode = verbose;
+    config.ui.show_tokens = show_tokens;
+    if compact || verbose {
+        // An explicit verbosity choice replaces the cognitive load heuristic
+        config.ui.cognitive_load.adaptive = false;
+    }
 
     // Initialize output control with merged settings
     output::init(compact, verbose, show_tokens);
@@ -421,7 +793,13 @@ pub async fn run() -> Result<()> {
             anyhow::bail!("Empty prompt provided");
         }
 
-        if !cli.quiet {
+        if cli.json {
+            return run_task_json(config, &actual_prompt, None)
+                .await
+                .map(|_| ());
+        }
+
+        if !quiet {
             println!("{}", render_header(&ctx));
             println!(
                 "\n{} {}\n",
@@ -432,9 +810,9 @@ pub async fn run() -> Result<()> {
 
         let start = std::time::Instant::now();
         let mut agent = Agent::new(config).await?;
-        agent.run_task(&actual_prompt).await?;
+        agent.run_task(&actual_prompt).await?.into_result()?;
 
-        if !cli.quiet {
+        if !quiet {
             println!("{}", render_task_complete(start.elapsed()));
         }
         return Ok(());
@@ -474,7 +852,7 @@ pub async fn run() -> Result<()> {
                 match input {
                     Ok(input) if input != "exit" && input != "quit" => {
                         // Run the task — this will emit events to the TUI through event_tx
-                        if let Err(e) = agent.run_task(&input).await {
+                        if let Err(e) = agent.run_task(&input).await.and_then(|r| r.into_result()) {
                             warn!("Agent failed to run task: {}", e);
                         }
                     }
@@ -498,23 +876,285 @@ pub async fn run() -> Result<()> {
     }
 
     // Default to Chat if no subcommand specified (non-extras builds)
-    let command = cli.command.unwrap_or(Commands::Chat);
-    handle_command(command, cli.quiet, config, &ctx, exec_mode).await
+    let command = cli.command.unwrap_or(Commands::Chat { max_runtime: None });
+    handle_command(command, quiet, cli.json, config, &sources, &ctx, exec_mode).await
+}
+
+/// Run `task` for `--json`: everything the agent prints goes to stderr and
+/// stdout receives only the [`TaskResult`](crate::agent::task_result::TaskResult).
+/// A failed task still prints its result before the error is returned.
+/// The analyze task for `path`, limited to files changed since `since` when
+/// given. Falls back to a full survey, with a warning, when the changed set
+/// cannot be worked out. `None` when nothing changed.
+fn analyze_task(path: &str, since: Option<&str>) -> Option<String> {
+    use crate::analysis::incremental::{changed_since, AnalyzeState};
+
+    let Some(since) = since else {
+        return Some(Planner::analyze_prompt(path));
+    };
+    match changed_since(std::path::Path::new(path), since, &AnalyzeState::new()) {
+        Ok(changed) if changed.files.is_empty() => {
+            eprintln!(
+                "{} Nothing changed under {} since {}",
+                Glyphs::leaf(),
+                path,
+                since
+            );
+            None
+        }
+        Ok(changed) => {
+            eprintln!(
+                "{} Surveying {} files changed since {} ({})",
+                Glyphs::magnifier(),
+                changed.files.len(),
+                since,
+                &changed.base[..changed.base.len().min(12)]
+            );
+            Some(Planner::analyze_changes_prompt(path, since, &changed.files))
+        }
+        Err(e) => {
+            eprintln!(
+                "{} warning: --since {}: {:#}; surveying the whole tree",
+                Glyphs::frost(),
+                since,
+                e
+            );
+            Some(Planner::analyze_prompt(path))
+        }
+    }
+}
+
+/// Remember the commit this analyze saw, for `--since last`
+fn record_analyze(path: &str) {
+    let state = crate::analysis::incremental::AnalyzeState::new();
+    if let Err(e) = state.record(std::path::Path::new(path)) {
+        tracing::debug!("Not recording analyzed commit: {:#}", e);
+    }
+}
+
+async fn run_task_json(
+    config: Config,
+    task: &str,
+    max_runtime: Option<Duration>,
+) -> Result<TaskOutcome> {
+    let (agent, outcome) = {
+        let _redirect = output::StdoutRedirect::begin();
+        let mut agent = Agent::new(config).await?;
+        if let Some(limit) = max_runtime {
+            agent = agent.with_max_runtime(limit);
+        }
+        let outcome = agent.run_task(task).await;
+        (agent, outcome)
+    };
+    let (result, error) = match outcome {
+        Ok(result) => (result, None),
+        Err(e) => (agent.failed_task_result(&e), Some(e)),
+    };
+    println!("{}", serde_json::to_string_pretty(&result)?);
+    match error {
+        Some(e) => Err(e),
+        None => result.into_result().map(|r| r.status),
+    }
+}
+
+/// Run a task with the live dashboard on a thread of its own. The agent's
+/// events channel closes when it is dropped, which ends the dashboard.
+#[cfg(feature = "tui")]
+async fn run_task_watched(
+    config: Config,
+    task: &str,
+    max_runtime: Option<Duration>,
+) -> Result<TaskOutcome> {
+    let (tx, rx) = mpsc::channel();
+    let task_label = task.to_string();
+    let dashboard =
+        std::thread::spawn(move || crate::observability::dashboard::live::run(rx, &task_label));
+
+    let outcome = async {
+        let mut agent = Agent::new(config).await?.with_event_sender(tx);
+        if let Some(limit) = max_runtime {
+            agent = agent.with_max_runtime(limit);
+        }
+        agent.run_task(task).await
+    }
+    .await;
+
+    // Waits for the dashboard to be closed if it is still on screen
+    let shown = tokio::task::block_in_place(|| dashboard.join())
+        .map_err(|_| anyhow::anyhow!("Live dashboard panicked"))?;
+    let result = outcome?.into_result()?;
+    shown?;
+    Ok(result.status)
+}
+
+/// Print the `--profile` breakdown, and write its folded stacks if asked.
+/// Goes to stderr so it never mixes with `--json` output.
+fn finish_profile(elapsed: Duration, folded: Option<&std::path::Path>) -> Result<()> {
+    let Some(profiler) = crate::observability::profile::profiler() else {
+        return Ok(());
+    };
+    let report = profiler.report(elapsed);
+    eprintln!(
+        "\n{} Where the time went\n{}",
+        Glyphs::gear(),
+        report.render_table()
+    );
+    if let Some(path) = folded {
+        report.write_folded(path)?;
+        eprintln!(
+            "{} Folded stacks written to {}",
+            Glyphs::leaf(),
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+/// `selfware config --check`: load without validating, then list every
+/// warning and error. Fails when there are errors.
+fn check_config(path: Option<&str>, quiet: bool, json: bool) -> Result<()> {
+    let (config, _) = Config::layered_unchecked(path)?;
+    let report = crate::config::typed::check(&config);
+    let errors = report.errors().count();
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report.issues)?);
+    } else {
+        for issue in report.warnings() {
+            println!("{} warning: {}", Glyphs::frost(), issue);
+        }
+        for issue in report.errors() {
+            println!("{} error: {}", Glyphs::wilt(), issue);
+        }
+        if !quiet && errors == 0 {
+            println!(
+                "{} Configuration OK ({} warning(s))",
+                Glyphs::bloom(),
+                report.issues.len()
+            );
+        }
+    }
+    if errors > 0 {
+        anyhow::bail!("Configuration has {} error(s)", errors);
+    }
+    Ok(())
+}
+
+/// Create and enter a worktree of HEAD for `--worktree`
+fn enter_worktree(enabled: bool, label: &str) -> Result<Option<TaskWorktree>> {
+    if !enabled {
+        return Ok(None);
+    }
+    let mut worktree = TaskWorktree::create(&std::env::current_dir()?, label)?;
+    worktree.enter()?;
+    eprintln!(
+        "{} Working in {} on branch {}\n",
+        Glyphs::branch(),
+        worktree.path().display(),
+        worktree.branch()
+    );
+    Ok(Some(worktree))
+}
+
+/// Settle a `--worktree` run. An aborted run, or one that changed nothing,
+/// is discarded; otherwise ask whether to merge, cherry-pick, keep or
+/// discard. Without a terminal to ask on, the worktree is kept.
+fn finish_worktree(worktree: Option<TaskWorktree>, label: &str, aborted: bool) -> Result<()> {
+    use crate::devops::worktree::MergeStrategy;
+    use std::io::{BufRead, IsTerminal, Write};
+
+    let Some(mut worktree) = worktree else {
+        return Ok(());
+    };
+    worktree.leave();
+    if aborted {
+        eprintln!(
+            "{} Run aborted; removing its worktree",
+            Glyphs::fallen_leaf()
+        );
+        return worktree.discard();
+    }
+    worktree.commit_pending(&format!("selfware: {}", label))?;
+    let commits = worktree.commits()?;
+    if commits.is_empty() {
+        eprintln!("{} No changes made; removing the worktree", Glyphs::leaf());
+        return worktree.discard();
+    }
+
+    eprintln!(
+        "\n{} {} commit(s) on {}:",
+        Glyphs::branch(),
+        commits.len(),
+        worktree.branch()
+    );
+    for commit in &commits {
+        eprintln!("    {}", commit);
+    }
+    let keep_hint = format!(
+        "Kept {} on {}. Merge with `git merge {}`, remove with `git worktree remove {}`.",
+        worktree.path().display(),
+        worktree.branch(),
+        worktree.branch(),
+        worktree.path().display()
+    );
+    if !std::io::stdin().is_terminal() {
+        eprintln!("{}", keep_hint);
+        return Ok(());
+    }
+
+    loop {
+        eprint!("[m]erge  [c]herry-pick  [k]eep  [d]iscard > ");
+        std::io::stderr().flush()?;
+        let mut answer = String::new();
+        std::io::stdin().lock().read_line(&mut answer)?;
+        let strategy = match answer.trim().to_ascii_lowercase().as_str() {
+            "m" | "merge" => MergeStrategy::Merge,
+            "c" | "cherry-pick" => MergeStrategy::CherryPick,
+            "" | "k" | "keep" => {
+                eprintln!("{}", keep_hint);
+                return Ok(());
+            }
+            "d" | "discard" => return worktree.discard(),
+            other => {
+                eprintln!("Unknown choice '{}'", other);
+                continue;
+            }
+        };
+        return match worktree.merge_back(strategy) {
+            Ok(()) => {
+                eprintln!(
+                    "{} Brought {} commit(s) into {}",
+                    Glyphs::bloom(),
+                    commits.len(),
+                    worktree.repo_root().display()
+                );
+                worktree.discard()
+            }
+            Err(e) => {
+                eprintln!("{} {:#}", Glyphs::wilt(), e);
+                Ok(())
+            }
+        };
+    }
 }
 
 async fn handle_command(
     command: Commands,
     quiet: bool,
+    json: bool,
     config: Config,
+    sources: &ConfigSources,
     ctx: &WorkshopContext,
     exec_mode: ExecutionMode,
 ) -> Result<()> {
     match command {
-        Commands::Chat => {
+        Commands::Chat { max_runtime } => {
             if !quiet {
                 println!("{}", ui::components::render_welcome(ctx));
             }
             let mut agent = Agent::new(config).await?;
+            if let Some(limit) = max_runtime {
+                agent = agent.with_max_runtime(limit);
+            }
             agent.interactive().await?;
         }
 
@@ -535,24 +1175,149 @@ async fn handle_command(
             multi_agent.interactive().await?;
         }
 
-        Commands::Run { task } => {
+        Commands::Run {
+            task,
+            max_runtime,
+            worktree,
+            profile_folded,
+            ..
+        } if json => {
+            let isolation = enter_worktree(worktree, &task)?;
+            let start = std::time::Instant::now();
+            let outcome = run_task_json(config, &task, max_runtime).await;
+            finish_profile(start.elapsed(), profile_folded.as_deref())?;
+            let aborted = matches!(outcome, Ok(TaskOutcome::Interrupted));
+            finish_worktree(isolation, &task, aborted)?;
+            outcome?;
+        }
+
+        #[cfg(feature = "tui")]
+        Commands::Run {
+            task,
+            max_runtime,
+            worktree,
+            profile_folded,
+            watch: true,
+            ..
+        } => {
+            let isolation = enter_worktree(worktree, &task)?;
+            let start = std::time::Instant::now();
+            let outcome = run_task_watched(config, &task, max_runtime).await;
+            finish_profile(start.elapsed(), profile_folded.as_deref())?;
+            let aborted = matches!(outcome, Ok(TaskOutcome::Interrupted));
+            finish_worktree(isolation, &task, aborted)?;
+            outcome?;
+        }
+
+        Commands::Run {
+            task,
+            max_runtime,
+            worktree,
+            profile_folded,
+            ..
+        } => {
             if !quiet {
                 println!("{}", render_header(ctx));
                 println!("{}", render_task_start(&task));
             }
 
+            let isolation = enter_worktree(worktree, &task)?;
             let start = std::time::Instant::now();
-            let mut agent = Agent::new(config).await?;
-            agent.run_task(&task).await?;
+            let outcome = async {
+                let mut agent = Agent::new(config).await?;
+                if let Some(limit) = max_runtime {
+                    agent = agent.with_max_runtime(limit);
+                }
+                agent.run_task(&task).await?.into_result()
+            }
+            .await;
+
+            if let Ok(result) = &outcome {
+                if !quiet && !matches!(result.status, TaskOutcome::TimedOut | TaskOutcome::Paused) {
+                    println!("{}", render_task_complete(start.elapsed()));
+                }
+            }
+            finish_profile(start.elapsed(), profile_folded.as_deref())?;
+            let aborted = matches!(&outcome, Ok(r) if r.status == TaskOutcome::Interrupted);
+            finish_worktree(isolation, &task, aborted)?;
+            outcome?;
+        }
 
+        Commands::Batch {
+            file,
+            fail_fast,
+            skip_completed,
+            report,
+            worktree,
+        } => {
+            // Resolved before entering the worktree, where it may not exist
+            let file = std::path::absolute(file)?;
             if !quiet {
-                println!("{}", render_task_complete(start.elapsed()));
+                println!("{}", render_header(ctx));
+                println!(
+                    "{} {} tasks from {}...\n",
+                    Glyphs::journal(),
+                    "Tending".craftsman_voice(),
+                    file.to_string_lossy().as_ref().path_local()
+                );
+            }
+
+            let options = batch::BatchOptions {
+                fail_fast,
+                skip_completed,
+            };
+            let label = format!(
+                "batch {}",
+                file.file_stem().unwrap_or_default().to_string_lossy()
+            );
+            let isolation = enter_worktree(worktree, &label)?;
+            let outcome = {
+                let _redirect = json.then(output::StdoutRedirect::begin);
+                batch::run_batch(&config, &file, &options).await
+            };
+            let aborted =
+                matches!(&outcome, Ok(r) if r.count(batch::BatchTaskStatus::Interrupted) > 0);
+            finish_worktree(isolation, &label, aborted)?;
+            let result = outcome?;
+            let report_path = report
+                .map(std::path::PathBuf::from)
+                .unwrap_or_else(|| batch::default_report_path(&file));
+            result.save(&report_path)?;
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&result)?);
+            } else if !quiet {
+                print_batch_summary(&result, &report_path);
+            }
+            if !result.succeeded() {
+                let done = result.count(batch::BatchTaskStatus::Completed)
+                    + result.count(batch::BatchTaskStatus::Skipped);
+                anyhow::bail!(
+                    "{} of {} batch tasks did not complete",
+                    result.total_tasks - done,
+                    result.total_tasks
+                );
             }
         }
 
-        Commands::Analyze { path } => {
+        Commands::Analyze { path, since } if json => {
+            let Some(task) = analyze_task(&path, since.as_deref()) else {
+                return Ok(());
+            };
+            let status = run_task_json(config, &task, None).await?;
+            if status == TaskOutcome::Completed {
+                record_analyze(&path);
+            }
+        }
+
+        Commands::Analyze { path, since } => {
             if !quiet {
                 println!("{}", render_header(ctx));
+            }
+            let Some(task) = analyze_task(&path, since.as_deref()) else {
+                return Ok(());
+            };
+            if !quiet {
                 println!(
                     "{} {} your garden at {}...\n",
                     Glyphs::magnifier(),
@@ -562,7 +1327,9 @@ async fn handle_command(
             }
 
             let mut agent = Agent::new(config).await?;
-            agent.analyze(&path).await?;
+            if agent.run_task(&task).await?.into_result()?.succeeded() {
+                record_analyze(&path);
+            }
         }
 
         Commands::Garden { path } => {
@@ -626,7 +1393,180 @@ async fn handle_command(
             }
         }
 
-        Commands::Journal => {
+        Commands::Fork { task_id, step } => {
+            let step = match step {
+                Some(step) => step,
+                None => Agent::task_status(&task_id)?.current_step,
+            };
+            let new_id = Agent::fork_task(&task_id, step)?;
+            if quiet {
+                println!("{}", new_id);
+            } else {
+                println!(
+                    "{} Forked journal entry {} at step {} into {}",
+                    Glyphs::branch(),
+                    task_id.muted(),
+                    step,
+                    new_id.as_str().emphasis()
+                );
+                println!(
+                    "   {} Continue with: selfware resume {}",
+                    Glyphs::sprout(),
+                    new_id
+                );
+            }
+        }
+
+        Commands::Search { query, limit, open } => {
+            let store = crate::session::chat_store::ChatStore::new()?;
+            let hits = store.search(&query, limit.max(open.unwrap_or(0)))?;
+
+            if let Some(n) = open {
+                let Some(hit) = n.checked_sub(1).and_then(|i| hits.get(i)) else {
+                    anyhow::bail!("No hit #{} for '{}' ({} found)", n, query, hits.len());
+                };
+                if !quiet {
+                    println!("{}", ui::components::render_welcome(ctx));
+                }
+                let mut agent = Agent::new(config).await?;
+                let chat = agent.resume_chat(&hit.name)?;
+                println!(
+                    "{} Resumed chat '{}' ({} messages)",
+                    Glyphs::bookmark(),
+                    chat.name.as_str().emphasis(),
+                    chat.message_count
+                );
+                agent.interactive().await?;
+            } else if json {
+                println!("{}", serde_json::to_string_pretty(&hits)?);
+            } else if hits.is_empty() {
+                println!(
+                    "{} No saved chats mention '{}'",
+                    Glyphs::journal(),
+                    query.as_str().muted()
+                );
+            } else {
+                for (i, hit) in hits.iter().enumerate() {
+                    println!(
+                        "{:>3}. {} {}",
+                        i + 1,
+                        hit.name.as_str().emphasis(),
+                        hit.saved_at
+                            .format("%Y-%m-%d %H:%M")
+                            .to_string()
+                            .as_str()
+                            .muted()
+                    );
+                    println!("     {}: {}", hit.role, hit.snippet);
+                }
+                println!(
+                    "\n   {} Open one with: selfware search \"{}\" --open <N>",
+                    Glyphs::sprout(),
+                    query
+                );
+            }
+        }
+
+        Commands::Migrate { encrypt } => {
+            if !encrypt {
+                anyhow::bail!("Nothing to migrate. Use `selfware migrate --encrypt`.");
+            }
+            let storage = LocalFirst::global();
+            if !storage.is_encrypted() {
+                anyhow::bail!(
+                    "Encryption is not configured. Set encryption.key_source in selfware.toml first."
+                );
+            }
+            let journals = storage.migrate_dir(
+                crate::session::checkpoint::CheckpointManager::default_path()?.checkpoints_dir(),
+            )?;
+            let chats =
+                storage.migrate_dir(crate::session::chat_store::ChatStore::new()?.chats_dir())?;
+            if json {
+                println!(
+                    "{}",
+                    serde_json::json!({
+                        "journals": {"encrypted": journals.encrypted, "already_encrypted": journals.already_encrypted},
+                        "chats": {"encrypted": chats.encrypted, "already_encrypted": chats.already_encrypted},
+                    })
+                );
+            } else {
+                println!(
+                    "{} Encrypted {} journal file(s) and {} chat(s) ({} already encrypted)",
+                    Glyphs::key(),
+                    journals.encrypted,
+                    chats.encrypted,
+                    journals.already_encrypted + chats.already_encrypted
+                );
+            }
+        }
+
+        Commands::Journal {
+            action:
+                Some(JournalAction::Prune {
+                    keep_last,
+                    keep_for,
+                    dry_run,
+                }),
+        } => {
+            let mut policy = checkpoint::RetentionPolicy::from_config(&config.checkpoint)?;
+            if keep_last.is_some() {
+                policy.keep_last = keep_last;
+            }
+            if let Some(age) = keep_for {
+                policy.keep_for = Some(crate::config::parse_age(&age)?);
+            }
+            if policy.keep_last == Some(0) {
+                anyhow::bail!("--keep-last must be at least 1");
+            }
+            if policy.is_unbounded() {
+                anyhow::bail!(
+                    "No retention policy. Pass --keep-last or --keep-for, or set [checkpoint] in selfware.toml."
+                );
+            }
+
+            let manager = checkpoint::CheckpointManager::default_path()?;
+            let removed = if dry_run {
+                policy.expired(&manager.list_tasks()?, chrono::Utc::now())
+            } else {
+                manager.prune(&policy)?
+            };
+
+            if json {
+                println!(
+                    "{}",
+                    serde_json::json!({"dry_run": dry_run, "removed": removed})
+                );
+            } else if removed.is_empty() {
+                println!("{} Nothing to prune.", Glyphs::journal());
+            } else {
+                for task_id in &removed {
+                    println!("   {} {}", Glyphs::fallen_leaf(), task_id.as_str().muted());
+                }
+                println!(
+                    "{} {} {} journal entr{}.",
+                    Glyphs::journal(),
+                    if dry_run {
+                        "Would compost"
+                    } else {
+                        "Composted"
+                    },
+                    removed.len(),
+                    if removed.len() == 1 { "y" } else { "ies" }
+                );
+            }
+        }
+
+        Commands::Journal {
+            action: Some(JournalAction::Export { task_id, format }),
+        } => {
+            let document = match format {
+                ExportFormat::Md => crate::ui::accessibility::literate::export(&task_id)?,
+            };
+            print!("{}", document);
+        }
+
+        Commands::Journal { action: None } => {
             if !quiet {
                 println!("{}", render_header(ctx));
             }
@@ -668,12 +1608,32 @@ async fn handle_command(
                         task.current_step.to_string().muted(),
                         task.status
                     );
+                    if let Some(ref origin) = task.fork_origin {
+                        println!(
+                            "      {} Forked from {} at step {}",
+                            Glyphs::branch().muted(),
+                            origin.parent_task_id.as_str().muted(),
+                            origin.forked_at_step
+                        );
+                    }
+                    for bookmark in &task.bookmarks {
+                        println!(
+                            "      {} Step {}{}",
+                            Glyphs::bookmark().muted(),
+                            bookmark.step,
+                            bookmark
+                                .label
+                                .as_deref()
+                                .map(|l| format!(" · {}", l.emphasis()))
+                                .unwrap_or_default()
+                        );
+                    }
                 }
                 println!();
             }
         }
 
-        Commands::JournalEntry { task_id } => {
+        Commands::JournalEntry { task_id, verbose } => {
             if !quiet {
                 println!("{}", render_header(ctx));
             }
@@ -703,6 +1663,14 @@ async fn handle_command(
                 Glyphs::branch().muted(),
                 checkpoint.current_step
             );
+            if let Some(ref origin) = checkpoint.fork_origin {
+                println!(
+                    "   {} Forked from: {} (step {})",
+                    Glyphs::branch().muted(),
+                    origin.parent_task_id.as_str().muted(),
+                    origin.forked_at_step
+                );
+            }
             println!(
                 "   {} Started:     {}",
                 Glyphs::seedling(),
@@ -767,6 +1735,29 @@ async fn handle_command(
                     );
                 }
             }
+
+            if verbose || config.verbose_mode {
+                println!(
+                    "\n   {} {}",
+                    Glyphs::journal(),
+                    "Reasoning:".craftsman_voice()
+                );
+                if checkpoint.reasoning.is_empty() {
+                    println!(
+                        "      {}",
+                        "None stored (set store_reasoning = true to keep it)".muted()
+                    );
+                }
+                for entry in &checkpoint.reasoning {
+                    println!("      Step {}:", entry.step);
+                    for line in entry.text.lines() {
+                        println!("        {}", line.muted());
+                    }
+                    if entry.truncated {
+                        println!("        {}", "[truncated]".muted());
+                    }
+                }
+            }
             println!();
         }
 
@@ -781,7 +1772,220 @@ async fn handle_command(
             }
         }
 
+        #[cfg(feature = "tokens")]
+        Commands::Tokens {
+            context,
+            chat,
+            task,
+            all,
+        } => {
+            if !context {
+                anyhow::bail!("Nothing to show: pass --context (see `selfware tokens --help`)");
+            }
+            let mut agent = match task {
+                Some(task_id) => Agent::resume(config, &task_id).await?,
+                None => Agent::new(config).await?,
+            };
+            if let Some(name) = chat {
+                agent.resume_chat(&name)?;
+            }
+            agent.show_token_distribution(all.then_some(usize::MAX));
+        }
+
+        Commands::Config { show, .. } => {
+            if !show {
+                println!("Usage: selfware config --show | --check");
+                return Ok(());
+            }
+            if !quiet {
+                println!(
+                    "{} Effective configuration (defaults < user < project < env < cli)\n",
+                    Glyphs::gear()
+                );
+            }
+            for line in config.annotated_lines(sources) {
+                println!("{}", line);
+            }
+        }
+
+        Commands::Kg {
+            action:
+                KgAction::Export {
+                    format,
+                    graph,
+                    subgraph,
+                    depth,
+                    output,
+                },
+        } => {
+            use crate::cognitive::knowledge_graph::KnowledgeGraph;
+
+            let mut kg = match graph {
+                Some(path) => {
+                    if !path.exists() {
+                        anyhow::bail!("Knowledge graph file not found: {}", path.display());
+                    }
+                    KnowledgeGraph::load_from_file(&path)?
+                }
+                None => KnowledgeGraph::from_rust_sources(&std::env::current_dir()?),
+            };
+            if let Some(entity) = subgraph {
+                kg = kg.subgraph(&entity, depth)?;
+            }
+            let rendered = match format {
+                GraphFormat::Dot => kg.to_dot(),
+                GraphFormat::Mermaid => kg.to_mermaid(),
+            };
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, rendered)
+                        .with_context(|| format!("Failed to write {}", path.display()))?;
+                    if !quiet {
+                        println!(
+                            "{} Exported {} entities and {} relations to {}",
+                            Glyphs::gear(),
+                            kg.entity_count(),
+                            kg.relation_count(),
+                            path.display()
+                        );
+                    }
+                }
+                None => print!("{}", rendered),
+            }
+        }
+
+        Commands::Analytics {
+            action: AnalyticsAction::ToolStats { period, reset },
+        } => {
+            let store = ToolTimingStore::new();
+            if reset {
+                store.clear()?;
+                if !quiet {
+                    println!("{} Tool timings cleared.", Glyphs::fallen_leaf());
+                }
+                return Ok(());
+            }
+            let period = TimePeriod::from(period);
+            let stats = store.stats(period)?;
+
+            if json {
+                println!(
+                    "{}",
+                    serde_json::json!({
+                        "period": period.label(),
+                        "tools": stats.iter().map(|s| serde_json::json!({
+                            "tool": s.tool,
+                            "calls": s.calls,
+                            "failures": s.failures,
+                            "failure_rate": s.failure_rate(),
+                            "p50_ms": s.p50_ms,
+                            "p95_ms": s.p95_ms,
+                            "total_ms": s.total_ms,
+                            "avg_result_bytes": s.avg_result_bytes,
+                        })).collect::<Vec<_>>(),
+                    })
+                );
+            } else if stats.is_empty() {
+                println!(
+                    "\n{} {} No tool timings recorded ({}).\n",
+                    Glyphs::gear(),
+                    "Note:".muted(),
+                    period.label()
+                );
+            } else {
+                println!(
+                    "\n{} {} ({})\n",
+                    Glyphs::gear(),
+                    "Tool Latency".workshop_title(),
+                    period.label()
+                );
+                println!(
+                    "   {:<24} {:>7} {:>9} {:>9} {:>9} {:>8}",
+                    "tool", "calls", "p50", "p95", "total", "failed"
+                );
+                for s in &stats {
+                    println!(
+                        "   {:<24} {:>7} {:>9} {:>9} {:>9} {:>7.1}%",
+                        s.tool,
+                        s.calls,
+                        format_ms(s.p50_ms),
+                        format_ms(s.p95_ms),
+                        format_ms(s.total_ms),
+                        s.failure_rate() * 100.0
+                    );
+                }
+                println!();
+            }
+        }
+
+        Commands::Analytics {
+            action: AnalyticsAction::Leaderboard { reset },
+        } => {
+            let store = ModelComparisonStore::new();
+            if reset {
+                store.clear()?;
+                if !quiet {
+                    println!("{} Model comparisons cleared.", Glyphs::fallen_leaf());
+                }
+                return Ok(());
+            }
+            let standings = store.leaderboard()?;
+
+            if json {
+                println!(
+                    "{}",
+                    serde_json::json!({
+                        "models": standings.iter().map(|s| serde_json::json!({
+                            "model": s.model,
+                            "comparisons": s.comparisons,
+                            "wins": s.wins,
+                            "losses": s.losses,
+                            "ties": s.ties,
+                            "win_rate": s.win_rate(),
+                            "failures": s.failures,
+                            "avg_duration_ms": s.avg_duration_ms,
+                            "avg_completion_tokens": s.avg_completion_tokens,
+                            "total_cost_usd": s.total_cost_usd,
+                        })).collect::<Vec<_>>(),
+                    })
+                );
+            } else if standings.is_empty() {
+                println!(
+                    "\n{} {} No comparisons yet. Try /compare <modelA> <modelB> <task>.\n",
+                    Glyphs::gear(),
+                    "Note:".muted()
+                );
+            } else {
+                println!(
+                    "\n{} {}\n",
+                    Glyphs::gear(),
+                    "Model Leaderboard".workshop_title()
+                );
+                println!(
+                    "   {:<28} {:>5} {:>11} {:>8} {:>9} {:>9}",
+                    "model", "runs", "W-L-T", "win", "avg time", "cost"
+                );
+                for s in &standings {
+                    println!(
+                        "   {:<28} {:>5} {:>11} {:>7.0}% {:>9} {:>9}",
+                        s.model,
+                        s.comparisons,
+                        format!("{}-{}-{}", s.wins, s.losses, s.ties),
+                        s.win_rate() * 100.0,
+                        format_ms(s.avg_duration_ms),
+                        format!("${:.4}", s.total_cost_usd)
+                    );
+                }
+                println!();
+            }
+        }
+
         Commands::Status { output_format } => {
+            let output_format = if json {
+                OutputFormat::Json
+            } else {
+                output_format
+            };
             // Count journal entries
             let tasks = match Agent::list_tasks() {
                 Ok(tasks) => tasks,
@@ -921,6 +2125,14 @@ async fn handle_command(
                 return Ok(());
             }
 
+            if !config.allow_self_edit {
+                println!(
+                    "\n   {} Edits to selfware's own source are blocked without --allow-self-edit.",
+                    Glyphs::frost()
+                );
+                return Ok(());
+            }
+
             let cycles = if continuous { max_cycles } else { 1 };
             let mut agent = Agent::new(config).await?;
 
@@ -943,8 +2155,8 @@ async fn handle_command(
                 );
 
                 let prompt = orchestrator.build_improvement_prompt(target);
-                match agent.run_task(&prompt).await {
-                    Ok(()) => {
+                match agent.run_task(&prompt).await.and_then(|r| r.into_result()) {
+                    Ok(_) => {
                         println!("   {} Improvement applied successfully.", Glyphs::bloom());
                     }
                     Err(e) => {
@@ -1071,6 +2283,17 @@ async fn handle_command(
                 WorkflowExecutor::new_with_config(&config.safety)
             };
 
+            // Reusable workflows that `call_workflow` steps can name
+            let library = std::path::Path::new(crate::workflows::WORKFLOW_LIBRARY_DIR);
+            let loaded = executor.load_dir(library)?;
+            if loaded > 0 {
+                println!(
+                    "   {} {} workflow(s) from {}",
+                    Glyphs::journal(),
+                    loaded,
+                    library.display()
+                );
+            }
             executor.load_file(path)?;
 
             // Determine which workflow to run
@@ -1151,6 +2374,60 @@ async fn handle_command(
     Ok(())
 }
 
+fn print_batch_summary(report: &batch::BatchReport, report_path: &std::path::Path) {
+    use batch::BatchTaskStatus;
+
+    println!(
+        "\n{} {}\n",
+        Glyphs::journal(),
+        "Batch Summary:".workshop_title()
+    );
+    for task in &report.tasks {
+        let glyph = match task.status {
+            BatchTaskStatus::Completed => Glyphs::bloom(),
+            BatchTaskStatus::Failed => Glyphs::frost(),
+            BatchTaskStatus::Interrupted
+            | BatchTaskStatus::Incomplete
+            | BatchTaskStatus::TimedOut
+            | BatchTaskStatus::Paused => Glyphs::wilt(),
+            BatchTaskStatus::Skipped => Glyphs::bookmark(),
+        };
+        let desc = truncate_with_ellipsis(&task.task, JOURNAL_DESC_MAX_CHARS);
+        println!(
+            "   {} {} {}",
+            glyph,
+            task.task_id.as_str().muted(),
+            desc.craftsman_voice()
+        );
+        let mut detail = format!("{:?} · {:.0}s", task.status, task.duration_secs);
+        if let Some(error) = &task.error {
+            detail.push_str(&format!(" · {}", truncate_with_ellipsis(error, 80)));
+        }
+        println!("      {} {}", Glyphs::branch().muted(), detail.muted());
+    }
+
+    let not_run = report.total_tasks - report.tasks.len();
+    println!(
+        "\n   {} completed · {} failed · {} incomplete · {} skipped{}",
+        report.count(BatchTaskStatus::Completed),
+        report.count(BatchTaskStatus::Failed),
+        report.count(BatchTaskStatus::Incomplete)
+            + report.count(BatchTaskStatus::Interrupted)
+            + report.count(BatchTaskStatus::TimedOut),
+        report.count(BatchTaskStatus::Skipped),
+        if not_run > 0 {
+            format!(" · {} not run", not_run)
+        } else {
+            String::new()
+        }
+    );
+    println!(
+        "   {} Report written to {}\n",
+        Glyphs::bookmark(),
+        report_path.to_string_lossy().as_ref().path_local()
+    );
+}
+
 #[cfg(feature = "tui")]
 fn run_demo_scenario(scenario: DemoScenarioKind, fast: bool, quiet: bool) -> Result<()> {
     use crate::ui::demo::{
@@ -1424,6 +2701,44 @@ token_budget = 500000
     Ok(())
 }
 
+/// Parse a wall-clock budget such as `45m`, `2h` or `1h30m`.
+fn parse_runtime(value: &str) -> std::result::Result<Duration, String> {
+    let mut total = 0u64;
+    let mut digits = String::new();
+    for c in value.trim().chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+        let unit = match c {
+            's' => 1,
+            'm' => 60,
+            'h' => 3600,
+            _ => return Err(format!("unknown unit '{}' (use s, m or h)", c)),
+        };
+        let amount: u64 = digits
+            .parse()
+            .map_err(|_| format!("expected a number before '{}'", c))?;
+        total = total.saturating_add(amount.saturating_mul(unit));
+        digits.clear();
+    }
+    if !digits.is_empty() || total == 0 {
+        return Err("expected a duration like 45m, 2h or 1h30m".to_string());
+    }
+    Ok(Duration::from_secs(total))
+}
+
+/// Milliseconds as e.g. `850ms`, `12.4s` or `3.2m`
+fn format_ms(ms: u64) -> String {
+    if ms < 1000 {
+        format!("{}ms", ms)
+    } else if ms < 60_000 {
+        format!("{:.1}s", ms as f64 / 1000.0)
+    } else {
+        format!("{:.1}m", ms as f64 / 60_000.0)
+    }
+}
+
 fn truncate_with_ellipsis(input: &str, max_chars: usize) -> String {
     if input.chars().count() <= max_chars {
         return input.to_string();
@@ -1458,6 +2773,26 @@ mod tests {
     use super::*;
     use std::path::Path;
 
+    // ── parse_runtime tests ──
+
+    #[test]
+    fn format_ms_picks_readable_unit() {
+        assert_eq!(format_ms(850), "850ms");
+        assert_eq!(format_ms(12_400), "12.4s");
+        assert_eq!(format_ms(192_000), "3.2m");
+    }
+
+    #[test]
+    fn parse_runtime_accepts_compound_units() {
+        assert_eq!(parse_runtime("45m"), Ok(Duration::from_secs(45 * 60)));
+        assert_eq!(parse_runtime("1h30m"), Ok(Duration::from_secs(5400)));
+        assert_eq!(parse_runtime("90s"), Ok(Duration::from_secs(90)));
+        assert!(parse_runtime("90").is_err());
+        assert!(parse_runtime("2d").is_err());
+        assert!(parse_runtime("0m").is_err());
+        assert!(parse_runtime("").is_err());
+    }
+
     // ── truncate_with_ellipsis tests ──
 
     #[test]
@@ -6,6 +6,7 @@
 //! - Dependency graph from Cargo.toml
 //! - Git state monitoring
 //! - Pattern detection for code structure
+//! - Task difficulty assessment for picking a model and thinking budget
 
 use crate::bm25::BM25Index;
 use anyhow::Result;
@@ -1455,6 +1456,232 @@ impl SearchResult {
     }
 }
 
+/// How hard a task looks before starting it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Difficulty {
+    Trivial,
+    Easy,
+    Moderate,
+    Hard,
+}
+
+impl Difficulty {
+    /// Difficulty for a predicted number of agent steps
+    pub fn from_steps(steps: f32) -> Self {
+        if steps <= 2.0 {
+            Difficulty::Trivial
+        } else if steps <= 5.0 {
+            Difficulty::Easy
+        } else if steps <= 12.0 {
+            Difficulty::Moderate
+        } else {
+            Difficulty::Hard
+        }
+    }
+
+    /// Key used for this difficulty in `agent.difficulty_models`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Difficulty::Trivial => "trivial",
+            Difficulty::Easy => "easy",
+            Difficulty::Moderate => "moderate",
+            Difficulty::Hard => "hard",
+        }
+    }
+
+    /// Scale a thinking budget for this difficulty. An uncapped budget stays
+    /// uncapped for moderate and hard tasks and gets a cap for easier ones.
+    fn scale_thinking_budget(&self, base: Option<u32>) -> Option<u32> {
+        match (self, base) {
+            (_, Some(0)) => Some(0),
+            (Difficulty::Trivial, base) => Some(base.unwrap_or(DEFAULT_THINKING_BUDGET) / 4),
+            (Difficulty::Easy, base) => Some(base.unwrap_or(DEFAULT_THINKING_BUDGET) / 2),
+            (Difficulty::Moderate, base) => base,
+            (Difficulty::Hard, base) => base.map(|b| b.saturating_mul(2)),
+        }
+    }
+}
+
+impl std::fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Budget that easier tasks are capped from when thinking is otherwise uncapped
+const DEFAULT_THINKING_BUDGET: u32 = 8192;
+
+/// Steps a task with no difficulty signals is expected to take
+const BASELINE_STEPS: f32 = 4.0;
+
+/// Words that tend to mean a task spans a lot of code or needs careful reasoning
+const HARD_KEYWORDS: &[&str] = &[
+    "architecture",
+    "redesign",
+    "refactor",
+    "migrate",
+    "migration",
+    "concurrency",
+    "race condition",
+    "deadlock",
+    "performance",
+    "optimize",
+    "security",
+    "across",
+    "entire",
+    "every",
+    "implement",
+];
+
+/// Words that tend to mean a small, local change or a question
+const EASY_KEYWORDS: &[&str] = &[
+    "typo", "rename", "comment", "readme", "format", "bump", "explain", "what is", "where is",
+    "list", "show",
+];
+
+/// Calibration weight given to each finished task
+const CALIBRATION_RATE: f32 = 0.2;
+
+/// What is known about a task besides its description
+#[derive(Debug, Clone, Default)]
+pub struct TaskContext<'a> {
+    /// Task type as inferred by the agent (e.g. "refactor")
+    pub task_type: &'a str,
+    /// Files already loaded into the conversation
+    pub files_in_context: usize,
+    /// Success rate of earlier tasks of the same type, when known
+    pub success_rate: Option<f32>,
+    /// Thinking budget configured for this task type (`None` is uncapped)
+    pub base_thinking_budget: Option<u32>,
+    /// Model profile or name to use per difficulty, keyed by [`Difficulty::as_str`]
+    pub models: Option<&'a HashMap<String, String>>,
+}
+
+/// Difficulty estimate for a task and what to run it with
+#[derive(Debug, Clone, PartialEq)]
+pub struct Assessment {
+    pub task_type: String,
+    pub difficulty: Difficulty,
+    /// Steps predicted from the task alone, before calibration
+    pub predicted_steps: f32,
+    /// Steps the task is expected to take, after calibration
+    pub expected_steps: f32,
+    /// Model to switch to, if one is configured for this difficulty
+    pub recommended_model: Option<String>,
+    pub thinking_budget: Option<u32>,
+}
+
+/// Running ratio of actual to predicted steps for one task type
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct Calibration {
+    ratio: f32,
+    samples: usize,
+}
+
+/// Estimates task difficulty from the description and past outcomes, and
+/// calibrates itself against how many steps tasks actually took.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Intelligence {
+    calibration: HashMap<String, Calibration>,
+}
+
+impl Intelligence {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load calibration saved by [`Intelligence::save`]
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Save calibration to a JSON file
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Assess a task before running it
+    pub fn assess(&self, task: &str, context: &TaskContext) -> Assessment {
+        let predicted_steps = Self::predict_steps(task, context);
+        let expected_steps = predicted_steps * self.ratio(context.task_type);
+        let difficulty = Difficulty::from_steps(expected_steps);
+        let recommended_model = context
+            .models
+            .and_then(|models| models.get(difficulty.as_str()))
+            .cloned();
+
+        Assessment {
+            task_type: context.task_type.to_string(),
+            difficulty,
+            predicted_steps,
+            expected_steps,
+            recommended_model,
+            thinking_budget: difficulty.scale_thinking_budget(context.base_thinking_budget),
+        }
+    }
+
+    /// Feed back how many steps an assessed task took so later predictions
+    /// for the same task type move toward it
+    pub fn calibrate(&mut self, assessment: &Assessment, actual_steps: usize) {
+        let observed = (actual_steps.max(1) as f32 / assessment.predicted_steps).clamp(0.25, 4.0);
+        let entry = self
+            .calibration
+            .entry(assessment.task_type.clone())
+            .or_insert(Calibration {
+                ratio: 1.0,
+                samples: 0,
+            });
+        entry.ratio += (observed - entry.ratio) * CALIBRATION_RATE;
+        entry.samples += 1;
+    }
+
+    /// Calibration factor for a task type (1.0 until tasks have finished)
+    pub fn ratio(&self, task_type: &str) -> f32 {
+        self.calibration.get(task_type).map_or(1.0, |c| c.ratio)
+    }
+
+    /// Uncalibrated step estimate from the task description and context
+    fn predict_steps(task: &str, context: &TaskContext) -> f32 {
+        let lower = task.to_lowercase();
+        let mut steps = BASELINE_STEPS;
+
+        let hard = HARD_KEYWORDS.iter().filter(|k| lower.contains(*k)).count();
+        let easy = EASY_KEYWORDS.iter().filter(|k| lower.contains(*k)).count();
+        steps *= 1.5f32.powi(hard.min(3) as i32);
+        steps *= 0.6f32.powi(easy.min(2) as i32);
+
+        let words = task.split_whitespace().count();
+        if words > 80 {
+            steps *= 1.5;
+        } else if words < 8 && hard == 0 {
+            steps *= 0.75;
+        }
+
+        let mentioned_files = task
+            .split_whitespace()
+            .filter(|w| {
+                let w = w.trim_matches(|c: char| !c.is_alphanumeric() && c != '.' && c != '/');
+                w.contains('/') || Path::new(w).extension().is_some_and(|e| e.len() <= 4)
+            })
+            .count();
+        let files = mentioned_files + context.files_in_context;
+        steps *= 1.0 + 0.15 * files.min(10) as f32;
+
+        if let Some(rate) = context.success_rate {
+            // Task types that often fail are harder than they read
+            steps *= 1.0 + (1.0 - rate.clamp(0.0, 1.0));
+        }
+
+        steps
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2203,4 +2430,74 @@ macro_rules! my_macro { () => {} }
         assert!(index.get("MyTrait").is_some());
         assert!(index.get("MyStruct").is_some()); // Both struct and impl
     }
+
+    #[test]
+    fn test_assess_task_difficulty() {
+        let intel = Intelligence::new();
+        let mut models = HashMap::new();
+        models.insert("hard".to_string(), "big".to_string());
+        let context = TaskContext {
+            task_type: "general",
+            models: Some(&models),
+            ..Default::default()
+        };
+
+        let easy = intel.assess("fix the typo in README.md", &context);
+        assert!(easy.difficulty <= Difficulty::Easy);
+        assert_eq!(easy.recommended_model, None);
+        assert_eq!(easy.thinking_budget, Some(DEFAULT_THINKING_BUDGET / 4));
+
+        let hard = intel.assess(
+            "Refactor the session store across src/session/mod.rs, src/session/store.rs \
+             and src/api/mod.rs to fix the race condition and improve performance",
+            &context,
+        );
+        assert_eq!(hard.difficulty, Difficulty::Hard);
+        assert_eq!(hard.recommended_model.as_deref(), Some("big"));
+        assert_eq!(hard.thinking_budget, None);
+
+        let budgeted = TaskContext {
+            base_thinking_budget: Some(4096),
+            ..context.clone()
+        };
+        let hard = intel.assess("redesign the entire architecture", &budgeted);
+        assert_eq!(hard.thinking_budget, Some(8192));
+
+        // A history of failures makes the same task look harder
+        let failing = TaskContext {
+            success_rate: Some(0.0),
+            ..context
+        };
+        let task = "add a flag for the output format";
+        assert!(
+            intel.assess(task, &failing).expected_steps
+                > intel.assess(task, &TaskContext::default()).expected_steps
+        );
+    }
+
+    #[test]
+    fn test_calibration_moves_prediction_toward_actual_steps() {
+        let mut intel = Intelligence::new();
+        let context = TaskContext {
+            task_type: "testing",
+            ..Default::default()
+        };
+        let task = "add a test for the parser";
+        let before = intel.assess(task, &context);
+
+        for _ in 0..20 {
+            intel.calibrate(&before, 30);
+        }
+        let after = intel.assess(task, &context);
+        assert!(after.expected_steps > before.expected_steps * 2.0);
+        assert!(after.difficulty > before.difficulty);
+        // Other task types are unaffected
+        assert_eq!(intel.ratio("general"), 1.0);
+
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("difficulty.json");
+        intel.save(&path).unwrap();
+        let loaded = Intelligence::load(&path).unwrap();
+        assert_eq!(loaded.ratio("testing"), intel.ratio("testing"));
+    }
 }
@@ -1034,7 +1034,31 @@ impl KnowledgeGraph {
         rec_stack.remove(node);
     }
 
+    /// Entities sorted by ID, so exports are stable across runs
+    fn sorted_entities(&self) -> Vec<&Entity> {
+        let mut entities: Vec<&Entity> = self.entities.values().collect();
+        entities.sort_by(|a, b| a.id.cmp(&b.id));
+        entities
+    }
+
+    /// Relations sorted by source, target and type, so exports are stable
+    fn sorted_relations(&self) -> Vec<&Relation> {
+        let mut relations: Vec<&Relation> = self.relations.values().collect();
+        relations.sort_by(|a, b| {
+            (&a.source_id, &a.target_id, a.relation_type.to_string()).cmp(&(
+                &b.source_id,
+                &b.target_id,
+                b.relation_type.to_string(),
+            ))
+        });
+        relations
+    }
+
     /// Export to DOT format
+    ///
+    /// Nodes are labelled `Type: name` and colored by entity type. Edges are
+    /// labelled with the relation type; their style encodes the relation's
+    /// strength: bold for strong, solid for medium, dashed for weak.
     pub fn to_dot(&self) -> String {
         let mut output = String::new();
         output.push_str("digraph KnowledgeGraph {\n");
@@ -1042,7 +1066,7 @@ impl KnowledgeGraph {
         output.push_str("    node [shape=record];\n\n");
 
         // Nodes
-        for entity in self.entities.values() {
+        for entity in self.sorted_entities() {
             let color = match entity.entity_type {
                 EntityType::Module => "lightblue",
                 EntityType::Function | EntityType::Method => "lightgreen",
@@ -1052,30 +1076,223 @@ impl KnowledgeGraph {
             };
             output.push_str(&format!(
                 "    \"{}\" [label=\"{}: {}\" style=filled fillcolor={}];\n",
-                entity.id, entity.entity_type, entity.name, color
+                dot_escape(&entity.id),
+                entity.entity_type,
+                dot_escape(&entity.name),
+                color
             ));
         }
 
         output.push('\n');
 
         // Edges
-        for rel in self.relations.values() {
-            let style = match rel.relation_type {
-                RelationType::Calls => "solid",
-                RelationType::Uses => "dashed",
-                RelationType::Implements => "bold",
-                RelationType::Contains => "dotted",
-                _ => "solid",
+        for rel in self.sorted_relations() {
+            let style = match Confidence::of(rel.strength) {
+                Confidence::Strong => "bold",
+                Confidence::Medium => "solid",
+                Confidence::Weak => "dashed",
             };
             output.push_str(&format!(
-                "    \"{}\" -> \"{}\" [label=\"{}\" style={}];\n",
-                rel.source_id, rel.target_id, rel.relation_type, style
+                "    \"{}\" -> \"{}\" [label=\"{} ({:.2})\" style={}];\n",
+                dot_escape(&rel.source_id),
+                dot_escape(&rel.target_id),
+                rel.relation_type,
+                rel.strength,
+                style
             ));
         }
 
         output.push_str("}\n");
         output
     }
+
+    /// Export to a Mermaid flowchart
+    ///
+    /// Node labels and edge strength follow [`KnowledgeGraph::to_dot`]:
+    /// strong relations are thick arrows (`==>`), medium ones plain (`-->`)
+    /// and weak ones dotted (`-.->`).
+    pub fn to_mermaid(&self) -> String {
+        let entities = self.sorted_entities();
+        // Mermaid node IDs must be plain words, so number the entities
+        let node_ids: HashMap<&str, String> = entities
+            .iter()
+            .enumerate()
+            .map(|(i, e)| (e.id.as_str(), format!("n{}", i)))
+            .collect();
+
+        let mut output = String::from("flowchart LR\n");
+        for entity in &entities {
+            output.push_str(&format!(
+                "    {}[\"{}: {}\"]\n",
+                node_ids[entity.id.as_str()],
+                entity.entity_type,
+                mermaid_escape(&entity.name)
+            ));
+        }
+        for rel in self.sorted_relations() {
+            let (Some(from), Some(to)) = (
+                node_ids.get(rel.source_id.as_str()),
+                node_ids.get(rel.target_id.as_str()),
+            ) else {
+                continue;
+            };
+            let label = format!("{} ({:.2})", rel.relation_type, rel.strength);
+            let edge = match Confidence::of(rel.strength) {
+                Confidence::Strong => format!("=={}==>", mermaid_edge_label(&label)),
+                Confidence::Medium => format!("--{}-->", mermaid_edge_label(&label)),
+                Confidence::Weak => format!("-.{}.->", mermaid_edge_label(&label)),
+            };
+            output.push_str(&format!("    {} {} {}\n", from, edge, to));
+        }
+        output
+    }
+
+    /// The part of the graph within `depth` relations of `entity`, following
+    /// relations in both directions
+    ///
+    /// `entity` is an entity ID, a name or a qualified name; every entity it
+    /// matches is a starting point. Fails if nothing matches.
+    pub fn subgraph(&self, entity: &str, depth: usize) -> anyhow::Result<KnowledgeGraph> {
+        let mut frontier: Vec<String> = if self.entities.contains_key(entity) {
+            vec![entity.to_string()]
+        } else {
+            self.sorted_entities()
+                .into_iter()
+                .filter(|e| e.name == entity || e.qualified_name == entity)
+                .map(|e| e.id.clone())
+                .collect()
+        };
+        if frontier.is_empty() {
+            anyhow::bail!("No entity named '{}' in the knowledge graph", entity);
+        }
+
+        let mut kept: HashSet<String> = frontier.iter().cloned().collect();
+        for _ in 0..depth {
+            let mut next = Vec::new();
+            for id in &frontier {
+                let neighbours = self
+                    .relations_from(id)
+                    .into_iter()
+                    .map(|r| &r.target_id)
+                    .chain(self.relations_to(id).into_iter().map(|r| &r.source_id));
+                for neighbour in neighbours {
+                    if self.entities.contains_key(neighbour) && kept.insert(neighbour.clone()) {
+                        next.push(neighbour.clone());
+                    }
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            frontier = next;
+        }
+
+        let mut sub = KnowledgeGraph::new();
+        for id in &kept {
+            sub.add_entity(self.entities[id].clone());
+        }
+        for rel in self.relations.values() {
+            if kept.contains(&rel.source_id) && kept.contains(&rel.target_id) {
+                sub.add_relation(rel.clone());
+            }
+        }
+        Ok(sub)
+    }
+
+    /// Build a graph from the Rust sources under `root`: one module entity
+    /// per file containing the items it defines, and import relations from
+    /// each file to the items it names in `use` paths
+    ///
+    /// An import whose name matches several items gets a proportionally
+    /// lower strength.
+    pub fn from_rust_sources(root: &Path) -> Self {
+        let extractor = RustEntityExtractor::new();
+        let linker = SemanticLinker::new();
+        let mut graph = KnowledgeGraph::new();
+        let mut files = Vec::new();
+
+        let walker = walkdir::WalkDir::new(root)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|e| {
+                let name = e.file_name().to_string_lossy();
+                e.depth() == 0 || !(name.starts_with('.') || name == "target")
+            });
+        for entry in walker.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "rs") {
+                continue;
+            }
+            let Ok(content) = std::fs::read_to_string(path) else {
+                continue;
+            };
+            let rel_path = path.strip_prefix(root).unwrap_or(path);
+            let module = Entity::new(rel_path.display().to_string(), EntityType::Module)
+                .with_location(rel_path.to_path_buf(), 1, 1);
+            let module_id = graph.add_entity(module);
+            for item in extractor.extract(&content, rel_path) {
+                let item_id = graph.add_entity(item);
+                graph.add_relation(Relation::new(&module_id, item_id, RelationType::Contains));
+            }
+            files.push((module_id, content));
+        }
+
+        for (module_id, content) in &files {
+            // `use crate::a::B` matches both the `use` and the `crate::`
+            // pattern, so dedup on the imported name
+            let mut names: Vec<String> = linker
+                .extract_imports(content, "rust")
+                .iter()
+                .map(|import| import.rsplit("::").next().unwrap_or(import).to_string())
+                .collect();
+            names.sort();
+            names.dedup();
+            for name in names {
+                let relations = linker.link_imports(module_id, &[name], &graph);
+                let strength = 1.0 / relations.len().max(1) as f32;
+                for relation in relations {
+                    if relation.target_id != *module_id {
+                        graph.add_relation(relation.with_strength(strength));
+                    }
+                }
+            }
+        }
+        graph
+    }
+}
+
+/// How strongly a relation holds, bucketed for edge styles
+enum Confidence {
+    Strong,
+    Medium,
+    Weak,
+}
+
+impl Confidence {
+    fn of(strength: f32) -> Self {
+        if strength >= 0.75 {
+            Confidence::Strong
+        } else if strength >= 0.4 {
+            Confidence::Medium
+        } else {
+            Confidence::Weak
+        }
+    }
+}
+
+/// Escape a string for a double-quoted DOT ID or label
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Escape a string for a double-quoted Mermaid label
+fn mermaid_escape(s: &str) -> String {
+    s.replace('"', "#quot;")
+}
+
+/// An edge label in Mermaid's `-- label -->` form, which takes no quotes
+fn mermaid_edge_label(label: &str) -> String {
+    format!(" \"{}\" ", mermaid_escape(label))
 }
 
 /// Entity extractor for Rust code
@@ -1976,6 +2193,80 @@ mod tests {
         assert!(dot.contains("Config"));
     }
 
+    #[test]
+    fn test_exports_encode_strength_as_edge_style() {
+        let mut graph = KnowledgeGraph::new();
+        let a = graph.add_entity(Entity::new("run \"fast\"", EntityType::Function));
+        let b = graph.add_entity(Entity::new("Config", EntityType::Struct));
+        let c = graph.add_entity(Entity::new("Parser", EntityType::Trait));
+        graph.add_relation(Relation::new(&a, &b, RelationType::Uses));
+        graph.add_relation(Relation::new(&a, &c, RelationType::Calls).with_strength(0.2));
+
+        let dot = graph.to_dot();
+        assert!(dot.contains("label=\"Function: run \\\"fast\\\"\""));
+        assert!(dot.contains("[label=\"uses (1.00)\" style=bold]"));
+        assert!(dot.contains("[label=\"calls (0.20)\" style=dashed]"));
+
+        let mermaid = graph.to_mermaid();
+        assert!(mermaid.starts_with("flowchart LR\n"));
+        assert!(mermaid.contains("[\"Function: run #quot;fast#quot;\"]"));
+        assert!(mermaid.contains("== \"uses (1.00)\" ==>"));
+        assert!(mermaid.contains("-. \"calls (0.20)\" .->"));
+        // Stable output for the same graph
+        assert_eq!(mermaid, graph.to_mermaid());
+    }
+
+    #[test]
+    fn test_subgraph_limits_depth_both_directions() {
+        let mut graph = KnowledgeGraph::new();
+        let ids: Vec<String> = ["a", "b", "c", "d"]
+            .iter()
+            .map(|n| graph.add_entity(Entity::new(*n, EntityType::Function)))
+            .collect();
+        // a -> b -> c -> d
+        for pair in ids.windows(2) {
+            graph.add_relation(Relation::new(&pair[0], &pair[1], RelationType::Calls));
+        }
+
+        let sub = graph.subgraph("b", 1).unwrap();
+        let mut names: Vec<&str> = sub
+            .sorted_entities()
+            .iter()
+            .map(|e| e.name.as_str())
+            .collect();
+        names.sort();
+        assert_eq!(names, ["a", "b", "c"]);
+        assert_eq!(sub.relation_count(), 2);
+
+        assert_eq!(graph.subgraph(&ids[0], 0).unwrap().entity_count(), 1);
+        assert!(graph.subgraph("missing", 2).is_err());
+    }
+
+    #[test]
+    fn test_from_rust_sources_links_imports() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/config.rs"), "pub struct Config {}\n").unwrap();
+        std::fs::write(
+            dir.path().join("src/main.rs"),
+            "use crate::config::Config;\nfn main() {}\n",
+        )
+        .unwrap();
+
+        let graph = KnowledgeGraph::from_rust_sources(dir.path());
+        let main = graph.find_by_name("src/main.rs")[0].id.clone();
+        let imported: Vec<&str> = graph
+            .get_related(&main, Some(RelationType::Imports))
+            .iter()
+            .map(|(e, _)| e.name.as_str())
+            .collect();
+        assert_eq!(imported, ["Config"]);
+        assert_eq!(
+            graph.get_related(&main, Some(RelationType::Contains)).len(),
+            1
+        );
+    }
+
     #[test]
     fn test_entity_type_display() {
         assert_eq!(format!("{}", EntityType::Function), "Function");
@@ -1,9 +1,12 @@
 //! Cognitive Load Reducer
 //!
 //! Accessibility features to reduce overwhelm through progressive disclosure,
-//! simplified views, context summaries, and focus mode.
+//! simplified views, context summaries, and focus mode. [`CognitiveLoad`]
+//! estimates how loaded the user is from how they are typing so output can
+//! get terser when they are in a hurry.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
 
 /// Detail level for progressive disclosure
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -997,10 +1000,179 @@ impl DistractionFilter {
     }
 }
 
+/// Estimated cognitive load of the user
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum LoadLevel {
+    /// Long, unhurried messages
+    Low,
+    #[default]
+    Normal,
+    /// Rapid-fire short messages, or `/brief`
+    High,
+}
+
+impl std::fmt::Display for LoadLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadLevel::Low => write!(f, "Low"),
+            LoadLevel::Normal => write!(f, "Normal"),
+            LoadLevel::High => write!(f, "High"),
+        }
+    }
+}
+
+impl LoadLevel {
+    /// Detail level output should use at this load
+    pub fn detail_level(&self) -> DetailLevel {
+        match self {
+            LoadLevel::Low => DetailLevel::Detailed,
+            LoadLevel::Normal => DetailLevel::Standard,
+            LoadLevel::High => DetailLevel::Basic,
+        }
+    }
+}
+
+/// Inputs kept for estimating load
+const MAX_TRACKED_INPUTS: usize = 20;
+
+/// How the user has been interacting during this session
+#[derive(Debug, Clone, Default)]
+pub struct SessionSignals {
+    /// (time the user took to reply, message length in chars), oldest first
+    inputs: VecDeque<(Option<Duration>, usize)>,
+    /// When the agent last handed control back to the user
+    ready_at: Option<Instant>,
+    /// Set by `/brief` until turned off
+    pub brief_requested: bool,
+}
+
+impl SessionSignals {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Note that the agent is waiting for the user again
+    pub fn mark_ready(&mut self) {
+        self.ready_at = Some(Instant::now());
+    }
+
+    /// Record a user message, timing the reply from the last `mark_ready`
+    pub fn record_input(&mut self, text: &str) {
+        let reply_time = self.ready_at.take().map(|t| t.elapsed());
+        self.record_input_with(text, reply_time);
+    }
+
+    /// Record a user message with a known reply time
+    pub fn record_input_with(&mut self, text: &str, reply_time: Option<Duration>) {
+        self.inputs
+            .push_back((reply_time, text.trim().chars().count()));
+        if self.inputs.len() > MAX_TRACKED_INPUTS {
+            self.inputs.pop_front();
+        }
+    }
+
+    /// Number of messages recorded
+    pub fn input_count(&self) -> usize {
+        self.inputs.len()
+    }
+}
+
+/// Heuristic load estimator
+#[derive(Debug, Clone, Copy)]
+pub struct CognitiveLoad {
+    /// Replies faster than this count as rapid-fire
+    pub rapid_reply: Duration,
+    /// Messages shorter than this count as short
+    pub short_input_chars: usize,
+    /// Number of recent messages considered
+    pub window: usize,
+}
+
+impl Default for CognitiveLoad {
+    fn default() -> Self {
+        Self {
+            rapid_reply: Duration::from_secs(20),
+            short_input_chars: 40,
+            window: 4,
+        }
+    }
+}
+
+impl CognitiveLoad {
+    /// Estimate load from the most recent messages. `/brief` always means
+    /// high load; fewer than two messages is not enough to tell.
+    pub fn estimate(&self, signals: &SessionSignals) -> LoadLevel {
+        if signals.brief_requested {
+            return LoadLevel::High;
+        }
+        let window = self.window.max(2);
+        let recent: Vec<_> = signals.inputs.iter().rev().take(window).collect();
+        if recent.len() < 2 {
+            return LoadLevel::Normal;
+        }
+
+        let n = recent.len();
+        let rapid = recent
+            .iter()
+            .filter(|(reply, _)| reply.is_some_and(|r| r < self.rapid_reply))
+            .count();
+        let short = recent
+            .iter()
+            .filter(|(_, len)| *len < self.short_input_chars)
+            .count();
+
+        if rapid * 2 > n && short * 2 > n {
+            LoadLevel::High
+        } else if rapid == 0 && short == 0 {
+            LoadLevel::Low
+        } else {
+            LoadLevel::Normal
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn signals(inputs: &[(u64, &str)]) -> SessionSignals {
+        let mut signals = SessionSignals::new();
+        for (secs, text) in inputs {
+            signals.record_input_with(text, Some(Duration::from_secs(*secs)));
+        }
+        signals
+    }
+
+    #[test]
+    fn test_cognitive_load_estimate() {
+        let load = CognitiveLoad::default();
+        assert_eq!(load.estimate(&SessionSignals::new()), LoadLevel::Normal);
+
+        let rushed = signals(&[(3, "fix it"), (5, "no, the other one"), (4, "go")]);
+        assert_eq!(load.estimate(&rushed), LoadLevel::High);
+
+        let long = "Please refactor the session store so that saves are atomic and add a test";
+        let relaxed = signals(&[(120, long), (300, long)]);
+        assert_eq!(load.estimate(&relaxed), LoadLevel::Low);
+
+        let mixed = signals(&[(3, "go"), (200, long)]);
+        assert_eq!(load.estimate(&mixed), LoadLevel::Normal);
+
+        // Only the latest window counts
+        let calmed = signals(&[(1, "a"), (1, "b"), (1, "c"), (90, long), (90, long)]);
+        let narrow = CognitiveLoad {
+            window: 2,
+            ..CognitiveLoad::default()
+        };
+        assert_eq!(narrow.estimate(&calmed), LoadLevel::Low);
+        assert_eq!(load.estimate(&calmed), LoadLevel::Normal);
+
+        let mut brief = SessionSignals::new();
+        brief.brief_requested = true;
+        assert_eq!(load.estimate(&brief), LoadLevel::High);
+        assert_eq!(LoadLevel::High.detail_level(), DetailLevel::Basic);
+    }
+
     #[test]
     fn test_detail_level_ordering() {
         assert!(DetailLevel::Minimal < DetailLevel::Basic);
@@ -19,10 +19,10 @@ use std::sync::Arc;
 use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use walkdir::WalkDir;
 
+use crate::analysis::chunking::CodeAwareChunker;
 use crate::token_count::estimate_content_tokens;
 use crate::vector_store::{
-    ChunkType, CodeChunker, CollectionScope, EmbeddingBackend, SearchFilter, SearchResult,
-    VectorStore,
+    ChunkType, CollectionScope, EmbeddingBackend, SearchFilter, SearchResult, VectorStore,
 };
 
 /// RAG configuration
@@ -346,8 +346,6 @@ pub struct RagEngine {
     config: RagConfig,
     /// Root directory
     root: PathBuf,
-    /// Code chunker
-    _chunker: CodeChunker,
     /// File watcher for incremental updates
     watcher: FileWatcher,
     /// Statistics
@@ -376,10 +374,11 @@ impl RagEngine {
         let watcher = FileWatcher::new(&root, config.clone());
 
         Self {
-            store: VectorStore::new(provider),
+            // ~4 chars per token
+            store: VectorStore::new(provider)
+                .with_chunker(CodeAwareChunker::new(config.max_chunk_tokens * 4)),
             config: config.clone(),
             root,
-            _chunker: CodeChunker::new(config.max_chunk_tokens * 4), // ~4 chars per token
             watcher,
             stats: RagStats::default(),
             indexed_files: HashMap::new(),
@@ -478,6 +477,9 @@ impl RagEngine {
             files_by_language: files_by_lang,
         };
 
+        // Later incremental updates only pick up changes since this build
+        self.watcher.scan_changes();
+
         Ok(self.stats.clone())
     }
 
@@ -538,6 +540,7 @@ impl RagEngine {
                     .as_secs(),
             );
             self.stats.total_files = self.indexed_files.len();
+            self.stats.total_chunks = self.indexed_files.values().map(|f| f.chunk_count).sum();
         }
 
         Ok(changes)
@@ -580,6 +583,22 @@ impl RagEngine {
         })
     }
 
+    /// Top `k` chunks for `query` above the score threshold, with overlapping
+    /// and near-identical chunks dropped. `k` is capped at `top_k`.
+    pub async fn search(&self, query: &str, k: usize) -> Result<Vec<SearchResult>> {
+        let filter = SearchFilter::new().with_min_score(self.config.min_score);
+        let results = self
+            .store
+            .search(&self.collection_name, query, k * 2, Some(&filter))
+            .await?;
+        Ok(self
+            .deduplicate_results(&results)
+            .into_iter()
+            .take(k)
+            .cloned()
+            .collect())
+    }
+
     /// Deduplicate similar results
     fn deduplicate_results<'a>(&self, results: &'a [SearchResult]) -> Vec<&'a SearchResult> {
         let mut deduped: Vec<&SearchResult> = Vec::new();
@@ -21,6 +21,9 @@ use std::time::{SystemTime, UNIX_EPOCH};
 /// Global limit for record vectors to prevent unbounded memory growth in long-running sessions.
 const MAX_ENTRIES: usize = 10_000;
 
+/// Explicit user feedback counts as this many automatic outcome signals.
+pub const FEEDBACK_WEIGHT: usize = 5;
+
 /// Outcome of a task or action
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Outcome {
@@ -205,18 +208,23 @@ impl PromptOptimizer {
 
     /// Record a prompt outcome
     pub fn record(&mut self, record: PromptRecord) {
+        self.record_weighted(record, 1);
+    }
+
+    /// Record a prompt outcome that counts as `weight` attempts in the task stats
+    pub fn record_weighted(&mut self, record: PromptRecord, weight: usize) {
         // Update task stats
         let stats = self.task_stats.entry(record.task_type.clone()).or_default();
         let old_total_quality = stats.avg_quality * stats.total_attempts as f32;
         let old_total_tokens = stats.avg_tokens * stats.total_attempts as f32;
-        stats.total_attempts += 1;
+        stats.total_attempts += weight;
         if record.outcome.is_positive() {
-            stats.successful += 1;
+            stats.successful += weight;
         }
-        stats.avg_quality =
-            (old_total_quality + record.quality_score) / stats.total_attempts as f32;
-        stats.avg_tokens =
-            (old_total_tokens + record.tokens_used as f32) / stats.total_attempts as f32;
+        stats.avg_quality = (old_total_quality + record.quality_score * weight as f32)
+            / stats.total_attempts as f32;
+        stats.avg_tokens = (old_total_tokens + record.tokens_used as f32 * weight as f32)
+            / stats.total_attempts as f32;
 
         // Trim task_stats if it grows too large
         if self.task_stats.len() > MAX_ENTRIES / 10 {
@@ -690,6 +698,28 @@ impl ToolSelectionLearner {
         }
     }
 
+    /// Apply an explicit judgement of a tool's outcome in `context`, counted
+    /// as `weight` automatic records. The stored record list is left alone.
+    pub fn record_feedback(&mut self, tool: &str, context: &str, outcome: Outcome, weight: usize) {
+        let stats = self.tool_stats.entry(tool.to_string()).or_default();
+        stats.usage_count += weight;
+        if outcome.is_positive() {
+            stats.success_count += weight;
+        } else {
+            stats.failure_count += weight;
+        }
+
+        // Same as `weight` moving-average updates in `record`
+        let keep = 0.8f32.powi(weight as i32);
+        let context_key = Self::normalize_context(context);
+        let tool_scores = self.context_tools.entry(context_key).or_default();
+        if let Some((_, score)) = tool_scores.iter_mut().find(|(t, _)| t == tool) {
+            *score = keep * *score + (1.0 - keep) * outcome.score();
+        } else {
+            tool_scores.push((tool.to_string(), outcome.score()));
+        }
+    }
+
     /// Normalize error message for pattern matching
     fn normalize_error(error: &str) -> String {
         // Extract first line and remove specific values
@@ -1482,6 +1512,30 @@ impl SelfImprovementEngine {
         }
     }
 
+    /// Record explicit user feedback on a step: the tools it ran and the task
+    /// prompt it served. Weighted by [`FEEDBACK_WEIGHT`] and applied even
+    /// when automatic learning is disabled, since the user asked for it.
+    pub fn record_feedback(
+        &self,
+        tools: &[String],
+        context: &str,
+        task_prompt: &str,
+        task_type: &str,
+        outcome: Outcome,
+    ) {
+        if let Ok(mut learner) = self.tool_learner.write() {
+            for tool in tools {
+                learner.record_feedback(tool, context, outcome, FEEDBACK_WEIGHT);
+            }
+        }
+        if let Ok(mut optimizer) = self.prompt_optimizer.write() {
+            optimizer.record_weighted(
+                PromptRecord::new(task_prompt.to_string(), task_type.to_string(), outcome),
+                FEEDBACK_WEIGHT,
+            );
+        }
+    }
+
     /// Get best tools for a context
     pub fn best_tools_for(&self, context: &str) -> Vec<(String, f32)> {
         if let Ok(learner) = self.tool_learner.read() {
@@ -1500,6 +1554,13 @@ impl SelfImprovementEngine {
         }
     }
 
+    /// Share of tasks of this type that succeeded, once a few have been seen
+    pub fn task_success_rate(&self, task_type: &str) -> Option<f32> {
+        let optimizer = self.prompt_optimizer.read().ok()?;
+        let stats = optimizer.get_task_stats(task_type)?;
+        (stats.total_attempts >= 3).then(|| stats.successful as f32 / stats.total_attempts as f32)
+    }
+
     /// Get prompt suggestions
     pub fn suggest_prompt_improvements(
         &self,
@@ -1801,6 +1862,39 @@ mod tests {
         assert!(!best.is_empty());
     }
 
+    #[test]
+    fn test_engine_feedback_outweighs_automatic_signal() {
+        let engine = SelfImprovementEngine::new();
+        for tool in ["file_edit", "file_write"] {
+            engine.record_tool(tool, "fix parser", Outcome::Success, 10, None);
+        }
+
+        // One "bad" verdict on file_edit overrides its automatic success
+        engine.record_feedback(
+            &["file_edit".to_string()],
+            "fix parser",
+            "fix parser",
+            "bug_fix",
+            Outcome::Failure,
+        );
+
+        let best = engine.best_tools_for("fix parser");
+        assert_eq!(best[0].0, "file_write");
+        let edit_score = best.iter().find(|(t, _)| t == "file_edit").unwrap().1;
+        assert!(edit_score < 0.5, "score was {}", edit_score);
+
+        let learner = engine.tool_learner.read().unwrap();
+        let stats = learner.get_tool_stats("file_edit").unwrap();
+        assert_eq!(stats.usage_count, 1 + FEEDBACK_WEIGHT);
+        assert_eq!(stats.failure_count, FEEDBACK_WEIGHT);
+        drop(learner);
+
+        let optimizer = engine.prompt_optimizer.read().unwrap();
+        let stats = optimizer.get_task_stats("bug_fix").unwrap();
+        assert_eq!(stats.total_attempts, FEEDBACK_WEIGHT);
+        assert_eq!(stats.successful, 0);
+    }
+
     #[test]
     fn test_error_record_new() {
         let record = ErrorRecord::new(
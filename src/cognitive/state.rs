@@ -524,6 +524,37 @@ impl EpisodicMemory {
             .collect()
     }
 
+    /// Up to `k` lessons that mention any of `paths`, most specific match
+    /// first, then most recent. Each lesson appears once even if it matches
+    /// several paths.
+    pub fn relevant_to<P: AsRef<Path>>(&self, paths: &[P], k: usize) -> Vec<String> {
+        let mut scored: Vec<(usize, usize, &Lesson)> = self
+            .lessons
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, lesson)| {
+                let text = format!(
+                    "{} {} {}",
+                    lesson.content,
+                    lesson.context,
+                    lesson.tags.join(" ")
+                );
+                let score = paths
+                    .iter()
+                    .map(|p| path_match_score(p.as_ref(), &text))
+                    .max()
+                    .unwrap_or(0);
+                (score > 0).then_some((score, idx, lesson))
+            })
+            .collect();
+        scored.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.cmp(&a.1)));
+        scored
+            .into_iter()
+            .take(k)
+            .map(|(_, _, l)| format!("[{:?}] {}", l.category, l.content))
+            .collect()
+    }
+
     /// Find relevant lessons for a context
     pub fn find_relevant(&self, context: &str) -> Vec<&Lesson> {
         let context_lower = context.to_lowercase();
@@ -572,6 +603,51 @@ impl EpisodicMemory {
     }
 }
 
+/// File names too common to identify a file without their parent directory.
+const GENERIC_FILE_NAMES: &[&str] = &[
+    "mod.rs",
+    "lib.rs",
+    "main.rs",
+    "__init__.py",
+    "index.ts",
+    "index.js",
+];
+
+/// Number of trailing components of `path` that appear in `text` as a
+/// `/`-joined suffix, e.g. 2 for `src/cognitive/state.rs` when the text only
+/// mentions `cognitive/state.rs`. Generic file names need their parent too.
+fn path_match_score(path: &Path, text: &str) -> usize {
+    let components: Vec<String> = path
+        .components()
+        .filter_map(|c| match c {
+            std::path::Component::Normal(s) => Some(s.to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .collect();
+    let Some(file_name) = components.last() else {
+        return 0;
+    };
+    let min = if GENERIC_FILE_NAMES.contains(&file_name.as_str()) {
+        2
+    } else {
+        1
+    };
+
+    let mut best = 0;
+    for len in 1..=components.len() {
+        let suffix = components[components.len() - len..].join("/");
+        if !text.contains(&suffix) {
+            break;
+        }
+        best = len;
+    }
+    if best >= min {
+        best
+    } else {
+        0
+    }
+}
+
 /// A lesson learned
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Lesson {
@@ -775,6 +851,36 @@ mod tests {
         assert!(relevant[0].content.contains("cargo check"));
     }
 
+    #[test]
+    fn test_episodic_memory_relevant_to_paths() {
+        let mut em = EpisodicMemory::new();
+        em.what_failed(
+            "file_edit",
+            "file_edit on src/api/mod.rs failed verification",
+        );
+        em.what_failed(
+            "file_edit",
+            "file_edit on src/parser.rs failed verification",
+        );
+        em.what_worked("cargo", "Always run cargo check");
+        em.what_worked("file_edit", "src/agent/mod.rs needs Agent::new updated too");
+        em.what_failed("cargo_test", "parser.rs tests are slow, filter them");
+
+        let relevant = em.relevant_to(&["./src/parser.rs", "/repo/src/parser.rs"], 5);
+        assert_eq!(relevant.len(), 2);
+        assert!(relevant[0].contains("src/parser.rs failed verification"));
+        assert!(relevant[1].contains("tests are slow"));
+
+        // mod.rs alone is too generic to match
+        let relevant = em.relevant_to(&["other/mod.rs"], 5);
+        assert!(relevant.is_empty());
+        let relevant = em.relevant_to(&["src/api/mod.rs"], 1);
+        assert_eq!(relevant.len(), 1);
+        assert!(relevant[0].contains("src/api/mod.rs"));
+
+        assert!(em.relevant_to::<&str>(&[], 5).is_empty());
+    }
+
     #[test]
     fn test_cognitive_state_summary() {
         let mut state = CognitiveState::new();
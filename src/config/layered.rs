@@ -0,0 +1,399 @@
+//! Layered configuration with per-field source tracking
+//!
+//! Precedence, lowest to highest:
+//! 1. Built-in defaults
+//! 2. User config (`~/.config/selfware/config.toml`)
+//! 3. Project config (`./.selfware.toml`, falling back to `./selfware.toml`),
+//!    or the file named by `--config` / `SELFWARE_CONFIG`
+//! 4. Environment variables (`SELFWARE_*`)
+//! 5. CLI flags (recorded by the caller after loading)
+//!
+//! File layers are deep-merged table by table, so a project config that only
+//! sets `[agent] max_iterations` keeps every other field from the user config.
+
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use super::Config;
+
+/// Project-local config file names, in lookup order.
+pub const PROJECT_CONFIG_FILES: &[&str] = &[".selfware.toml", "selfware.toml"];
+
+/// Where an effective config value came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigSource {
+    Default,
+    UserFile(PathBuf),
+    ProjectFile(PathBuf),
+    /// File passed explicitly via `--config` or `SELFWARE_CONFIG`
+    ExplicitFile(PathBuf),
+    Env(&'static str),
+    Keyring,
+    Cli,
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigSource::Default => write!(f, "default"),
+            ConfigSource::UserFile(p) => write!(f, "user: {}", p.display()),
+            ConfigSource::ProjectFile(p) => write!(f, "project: {}", p.display()),
+            ConfigSource::ExplicitFile(p) => write!(f, "file: {}", p.display()),
+            ConfigSource::Env(var) => write!(f, "env: {}", var),
+            ConfigSource::Keyring => write!(f, "keyring"),
+            ConfigSource::Cli => write!(f, "cli"),
+        }
+    }
+}
+
+impl ConfigSource {
+    fn file_path(&self) -> Option<&Path> {
+        match self {
+            ConfigSource::UserFile(p)
+            | ConfigSource::ProjectFile(p)
+            | ConfigSource::ExplicitFile(p) => Some(p),
+            _ => None,
+        }
+    }
+}
+
+/// Source annotation for every field that was set above the defaults,
+/// keyed by dotted path (e.g. `agent.max_iterations`).
+#[derive(Debug, Clone, Default)]
+pub struct ConfigSources {
+    fields: BTreeMap<String, ConfigSource>,
+}
+
+impl ConfigSources {
+    /// Record the source of a field, replacing any lower-precedence entry.
+    pub fn set(&mut self, key: impl Into<String>, source: ConfigSource) {
+        self.fields.insert(key.into(), source);
+    }
+
+    /// Source of a field, falling back to the nearest recorded parent table
+    /// and finally to [`ConfigSource::Default`].
+    pub fn get(&self, key: &str) -> &ConfigSource {
+        static DEFAULT: ConfigSource = ConfigSource::Default;
+        let mut current = key;
+        loop {
+            if let Some(source) = self.fields.get(current) {
+                return source;
+            }
+            match current.rfind('.') {
+                Some(idx) => current = &current[..idx],
+                None => return &DEFAULT,
+            }
+        }
+    }
+
+    /// Config files that contributed at least one field.
+    pub fn files(&self) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = Vec::new();
+        for source in self.fields.values() {
+            if let Some(path) = source.file_path() {
+                if !files.iter().any(|f| f == path) {
+                    files.push(path.to_path_buf());
+                }
+            }
+        }
+        files
+    }
+}
+
+impl Config {
+    /// Load configuration by deep-merging every layer in the precedence chain.
+    ///
+    /// `path` (or `SELFWARE_CONFIG`) replaces the project layer; the user
+    /// config still applies beneath it. Environment overrides are applied on
+    /// top, and CLI flags are left to the caller to record via
+    /// [`ConfigSources::set`].
+    pub fn layered(path: Option<&str>) -> Result<(Self, ConfigSources)> {
+        Self::layered_with(path, true)
+    }
+
+    /// [`Config::layered`] without the final validation, for
+    /// `selfware config --check` to report problems instead of failing on
+    /// them
+    pub fn layered_unchecked(path: Option<&str>) -> Result<(Self, ConfigSources)> {
+        Self::layered_with(path, false)
+    }
+
+    fn layered_with(path: Option<&str>, validate: bool) -> Result<(Self, ConfigSources)> {
+        let mut layers: Vec<(toml::Table, ConfigSource)> = Vec::new();
+
+        if let Some(user_path) = dirs::home_dir().map(|h| h.join(".config/selfware/config.toml")) {
+            if user_path.is_file() {
+                layers.push((read_layer(&user_path)?, ConfigSource::UserFile(user_path)));
+            }
+        }
+
+        let env_config_path = std::env::var("SELFWARE_CONFIG").ok();
+        match path.or(env_config_path.as_deref()) {
+            Some(p) => {
+                let p = PathBuf::from(p);
+                layers.push((read_layer(&p)?, ConfigSource::ExplicitFile(p)));
+            }
+            None => {
+                if let Some(project_path) = PROJECT_CONFIG_FILES
+                    .iter()
+                    .map(PathBuf::from)
+                    .find(|p| p.is_file())
+                {
+                    layers.push((
+                        read_layer(&project_path)?,
+                        ConfigSource::ProjectFile(project_path),
+                    ));
+                }
+            }
+        }
+
+        if layers.is_empty() {
+            eprintln!("No config file found, using defaults");
+        }
+
+        let (config, mut sources) = Self::merge_layers(layers)?;
+        let files: Vec<String> = sources
+            .files()
+            .iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect();
+        let config = config.finish_load(&files, &mut sources, validate)?;
+        Ok((config, sources))
+    }
+
+    /// Deep-merge parsed file layers over the built-in defaults.
+    pub(super) fn merge_layers(
+        layers: Vec<(toml::Table, ConfigSource)>,
+    ) -> Result<(Self, ConfigSources)> {
+        let mut merged = toml::Table::try_from(Config::default())
+            .context("Failed to serialize default config")?;
+        let mut sources = ConfigSources::default();
+
+        for (layer, source) in layers {
+            merge_table(&mut merged, layer, "", &source, &mut sources);
+        }
+
+        let config: Config = toml::Value::Table(merged)
+            .try_into()
+            .context("Failed to parse merged config")?;
+        Ok((config, sources))
+    }
+
+    /// Render the effective config as `key = value  # source` lines.
+    ///
+    /// Secrets are redacted; runtime-only fields that are not persisted
+    /// (such as the execution mode) are appended at the end.
+    pub fn annotated_lines(&self, sources: &ConfigSources) -> Vec<String> {
+        let mut leaves = Vec::new();
+        if let Ok(table) = toml::Table::try_from(self) {
+            flatten_table(&table, "", &mut leaves);
+        }
+        leaves.push((
+            "execution_mode".to_string(),
+            toml::Value::String(self.execution_mode.to_string()),
+        ));
+
+        leaves
+            .into_iter()
+            .map(|(key, value)| {
+                let rendered = if key == "api_key" || key.ends_with(".api_key") {
+                    "\"[REDACTED]\"".to_string()
+                } else {
+                    value.to_string()
+                };
+                format!("{} = {}  # {}", key, rendered, sources.get(&key))
+            })
+            .collect()
+    }
+}
+
+fn read_layer(path: &Path) -> Result<toml::Table> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config from {}", path.display()))?;
+    toml::from_str(&content).with_context(|| format!("Failed to parse config {}", path.display()))
+}
+
+fn join_key(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", prefix, key)
+    }
+}
+
+/// Merge `layer` into `base`: tables recurse, everything else (including
+/// arrays) replaces the lower layer's value wholesale.
+fn merge_table(
+    base: &mut toml::Table,
+    layer: toml::Table,
+    prefix: &str,
+    source: &ConfigSource,
+    sources: &mut ConfigSources,
+) {
+    for (key, value) in layer {
+        let path = join_key(prefix, &key);
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(existing)), toml::Value::Table(incoming)) => {
+                merge_table(existing, incoming, &path, source, sources);
+            }
+            (_, value) => {
+                record_leaves(&value, &path, source, sources);
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn record_leaves(
+    value: &toml::Value,
+    path: &str,
+    source: &ConfigSource,
+    sources: &mut ConfigSources,
+) {
+    match value {
+        toml::Value::Table(table) if !table.is_empty() => {
+            for (key, child) in table {
+                record_leaves(child, &join_key(path, key), source, sources);
+            }
+        }
+        _ => sources.set(path, source.clone()),
+    }
+}
+
+fn flatten_table(table: &toml::Table, prefix: &str, out: &mut Vec<(String, toml::Value)>) {
+    for (key, value) in table {
+        let path = join_key(prefix, key);
+        match value {
+            toml::Value::Table(child) if !child.is_empty() => flatten_table(child, &path, out),
+            other => out.push((path, other.clone())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layer(src: &str) -> toml::Table {
+        toml::from_str(src).unwrap()
+    }
+
+    #[test]
+    fn test_merge_without_layers_matches_defaults() {
+        let (config, sources) = Config::merge_layers(Vec::new()).unwrap();
+        let defaults = Config::default();
+        assert_eq!(config.endpoint, defaults.endpoint);
+        assert_eq!(config.agent.max_iterations, defaults.agent.max_iterations);
+        assert_eq!(sources.get("agent.max_iterations"), &ConfigSource::Default);
+    }
+
+    #[test]
+    fn test_project_overrides_user_field_by_field() {
+        let user = ConfigSource::UserFile(PathBuf::from("user.toml"));
+        let project = ConfigSource::ProjectFile(PathBuf::from(".selfware.toml"));
+        let (config, sources) = Config::merge_layers(vec![
+            (
+                layer("model = \"user-model\"\n[agent]\nmax_iterations = 7\nstreaming = false\n"),
+                user.clone(),
+            ),
+            (layer("[agent]\nmax_iterations = 42\n"), project.clone()),
+        ])
+        .unwrap();
+
+        assert_eq!(config.model, "user-model");
+        assert_eq!(config.agent.max_iterations, 42);
+        assert!(!config.agent.streaming);
+        // Untouched fields in a partially-specified table keep their defaults
+        assert_eq!(config.agent.step_timeout_secs, 300);
+
+        assert_eq!(sources.get("model"), &user);
+        assert_eq!(sources.get("agent.streaming"), &user);
+        assert_eq!(sources.get("agent.max_iterations"), &project);
+        assert_eq!(
+            sources.get("agent.step_timeout_secs"),
+            &ConfigSource::Default
+        );
+    }
+
+    #[test]
+    fn test_arrays_replace_instead_of_appending() {
+        let project = ConfigSource::ProjectFile(PathBuf::from(".selfware.toml"));
+        let (config, _) = Config::merge_layers(vec![(
+            layer("[safety]\nprotected_branches = [\"release\"]\n"),
+            project,
+        )])
+        .unwrap();
+        assert_eq!(
+            config.safety.protected_branches,
+            vec!["release".to_string()]
+        );
+        // Sibling fields in the same table survive the merge
+        assert!(!config.safety.denied_paths.is_empty());
+    }
+
+    #[test]
+    fn test_partial_nested_table_fills_from_defaults() {
+        let project = ConfigSource::ProjectFile(PathBuf::from(".selfware.toml"));
+        let (config, _) = Config::merge_layers(vec![(
+            layer("[resources.gpu]\ntemperature_threshold = 70\n"),
+            project,
+        )])
+        .unwrap();
+        assert_eq!(config.resources.gpu.temperature_threshold, 70);
+        assert_eq!(config.resources.gpu.monitor_interval_seconds, 5);
+    }
+
+    #[test]
+    fn test_new_table_records_every_leaf() {
+        let project = ConfigSource::ProjectFile(PathBuf::from(".selfware.toml"));
+        let (_, sources) = Config::merge_layers(vec![(
+            layer("[models.vision]\nendpoint = \"http://localhost:1/v1\"\nmodel = \"v\"\n"),
+            project.clone(),
+        )])
+        .unwrap();
+        assert_eq!(sources.get("models.vision.endpoint"), &project);
+        assert_eq!(sources.get("models.vision.model"), &project);
+        assert_eq!(sources.files(), vec![PathBuf::from(".selfware.toml")]);
+    }
+
+    #[test]
+    fn test_sources_get_falls_back_to_parent() {
+        let mut sources = ConfigSources::default();
+        sources.set("ui", ConfigSource::Cli);
+        assert_eq!(sources.get("ui.theme"), &ConfigSource::Cli);
+        assert_eq!(sources.get("agent.streaming"), &ConfigSource::Default);
+    }
+
+    #[test]
+    fn test_annotated_lines_redact_secrets_and_show_sources() {
+        let config = Config {
+            api_key: Some("sk-secret".into()),
+            ..Config::default()
+        };
+        let mut sources = ConfigSources::default();
+        sources.set("api_key", ConfigSource::Env("SELFWARE_API_KEY"));
+        sources.set("execution_mode", ConfigSource::Cli);
+
+        let lines = config.annotated_lines(&sources);
+        let text = lines.join("\n");
+        assert!(!text.contains("sk-secret"));
+        assert!(text.contains("api_key = \"[REDACTED]\"  # env: SELFWARE_API_KEY"));
+        assert!(text.contains("execution_mode = \"normal\"  # cli"));
+        assert!(text.contains("agent.max_iterations = 100  # default"));
+    }
+
+    #[test]
+    fn test_source_display() {
+        assert_eq!(ConfigSource::Default.to_string(), "default");
+        assert_eq!(
+            ConfigSource::ProjectFile(PathBuf::from(".selfware.toml")).to_string(),
+            "project: .selfware.toml"
+        );
+        assert_eq!(
+            ConfigSource::Env("SELFWARE_MODEL").to_string(),
+            "env: SELFWARE_MODEL"
+        );
+    }
+}
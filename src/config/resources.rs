@@ -11,6 +11,8 @@ pub struct ResourcesConfig {
     pub disk: DiskConfig,
     #[serde(default)]
     pub quotas: ResourceQuotas,
+    #[serde(default)]
+    pub pause: PauseConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -89,6 +91,34 @@ impl Default for ResourceQuotas {
     }
 }
 
+/// When the agent loop stops to wait out host resource pressure
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PauseConfig {
+    pub enabled: bool,
+    /// Fraction of RAM in use at which the agent pauses
+    pub memory_threshold: f32,
+    /// Fraction of total CPU in use at which the agent pauses. Unset by
+    /// default: a local model keeps the CPU busy without risking an OOM kill.
+    pub cpu_threshold: Option<f32>,
+    /// Seconds between pressure checks while paused
+    pub pause_secs: u64,
+    /// Give up waiting and carry on after this long; 0 waits indefinitely
+    pub max_pause_secs: u64,
+}
+
+impl Default for PauseConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            memory_threshold: 0.95,
+            cpu_threshold: None,
+            pause_secs: 30,
+            max_pause_secs: 600,
+        }
+    }
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -0,0 +1,739 @@
+//! Whole-config validation with every problem reported at once
+//!
+//! [`check`] walks a loaded [`Config`] and collects an [`Issue`] for each
+//! bad or suspicious value instead of stopping at the first. Errors make
+//! [`Config::validate`] (and so `Config::load`) fail with all of them
+//! listed; warnings are printed and the load goes ahead. Besides per-field
+//! range checks it covers invariants that span fields: the response limit
+//! against the model's context window, allowed paths against the
+//! filesystem, and `require_confirmation` against the tools that exist.
+//! `selfware config --check` runs the same pass without failing the load.
+
+use std::fmt;
+use std::path::Path;
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+
+use super::{is_local_endpoint, Config, NotificationKind};
+
+/// Largest `max_tokens` / `agent.token_budget` accepted
+const MAX_TOKEN_LIMIT: usize = 10_000_000;
+
+/// Share of the context window `max_tokens` may take before the prompt is
+/// considered squeezed
+const MAX_RESPONSE_SHARE: f64 = 0.75;
+
+/// Whether an issue stops the config from loading
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Suspicious but usable
+    Warning,
+    /// The config cannot be used as written
+    Error,
+}
+
+/// One problem found in the config
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Issue {
+    pub severity: Severity,
+    /// Dotted config key, e.g. `safety.require_confirmation`
+    pub key: String,
+    pub message: String,
+    /// What to change to fix it, when there is an obvious answer
+    pub suggestion: Option<String>,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, "\n    help: {}", suggestion)?;
+        }
+        Ok(())
+    }
+}
+
+/// Everything [`check`] found
+#[derive(Debug, Clone, Default)]
+pub struct ConfigReport {
+    pub issues: Vec<Issue>,
+}
+
+impl ConfigReport {
+    pub fn errors(&self) -> impl Iterator<Item = &Issue> {
+        self.issues.iter().filter(|i| i.severity == Severity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &Issue> {
+        self.issues
+            .iter()
+            .filter(|i| i.severity == Severity::Warning)
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.errors().next().is_some()
+    }
+
+    /// Fail with every error in one message
+    pub fn into_result(self) -> Result<()> {
+        let errors: Vec<&Issue> = self.errors().collect();
+        match errors.as_slice() {
+            [] => Ok(()),
+            [only] => bail!("Config error: {}", only),
+            many => {
+                let list: Vec<String> = many.iter().map(|i| format!("  - {}", i)).collect();
+                bail!(
+                    "Config error: {} problems found:\n{}",
+                    many.len(),
+                    list.join("\n")
+                )
+            }
+        }
+    }
+}
+
+/// Collects issues for one [`check`] run
+#[derive(Default)]
+struct Checker {
+    issues: Vec<Issue>,
+}
+
+impl Checker {
+    fn push(&mut self, severity: Severity, key: &str, message: String) -> &mut Issue {
+        self.issues.push(Issue {
+            severity,
+            key: key.to_string(),
+            message,
+            suggestion: None,
+        });
+        self.issues.last_mut().expect("just pushed")
+    }
+
+    fn error(&mut self, key: &str, message: impl Into<String>) -> &mut Issue {
+        self.push(Severity::Error, key, message.into())
+    }
+
+    fn warn(&mut self, key: &str, message: impl Into<String>) -> &mut Issue {
+        self.push(Severity::Warning, key, message.into())
+    }
+}
+
+impl Issue {
+    fn help(&mut self, suggestion: impl Into<String>) -> &mut Self {
+        self.suggestion = Some(suggestion.into());
+        self
+    }
+}
+
+/// Check `config` and report every problem found
+pub fn check(config: &Config) -> ConfigReport {
+    let mut c = Checker::default();
+    check_endpoint(&mut c, "endpoint", &config.endpoint);
+    check_model(&mut c, config);
+    check_agent(&mut c, config);
+    check_safety(&mut c, config);
+    check_patterns(&mut c, config);
+    check_misc(&mut c, config);
+    ConfigReport { issues: c.issues }
+}
+
+fn check_endpoint(c: &mut Checker, key: &str, endpoint: &str) {
+    if endpoint.is_empty() {
+        c.error(key, format!("{} must not be empty", key)).help(
+            "set it to your server's OpenAI-compatible base URL, e.g. http://localhost:8080/v1",
+        );
+        return;
+    }
+    let Some(after_scheme) = endpoint
+        .strip_prefix("https://")
+        .or_else(|| endpoint.strip_prefix("http://"))
+    else {
+        let help = match endpoint.split_once("://") {
+            Some((_, rest)) => format!("use http://{}", rest),
+            None => format!("use http://{}", endpoint),
+        };
+        c.error(
+            key,
+            format!(
+                "{} must start with http:// or https://, got: {}",
+                key, endpoint
+            ),
+        )
+        .help(help);
+        return;
+    };
+    if after_scheme.is_empty() || after_scheme.starts_with('/') {
+        c.error(key, format!("{} URL has no host: {}", key, endpoint));
+        return;
+    }
+    // Local HTTP is fine: most local LLM servers only speak HTTP
+    if endpoint.starts_with("http://") && !is_local_endpoint(endpoint) {
+        c.warn(
+            key,
+            format!(
+                "{} '{}' uses plain HTTP to a remote host; API keys and data will be transmitted unencrypted",
+                key, endpoint
+            ),
+        )
+        .help(format!("use https://{}", after_scheme));
+    }
+}
+
+fn check_model(c: &mut Checker, config: &Config) {
+    if config.model.trim().is_empty() {
+        c.error("model", "model name must not be empty");
+    }
+
+    let active = active_profile(config);
+    if config.max_tokens == 0 {
+        c.error("max_tokens", "max_tokens must be greater than 0")
+            .help("65536 suits most models");
+    } else if config.max_tokens > MAX_TOKEN_LIMIT {
+        c.error(
+            "max_tokens",
+            format!(
+                "max_tokens ({}) exceeds maximum allowed ({})",
+                config.max_tokens, MAX_TOKEN_LIMIT
+            ),
+        );
+    } else if let Some((id, profile)) = active {
+        let window = profile.context_length;
+        // A response limit at or past the window squeezes the prompt but
+        // requests still go out, so it is not worth refusing the load over
+        if config.max_tokens >= window {
+            c.warn(
+                "max_tokens",
+                format!(
+                    "max_tokens ({}) leaves no room for the prompt in the {} token context window of models.{}",
+                    config.max_tokens, window, id
+                ),
+            )
+            .help(format!(
+                "lower max_tokens to at most {}, or set models.{}.context_length if the window is larger",
+                (window as f64 * MAX_RESPONSE_SHARE) as usize,
+                id
+            ));
+        } else if config.max_tokens as f64 > window as f64 * MAX_RESPONSE_SHARE {
+            c.warn(
+                "max_tokens",
+                format!(
+                    "max_tokens ({}) takes over {:.0}% of the {} token context window of models.{}",
+                    config.max_tokens,
+                    MAX_RESPONSE_SHARE * 100.0,
+                    window,
+                    id
+                ),
+            )
+            .help("long conversations will be compressed early; consider a smaller max_tokens");
+        }
+    }
+
+    let mut ids: Vec<&String> = config.models.keys().collect();
+    ids.sort();
+    for id in ids {
+        let profile = &config.models[id];
+        // Already reported against max_tokens above
+        let mirrors_top_level = active.is_some_and(|(active_id, _)| active_id == id)
+            && profile.max_tokens == config.max_tokens;
+        check_endpoint(c, &format!("models.{}.endpoint", id), &profile.endpoint);
+        if profile.max_tokens >= profile.context_length && !mirrors_top_level {
+            c.warn(
+                &format!("models.{}.max_tokens", id),
+                format!(
+                    "models.{}.max_tokens ({}) should be below its context_length ({})",
+                    id, profile.max_tokens, profile.context_length
+                ),
+            )
+            .help(format!(
+                "set models.{}.max_tokens to at most {}",
+                id,
+                (profile.context_length as f64 * MAX_RESPONSE_SHARE) as usize
+            ));
+        }
+    }
+
+    if config.temperature < 0.0 {
+        c.error(
+            "temperature",
+            format!(
+                "temperature must be non-negative, got: {}",
+                config.temperature
+            ),
+        );
+    } else if config.temperature > 10.0 {
+        c.warn(
+            "temperature",
+            format!(
+                "temperature {} is unusually high (typical range 0.0-2.0)",
+                config.temperature
+            ),
+        );
+    }
+}
+
+/// The model profile serving `config.model`: one whose `model` matches,
+/// else `default`
+fn active_profile(config: &Config) -> Option<(&str, &super::ModelProfile)> {
+    let mut matching: Vec<(&String, &super::ModelProfile)> = config
+        .models
+        .iter()
+        .filter(|(_, p)| p.model == config.model)
+        .collect();
+    matching.sort_by(|a, b| a.0.cmp(b.0));
+    matching
+        .into_iter()
+        .next()
+        .or_else(|| config.models.get_key_value("default"))
+        .map(|(id, p)| (id.as_str(), p))
+}
+
+fn check_agent(c: &mut Checker, config: &Config) {
+    let agent = &config.agent;
+    if agent.max_iterations == 0 {
+        c.error(
+            "agent.max_iterations",
+            "agent.max_iterations must be greater than 0",
+        );
+    }
+    if agent.step_timeout_secs == 0 {
+        c.error(
+            "agent.step_timeout_secs",
+            "agent.step_timeout_secs must be greater than 0",
+        );
+    } else if agent.step_timeout_secs > 3600 {
+        c.warn(
+            "agent.step_timeout_secs",
+            format!(
+                "agent.step_timeout_secs ({}) exceeds 1 hour",
+                agent.step_timeout_secs
+            ),
+        );
+    }
+    if agent.token_budget == 0 {
+        c.error(
+            "agent.token_budget",
+            "agent.token_budget must be greater than 0",
+        );
+    } else if agent.token_budget > MAX_TOKEN_LIMIT {
+        c.error(
+            "agent.token_budget",
+            format!(
+                "agent.token_budget ({}) exceeds maximum allowed ({})",
+                agent.token_budget, MAX_TOKEN_LIMIT
+            ),
+        );
+    }
+    if !(0.0..=1.0).contains(&agent.tool_result_head_fraction) {
+        c.error(
+            "agent.tool_result_head_fraction",
+            format!(
+                "agent.tool_result_head_fraction must be between 0.0 and 1.0, got: {}",
+                agent.tool_result_head_fraction
+            ),
+        );
+    }
+    if let (Some(complete), Some(blocked)) = (&agent.completion_marker, &agent.blocked_marker) {
+        if !complete.trim().is_empty() && complete.trim() == blocked.trim() {
+            c.error(
+                "agent.blocked_marker",
+                format!(
+                    "agent.completion_marker and agent.blocked_marker must differ, both are '{}'",
+                    complete
+                ),
+            );
+        }
+    }
+}
+
+fn check_safety(c: &mut Checker, config: &Config) {
+    let safety = &config.safety;
+    if !(0.0..=1.0).contains(&safety.large_edit_ratio) {
+        c.error(
+            "safety.large_edit_ratio",
+            format!(
+                "safety.large_edit_ratio must be between 0.0 and 1.0, got: {}",
+                safety.large_edit_ratio
+            ),
+        );
+    }
+
+    for (key, patterns) in [
+        ("safety.allowed_paths", &safety.allowed_paths),
+        ("safety.denied_paths", &safety.denied_paths),
+    ] {
+        for pattern in patterns {
+            if let Err(e) = glob::Pattern::new(pattern) {
+                c.error(
+                    key,
+                    format!("{} entry '{}' is not a valid pattern: {}", key, pattern, e),
+                );
+                continue;
+            }
+            if key != "safety.allowed_paths" {
+                continue;
+            }
+            let base = literal_prefix(pattern);
+            if !base.is_empty() && !base.starts_with('~') && !Path::new(base).exists() {
+                c.warn(
+                    key,
+                    format!(
+                        "{} entry '{}' points at '{}', which does not exist",
+                        key, pattern, base
+                    ),
+                )
+                .help("create the directory or remove the entry");
+            }
+        }
+    }
+
+    let registry = crate::tools::ToolRegistry::new();
+    let known: Vec<&str> = registry.list().iter().map(|t| t.name()).collect();
+    for name in &safety.require_confirmation {
+        if known.contains(&name.as_str()) {
+            continue;
+        }
+        let key = "safety.require_confirmation";
+        match closest(name, &known) {
+            // A near miss is almost certainly a typo, and a typo here means
+            // the intended tool runs without asking
+            Some(close) => {
+                c.error(key, format!("{} entry '{}' is not a tool name", key, name))
+                    .help(format!("did you mean '{}'?", close));
+            }
+            None => {
+                c.warn(
+                    key,
+                    format!("{} entry '{}' is not a built-in tool", key, name),
+                )
+                .help(
+                    "it only takes effect if an MCP server or plugin provides a tool by that name",
+                );
+            }
+        }
+    }
+}
+
+fn check_patterns(c: &mut Checker, config: &Config) {
+    for pattern in &config.agent.deny_patterns {
+        if let Err(e) = regex::Regex::new(pattern) {
+            c.error(
+                "agent.deny_patterns",
+                format!(
+                    "agent.deny_patterns has an invalid regex '{}': {}",
+                    pattern, e
+                ),
+            );
+        }
+    }
+    for (key, patterns) in [
+        ("tools.allow", &config.tools.allow),
+        ("tools.deny", &config.tools.deny),
+    ] {
+        for pattern in patterns {
+            if let Err(e) = glob::Pattern::new(pattern) {
+                c.error(
+                    key,
+                    format!("{} entry '{}' is not a valid pattern: {}", key, pattern, e),
+                );
+            }
+        }
+    }
+    for pattern in &config.redaction.allow_patterns {
+        if let Err(e) = regex::Regex::new(pattern) {
+            c.error(
+                "redaction.allow_patterns",
+                format!(
+                    "redaction.allow_patterns has an invalid regex '{}': {}",
+                    pattern, e
+                ),
+            );
+        }
+    }
+}
+
+fn check_misc(c: &mut Checker, config: &Config) {
+    if let Some(source) = &config.encryption.key_source {
+        if source != "passphrase" && source != "keyring" {
+            c.error(
+                "encryption.key_source",
+                format!(
+                    "encryption.key_source must be \"passphrase\" or \"keyring\", got: {}",
+                    source
+                ),
+            );
+        }
+    }
+
+    if config.checkpoint.keep_last == Some(0) {
+        c.error(
+            "checkpoint.keep_last",
+            "checkpoint.keep_last must be at least 1",
+        )
+        .help("remove the setting to keep every checkpoint");
+    }
+    if let Err(e) = config.checkpoint.keep_for_duration() {
+        c.error("checkpoint.keep_for", format!("checkpoint.keep_for: {}", e));
+    }
+    if let Some(endpoint) = &config.telemetry.otlp_endpoint {
+        if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
+            c.error(
+                "telemetry.otlp_endpoint",
+                format!(
+                    "telemetry.otlp_endpoint must start with http:// or https://, got: {}",
+                    endpoint
+                ),
+            )
+            .help("the OTLP/HTTP collector usually listens on http://localhost:4318");
+        }
+    }
+
+    for (i, hook) in config.notifications.hooks.iter().enumerate() {
+        let key = format!("notifications.hooks[{}]", i);
+        match hook.kind {
+            NotificationKind::Webhook => match hook.url.as_deref() {
+                None => {
+                    c.error(&key, format!("{}: a webhook hook needs a url", key));
+                }
+                Some(url) if !url.starts_with("http://") && !url.starts_with("https://") => {
+                    c.error(
+                        &key,
+                        format!(
+                            "{}.url must start with http:// or https://, got: {}",
+                            key, url
+                        ),
+                    );
+                }
+                Some(_) => {}
+            },
+            NotificationKind::Command => {
+                if hook
+                    .command
+                    .as_deref()
+                    .is_none_or(|cmd| cmd.trim().is_empty())
+                {
+                    c.error(&key, format!("{}: a command hook needs a command", key));
+                }
+            }
+            NotificationKind::Desktop => {}
+        }
+    }
+
+    let pause = &config.resources.pause;
+    for (key, value) in [
+        (
+            "resources.pause.memory_threshold",
+            Some(pause.memory_threshold),
+        ),
+        ("resources.pause.cpu_threshold", pause.cpu_threshold),
+    ] {
+        if let Some(value) = value {
+            if !(value > 0.0 && value <= 1.0) {
+                c.error(
+                    key,
+                    format!("{} must be in (0.0, 1.0], got: {}", key, value),
+                );
+            }
+        }
+    }
+    if pause.enabled && pause.pause_secs == 0 {
+        c.error(
+            "resources.pause.pause_secs",
+            "resources.pause.pause_secs must be greater than 0",
+        );
+    }
+
+    if config.retry.base_delay_ms > config.retry.max_delay_ms {
+        c.error(
+            "retry.base_delay_ms",
+            format!(
+                "retry.base_delay_ms ({}) must not exceed retry.max_delay_ms ({})",
+                config.retry.base_delay_ms, config.retry.max_delay_ms
+            ),
+        );
+    }
+
+    if config.ui.animation_speed <= 0.0 {
+        c.error(
+            "ui.animation_speed",
+            format!(
+                "ui.animation_speed must be positive, got: {}",
+                config.ui.animation_speed
+            ),
+        );
+    } else if config.ui.animation_speed > 100.0 {
+        c.warn(
+            "ui.animation_speed",
+            format!(
+                "ui.animation_speed {} is unusually high",
+                config.ui.animation_speed
+            ),
+        );
+    }
+
+    if !(config.ui.token_hog_share > 0.0 && config.ui.token_hog_share <= 1.0) {
+        c.error(
+            "ui.token_hog_share",
+            format!(
+                "ui.token_hog_share must be in (0, 1], got: {}",
+                config.ui.token_hog_share
+            ),
+        );
+    }
+
+    if let Some(key) = &config.api_key {
+        if key.expose().is_empty() {
+            c.warn("api_key", "api_key is set but empty")
+                .help("remove it, or set SELFWARE_API_KEY instead");
+        }
+    }
+}
+
+/// The part of a glob pattern before its first wildcard, trimmed back to
+/// a whole path component
+fn literal_prefix(pattern: &str) -> &str {
+    let Some(wild) = pattern.find(['*', '?', '[', '{']) else {
+        return pattern;
+    };
+    match pattern[..wild].rfind('/') {
+        Some(0) => "/",
+        Some(slash) => &pattern[..slash],
+        None => "",
+    }
+}
+
+/// The candidate within edit distance 2 of `name`, if any
+fn closest<'a>(name: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    candidates
+        .iter()
+        .map(|c| (edit_distance(name, c), *c))
+        .filter(|(d, _)| *d <= 2)
+        .min()
+        .map(|(_, c)| c)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitute = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitute.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ModelProfile;
+
+    fn profile(model: &str, max_tokens: usize, context_length: usize) -> ModelProfile {
+        ModelProfile {
+            endpoint: "http://localhost:8080/v1".into(),
+            model: model.into(),
+            api_key: None,
+            max_tokens,
+            temperature: 0.7,
+            modalities: vec!["text".into()],
+            context_length,
+        }
+    }
+
+    #[test]
+    fn test_check_reports_every_problem_with_suggestions() {
+        let mut config = Config {
+            endpoint: "ftp://localhost:8080/v1".into(),
+            model: "coder".into(),
+            max_tokens: 40_000,
+            ..Config::default()
+        };
+        config.agent.max_iterations = 0;
+        config.safety.require_confirmation =
+            vec!["git_push".into(), "shel_exec".into(), "deploy".into()];
+        config.safety.allowed_paths = vec!["./**".into(), "/no/such/selfware-dir/**".into()];
+        config
+            .models
+            .insert("coder".into(), profile("coder", 8_192, 32_768));
+
+        let report = check(&config);
+        let errors: Vec<&str> = report.errors().map(|i| i.key.as_str()).collect();
+        assert_eq!(
+            errors,
+            [
+                "endpoint",
+                "agent.max_iterations",
+                "safety.require_confirmation"
+            ],
+            "{:?}",
+            report.issues
+        );
+        let typo = report
+            .errors()
+            .find(|i| i.key == "safety.require_confirmation")
+            .unwrap();
+        assert_eq!(
+            typo.suggestion.as_deref(),
+            Some("did you mean 'shell_exec'?")
+        );
+        let window = report.warnings().find(|i| i.key == "max_tokens").unwrap();
+        assert!(window.message.contains("32768"), "{}", window);
+        assert!(window.suggestion.as_deref().unwrap().contains("24576"));
+
+        let warnings: Vec<String> = report.warnings().map(|i| i.message.clone()).collect();
+        assert_eq!(warnings.len(), 3, "{:?}", warnings);
+        assert!(warnings.iter().any(|w| w.contains("'deploy'")));
+        assert!(warnings.iter().any(|w| w.contains("/no/such/selfware-dir")));
+
+        let err = report.into_result().unwrap_err().to_string();
+        assert!(
+            err.starts_with("Config error: 3 problems found:"),
+            "{}",
+            err
+        );
+        assert!(
+            err.contains("help: use http://localhost:8080/v1"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_check_warns_when_response_crowds_the_window() {
+        let mut config = Config {
+            model: "coder".into(),
+            max_tokens: 30_000,
+            ..Config::default()
+        };
+        config
+            .models
+            .insert("default".into(), profile("other", 4_096, 32_768));
+        let report = check(&config);
+        assert!(!report.has_errors(), "{:?}", report.issues);
+        assert_eq!(report.warnings().next().unwrap().key, "max_tokens");
+
+        // Without a profile the window is unknown
+        config.models.clear();
+        assert!(check(&config).issues.is_empty());
+        assert!(check(&Config::default()).into_result().is_ok());
+    }
+
+    #[test]
+    fn test_literal_prefix_and_closest() {
+        assert_eq!(literal_prefix("./**"), ".");
+        assert_eq!(literal_prefix("/**"), "/");
+        assert_eq!(literal_prefix("/srv/app/src/**/*.rs"), "/srv/app/src");
+        assert_eq!(literal_prefix("**/*.rs"), "");
+        assert_eq!(literal_prefix("/etc/hosts"), "/etc/hosts");
+        assert_eq!(
+            closest("git_psh", &["git_push", "git_pull"]),
+            Some("git_push")
+        );
+        assert_eq!(closest("deploy", &["git_push"]), None);
+    }
+}
@@ -3,6 +3,8 @@
 //! This module contains infrastructure and DevOps functionality including:
 //! - Container management
 //! - Process management
+//! - Task worktrees
 
 pub mod container;
 pub mod process_manager;
+pub mod worktree;
@@ -0,0 +1,292 @@
+//! Task Worktrees - Isolated Checkouts for Risky Runs
+//!
+//! `selfware run --worktree` runs a task in a fresh `git worktree` on a new
+//! branch made from the current HEAD, so its edits and commits never touch
+//! the user's working tree. Afterwards the work can be merged or
+//! cherry-picked back, kept for inspection, or discarded with its branch.
+//!
+//! The agent's tools resolve paths against the process working directory,
+//! so [`TaskWorktree::enter`] changes it for the duration of the run.
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tracing::{info, warn};
+
+/// Longest task-derived part of a worktree branch name
+const MAX_SLUG_LEN: usize = 40;
+
+/// Prefix of branches created for task worktrees
+pub const BRANCH_PREFIX: &str = "selfware/";
+
+/// A git worktree a task runs in
+#[derive(Debug)]
+pub struct TaskWorktree {
+    /// Top level of the repository the worktree was made from
+    repo_root: PathBuf,
+    /// Where the worktree is checked out
+    path: PathBuf,
+    branch: String,
+    /// Commit the branch started from
+    base: String,
+    /// Working directory to restore on [`leave`](Self::leave)
+    original_dir: Option<PathBuf>,
+}
+
+/// How to bring worktree commits back into the original checkout
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// `git merge` the worktree branch
+    Merge,
+    /// Replay the worktree's commits onto the current branch
+    CherryPick,
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Branch-safe slug of the first words of a task
+fn slug(label: &str) -> String {
+    let mut slug = String::new();
+    for c in label.chars() {
+        if slug.len() >= MAX_SLUG_LEN {
+            break;
+        }
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        "task".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+impl TaskWorktree {
+    /// Create a worktree of HEAD for the repository containing `dir`, on a
+    /// new branch named after `label`
+    pub fn create(dir: &Path, label: &str) -> Result<Self> {
+        let repo_root = PathBuf::from(
+            git(dir, &["rev-parse", "--show-toplevel"])
+                .context("--worktree needs to run inside a git repository")?,
+        );
+        let base = git(&repo_root, &["rev-parse", "HEAD"])
+            .context("--worktree needs at least one commit to branch from")?;
+        let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+        let name = format!("{}-{}", slug(label), stamp);
+        let branch = format!("{}{}", BRANCH_PREFIX, name);
+        let repo_name = repo_root
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "repo".to_string());
+        let path = std::env::temp_dir()
+            .join("selfware-worktrees")
+            .join(format!("{}-{}", repo_name, name));
+
+        let path_str = path.to_string_lossy();
+        git(
+            &repo_root,
+            &["worktree", "add", "-b", &branch, &path_str, &base],
+        )?;
+        info!("Created worktree {} on {}", path.display(), branch);
+        Ok(Self {
+            repo_root,
+            path,
+            branch,
+            base,
+            original_dir: None,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn branch(&self) -> &str {
+        &self.branch
+    }
+
+    pub fn repo_root(&self) -> &Path {
+        &self.repo_root
+    }
+
+    /// Make the worktree the working directory, keeping the current
+    /// subdirectory of the repository
+    pub fn enter(&mut self) -> Result<()> {
+        let cwd = std::env::current_dir()?;
+        let target = cwd
+            .strip_prefix(&self.repo_root)
+            .map(|rel| self.path.join(rel))
+            .unwrap_or_else(|_| self.path.clone());
+        std::env::set_current_dir(&target)
+            .with_context(|| format!("Failed to enter worktree {}", target.display()))?;
+        self.original_dir = Some(cwd);
+        Ok(())
+    }
+
+    /// Go back to the directory [`enter`](Self::enter) was called from
+    pub fn leave(&mut self) {
+        if let Some(dir) = self.original_dir.take() {
+            if let Err(e) = std::env::set_current_dir(&dir) {
+                warn!("Failed to return to {}: {}", dir.display(), e);
+            }
+        }
+    }
+
+    /// Commit anything the task left uncommitted, so it travels with the
+    /// branch. Returns whether a commit was made.
+    pub fn commit_pending(&self, message: &str) -> Result<bool> {
+        if git(&self.path, &["status", "--porcelain"])?.is_empty() {
+            return Ok(false);
+        }
+        git(&self.path, &["add", "-A"])?;
+        git(&self.path, &["commit", "-q", "-m", message])?;
+        Ok(true)
+    }
+
+    /// One-line summaries of the commits made in the worktree, oldest first
+    pub fn commits(&self) -> Result<Vec<String>> {
+        let range = format!("{}..{}", self.base, self.branch);
+        Ok(git(
+            &self.repo_root,
+            &["log", "--reverse", "--format=%h %s", &range],
+        )?
+        .lines()
+        .map(str::to_string)
+        .collect())
+    }
+
+    /// Bring the worktree's commits into the original checkout. A conflict
+    /// is backed out, leaving the worktree in place to resolve by hand.
+    pub fn merge_back(&self, strategy: MergeStrategy) -> Result<()> {
+        let (args, abort): (Vec<String>, &str) = match strategy {
+            MergeStrategy::Merge => (
+                vec!["merge".into(), "--no-edit".into(), self.branch.clone()],
+                "merge",
+            ),
+            MergeStrategy::CherryPick => (
+                vec![
+                    "cherry-pick".into(),
+                    format!("{}..{}", self.base, self.branch),
+                ],
+                "cherry-pick",
+            ),
+        };
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        if let Err(e) = git(&self.repo_root, &args) {
+            let _ = git(&self.repo_root, &[abort, "--abort"]);
+            return Err(e.context(format!(
+                "Could not {} {}; the worktree is kept at {}",
+                abort,
+                self.branch,
+                self.path.display()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Remove the worktree and delete its branch
+    pub fn discard(mut self) -> Result<()> {
+        self.leave();
+        let path = self.path.to_string_lossy().into_owned();
+        git(&self.repo_root, &["worktree", "remove", "--force", &path])?;
+        git(&self.repo_root, &["branch", "-D", &self.branch])?;
+        info!("Removed worktree {} and {}", path, self.branch);
+        Ok(())
+    }
+}
+
+impl Drop for TaskWorktree {
+    fn drop(&mut self) {
+        self.leave();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn init_repo(dir: &Path) {
+        for args in [
+            &["init", "-q"][..],
+            &["config", "user.name", "dev"],
+            &["config", "user.email", "dev@example.com"],
+        ] {
+            git(dir, args).unwrap();
+        }
+        std::fs::write(dir.join("lib.rs"), "fn a() {}\n").unwrap();
+        git(dir, &["add", "-A"]).unwrap();
+        git(dir, &["commit", "-q", "-m", "Initial"]).unwrap();
+    }
+
+    #[test]
+    fn test_slug() {
+        assert_eq!(
+            slug("Fix the parser's error messages!"),
+            "fix-the-parser-s-error-messages"
+        );
+        assert_eq!(slug("???"), "task");
+        assert!(slug(&"word ".repeat(20)).len() <= MAX_SLUG_LEN);
+    }
+
+    #[test]
+    fn test_worktree_cherry_pick_back_and_discard() {
+        let dir = tempfile::tempdir().unwrap();
+        init_repo(dir.path());
+
+        let worktree = TaskWorktree::create(dir.path(), "Fix lib").unwrap();
+        assert!(worktree.branch().starts_with("selfware/fix-lib-"));
+        assert!(worktree.path().join("lib.rs").exists());
+
+        std::fs::write(worktree.path().join("lib.rs"), "fn a() { 1 }\n").unwrap();
+        // The original checkout is untouched
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("lib.rs")).unwrap(),
+            "fn a() {}\n"
+        );
+        assert!(worktree.commit_pending("selfware: Fix lib").unwrap());
+        assert!(!worktree.commit_pending("nothing left").unwrap());
+        let commits = worktree.commits().unwrap();
+        assert_eq!(commits.len(), 1);
+        assert!(commits[0].ends_with("selfware: Fix lib"));
+
+        worktree.merge_back(MergeStrategy::CherryPick).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("lib.rs")).unwrap(),
+            "fn a() { 1 }\n"
+        );
+
+        let path = worktree.path().to_path_buf();
+        let branch = worktree.branch().to_string();
+        worktree.discard().unwrap();
+        assert!(!path.exists());
+        assert!(git(dir.path(), &["branch", "--list", &branch])
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_create_outside_repo_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let err = TaskWorktree::create(dir.path(), "task").unwrap_err();
+        assert!(format!("{:#}", err).contains("inside a git repository"));
+    }
+}
@@ -81,6 +81,40 @@ pub enum ApiError {
 
     #[error("Model not found: {0}")]
     ModelNotFound(String),
+
+    #[error("Request exceeds the model's context window: {0}")]
+    ContextLengthExceeded(String),
+}
+
+/// Phrases backends use when a request does not fit the context window
+/// (OpenAI, vLLM, SGLang, llama.cpp, Ollama, Anthropic-style gateways).
+const CONTEXT_OVERFLOW_PHRASES: &[&str] = &[
+    "context_length_exceeded",
+    "maximum context length",
+    "exceeds the available context size",
+    "exceed_context_size",
+    "longer than the model's context length",
+    "exceeds the context length",
+    "exceeds the context window",
+    "prompt is too long",
+    "input is too long",
+];
+
+impl ApiError {
+    /// Error for a non-success HTTP response, recognising context window
+    /// overflows by their message so callers can shrink the request.
+    pub fn from_status(status: u16, message: String) -> Self {
+        if Self::is_context_overflow_message(&message) {
+            Self::ContextLengthExceeded(message)
+        } else {
+            Self::HttpStatus { status, message }
+        }
+    }
+
+    pub fn is_context_overflow_message(message: &str) -> bool {
+        let message = message.to_lowercase();
+        CONTEXT_OVERFLOW_PHRASES.iter().any(|p| message.contains(p))
+    }
 }
 
 #[derive(Error, Debug)]
@@ -94,8 +128,14 @@ pub enum ToolError {
     #[error("Invalid arguments for tool '{name}': {message}")]
     InvalidArguments { name: String, message: String },
 
+    #[error("Tool '{name}' returned output that does not match its output schema: {message}")]
+    InvalidOutput { name: String, message: String },
+
     #[error("Tool execution timed out")]
     Timeout,
+
+    #[error("Tool execution cancelled")]
+    Cancelled,
 }
 
 #[derive(Error, Debug)]
@@ -150,6 +190,17 @@ pub fn is_confirmation_error(e: &anyhow::Error) -> bool {
     false
 }
 
+/// Check if an anyhow error is a request that overflowed the context window
+pub fn is_context_overflow_error(e: &anyhow::Error) -> bool {
+    matches!(
+        e.downcast_ref::<ApiError>(),
+        Some(ApiError::ContextLengthExceeded(_))
+    ) || matches!(
+        e.downcast_ref::<SelfwareError>(),
+        Some(SelfwareError::Api(ApiError::ContextLengthExceeded(_)))
+    )
+}
+
 #[derive(Error, Debug)]
 pub enum ResourceError {
     #[error("Memory exhausted: {0}")]
@@ -538,4 +589,43 @@ mod tests {
         assert_eq!(EXIT_SAFETY_ERROR, 5);
         assert_eq!(EXIT_CONFIRMATION_REQUIRED, 6);
     }
+
+    // =========================================================================
+    // context overflow classification
+    // =========================================================================
+
+    /// Recorded 400 body from vLLM's OpenAI-compatible server
+    const VLLM_OVERFLOW_BODY: &str = r#"{"object":"error","message":"This model's maximum context length is 32768 tokens. However, you requested 33871 tokens (25679 in the messages, 8192 in the completion). Please reduce the length of the messages or completion.","type":"BadRequestError","param":null,"code":400}"#;
+
+    /// Recorded 400 body from llama.cpp's server
+    const LLAMA_CPP_OVERFLOW_BODY: &str = r#"{"error":{"code":400,"message":"the request exceeds the available context size, try increasing it","type":"exceed_context_size_error","n_prompt_tokens":40123,"n_ctx":32768}}"#;
+
+    #[test]
+    fn test_from_status_recognises_context_overflow() {
+        for body in [VLLM_OVERFLOW_BODY, LLAMA_CPP_OVERFLOW_BODY] {
+            let err = ApiError::from_status(400, body.to_string());
+            assert!(
+                matches!(err, ApiError::ContextLengthExceeded(_)),
+                "{}",
+                body
+            );
+            assert!(is_context_overflow_error(&err.into()));
+        }
+
+        let err = ApiError::from_status(400, r#"{"error":"invalid tool schema"}"#.to_string());
+        assert!(matches!(err, ApiError::HttpStatus { status: 400, .. }));
+        assert!(!is_context_overflow_error(&err.into()));
+    }
+
+    #[test]
+    fn test_context_overflow_survives_context_and_wrapping() {
+        let err: anyhow::Error =
+            SelfwareError::Api(ApiError::ContextLengthExceeded("too long".into())).into();
+        assert!(is_context_overflow_error(&err));
+
+        let err = anyhow::Error::from(ApiError::ContextLengthExceeded("too long".into()))
+            .context("Non-streaming fallback request also failed");
+        assert!(is_context_overflow_error(&err));
+        assert_eq!(get_exit_code(&err), EXIT_API_ERROR);
+    }
 }
@@ -58,6 +58,16 @@ pub static COMMANDS: &[CommandEntry] = &[
         description: "Show token usage and cost estimate",
         category: CommandCategory::General,
     },
+    CommandEntry {
+        name: "/feedback",
+        description: "Mark the last step good or bad to correct what the agent learned",
+        category: CommandCategory::General,
+    },
+    CommandEntry {
+        name: "/reset-circuit",
+        description: "Close the API circuit breaker without waiting for cooldown",
+        category: CommandCategory::General,
+    },
     CommandEntry {
         name: "/model",
         description: "Show or switch the current model",
@@ -65,7 +75,7 @@ pub static COMMANDS: &[CommandEntry] = &[
     },
     CommandEntry {
         name: "/garden",
-        description: "Show the garden status",
+        description: "Explore files as a garden; Enter inspects, a analyzes",
         category: CommandCategory::General,
     },
     CommandEntry {
@@ -109,11 +119,41 @@ pub static COMMANDS: &[CommandEntry] = &[
         description: "Show context window usage (alias for /ctx)",
         category: CommandCategory::Context,
     },
+    CommandEntry {
+        name: "/reload",
+        description: "Re-read project guidance files (AGENTS.md, CLAUDE.md)",
+        category: CommandCategory::Context,
+    },
     CommandEntry {
         name: "/compress",
         description: "Compress context to free token budget",
         category: CommandCategory::Context,
     },
+    CommandEntry {
+        name: "/compact",
+        description: "Pin decisions and constraints, then compress context",
+        category: CommandCategory::Context,
+    },
+    CommandEntry {
+        name: "/compact show",
+        description: "Show the pinned decisions note",
+        category: CommandCategory::Context,
+    },
+    CommandEntry {
+        name: "/compact edit",
+        description: "Edit the pinned decisions note in $EDITOR",
+        category: CommandCategory::Context,
+    },
+    CommandEntry {
+        name: "/focus",
+        description: "Scope edits and retrieval to paths (/focus <paths...>)",
+        category: CommandCategory::Context,
+    },
+    CommandEntry {
+        name: "/focus clear",
+        description: "Lift the focus so any file may be edited again",
+        category: CommandCategory::Context,
+    },
     CommandEntry {
         name: "/memory",
         description: "Show memory hierarchy status",
@@ -121,7 +161,7 @@ pub static COMMANDS: &[CommandEntry] = &[
     },
     // Display
     CommandEntry {
-        name: "/compact",
+        name: "/compact output",
         description: "Switch to compact output mode",
         category: CommandCategory::Display,
     },
@@ -130,6 +170,11 @@ pub static COMMANDS: &[CommandEntry] = &[
         description: "Switch to verbose output mode",
         category: CommandCategory::Display,
     },
+    CommandEntry {
+        name: "/brief",
+        description: "Keep replies short until /brief off",
+        category: CommandCategory::Display,
+    },
     CommandEntry {
         name: "/clear",
         description: "Clear the screen",
@@ -146,6 +191,11 @@ pub static COMMANDS: &[CommandEntry] = &[
         description: "Show details of the last tool execution",
         category: CommandCategory::Tools,
     },
+    CommandEntry {
+        name: "/explain",
+        description: "Explain why the agent took its last step",
+        category: CommandCategory::Tools,
+    },
     CommandEntry {
         name: "/tools",
         description: "List available tools",
@@ -161,11 +211,21 @@ pub static COMMANDS: &[CommandEntry] = &[
         description: "Review recent changes",
         category: CommandCategory::Tools,
     },
+    CommandEntry {
+        name: "/retry",
+        description: "Retry the last failed step, optionally with a hint",
+        category: CommandCategory::Tools,
+    },
     CommandEntry {
         name: "/plan",
         description: "Create an execution plan",
         category: CommandCategory::Tools,
     },
+    CommandEntry {
+        name: "/plan export",
+        description: "Write the current plan as Markdown or Mermaid",
+        category: CommandCategory::Tools,
+    },
     CommandEntry {
         name: "/swarm",
         description: "Launch multi-agent swarm",
@@ -207,6 +267,11 @@ pub static COMMANDS: &[CommandEntry] = &[
         description: "Undo the last file edit",
         category: CommandCategory::Git,
     },
+    CommandEntry {
+        name: "/rewind",
+        description: "Take back the last turn: its messages and file edits",
+        category: CommandCategory::Git,
+    },
     // Session
     CommandEntry {
         name: "/copy",
@@ -218,6 +283,16 @@ pub static COMMANDS: &[CommandEntry] = &[
         description: "Restore from checkpoint",
         category: CommandCategory::Session,
     },
+    CommandEntry {
+        name: "/checkpoint",
+        description: "Save a labelled checkpoint now",
+        category: CommandCategory::Session,
+    },
+    CommandEntry {
+        name: "/fork",
+        description: "Fork a journal checkpoint into a new task",
+        category: CommandCategory::Session,
+    },
     CommandEntry {
         name: "/chat",
         description: "Chat session management",
@@ -243,6 +318,41 @@ pub static COMMANDS: &[CommandEntry] = &[
         description: "Delete a saved chat session",
         category: CommandCategory::Session,
     },
+    CommandEntry {
+        name: "/save",
+        description: "Save messages, context files and mode as a snapshot",
+        category: CommandCategory::Session,
+    },
+    CommandEntry {
+        name: "/load",
+        description: "Replace the current context with a saved snapshot",
+        category: CommandCategory::Session,
+    },
+    CommandEntry {
+        name: "/saved",
+        description: "List saved context snapshots",
+        category: CommandCategory::Session,
+    },
+    CommandEntry {
+        name: "/image",
+        description: "Attach an image (path or URL) to your next message",
+        category: CommandCategory::Context,
+    },
+    CommandEntry {
+        name: "/compare",
+        description: "Run a prompt (or --full task) on two models side by side",
+        category: CommandCategory::General,
+    },
+    CommandEntry {
+        name: "/tokens",
+        description: "Show which messages and files use the most context tokens",
+        category: CommandCategory::Context,
+    },
+    CommandEntry {
+        name: "/wellness",
+        description: "Show session time or set break reminders (on, off, minutes)",
+        category: CommandCategory::General,
+    },
     CommandEntry {
         name: "/vim",
         description: "Switch to vim input mode",
@@ -346,14 +456,17 @@ mod tests {
             "/compress",
             "/memory",
             "/compact",
+            "/focus",
             "/verbose",
             "/clear",
             "/theme",
             "/last",
+            "/explain",
             "/tools",
             "/analyze",
             "/review",
             "/plan",
+            "/plan export",
             "/swarm",
             "/queue",
             "/queue list",
@@ -362,6 +475,7 @@ mod tests {
             "/diff",
             "/git",
             "/undo",
+            "/rewind",
             "/copy",
             "/restore",
             "/chat",
@@ -369,6 +483,13 @@ mod tests {
             "/chat resume",
             "/chat list",
             "/chat delete",
+            "/save",
+            "/load",
+            "/saved",
+            "/image",
+            "/wellness",
+            "/compare",
+            "/tokens",
             "/vim",
         ];
         let registry: HashSet<&str> = COMMANDS.iter().map(|c| c.name).collect();
@@ -771,7 +892,7 @@ mod tests {
 
     #[test]
     fn test_git_commands_are_in_git_category() {
-        let git_commands = ["/diff", "/git", "/undo"];
+        let git_commands = ["/diff", "/git", "/undo", "/rewind"];
         let registry: std::collections::HashMap<&str, CommandCategory> =
             COMMANDS.iter().map(|c| (c.name, c.category)).collect();
 
@@ -789,7 +910,7 @@ mod tests {
 
     #[test]
     fn test_display_commands_are_in_display_category() {
-        let display_commands = ["/compact", "/verbose", "/clear", "/theme"];
+        let display_commands = ["/compact output", "/verbose", "/clear", "/theme"];
         let registry: std::collections::HashMap<&str, CommandCategory> =
             COMMANDS.iter().map(|c| (c.name, c.category)).collect();
 
@@ -835,10 +956,12 @@ mod tests {
     fn test_tools_commands_are_in_tools_category() {
         let tools_commands = [
             "/last",
+            "/explain",
             "/tools",
             "/analyze",
             "/review",
             "/plan",
+            "/plan export",
             "/swarm",
             "/queue",
             "/queue list",
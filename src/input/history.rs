@@ -0,0 +1,302 @@
+//! Prompt history shared by every interactive session.
+//!
+//! Each submitted line is written to the history file straight away, so
+//! up-arrow and Ctrl+R reach lines from earlier and concurrent sessions.
+//! Repeated lines keep only their latest occurrence, the file is capped at
+//! the configured size, and lines starting with a space are never stored
+//! (like bash's `HISTCONTROL=ignorespace`), which keeps typed secrets out
+//! of the file.
+//!
+//! Sessions merge their new lines into the file while holding an exclusive
+//! lock on a sidecar `.lock` file, and replace the file by renaming a fully
+//! written temporary file, so a crashed or concurrent session never leaves
+//! it half-written.
+
+use reedline::{
+    FileBackedHistory, History, HistoryItem, HistoryItemId, HistorySessionId, ReedlineError,
+    ReedlineErrorVariants, SearchQuery,
+};
+use std::collections::HashSet;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// How embedded newlines are stored, matching reedline's file format so
+/// existing history files keep working
+const NEWLINE_ESCAPE: &str = "<\\n>";
+
+/// Persistent, de-duplicated prompt history
+pub struct PromptHistory {
+    /// Oldest first, no duplicates, at most `capacity` lines
+    entries: Vec<String>,
+    /// Lines saved since the last sync
+    pending: Vec<String>,
+    capacity: usize,
+    path: Option<PathBuf>,
+    /// In-memory view handed the browsing and search queries
+    view: FileBackedHistory,
+}
+
+impl PromptHistory {
+    /// History kept in memory only
+    pub fn new(capacity: usize) -> reedline::Result<Self> {
+        Ok(Self {
+            entries: Vec::new(),
+            pending: Vec::new(),
+            capacity,
+            path: None,
+            view: FileBackedHistory::new(capacity)?,
+        })
+    }
+
+    /// History backed by `path`, loading what earlier sessions stored
+    pub fn with_file(capacity: usize, path: PathBuf) -> reedline::Result<Self> {
+        let mut history = Self::new(capacity)?;
+        history.path = Some(path);
+        history.sync().map_err(io_error)?;
+        Ok(history)
+    }
+
+    /// Lines in the history, oldest first
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    /// Whether a submitted line should be stored at all
+    fn is_recordable(line: &str) -> bool {
+        !line.trim().is_empty() && !line.starts_with(' ')
+    }
+
+    /// Rebuild the search view after entries were removed or merged
+    fn rebuild_view(&mut self) -> reedline::Result<()> {
+        let mut view = FileBackedHistory::new(self.capacity)?;
+        for line in &self.entries {
+            view.save(HistoryItem::from_command_line(line))?;
+        }
+        self.view = view;
+        Ok(())
+    }
+
+    fn lock_path(path: &Path) -> PathBuf {
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(".lock");
+        path.with_file_name(name)
+    }
+}
+
+/// `lines` with earlier duplicates dropped, trimmed to the newest `capacity`
+fn dedup_latest(lines: impl IntoIterator<Item = String>, capacity: usize) -> Vec<String> {
+    let lines: Vec<String> = lines.into_iter().collect();
+    let mut seen = HashSet::new();
+    let mut kept: Vec<String> = lines
+        .into_iter()
+        .rev()
+        .filter(|line| seen.insert(line.clone()))
+        .take(capacity)
+        .collect();
+    kept.reverse();
+    kept
+}
+
+fn read_lines(path: &Path) -> std::io::Result<Vec<String>> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => Ok(text
+            .lines()
+            .filter(|l| !l.is_empty())
+            .map(|l| l.replace(NEWLINE_ESCAPE, "\n"))
+            .collect()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Replace `path` with `lines` via a temporary file and rename
+fn write_lines(path: &Path, lines: &[String]) -> std::io::Result<()> {
+    let tmp = path.with_extension(format!("tmp.{}", std::process::id()));
+    {
+        let mut file = File::create(&tmp)?;
+        for line in lines {
+            file.write_all(line.replace('\n', NEWLINE_ESCAPE).as_bytes())?;
+            file.write_all(b"\n")?;
+        }
+        file.sync_all()?;
+    }
+    std::fs::rename(&tmp, path)
+}
+
+fn io_error(e: std::io::Error) -> ReedlineError {
+    ReedlineError(ReedlineErrorVariants::IOError(e))
+}
+
+impl History for PromptHistory {
+    fn save(&mut self, h: HistoryItem) -> reedline::Result<HistoryItem> {
+        let line = h.command_line.clone();
+        if !Self::is_recordable(&line) || self.capacity == 0 {
+            return Ok(HistoryItem { id: None, ..h });
+        }
+        self.pending.push(line.clone());
+        let synced = self.path.is_some()
+            && match self.sync() {
+                Ok(()) => true,
+                Err(e) => {
+                    // Kept pending, so the next sync retries it
+                    tracing::warn!("Failed to write prompt history: {}", e);
+                    false
+                }
+            };
+        if !synced {
+            if self.path.is_none() {
+                self.pending.clear();
+            }
+            let entries = std::mem::take(&mut self.entries);
+            self.entries = dedup_latest(entries.into_iter().chain([line]), self.capacity);
+            self.rebuild_view()?;
+        }
+        let id = HistoryItemId(self.entries.len() as i64 - 1);
+        Ok(HistoryItem { id: Some(id), ..h })
+    }
+
+    fn load(&self, id: HistoryItemId) -> reedline::Result<HistoryItem> {
+        self.view.load(id)
+    }
+
+    fn count(&self, query: SearchQuery) -> reedline::Result<i64> {
+        self.view.count(query)
+    }
+
+    fn search(&self, query: SearchQuery) -> reedline::Result<Vec<HistoryItem>> {
+        self.view.search(query)
+    }
+
+    fn update(
+        &mut self,
+        id: HistoryItemId,
+        updater: &dyn Fn(HistoryItem) -> HistoryItem,
+    ) -> reedline::Result<()> {
+        self.view.update(id, updater)
+    }
+
+    fn clear(&mut self) -> reedline::Result<()> {
+        self.entries.clear();
+        self.pending.clear();
+        self.rebuild_view()?;
+        if let Some(path) = &self.path {
+            let lock = OpenOptions::new()
+                .create(true)
+                .truncate(false)
+                .write(true)
+                .open(Self::lock_path(path))
+                .map_err(io_error)?;
+            lock.lock().map_err(io_error)?;
+            write_lines(path, &[]).map_err(io_error)?;
+        }
+        Ok(())
+    }
+
+    fn delete(&mut self, id: HistoryItemId) -> reedline::Result<()> {
+        self.view.delete(id)
+    }
+
+    /// Merge lines saved since the last sync into the file and pick up what
+    /// other sessions stored meanwhile
+    fn sync(&mut self) -> std::io::Result<()> {
+        let Some(path) = self.path.clone() else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let lock = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(Self::lock_path(&path))?;
+        // Released when `lock` is dropped
+        lock.lock()?;
+
+        let on_disk = read_lines(&path)?;
+        let merged = dedup_latest(
+            on_disk.iter().cloned().chain(self.pending.iter().cloned()),
+            self.capacity,
+        );
+        if merged != on_disk {
+            write_lines(&path, &merged)?;
+        }
+        self.pending.clear();
+        self.entries = merged;
+        self.rebuild_view().map_err(std::io::Error::other)
+    }
+
+    fn session(&self) -> Option<HistorySessionId> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn save(history: &mut PromptHistory, line: &str) {
+        history.save(HistoryItem::from_command_line(line)).unwrap();
+    }
+
+    #[test]
+    fn test_history_dedups_caps_and_skips_space_prefixed_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.txt");
+        let mut history = PromptHistory::with_file(3, path.clone()).unwrap();
+
+        save(&mut history, "cargo test");
+        save(&mut history, " export API_KEY=secret");
+        save(&mut history, "/ctx");
+        save(&mut history, "cargo test");
+        save(&mut history, "multi\nline");
+        save(&mut history, "/tools");
+
+        assert_eq!(history.entries(), ["cargo test", "multi\nline", "/tools"]);
+        let on_disk = std::fs::read_to_string(&path).unwrap();
+        assert!(!on_disk.contains("secret"));
+        assert_eq!(on_disk, "cargo test\nmulti<\\n>line\n/tools\n");
+
+        let found = history
+            .search(SearchQuery::all_that_contain_rev("cargo".to_string()))
+            .unwrap();
+        assert_eq!(found[0].command_line, "cargo test");
+    }
+
+    #[test]
+    fn test_history_is_shared_between_sessions() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.txt");
+        let mut first = PromptHistory::with_file(100, path.clone()).unwrap();
+        let mut second = PromptHistory::with_file(100, path.clone()).unwrap();
+
+        save(&mut first, "fix the parser");
+        save(&mut second, "run the benchmarks");
+        save(&mut first, "fix the parser");
+        assert_eq!(
+            first.entries(),
+            ["run the benchmarks", "fix the parser"],
+            "a save picks up lines from the other session"
+        );
+
+        let handles: Vec<_> = (0..4)
+            .map(|session| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    let mut history = PromptHistory::with_file(100, path).unwrap();
+                    for i in 0..10 {
+                        save(&mut history, &format!("session {} line {}", session, i));
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let reopened = PromptHistory::with_file(100, path).unwrap();
+        assert_eq!(reopened.entries().len(), 42);
+        assert!(reopened.entries().contains(&"session 3 line 9".to_string()));
+    }
+}
@@ -6,17 +6,20 @@
 pub mod command_registry;
 mod completer;
 mod highlighter;
+mod history;
+mod paste;
 mod prompt;
 
 pub use completer::SelfwareCompleter;
 pub use highlighter::SelfwareHighlighter;
+pub use history::PromptHistory;
+pub use paste::{ComposeValidator, PasteHinter};
 pub use prompt::SelfwarePrompt;
 
 use anyhow::Result;
 use reedline::{
-    default_emacs_keybindings, ColumnarMenu, DefaultHinter, DefaultValidator, EditCommand, Emacs,
-    FileBackedHistory, KeyCode, KeyModifiers, Keybindings, MenuBuilder, Reedline, ReedlineEvent,
-    ReedlineMenu, Signal, Vi,
+    default_emacs_keybindings, ColumnarMenu, EditCommand, Emacs, KeyCode, KeyModifiers,
+    Keybindings, MenuBuilder, Reedline, ReedlineEvent, ReedlineMenu, Signal, Vi,
 };
 use std::path::PathBuf;
 
@@ -45,6 +48,9 @@ pub struct InputConfig {
     pub tool_names: Vec<String>,
     /// Available commands for completion
     pub commands: Vec<String>,
+    /// Capture multi-line pastes as one input (ignored by terminals
+    /// without bracketed paste support)
+    pub bracketed_paste: bool,
 }
 
 impl Default for InputConfig {
@@ -57,6 +63,7 @@ impl Default for InputConfig {
             show_hints: true,
             tool_names: vec![],
             commands: command_registry::command_names(),
+            bracketed_paste: std::env::var("TERM").map_or(true, |t| t != "dumb"),
         }
     }
 }
@@ -76,18 +83,11 @@ pub struct SelfwareEditor {
 impl SelfwareEditor {
     /// Create a new editor with configuration
     pub fn new(config: InputConfig) -> Result<Self> {
-        // Set up history
+        // Set up history, shared with other sessions through the history file
         let history = if let Some(path) = &config.history_path {
-            // Ensure parent directory exists
-            if let Some(parent) = path.parent() {
-                std::fs::create_dir_all(parent)?;
-            }
-            Box::new(FileBackedHistory::with_file(
-                config.max_history,
-                path.clone(),
-            )?)
+            Box::new(PromptHistory::with_file(config.max_history, path.clone())?)
         } else {
-            Box::new(FileBackedHistory::new(config.max_history)?)
+            Box::new(PromptHistory::new(config.max_history)?)
         };
 
         // Set up completer
@@ -99,8 +99,8 @@ impl SelfwareEditor {
         // Set up highlighter
         let highlighter = Box::new(SelfwareHighlighter::new());
 
-        // Set up hinter
-        let hinter = Box::new(DefaultHinter::default());
+        // Set up hinter, which also reports pastes and compose blocks
+        let hinter = Box::new(PasteHinter::default());
 
         // Set up completion menu - IDE style that cycles with Tab
         let completion_menu = Box::new(
@@ -120,8 +120,8 @@ impl SelfwareEditor {
             InputMode::Vi => Box::new(Vi::default()),
         };
 
-        // Set up validator
-        let validator = Box::new(DefaultValidator);
+        // Set up validator: a ``` line opens a block that Enter extends
+        let validator = Box::new(ComposeValidator);
 
         // Configure external editor for Ctrl+X
         let editor_cmd = std::env::var("VISUAL")
@@ -141,9 +141,10 @@ impl SelfwareEditor {
             .with_validator(validator)
             .with_menu(ReedlineMenu::EngineCompleter(completion_menu))
             .with_edit_mode(edit_mode)
-            .with_buffer_editor(buffer_editor, temp_file);
+            .with_buffer_editor(buffer_editor, temp_file)
+            .use_bracketed_paste(config.bracketed_paste);
 
-        // Add Ctrl+R for history search
+        // Lines starting with a space stay out of history (Ctrl+R searches it)
         editor = editor.with_history_exclusion_prefix(Some(" ".into()));
 
         let prompt = SelfwarePrompt::new();
@@ -0,0 +1,181 @@
+//! Multi-line input: pastes and compose blocks
+//!
+//! With bracketed paste enabled a multi-line paste lands in the buffer as
+//! one input instead of submitting at its first newline, and the hint area
+//! shows how many lines were pasted. Opening a line with triple backticks
+//! starts a compose block: Enter adds lines until the closing fence. The
+//! block also keeps pasted code together on terminals without bracketed
+//! paste, which send a paste as ordinary keystrokes.
+
+use nu_ansi_term::{Color, Style};
+use reedline::{DefaultHinter, DefaultValidator, Hinter, History, ValidationResult, Validator};
+
+/// Whether `text` has a ``` fence that is not closed yet
+pub(crate) fn fence_open(text: &str) -> bool {
+    text.lines()
+        .filter(|line| line.trim_start().starts_with("```"))
+        .count()
+        % 2
+        == 1
+}
+
+/// Lines in the buffer, counting a trailing newline as starting a line
+fn line_count(text: &str) -> usize {
+    text.split('\n').count()
+}
+
+/// Keeps the input open while a compose block is unclosed, otherwise
+/// behaves like reedline's default validator
+pub struct ComposeValidator;
+
+impl Validator for ComposeValidator {
+    fn validate(&self, line: &str) -> ValidationResult {
+        if fence_open(line) {
+            ValidationResult::Incomplete
+        } else {
+            DefaultValidator.validate(line)
+        }
+    }
+}
+
+/// History hints, replaced by a status note while a paste or compose
+/// block is in the buffer. The note is never completed into the input.
+pub struct PasteHinter {
+    inner: DefaultHinter,
+    /// Line count at the previous repaint, to spot lines arriving at once
+    last_lines: usize,
+    /// Lines in the buffer right after the last multi-line paste
+    pasted: Option<usize>,
+    /// Whether the current hint is a note rather than a history hint
+    showing_note: bool,
+}
+
+impl Default for PasteHinter {
+    fn default() -> Self {
+        Self {
+            inner: DefaultHinter::default(),
+            last_lines: 1,
+            pasted: None,
+            showing_note: false,
+        }
+    }
+}
+
+impl PasteHinter {
+    /// The note for `line`, tracking pastes as the buffer changes
+    fn note(&mut self, line: &str) -> Option<String> {
+        let lines = line_count(line);
+        if lines <= 1 {
+            self.pasted = None;
+        } else if lines > self.last_lines + 1 {
+            self.pasted = Some(lines);
+        }
+        self.last_lines = lines;
+
+        if fence_open(line) {
+            Some(format!(
+                "  [compose: {} lines, close with ``` to send]",
+                lines
+            ))
+        } else {
+            self.pasted.map(|n| format!("  [pasted {} lines]", n))
+        }
+    }
+}
+
+impl Hinter for PasteHinter {
+    fn handle(
+        &mut self,
+        line: &str,
+        pos: usize,
+        history: &dyn History,
+        use_ansi_coloring: bool,
+        cwd: &str,
+    ) -> String {
+        match self.note(line) {
+            Some(note) => {
+                self.showing_note = true;
+                if use_ansi_coloring {
+                    Style::new()
+                        .fg(Color::DarkGray)
+                        .italic()
+                        .paint(note)
+                        .to_string()
+                } else {
+                    note
+                }
+            }
+            None => {
+                self.showing_note = false;
+                self.inner
+                    .handle(line, pos, history, use_ansi_coloring, cwd)
+            }
+        }
+    }
+
+    fn complete_hint(&self) -> String {
+        if self.showing_note {
+            String::new()
+        } else {
+            self.inner.complete_hint()
+        }
+    }
+
+    fn next_hint_token(&self) -> String {
+        if self.showing_note {
+            String::new()
+        } else {
+            self.inner.next_hint_token()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reedline::FileBackedHistory;
+
+    #[test]
+    fn test_compose_block_keeps_input_open_until_closed() {
+        let validator = ComposeValidator;
+        assert!(matches!(
+            validator.validate("fix this:\n```\nfn main() {}"),
+            ValidationResult::Incomplete
+        ));
+        assert!(matches!(
+            validator.validate("fix this:\n```\nfn main() {}\n```"),
+            ValidationResult::Complete
+        ));
+        assert!(matches!(
+            validator.validate("explain the parser"),
+            ValidationResult::Complete
+        ));
+        assert!(fence_open("```rust"));
+        assert!(!fence_open("use `x` and ``y``"));
+    }
+
+    #[test]
+    fn test_paste_hinter_reports_pasted_lines_without_completing_them() {
+        let history = FileBackedHistory::new(10).unwrap();
+        let mut hinter = PasteHinter::default();
+        let trace = "thread 'main' panicked\nat src/main.rs:3\nstack backtrace:";
+
+        assert_eq!(hinter.handle("look at", 7, &history, false, ""), "");
+        assert_eq!(
+            hinter.handle(&format!("look at {}", trace), 0, &history, false, ""),
+            "  [pasted 3 lines]"
+        );
+        assert_eq!(hinter.complete_hint(), "");
+
+        // A newline typed by hand is not a paste
+        let mut typed = PasteHinter::default();
+        assert_eq!(typed.handle("a\nb", 3, &history, false, ""), "");
+
+        assert_eq!(
+            hinter.handle("```\nlet x = 1;", 0, &history, false, ""),
+            "  [compose: 2 lines, close with ``` to send]"
+        );
+        assert_eq!(hinter.handle("", 0, &history, false, ""), "");
+        assert!(hinter.pasted.is_none());
+    }
+}
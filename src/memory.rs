@@ -5,14 +5,18 @@
 //! - Token estimation for messages
 //! - Context window limit awareness
 //! - Message summarization for long conversations
+//! - Consolidation of old entries into a cached, model-written summary
 //! - Memory statistics for monitoring
 
 use crate::api::types::Message;
+use crate::api::{ApiClient, ThinkingMode};
 use crate::config::Config;
 use crate::token_count::estimate_tokens_with_overhead;
 use anyhow::Result;
 use chrono::Utc;
-use std::collections::VecDeque;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
 
 /// Maximum number of memory entries before eviction kicks in.
 const MAX_MEMORY_ENTRIES: usize = 10_000;
@@ -22,9 +26,30 @@ const MAX_MEMORY_ENTRIES: usize = 10_000;
 /// This is complementary to the `MAX_MEMORY_ENTRIES` limit.
 const MAX_MEMORY_TOKENS: usize = 500_000;
 
+/// Percent of the context window memory may fill before `consolidate` rolls
+/// old entries up into a summary. Below `is_near_limit`'s 85 % so
+/// consolidation gets there first.
+const CONSOLIDATE_AT_PERCENT: usize = 60;
+
+/// Percent of the context window kept verbatim as recent entries when
+/// consolidating
+const KEEP_RECENT_PERCENT: usize = 25;
+
+/// Characters of each entry, and of the whole transcript, sent to the summarizer
+const SUMMARY_ENTRY_CHARS: usize = 500;
+const SUMMARY_INPUT_CHARS: usize = 48_000;
+
+/// Summaries kept in the cache before it is emptied
+const MAX_CACHED_SUMMARIES: usize = 32;
+
+/// Role of the entry holding consolidated history
+pub const SUMMARY_ROLE: &str = "summary";
+
 pub struct AgentMemory {
     context_window: usize,
     entries: VecDeque<MemoryEntry>,
+    /// Summaries already generated, keyed by a hash of the transcript they cover
+    summaries: HashMap<u64, String>,
 }
 
 pub struct MemoryEntry {
@@ -55,6 +80,7 @@ impl AgentMemory {
         Ok(Self {
             context_window: config.agent.token_budget,
             entries: VecDeque::new(),
+            summaries: HashMap::new(),
         })
     }
 
@@ -98,6 +124,79 @@ impl AgentMemory {
         self.entries.iter().map(|e| e.token_estimate).sum()
     }
 
+    /// Whether memory has grown past the point where `consolidate` would roll
+    /// up old entries
+    pub fn needs_consolidation(&self) -> bool {
+        self.entries.len() > 1
+            && self.total_tokens().saturating_mul(100)
+                > self.context_window.saturating_mul(CONSOLIDATE_AT_PERCENT)
+    }
+
+    /// Roll every entry but the most recent ones into a single summary entry
+    /// written by the model, keeping recent entries (up to a quarter of the
+    /// window) verbatim. An earlier summary is folded into the new one.
+    ///
+    /// Summaries are cached by the transcript they cover, so the same history
+    /// is never summarized twice. Below the threshold this does nothing, which
+    /// makes repeated calls safe. Returns the estimated tokens saved.
+    pub async fn consolidate(&mut self, client: &ApiClient) -> Result<usize> {
+        if !self.needs_consolidation() {
+            return Ok(0);
+        }
+        let before = self.total_tokens();
+
+        let keep_budget = self.context_window.saturating_mul(KEEP_RECENT_PERCENT) / 100;
+        let mut kept_tokens = 0;
+        let kept = self
+            .entries
+            .iter()
+            .rev()
+            .take_while(|e| {
+                kept_tokens += e.token_estimate;
+                kept_tokens <= keep_budget
+            })
+            .count();
+        // Always roll up at least one entry besides an existing summary
+        let split = (self.entries.len() - kept).max(
+            if self.entries.front().is_some_and(|e| e.role == SUMMARY_ROLE) {
+                2
+            } else {
+                1
+            },
+        );
+        let split = split.min(self.entries.len());
+
+        let transcript = consolidation_transcript(self.entries.range(..split));
+        let mut hasher = DefaultHasher::new();
+        transcript.hash(&mut hasher);
+        let key = hasher.finish();
+
+        let summary = match self.summaries.get(&key) {
+            Some(summary) => summary.clone(),
+            None => {
+                let summary = summarize(client, &transcript).await?;
+                if self.summaries.len() >= MAX_CACHED_SUMMARIES {
+                    self.summaries.clear();
+                }
+                self.summaries.insert(key, summary.clone());
+                summary
+            }
+        };
+
+        let content = format!(
+            "[Consolidated memory of {} earlier entries]\n{}",
+            split, summary
+        );
+        self.entries.drain(..split);
+        self.entries.push_front(MemoryEntry {
+            timestamp: Utc::now().to_rfc3339(),
+            role: SUMMARY_ROLE.to_string(),
+            token_estimate: estimate_tokens(&content),
+            content,
+        });
+        Ok(before.saturating_sub(self.total_tokens()))
+    }
+
     /// Check if memory is approaching the context window limit
     pub fn is_near_limit(&self) -> bool {
         self.total_tokens().saturating_mul(100) > self.context_window.saturating_mul(85)
@@ -142,6 +241,59 @@ impl AgentMemory {
     }
 }
 
+/// Text handed to the summarizer: each entry truncated, newest entries kept
+/// when the whole would be too long, and an earlier summary always included
+fn consolidation_transcript<'a>(
+    entries: impl DoubleEndedIterator<Item = &'a MemoryEntry>,
+) -> String {
+    let mut parts = Vec::new();
+    let mut chars = 0;
+    let mut earlier_summary = None;
+    for entry in entries.rev() {
+        if entry.role == SUMMARY_ROLE {
+            earlier_summary = Some(format!("Earlier summary:\n{}", entry.content));
+            continue;
+        }
+        let content: String = entry.content.chars().take(SUMMARY_ENTRY_CHARS).collect();
+        let part = format!("{}: {}", entry.role, content);
+        chars += part.len();
+        if chars > SUMMARY_INPUT_CHARS {
+            break;
+        }
+        parts.push(part);
+    }
+    parts.extend(earlier_summary);
+    parts.reverse();
+    parts.join("\n\n")
+}
+
+/// Ask the model for a compact summary of `transcript`
+async fn summarize(client: &ApiClient, transcript: &str) -> Result<String> {
+    let request = vec![
+        Message::system(
+            "You maintain an agent's long-term memory. Condense the history you are \
+             given into a short summary that keeps goals, decisions, facts learned, \
+             file paths and unresolved problems. Leave out routine tool output.",
+        ),
+        Message::user(transcript.to_string()),
+    ];
+    let response = tokio::time::timeout(
+        std::time::Duration::from_secs(120),
+        client.chat(request, None, ThinkingMode::Disabled),
+    )
+    .await
+    .map_err(|_| anyhow::anyhow!("Memory consolidation timed out after 120s"))??;
+    let summary = response
+        .choices
+        .first()
+        .map(|c| c.message.content.text().trim().to_string())
+        .unwrap_or_default();
+    if summary.is_empty() {
+        anyhow::bail!("Memory consolidation returned an empty summary");
+    }
+    Ok(summary)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -726,4 +878,58 @@ mod tests {
         memory.clear();
         assert_eq!(memory.total_estimated_tokens(), 0);
     }
+
+    #[tokio::test]
+    async fn test_consolidate_rolls_up_old_entries_once_and_caches_summary() {
+        use crate::testing::mock_api::MockLlmServer;
+
+        // One summary, then errors: a second model call would fail the test
+        let server = MockLlmServer::builder()
+            .with_response("Goal: fix the parser. Edited src/parse.rs.")
+            .with_error(400, r#"{"error":"no more summaries"}"#)
+            .build()
+            .await;
+        let mut config = Config {
+            endpoint: format!("{}/v1", server.url()),
+            model: "mock-model".to_string(),
+            ..Default::default()
+        };
+        config.agent.token_budget = 2000;
+        let client = ApiClient::new(&config).unwrap();
+        let mut memory = AgentMemory::new(&config).unwrap();
+
+        let fill = |memory: &mut AgentMemory| {
+            let mut i = 0;
+            while !memory.needs_consolidation() {
+                memory.add_message(&Message::user(format!("step {} {}", i, "note ".repeat(40))));
+                i += 1;
+            }
+        };
+        fill(&mut memory);
+        let entries = memory.len();
+
+        let saved = memory.consolidate(&client).await.unwrap();
+        assert!(saved > 0);
+        assert!(memory.len() < entries);
+        assert!(!memory.needs_consolidation());
+        assert!(!memory.is_near_limit());
+        let oldest = memory.entries.front().unwrap();
+        assert_eq!(oldest.role, SUMMARY_ROLE);
+        assert!(oldest.content.contains("Edited src/parse.rs"));
+        // Most recent entry is kept verbatim
+        assert!(memory.recent(1)[0].content.starts_with("step "));
+
+        // Idempotent: nothing left to roll up
+        let snapshot: Vec<String> = memory.entries.iter().map(|e| e.content.clone()).collect();
+        assert_eq!(memory.consolidate(&client).await.unwrap(), 0);
+        let after: Vec<String> = memory.entries.iter().map(|e| e.content.clone()).collect();
+        assert_eq!(snapshot, after);
+
+        // The same history again is served from the cache, not the model
+        memory.clear();
+        fill(&mut memory);
+        assert!(memory.consolidate(&client).await.unwrap() > 0);
+        assert!(memory.entries[0].content.contains("Edited src/parse.rs"));
+        server.stop().await;
+    }
 }
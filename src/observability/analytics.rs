@@ -5,6 +5,7 @@
 //! - Bugs prevented
 //! - Code quality metrics
 //! - Productivity trends
+//! - Per-tool latency and failure rates, persisted across sessions
 //!
 //! Privacy: Analytics can be disabled via `SELFWARE_ANALYTICS=false` env var
 //! or by calling `AnalyticsDashboard::set_enabled(false)`. When disabled,
@@ -12,8 +13,12 @@
 
 #![allow(dead_code, unused_imports, unused_variables)]
 
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::RwLock;
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -1144,6 +1149,387 @@ pub struct RoiEstimate {
     pub hourly_rate: f64,
 }
 
+/// Timing log size past which the oldest half is dropped
+const MAX_TOOL_TIMINGS_BYTES: u64 = 4 * 1024 * 1024;
+
+/// One timed tool execution. The duration covers only the tool itself,
+/// not confirmation prompts or argument parsing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolTiming {
+    pub tool: String,
+    pub timestamp: u64,
+    pub duration_ms: u64,
+    pub success: bool,
+    pub result_bytes: usize,
+}
+
+impl ToolTiming {
+    pub fn new(tool: &str, duration_ms: u64, success: bool, result_bytes: usize) -> Self {
+        Self {
+            tool: tool.to_string(),
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            duration_ms,
+            success,
+            result_bytes,
+        }
+    }
+}
+
+/// Latency and failure statistics for one tool
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ToolStats {
+    pub tool: String,
+    pub calls: usize,
+    pub failures: usize,
+    pub p50_ms: u64,
+    pub p95_ms: u64,
+    pub total_ms: u64,
+    pub avg_result_bytes: usize,
+}
+
+impl ToolStats {
+    pub fn failure_rate(&self) -> f64 {
+        if self.calls == 0 {
+            0.0
+        } else {
+            self.failures as f64 / self.calls as f64
+        }
+    }
+}
+
+/// Nearest-rank percentile of sorted durations
+fn percentile(sorted: &[u64], pct: f64) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = ((pct / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Per-tool statistics over `timings` within `period`, slowest total first
+pub fn tool_stats(timings: &[ToolTiming], period: TimePeriod) -> Vec<ToolStats> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let cutoff = now.saturating_sub(period.seconds());
+
+    let mut by_tool: HashMap<&str, Vec<&ToolTiming>> = HashMap::new();
+    for timing in timings.iter().filter(|t| t.timestamp >= cutoff) {
+        by_tool
+            .entry(timing.tool.as_str())
+            .or_default()
+            .push(timing);
+    }
+
+    let mut stats: Vec<ToolStats> = by_tool
+        .into_iter()
+        .map(|(tool, runs)| {
+            let mut durations: Vec<u64> = runs.iter().map(|t| t.duration_ms).collect();
+            durations.sort_unstable();
+            ToolStats {
+                tool: tool.to_string(),
+                calls: runs.len(),
+                failures: runs.iter().filter(|t| !t.success).count(),
+                p50_ms: percentile(&durations, 50.0),
+                p95_ms: percentile(&durations, 95.0),
+                total_ms: durations.iter().sum(),
+                avg_result_bytes: runs.iter().map(|t| t.result_bytes).sum::<usize>() / runs.len(),
+            }
+        })
+        .collect();
+    stats.sort_by(|a, b| b.total_ms.cmp(&a.total_ms).then(a.tool.cmp(&b.tool)));
+    stats
+}
+
+/// Append-only JSONL log of tool timings, kept across sessions
+pub struct ToolTimingStore {
+    path: PathBuf,
+}
+
+impl ToolTimingStore {
+    /// Store at the default path under the local data directory
+    pub fn new() -> Self {
+        let path = dirs::data_local_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("selfware")
+            .join("analytics")
+            .join("tool_timings.jsonl");
+        Self { path }
+    }
+
+    /// Store at a custom path
+    pub fn with_path(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Append a timing; a no-op when analytics are disabled
+    pub fn record(&self, timing: &ToolTiming) -> Result<()> {
+        if !analytics_enabled() {
+            return Ok(());
+        }
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(timing)?)?;
+
+        if file.metadata()?.len() > MAX_TOOL_TIMINGS_BYTES {
+            drop(file);
+            self.drop_oldest_half()?;
+        }
+        Ok(())
+    }
+
+    /// All recorded timings, oldest first. Unreadable lines are skipped.
+    pub fn load(&self) -> Result<Vec<ToolTiming>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let file = std::fs::File::open(&self.path)?;
+        let mut timings = Vec::new();
+        for line in std::io::BufReader::new(file).lines() {
+            if let Ok(timing) = serde_json::from_str::<ToolTiming>(&line?) {
+                timings.push(timing);
+            }
+        }
+        Ok(timings)
+    }
+
+    pub fn stats(&self, period: TimePeriod) -> Result<Vec<ToolStats>> {
+        Ok(tool_stats(&self.load()?, period))
+    }
+
+    /// Delete all recorded timings
+    pub fn clear(&self) -> Result<()> {
+        if self.path.exists() {
+            std::fs::remove_file(&self.path)?;
+        }
+        Ok(())
+    }
+
+    fn drop_oldest_half(&self) -> Result<()> {
+        let timings = self.load()?;
+        let mut kept = String::new();
+        for timing in &timings[timings.len() / 2..] {
+            kept.push_str(&serde_json::to_string(timing)?);
+            kept.push('\n');
+        }
+        std::fs::write(&self.path, kept)?;
+        Ok(())
+    }
+}
+
+impl Default for ToolTimingStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// One model's side of a `/compare`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComparisonRun {
+    pub model: String,
+    pub duration_ms: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub cost_usd: f64,
+    /// False when the request or task failed
+    pub success: bool,
+}
+
+/// Two models given the same prompt or task, with the user's verdict
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelComparison {
+    pub timestamp: u64,
+    /// Anonymized task, see `anonymize_task`
+    pub task: String,
+    /// Whole task rather than a single turn
+    pub full: bool,
+    pub runs: Vec<ComparisonRun>,
+    /// Model the user preferred; `None` for a tie or no vote
+    pub winner: Option<String>,
+    /// Whether the user voted at all (a tie is a vote)
+    pub voted: bool,
+}
+
+impl ModelComparison {
+    pub fn new(task: &str, full: bool, runs: Vec<ComparisonRun>) -> Self {
+        Self {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            task: anonymize_task(task),
+            full,
+            runs,
+            winner: None,
+            voted: false,
+        }
+    }
+}
+
+/// A model's record across comparisons
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ModelStanding {
+    pub model: String,
+    pub comparisons: usize,
+    pub wins: usize,
+    pub losses: usize,
+    pub ties: usize,
+    pub failures: usize,
+    pub avg_duration_ms: u64,
+    pub avg_completion_tokens: u64,
+    pub total_cost_usd: f64,
+}
+
+impl ModelStanding {
+    /// Wins per voted comparison, ties counting half
+    pub fn win_rate(&self) -> f64 {
+        let voted = self.wins + self.losses + self.ties;
+        if voted == 0 {
+            0.0
+        } else {
+            (self.wins as f64 + self.ties as f64 / 2.0) / voted as f64
+        }
+    }
+}
+
+/// Standings over `comparisons`, best win rate first, faster first on ties
+pub fn leaderboard(comparisons: &[ModelComparison]) -> Vec<ModelStanding> {
+    let mut by_model: HashMap<&str, (ModelStanding, u64, u64)> = HashMap::new();
+    for comparison in comparisons {
+        for run in &comparison.runs {
+            let (standing, durations, tokens) =
+                by_model.entry(run.model.as_str()).or_insert_with(|| {
+                    (
+                        ModelStanding {
+                            model: run.model.clone(),
+                            comparisons: 0,
+                            wins: 0,
+                            losses: 0,
+                            ties: 0,
+                            failures: 0,
+                            avg_duration_ms: 0,
+                            avg_completion_tokens: 0,
+                            total_cost_usd: 0.0,
+                        },
+                        0,
+                        0,
+                    )
+                });
+            standing.comparisons += 1;
+            if !run.success {
+                standing.failures += 1;
+            }
+            *durations += run.duration_ms;
+            *tokens += run.completion_tokens;
+            standing.total_cost_usd += run.cost_usd;
+            if comparison.voted {
+                match comparison.winner.as_deref() {
+                    None => standing.ties += 1,
+                    Some(winner) if winner == run.model => standing.wins += 1,
+                    Some(_) => standing.losses += 1,
+                }
+            }
+        }
+    }
+
+    let mut standings: Vec<ModelStanding> = by_model
+        .into_values()
+        .map(|(mut standing, durations, tokens)| {
+            let n = standing.comparisons.max(1) as u64;
+            standing.avg_duration_ms = durations / n;
+            standing.avg_completion_tokens = tokens / n;
+            standing
+        })
+        .collect();
+    standings.sort_by(|a, b| {
+        b.win_rate()
+            .total_cmp(&a.win_rate())
+            .then(a.avg_duration_ms.cmp(&b.avg_duration_ms))
+            .then(a.model.cmp(&b.model))
+    });
+    standings
+}
+
+/// Append-only JSONL log of model comparisons, kept across sessions
+pub struct ModelComparisonStore {
+    path: PathBuf,
+}
+
+impl ModelComparisonStore {
+    /// Store at the default path under the local data directory
+    pub fn new() -> Self {
+        let path = dirs::data_local_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("selfware")
+            .join("analytics")
+            .join("model_comparisons.jsonl");
+        Self { path }
+    }
+
+    /// Store at a custom path
+    pub fn with_path(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Append a comparison; a no-op when analytics are disabled
+    pub fn record(&self, comparison: &ModelComparison) -> Result<()> {
+        if !analytics_enabled() {
+            return Ok(());
+        }
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(comparison)?)?;
+        Ok(())
+    }
+
+    /// All recorded comparisons, oldest first. Unreadable lines are skipped.
+    pub fn load(&self) -> Result<Vec<ModelComparison>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let file = std::fs::File::open(&self.path)?;
+        let mut comparisons = Vec::new();
+        for line in std::io::BufReader::new(file).lines() {
+            if let Ok(comparison) = serde_json::from_str::<ModelComparison>(&line?) {
+                comparisons.push(comparison);
+            }
+        }
+        Ok(comparisons)
+    }
+
+    pub fn leaderboard(&self) -> Result<Vec<ModelStanding>> {
+        Ok(leaderboard(&self.load()?))
+    }
+
+    /// Delete all recorded comparisons
+    pub fn clear(&self) -> Result<()> {
+        if self.path.exists() {
+            std::fs::remove_file(&self.path)?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for ModelComparisonStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1895,4 +2281,100 @@ mod tests {
 
         set_analytics_enabled(original);
     }
+
+    #[test]
+    fn test_tool_stats_percentiles_and_failure_rate() {
+        let mut timings: Vec<ToolTiming> = (1..=20)
+            .map(|i| ToolTiming::new("cargo_check", i * 100, i != 20, 1000))
+            .collect();
+        timings.push(ToolTiming::new("file_read", 5, true, 200));
+        let mut old = ToolTiming::new("file_read", 9999, false, 0);
+        old.timestamp -= 2 * TimePeriod::Day.seconds();
+        timings.push(old);
+
+        let stats = tool_stats(&timings, TimePeriod::Day);
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].tool, "cargo_check");
+        assert_eq!(stats[0].calls, 20);
+        assert_eq!(stats[0].p50_ms, 1000);
+        assert_eq!(stats[0].p95_ms, 1900);
+        assert!((stats[0].failure_rate() - 0.05).abs() < 1e-9);
+
+        // The two-day-old failure is outside the period
+        assert_eq!(stats[1].tool, "file_read");
+        assert_eq!(stats[1].calls, 1);
+        assert_eq!(stats[1].failure_rate(), 0.0);
+        assert_eq!(tool_stats(&timings, TimePeriod::All)[1].calls, 2);
+    }
+
+    #[test]
+    fn test_tool_timing_store_persists_across_instances() {
+        let _guard = ANALYTICS_TEST_MUTEX.lock().unwrap();
+        let original = analytics_enabled();
+        set_analytics_enabled(true);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("analytics").join("tool_timings.jsonl");
+
+        let store = ToolTimingStore::with_path(path.clone());
+        store
+            .record(&ToolTiming::new("shell_exec", 250, true, 64))
+            .unwrap();
+        store
+            .record(&ToolTiming::new("shell_exec", 750, false, 10))
+            .unwrap();
+
+        let reopened = ToolTimingStore::with_path(path);
+        let stats = reopened.stats(TimePeriod::All).unwrap();
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].calls, 2);
+        assert_eq!(stats[0].failures, 1);
+        assert_eq!(stats[0].p95_ms, 750);
+        assert_eq!(stats[0].avg_result_bytes, 37);
+
+        reopened.clear().unwrap();
+        assert!(reopened.load().unwrap().is_empty());
+
+        set_analytics_enabled(original);
+    }
+
+    #[test]
+    fn test_model_comparisons_build_leaderboard() {
+        let _guard = ANALYTICS_TEST_MUTEX.lock().unwrap();
+        let original = analytics_enabled();
+        set_analytics_enabled(true);
+        let dir = tempfile::tempdir().unwrap();
+        let store = ModelComparisonStore::with_path(dir.path().join("model_comparisons.jsonl"));
+
+        let run = |model: &str, duration_ms: u64| ComparisonRun {
+            model: model.to_string(),
+            duration_ms,
+            prompt_tokens: 100,
+            completion_tokens: 50,
+            cost_usd: 0.001,
+            success: true,
+        };
+        let mut first =
+            ModelComparison::new("fix the bug", false, vec![run("a", 100), run("b", 300)]);
+        first.voted = true;
+        first.winner = Some("b".to_string());
+        let mut tie =
+            ModelComparison::new("fix the bug", false, vec![run("a", 300), run("b", 300)]);
+        tie.voted = true;
+        let unvoted = ModelComparison::new("other", true, vec![run("a", 200), run("b", 200)]);
+        for comparison in [&first, &tie, &unvoted] {
+            store.record(comparison).unwrap();
+        }
+        assert!(first.task.starts_with("task:"));
+
+        let standings = store.leaderboard().unwrap();
+        assert_eq!(standings[0].model, "b");
+        assert_eq!((standings[0].wins, standings[0].ties), (1, 1));
+        assert_eq!(standings[0].win_rate(), 0.75);
+        assert_eq!(standings[1].model, "a");
+        assert_eq!(standings[1].comparisons, 3);
+        assert_eq!(standings[1].losses, 1);
+        assert_eq!(standings[1].avg_duration_ms, 200);
+
+        set_analytics_enabled(original);
+    }
 }
@@ -275,6 +275,8 @@ pub struct EmissionRecord {
     pub provider: Option<CloudProvider>,
     /// Region
     pub region: Option<String>,
+    /// Estimated from a weaker proxy (e.g. wall-clock instead of CPU time)
+    pub low_confidence: bool,
 }
 
 impl EmissionRecord {
@@ -296,6 +298,7 @@ impl EmissionRecord {
             operation: None,
             provider: None,
             region: None,
+            low_confidence: false,
         }
     }
 
@@ -334,6 +337,12 @@ impl EmissionRecord {
         self.region = Some(region.into());
         self
     }
+
+    /// Mark as estimated from a weaker proxy
+    pub fn with_low_confidence(mut self) -> Self {
+        self.low_confidence = true;
+        self
+    }
 }
 
 /// Optimization suggestion
@@ -669,6 +678,8 @@ pub struct CarbonTracker {
     provider: CloudProvider,
     /// Region
     region: Option<String>,
+    /// Estimated power draw of one fully busy CPU core, in W
+    cpu_watts: f64,
 }
 
 impl Default for CarbonTracker {
@@ -691,6 +702,7 @@ impl CarbonTracker {
             _session_start,
             provider: CloudProvider::Local,
             region: None,
+            cpu_watts: DEFAULT_CPU_WATTS,
         }
     }
 
@@ -713,6 +725,12 @@ impl CarbonTracker {
         self
     }
 
+    /// Set the per-core power estimate used by [`CarbonTracker::record_compute`]
+    pub fn with_cpu_watts(mut self, cpu_watts: f64) -> Self {
+        self.cpu_watts = cpu_watts;
+        self
+    }
+
     /// Record an emission
     pub fn record(&mut self, record: EmissionRecord) {
         self.records.push(record);
@@ -754,6 +772,81 @@ impl CarbonTracker {
         self.record(record);
     }
 
+    /// Attribute `cpu_secs` of CPU time (summed over cores) to `tool`
+    pub fn record_compute(&mut self, tool: &str, cpu_secs: f64) {
+        self.record_compute_secs(tool, cpu_secs, false);
+    }
+
+    /// Attribute `wall_secs` of wall-clock time to `tool`, for platforms
+    /// where child CPU time can't be read. The record is flagged low
+    /// confidence since an idle wait counts the same as a busy core.
+    pub fn record_compute_wall_clock(&mut self, tool: &str, wall_secs: f64) {
+        self.record_compute_secs(tool, wall_secs, true);
+    }
+
+    /// Record a [`ComputeSample`] with the matching confidence
+    pub fn record_sample(&mut self, tool: &str, sample: ComputeSample) {
+        self.record_compute_secs(tool, sample.secs, !sample.measured);
+    }
+
+    fn record_compute_secs(&mut self, tool: &str, secs: f64, low_confidence: bool) {
+        if !secs.is_finite() || secs <= 0.0 {
+            return;
+        }
+        let mut record = self
+            .calculator
+            .cpu_emission(Duration::from_secs_f64(secs), self.cpu_watts)
+            .with_provider(self.provider)
+            .with_operation(tool)
+            .with_description(format!("{}: {:.2}s CPU", tool, secs));
+        if low_confidence {
+            record = record.with_low_confidence();
+        }
+        self.record(record);
+    }
+
+    /// Compute recorded per tool, heaviest first
+    pub fn compute_by_tool(&self) -> Vec<ToolCompute> {
+        let mut by_tool: HashMap<&str, ToolCompute> = HashMap::new();
+        for record in &self.records {
+            let Some(tool) = record.operation.as_deref() else {
+                continue;
+            };
+            if record.source != EmissionSource::CpuCompute {
+                continue;
+            }
+            let entry = by_tool.entry(tool).or_insert_with(|| ToolCompute {
+                tool: tool.to_string(),
+                ..Default::default()
+            });
+            entry.calls += 1;
+            entry.cpu_secs += record.duration.map_or(0.0, |d| d.as_secs_f64());
+            entry.energy_wh += record.energy_wh;
+            entry.co2e_grams += record.co2e_grams;
+            entry.low_confidence |= record.low_confidence;
+        }
+        let mut tools: Vec<ToolCompute> = by_tool.into_values().collect();
+        tools.sort_by(|a, b| {
+            b.energy_wh
+                .total_cmp(&a.energy_wh)
+                .then_with(|| a.tool.cmp(&b.tool))
+        });
+        tools
+    }
+
+    /// Energy and CO2e of all tool compute, for adding to the session total
+    pub fn compute_footprint(&self) -> SessionFootprint {
+        self.compute_by_tool()
+            .iter()
+            .fold(SessionFootprint::default(), |total, tool| {
+                total
+                    + SessionFootprint {
+                        energy_wh: tool.energy_wh,
+                        co2e_grams: tool.co2e_grams,
+                    }
+            })
+    }
+
     /// Get total CO2e in grams
     pub fn total_co2e(&self) -> f64 {
         self.records.iter().map(|r| r.co2e_grams).sum()
@@ -1070,6 +1163,155 @@ impl CarbonEquivalents {
     }
 }
 
+/// Default power draw of one fully busy CPU core, in W. Roughly a desktop
+/// CPU's package power divided by its core count under an all-core build.
+pub const DEFAULT_CPU_WATTS: f64 = 15.0;
+
+/// Compute attributed to one tool over the session
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ToolCompute {
+    /// Tool name
+    pub tool: String,
+    /// Number of calls that used measurable compute
+    pub calls: usize,
+    /// CPU seconds (or wall-clock seconds when `low_confidence`)
+    pub cpu_secs: f64,
+    /// Estimated energy in Wh
+    pub energy_wh: f64,
+    /// Estimated CO2e in grams
+    pub co2e_grams: f64,
+    /// At least one call fell back to wall-clock time
+    pub low_confidence: bool,
+}
+
+/// Time spent by the processes a tool call spawned
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ComputeSample {
+    /// Seconds of CPU time, or of wall-clock time when not `measured`
+    pub secs: f64,
+    /// Whether `secs` is child CPU time rather than a wall-clock fallback
+    pub measured: bool,
+}
+
+/// Measures the CPU time of child processes reaped between `start` and
+/// `finish`, falling back to wall-clock time where that isn't available
+#[derive(Debug, Clone, Copy)]
+pub struct ComputeProbe {
+    children_cpu: Option<Duration>,
+    started: std::time::Instant,
+}
+
+impl ComputeProbe {
+    /// Take the starting reading
+    pub fn start() -> Self {
+        Self {
+            children_cpu: children_cpu_time(),
+            started: std::time::Instant::now(),
+        }
+    }
+
+    /// Compute used since `start`
+    pub fn finish(&self) -> ComputeSample {
+        match (self.children_cpu, children_cpu_time()) {
+            (Some(before), Some(after)) => ComputeSample {
+                secs: after.saturating_sub(before).as_secs_f64(),
+                measured: true,
+            },
+            _ => ComputeSample {
+                secs: self.started.elapsed().as_secs_f64(),
+                measured: false,
+            },
+        }
+    }
+}
+
+/// User plus system CPU time of all terminated, waited-for child processes
+#[cfg(unix)]
+fn children_cpu_time() -> Option<Duration> {
+    use nix::sys::resource::{getrusage, UsageWho};
+    let usage = getrusage(UsageWho::RUSAGE_CHILDREN).ok()?;
+    let secs = |tv: nix::sys::time::TimeVal| {
+        Duration::new(
+            tv.tv_sec().max(0) as u64,
+            (tv.tv_usec().max(0) * 1000) as u32,
+        )
+    };
+    Some(secs(usage.user_time()) + secs(usage.system_time()))
+}
+
+#[cfg(not(unix))]
+fn children_cpu_time() -> Option<Duration> {
+    None
+}
+
+/// Cumulative energy and emissions for an interactive session, estimated
+/// from the number of tokens processed.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SessionFootprint {
+    /// Estimated energy in Wh
+    pub energy_wh: f64,
+    /// Estimated CO2e in grams
+    pub co2e_grams: f64,
+}
+
+impl SessionFootprint {
+    /// Estimate from `tokens` at `wh_per_1k_tokens` on a grid of the given
+    /// intensity. Invalid factors (negative, NaN, infinite) yield zero rather
+    /// than propagating into the display.
+    pub fn from_tokens(tokens: u64, wh_per_1k_tokens: f64, grid: GridIntensity) -> Self {
+        let energy_wh = wh_per_1k_tokens * (tokens as f64 / 1000.0);
+        let co2e_grams = (energy_wh / 1000.0) * grid.grams_co2_per_kwh();
+        let valid = |v: f64| v.is_finite() && v >= 0.0;
+        if !valid(energy_wh) || !valid(co2e_grams) {
+            return Self::default();
+        }
+        Self {
+            energy_wh,
+            co2e_grams,
+        }
+    }
+
+    /// Short label for the status bar. `plain` avoids symbols and subscripts
+    /// for screen readers and ASCII-only terminals.
+    pub fn label(&self, plain: bool) -> String {
+        if plain {
+            format!(
+                "{} Wh, {} g CO2",
+                format_amount(self.energy_wh),
+                format_amount(self.co2e_grams)
+            )
+        } else {
+            format!(
+                "⚡{}Wh {}gCO₂",
+                format_amount(self.energy_wh),
+                format_amount(self.co2e_grams)
+            )
+        }
+    }
+}
+
+impl std::ops::Add for SessionFootprint {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            energy_wh: self.energy_wh + other.energy_wh,
+            co2e_grams: self.co2e_grams + other.co2e_grams,
+        }
+    }
+}
+
+/// Fewer decimals as values grow, so the status bar stays narrow.
+fn format_amount(value: f64) -> String {
+    if value >= 100.0 {
+        format!("{:.0}", value)
+    } else if value >= 10.0 {
+        format!("{:.1}", value)
+    } else {
+        format!("{:.2}", value)
+    }
+}
+
 /// Carbon budget
 #[derive(Debug)]
 pub struct CarbonBudget {
@@ -1146,6 +1388,91 @@ impl CarbonBudget {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_session_footprint_from_tokens() {
+        let fp = SessionFootprint::from_tokens(10_000, 0.1, GridIntensity::Custom(400.0));
+        assert!((fp.energy_wh - 1.0).abs() < 1e-9);
+        assert!((fp.co2e_grams - 0.4).abs() < 1e-9);
+        assert_eq!(fp.label(true), "1.00 Wh, 0.40 g CO2");
+        assert_eq!(fp.label(false), "⚡1.00Wh 0.40gCO₂");
+    }
+
+    #[test]
+    fn test_session_footprint_invalid_factors_are_zero() {
+        let nan = SessionFootprint::from_tokens(1000, f64::NAN, GridIntensity::Medium);
+        assert_eq!(nan, SessionFootprint::default());
+        let negative = SessionFootprint::from_tokens(1000, 0.1, GridIntensity::Custom(-5.0));
+        assert_eq!(negative, SessionFootprint::default());
+        assert_eq!(
+            SessionFootprint::default().label(true),
+            "0.00 Wh, 0.00 g CO2"
+        );
+    }
+
+    #[test]
+    fn test_record_compute_attributes_energy_per_tool() {
+        let mut tracker = CarbonTracker::new()
+            .with_calculator(EmissionCalculator::new().with_intensity(GridIntensity::Custom(500.0)))
+            .with_provider(CloudProvider::Gcp)
+            .with_cpu_watts(36.0);
+        tracker.track_llm_call(LlmModel::Local, 1000);
+        tracker.record_compute("cargo_test", 100.0);
+        tracker.record_compute("cargo_test", 200.0);
+        tracker.record_compute_wall_clock("npm_install", 50.0);
+        tracker.record_compute("cargo_check", 0.0);
+        tracker.record_compute("cargo_check", f64::NAN);
+
+        let tools = tracker.compute_by_tool();
+        assert_eq!(tools.len(), 2);
+        let cargo = &tools[0];
+        assert_eq!((cargo.tool.as_str(), cargo.calls), ("cargo_test", 2));
+        assert!((cargo.cpu_secs - 300.0).abs() < 1e-9);
+        // 36 W for 300 s = 3 Wh, at PUE 1.1 on a 500 g/kWh grid
+        assert!((cargo.energy_wh - 3.0).abs() < 1e-9);
+        assert!((cargo.co2e_grams - 3.0 * 1.1 * 0.5).abs() < 1e-9);
+        assert!(!cargo.low_confidence);
+        assert_eq!(tools[1].tool, "npm_install");
+        assert!(tools[1].low_confidence);
+
+        // Compute joins the tracker total; the footprint covers only tools
+        let footprint = tracker.compute_footprint();
+        assert!((footprint.energy_wh - 3.5).abs() < 1e-9);
+        assert!(tracker.total_energy() > footprint.energy_wh);
+        assert_eq!(
+            tracker
+                .by_source()
+                .get(&EmissionSource::CpuCompute)
+                .copied(),
+            Some(footprint.co2e_grams)
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_compute_probe_measures_child_cpu_time() {
+        let probe = ComputeProbe::start();
+        let status = std::process::Command::new("sh")
+            .args(["-c", "i=0; while [ $i -lt 200000 ]; do i=$((i+1)); done"])
+            .status()
+            .unwrap();
+        assert!(status.success());
+        let sample = probe.finish();
+        assert!(sample.measured);
+        assert!(sample.secs > 0.0, "{sample:?}");
+
+        let mut tracker = CarbonTracker::new();
+        tracker.record_sample("shell_exec", sample);
+        assert!(!tracker.compute_by_tool()[0].low_confidence);
+        tracker.record_sample(
+            "shell_exec",
+            ComputeSample {
+                secs: 1.0,
+                measured: false,
+            },
+        );
+        assert!(tracker.compute_by_tool()[0].low_confidence);
+    }
+
     #[test]
     fn test_emission_source_display() {
         assert_eq!(format!("{}", EmissionSource::LlmApiCall), "LLM API");
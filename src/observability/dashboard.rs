@@ -6,13 +6,52 @@
 //! - Tool success rates
 //! - Error tracking
 //! - CLI stats command
+//! - Live dashboard for a running task (`live`, behind `tui`)
 
 #![allow(dead_code, unused_imports, unused_variables)]
 
+#[cfg(feature = "tui")]
+pub mod live;
+
 use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// Figures for a running task, the same ones `/stats` and the status bar show
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunStats {
+    /// Estimated tokens in context
+    pub context_tokens: usize,
+    /// Model context window
+    pub context_window: usize,
+    /// API-reported tokens this session
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    /// Rough USD cost of those tokens
+    pub cost_usd: f64,
+    /// Zero when carbon tracking is off
+    pub footprint: super::carbon_tracker::SessionFootprint,
+    /// Current PDVR phase
+    pub phase: String,
+    /// Agent loop step
+    pub step: usize,
+    /// Plan steps completed (or skipped) out of `plan_total`
+    pub plan_done: usize,
+    pub plan_total: usize,
+    /// Description of the plan step in progress
+    pub plan_current: Option<String>,
+}
+
+impl RunStats {
+    /// Context usage in percent, capped at 100
+    pub fn context_pct(&self) -> f64 {
+        if self.context_window == 0 {
+            return 0.0;
+        }
+        (self.context_tokens as f64 / self.context_window as f64 * 100.0).min(100.0)
+    }
+}
+
 /// Token usage tracking
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TokenUsage {
@@ -0,0 +1,717 @@
+//! Live dashboard for a running task
+//!
+//! Shows context usage, tokens/cost/carbon, the PDVR phase, plan progress
+//! and recent tool calls, updated from [`TuiEvent`]s as the agent works.
+//! Panes toggle with `1`-`4`. When stderr is not a terminal, a one-line
+//! summary is printed periodically instead.
+
+use super::RunStats;
+use crate::ui::tui::{TuiEvent, TuiPalette};
+use anyhow::Result;
+use crossterm::{
+    cursor::Show as ShowCursor,
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::{Backend, CrosstermBackend},
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Gauge, List, ListItem, Paragraph},
+    Frame, Terminal,
+};
+use std::collections::VecDeque;
+use std::io::IsTerminal;
+use std::path::PathBuf;
+use std::sync::mpsc::{Receiver, TryRecvError};
+use std::time::{Duration, Instant};
+
+/// Tool calls kept for the tools pane
+const MAX_RECENT_TOOLS: usize = 50;
+
+/// How often the printed summary is repeated when nothing else changes
+pub const PRINT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Redraw at most this often
+const FRAME_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A pane of the live dashboard, toggled with its number key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pane {
+    Context,
+    Usage,
+    Progress,
+    Tools,
+}
+
+impl Pane {
+    pub const ALL: [Pane; 4] = [Pane::Context, Pane::Usage, Pane::Progress, Pane::Tools];
+
+    /// Pane toggled by a number key
+    pub fn from_key(c: char) -> Option<Self> {
+        match c {
+            '1' => Some(Self::Context),
+            '2' => Some(Self::Usage),
+            '3' => Some(Self::Progress),
+            '4' => Some(Self::Tools),
+            _ => None,
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::Context => "Context",
+            Self::Usage => "Usage",
+            Self::Progress => "Progress",
+            Self::Tools => "Tools",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Rows the pane needs; `None` takes the remaining space
+    fn height(self) -> Option<u16> {
+        match self {
+            Self::Context => Some(3),
+            Self::Usage => Some(5),
+            Self::Progress => Some(7),
+            Self::Tools => None,
+        }
+    }
+}
+
+/// A finished (or running) tool call in the tools pane
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolCall {
+    pub name: String,
+    /// `None` while running
+    pub success: Option<bool>,
+    pub duration_ms: u64,
+}
+
+/// How the watched task ended
+#[derive(Debug, Clone, PartialEq)]
+pub enum Finish {
+    Completed(String),
+    Failed(String),
+}
+
+/// State behind the live dashboard
+#[derive(Debug)]
+pub struct LiveDashboard {
+    task: String,
+    stats: RunStats,
+    tools: VecDeque<ToolCall>,
+    status: String,
+    visible: [bool; 4],
+    finish: Option<Finish>,
+    started: Instant,
+    /// Bumped on every applied event, so the printed mode knows when
+    /// something changed
+    revision: u64,
+}
+
+impl LiveDashboard {
+    pub fn new(task: &str) -> Self {
+        Self {
+            task: task.to_string(),
+            stats: RunStats::default(),
+            tools: VecDeque::new(),
+            status: "Starting".to_string(),
+            visible: [true; 4],
+            finish: None,
+            started: Instant::now(),
+            revision: 0,
+        }
+    }
+
+    pub fn stats(&self) -> &RunStats {
+        &self.stats
+    }
+
+    pub fn recent_tools(&self) -> impl Iterator<Item = &ToolCall> {
+        self.tools.iter()
+    }
+
+    pub fn finish(&self) -> Option<&Finish> {
+        self.finish.as_ref()
+    }
+
+    pub fn is_visible(&self, pane: Pane) -> bool {
+        self.visible[pane.index()]
+    }
+
+    pub fn toggle(&mut self, pane: Pane) {
+        self.visible[pane.index()] = !self.visible[pane.index()];
+    }
+
+    /// Update from an agent event
+    pub fn apply(&mut self, event: TuiEvent) {
+        self.revision += 1;
+        match event {
+            TuiEvent::AgentStarted => self.status = "Working".to_string(),
+            TuiEvent::AgentCompleted { message } => {
+                self.status = "Completed".to_string();
+                self.finish = Some(Finish::Completed(message));
+            }
+            TuiEvent::AgentError { message } => {
+                self.status = "Failed".to_string();
+                self.finish = Some(Finish::Failed(message));
+            }
+            TuiEvent::StatusUpdate { message } => self.status = message,
+            TuiEvent::ToolStarted { name } => {
+                self.tools.push_front(ToolCall {
+                    name,
+                    success: None,
+                    duration_ms: 0,
+                });
+                self.tools.truncate(MAX_RECENT_TOOLS);
+            }
+            TuiEvent::ToolCompleted {
+                name,
+                success,
+                duration_ms,
+            } => {
+                let completed = ToolCall {
+                    name,
+                    success: Some(success),
+                    duration_ms,
+                };
+                match self
+                    .tools
+                    .iter_mut()
+                    .find(|t| t.success.is_none() && t.name == completed.name)
+                {
+                    Some(running) => *running = completed,
+                    None => {
+                        self.tools.push_front(completed);
+                        self.tools.truncate(MAX_RECENT_TOOLS);
+                    }
+                }
+            }
+            // Per-response usage arrives between snapshots; keep totals current
+            TuiEvent::TokenUsage {
+                prompt_tokens,
+                completion_tokens,
+            } => {
+                self.stats.prompt_tokens += prompt_tokens;
+                self.stats.completion_tokens += completion_tokens;
+                self.stats.cost_usd = crate::output::estimate_cost_usd(
+                    self.stats.prompt_tokens,
+                    self.stats.completion_tokens,
+                );
+            }
+            TuiEvent::RunStats(stats) => self.stats = stats,
+            TuiEvent::GardenHealthUpdate { .. } | TuiEvent::Log { .. } => self.revision -= 1,
+        }
+    }
+
+    fn elapsed_label(&self) -> String {
+        let secs = self.started.elapsed().as_secs();
+        format!("{:02}:{:02}", secs / 60, secs % 60)
+    }
+
+    fn plan_label(&self) -> String {
+        if self.stats.plan_total == 0 {
+            "no plan".to_string()
+        } else {
+            format!("{}/{}", self.stats.plan_done, self.stats.plan_total)
+        }
+    }
+
+    fn tokens_label(&self) -> String {
+        let total = self.stats.prompt_tokens + self.stats.completion_tokens;
+        format!(
+            "{:.1}k tokens ${:.2}",
+            total as f64 / 1000.0,
+            self.stats.cost_usd
+        )
+    }
+
+    /// One-line summary for the printed (non-terminal) mode
+    pub fn summary(&self) -> String {
+        let s = &self.stats;
+        let mut line = format!(
+            "[watch {}] {} · step {} · {} · plan {} · context {:.1}% ({:.1}k/{:.0}k) · {}",
+            self.elapsed_label(),
+            self.status,
+            s.step + 1,
+            s.phase_or_default(),
+            self.plan_label(),
+            s.context_pct(),
+            s.context_tokens as f64 / 1000.0,
+            s.context_window as f64 / 1000.0,
+            self.tokens_label(),
+        );
+        if s.footprint != Default::default() {
+            line.push_str(&format!(" · {}", s.footprint.label(true)));
+        }
+        if let Some(tool) = self.tools.front() {
+            let outcome = match tool.success {
+                None => "running".to_string(),
+                Some(true) => format!("ok {}ms", tool.duration_ms),
+                Some(false) => format!("failed {}ms", tool.duration_ms),
+            };
+            line.push_str(&format!(" · last: {} {}", tool.name, outcome));
+        }
+        line
+    }
+
+    /// Draw the visible panes, a header and a key legend
+    pub fn render(&self, frame: &mut Frame, output_log: Option<&PathBuf>) {
+        let area = frame.area();
+        let panes: Vec<Pane> = Pane::ALL
+            .into_iter()
+            .filter(|p| self.is_visible(*p))
+            .collect();
+
+        let mut constraints = vec![Constraint::Length(1)];
+        constraints.extend(panes.iter().map(|p| match p.height() {
+            Some(h) => Constraint::Length(h),
+            None => Constraint::Min(3),
+        }));
+        if !panes.iter().any(|p| p.height().is_none()) {
+            constraints.push(Constraint::Min(0));
+        }
+        constraints.push(Constraint::Length(1));
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(constraints)
+            .split(area);
+
+        self.render_header(frame, chunks[0]);
+        for (pane, rect) in panes.iter().zip(chunks.iter().skip(1)) {
+            match pane {
+                Pane::Context => self.render_context(frame, *rect),
+                Pane::Usage => self.render_usage(frame, *rect),
+                Pane::Progress => self.render_progress(frame, *rect),
+                Pane::Tools => self.render_tools(frame, *rect),
+            }
+        }
+        self.render_legend(frame, chunks[chunks.len() - 1], output_log);
+    }
+
+    fn pane_block(pane: Pane) -> Block<'static> {
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(TuiPalette::border_style())
+            .title(Span::styled(
+                format!(" {} {} ", pane.index() + 1, pane.title()),
+                TuiPalette::title_style(),
+            ))
+    }
+
+    fn render_header(&self, frame: &mut Frame, area: Rect) {
+        let status_style = match self.finish {
+            Some(Finish::Completed(_)) => TuiPalette::success_style(),
+            Some(Finish::Failed(_)) => TuiPalette::error_style(),
+            None => Style::default().fg(TuiPalette::accent()),
+        };
+        let line = Line::from(vec![
+            Span::styled(
+                format!(" {} ", self.status),
+                status_style.add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(self.elapsed_label(), TuiPalette::muted_style()),
+            Span::raw("  "),
+            Span::styled(
+                self.task.clone(),
+                Style::default().fg(TuiPalette::primary()),
+            ),
+        ]);
+        frame.render_widget(Paragraph::new(line), area);
+    }
+
+    fn render_context(&self, frame: &mut Frame, area: Rect) {
+        let pct = self.stats.context_pct();
+        let color = if pct > 90.0 {
+            TuiPalette::error()
+        } else if pct > 70.0 {
+            TuiPalette::warning()
+        } else {
+            TuiPalette::success()
+        };
+        let gauge = Gauge::default()
+            .block(Self::pane_block(Pane::Context))
+            .gauge_style(Style::default().fg(color))
+            .ratio(pct / 100.0)
+            .label(format!(
+                "{:.1}% ({:.1}k / {:.0}k tokens)",
+                pct,
+                self.stats.context_tokens as f64 / 1000.0,
+                self.stats.context_window as f64 / 1000.0
+            ));
+        frame.render_widget(gauge, area);
+    }
+
+    fn render_usage(&self, frame: &mut Frame, area: Rect) {
+        let s = &self.stats;
+        let carbon = if s.footprint == Default::default() {
+            "off".to_string()
+        } else {
+            s.footprint.label(crate::ui::style::is_ascii_mode())
+        };
+        let lines = vec![
+            labelled(
+                "Tokens",
+                format!("{} in · {} out", s.prompt_tokens, s.completion_tokens),
+            ),
+            labelled("Cost", format!("${:.4}", s.cost_usd)),
+            labelled("Carbon", carbon),
+        ];
+        frame.render_widget(
+            Paragraph::new(lines).block(Self::pane_block(Pane::Usage)),
+            area,
+        );
+    }
+
+    fn render_progress(&self, frame: &mut Frame, area: Rect) {
+        let s = &self.stats;
+        let current = s.phase_or_default();
+        let mut phases = vec![Span::styled("Phase   ", TuiPalette::muted_style())];
+        for phase in ["plan", "do", "verify", "reflect"] {
+            let style = if phase == current {
+                TuiPalette::selected_style()
+            } else {
+                TuiPalette::muted_style()
+            };
+            phases.push(Span::styled(format!(" {} ", phase), style));
+        }
+
+        let block = Self::pane_block(Pane::Progress);
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Min(0),
+            ])
+            .split(inner);
+
+        frame.render_widget(Paragraph::new(Line::from(phases)), rows[0]);
+        frame.render_widget(
+            Paragraph::new(labelled("Step", (s.step + 1).to_string())),
+            rows[1],
+        );
+        let ratio = if s.plan_total == 0 {
+            0.0
+        } else {
+            s.plan_done as f64 / s.plan_total as f64
+        };
+        frame.render_widget(
+            Gauge::default()
+                .gauge_style(Style::default().fg(TuiPalette::success()))
+                .ratio(ratio.min(1.0))
+                .label(format!("plan {}", self.plan_label())),
+            rows[2],
+        );
+        if let Some(current) = &s.plan_current {
+            frame.render_widget(Paragraph::new(labelled("Now", current.clone())), rows[3]);
+        }
+    }
+
+    fn render_tools(&self, frame: &mut Frame, area: Rect) {
+        let items: Vec<ListItem> = self
+            .tools
+            .iter()
+            .map(|tool| {
+                let (mark, style) = match tool.success {
+                    None => ("…", Style::default().fg(TuiPalette::accent())),
+                    Some(true) => ("✓", TuiPalette::success_style()),
+                    Some(false) => ("✗", TuiPalette::error_style()),
+                };
+                let duration = match tool.success {
+                    None => String::new(),
+                    Some(_) => format!("{}ms", tool.duration_ms),
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!(" {} ", mark), style),
+                    Span::styled(
+                        format!("{:<28}", tool.name),
+                        Style::default().fg(TuiPalette::tool()),
+                    ),
+                    Span::styled(duration, TuiPalette::muted_style()),
+                ]))
+            })
+            .collect();
+        frame.render_widget(List::new(items).block(Self::pane_block(Pane::Tools)), area);
+    }
+
+    fn render_legend(&self, frame: &mut Frame, area: Rect, output_log: Option<&PathBuf>) {
+        let mut spans = Vec::new();
+        for pane in Pane::ALL {
+            let style = if self.is_visible(pane) {
+                TuiPalette::title_style()
+            } else {
+                TuiPalette::muted_style()
+            };
+            spans.push(Span::styled(
+                format!(" {} {} ", pane.index() + 1, pane.title()),
+                style,
+            ));
+        }
+        spans.push(Span::styled(" · q close", TuiPalette::muted_style()));
+        if let Some(path) = output_log {
+            spans.push(Span::styled(
+                format!(" · output: {}", path.display()),
+                TuiPalette::muted_style(),
+            ));
+        }
+        frame.render_widget(Paragraph::new(Line::from(spans)), area);
+    }
+}
+
+impl RunStats {
+    fn phase_or_default(&self) -> &str {
+        if self.phase.is_empty() {
+            "plan"
+        } else {
+            &self.phase
+        }
+    }
+}
+
+fn labelled(label: &str, value: String) -> Line<'static> {
+    Line::from(vec![
+        Span::styled(format!("{:<8}", label), TuiPalette::muted_style()),
+        Span::raw(value),
+    ])
+}
+
+/// Watch `events` until the sender is dropped. In a terminal the dashboard
+/// stays up after the task ends until closed with `q`; closing it early
+/// leaves the task running with its normal output.
+pub fn run(events: Receiver<TuiEvent>, task: &str) -> Result<()> {
+    let dashboard = LiveDashboard::new(task);
+    if std::io::stderr().is_terminal() && std::io::stdout().is_terminal() {
+        run_terminal(dashboard, events)
+    } else {
+        run_printed(dashboard, events, PRINT_INTERVAL);
+        Ok(())
+    }
+}
+
+/// Drain pending events; false once the sender is gone
+fn drain(dashboard: &mut LiveDashboard, events: &Receiver<TuiEvent>) -> bool {
+    loop {
+        match events.try_recv() {
+            Ok(event) => dashboard.apply(event),
+            Err(TryRecvError::Empty) => return true,
+            Err(TryRecvError::Disconnected) => return false,
+        }
+    }
+}
+
+/// Print a summary when something changed, at most every `interval`, and
+/// once more at the end
+fn run_printed(mut dashboard: LiveDashboard, events: Receiver<TuiEvent>, interval: Duration) {
+    let mut printed_revision = 0;
+    let mut last_print = Instant::now();
+    loop {
+        let connected = match events.recv_timeout(interval.min(Duration::from_secs(1))) {
+            Ok(event) => {
+                dashboard.apply(event);
+                drain(&mut dashboard, &events)
+            }
+            Err(std::sync::mpsc::RecvTimeoutError::Timeout) => true,
+            Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => false,
+        };
+        if !connected {
+            println!("{}", dashboard.summary());
+            return;
+        }
+        if dashboard.revision != printed_revision && last_print.elapsed() >= interval {
+            println!("{}", dashboard.summary());
+            printed_revision = dashboard.revision;
+            last_print = Instant::now();
+        }
+    }
+}
+
+/// Agent output is sent here while the dashboard covers the terminal
+fn output_log_path() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("selfware")
+        .join("logs")
+        .join("watch-output.log")
+}
+
+/// Full-screen dashboard drawn on stderr, with stdout sent to a log file
+fn run_terminal(mut dashboard: LiveDashboard, events: Receiver<TuiEvent>) -> Result<()> {
+    let log_path = output_log_path();
+    if let Some(parent) = log_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let log_file = std::fs::File::create(&log_path)?;
+    let redirect = crate::output::StdoutRedirect::to_file(&log_file);
+
+    enable_raw_mode()?;
+    let mut stderr = std::io::stderr();
+    execute!(stderr, EnterAlternateScreen)?;
+    let original_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        restore_stderr_terminal();
+        original_hook(info);
+    }));
+    let mut terminal = Terminal::new(CrosstermBackend::new(stderr))?;
+
+    let result = draw_until_closed(&mut terminal, &mut dashboard, &events, &log_path);
+    restore_stderr_terminal();
+    let _ = terminal.show_cursor();
+    drop(redirect);
+
+    // Closed early: the task keeps running with plain output
+    if dashboard.finish().is_none() {
+        eprintln!(
+            "Live dashboard closed; output so far is in {}",
+            log_path.display()
+        );
+    }
+    result
+}
+
+fn draw_until_closed<B: Backend>(
+    terminal: &mut Terminal<B>,
+    dashboard: &mut LiveDashboard,
+    events: &Receiver<TuiEvent>,
+    log_path: &PathBuf,
+) -> Result<()>
+where
+    B::Error: Send + Sync + 'static,
+{
+    loop {
+        // Keep showing the final state after the sender is dropped
+        let _ = drain(dashboard, events);
+        terminal.draw(|frame| dashboard.render(frame, Some(log_path)))?;
+
+        if !event::poll(FRAME_INTERVAL)? {
+            continue;
+        }
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    return Ok(())
+                }
+                KeyCode::Char(c) => {
+                    if let Some(pane) = Pane::from_key(c) {
+                        dashboard.toggle(pane);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+fn restore_stderr_terminal() {
+    let _ = disable_raw_mode();
+    let _ = execute!(std::io::stderr(), LeaveAlternateScreen, ShowCursor);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+
+    fn stats() -> RunStats {
+        RunStats {
+            context_tokens: 45_000,
+            context_window: 100_000,
+            prompt_tokens: 9_000,
+            completion_tokens: 1_000,
+            cost_usd: 0.042,
+            phase: "verify".to_string(),
+            step: 3,
+            plan_done: 2,
+            plan_total: 5,
+            plan_current: Some("Run the tests".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_live_dashboard_tracks_events() {
+        let mut dashboard = LiveDashboard::new("fix the build");
+        dashboard.apply(TuiEvent::RunStats(stats()));
+        dashboard.apply(TuiEvent::ToolStarted {
+            name: "cargo_check".into(),
+        });
+        assert_eq!(dashboard.recent_tools().next().unwrap().success, None);
+        dashboard.apply(TuiEvent::ToolCompleted {
+            name: "cargo_check".into(),
+            success: false,
+            duration_ms: 3200,
+        });
+        dashboard.apply(TuiEvent::TokenUsage {
+            prompt_tokens: 1_000,
+            completion_tokens: 500,
+        });
+
+        let tools: Vec<&ToolCall> = dashboard.recent_tools().collect();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].success, Some(false));
+        assert_eq!(dashboard.stats().prompt_tokens, 10_000);
+        assert_eq!(dashboard.stats().completion_tokens, 1_500);
+
+        let summary = dashboard.summary();
+        assert!(summary.contains("step 4"));
+        assert!(summary.contains("verify"));
+        assert!(summary.contains("plan 2/5"));
+        assert!(summary.contains("context 45.0%"));
+        assert!(summary.contains("last: cargo_check failed 3200ms"));
+
+        dashboard.apply(TuiEvent::AgentCompleted {
+            message: "done".into(),
+        });
+        assert_eq!(dashboard.finish(), Some(&Finish::Completed("done".into())));
+    }
+
+    #[test]
+    fn test_live_dashboard_toggles_panes_by_number() {
+        let mut dashboard = LiveDashboard::new("task");
+        dashboard.apply(TuiEvent::RunStats(stats()));
+        assert!(Pane::ALL.iter().all(|p| dashboard.is_visible(*p)));
+
+        dashboard.toggle(Pane::from_key('2').unwrap());
+        assert!(!dashboard.is_visible(Pane::Usage));
+        assert_eq!(Pane::from_key('9'), None);
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        terminal
+            .draw(|frame| dashboard.render(frame, None))
+            .unwrap();
+        let text: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(text.contains("1 Context"));
+        assert!(!text.contains("Carbon"));
+        assert!(text.contains("Run the tests"));
+        assert!(text.contains("45.0%"));
+    }
+
+    #[test]
+    fn test_printed_mode_ends_with_summary_when_sender_drops() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        tx.send(TuiEvent::RunStats(stats())).unwrap();
+        drop(tx);
+        // Returns rather than waiting for the interval
+        run_printed(LiveDashboard::new("task"), rx, Duration::from_secs(3600));
+    }
+}
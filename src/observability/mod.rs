@@ -5,11 +5,15 @@
 //! - Usage analytics
 //! - Log analysis
 //! - Carbon tracking
+//! - Run profiling
+//! - Task notifications
 //! - Test dashboards
 
 pub mod analytics;
 pub mod carbon_tracker;
 pub mod dashboard;
+pub mod notifications;
+pub mod profile;
 pub mod telemetry;
 pub mod test_dashboard;
 
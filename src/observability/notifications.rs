@@ -0,0 +1,313 @@
+//! Notifications when a task ends
+//!
+//! Each `[[notifications.hooks]]` entry fires when a task completes, fails or
+//! pauses: a desktop notification, a webhook POST (e.g. a Slack incoming
+//! webhook) or a shell command. All of them get the same payload (task id,
+//! status, duration, summary) with secrets redacted. A hook that fails is
+//! logged and never fails the task.
+
+use std::process::Stdio;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use tokio::io::AsyncWriteExt;
+use tracing::{info, warn};
+
+use crate::config::{NotificationEvent, NotificationHook, NotificationKind, NotificationsConfig};
+use crate::safety::redact::redact_secrets;
+use crate::ui::animations::format_duration;
+
+/// How long a webhook or command hook may take
+const HOOK_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Characters of the task summary put in the payload
+const MAX_SUMMARY_CHARS: usize = 500;
+
+/// Message used when a hook has no template
+const DEFAULT_TEMPLATE: &str = "Task {task_id} {status} after {duration}: {summary}";
+
+/// What a hook is told about a finished task
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskNotification {
+    pub task_id: String,
+    pub event: NotificationEvent,
+    /// The precise outcome, e.g. `timed_out` for a paused event
+    pub status: String,
+    pub duration_secs: u64,
+    pub summary: String,
+}
+
+impl TaskNotification {
+    /// Build the payload, cutting the summary down and redacting secrets
+    pub fn new(
+        task_id: &str,
+        event: NotificationEvent,
+        status: &str,
+        duration: Duration,
+        summary: &str,
+    ) -> Self {
+        let summary: String = summary.trim().chars().take(MAX_SUMMARY_CHARS).collect();
+        Self {
+            task_id: task_id.to_string(),
+            event,
+            status: status.to_string(),
+            duration_secs: duration.as_secs(),
+            summary: redact_secrets(&summary).into_owned(),
+        }
+    }
+
+    /// Fill `{task_id}`, `{status}`, `{duration}` and `{summary}` in
+    /// `template`, passing each value through `escape`
+    fn render(&self, template: &str, escape: impl Fn(&str) -> String) -> String {
+        template
+            .replace("{task_id}", &escape(&self.task_id))
+            .replace("{status}", &escape(&self.status))
+            .replace(
+                "{duration}",
+                &escape(&format_duration(Duration::from_secs(self.duration_secs))),
+            )
+            .replace("{summary}", &escape(&self.summary))
+    }
+
+    /// The hook's message in plain text
+    fn message(&self, hook: &NotificationHook) -> String {
+        self.render(
+            hook.template.as_deref().unwrap_or(DEFAULT_TEMPLATE),
+            str::to_string,
+        )
+    }
+}
+
+/// Escape `value` for use inside a JSON string literal
+fn json_escape(value: &str) -> String {
+    let quoted = serde_json::Value::String(value.to_string()).to_string();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+/// Fires the configured hooks at a task's terminal state
+pub struct Notifier {
+    hooks: Vec<NotificationHook>,
+    client: reqwest::Client,
+}
+
+impl Notifier {
+    pub fn new(config: &NotificationsConfig) -> Self {
+        Self {
+            hooks: config.hooks.clone(),
+            client: reqwest::Client::builder()
+                .timeout(HOOK_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Fire every hook subscribed to the notification's event. Failures are
+    /// logged and otherwise ignored.
+    pub async fn notify(&self, notification: &TaskNotification) {
+        for hook in &self.hooks {
+            if !hook.on.is_empty() && !hook.on.contains(&notification.event) {
+                continue;
+            }
+            match self.fire(hook, notification).await {
+                Ok(()) => info!(
+                    "Sent {:?} notification for task {}",
+                    hook.kind, notification.task_id
+                ),
+                Err(e) => warn!(
+                    "{:?} notification for task {} failed: {:#}",
+                    hook.kind, notification.task_id, e
+                ),
+            }
+        }
+    }
+
+    async fn fire(&self, hook: &NotificationHook, notification: &TaskNotification) -> Result<()> {
+        match hook.kind {
+            NotificationKind::Webhook => self.post(hook, notification).await,
+            NotificationKind::Command => run_command(hook, notification).await,
+            NotificationKind::Desktop => desktop(notification, &notification.message(hook)).await,
+        }
+    }
+
+    async fn post(&self, hook: &NotificationHook, notification: &TaskNotification) -> Result<()> {
+        let url = hook.url.as_deref().context("webhook hook has no url")?;
+        let body = match &hook.template {
+            Some(template) => notification.render(template, json_escape),
+            None => serde_json::to_string(notification)?,
+        };
+        let response = self
+            .client
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body)
+            .send()
+            .await
+            .context("webhook request failed")?;
+        if !response.status().is_success() {
+            bail!("webhook returned HTTP {}", response.status());
+        }
+        Ok(())
+    }
+}
+
+/// Run a command hook with the payload on stdin and as environment variables
+async fn run_command(hook: &NotificationHook, notification: &TaskNotification) -> Result<()> {
+    let command = hook
+        .command
+        .as_deref()
+        .context("command hook has no command")?;
+    let (shell, flag) = if cfg!(windows) {
+        ("cmd", "/C")
+    } else {
+        ("sh", "-c")
+    };
+    let mut child = tokio::process::Command::new(shell)
+        .arg(flag)
+        .arg(command)
+        .env("SELFWARE_TASK_ID", &notification.task_id)
+        .env("SELFWARE_TASK_STATUS", &notification.status)
+        .env(
+            "SELFWARE_TASK_DURATION_SECS",
+            notification.duration_secs.to_string(),
+        )
+        .env("SELFWARE_TASK_SUMMARY", &notification.summary)
+        .env("SELFWARE_NOTIFICATION", notification.message(hook))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .with_context(|| format!("failed to start '{}'", command))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        let payload = serde_json::to_vec(notification)?;
+        // A command that ignores its input may close stdin early
+        let _ = stdin.write_all(&payload).await;
+    }
+    let output = tokio::time::timeout(HOOK_TIMEOUT, child.wait_with_output())
+        .await
+        .with_context(|| format!("'{}' timed out", command))??;
+    if !output.status.success() {
+        bail!(
+            "'{}' exited with {}: {}",
+            command,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Show a desktop notification with the platform's own tool
+async fn desktop(notification: &TaskNotification, message: &str) -> Result<()> {
+    let title = format!("selfware: task {}", notification.status);
+    let mut command = if cfg!(target_os = "macos") {
+        let script = format!(
+            "display notification {} with title {}",
+            serde_json::Value::String(message.to_string()),
+            serde_json::Value::String(title)
+        );
+        let mut command = tokio::process::Command::new("osascript");
+        command.arg("-e").arg(script);
+        command
+    } else if cfg!(unix) {
+        let mut command = tokio::process::Command::new("notify-send");
+        command.arg(title).arg(message);
+        command
+    } else {
+        bail!("desktop notifications are not supported on this platform");
+    };
+    let status = command
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .await
+        .context("failed to run the desktop notifier")?;
+    if !status.success() {
+        bail!("desktop notifier exited with {}", status);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hook(kind: NotificationKind) -> NotificationHook {
+        NotificationHook {
+            kind,
+            url: None,
+            command: None,
+            on: Vec::new(),
+            template: None,
+        }
+    }
+
+    #[test]
+    fn test_payload_redacts_secrets_and_renders_templates() {
+        let notification = TaskNotification::new(
+            "task-1",
+            NotificationEvent::Failed,
+            "failed",
+            Duration::from_secs(125),
+            "Auth failed with key sk-abcdefghijklmnopqrstuvwxyz0123456789 \"quoted\"",
+        );
+        assert!(!notification.summary.contains("abcdefghijklmnop"));
+
+        let mut slack = hook(NotificationKind::Webhook);
+        slack.template =
+            Some(r#"{"text": "{task_id} {status} after {duration}: {summary}"}"#.into());
+        let body = notification.render(slack.template.as_deref().unwrap(), json_escape);
+        let parsed: serde_json::Value = serde_json::from_str(&body).unwrap();
+        let text = parsed["text"].as_str().unwrap();
+        assert!(text.starts_with("task-1 failed after 2m 05s: Auth failed"));
+        assert!(text.ends_with("\"quoted\""));
+
+        assert!(notification
+            .message(&hook(NotificationKind::Desktop))
+            .starts_with("Task task-1 failed after 2m 05s"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_notify_runs_subscribed_hooks_and_survives_failures() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("out.json");
+        let status = dir.path().join("status");
+        let mut command = hook(NotificationKind::Command);
+        command.command = Some(format!(
+            "cat > {} && echo \"$SELFWARE_TASK_STATUS\" > {}",
+            out.display(),
+            status.display()
+        ));
+        command.on = vec![NotificationEvent::Completed];
+        let mut broken = hook(NotificationKind::Webhook);
+        broken.url = Some("http://127.0.0.1:9/unreachable".into());
+        let notifier = Notifier::new(&NotificationsConfig {
+            hooks: vec![broken, command],
+        });
+
+        let paused =
+            TaskNotification::new("t", NotificationEvent::Paused, "paused", Duration::ZERO, "");
+        notifier.notify(&paused).await;
+        assert!(!out.exists());
+
+        let done = TaskNotification::new(
+            "t",
+            NotificationEvent::Completed,
+            "completed",
+            Duration::from_secs(3),
+            "All tests pass",
+        );
+        notifier.notify(&done).await;
+        let payload: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&out).unwrap()).unwrap();
+        assert_eq!(payload["event"], "completed");
+        assert_eq!(payload["duration_secs"], 3);
+        assert_eq!(payload["summary"], "All tests pass");
+        assert_eq!(
+            std::fs::read_to_string(&status).unwrap().trim(),
+            "completed"
+        );
+    }
+}
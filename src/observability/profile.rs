@@ -0,0 +1,363 @@
+//! Run profiling for `selfware run --profile`
+//!
+//! A [`Profiler`] is a tracing layer that times the spans the agent already
+//! emits: model requests, tool calls, context compression, `@file`
+//! expansion and confirmation prompts. Each span's wall time is split into
+//! its own time and the time of profiled spans nested inside it, so the
+//! breakdown never counts anything twice. Whatever no span covers is the
+//! agent's own work and idle time.
+//!
+//! The layer is only installed when profiling was enabled before tracing
+//! started; without it these spans have no subscriber interested in them
+//! and cost next to nothing.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context as _, Result};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::Subscriber;
+use tracing_subscriber::layer::{Context, Filter, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+/// Spans the profiler times; everything else is ignored
+const PROFILED_SPANS: &[&str] = &[
+    "api.request",
+    "api.stream",
+    "tool.call",
+    "tool.confirm",
+    "context.compress",
+    "context.references",
+];
+
+/// Root frame of the folded stacks, holding time no span covers
+const ROOT_FRAME: &str = "selfware";
+
+static PROFILER: OnceLock<Profiler> = OnceLock::new();
+
+/// Turn on profiling for this process. Must run before tracing is
+/// initialized, which is when the layer gets installed.
+pub fn enable() {
+    let _ = PROFILER.get_or_init(Profiler::default);
+}
+
+/// The process-wide profiler, if profiling was enabled
+pub fn profiler() -> Option<&'static Profiler> {
+    PROFILER.get()
+}
+
+/// Time collected for one profiled span name or stack
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct Timing {
+    calls: usize,
+    time: Duration,
+}
+
+#[derive(Debug, Default)]
+struct Samples {
+    /// Total time of outermost spans, by frame
+    roots: BTreeMap<String, Timing>,
+    /// Own time of every span, by `;`-joined stack of frames
+    stacks: BTreeMap<String, Duration>,
+}
+
+/// Collects span timings; see the module docs
+#[derive(Debug, Clone, Default)]
+pub struct Profiler {
+    samples: Arc<Mutex<Samples>>,
+}
+
+/// Per-span state kept in the span's extensions
+struct OpenSpan {
+    frame: String,
+    opened: Instant,
+    /// Wall time of profiled spans that closed inside this one
+    nested: Duration,
+}
+
+/// Picks `tool_name` out of a `tool.call` span's fields
+#[derive(Default)]
+struct ToolName(Option<String>);
+
+impl Visit for ToolName {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "tool_name" {
+            self.0 = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "tool_name" {
+            self.0 = Some(format!("{:?}", value).trim_matches('"').to_string());
+        }
+    }
+}
+
+/// Only lets the profiled spans through to the profiler
+struct ProfiledSpans;
+
+impl<S> Filter<S> for ProfiledSpans {
+    fn enabled(&self, meta: &tracing::Metadata<'_>, _: &Context<'_, S>) -> bool {
+        meta.is_span() && PROFILED_SPANS.contains(&meta.name())
+    }
+}
+
+impl Profiler {
+    /// The tracing layer that feeds this profiler
+    pub fn layer<S>(&self) -> impl Layer<S> + Send + Sync + 'static
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        self.clone().with_filter(ProfiledSpans)
+    }
+
+    /// Summarize everything recorded so far against the run's wall time
+    pub fn report(&self, total: Duration) -> ProfileReport {
+        let samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        let mut rows: Vec<ProfileRow> = samples
+            .roots
+            .iter()
+            .map(|(frame, timing)| ProfileRow {
+                label: frame_label(frame),
+                calls: timing.calls,
+                time: timing.time,
+            })
+            .collect();
+        rows.sort_by(|a, b| b.time.cmp(&a.time).then_with(|| a.label.cmp(&b.label)));
+        let covered: Duration = samples.roots.values().map(|t| t.time).sum();
+        ProfileReport {
+            total,
+            rows,
+            unaccounted: total.saturating_sub(covered),
+            stacks: samples.stacks.clone(),
+        }
+    }
+}
+
+impl<S> Layer<S> for Profiler
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        let name = attrs.metadata().name();
+        let frame = if name == "tool.call" {
+            let mut tool = ToolName::default();
+            attrs.record(&mut tool);
+            format!("tool.call:{}", tool.0.as_deref().unwrap_or("unknown"))
+        } else {
+            name.to_string()
+        };
+        span.extensions_mut().insert(OpenSpan {
+            frame,
+            opened: Instant::now(),
+            nested: Duration::ZERO,
+        });
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else { return };
+        let Some(open) = span.extensions_mut().remove::<OpenSpan>() else {
+            return;
+        };
+        let elapsed = open.opened.elapsed();
+        let own = elapsed.saturating_sub(open.nested);
+
+        let mut stack = vec![ROOT_FRAME.to_string()];
+        for ancestor in span.scope().skip(1).collect::<Vec<_>>().into_iter().rev() {
+            if let Some(parent) = ancestor.extensions().get::<OpenSpan>() {
+                stack.push(parent.frame.clone());
+            }
+        }
+        stack.push(open.frame.clone());
+
+        let parent = span.parent();
+        if let Some(parent) = &parent {
+            if let Some(parent) = parent.extensions_mut().get_mut::<OpenSpan>() {
+                parent.nested += elapsed;
+            }
+        }
+
+        let mut samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        *samples.stacks.entry(stack.join(";")).or_default() += own;
+        if parent.is_none() {
+            let root = samples.roots.entry(open.frame).or_default();
+            root.calls += 1;
+            root.time += elapsed;
+        }
+    }
+}
+
+/// Table label for a frame
+fn frame_label(frame: &str) -> String {
+    match frame {
+        "api.request" => "Model request".to_string(),
+        "api.stream" => "Model streaming".to_string(),
+        "tool.confirm" => "Waiting for confirmation".to_string(),
+        "context.compress" => "Context compression".to_string(),
+        "context.references" => "@file expansion".to_string(),
+        _ => match frame.strip_prefix("tool.call:") {
+            Some(tool) => format!("Tool {}", tool),
+            None => frame.to_string(),
+        },
+    }
+}
+
+/// One row of the profile table
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfileRow {
+    pub label: String,
+    pub calls: usize,
+    pub time: Duration,
+}
+
+/// Where a run's time went
+#[derive(Debug, Clone)]
+pub struct ProfileReport {
+    /// Wall time of the whole run
+    pub total: Duration,
+    /// Outermost profiled spans, slowest first
+    pub rows: Vec<ProfileRow>,
+    /// Time outside any profiled span: the agent's own work and idling
+    pub unaccounted: Duration,
+    stacks: BTreeMap<String, Duration>,
+}
+
+impl ProfileReport {
+    /// Plain-text table of time per category with share of the run
+    pub fn render_table(&self) -> String {
+        let total = self.total.as_secs_f64().max(f64::EPSILON);
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "{:<32} {:>6} {:>10} {:>6}",
+            "Where", "Calls", "Time", "Share"
+        );
+        let other = ProfileRow {
+            label: "Agent and idle".to_string(),
+            calls: 0,
+            time: self.unaccounted,
+        };
+        for row in self.rows.iter().chain(std::iter::once(&other)) {
+            let calls = if row.calls == 0 {
+                "-".to_string()
+            } else {
+                row.calls.to_string()
+            };
+            let _ = writeln!(
+                out,
+                "{:<32} {:>6} {:>9.2}s {:>5.1}%",
+                row.label,
+                calls,
+                row.time.as_secs_f64(),
+                row.time.as_secs_f64() / total * 100.0
+            );
+        }
+        let _ = write!(
+            out,
+            "{:<32} {:>6} {:>9.2}s {:>5.1}%",
+            "Total",
+            "",
+            self.total.as_secs_f64(),
+            100.0
+        );
+        out
+    }
+
+    /// Folded stacks (`frame;frame microseconds` per line), the input
+    /// format of flamegraph.pl and inferno
+    pub fn to_folded(&self) -> String {
+        let mut out = String::new();
+        let unaccounted = self.unaccounted.as_micros();
+        if unaccounted > 0 {
+            let _ = writeln!(out, "{} {}", ROOT_FRAME, unaccounted);
+        }
+        for (stack, own) in &self.stacks {
+            let micros = own.as_micros();
+            if micros > 0 {
+                let _ = writeln!(out, "{} {}", stack, micros);
+            }
+        }
+        out
+    }
+
+    /// Write [`Self::to_folded`] to `path`
+    pub fn write_folded(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, self.to_folded())
+            .with_context(|| format!("Failed to write profile to {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn test_profiler_splits_nested_time_and_folds_stacks() {
+        let profiler = Profiler::default();
+        let subscriber = tracing_subscriber::registry().with(profiler.layer());
+        tracing::subscriber::with_default(subscriber, || {
+            for _ in 0..2 {
+                let _tool = tracing::info_span!("tool.call", tool_name = "cargo_test").entered();
+                std::thread::sleep(Duration::from_millis(5));
+            }
+            let stream = tracing::info_span!("api.stream").entered();
+            {
+                let _request =
+                    tracing::info_span!("api.request", model = "mock", streaming = true).entered();
+                std::thread::sleep(Duration::from_millis(5));
+            }
+            // Not profiled, and does not break the stack
+            let _step = tracing::info_span!("agent.step").entered();
+            drop(stream);
+        });
+
+        let report = profiler.report(Duration::from_secs(1));
+        let calls: Vec<(&str, usize)> = report
+            .rows
+            .iter()
+            .map(|row| (row.label.as_str(), row.calls))
+            .collect();
+        assert_eq!(calls.len(), 2, "{:?}", calls);
+        assert!(calls.contains(&("Tool cargo_test", 2)), "{:?}", calls);
+        assert!(calls.contains(&("Model streaming", 1)), "{:?}", calls);
+        assert!(report
+            .rows
+            .iter()
+            .all(|row| row.time >= Duration::from_millis(5)));
+        let covered: Duration = report.rows.iter().map(|row| row.time).sum();
+        assert_eq!(report.unaccounted, Duration::from_secs(1) - covered);
+
+        let folded = report.to_folded();
+        let stacks: Vec<&str> = folded
+            .lines()
+            .map(|line| line.rsplit_once(' ').unwrap().0)
+            .collect();
+        assert!(stacks.contains(&"selfware"), "{}", folded);
+        assert!(
+            stacks.contains(&"selfware;tool.call:cargo_test"),
+            "{}",
+            folded
+        );
+        assert!(
+            stacks.contains(&"selfware;api.stream;api.request"),
+            "{}",
+            folded
+        );
+        assert!(!folded.contains("agent.step"), "{}", folded);
+
+        let table = report.render_table();
+        assert!(table.contains("Tool cargo_test"), "{}", table);
+        assert!(table.contains("Agent and idle"), "{}", table);
+        assert!(
+            table.lines().last().unwrap().starts_with("Total"),
+            "{}",
+            table
+        );
+    }
+}
@@ -9,14 +9,15 @@
 //! - Configurable sampling rate for non-error events
 //! - Log rotation with configurable entry limits
 
+use crate::config::TelemetryConfig;
 use metrics_exporter_prometheus::PrometheusBuilder;
 use regex::Regex;
-use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::{Mutex, OnceLock};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tracing::Instrument;
-use tracing::{error, info, info_span, Span};
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+use tracing::{error, info, info_span, warn, Span};
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer as _};
 
 /// Maximum number of in-memory log entries before rotation.
 /// When this limit is reached, `rotate_if_needed()` will discard the oldest half.
@@ -157,14 +158,33 @@ pub fn redact_secrets(input: &str) -> String {
     result
 }
 
+/// Filter for spans exported over OTLP, independent of `RUST_LOG`
+const OTLP_FILTER: &str = "selfware=info";
+
+/// Per-request timeout for OTLP exports, so an unreachable collector
+/// cannot stall shutdown for long
+const OTLP_EXPORT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Whether an OTLP tracer provider was installed (and must be flushed)
+static OTLP_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// Whether a failed OTLP export has been reported; later failures are silent
+static OTLP_ERROR_REPORTED: AtomicBool = AtomicBool::new(false);
+
 /// Initialize global tracing subscriber with configurable output
 /// By default, only enables tracing if RUST_LOG is explicitly set
 pub fn init_tracing() {
+    init_tracing_with_config(&TelemetryConfig::default())
+}
+
+/// Initialize tracing, exporting spans over OTLP when an endpoint is
+/// configured (`OTEL_EXPORTER_OTLP_ENDPOINT` overrides `telemetry.otlp_endpoint`).
+/// Local output still only appears when RUST_LOG is set.
+pub fn init_tracing_with_config(config: &TelemetryConfig) {
     // Only initialize verbose tracing if RUST_LOG is set
     // Otherwise use a quiet "error-only" mode to avoid polluting CLI output
-    if std::env::var("RUST_LOG").is_ok() {
-        init_tracing_with_filter(&std::env::var("RUST_LOG").unwrap_or_else(|_| "info".to_string()));
-    }
+    let filter = std::env::var("RUST_LOG").ok();
+    init_subscriber(filter.as_deref(), config);
 }
 
 /// Initialize tracing only for debug/verbose mode
@@ -174,70 +194,142 @@ pub fn init_tracing_verbose() {
 
 /// Initialize with custom filter string, file log rotation, and OpenTelemetry
 pub fn init_tracing_with_filter(filter: &str) {
+    init_subscriber(Some(filter), &TelemetryConfig::default());
+}
+
+/// OTLP endpoint to export to, if any
+fn otlp_endpoint(config: &TelemetryConfig) -> Option<String> {
+    std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT")
+        .ok()
+        .or_else(|| config.otlp_endpoint.clone())
+        .filter(|e| !e.trim().is_empty())
+}
+
+fn init_subscriber(filter: Option<&str>, config: &TelemetryConfig) {
     // Skip if already initialized
     use std::sync::Once;
     static INIT: Once = Once::new();
 
     INIT.call_once(|| {
-        let filter_layer = EnvFilter::try_new(filter).unwrap_or_else(|_| EnvFilter::new("warn"));
-
-        let fmt_layer = tracing_subscriber::fmt::layer()
-            .with_target(false)
-            .with_thread_ids(false)
-            .with_thread_names(false)
-            .with_file(false)
-            .with_line_number(false)
-            .with_level(true)
-            .compact()
-            .with_writer(std::io::stderr); // Write to stderr, not stdout
-
-        // Implement Log Rotation with daily rolling
-        let log_dir = dirs::data_local_dir()
-            .unwrap_or_else(|| std::path::PathBuf::from("."))
-            .join("selfware")
-            .join("logs");
-        let _ = std::fs::create_dir_all(&log_dir);
-        let file_appender = tracing_appender::rolling::daily(log_dir, "selfware.log");
-        let (non_blocking, guard) = tracing_appender::non_blocking(file_appender);
-        // Store the guard so the background thread stays alive; drop via shutdown_tracing()
-        let _ = TRACING_GUARD.set(Mutex::new(Some(guard)));
-
-        let file_layer = tracing_subscriber::fmt::layer()
-            .with_writer(non_blocking)
-            .with_ansi(false)
-            .with_file(true)
-            .with_line_number(true);
-
-        // OpenTelemetry setup (if endpoint provided via env)
-        let subscriber = tracing_subscriber::registry()
-            .with(filter_layer)
-            .with(fmt_layer)
-            .with(file_layer);
-
-        if let Ok(endpoint) = std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT") {
-            use opentelemetry_otlp::WithExportConfig;
-            if let Ok(tracer) = opentelemetry_otlp::new_pipeline()
-                .tracing()
-                .with_exporter(
-                    opentelemetry_otlp::new_exporter()
-                        .tonic()
-                        .with_endpoint(endpoint),
-                )
-                .install_batch(opentelemetry_sdk::runtime::Tokio)
-            {
-                let telemetry = tracing_opentelemetry::layer().with_tracer(tracer);
-                let _ = subscriber.with(telemetry).try_init();
-                return; // Early return to avoid double init
+        let local_layers = filter.map(|filter| {
+            let filter_layer =
+                EnvFilter::try_new(filter).unwrap_or_else(|_| EnvFilter::new("warn"));
+
+            let fmt_layer = tracing_subscriber::fmt::layer()
+                .with_target(false)
+                .with_thread_ids(false)
+                .with_thread_names(false)
+                .with_file(false)
+                .with_line_number(false)
+                .with_level(true)
+                .compact()
+                .with_writer(std::io::stderr); // Write to stderr, not stdout
+
+            // Implement Log Rotation with daily rolling
+            let log_dir = dirs::data_local_dir()
+                .unwrap_or_else(|| std::path::PathBuf::from("."))
+                .join("selfware")
+                .join("logs");
+            let _ = std::fs::create_dir_all(&log_dir);
+            let file_appender = tracing_appender::rolling::daily(log_dir, "selfware.log");
+            let (non_blocking, guard) = tracing_appender::non_blocking(file_appender);
+            // Store the guard so the background thread stays alive; drop via shutdown_tracing()
+            let _ = TRACING_GUARD.set(Mutex::new(Some(guard)));
+
+            let file_layer = tracing_subscriber::fmt::layer()
+                .with_writer(non_blocking)
+                .with_ansi(false)
+                .with_file(true)
+                .with_line_number(true);
+
+            fmt_layer.and_then(file_layer).with_filter(filter_layer)
+        });
+
+        // A bad endpoint or missing runtime leaves tracing local-only
+        let mut otlp_error = None;
+        let otlp_layer = otlp_endpoint(config).and_then(|endpoint| {
+            match install_otlp_tracer(&endpoint, config.service_name()) {
+                Ok(tracer) => Some(
+                    tracing_opentelemetry::layer()
+                        .with_tracer(tracer)
+                        .with_filter(EnvFilter::new(OTLP_FILTER)),
+                ),
+                Err(e) => {
+                    otlp_error = Some(format!("{}: {}", endpoint, e));
+                    None
+                }
             }
+        });
+
+        // Only installed for `--profile`, so its spans cost nothing otherwise
+        let profile_layer = super::profile::profiler().map(|profiler| profiler.layer());
+
+        let _ = tracing_subscriber::registry()
+            .with(local_layers)
+            .with(otlp_layer)
+            .with(profile_layer)
+            .try_init();
+
+        if let Some(e) = otlp_error {
+            warn!("OTLP export disabled, traces stay local ({})", e);
         }
+    });
+}
 
-        let _ = subscriber.try_init();
+/// Install a batching OTLP exporter as the global tracer provider.
+///
+/// The gRPC channel connects lazily, so an unreachable collector never
+/// blocks startup; failed exports are dropped and reported once.
+fn install_otlp_tracer(
+    endpoint: &str,
+    service_name: &str,
+) -> anyhow::Result<opentelemetry_sdk::trace::Tracer> {
+    use opentelemetry_otlp::WithExportConfig;
+
+    // install_batch spawns its worker on the current Tokio runtime
+    if tokio::runtime::Handle::try_current().is_err() {
+        anyhow::bail!("no async runtime to run the exporter on");
+    }
+
+    let _ = opentelemetry::global::set_error_handler(|e| {
+        if !OTLP_ERROR_REPORTED.swap(true, Ordering::Relaxed) {
+            warn!(
+                "OTLP export failed, further export errors are suppressed: {}",
+                e
+            );
+        }
     });
+
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(endpoint)
+                .with_timeout(OTLP_EXPORT_TIMEOUT),
+        )
+        .with_trace_config(opentelemetry_sdk::trace::config().with_resource(
+            opentelemetry_sdk::Resource::new(vec![opentelemetry::KeyValue::new(
+                "service.name",
+                service_name.to_string(),
+            )]),
+        ))
+        .with_batch_config(
+            opentelemetry_sdk::trace::BatchConfig::default()
+                .with_max_export_timeout(OTLP_EXPORT_TIMEOUT),
+        )
+        .install_batch(opentelemetry_sdk::runtime::Tokio)?;
+    OTLP_ACTIVE.store(true, Ordering::Relaxed);
+    Ok(tracer)
 }
 
 /// Flush and shut down the tracing background writer.
 /// Call this during graceful shutdown to ensure all logs are flushed.
 pub fn shutdown_tracing() {
+    if OTLP_ACTIVE.swap(false, Ordering::Relaxed) {
+        // Flushes spans still queued in the batch exporter
+        opentelemetry::global::shutdown_tracer_provider();
+    }
     if let Some(guard_slot) = TRACING_GUARD.get() {
         if let Ok(mut slot) = guard_slot.lock() {
             drop(slot.take()); // Drop the guard, flushing the writer
@@ -414,6 +506,54 @@ pub fn enter_agent_step(state: &str, step: usize) -> tracing::span::Span {
     span
 }
 
+/// Span for one tool call made by the agent; close it with [`record_span_outcome`]
+pub fn tool_call_span(tool_name: &str) -> Span {
+    let safe_name = sanitize_for_log(tool_name);
+    info_span!(
+        "tool.call",
+        tool_name = safe_name.as_str(),
+        status = tracing::field::Empty,
+        duration_ms = tracing::field::Empty,
+        result_tokens = tracing::field::Empty,
+        error = tracing::field::Empty,
+        otel.status_code = tracing::field::Empty,
+    )
+}
+
+/// Span for one chat completion request, covering its retries
+pub fn api_request_span(model: &str, streaming: bool) -> Span {
+    let safe_model = sanitize_for_log(model);
+    info_span!(
+        "api.request",
+        model = safe_model.as_str(),
+        streaming = streaming,
+        status = tracing::field::Empty,
+        attempts = tracing::field::Empty,
+        duration_ms = tracing::field::Empty,
+        prompt_tokens = tracing::field::Empty,
+        completion_tokens = tracing::field::Empty,
+        error = tracing::field::Empty,
+        otel.status_code = tracing::field::Empty,
+    )
+}
+
+/// Record how a span's operation ended. `status` is "ok", "error" or a more
+/// specific failure such as "timeout"; `error` marks the span failed.
+pub fn record_span_outcome(span: &Span, status: &str, duration_ms: u64, error: Option<&str>) {
+    span.record("status", status);
+    span.record("duration_ms", duration_ms);
+    match error {
+        Some(error) => {
+            let safe_err = redact_secrets(&sanitize_for_log(error));
+            span.record("error", safe_err.as_str());
+            span.record("otel.status_code", "ERROR");
+        }
+        None => {
+            span.record("otel.status_code", "OK");
+        }
+    }
+}
+
 /// Record agent state transition
 pub fn record_state_transition(from: &str, to: &str) {
     let safe_from = sanitize_for_log(from);
@@ -428,6 +568,50 @@ pub fn record_state_transition(from: &str, to: &str) {
     increment_log_count();
 }
 
+/// Record a mid-session model switch
+pub fn record_model_switch(from: &str, to: &str) {
+    let safe_from = sanitize_for_log(from);
+    let safe_to = sanitize_for_log(to);
+    info!(
+        from = safe_from.as_str(),
+        to = safe_to.as_str(),
+        "Model switched"
+    );
+    metrics::increment_counter!("selfware_model_switches_total");
+    increment_log_count();
+}
+
+/// Record an API circuit breaker state change and the error or event behind it
+pub fn record_circuit_transition(from: &str, to: &str, trigger: Option<&str>) {
+    let safe_trigger = trigger.map(|t| redact_secrets(&sanitize_for_log(t)));
+    info!(
+        from = from,
+        to = to,
+        trigger = safe_trigger.as_deref().unwrap_or("none"),
+        "Circuit breaker state changed"
+    );
+    metrics::increment_counter!("selfware_circuit_transitions_total", "to" => to.to_string());
+    increment_log_count();
+}
+
+/// Record the thinking budget picked for a task so budgets can be tuned per task type
+pub fn record_thinking_budget(task_type: &str, budget: Option<u32>) {
+    let label = budget.map_or_else(|| "unbounded".to_string(), |b| b.to_string());
+    info!(
+        task_type = task_type,
+        thinking_budget = label.as_str(),
+        "Thinking budget selected"
+    );
+    if let Some(budget) = budget {
+        metrics::histogram!(
+            "selfware_thinking_budget_tokens",
+            budget as f64,
+            "task_type" => task_type.to_string()
+        );
+    }
+    increment_log_count();
+}
+
 /// Initialize tracing for tests with a simple subscriber
 #[cfg(test)]
 pub fn init_test_tracing() {
@@ -463,6 +647,71 @@ mod tests {
         record_state_transition("Planning", "Executing");
     }
 
+    /// Collects fields recorded on spans after creation
+    #[derive(Clone, Default)]
+    struct RecordedFields(std::sync::Arc<Mutex<Vec<(String, String)>>>);
+
+    impl tracing::field::Visit for RecordedFields {
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            self.0
+                .lock()
+                .unwrap()
+                .push((field.name().to_string(), format!("{:?}", value)));
+        }
+    }
+
+    impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for RecordedFields {
+        fn on_record(
+            &self,
+            _id: &tracing::span::Id,
+            values: &tracing::span::Record<'_>,
+            _ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            values.record(&mut self.clone());
+        }
+    }
+
+    #[test]
+    fn test_record_span_outcome_marks_errors() {
+        let recorded = RecordedFields::default();
+        let subscriber = tracing_subscriber::registry().with(recorded.clone());
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tool_call_span("shell_exec");
+            record_span_outcome(&span, "timeout", 42, Some("token-abcdefghijkl leaked"));
+            let span = api_request_span("qwen", false);
+            record_span_outcome(&span, "ok", 7, None);
+        });
+
+        let fields = recorded.0.lock().unwrap().clone();
+        let get = |name: &str| -> Vec<String> {
+            fields
+                .iter()
+                .filter(|(n, _)| n == name)
+                .map(|(_, v)| v.clone())
+                .collect()
+        };
+        assert_eq!(get("status"), vec!["\"timeout\"", "\"ok\""]);
+        assert_eq!(get("duration_ms"), vec!["42", "7"]);
+        assert_eq!(get("otel.status_code"), vec!["\"ERROR\"", "\"OK\""]);
+        assert_eq!(get("error"), vec!["\"[REDACTED] leaked\""]);
+    }
+
+    #[test]
+    fn test_otlp_endpoint_ignores_blank_config() {
+        if std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT").is_ok() {
+            return;
+        }
+        let mut config = TelemetryConfig::default();
+        assert_eq!(otlp_endpoint(&config), None);
+        config.otlp_endpoint = Some("  ".to_string());
+        assert_eq!(otlp_endpoint(&config), None);
+        config.otlp_endpoint = Some("http://localhost:4317".to_string());
+        assert_eq!(
+            otlp_endpoint(&config).as_deref(),
+            Some("http://localhost:4317")
+        );
+    }
+
     #[test]
     fn test_enter_agent_step_returns_span() {
         // Ensure enter_agent_step creates a valid span
@@ -648,6 +897,12 @@ mod tests {
         record_success();
     }
 
+    #[test]
+    fn test_record_circuit_transition_does_not_panic() {
+        record_circuit_transition("closed", "open", Some("api_key=sk-secret123 refused"));
+        record_circuit_transition("open", "closed", None);
+    }
+
     #[test]
     fn test_record_state_transition_same_state() {
         record_state_transition("Running", "Running");
@@ -13,6 +13,11 @@ use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+
+/// Dashboard the test tools record their runs into
+static GLOBAL_DASHBOARD: LazyLock<Mutex<TestDashboard>> =
+    LazyLock::new(|| Mutex::new(TestDashboard::new()));
 
 /// Test status
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
@@ -1194,6 +1199,18 @@ impl TestDashboard {
         }
     }
 
+    /// Process-wide dashboard shared with the test tools
+    pub fn global() -> &'static Mutex<TestDashboard> {
+        &GLOBAL_DASHBOARD
+    }
+
+    /// Record a run whose results are already known
+    pub fn record_run(&mut self, results: Vec<Test>) {
+        self.update_explorer(&results);
+        self.start_run().results = results;
+        self.complete_run();
+    }
+
     /// Start a new test run
     pub fn start_run(&mut self) -> &mut TestRun {
         self.current_run = Some(TestRun::new());
@@ -2016,6 +2033,19 @@ mod tests {
         assert_eq!(dashboard.failure_streak(), 0);
     }
 
+    #[test]
+    fn test_test_dashboard_record_run() {
+        let mut dashboard = TestDashboard::new();
+        let mut failing = Test::new("a::b".to_string());
+        failing.fail(Duration::milliseconds(3), "boom".to_string());
+        dashboard.record_run(vec![failing]);
+
+        assert_eq!(dashboard.history.len(), 1);
+        assert_eq!(dashboard.last_run().unwrap().status, RunStatus::Failed);
+        assert_eq!(dashboard.failure_streak(), 1);
+        assert!(dashboard.current_run.is_none());
+    }
+
     #[test]
     fn test_test_dashboard_update_explorer() {
         let mut dashboard = TestDashboard::new();
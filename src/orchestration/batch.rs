@@ -0,0 +1,433 @@
+//! Headless batch runs
+//!
+//! `selfware batch tasks.txt` runs a list of tasks one after another without
+//! supervision. Each task gets a fresh agent and a checkpoint whose ID is
+//! derived from the batch file and the task text, so failures can be
+//! inspected with `journal-entry`/`resume` and `--continue` can skip work
+//! that already completed.
+//!
+//! Two file formats are accepted:
+//! - plain text: one task per line, blank lines and `#` comments ignored
+//! - YAML (`.yaml`/`.yml`): a list whose items are either a task string or a
+//!   map with `task` and optional `mode`, `scope`, `on_tool_error` and `id`
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use crate::agent::task_result::{TaskOutcome, TaskResult};
+use crate::agent::Agent;
+use crate::checkpoint::{TaskCheckpoint, TaskStatus};
+use crate::config::{Config, ExecutionMode, ToolErrorPolicy};
+
+/// Hex characters of the task hash kept in derived checkpoint IDs.
+const TASK_HASH_CHARS: usize = 8;
+
+/// One task from a batch file.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct BatchTask {
+    pub task: String,
+    /// Execution mode for this task; batches default to daemon autonomy.
+    #[serde(default)]
+    pub mode: Option<ExecutionMode>,
+    /// Crate directory the task may touch, relative to the working directory.
+    #[serde(default)]
+    pub scope: Option<String>,
+    /// `strict` or `lenient` handling of failed tool calls for this task;
+    /// `agent.on_tool_error` otherwise.
+    #[serde(default)]
+    pub on_tool_error: Option<ToolErrorPolicy>,
+    /// Checkpoint ID override; derived from the file and task text otherwise.
+    #[serde(default)]
+    pub id: Option<String>,
+}
+
+impl BatchTask {
+    fn new(task: impl Into<String>) -> Self {
+        Self {
+            task: task.into(),
+            mode: None,
+            scope: None,
+            on_tool_error: None,
+            id: None,
+        }
+    }
+
+    /// Apply the task's mode, scope and tool error policy to a copy of the
+    /// base config.
+    fn config(&self, base: &Config) -> Config {
+        let mut config = base.clone();
+        config.execution_mode = self.mode.unwrap_or(ExecutionMode::Daemon);
+        if let Some(policy) = self.on_tool_error {
+            config.agent.on_tool_error = policy;
+        }
+        if let Some(scope) = self.scope_dir() {
+            config.safety.allowed_paths = vec![format!("./{}/**", scope)];
+        }
+        config
+    }
+
+    /// The prompt sent to the agent, naming the scope when one is set.
+    fn prompt(&self) -> String {
+        match self.scope_dir() {
+            Some(scope) => format!(
+                "{}\n\nOnly modify files inside the `{}` crate; changes elsewhere will be rejected.",
+                self.task, scope
+            ),
+            None => self.task.clone(),
+        }
+    }
+
+    fn scope_dir(&self) -> Option<&str> {
+        let scope = self.scope.as_deref()?;
+        let scope = scope.trim().trim_start_matches("./").trim_end_matches('/');
+        (!scope.is_empty()).then_some(scope)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum YamlEntry {
+    Task(String),
+    Detailed(BatchTask),
+}
+
+/// Read a batch file, choosing the format from its extension.
+pub fn load_batch(path: &Path) -> Result<Vec<BatchTask>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read batch file: {}", path.display()))?;
+    let is_yaml = matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("yaml" | "yml")
+    );
+    let tasks = if is_yaml {
+        parse_yaml(&content).with_context(|| format!("Invalid batch file: {}", path.display()))?
+    } else {
+        parse_lines(&content)
+    };
+    if tasks.is_empty() {
+        bail!("Batch file {} contains no tasks", path.display());
+    }
+    Ok(tasks)
+}
+
+fn parse_lines(content: &str) -> Vec<BatchTask> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(BatchTask::new)
+        .collect()
+}
+
+fn parse_yaml(content: &str) -> Result<Vec<BatchTask>> {
+    let entries: Vec<YamlEntry> = serde_yaml::from_str(content)?;
+    let mut tasks = Vec::with_capacity(entries.len());
+    for (i, entry) in entries.into_iter().enumerate() {
+        let task = match entry {
+            YamlEntry::Task(task) => BatchTask::new(task),
+            YamlEntry::Detailed(task) => task,
+        };
+        if task.task.trim().is_empty() {
+            bail!("Task {} has an empty description", i + 1);
+        }
+        tasks.push(task);
+    }
+    Ok(tasks)
+}
+
+/// Stable checkpoint ID for the task at `index`: editing a task's text
+/// gives it a new ID, so `--continue` reruns it.
+pub fn checkpoint_id(batch_file: &Path, index: usize, task: &BatchTask) -> String {
+    if let Some(id) = &task.id {
+        return id.clone();
+    }
+    let stem = batch_file
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("batch");
+    let digest = format!("{:x}", Sha256::digest(task.task.as_bytes()));
+    format!(
+        "batch-{}-{:03}-{}",
+        stem,
+        index + 1,
+        &digest[..TASK_HASH_CHARS]
+    )
+}
+
+/// Options from the `batch` command line.
+#[derive(Debug, Clone, Default)]
+pub struct BatchOptions {
+    /// Stop at the first task that does not complete.
+    pub fail_fast: bool,
+    /// Skip tasks whose checkpoint is already marked completed.
+    pub skip_completed: bool,
+}
+
+/// Final state of one batch task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchTaskStatus {
+    Completed,
+    Failed,
+    Interrupted,
+    Incomplete,
+    TimedOut,
+    Paused,
+    /// Already completed in an earlier run (`--continue`).
+    Skipped,
+}
+
+impl From<TaskOutcome> for BatchTaskStatus {
+    fn from(outcome: TaskOutcome) -> Self {
+        match outcome {
+            TaskOutcome::Completed => Self::Completed,
+            TaskOutcome::Failed => Self::Failed,
+            TaskOutcome::Interrupted => Self::Interrupted,
+            TaskOutcome::Incomplete => Self::Incomplete,
+            TaskOutcome::TimedOut => Self::TimedOut,
+            TaskOutcome::Paused => Self::Paused,
+        }
+    }
+}
+
+/// Report entry for one task.
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchTaskReport {
+    pub index: usize,
+    pub task: String,
+    pub task_id: String,
+    pub status: BatchTaskStatus,
+    pub duration_secs: f64,
+    /// Absent for skipped tasks and tasks whose agent failed to start.
+    pub result: Option<TaskResult>,
+    pub error: Option<String>,
+}
+
+/// Summary written at the end of a batch.
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchReport {
+    pub batch_file: PathBuf,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    /// Tasks in the file, including any never started after a stop.
+    pub total_tasks: usize,
+    /// True when `--fail-fast` or an interrupt ended the batch early.
+    pub stopped_early: bool,
+    pub tasks: Vec<BatchTaskReport>,
+}
+
+impl BatchReport {
+    pub fn count(&self, status: BatchTaskStatus) -> usize {
+        self.tasks.iter().filter(|t| t.status == status).count()
+    }
+
+    /// True when every task in the file completed now or in an earlier run.
+    pub fn succeeded(&self) -> bool {
+        self.tasks.len() == self.total_tasks
+            && self.tasks.iter().all(|t| {
+                matches!(
+                    t.status,
+                    BatchTaskStatus::Completed | BatchTaskStatus::Skipped
+                )
+            })
+    }
+
+    /// Write the report as pretty JSON.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, json)
+            .with_context(|| format!("Failed to write batch report: {}", path.display()))
+    }
+}
+
+/// Default report location: `<batch file stem>.report.json` beside the file.
+pub fn default_report_path(batch_file: &Path) -> PathBuf {
+    let stem = batch_file
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("batch");
+    batch_file.with_file_name(format!("{}.report.json", stem))
+}
+
+/// Run every task in `batch_file` sequentially, each with its own agent and
+/// checkpoint. A task that fails is recorded and the batch moves on unless
+/// `fail_fast` is set; an interrupt always stops the batch.
+pub async fn run_batch(
+    config: &Config,
+    batch_file: &Path,
+    options: &BatchOptions,
+) -> Result<BatchReport> {
+    let tasks = load_batch(batch_file)?;
+    let mut report = BatchReport {
+        batch_file: batch_file.to_path_buf(),
+        started_at: Utc::now(),
+        finished_at: Utc::now(),
+        total_tasks: tasks.len(),
+        stopped_early: false,
+        tasks: Vec::with_capacity(tasks.len()),
+    };
+
+    for (index, task) in tasks.iter().enumerate() {
+        let task_id = checkpoint_id(batch_file, index, task);
+        let started = Instant::now();
+
+        let already_done = options.skip_completed
+            && Agent::task_status(&task_id)
+                .map(|c| c.status == TaskStatus::Completed)
+                .unwrap_or(false);
+        let (status, result, error) = if already_done {
+            (BatchTaskStatus::Skipped, None, None)
+        } else {
+            run_one(config, task, &task_id).await
+        };
+
+        report.tasks.push(BatchTaskReport {
+            index,
+            task: task.task.clone(),
+            task_id,
+            status,
+            duration_secs: started.elapsed().as_secs_f64(),
+            result,
+            error,
+        });
+
+        let stop = status == BatchTaskStatus::Interrupted
+            || (options.fail_fast
+                && !matches!(
+                    status,
+                    BatchTaskStatus::Completed | BatchTaskStatus::Skipped
+                ));
+        if stop {
+            report.stopped_early = index + 1 < tasks.len();
+            break;
+        }
+    }
+
+    report.finished_at = Utc::now();
+    Ok(report)
+}
+
+async fn run_one(
+    config: &Config,
+    task: &BatchTask,
+    task_id: &str,
+) -> (BatchTaskStatus, Option<TaskResult>, Option<String>) {
+    let mut agent = match Agent::new(task.config(config)).await {
+        Ok(agent) => agent,
+        Err(e) => return (BatchTaskStatus::Failed, None, Some(format!("{:#}", e))),
+    };
+    agent.current_checkpoint = Some(TaskCheckpoint::new(task_id.to_string(), task.task.clone()));
+    match agent.run_task(&task.prompt()).await {
+        Ok(result) => {
+            let error = result
+                .failure()
+                .map(|reason| format!("Agent failed: {}", reason));
+            (result.status.into(), Some(result), error)
+        }
+        Err(e) => (
+            BatchTaskStatus::Failed,
+            Some(agent.failed_task_result(&e)),
+            Some(format!("{:#}", e)),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lines_skips_blanks_and_comments() {
+        let tasks = parse_lines("# overnight\nFix clippy warnings\n\n  Add docs to api  \n");
+        assert_eq!(
+            tasks,
+            vec![
+                BatchTask::new("Fix clippy warnings"),
+                BatchTask::new("Add docs to api")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_yaml_mixes_strings_and_options() {
+        let yaml = "- Bump dependencies\n- task: Split parser module\n  mode: autoedit\n  scope: crates/parser/\n  on_tool_error: strict\n";
+        let tasks = parse_yaml(yaml).unwrap();
+        assert_eq!(tasks[0], BatchTask::new("Bump dependencies"));
+        assert_eq!(tasks[1].mode, Some(ExecutionMode::AutoEdit));
+        assert_eq!(tasks[1].scope_dir(), Some("crates/parser"));
+        assert!(tasks[1].prompt().contains("`crates/parser` crate"));
+
+        let config = tasks[1].config(&Config::default());
+        assert_eq!(config.execution_mode, ExecutionMode::AutoEdit);
+        assert_eq!(config.safety.allowed_paths, vec!["./crates/parser/**"]);
+        assert_eq!(config.agent.on_tool_error, ToolErrorPolicy::Strict);
+        let first = tasks[0].config(&Config::default());
+        assert_eq!(first.execution_mode, ExecutionMode::Daemon);
+        assert_eq!(first.agent.on_tool_error, ToolErrorPolicy::Lenient);
+    }
+
+    #[test]
+    fn test_checkpoint_id_is_stable_and_tracks_task_text() {
+        let file = Path::new("/work/nightly.txt");
+        let task = BatchTask::new("Fix clippy warnings");
+        let id = checkpoint_id(file, 0, &task);
+        assert!(id.starts_with("batch-nightly-001-"));
+        assert_eq!(id, checkpoint_id(file, 0, &task));
+        assert_ne!(
+            id,
+            checkpoint_id(file, 0, &BatchTask::new("Fix all clippy warnings"))
+        );
+
+        let pinned = BatchTask {
+            id: Some("release-notes".into()),
+            ..BatchTask::new("Write release notes")
+        };
+        assert_eq!(checkpoint_id(file, 3, &pinned), "release-notes");
+    }
+
+    #[test]
+    fn test_load_batch_rejects_empty_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tasks.txt");
+        std::fs::write(&path, "# nothing yet\n").unwrap();
+        assert!(load_batch(&path).is_err());
+        assert_eq!(
+            default_report_path(&path),
+            dir.path().join("tasks.report.json")
+        );
+    }
+
+    #[test]
+    fn test_report_success_requires_every_task() {
+        let entry = |status| BatchTaskReport {
+            index: 0,
+            task: "t".into(),
+            task_id: "id".into(),
+            status,
+            duration_secs: 0.0,
+            result: None,
+            error: None,
+        };
+        let mut report = BatchReport {
+            batch_file: PathBuf::from("tasks.txt"),
+            started_at: Utc::now(),
+            finished_at: Utc::now(),
+            total_tasks: 2,
+            stopped_early: false,
+            tasks: vec![
+                entry(BatchTaskStatus::Skipped),
+                entry(BatchTaskStatus::Completed),
+            ],
+        };
+        assert!(report.succeeded());
+        report.tasks[1].status = BatchTaskStatus::Failed;
+        assert!(!report.succeeded());
+        assert_eq!(report.count(BatchTaskStatus::Failed), 1);
+        report.tasks.pop();
+        assert!(!report.succeeded());
+    }
+}
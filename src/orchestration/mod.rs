@@ -7,7 +7,9 @@
 //! - Swarm agents
 //! - Multi-agent coordination
 //! - Planning
+//! - Headless batch runs
 
+pub mod batch;
 pub mod multiagent;
 pub mod planning;
 pub mod swarm;
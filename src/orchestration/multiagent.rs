@@ -4,7 +4,7 @@
 //!
 //! Features:
 //! - Concurrent agent execution with configurable parallelism
-//! - Streaming responses from all agents
+//! - Streaming responses from all agents, shown in per-agent panes
 //! - Task distribution and coordination
 //! - Shared context and results aggregation
 
@@ -15,11 +15,12 @@ use tokio::sync::{mpsc, Mutex, RwLock, Semaphore};
 use tokio::task::JoinSet;
 
 use crate::api::types::Message;
-use crate::api::{ApiClient, ThinkingMode};
+use crate::api::{ApiClient, StreamChunk, ThinkingMode};
 use crate::config::Config;
 use crate::swarm::AgentRole;
 use crate::tool_parser::parse_tool_calls;
 use crate::tools::ToolRegistry;
+use crate::ui::panes::{PaneSet, PaneStatus, DEFAULT_PANE_LINES};
 
 /// Maximum number of concurrent agent streams
 pub const MAX_CONCURRENT_AGENTS: usize = 16;
@@ -125,6 +126,8 @@ pub enum MultiAgentEvent {
         name: String,
         task: String,
     },
+    /// A piece of an agent's streamed response, tagged with the agent so
+    /// renderers can route it to that agent's pane
     AgentProgress {
         agent_id: usize,
         content: String,
@@ -147,6 +150,68 @@ pub enum MultiAgentEvent {
     },
 }
 
+impl MultiAgentEvent {
+    /// Agent the event belongs to, or `None` for run-wide events
+    pub fn agent_id(&self) -> Option<usize> {
+        match self {
+            Self::AgentStarted { agent_id, .. }
+            | Self::AgentProgress { agent_id, .. }
+            | Self::AgentToolCall { agent_id, .. }
+            | Self::AgentCompleted { agent_id, .. }
+            | Self::AgentFailed { agent_id, .. } => Some(*agent_id),
+            Self::AllCompleted { .. } => None,
+        }
+    }
+}
+
+/// How often the interactive view redraws agent panes
+const PANE_REDRAW_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How often an agent's buffered stream text is forwarded
+const CHUNK_FLUSH_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Forward buffered stream text early once it reaches this many bytes
+const CHUNK_FLUSH_BYTES: usize = 512;
+
+/// Batches one agent's streamed text so it sends a few larger progress
+/// events instead of one per token
+#[derive(Debug)]
+struct ChunkCoalescer {
+    buffer: String,
+    last_flush: Instant,
+}
+
+impl ChunkCoalescer {
+    fn new() -> Self {
+        Self {
+            buffer: String::new(),
+            last_flush: Instant::now(),
+        }
+    }
+
+    /// Buffer `text`, returning a batch when one is due
+    fn push(&mut self, text: &str) -> Option<String> {
+        self.buffer.push_str(text);
+        if self.buffer.len() >= CHUNK_FLUSH_BYTES
+            || self.last_flush.elapsed() >= CHUNK_FLUSH_INTERVAL
+        {
+            self.flush()
+        } else {
+            None
+        }
+    }
+
+    /// Take whatever is buffered
+    fn flush(&mut self) -> Option<String> {
+        self.last_flush = Instant::now();
+        if self.buffer.is_empty() {
+            None
+        } else {
+            Some(std::mem::take(&mut self.buffer))
+        }
+    }
+}
+
 /// Multi-agent chat orchestrator
 ///
 /// NOTE: This struct uses `tokio::sync::RwLock` and `tokio::sync::Mutex`, which do NOT
@@ -260,6 +325,7 @@ impl MultiAgentChat {
             let timeout = Duration::from_secs(self.config.timeout_secs);
             let event_tx = self.event_tx.clone();
             let failure_policy = self.config.failure_policy;
+            let streaming = self.config.streaming;
             let cancelled = Arc::clone(&cancelled);
 
             join_set.spawn(async move {
@@ -269,7 +335,8 @@ impl MultiAgentChat {
                         Ok(())
                     }
                     res = Self::run_single_agent(
-                        agent_id, task, client, tools, semaphore, agents, results, timeout, event_tx,
+                        agent_id, task, client, tools, semaphore, agents, results, timeout,
+                        streaming, event_tx,
                     ) => {
                         if failure_policy == MultiAgentFailurePolicy::FailFast && res.is_err() {
                             cancelled.notify_waiters();
@@ -344,6 +411,7 @@ impl MultiAgentChat {
         agents: Arc<RwLock<Vec<AgentInstance>>>,
         results: Arc<Mutex<Vec<AgentResult>>>,
         timeout: Duration,
+        streaming: bool,
         event_tx: Option<mpsc::Sender<MultiAgentEvent>>,
     ) -> Result<()> {
         // Acquire semaphore permit
@@ -376,20 +444,16 @@ impl MultiAgentChat {
         messages.push(Message::user(&task));
 
         // Call the API with timeout
-        let result =
-            tokio::time::timeout(timeout, client.chat(messages, None, ThinkingMode::Disabled))
-                .await;
+        let result = tokio::time::timeout(
+            timeout,
+            Self::fetch_response(&client, messages, agent_id, streaming, event_tx.as_ref()),
+        )
+        .await;
 
         let duration = start.elapsed();
 
         let agent_result = match result {
-            Ok(Ok(response)) => {
-                let content = response
-                    .choices
-                    .first()
-                    .map(|c| c.message.content.text().to_string())
-                    .unwrap_or_default();
-
+            Ok(Ok(content)) => {
                 // Parse any tool calls
                 let parsed = parse_tool_calls(&content);
                 let tool_calls: Vec<String> = parsed
@@ -494,6 +558,62 @@ impl MultiAgentChat {
         }
     }
 
+    /// Get one agent's response, forwarding its text as `AgentProgress`
+    /// events. Stream chunks are coalesced, and each send waits for room in
+    /// the channel, so a chatty agent is slowed down rather than crowding
+    /// out the other agents' events.
+    async fn fetch_response(
+        client: &ApiClient,
+        messages: Vec<Message>,
+        agent_id: usize,
+        streaming: bool,
+        event_tx: Option<&mpsc::Sender<MultiAgentEvent>>,
+    ) -> Result<String> {
+        let send = |content: String| async move {
+            if let Some(tx) = event_tx {
+                // A closed channel just means nobody is rendering
+                let _ = tx
+                    .send(MultiAgentEvent::AgentProgress { agent_id, content })
+                    .await;
+            }
+        };
+
+        if !streaming {
+            let response = client.chat(messages, None, ThinkingMode::Disabled).await?;
+            let content = response
+                .choices
+                .first()
+                .map(|c| c.message.content.text().to_string())
+                .unwrap_or_default();
+            send(content.clone()).await;
+            return Ok(content);
+        }
+
+        let mut rx = client
+            .chat_stream(messages, None, ThinkingMode::Disabled)
+            .await?
+            .into_channel()
+            .await;
+        let mut content = String::new();
+        let mut coalescer = ChunkCoalescer::new();
+        while let Some(chunk) = rx.recv().await {
+            match chunk? {
+                StreamChunk::Content(text) => {
+                    content.push_str(&text);
+                    if let Some(batch) = coalescer.push(&text) {
+                        send(batch).await;
+                    }
+                }
+                StreamChunk::Done => break,
+                _ => {}
+            }
+        }
+        if let Some(batch) = coalescer.flush() {
+            send(batch).await;
+        }
+        Ok(content)
+    }
+
     /// Run interactive multi-agent chat
     pub async fn interactive(&mut self) -> Result<()> {
         use colored::Colorize;
@@ -654,46 +774,52 @@ impl MultiAgentChat {
             let (tx, mut rx) = mpsc::channel::<MultiAgentEvent>(1000);
             self.event_tx = Some(tx);
 
-            // Spawn event handler
+            // Render each running agent in its own pane, routing events by
+            // agent ID. Redrawing on a timer rather than per event keeps the
+            // display cost independent of how fast any one agent streams.
+            let live = crate::ui::spinner::supports_ansi();
             let handle = tokio::spawn(async move {
-                while let Some(event) = rx.recv().await {
+                let mut panes = PaneSet::new(DEFAULT_PANE_LINES, live);
+                let mut stdout = io::stdout();
+                let mut redraw = tokio::time::interval(PANE_REDRAW_INTERVAL);
+                loop {
+                    let event = tokio::select! {
+                        event = rx.recv() => match event {
+                            Some(event) => event,
+                            None => break,
+                        },
+                        _ = redraw.tick() => {
+                            let _ = panes.draw(&mut stdout);
+                            continue;
+                        }
+                    };
+                    let mut above = Vec::new();
                     match event {
-                        MultiAgentEvent::AgentStarted { name, .. } => {
-                            println!("  {} {} started", "▶".bright_blue(), name);
+                        MultiAgentEvent::AgentStarted { agent_id, name, .. } => {
+                            panes.open(agent_id, name);
+                        }
+                        MultiAgentEvent::AgentProgress { agent_id, content } => {
+                            above = panes.push(agent_id, &content);
                         }
                         MultiAgentEvent::AgentToolCall { agent_id, tool } => {
-                            println!(
-                                "  {} Agent-{} calling {}",
-                                "🔧".bright_yellow(),
-                                agent_id,
-                                tool
-                            );
+                            above = panes.push(agent_id, &format!("🔧 calling {}\n", tool));
                         }
-                        MultiAgentEvent::AgentCompleted { result, .. } => {
+                        MultiAgentEvent::AgentCompleted { agent_id, result } => {
                             let status = if result.success {
-                                "✓".bright_green()
+                                PaneStatus::Done(result.duration)
                             } else {
-                                "✗".bright_red()
+                                PaneStatus::Failed(result.error.unwrap_or_default())
                             };
-                            println!(
-                                "  {} {} completed in {:.2}s",
-                                status,
-                                result.agent_name,
-                                result.duration.as_secs_f64()
-                            );
+                            above.extend(panes.close(agent_id, status));
                         }
                         MultiAgentEvent::AgentFailed { agent_id, error } => {
-                            println!(
-                                "  {} Agent-{} failed: {}",
-                                "✗".bright_red(),
-                                agent_id,
-                                error
-                            );
+                            above.extend(panes.close(agent_id, PaneStatus::Failed(error)));
                         }
                         MultiAgentEvent::AllCompleted {
                             results,
                             total_duration,
                         } => {
+                            let _ = panes.clear(&mut stdout);
                             let success_count = results.iter().filter(|r| r.success).count();
                             println!(
                                 "\n{} {}/{} agents completed in {:.2}s",
@@ -704,7 +830,9 @@ impl MultiAgentChat {
                             );
                             break;
                         }
-                        _ => {}
+                    }
+                    for line in above {
+                        let _ = panes.print_above(&mut stdout, &line);
                     }
                 }
             });
@@ -1044,6 +1172,61 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_chunk_coalescer_batches_stream_text() {
+        let mut coalescer = ChunkCoalescer::new();
+        assert_eq!(coalescer.push("Hel"), None);
+        assert_eq!(coalescer.push("lo"), None);
+        assert_eq!(coalescer.flush().as_deref(), Some("Hello"));
+        assert_eq!(coalescer.flush(), None);
+
+        let big = "x".repeat(CHUNK_FLUSH_BYTES);
+        assert_eq!(
+            coalescer.push(&big).map(|b| b.len()),
+            Some(CHUNK_FLUSH_BYTES)
+        );
+
+        coalescer.push("late");
+        coalescer.last_flush -= CHUNK_FLUSH_INTERVAL;
+        assert_eq!(coalescer.push("r").as_deref(), Some("later"));
+    }
+
+    #[tokio::test]
+    async fn test_run_task_tags_progress_with_agent_id() {
+        let server = crate::testing::mock_api::MockLlmServer::builder()
+            .with_response("first answer")
+            .with_response("second answer")
+            .build()
+            .await;
+        let config = Config {
+            endpoint: format!("{}/v1", server.url()),
+            ..Default::default()
+        };
+        let agent_config = MultiAgentConfig {
+            streaming: false,
+            ..MultiAgentConfig::default().with_roles(vec![AgentRole::Coder, AgentRole::Tester])
+        };
+        let (tx, mut rx) = mpsc::channel(100);
+        let chat = MultiAgentChat::new(&config, agent_config)
+            .unwrap()
+            .with_events(tx);
+
+        let results = chat.run_task("do it").await.unwrap();
+        assert_eq!(results.len(), 2);
+
+        let mut progress = std::collections::HashMap::new();
+        while let Ok(event) = rx.try_recv() {
+            if let MultiAgentEvent::AgentProgress { agent_id, content } = &event {
+                assert_eq!(event.agent_id(), Some(*agent_id));
+                progress.insert(*agent_id, content.clone());
+            }
+        }
+        for result in &results {
+            assert_eq!(progress[&result.agent_id], result.content);
+        }
+        server.stop().await;
+    }
+
     #[test]
     fn test_multiagent_event_tool_call() {
         let event = MultiAgentEvent::AgentToolCall {
@@ -29,7 +29,7 @@ use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::{Arc, RwLock as StdRwLock};
+use std::sync::{Arc, Mutex as StdMutex, RwLock as StdRwLock};
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::sync::{Mutex as TokioMutex, RwLock as TokioRwLock, Semaphore};
 
@@ -53,6 +53,8 @@ impl Default for ParallelConfig {
         // Tools that modify state and shouldn't run in parallel
         sequential_only.insert("file_write".to_string());
         sequential_only.insert("file_edit".to_string());
+        sequential_only.insert("apply_patch".to_string());
+        sequential_only.insert("symbol_rename".to_string());
         sequential_only.insert("git_commit".to_string());
         sequential_only.insert("git_push".to_string());
         sequential_only.insert("shell_exec".to_string());
@@ -74,17 +76,80 @@ pub struct ParallelResult {
     pub duration_ms: u64,
 }
 
+/// Snapshot of the calls an executor has run.
+///
+/// Every call that gets a permit is `started`, and ends as exactly one of
+/// `completed` or `failed` (a panicking tool counts as failed), so once the
+/// executor is idle `started == completed + failed` and `running == 0`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutorCounters {
+    pub running: usize,
+    /// Most calls ever running at once; never above `max_concurrency`
+    pub peak: usize,
+    pub started: u64,
+    pub completed: u64,
+    pub failed: u64,
+}
+
+/// A call's place among the running ones. Taken after the semaphore permit
+/// and dropped before it, so `running` never exceeds the permit count; a
+/// slot dropped without [`RunningSlot::finish`] counts as failed.
+struct RunningSlot {
+    counters: Arc<StdMutex<ExecutorCounters>>,
+    succeeded: bool,
+}
+
+impl RunningSlot {
+    fn enter(counters: &Arc<StdMutex<ExecutorCounters>>) -> Self {
+        let mut c = counters.lock().unwrap_or_else(|e| e.into_inner());
+        c.running += 1;
+        c.started += 1;
+        c.peak = c.peak.max(c.running);
+        Self {
+            counters: counters.clone(),
+            succeeded: false,
+        }
+    }
+
+    fn finish(mut self, succeeded: bool) {
+        self.succeeded = succeeded;
+    }
+}
+
+impl Drop for RunningSlot {
+    fn drop(&mut self) {
+        let mut c = self.counters.lock().unwrap_or_else(|e| e.into_inner());
+        c.running -= 1;
+        if self.succeeded {
+            c.completed += 1;
+        } else {
+            c.failed += 1;
+        }
+    }
+}
+
 /// Executor for parallel tool operations
 pub struct ParallelExecutor {
     config: ParallelConfig,
     semaphore: Arc<Semaphore>,
+    counters: Arc<StdMutex<ExecutorCounters>>,
 }
 
 impl ParallelExecutor {
-    /// Create a new parallel executor
+    /// Create a new parallel executor. A `max_concurrency` of 0 is treated
+    /// as 1, since no call could ever get a permit.
     pub fn new(config: ParallelConfig) -> Self {
-        let semaphore = Arc::new(Semaphore::new(config.max_concurrency));
-        Self { config, semaphore }
+        let semaphore = Arc::new(Semaphore::new(config.max_concurrency.max(1)));
+        Self {
+            config,
+            semaphore,
+            counters: Arc::new(StdMutex::new(ExecutorCounters::default())),
+        }
+    }
+
+    /// Counters for every call run through [`Self::execute_parallel`]
+    pub fn counters(&self) -> ExecutorCounters {
+        *self.counters.lock().unwrap_or_else(|e| e.into_inner())
     }
 
     /// Check if a tool can run in parallel with others
@@ -165,12 +230,15 @@ impl ParallelExecutor {
 
         for (tool_call_id, call) in calls {
             let semaphore = self.semaphore.clone();
+            let counters = self.counters.clone();
             let registry = registry.clone();
             let tool_name = call.tool_name.clone();
             let arguments = call.arguments.clone();
+            let ids = (tool_name.clone(), tool_call_id.clone());
 
             let handle = tokio::spawn(async move {
-                // Acquire semaphore permit
+                // The permit is the only capacity check; the slot below just
+                // counts what the permit already allowed
                 let _permit = match semaphore.acquire().await {
                     Ok(permit) => permit,
                     Err(_) => {
@@ -184,10 +252,12 @@ impl ParallelExecutor {
                         };
                     }
                 };
+                let slot = RunningSlot::enter(&counters);
                 let start = Instant::now();
 
                 let result = registry.execute(&tool_name, arguments).await;
                 let duration_ms = start.elapsed().as_millis() as u64;
+                slot.finish(result.is_ok());
 
                 ParallelResult {
                     tool_name,
@@ -197,15 +267,18 @@ impl ParallelExecutor {
                 }
             });
 
-            handles.push(handle);
+            handles.push((ids, handle));
         }
 
-        // Wait for all to complete
+        // Wait for all to complete; a panicked call still gets a result
         let mut results = Vec::new();
-        for handle in handles {
-            if let Ok(result) = handle.await {
-                results.push(result);
-            }
+        for ((tool_name, tool_call_id), handle) in handles {
+            results.push(handle.await.unwrap_or_else(|e| ParallelResult {
+                tool_name,
+                tool_call_id,
+                result: Err(anyhow::anyhow!("Parallel execution task failed: {}", e)),
+                duration_ms: 0,
+            }));
         }
 
         results
@@ -478,8 +551,11 @@ impl DependencyAnalyzer {
             "grep_search",
             "glob_find",
             "symbol_search",
+            "find_cycles",
+            "panic_risk",
             "git_status",
             "git_diff",
+            "git_blame",
         ]
         .iter()
         .map(|s| s.to_string())
@@ -488,6 +564,8 @@ impl DependencyAnalyzer {
         let write_tools: HashSet<String> = [
             "file_write",
             "file_edit",
+            "apply_patch",
+            "symbol_rename",
             "git_commit",
             "git_push",
             "shell_exec",
@@ -934,17 +1012,26 @@ pub struct BatchStatsSummary {
 // ============================================================================
 
 /// Tracks parallel execution performance
+///
+/// Counters and history share one lock, so a reader never sees a record
+/// counted in `total` but not yet in `parallel`, or in the history but not
+/// the counters.
 pub struct ExecutionStats {
+    inner: StdMutex<StatsState>,
+}
+
+#[derive(Default)]
+struct StatsState {
     /// Total executions
-    total: AtomicU64,
+    total: u64,
     /// Parallel executions
-    parallel: AtomicU64,
+    parallel: u64,
     /// Sequential executions
-    sequential: AtomicU64,
+    sequential: u64,
     /// Total time saved (ms)
-    time_saved_ms: AtomicU64,
+    time_saved_ms: u64,
     /// Execution history
-    history: StdRwLock<VecDeque<ExecutionRecord>>,
+    history: VecDeque<ExecutionRecord>,
 }
 
 /// Record of an execution
@@ -962,57 +1049,59 @@ pub struct ExecutionRecord {
 impl ExecutionStats {
     pub fn new() -> Self {
         Self {
-            total: AtomicU64::new(0),
-            parallel: AtomicU64::new(0),
-            sequential: AtomicU64::new(0),
-            time_saved_ms: AtomicU64::new(0),
-            history: StdRwLock::new(VecDeque::with_capacity(100)),
+            inner: StdMutex::new(StatsState {
+                history: VecDeque::with_capacity(100),
+                ..Default::default()
+            }),
         }
     }
 
+    fn state(&self) -> std::sync::MutexGuard<'_, StatsState> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
     /// Record an execution
     pub fn record(&self, record: ExecutionRecord) {
-        self.total.fetch_add(1, Ordering::Relaxed);
-        self.parallel
-            .fetch_add(record.parallel_count as u64, Ordering::Relaxed);
-        self.sequential
-            .fetch_add(record.sequential_count as u64, Ordering::Relaxed);
-        self.time_saved_ms
-            .fetch_add(record.time_saved_ms, Ordering::Relaxed);
-
-        if let Ok(mut history) = self.history.write() {
-            history.push_back(record);
-            while history.len() > 100 {
-                history.pop_front();
-            }
+        let mut state = self.state();
+        state.total += 1;
+        state.parallel += record.parallel_count as u64;
+        state.sequential += record.sequential_count as u64;
+        state.time_saved_ms += record.time_saved_ms;
+        state.history.push_back(record);
+        while state.history.len() > 100 {
+            state.history.pop_front();
         }
     }
 
     /// Get total executions
     pub fn total(&self) -> u64 {
-        self.total.load(Ordering::Relaxed)
+        self.state().total
     }
 
     /// Get parallel execution count
     pub fn parallel(&self) -> u64 {
-        self.parallel.load(Ordering::Relaxed)
+        self.state().parallel
     }
 
     /// Get sequential execution count
     pub fn sequential(&self) -> u64 {
-        self.sequential.load(Ordering::Relaxed)
+        self.state().sequential
     }
 
     /// Get total time saved
     pub fn time_saved_ms(&self) -> u64 {
-        self.time_saved_ms.load(Ordering::Relaxed)
+        self.state().time_saved_ms
     }
 
     /// Get parallelization ratio
     pub fn parallelization_ratio(&self) -> f64 {
-        let total = self.parallel() + self.sequential();
+        Self::ratio(&self.state())
+    }
+
+    fn ratio(state: &StatsState) -> f64 {
+        let total = state.parallel + state.sequential;
         if total > 0 {
-            self.parallel() as f64 / total as f64
+            state.parallel as f64 / total as f64
         } else {
             0.0
         }
@@ -1020,32 +1109,29 @@ impl ExecutionStats {
 
     /// Get history
     pub fn history(&self) -> Vec<ExecutionRecord> {
-        self.history
-            .read()
-            .map(|h| h.iter().cloned().collect())
-            .unwrap_or_default()
+        self.state().history.iter().cloned().collect()
     }
 
-    /// Get summary
+    /// Get summary, taken from one consistent snapshot
     pub fn summary(&self) -> ExecutionStatsSummary {
+        let state = self.state();
         ExecutionStatsSummary {
-            total_executions: self.total(),
-            parallel_calls: self.parallel(),
-            sequential_calls: self.sequential(),
-            time_saved_ms: self.time_saved_ms(),
-            parallelization_ratio: self.parallelization_ratio(),
+            total_executions: state.total,
+            parallel_calls: state.parallel,
+            sequential_calls: state.sequential,
+            time_saved_ms: state.time_saved_ms,
+            parallelization_ratio: Self::ratio(&state),
         }
     }
 
     /// Reset statistics
     pub fn reset(&self) {
-        self.total.store(0, Ordering::Relaxed);
-        self.parallel.store(0, Ordering::Relaxed);
-        self.sequential.store(0, Ordering::Relaxed);
-        self.time_saved_ms.store(0, Ordering::Relaxed);
-        if let Ok(mut history) = self.history.write() {
-            history.clear();
-        }
+        let mut state = self.state();
+        state.total = 0;
+        state.parallel = 0;
+        state.sequential = 0;
+        state.time_saved_ms = 0;
+        state.history.clear();
     }
 }
 
@@ -1078,8 +1164,11 @@ pub fn are_independent(tool1: &ParsedToolCall, tool2: &ParsedToolCall) -> bool {
         "grep_search",
         "glob_find",
         "symbol_search",
+        "find_cycles",
+        "panic_risk",
         "git_status",
         "git_diff",
+        "git_blame",
     ];
 
     let tool1_read_only = read_only_tools.contains(&tool1.tool_name.as_str());
@@ -2994,10 +3083,12 @@ mod parallel_config_extended_tests {
         let config = ParallelConfig::default();
         assert!(config.sequential_only.contains("file_write"));
         assert!(config.sequential_only.contains("file_edit"));
+        assert!(config.sequential_only.contains("apply_patch"));
+        assert!(config.sequential_only.contains("symbol_rename"));
         assert!(config.sequential_only.contains("git_commit"));
         assert!(config.sequential_only.contains("git_push"));
         assert!(config.sequential_only.contains("shell_exec"));
-        assert_eq!(config.sequential_only.len(), 5);
+        assert_eq!(config.sequential_only.len(), 7);
     }
 
     #[test]
@@ -3125,6 +3216,110 @@ mod parallel_executor_extended_tests {
         let debug_str = format!("{:?}", result);
         assert!(debug_str.contains("failing_tool"));
     }
+
+    /// Tracks how many of its calls overlap; fails or panics on request
+    struct ProbeTool {
+        running: Arc<std::sync::atomic::AtomicUsize>,
+        peak: Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    #[async_trait::async_trait]
+    impl crate::tools::Tool for ProbeTool {
+        fn name(&self) -> &str {
+            "probe"
+        }
+
+        fn description(&self) -> &str {
+            "Concurrency probe"
+        }
+
+        fn schema(&self) -> serde_json::Value {
+            serde_json::json!({"type": "object"})
+        }
+
+        async fn execute(&self, args: serde_json::Value) -> Result<serde_json::Value> {
+            use std::sync::atomic::Ordering::SeqCst;
+            let now = self.running.fetch_add(1, SeqCst) + 1;
+            self.peak.fetch_max(now, SeqCst);
+            tokio::time::sleep(std::time::Duration::from_millis(2)).await;
+            self.running.fetch_sub(1, SeqCst);
+            match args["mode"].as_str() {
+                Some("fail") => anyhow::bail!("probe failed"),
+                Some("panic") => panic!("probe panicked"),
+                _ => Ok(serde_json::json!({"ok": true})),
+            }
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_execute_parallel_never_exceeds_cap_and_counters_reconcile() {
+        let running = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let peak = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let mut registry = ToolRegistry::new();
+        registry.register(ProbeTool {
+            running: running.clone(),
+            peak: peak.clone(),
+        });
+        let registry = Arc::new(registry);
+        let executor = ParallelExecutor::new(ParallelConfig {
+            max_concurrency: 3,
+            ..Default::default()
+        });
+
+        let batch = |offset: usize| -> Vec<(String, ParsedToolCall)> {
+            (offset..offset + 60)
+                .map(|i| {
+                    let mode = match i % 20 {
+                        0 => "fail",
+                        7 => "panic",
+                        _ => "ok",
+                    };
+                    (
+                        format!("call_{}", i),
+                        make_call("probe", serde_json::json!({"i": i, "mode": mode})),
+                    )
+                })
+                .collect()
+        };
+        // Two batches at once contend for the same permits
+        let (first, second) = tokio::join!(
+            executor.execute_parallel(batch(0), registry.clone()),
+            executor.execute_parallel(batch(60), registry.clone()),
+        );
+
+        assert_eq!(first.len() + second.len(), 120);
+        let failed = first
+            .iter()
+            .chain(&second)
+            .filter(|r| r.result.is_err())
+            .count();
+        assert_eq!(failed, 12);
+        assert!(peak.load(std::sync::atomic::Ordering::SeqCst) <= 3);
+
+        let counters = executor.counters();
+        assert!(counters.peak <= 3, "{:?}", counters);
+        assert_eq!(counters.running, 0);
+        assert_eq!(counters.started, 120);
+        assert_eq!(counters.completed, 108);
+        assert_eq!(counters.failed, 12);
+    }
+
+    #[tokio::test]
+    async fn test_zero_concurrency_still_runs() {
+        let executor = ParallelExecutor::new(ParallelConfig {
+            max_concurrency: 0,
+            ..Default::default()
+        });
+        let calls = vec![(
+            "call_1".to_string(),
+            make_call("no_such_tool", serde_json::json!({})),
+        )];
+        let results = executor
+            .execute_parallel(calls, Arc::new(ToolRegistry::new()))
+            .await;
+        assert_eq!(results.len(), 1);
+        assert_eq!(executor.counters().failed, 1);
+    }
 }
 
 #[cfg(test)]
@@ -9,6 +9,7 @@
 //! - Plan validation and refinement
 //! - Progress tracking
 //! - Adaptive replanning
+//! - Export of plans as Markdown or Mermaid flowcharts
 
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
@@ -309,6 +310,154 @@ impl Plan {
     pub fn has_failed(&self) -> bool {
         self.steps.iter().any(|s| s.status == GoalStatus::Failed)
     }
+
+    /// Dependency edges as `(from, to)` step indices. A plan whose steps
+    /// declare no dependencies runs them in order.
+    fn edges(&self) -> Vec<(usize, usize)> {
+        if self.steps.iter().all(|s| s.dependencies.is_empty()) {
+            return (1..self.steps.len()).map(|i| (i - 1, i)).collect();
+        }
+        let index: HashMap<&str, usize> = self
+            .steps
+            .iter()
+            .enumerate()
+            .map(|(i, s)| (s.id.as_str(), i))
+            .collect();
+        self.steps
+            .iter()
+            .enumerate()
+            .flat_map(|(to, step)| {
+                step.dependencies
+                    .iter()
+                    .filter_map(|dep| index.get(dep.as_str()).map(|&from| (from, to)))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Mermaid flowchart of the plan: one node per step, dependencies as
+    /// edges, and finished, active and failed steps in their own styles
+    pub fn to_mermaid(&self) -> String {
+        let mut out = vec!["flowchart TD".to_string()];
+        for (i, step) in self.steps.iter().enumerate() {
+            out.push(format!(
+                "    s{}[\"{}. {}\"]",
+                i,
+                i + 1,
+                mermaid_escape(&step.description)
+            ));
+        }
+        for (from, to) in self.edges() {
+            out.push(format!("    s{} --> s{}", from, to));
+        }
+
+        let classes = [
+            ("done", GoalStatus::Achieved, "fill:#d4edda,stroke:#28a745"),
+            ("active", GoalStatus::Active, "fill:#fff3cd,stroke:#ffc107"),
+            ("failed", GoalStatus::Failed, "fill:#f8d7da,stroke:#dc3545"),
+        ];
+        for (class, status, style) in classes {
+            let nodes: Vec<String> = self
+                .steps
+                .iter()
+                .enumerate()
+                .filter(|(_, s)| s.status == status)
+                .map(|(i, _)| format!("s{}", i))
+                .collect();
+            if !nodes.is_empty() {
+                out.push(format!("    classDef {} {}", class, style));
+                out.push(format!("    class {} {}", nodes.join(","), class));
+            }
+        }
+        out.join("\n")
+    }
+
+    /// Markdown summary of the plan: a checklist of steps followed by the
+    /// [`to_mermaid`](Self::to_mermaid) flowchart
+    pub fn to_markdown(&self) -> String {
+        let mut out = vec![format!("# {}", self.name)];
+        if !self.description.is_empty() {
+            out.push(String::new());
+            out.push(self.description.clone());
+        }
+        let done = self
+            .steps
+            .iter()
+            .filter(|s| s.status == GoalStatus::Achieved)
+            .count();
+        out.push(String::new());
+        out.push(format!(
+            "Progress: {}/{} steps ({}%)",
+            done,
+            self.steps.len(),
+            self.progress()
+        ));
+        out.push(String::new());
+
+        let explicit_deps = self.steps.iter().any(|s| !s.dependencies.is_empty());
+        for (i, step) in self.steps.iter().enumerate() {
+            let check = if step.status == GoalStatus::Achieved {
+                "x"
+            } else {
+                " "
+            };
+            let mut line = format!("- [{}] {}. {}", check, i + 1, step.description);
+            match step.status {
+                GoalStatus::Active => line.push_str(" _(in progress)_"),
+                GoalStatus::Failed => line.push_str(" _(failed)_"),
+                GoalStatus::Blocked => line.push_str(" _(blocked)_"),
+                GoalStatus::Abandoned => line.push_str(" _(abandoned)_"),
+                GoalStatus::Pending | GoalStatus::Achieved => {}
+            }
+            if explicit_deps && !step.dependencies.is_empty() {
+                let after: Vec<String> = step
+                    .dependencies
+                    .iter()
+                    .map(|dep| {
+                        self.steps
+                            .iter()
+                            .position(|s| &s.id == dep)
+                            .map(|j| (j + 1).to_string())
+                            .unwrap_or_else(|| dep.clone())
+                    })
+                    .collect();
+                line.push_str(&format!(" (after {})", after.join(", ")));
+            }
+            out.push(line);
+            if let Some(verification) = &step.verification {
+                out.push(format!("  - Verify: {}", verification));
+            }
+            if let Some(error) = &step.error {
+                out.push(format!("  - Error: {}", error));
+            }
+        }
+
+        out.push(String::new());
+        out.push("```mermaid".to_string());
+        out.push(self.to_mermaid());
+        out.push("```".to_string());
+        out.push(String::new());
+        out.join("\n")
+    }
+}
+
+/// Make text safe inside a quoted Mermaid node label. Quotes, brackets and
+/// the like become Mermaid entity codes; line breaks become spaces.
+fn mermaid_escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '#' => out.push_str("#35;"),
+            '"' => out.push_str("#quot;"),
+            '<' => out.push_str("#lt;"),
+            '>' => out.push_str("#gt;"),
+            '&' => out.push_str("#amp;"),
+            '`' => out.push_str("#96;"),
+            '\n' | '\r' | '\t' => out.push(' '),
+            c => out.push(c),
+        }
+    }
+    out
 }
 
 /// A step in a tactical plan
@@ -332,6 +481,9 @@ pub struct PlanStep {
     pub retry_count: u32,
     /// Max retries allowed
     pub max_retries: u32,
+    /// IDs of steps that must finish before this one
+    #[serde(default)]
+    pub dependencies: Vec<String>,
 }
 
 impl PlanStep {
@@ -347,9 +499,16 @@ impl PlanStep {
             duration_ms: None,
             retry_count: 0,
             max_retries: 3,
+            dependencies: Vec::new(),
         }
     }
 
+    /// Add dependency on another step
+    pub fn with_dependency(mut self, step_id: impl Into<String>) -> Self {
+        self.dependencies.push(step_id.into());
+        self
+    }
+
     /// Set action type
     pub fn with_action(mut self, action_type: ActionType) -> Self {
         self.action_type = action_type;
@@ -1136,6 +1295,62 @@ mod tests {
         assert!(!step.can_retry());
     }
 
+    fn review_plan() -> Plan {
+        let mut plan = Plan::new("p1", "Add auth", "g1");
+        let mut read = PlanStep::new("read", "Read the \"login\" handler");
+        read.complete(10);
+        plan.add_step(read);
+        let mut tokens = PlanStep::new("tokens", "Add Token<T> type & #tests")
+            .with_dependency("read")
+            .with_verification("cargo test auth");
+        tokens.status = GoalStatus::Active;
+        plan.add_step(tokens);
+        plan.add_step(PlanStep::new("docs", "Document it").with_dependency("read"));
+        plan
+    }
+
+    #[test]
+    fn test_plan_to_mermaid_escapes_labels_and_styles_status() {
+        let mermaid = review_plan().to_mermaid();
+        assert!(mermaid.starts_with("flowchart TD\n"));
+        assert!(
+            mermaid.contains("s0[\"1. Read the #quot;login#quot; handler\"]"),
+            "{}",
+            mermaid
+        );
+        assert!(
+            mermaid.contains("s1[\"2. Add Token#lt;T#gt; type #amp; #35;tests\"]"),
+            "{}",
+            mermaid
+        );
+        assert!(mermaid.contains("s0 --> s1"));
+        assert!(mermaid.contains("s0 --> s2"));
+        assert!(!mermaid.contains("s1 --> s2"));
+        assert!(mermaid.contains("class s0 done"));
+        assert!(mermaid.contains("class s1 active"));
+        assert!(!mermaid.contains("classDef failed"));
+
+        // Without declared dependencies the steps run in order
+        let mut sequential = Plan::new("p2", "Fix", "g1");
+        for (id, text) in [("a", "One"), ("b", "Two"), ("c", "Three")] {
+            sequential.add_step(PlanStep::new(id, text));
+        }
+        let mermaid = sequential.to_mermaid();
+        assert!(mermaid.contains("s0 --> s1") && mermaid.contains("s1 --> s2"));
+        assert!(!mermaid.contains("s0 --> s2"));
+    }
+
+    #[test]
+    fn test_plan_to_markdown() {
+        let markdown = review_plan().to_markdown();
+        assert!(markdown.starts_with("# Add auth\n"));
+        assert!(markdown.contains("Progress: 1/3 steps (33%)"));
+        assert!(markdown.contains("- [x] 1. Read the \"login\" handler\n"));
+        assert!(markdown.contains("- [ ] 2. Add Token<T> type & #tests _(in progress)_ (after 1)"));
+        assert!(markdown.contains("  - Verify: cargo test auth"));
+        assert!(markdown.contains("```mermaid\nflowchart TD"));
+    }
+
     #[test]
     fn test_action_lifecycle() {
         let mut action = Action::new("action1", "step1", ActionType::FileRead)
@@ -9,11 +9,15 @@
 //! - Consensus voting for decisions
 //! - Conflict resolution strategies
 //! - Shared working memory
+//! - Typed blackboard for handing artifacts between phases
+//! - Trust-based task assignment, persisted across sessions
 //! - Agent coordination
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context, Result};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock};
 use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -153,6 +157,12 @@ pub enum AgentStatus {
     Paused,
 }
 
+/// Trust a new agent starts with, and that stale trust decays back towards
+pub const TRUST_MEAN: f32 = 0.5;
+
+/// Time for persisted trust to move halfway back to [`TRUST_MEAN`] (seconds)
+pub const TRUST_HALF_LIFE_SECS: u64 = 14 * 24 * 60 * 60;
+
 /// A specialist agent
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Agent {
@@ -198,7 +208,7 @@ impl Agent {
             status: AgentStatus::Idle,
             custom_prompt: None,
             expertise: Vec::new(),
-            trust_score: 0.5,
+            trust_score: TRUST_MEAN,
             tasks_completed: 0,
             tasks_failed: 0,
             created_at: now,
@@ -258,6 +268,18 @@ impl Agent {
             .as_secs();
     }
 
+    /// Key that identifies this specialist configuration across sessions.
+    /// Agent IDs are fresh per session, so trust is keyed by role, name and
+    /// model instead.
+    pub fn trust_key(&self) -> String {
+        format!(
+            "{}/{}/{}",
+            self.role.name(),
+            self.name,
+            self.model_id.as_deref().unwrap_or("default")
+        )
+    }
+
     /// Get success rate
     pub fn success_rate(&self) -> f32 {
         let total = self.tasks_completed + self.tasks_failed;
@@ -641,6 +663,199 @@ impl SharedMemory {
     }
 }
 
+/// A typed artifact that one swarm phase hands to the next through the
+/// [`SwarmBlackboard`]
+pub trait BlackboardArtifact: Serialize + DeserializeOwned {
+    /// Blackboard slot the artifact is stored under
+    const KIND: &'static str;
+
+    /// Text shown to roles that read this artifact
+    fn render(&self) -> String;
+}
+
+/// The Architect's design for the task
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DesignDoc {
+    pub content: String,
+}
+
+impl BlackboardArtifact for DesignDoc {
+    const KIND: &'static str = "design_doc";
+
+    fn render(&self) -> String {
+        self.content.clone()
+    }
+}
+
+/// Files the Coder changed and what the change does
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Changelist {
+    pub files: Vec<String>,
+    pub summary: String,
+}
+
+impl BlackboardArtifact for Changelist {
+    const KIND: &'static str = "changelist";
+
+    fn render(&self) -> String {
+        if self.files.is_empty() {
+            return format!("No files changed.\n\n{}", self.summary);
+        }
+        format!(
+            "Files changed:\n- {}\n\n{}",
+            self.files.join("\n- "),
+            self.summary
+        )
+    }
+}
+
+/// The Tester's verdict on the changes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TestReport {
+    pub passed: bool,
+    pub summary: String,
+}
+
+impl BlackboardArtifact for TestReport {
+    const KIND: &'static str = "test_report";
+
+    fn render(&self) -> String {
+        format!(
+            "Result: {}\n\n{}",
+            if self.passed { "passed" } else { "failed" },
+            self.summary
+        )
+    }
+}
+
+/// The Reviewer's findings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReviewNotes {
+    pub content: String,
+}
+
+impl BlackboardArtifact for ReviewNotes {
+    const KIND: &'static str = "review_notes";
+
+    fn render(&self) -> String {
+        self.content.clone()
+    }
+}
+
+/// One version of an artifact on the blackboard
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlackboardEntry {
+    pub kind: String,
+    /// 1 for the first write of this kind, incremented on each rewrite
+    pub version: u32,
+    pub author: String,
+    pub written_at: u64,
+    /// Serialized artifact
+    pub value: serde_json::Value,
+    /// Artifact as shown to readers
+    pub rendered: String,
+}
+
+/// Typed artifact store for handing work between swarm phases.
+///
+/// Each phase reads the artifacts its role depends on and writes its own,
+/// so handoffs don't depend on conversational carryover and a failed phase
+/// can be retried with exactly the inputs it had. Every version is kept for
+/// inspection.
+#[derive(Debug, Clone, Default)]
+pub struct SwarmBlackboard {
+    entries: HashMap<&'static str, Vec<BlackboardEntry>>,
+}
+
+impl SwarmBlackboard {
+    /// Create an empty blackboard
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Write a new version of an artifact, returning its version number
+    pub fn put<A: BlackboardArtifact>(
+        &mut self,
+        artifact: &A,
+        author: impl Into<String>,
+    ) -> Result<u32> {
+        let versions = self.entries.entry(A::KIND).or_default();
+        let version = versions.len() as u32 + 1;
+        versions.push(BlackboardEntry {
+            kind: A::KIND.to_string(),
+            version,
+            author: author.into(),
+            written_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            value: serde_json::to_value(artifact)?,
+            rendered: artifact.render(),
+        });
+        Ok(version)
+    }
+
+    /// Latest version of an artifact
+    pub fn get<A: BlackboardArtifact>(&self) -> Option<A> {
+        let entry = self.latest(A::KIND)?;
+        serde_json::from_value(entry.value.clone()).ok()
+    }
+
+    /// Latest entry stored under `kind`
+    pub fn latest(&self, kind: &str) -> Option<&BlackboardEntry> {
+        self.entries.get(kind).and_then(|versions| versions.last())
+    }
+
+    /// Every version written under `kind`, oldest first
+    pub fn history(&self, kind: &str) -> &[BlackboardEntry] {
+        self.entries.get(kind).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Latest entry of every artifact, sorted by kind
+    pub fn entries(&self) -> Vec<&BlackboardEntry> {
+        let mut latest: Vec<_> = self.entries.values().filter_map(|v| v.last()).collect();
+        latest.sort_by(|a, b| a.kind.cmp(&b.kind));
+        latest
+    }
+
+    /// Check if nothing has been written
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Artifact kinds a role reads before starting its phase
+    pub fn inputs_for(role: AgentRole) -> &'static [&'static str] {
+        match role {
+            AgentRole::Coder | AgentRole::Documenter => &[DesignDoc::KIND],
+            AgentRole::Tester => &[DesignDoc::KIND, Changelist::KIND],
+            AgentRole::Reviewer | AgentRole::Security | AgentRole::Performance => {
+                &[DesignDoc::KIND, Changelist::KIND, TestReport::KIND]
+            }
+            _ => &[],
+        }
+    }
+
+    /// Prompt section with the artifacts available to `role`, or `None`
+    /// when none have been written yet
+    pub fn context_for(&self, role: AgentRole) -> Option<String> {
+        let sections: Vec<String> = Self::inputs_for(role)
+            .iter()
+            .filter_map(|kind| self.latest(kind))
+            .map(|entry| {
+                format!(
+                    "### {} (v{}, from {})\n{}",
+                    entry.kind, entry.version, entry.author, entry.rendered
+                )
+            })
+            .collect();
+        if sections.is_empty() {
+            None
+        } else {
+            Some(sections.join("\n\n"))
+        }
+    }
+}
+
 /// Conflict resolution strategy
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub enum ConflictStrategy {
@@ -668,6 +883,8 @@ pub struct Swarm {
     /// executor starvation is not a concern. Callers use the
     /// `unwrap_or_else(|e| e.into_inner())` pattern to recover from poisoning.
     memory: Arc<RwLock<SharedMemory>>,
+    /// Artifacts handed between phases, locked the same way as `memory`
+    blackboard: Arc<RwLock<SwarmBlackboard>>,
     /// Active decisions
     decisions: HashMap<String, Decision>,
     /// Conflict resolution strategy
@@ -750,6 +967,7 @@ impl Swarm {
         Self {
             agents: HashMap::new(),
             memory: Arc::new(RwLock::new(SharedMemory::new())),
+            blackboard: Arc::new(RwLock::new(SwarmBlackboard::new())),
             decisions: HashMap::new(),
             conflict_strategy: ConflictStrategy::default(),
             consensus_threshold: 0.6,
@@ -854,6 +1072,11 @@ impl Swarm {
         Arc::clone(&self.memory)
     }
 
+    /// Get the artifact blackboard
+    pub fn blackboard(&self) -> Arc<RwLock<SwarmBlackboard>> {
+        Arc::clone(&self.blackboard)
+    }
+
     /// Create a decision
     pub fn create_decision(&mut self, question: impl Into<String>, options: Vec<String>) -> String {
         let decision = Decision::new(question, options);
@@ -1042,24 +1265,122 @@ impl Swarm {
         assigned
     }
 
-    /// Complete task for an agent
-    pub fn complete_task(&mut self, task_id: &str, agent_id: &str, result: impl Into<String>) {
+    /// Assign each of a task's roles to the most trusted idle agent. Tasks
+    /// leave the queue highest priority first, so critical work claims the
+    /// most trusted agents before routine work does. Unlike
+    /// [`Swarm::assign_task`] this works on a task already taken off the
+    /// queue with [`Swarm::next_task`].
+    pub fn assign_by_trust(&mut self, task: &mut SwarmTask) -> Vec<String> {
+        let mut assigned = Vec::new();
+
+        for role in &task.required_roles {
+            let picked = self
+                .agents
+                .values()
+                .filter(|a| a.role == *role && a.status == AgentStatus::Idle)
+                .max_by(|a, b| {
+                    a.trust_score
+                        .partial_cmp(&b.trust_score)
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                });
+
+            if let Some(agent) = picked {
+                let agent_id = agent.id.clone();
+                if let Some(agent) = self.agents.get_mut(&agent_id) {
+                    agent.start_working();
+                    assigned.push(agent_id);
+                }
+            }
+        }
+
+        task.assigned_agents = assigned.clone();
+        task.status = TaskStatus::InProgress;
+
+        assigned
+    }
+
+    /// Complete task for an agent. `success` is whether its work passed
+    /// verification; the agent's trust rises or falls accordingly.
+    pub fn complete_task(
+        &mut self,
+        task_id: &str,
+        agent_id: &str,
+        result: impl Into<String>,
+        success: bool,
+    ) {
         if let Some(task) = self.task_queue.iter_mut().find(|t| t.id == task_id) {
             task.results.insert(agent_id.to_string(), result.into());
 
             // Check if all agents have submitted results — done atomically
             // within the same mutable borrow to avoid inconsistent state
-            let all_done = task.results.len() >= task.assigned_agents.len();
-            if all_done {
+            if !success {
+                task.status = TaskStatus::Failed;
+            } else if task.results.len() >= task.assigned_agents.len()
+                && task.status != TaskStatus::Failed
+            {
                 task.status = TaskStatus::Completed;
             }
+        }
+
+        // Trust follows the agent's work even when the task has already
+        // left the queue via `next_task`
+        if let Some(agent) = self.agents.get_mut(agent_id) {
+            agent.complete_task(success);
+        }
+    }
 
-            // Update agent status only when task was found, keeping both
-            // operations together so they succeed or fail as a unit
-            if let Some(agent) = self.agents.get_mut(agent_id) {
-                agent.complete_task(true);
+    /// `swarm_trust.json` under the local data directory
+    pub fn default_trust_path() -> PathBuf {
+        dirs::data_local_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("selfware")
+            .join("swarm_trust.json")
+    }
+
+    /// Restore agents' trust saved by an earlier session, decayed towards
+    /// [`TRUST_MEAN`] by how long ago it was saved. A missing or unreadable
+    /// file leaves trust untouched. Returns how many agents were restored.
+    pub fn load_trust(&mut self, path: &Path) -> usize {
+        let records = read_trust_records(path);
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        let mut restored = 0;
+        for agent in self.agents.values_mut() {
+            if let Some(record) = records.get(&agent.trust_key()) {
+                let age = now.saturating_sub(record.updated_at);
+                agent.trust_score = decayed_trust(record.trust_score, age);
+                restored += 1;
             }
         }
+        restored
+    }
+
+    /// Save every agent's trust to `path`, keeping records for specialist
+    /// configurations that are not in this swarm.
+    pub fn save_trust(&self, path: &Path) -> Result<()> {
+        let mut records = read_trust_records(path);
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        for agent in self.agents.values() {
+            records.insert(
+                agent.trust_key(),
+                TrustRecord {
+                    trust_score: agent.trust_score,
+                    updated_at: now,
+                },
+            );
+        }
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(&records)?)
+            .with_context(|| format!("Failed to save {}", path.display()))
     }
 
     /// Get swarm statistics
@@ -1108,6 +1429,28 @@ pub struct SwarmStats {
     pub average_trust: f32,
 }
 
+/// Trust of one specialist configuration as saved between sessions
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct TrustRecord {
+    trust_score: f32,
+    /// When the score was saved (unix seconds)
+    updated_at: u64,
+}
+
+fn read_trust_records(path: &Path) -> HashMap<String, TrustRecord> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+/// `trust` after `age_secs` without activity: the distance from
+/// [`TRUST_MEAN`] halves every [`TRUST_HALF_LIFE_SECS`].
+fn decayed_trust(trust: f32, age_secs: u64) -> f32 {
+    let half_lives = age_secs as f32 / TRUST_HALF_LIFE_SECS as f32;
+    TRUST_MEAN + (trust - TRUST_MEAN) * 0.5f32.powf(half_lives)
+}
+
 /// Create a standard development swarm
 pub fn create_dev_swarm() -> Swarm {
     let mut swarm = Swarm::new();
@@ -1253,6 +1596,68 @@ mod tests {
         assert_eq!(outcome.unwrap(), "A");
     }
 
+    #[test]
+    fn test_blackboard_typed_put_get() {
+        let swarm = create_dev_swarm();
+        let blackboard = swarm.blackboard();
+        let mut board = blackboard.write().unwrap();
+        assert!(board.is_empty());
+        assert!(board.get::<DesignDoc>().is_none());
+
+        let design = DesignDoc {
+            content: "Split the parser into lexer and grammar".to_string(),
+        };
+        assert_eq!(board.put(&design, "Archie").unwrap(), 1);
+        assert_eq!(board.get::<DesignDoc>(), Some(design));
+        assert!(board.get::<Changelist>().is_none());
+
+        let changes = Changelist {
+            files: vec!["src/lexer.rs".to_string()],
+            summary: "Added the lexer".to_string(),
+        };
+        board.put(&changes, "Cody").unwrap();
+        let revised = DesignDoc {
+            content: "Keep the grammar in one module".to_string(),
+        };
+        assert_eq!(board.put(&revised, "Archie").unwrap(), 2);
+        assert_eq!(board.history(DesignDoc::KIND).len(), 2);
+        assert_eq!(board.get::<DesignDoc>(), Some(revised));
+        assert_eq!(board.entries().len(), 2);
+    }
+
+    #[test]
+    fn test_blackboard_context_for_role() {
+        let mut board = SwarmBlackboard::new();
+        assert!(board.context_for(AgentRole::Tester).is_none());
+
+        board
+            .put(
+                &DesignDoc {
+                    content: "Use a trie".to_string(),
+                },
+                "Archie",
+            )
+            .unwrap();
+        board
+            .put(
+                &Changelist {
+                    files: vec!["src/trie.rs".to_string()],
+                    summary: "Implemented the trie".to_string(),
+                },
+                "Cody",
+            )
+            .unwrap();
+
+        let coder = board.context_for(AgentRole::Coder).unwrap();
+        assert!(coder.contains("design_doc (v1, from Archie)"));
+        assert!(!coder.contains("src/trie.rs"));
+
+        let tester = board.context_for(AgentRole::Tester).unwrap();
+        assert!(tester.contains("Use a trie"));
+        assert!(tester.contains("- src/trie.rs"));
+        assert!(board.context_for(AgentRole::Architect).is_none());
+    }
+
     #[test]
     fn test_shared_memory_write_read() {
         let mut memory = SharedMemory::new();
@@ -2635,7 +3040,7 @@ mod tests {
         let assigned = swarm.assign_task(&task_id);
         assert_eq!(assigned.len(), 1);
 
-        swarm.complete_task(&task_id, &coder_id, "Done!");
+        swarm.complete_task(&task_id, &coder_id, "Done!", true);
 
         // Task should be Completed since all assigned agents submitted
         let task = swarm.get_task(&task_id).unwrap();
@@ -2662,14 +3067,14 @@ mod tests {
         swarm.assign_task(&task_id);
 
         // Only one agent completes
-        swarm.complete_task(&task_id, &c1, "Partial");
+        swarm.complete_task(&task_id, &c1, "Partial", true);
 
         let task = swarm.get_task(&task_id).unwrap();
         // Not all agents submitted, so task should still be InProgress
         assert_eq!(task.status, TaskStatus::InProgress);
 
         // Second agent completes
-        swarm.complete_task(&task_id, &c2, "Full");
+        swarm.complete_task(&task_id, &c2, "Full", true);
         let task = swarm.get_task(&task_id).unwrap();
         assert_eq!(task.status, TaskStatus::Completed);
     }
@@ -2679,7 +3084,145 @@ mod tests {
         let mut swarm = Swarm::new();
         let coder_id = swarm.add_agent(Agent::new("Cody", AgentRole::Coder));
         // Completing a nonexistent task should not panic
-        swarm.complete_task("nonexistent", &coder_id, "result");
+        swarm.complete_task("nonexistent", &coder_id, "result", true);
+    }
+
+    // ---- Trust-based assignment and persistence ----
+
+    #[test]
+    fn test_swarm_complete_task_failure_lowers_trust() {
+        let mut swarm = Swarm::new();
+        let coder_id = swarm.add_agent(Agent::new("Cody", AgentRole::Coder));
+
+        let task = SwarmTask::new("Build it").with_role(AgentRole::Coder);
+        let task_id = task.id.clone();
+        swarm.queue_task(task).unwrap();
+        swarm.assign_task(&task_id);
+
+        swarm.complete_task(&task_id, &coder_id, "cargo test failed", false);
+
+        assert_eq!(swarm.get_task(&task_id).unwrap().status, TaskStatus::Failed);
+        let agent = swarm.get_agent(&coder_id).unwrap();
+        assert!(agent.trust_score < TRUST_MEAN);
+        assert_eq!(agent.tasks_failed, 1);
+    }
+
+    #[test]
+    fn test_swarm_complete_task_updates_trust_after_dequeue() {
+        let mut swarm = Swarm::new();
+        let coder_id = swarm.add_agent(Agent::new("Cody", AgentRole::Coder));
+        swarm
+            .queue_task(SwarmTask::new("Build it").with_role(AgentRole::Coder))
+            .unwrap();
+
+        let mut task = swarm.next_task().unwrap();
+        assert_eq!(swarm.assign_by_trust(&mut task), vec![coder_id.clone()]);
+        swarm.complete_task(&task.id, &coder_id, "Done", true);
+
+        assert!(swarm.get_agent(&coder_id).unwrap().trust_score > TRUST_MEAN);
+    }
+
+    #[test]
+    fn test_swarm_assign_by_trust_critical_prefers_trusted() {
+        let mut swarm = Swarm::new();
+        let mut trusted = Agent::new("Trusted", AgentRole::Coder);
+        trusted.trust_score = 0.9;
+        let trusted_id = swarm.add_agent(trusted);
+        let mut shaky = Agent::new("Shaky", AgentRole::Coder);
+        shaky.trust_score = 0.2;
+        let shaky_id = swarm.add_agent(shaky);
+        swarm
+            .queue_task(SwarmTask::new("Tidy up").with_role(AgentRole::Coder))
+            .unwrap();
+        swarm
+            .queue_task(
+                SwarmTask::new("Ship it")
+                    .with_role(AgentRole::Coder)
+                    .with_priority(9),
+            )
+            .unwrap();
+
+        let mut critical = swarm.next_task().unwrap();
+        assert_eq!(
+            swarm.assign_by_trust(&mut critical),
+            vec![trusted_id.clone()]
+        );
+        assert_eq!(critical.assigned_agents, vec![trusted_id]);
+        assert_eq!(critical.status, TaskStatus::InProgress);
+
+        // The trusted agent is busy; routine work goes to the next best
+        let mut routine = swarm.next_task().unwrap();
+        assert_eq!(swarm.assign_by_trust(&mut routine), vec![shaky_id]);
+    }
+
+    #[test]
+    fn test_swarm_assign_by_trust_routine_prefers_trusted() {
+        let mut swarm = Swarm::new();
+        let mut trusted = Agent::new("Trusted", AgentRole::Tester);
+        trusted.trust_score = 0.9;
+        let trusted_id = swarm.add_agent(trusted);
+        let mut shaky = Agent::new("Shaky", AgentRole::Tester);
+        shaky.trust_score = 0.2;
+        swarm.add_agent(shaky);
+
+        let mut task = SwarmTask::new("Smoke test").with_role(AgentRole::Tester);
+        assert_eq!(swarm.assign_by_trust(&mut task), vec![trusted_id]);
+    }
+
+    #[test]
+    fn test_swarm_trust_persists_across_sessions() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("swarm_trust.json");
+
+        let mut first = create_dev_swarm();
+        let cody = first.agents_by_role(AgentRole::Coder)[0].id.clone();
+        first.get_agent_mut(&cody).unwrap().trust_score = 0.9;
+        first.save_trust(&path).unwrap();
+
+        let mut second = create_dev_swarm();
+        assert_eq!(second.load_trust(&path), 4);
+        let coder = second.agents_by_role(AgentRole::Coder)[0];
+        assert!((coder.trust_score - 0.9).abs() < 0.01);
+        let tester = second.agents_by_role(AgentRole::Tester)[0];
+        assert!((tester.trust_score - TRUST_MEAN).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_swarm_save_trust_keeps_other_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("swarm_trust.json");
+
+        create_security_swarm().save_trust(&path).unwrap();
+        create_dev_swarm().save_trust(&path).unwrap();
+
+        let records = read_trust_records(&path);
+        // Tessa and Rex are in both swarms; Guardian only in the first
+        assert_eq!(records.len(), 5);
+        assert!(records.keys().any(|k| k.starts_with("Security/Guardian/")));
+    }
+
+    #[test]
+    fn test_swarm_load_trust_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut swarm = create_dev_swarm();
+        assert_eq!(swarm.load_trust(&dir.path().join("missing.json")), 0);
+        assert!((swarm.stats().average_trust - TRUST_MEAN).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_decayed_trust_regresses_to_mean() {
+        assert!((decayed_trust(0.9, 0) - 0.9).abs() < 1e-6);
+        assert!((decayed_trust(0.9, TRUST_HALF_LIFE_SECS) - 0.7).abs() < 1e-6);
+        assert!((decayed_trust(0.1, TRUST_HALF_LIFE_SECS) - 0.3).abs() < 1e-6);
+        assert!((decayed_trust(1.0, TRUST_HALF_LIFE_SECS * 20) - TRUST_MEAN).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_agent_trust_key_includes_model() {
+        let agent = Agent::new("Cody", AgentRole::Coder);
+        assert_eq!(agent.trust_key(), "Coder/Cody/default");
+        let agent = agent.with_model("fast");
+        assert_eq!(agent.trust_key(), "Coder/Cody/fast");
     }
 
     // ---- Swarm::next_task returns None when empty ----
@@ -20,6 +20,8 @@
 //! - Conditional branching
 //! - Variable substitution
 //! - Tool integration (via handler injection)
+//! - Composition: a `call_workflow` step runs another registered workflow,
+//!   such as one from the project's `.selfware/workflows` library
 //! - Progress tracking
 
 use anyhow::{anyhow, Result};
@@ -182,13 +184,20 @@ pub enum StepType {
     },
     /// Pause for user confirmation
     Pause { message: String },
-    /// Call another workflow
+    /// Call another workflow (`type: call_workflow` or `sub_workflow`)
+    #[serde(alias = "call_workflow")]
     SubWorkflow {
         /// Name of the sub-workflow to execute
         #[serde(rename = "workflow")]
         workflow_name: String,
+        /// Child input name to value; a value that is exactly `${var}`
+        /// passes the variable through unchanged, lists and maps included
         #[serde(default)]
         inputs: HashMap<String, String>,
+        /// Variable to set in the caller to child output name. Empty copies
+        /// every child output under its own name.
+        #[serde(default)]
+        outputs: HashMap<String, String>,
     },
 }
 
@@ -337,6 +346,12 @@ pub struct StepResult {
 /// Maximum recursion depth for nested step execution
 const MAX_RECURSION_DEPTH: usize = 10;
 
+/// Maximum depth of workflows calling workflows
+const MAX_WORKFLOW_DEPTH: usize = 10;
+
+/// Project directory of reusable workflows, loaded by `selfware workflow`
+pub const WORKFLOW_LIBRARY_DIR: &str = ".selfware/workflows";
+
 /// Maximum number of workflow log entries before oldest entries are evicted.
 const MAX_WORKFLOW_LOG_ENTRIES: usize = 1000;
 
@@ -743,6 +758,29 @@ impl WorkflowExecutor {
         self.load_yaml(&content)
     }
 
+    /// Load every `.yaml`/`.yml` workflow in `dir`, returning how many were
+    /// loaded. A missing directory loads nothing.
+    pub fn load_dir(&mut self, dir: &Path) -> Result<usize> {
+        if !dir.is_dir() {
+            return Ok(0);
+        }
+        let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                matches!(
+                    path.extension().and_then(|e| e.to_str()),
+                    Some("yaml" | "yml")
+                )
+            })
+            .collect();
+        paths.sort();
+        for path in &paths {
+            self.load_file(path)
+                .map_err(|e| anyhow!("{}: {}", path.display(), e))?;
+        }
+        Ok(paths.len())
+    }
+
     /// Get workflow by name
     pub fn get(&self, name: &str) -> Option<&Workflow> {
         self.workflows.get(name)
@@ -782,27 +820,28 @@ impl WorkflowExecutor {
         call_stack: Vec<String>,
     ) -> Result<WorkflowResult> {
         // Check for workflow-level cycles
-        if call_stack.contains(&name.to_string()) {
+        if call_stack.iter().any(|caller| caller == name) {
             return Err(anyhow!(
-                "Workflow cycle detected: {} is already in call stack {:?}",
-                name,
-                call_stack
+                "Workflow cycle detected: {} -> {}",
+                call_stack.join(" -> "),
+                name
             ));
         }
 
         // Check max workflow nesting depth
-        const MAX_WORKFLOW_DEPTH: usize = 10;
         if call_stack.len() >= MAX_WORKFLOW_DEPTH {
             return Err(anyhow!(
-                "Maximum workflow nesting depth ({}) exceeded",
-                MAX_WORKFLOW_DEPTH
+                "Maximum workflow nesting depth ({}) exceeded: {} -> {}",
+                MAX_WORKFLOW_DEPTH,
+                call_stack.join(" -> "),
+                name
             ));
         }
 
         let workflow = self
             .workflows
             .get(name)
-            .ok_or_else(|| anyhow!("Workflow not found: {}", name))?
+            .ok_or_else(|| self.not_found("Workflow", name))?
             .clone();
 
         let mut context = WorkflowContext::new(working_dir.clone());
@@ -1610,10 +1649,22 @@ impl WorkflowExecutor {
             StepType::SubWorkflow {
                 workflow_name,
                 inputs,
+                outputs,
             } => {
                 let resolved_inputs: HashMap<String, VarValue> = inputs
                     .iter()
-                    .map(|(k, v)| (k.clone(), VarValue::String(context.substitute(v))))
+                    .map(|(k, v)| {
+                        // `${var}` alone passes the value itself, not its text
+                        let passed = v
+                            .trim()
+                            .strip_prefix("${")
+                            .and_then(|rest| rest.strip_suffix('}'))
+                            .and_then(|var| context.get_var(var));
+                        let value = passed
+                            .cloned()
+                            .unwrap_or_else(|| VarValue::String(context.substitute(v)));
+                        (k.clone(), value)
+                    })
                     .collect();
 
                 if self.dry_run {
@@ -1648,9 +1699,22 @@ impl WorkflowExecutor {
                     ))
                     .await?;
 
-                    // Merge sub-workflow outputs into current context
-                    for (key, value) in &sub_result.outputs {
-                        context.set_var(key, value.clone());
+                    // Thread sub-workflow outputs back into the current context
+                    if outputs.is_empty() {
+                        for (key, value) in &sub_result.outputs {
+                            context.set_var(key, value.clone());
+                        }
+                    } else {
+                        for (var, output) in outputs {
+                            let value = sub_result.outputs.get(output).ok_or_else(|| {
+                                anyhow!(
+                                    "Sub-workflow '{}' has no output '{}'",
+                                    workflow_name,
+                                    output
+                                )
+                            })?;
+                            context.set_var(var, value.clone());
+                        }
                     }
 
                     // Log sub-workflow completion
@@ -1664,10 +1728,7 @@ impl WorkflowExecutor {
                     );
 
                     if sub_result.is_success() {
-                        Ok(VarValue::String(format!(
-                            "sub-workflow {} completed successfully",
-                            workflow_name
-                        )))
+                        Ok(VarValue::Map(sub_result.outputs))
                     } else {
                         Err(anyhow!(
                             "Sub-workflow '{}' failed: {:?}",
@@ -1676,13 +1737,31 @@ impl WorkflowExecutor {
                         ))
                     }
                 } else {
-                    Err(anyhow!("Sub-workflow '{}' not found", workflow_name))
+                    Err(self.not_found("Sub-workflow", workflow_name))
                 }
             }
         }
     }
 }
 
+impl WorkflowExecutor {
+    /// Error for a workflow that is not registered, naming those that are
+    fn not_found(&self, what: &str, name: &str) -> anyhow::Error {
+        let mut known: Vec<&str> = self.workflows.keys().map(String::as_str).collect();
+        known.sort_unstable();
+        if known.is_empty() {
+            anyhow!("{} '{}' not found; none are registered", what, name)
+        } else {
+            anyhow!(
+                "{} '{}' not found; registered: {}",
+                what,
+                name,
+                known.join(", ")
+            )
+        }
+    }
+}
+
 impl Default for WorkflowExecutor {
     fn default() -> Self {
         Self::new()
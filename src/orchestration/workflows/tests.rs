@@ -791,6 +791,7 @@ async fn test_sub_workflow_step() {
     let step_type = StepType::SubWorkflow {
         workflow_name: "sub_wf".into(),
         inputs: HashMap::from([("param".into(), "value".into())]),
+        outputs: HashMap::new(),
     };
 
     // In dry-run, sub-workflow returns placeholder even if not registered
@@ -2597,6 +2598,7 @@ async fn test_sub_workflow_not_found_live() {
     let step_type = StepType::SubWorkflow {
         workflow_name: "missing_wf".into(),
         inputs: HashMap::new(),
+        outputs: HashMap::new(),
     };
     let result = executor.execute_step_inner(&step_type, &mut ctx).await;
     assert!(result.is_err());
@@ -2613,6 +2615,7 @@ async fn test_sub_workflow_dryrun_substitution() {
     let step_type = StepType::SubWorkflow {
         workflow_name: "deploy".into(),
         inputs: HashMap::from([("environment".into(), "${env}".into())]),
+        outputs: HashMap::new(),
     };
     let result = executor
         .execute_step_inner(&step_type, &mut ctx)
@@ -3377,6 +3380,7 @@ async fn test_sub_workflow_inputs_passed() {
                 step_type: StepType::SubWorkflow {
                     workflow_name: "child_input".into(),
                     inputs: HashMap::from([("input_name".into(), "${user_name}".into())]),
+                    outputs: HashMap::new(),
                 },
                 required: true,
                 retry: RetryConfig::default(),
@@ -4834,3 +4838,108 @@ async fn test_mixed_required_optional_steps() {
     assert_eq!(result.step_results["s4"].status, StepStatus::Failed);
     assert_eq!(result.step_results["s5"].status, StepStatus::Completed);
 }
+
+// --- call_workflow: composing workflows from a library ---
+
+#[tokio::test]
+async fn test_call_workflow_composes_library_workflows() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("bump-version.yaml"),
+        r#"
+name: bump-version
+inputs:
+  - name: version
+    required: true
+outputs:
+  - name: new_version
+    from: version
+steps:
+  - id: bump
+    name: Bump
+    type: log
+    message: "Bumping to ${version}"
+"#,
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("release.yml"),
+        r#"
+name: release
+inputs:
+  - name: next
+    default: "1.2.0"
+steps:
+  - id: bump
+    name: Bump version
+    type: call_workflow
+    workflow: bump-version
+    inputs:
+      version: "${next}"
+    outputs:
+      released: new_version
+  - id: announce
+    name: Announce
+    type: log
+    message: "Released ${released}"
+    depends_on:
+      - bump
+  - id: loop_back
+    name: Call a missing workflow
+    type: call_workflow
+    workflow: run-ci
+    required: false
+"#,
+    )
+    .unwrap();
+    std::fs::write(dir.path().join("notes.txt"), "not a workflow").unwrap();
+
+    let mut executor = WorkflowExecutor::new();
+    assert_eq!(executor.load_dir(dir.path()).unwrap(), 2);
+    assert_eq!(executor.load_dir(&dir.path().join("missing")).unwrap(), 0);
+
+    let result = executor
+        .execute("release", HashMap::new(), PathBuf::from("/tmp"))
+        .await
+        .unwrap();
+    assert!(result.is_success(), "{:?}", result.logs);
+    assert!(result
+        .logs
+        .iter()
+        .any(|l| l.message.contains("Released 1.2.0")));
+    match &result.step_results["bump"].output {
+        Some(VarValue::Map(outputs)) => {
+            assert_eq!(outputs["new_version"].as_string().unwrap(), "1.2.0")
+        }
+        other => panic!("Expected child outputs, got {:?}", other),
+    }
+    let missing = result.step_results["loop_back"].error.as_deref().unwrap();
+    assert!(missing.contains("'run-ci' not found"), "{}", missing);
+    assert!(missing.contains("bump-version, release"), "{}", missing);
+}
+
+#[tokio::test]
+async fn test_call_workflow_reports_cycle_path() {
+    let mut executor = WorkflowExecutor::new();
+    for (name, callee) in [("cycle_a", "cycle_b"), ("cycle_b", "cycle_a")] {
+        executor
+            .load_yaml(&format!(
+                "name: {}\nsteps:\n  - id: call\n    name: Call\n    type: call_workflow\n    workflow: {}\n",
+                name, callee
+            ))
+            .unwrap();
+    }
+    let result = executor
+        .execute("cycle_a", HashMap::new(), PathBuf::from("/tmp"))
+        .await
+        .unwrap();
+    assert!(!result.is_success());
+    assert!(
+        result
+            .logs
+            .iter()
+            .any(|l| l.message.contains("cycle_a -> cycle_b -> cycle_a")),
+        "{:?}",
+        result.logs
+    );
+}
@@ -0,0 +1,535 @@
+//! Streaming Markdown Renderer
+//!
+//! Styles assistant markdown as it streams in: headers, lists, quotes and
+//! inline code are rendered line by line, and fenced code blocks are
+//! syntax highlighted once their closing fence arrives. Text is held back
+//! only while a construct is still open (the start of a line that might be
+//! a header, an unclosed backtick, a code block body); everything else is
+//! passed straight through.
+//!
+//! Without color (`--no-color`, `--accessible`, `NO_COLOR`) the renderer
+//! passes text through unchanged, except that in dyslexia-friendly mode
+//! code blocks are still spaced out and diffs get their shape markers.
+
+use std::sync::OnceLock;
+
+use colored::*;
+
+use crate::ui::accessibility::dyslexia_friendly;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::ThemeSet;
+use syntect::parsing::SyntaxSet;
+use syntect::util::as_24_bit_terminal_escaped;
+
+/// Highlighting theme for code blocks
+const CODE_THEME: &str = "base16-ocean.dark";
+
+/// Syntax definitions and themes, loaded on the first highlighted block
+fn highlighting() -> &'static (SyntaxSet, ThemeSet) {
+    static HIGHLIGHTING: OnceLock<(SyntaxSet, ThemeSet)> = OnceLock::new();
+    HIGHLIGHTING.get_or_init(|| {
+        (
+            SyntaxSet::load_defaults_newlines(),
+            ThemeSet::load_defaults(),
+        )
+    })
+}
+
+/// How the line being streamed is rendered
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum LineKind {
+    /// Not enough of the line has arrived to tell
+    Pending,
+    /// Paragraph text, streamed as it arrives
+    Paragraph,
+    /// Header, list item, quote, rule or fence, rendered once complete
+    Block,
+}
+
+/// A code block whose closing fence has not arrived yet
+#[derive(Debug)]
+struct OpenFence {
+    /// The fence characters, e.g. "```"
+    marker: String,
+    lang: String,
+    body: String,
+}
+
+/// Incremental markdown-to-terminal renderer for streamed text.
+///
+/// Feed chunks to [`push`](Self::push) and print what it returns; call
+/// [`finish`](Self::finish) at the end of the stream to flush anything
+/// still held back.
+#[derive(Debug)]
+pub struct MarkdownStreamRenderer {
+    color: bool,
+    /// Dyslexia-friendly code and diff rendering
+    friendly: bool,
+    /// Text of the current line not yet rendered
+    line: String,
+    kind: LineKind,
+    fence: Option<OpenFence>,
+}
+
+impl Default for MarkdownStreamRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MarkdownStreamRenderer {
+    /// Create a renderer that styles output when the terminal has color
+    pub fn new() -> Self {
+        Self::with_color(colored::control::SHOULD_COLORIZE.should_colorize())
+    }
+
+    /// Create a renderer that styles output only when `color` is set
+    pub fn with_color(color: bool) -> Self {
+        Self {
+            color,
+            friendly: dyslexia_friendly::is_enabled(),
+            line: String::new(),
+            kind: LineKind::Pending,
+            fence: None,
+        }
+    }
+
+    /// Override the dyslexia-friendly setting taken from the config
+    pub fn with_dyslexia_friendly(mut self, friendly: bool) -> Self {
+        self.friendly = friendly;
+        self
+    }
+
+    /// Add streamed text, returning what can be printed now
+    pub fn push(&mut self, text: &str) -> String {
+        let plain = !self.color;
+        if plain && !self.friendly {
+            return text.to_string();
+        }
+        let mut out = String::new();
+        let mut rest = text;
+        while let Some(pos) = rest.find('\n') {
+            self.line.push_str(&rest[..pos]);
+            if plain {
+                self.end_plain_line(&mut out);
+            } else {
+                self.end_line(&mut out);
+            }
+            rest = &rest[pos + 1..];
+        }
+        self.line.push_str(rest);
+        if plain {
+            // Only whole lines can be spaced out
+            return out;
+        }
+        self.stream_partial(&mut out);
+        out
+    }
+
+    /// Flush everything still held back. An unclosed code block is emitted
+    /// as plain text rather than dropped.
+    pub fn finish(&mut self) -> String {
+        let mut out = String::new();
+        if !self.color {
+            self.fence = None;
+            out.push_str(&self.line);
+        } else if let Some(fence) = self.fence.take() {
+            out.push_str(&fence.body);
+            out.push_str(&self.line);
+        } else {
+            match self.kind {
+                LineKind::Paragraph => out.push_str(&render_inline(&self.line)),
+                _ if self.line.trim().is_empty() || opening_fence(&self.line).is_some() => {
+                    out.push_str(&self.line)
+                }
+                LineKind::Pending | LineKind::Block => out.push_str(&render_block_line(&self.line)),
+            }
+        }
+        self.line.clear();
+        self.kind = LineKind::Pending;
+        out
+    }
+
+    /// Render as much of the unfinished current line as is safe
+    fn stream_partial(&mut self, out: &mut String) {
+        if self.fence.is_some() {
+            return;
+        }
+        if self.kind == LineKind::Pending {
+            self.kind = classify(&self.line);
+        }
+        if self.kind == LineKind::Paragraph {
+            let held = inline_hold_point(&self.line);
+            out.push_str(&render_inline(&self.line[..held]));
+            self.line.drain(..held);
+        }
+    }
+
+    /// Render the current line now that its newline has arrived
+    fn end_line(&mut self, out: &mut String) {
+        let line = std::mem::take(&mut self.line);
+        let kind = std::mem::replace(&mut self.kind, LineKind::Pending);
+
+        if let Some(mut fence) = self.fence.take() {
+            if is_closing_fence(&line, &fence.marker) {
+                out.push_str(&render_code(&fence.lang, &fence.body, true, self.friendly));
+                out.push_str(&format!("{}\n", line.dimmed()));
+            } else {
+                fence.body.push_str(&line);
+                fence.body.push('\n');
+                self.fence = Some(fence);
+            }
+            return;
+        }
+
+        if kind == LineKind::Paragraph {
+            out.push_str(&render_inline(&line));
+            out.push('\n');
+            return;
+        }
+        if let Some((marker, lang)) = opening_fence(&line) {
+            out.push_str(&format!("{}\n", line.dimmed()));
+            self.fence = Some(OpenFence {
+                marker,
+                lang,
+                body: String::new(),
+            });
+            return;
+        }
+        out.push_str(&render_block_line(&line));
+        out.push('\n');
+    }
+    /// Pass an uncolored line through, spacing out code blocks and marking
+    /// diff lines in dyslexia-friendly mode
+    fn end_plain_line(&mut self, out: &mut String) {
+        let line = std::mem::take(&mut self.line);
+        if let Some(fence) = self.fence.as_ref() {
+            if is_closing_fence(&line, &fence.marker) {
+                self.fence = None;
+                out.push_str(&line);
+                out.push('\n');
+            } else {
+                out.push_str(&render_code(
+                    &fence.lang,
+                    &format!("{}\n", line),
+                    false,
+                    true,
+                ));
+            }
+            return;
+        }
+        if let Some((marker, lang)) = opening_fence(&line) {
+            self.fence = Some(OpenFence {
+                marker,
+                lang,
+                body: String::new(),
+            });
+        }
+        out.push_str(&line);
+        out.push('\n');
+    }
+}
+
+/// Decide how a line is rendered from its first characters
+fn classify(line: &str) -> LineKind {
+    let trimmed = line.trim_start();
+    match trimmed.chars().next() {
+        None => LineKind::Pending,
+        Some('#' | '-' | '*' | '+' | '>' | '`' | '~' | '_') => LineKind::Block,
+        Some(c) if c.is_ascii_digit() => LineKind::Block,
+        Some(_) => LineKind::Paragraph,
+    }
+}
+
+/// The fence marker and language if `line` opens a code block
+fn opening_fence(line: &str) -> Option<(String, String)> {
+    let trimmed = line.trim_start();
+    let fence_char = trimmed.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let len = trimmed.chars().take_while(|c| *c == fence_char).count();
+    if len < 3 || line.len() - trimmed.len() > 3 {
+        return None;
+    }
+    let info = trimmed[len..].trim();
+    if fence_char == '`' && info.contains('`') {
+        return None;
+    }
+    let lang = info.split_whitespace().next().unwrap_or("").to_string();
+    Some((trimmed[..len].to_string(), lang))
+}
+
+/// Whether `line` closes a block opened with `marker`
+fn is_closing_fence(line: &str, marker: &str) -> bool {
+    let trimmed = line.trim();
+    let fence_char = marker.chars().next().unwrap_or('`');
+    trimmed.len() >= marker.len() && trimmed.chars().all(|c| c == fence_char)
+}
+
+/// Render a finished code block: highlighted when `color`, and in
+/// dyslexia-friendly mode spaced out, with diffs given shape markers
+fn render_code(lang: &str, code: &str, color: bool, friendly: bool) -> String {
+    if !friendly {
+        return highlight(lang, code, CODE_THEME);
+    }
+    if dyslexia_friendly::is_diff_lang(lang) {
+        return dyslexia_friendly::render_diff(code, color);
+    }
+    if color {
+        dyslexia_friendly::spaced(&highlight(lang, code, dyslexia_friendly::CODE_THEME))
+    } else {
+        dyslexia_friendly::spaced(code)
+    }
+}
+
+/// Syntax highlight a code block, falling back to plain text for unknown
+/// languages
+fn highlight(lang: &str, code: &str, theme: &str) -> String {
+    let (syntaxes, themes) = highlighting();
+    let theme = &themes.themes.get(theme).cloned().unwrap_or_default();
+    let Some(syntax) = (!lang.is_empty())
+        .then(|| syntaxes.find_syntax_by_token(lang))
+        .flatten()
+    else {
+        return code.to_string();
+    };
+    let mut highlighter = HighlightLines::new(syntax, theme);
+    let mut out = String::new();
+    for line in syntect::util::LinesWithEndings::from(code) {
+        match highlighter.highlight_line(line, syntaxes) {
+            Ok(ranges) => {
+                out.push_str(&as_24_bit_terminal_escaped(&ranges, false));
+                out.push_str("\x1b[0m");
+            }
+            Err(_) => out.push_str(line),
+        }
+    }
+    out
+}
+
+/// Render a complete header, list item, quote or rule line
+fn render_block_line(line: &str) -> String {
+    let trimmed = line.trim_start();
+    let indent = &line[..line.len() - trimmed.len()];
+
+    let hashes = trimmed.chars().take_while(|c| *c == '#').count();
+    if (1..=6).contains(&hashes) && trimmed[hashes..].starts_with(' ') {
+        let title = render_inline(trimmed[hashes..].trim());
+        return if hashes == 1 {
+            title.bright_cyan().bold().underline().to_string()
+        } else {
+            title.bright_cyan().bold().to_string()
+        };
+    }
+
+    let rule_char = trimmed.chars().next().unwrap_or(' ');
+    if matches!(rule_char, '-' | '*' | '_')
+        && trimmed.chars().filter(|c| !c.is_whitespace()).count() >= 3
+        && trimmed.chars().all(|c| c == rule_char || c.is_whitespace())
+    {
+        return "─".repeat(40).dimmed().to_string();
+    }
+
+    if let Some(item) = ["- ", "* ", "+ "]
+        .iter()
+        .find_map(|bullet| trimmed.strip_prefix(bullet))
+    {
+        return format!("{}{} {}", indent, "•".bright_cyan(), render_inline(item));
+    }
+
+    let digits = trimmed.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits > 0 {
+        let after = &trimmed[digits..];
+        if let Some(item) = after
+            .strip_prefix(". ")
+            .or_else(|| after.strip_prefix(") "))
+        {
+            let number = &trimmed[..digits + 1];
+            return format!("{}{} {}", indent, number.bright_cyan(), render_inline(item));
+        }
+    }
+
+    if let Some(quote) = trimmed.strip_prefix('>') {
+        return format!(
+            "{}{} {}",
+            indent,
+            "│".dimmed(),
+            render_inline(quote.trim_start()).italic()
+        );
+    }
+
+    render_inline(line)
+}
+
+/// Byte offset up to which a paragraph line can be rendered without
+/// splitting an inline code span or bold run that is still streaming
+fn inline_hold_point(text: &str) -> usize {
+    let bytes = text.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'`' => match text[i + 1..].find('`') {
+                Some(end) => i += end + 2,
+                None => return i,
+            },
+            b'*' if i + 1 == bytes.len() => return i,
+            b'*' if bytes[i + 1] == b'*' => match text[i + 2..].find("**") {
+                Some(end) => i += end + 4,
+                None => return i,
+            },
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+/// Style inline code and bold runs; unclosed markers are left as typed
+fn render_inline(text: &str) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    loop {
+        let next = rest.find(['`', '*']);
+        let Some(pos) = next else {
+            out.push_str(rest);
+            return out;
+        };
+        out.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+        if let Some(code) = rest.strip_prefix('`') {
+            if let Some(end) = code.find('`') {
+                out.push_str(&code[..end].bright_yellow().to_string());
+                rest = &code[end + 1..];
+                continue;
+            }
+        } else if let Some(bold) = rest.strip_prefix("**") {
+            if let Some(end) = bold.find("**").filter(|end| *end > 0) {
+                out.push_str(&bold[..end].bold().to_string());
+                rest = &bold[end + 2..];
+                continue;
+            }
+        }
+        // A marker with nothing to close it
+        let width = if rest.starts_with("**") { 2 } else { 1 };
+        out.push_str(&rest[..width]);
+        rest = &rest[width..];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(chunks: &[&str]) -> String {
+        let mut renderer = MarkdownStreamRenderer::with_color(true);
+        let mut out = String::new();
+        for chunk in chunks {
+            out.push_str(&renderer.push(chunk));
+        }
+        out.push_str(&renderer.finish());
+        out
+    }
+
+    fn strip_ansi(text: &str) -> String {
+        let mut out = String::new();
+        let mut chars = text.chars();
+        while let Some(c) = chars.next() {
+            if c == '\x1b' {
+                for c in chars.by_ref() {
+                    if c.is_ascii_alphabetic() {
+                        break;
+                    }
+                }
+            } else {
+                out.push(c);
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn test_markdown_stream_styles_blocks_across_chunks() {
+        let out = render(&[
+            "## Pl",
+            "an\n- first `st",
+            "ep`\n2. se",
+            "cond\nplain **bo",
+            "ld** text\n",
+        ]);
+        assert_eq!(
+            strip_ansi(&out),
+            "Plan\n• first step\n2. second\nplain bold text\n"
+        );
+        assert!(out.contains(&"Plan".bright_cyan().bold().to_string()));
+        assert!(out.contains(&"step".bright_yellow().to_string()));
+        assert!(out.contains(&"bold".bold().to_string()));
+    }
+
+    #[test]
+    fn test_markdown_stream_only_buffers_open_constructs() {
+        let mut renderer = MarkdownStreamRenderer::with_color(true);
+        // Paragraph text streams immediately
+        assert_eq!(renderer.push("Hello wor"), "Hello wor");
+        // An unclosed code span is held until it closes
+        assert_eq!(renderer.push("ld `fo"), "ld ");
+        assert!(renderer.push("o` bar").contains("foo"));
+        // A line that may be a header is held until its newline
+        renderer.push("\n");
+        assert_eq!(renderer.push("# Ti"), "");
+        assert!(!renderer.push("tle\n").is_empty());
+    }
+
+    #[test]
+    fn test_markdown_stream_highlights_code_on_closing_fence() {
+        let mut renderer = MarkdownStreamRenderer::with_color(true);
+        let opened = renderer.push("```rust\nfn main() {\n");
+        assert_eq!(strip_ansi(&opened), "```rust\n");
+        assert_eq!(
+            renderer.fence.as_ref().map(|f| f.lang.as_str()),
+            Some("rust")
+        );
+        assert_eq!(renderer.push("    let x = 1;\n"), "");
+        let closed = renderer.push("}\n```\nafter\n");
+        assert!(closed.contains("\x1b[38;2;"));
+        assert_eq!(
+            strip_ansi(&closed),
+            "fn main() {\n    let x = 1;\n}\n```\nafter\n"
+        );
+    }
+
+    #[test]
+    fn test_markdown_stream_flushes_unclosed_fence_as_plain_text() {
+        let out = render(&["intro\n```python\nprint('hi')\n", "x = `1"]);
+        assert_eq!(strip_ansi(&out), "intro\n```python\nprint('hi')\nx = `1");
+    }
+
+    #[test]
+    fn test_markdown_stream_plain_without_color() {
+        let mut renderer = MarkdownStreamRenderer::with_color(false);
+        let text = "# Title\n```rust\nfn x() {}\n";
+        assert_eq!(renderer.push(text), text);
+        assert_eq!(renderer.finish(), "");
+    }
+
+    #[test]
+    fn test_markdown_dyslexia_friendly_code_and_diffs() {
+        let mut renderer = MarkdownStreamRenderer::with_color(false).with_dyslexia_friendly(true);
+        let mut out = renderer.push("See:\n```diff\n-old\n+new\n```\n```rust\nlet a");
+        out.push_str(&renderer.push(" = 1;\n```\n- item"));
+        out.push_str(&renderer.finish());
+        let mut renderer = MarkdownStreamRenderer::with_color(true).with_dyslexia_friendly(true);
+        let colored = renderer.push("```diff\n+new\n```\n");
+
+        assert_eq!(
+            out,
+            "See:\n```diff\n[-] old\n\n[+] new\n\n```\n```rust\nlet a = 1;\n\n```\n- item"
+        );
+        assert!(strip_ansi(&colored).contains("[+] new\n\n"));
+    }
+
+    #[test]
+    fn test_markdown_inline_leaves_unclosed_markers() {
+        assert_eq!(render_inline("2 * 3 and **open"), "2 * 3 and **open");
+        assert_eq!(inline_hold_point("a `b"), 2);
+        assert_eq!(inline_hold_point("a *"), 2);
+        assert_eq!(inline_hold_point("a * b"), 5);
+        assert_eq!(inline_hold_point("**x** y"), 7);
+    }
+}
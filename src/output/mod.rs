@@ -5,14 +5,24 @@
 //! - `verbose_mode`: Extra detail, show reasoning, debug info
 //! - `show_tokens`: Display token usage after responses
 //! - `show_mascot`: Display ASCII fox mascot during key moments
+//!
+//! Streamed assistant markdown is styled by [`MarkdownStreamRenderer`].
 
 use colored::*;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 
+pub mod markdown;
+
+pub use markdown::MarkdownStreamRenderer;
+
 /// Global output mode flags (set once at startup)
 static COMPACT_MODE: AtomicBool = AtomicBool::new(false);
 static VERBOSE_MODE: AtomicBool = AtomicBool::new(false);
 static SHOW_TOKENS: AtomicBool = AtomicBool::new(false);
+/// Set by `--quiet` / `--json`
+static QUIET_MODE: AtomicBool = AtomicBool::new(false);
+/// Set while the user's estimated cognitive load is high
+static HIGH_LOAD: AtomicBool = AtomicBool::new(false);
 
 /// Token counters for the session
 static TOTAL_PROMPT_TOKENS: AtomicU64 = AtomicU64::new(0);
@@ -25,10 +35,31 @@ pub(crate) fn init(compact: bool, verbose: bool, show_tokens: bool) {
     SHOW_TOKENS.store(show_tokens, Ordering::SeqCst);
 }
 
-/// Check if compact mode is enabled
+/// Suppress optional messages such as break reminders
+pub(crate) fn set_quiet(quiet: bool) {
+    QUIET_MODE.store(quiet, Ordering::SeqCst);
+}
+
+/// Check if quiet mode is enabled
+#[inline]
+pub(crate) fn is_quiet() -> bool {
+    QUIET_MODE.load(Ordering::SeqCst)
+}
+
+/// Check if compact mode is enabled, either explicitly or because the
+/// user's cognitive load is high (verbose mode wins over the latter)
 #[inline]
 pub(crate) fn is_compact() -> bool {
     COMPACT_MODE.load(Ordering::SeqCst)
+        || (HIGH_LOAD.load(Ordering::SeqCst) && !VERBOSE_MODE.load(Ordering::SeqCst))
+}
+
+/// Adapt rendering to the user's estimated cognitive load
+pub(crate) fn set_cognitive_load(level: crate::cognitive::load::LoadLevel) {
+    HIGH_LOAD.store(
+        level == crate::cognitive::load::LoadLevel::High,
+        Ordering::SeqCst,
+    );
 }
 
 /// Check if verbose mode is enabled
@@ -68,6 +99,58 @@ pub(crate) fn reset_tokens() {
     TOTAL_COMPLETION_TOKENS.store(0, Ordering::SeqCst);
 }
 
+/// Rough USD cost at $3 / $15 per million prompt / completion tokens
+pub(crate) fn estimate_cost_usd(prompt: u64, completion: u64) -> f64 {
+    (prompt as f64 * 3.0 + completion as f64 * 15.0) / 1_000_000.0
+}
+
+/// Points stdout elsewhere until dropped: at stderr, so a `--json` run
+/// leaves stdout holding nothing but the final JSON document, or at a file,
+/// so agent output does not draw over a full-screen view. A no-op off Unix.
+pub(crate) struct StdoutRedirect {
+    #[cfg(unix)]
+    saved: Option<std::os::fd::OwnedFd>,
+}
+
+impl StdoutRedirect {
+    /// Redirect stdout to stderr
+    pub(crate) fn begin() -> Self {
+        Self::to(std::io::stderr())
+    }
+
+    /// Redirect stdout to `file`
+    #[cfg(feature = "tui")]
+    pub(crate) fn to_file(file: &std::fs::File) -> Self {
+        Self::to(file)
+    }
+
+    #[cfg(unix)]
+    fn to(target: impl std::os::fd::AsFd) -> Self {
+        use std::io::Write;
+        let _ = std::io::stdout().flush();
+        let saved = nix::unistd::dup(std::io::stdout())
+            .ok()
+            .filter(|_| nix::unistd::dup2_stdout(target).is_ok());
+        Self { saved }
+    }
+
+    #[cfg(not(unix))]
+    fn to<T>(_target: T) -> Self {
+        Self {}
+    }
+}
+
+impl Drop for StdoutRedirect {
+    fn drop(&mut self) {
+        use std::io::Write;
+        let _ = std::io::stdout().flush();
+        #[cfg(unix)]
+        if let Some(saved) = self.saved.take() {
+            let _ = nix::unistd::dup2_stdout(&saved);
+        }
+    }
+}
+
 /// Print token usage summary
 pub(crate) fn print_token_usage(prompt: u64, completion: u64) {
     if should_show_tokens() {
@@ -164,7 +247,7 @@ pub(crate) fn semantic_summary(
 
         // === Shell ===
         "shell_exec" => {
-            let cmd = extract_command(args).unwrap_or("?");
+            let cmd = crate::tools::shell::command_line(args).unwrap_or_else(|| "?".into());
             let short_cmd = if cmd.chars().count() > 40 {
                 &cmd[..cmd
                     .char_indices()
@@ -172,7 +255,7 @@ pub(crate) fn semantic_summary(
                     .map(|(i, _)| i)
                     .unwrap_or(cmd.len())]
             } else {
-                cmd
+                &cmd
             };
             let exit_code =
                 result_json(result).and_then(|v| v.get("exit_code").and_then(|c| c.as_i64()));
@@ -194,6 +277,8 @@ pub(crate) fn semantic_summary(
                     })
                     .unwrap_or("all");
                 format!("Tests: {} passed", passed)
+            } else if result.is_some_and(|r| r.contains("\"outcome\":\"compile_error\"")) {
+                "Tests: failed to compile".to_string()
             } else {
                 "Tests: some failed".to_string()
             }
@@ -287,6 +372,17 @@ pub(crate) fn semantic_summary(
                 "Git diff".to_string()
             }
         }
+        "git_blame" => {
+            let path = extract_path(args).unwrap_or("?");
+            let commits = result_json(result).and_then(|v| {
+                v.get("commits")
+                    .and_then(|c| c.as_object().map(|c| c.len()))
+            });
+            match commits {
+                Some(n) => format!("Blame {} ({} commits)", path, n),
+                None => format!("Blame {}", path),
+            }
+        }
         "git_log" => "Git log".to_string(),
         "git_commit" => "Git commit".to_string(),
         "git_checkpoint" => {
@@ -329,6 +425,29 @@ pub(crate) fn semantic_summary(
             format!("HTTP {} {}", method, short_url)
         }
 
+        // === Contracts ===
+        "contract_generate" => {
+            let consumer = args.get("consumer").and_then(|v| v.as_str()).unwrap_or("?");
+            let provider = args.get("provider").and_then(|v| v.as_str()).unwrap_or("?");
+            format!("Contract {} → {}", consumer, provider)
+        }
+        "contract_verify" => {
+            let contract = args.get("contract").and_then(|v| v.as_str()).unwrap_or("?");
+            format!("Verify contract {}", contract)
+        }
+
+        // === Review ===
+        "code_review" => {
+            let comments = result_json(result).and_then(|v| {
+                v.get("comments")
+                    .and_then(|c| c.as_array().map(|a| a.len()))
+            });
+            match comments {
+                Some(n) => format!("Code review ({} comments)", n),
+                None => "Code review".to_string(),
+            }
+        }
+
         // === Process management ===
         "process_start" => {
             let cmd = extract_command(args).unwrap_or("process");
@@ -518,6 +637,11 @@ pub(crate) fn semantic_summary(
         }
         "knowledge_export" => "Knowledge export".to_string(),
 
+        "tool_output" => {
+            let call_id = args.get("call_id").and_then(|v| v.as_str()).unwrap_or("?");
+            format!("Read elided output of {}", call_id)
+        }
+
         // === Fallback ===
         _ => format!("{} ({}ms)", tool_name, duration_ms),
     }
@@ -545,7 +669,7 @@ pub(crate) fn tool_activity_message(name: &str, args: &serde_json::Value) -> Str
         "file_delete" => format!("Deleting {}...", extract_path(args).unwrap_or("file")),
         "shell_exec" => format!(
             "Running {}...",
-            extract_command(args)
+            crate::tools::shell::command_line(args)
                 .map(|c| {
                     if c.chars().count() > 40 {
                         c.chars().take(40).collect::<String>()
@@ -568,6 +692,7 @@ pub(crate) fn tool_activity_message(name: &str, args: &serde_json::Value) -> Str
         ),
         "git_status" => "Checking git status...".to_string(),
         "git_diff" => "Getting diff...".to_string(),
+        "git_blame" => format!("Blaming {}...", extract_path(args).unwrap_or("?")),
         "git_log" => "Reading git log...".to_string(),
         "git_commit" => "Committing...".to_string(),
         "git_push" => "Pushing...".to_string(),
@@ -623,19 +748,26 @@ pub(crate) fn thinking(text: &str, inline: bool) {
     }
 }
 
-/// Print thinking prefix (for streaming)
-pub(crate) fn thinking_prefix() {
+/// Print intent detection message
+pub(crate) fn intent_without_action() {
     if !is_compact() {
-        print!("{} ", "Thinking:".dimmed());
+        println!(
+            "{}",
+            "🔄 Model described intent but didn't act - prompting for action...".bright_yellow()
+        );
     }
 }
 
-/// Print intent detection message
-pub(crate) fn intent_without_action() {
+/// Print context overflow recovery message
+pub(crate) fn context_overflow_recovered(dropped_tokens: usize) {
     if !is_compact() {
         println!(
             "{}",
-            "🔄 Model described intent but didn't act - prompting for action...".bright_yellow()
+            format!(
+                "🗜️ Request exceeded the model's context window - trimmed ~{} tokens and retrying...",
+                dropped_tokens
+            )
+            .bright_yellow()
         );
     }
 }
@@ -11,11 +11,13 @@ pub mod disk;
 pub mod gpu;
 pub mod memory;
 pub mod quotas;
+pub mod sampler;
 
 pub use disk::DiskManager;
 pub use gpu::GpuManager;
 pub use memory::MemoryManager;
 pub use quotas::AdaptiveQuotas;
+pub use sampler::{PressureSample, PressureSampler};
 
 /// Resource manager for coordinating all resource types
 pub struct ResourceManager {
@@ -161,18 +163,7 @@ impl ResourceManager {
 
         // Determine overall pressure
         let max_ratio = memory_ratio.max(gpu_memory_ratio);
-
-        if max_ratio > self.config.memory.emergency_threshold {
-            ResourcePressure::Critical
-        } else if max_ratio > self.config.memory.critical_threshold {
-            ResourcePressure::High
-        } else if max_ratio > self.config.memory.warning_threshold {
-            ResourcePressure::Medium
-        } else if max_ratio > 0.5 {
-            ResourcePressure::Low
-        } else {
-            ResourcePressure::None
-        }
+        sampler::pressure_for_ratio(max_ratio, &self.config.memory)
     }
 
     /// Handle resource pressure
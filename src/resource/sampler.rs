@@ -0,0 +1,171 @@
+//! Host pressure sampling for the agent loop
+//!
+//! [`ResourceManager`](super::ResourceManager) runs a background monitor
+//! with GPU and disk managers behind it. The agent loop only needs a cheap
+//! memory/CPU reading once per iteration, taken by [`PressureSampler`].
+
+use super::ResourcePressure;
+use crate::config::{MemoryConfig, PauseConfig};
+use sysinfo::System;
+
+/// Pressure level for a fraction of memory in use
+pub fn pressure_for_ratio(ratio: f32, config: &MemoryConfig) -> ResourcePressure {
+    if ratio > config.emergency_threshold {
+        ResourcePressure::Critical
+    } else if ratio > config.critical_threshold {
+        ResourcePressure::High
+    } else if ratio > config.warning_threshold {
+        ResourcePressure::Medium
+    } else if ratio > 0.5 {
+        ResourcePressure::Low
+    } else {
+        ResourcePressure::None
+    }
+}
+
+/// One reading of host memory and CPU
+#[derive(Debug, Clone, Copy)]
+pub struct PressureSample {
+    /// Fraction of RAM in use (total minus available)
+    pub memory_ratio: f32,
+    /// Fraction of total CPU in use since the previous sample
+    pub cpu_ratio: f32,
+    pub pressure: ResourcePressure,
+    /// Whether the agent should stop and wait before its next step
+    pub pause: bool,
+}
+
+impl PressureSample {
+    /// Status bar text, or `None` while pressure is low
+    pub fn label(&self) -> Option<String> {
+        if !self.pause && !self.pressure.requires_action() {
+            return None;
+        }
+        Some(format!(
+            "mem {:.0}% cpu {:.0}%",
+            self.memory_ratio * 100.0,
+            self.cpu_ratio * 100.0
+        ))
+    }
+}
+
+/// Samples host memory and CPU against the `[resources]` thresholds
+pub struct PressureSampler {
+    system: System,
+    memory: MemoryConfig,
+    pause: PauseConfig,
+    last: Option<PressureSample>,
+}
+
+impl PressureSampler {
+    pub fn new(memory: &MemoryConfig, pause: &PauseConfig) -> Self {
+        Self {
+            system: System::new(),
+            memory: memory.clone(),
+            pause: pause.clone(),
+            last: None,
+        }
+    }
+
+    /// Refresh memory and CPU figures and classify them. The first CPU
+    /// reading is always zero; it needs a previous sample to compare with.
+    pub fn sample(&mut self) -> PressureSample {
+        self.system.refresh_memory();
+        self.system.refresh_cpu_usage();
+        let total = self.system.total_memory();
+        let memory_ratio = if total > 0 {
+            total.saturating_sub(self.system.available_memory()) as f32 / total as f32
+        } else {
+            0.0
+        };
+        let cpu_ratio = self.system.global_cpu_usage() / 100.0;
+        let sample = self.classify(memory_ratio, cpu_ratio);
+        self.last = Some(sample);
+        sample
+    }
+
+    /// Classify a reading without touching the host
+    pub fn classify(&self, memory_ratio: f32, cpu_ratio: f32) -> PressureSample {
+        let cpu_over = self
+            .pause
+            .cpu_threshold
+            .is_some_and(|threshold| cpu_ratio >= threshold);
+        let pressure = if cpu_over {
+            ResourcePressure::Critical
+        } else {
+            pressure_for_ratio(memory_ratio, &self.memory)
+        };
+        PressureSample {
+            memory_ratio,
+            cpu_ratio,
+            pressure,
+            pause: self.pause.enabled && (memory_ratio >= self.pause.memory_threshold || cpu_over),
+        }
+    }
+
+    /// The most recent sample, if one has been taken
+    pub fn last(&self) -> Option<PressureSample> {
+        self.last
+    }
+
+    pub fn config(&self) -> &PauseConfig {
+        &self.pause
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_pauses_at_memory_threshold() {
+        let sampler = PressureSampler::new(&MemoryConfig::default(), &PauseConfig::default());
+
+        let calm = sampler.classify(0.4, 1.0);
+        assert_eq!(calm.pressure, ResourcePressure::None);
+        assert!(!calm.pause);
+        assert!(calm.label().is_none());
+
+        let busy = sampler.classify(0.9, 0.2);
+        assert_eq!(busy.pressure, ResourcePressure::High);
+        assert!(!busy.pause);
+        assert_eq!(busy.label().as_deref(), Some("mem 90% cpu 20%"));
+
+        let full = sampler.classify(0.97, 0.2);
+        assert_eq!(full.pressure, ResourcePressure::Critical);
+        assert!(full.pause);
+
+        let disabled = PressureSampler::new(
+            &MemoryConfig::default(),
+            &PauseConfig {
+                enabled: false,
+                ..Default::default()
+            },
+        );
+        assert!(!disabled.classify(0.97, 0.2).pause);
+    }
+
+    #[test]
+    fn test_classify_cpu_only_counts_with_threshold() {
+        let sampler = PressureSampler::new(
+            &MemoryConfig::default(),
+            &PauseConfig {
+                cpu_threshold: Some(0.95),
+                ..Default::default()
+            },
+        );
+        let sample = sampler.classify(0.3, 0.99);
+        assert!(sample.pause);
+        assert_eq!(sample.pressure, ResourcePressure::Critical);
+        assert!(!sampler.classify(0.3, 0.5).pause);
+    }
+
+    #[test]
+    fn test_sample_reads_host_memory() {
+        let mut sampler = PressureSampler::new(&MemoryConfig::default(), &PauseConfig::default());
+        assert!(sampler.last().is_none());
+        let sample = sampler.sample();
+        assert!((0.0..=1.0).contains(&sample.memory_ratio));
+        assert!(sampler.last().is_some());
+    }
+}
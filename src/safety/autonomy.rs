@@ -875,6 +875,8 @@ pub fn default_tool_settings() -> Vec<ToolAutonomy> {
         // File modification tools
         ToolAutonomy::new("file_write", ToolCategory::FileWrite),
         ToolAutonomy::new("file_edit", ToolCategory::FileWrite),
+        ToolAutonomy::new("apply_patch", ToolCategory::FileWrite),
+        ToolAutonomy::new("symbol_rename", ToolCategory::FileWrite),
         // Dangerous tools
         ToolAutonomy::new("file_delete", ToolCategory::FileDelete).always_confirm(),
         ToolAutonomy::new("shell_exec", ToolCategory::Shell)
@@ -7,22 +7,100 @@
 //! - Command blacklisting for shell operations with obfuscation detection
 //! - Symlink attack prevention
 //! - Configurable per-tool safety rules
+//! - Self-edit protection: edits to selfware's own source tree need
+//!   `--allow-self-edit` and a confirmation, even in YOLO mode
+//! - Large-edit detection: a single write or edit that rewrites much of a
+//!   file is flagged so the agent can ask first
 //!
 //! This is the first line of defense; YOLO mode provides additional controls.
 
 use crate::api::types::ToolCall;
 use crate::config::SafetyConfig;
-#[cfg(test)]
 use crate::safety::path_validator::normalize_path as normalize_path_impl;
 use crate::safety::path_validator::PathValidator;
 use crate::safety::scanner::{SecurityScanner, SecuritySeverity};
 use anyhow::Result;
 use regex::Regex;
-#[cfg(test)]
-use std::path::Path;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::LazyLock;
 
+/// Tools that modify the files named by their `path` argument
+const EDIT_TOOLS: [&str; 4] = ["file_write", "file_edit", "file_delete", "file_fim_edit"];
+
+/// Tools that can change any file under the directory they run in, with the
+/// argument naming that directory (or its manifest); without it they run in
+/// the working directory. `shell_exec` counts only for commands that write.
+const TREE_WRITING_TOOLS: [(&str, Option<&str>); 4] = [
+    ("cargo_fmt", None),
+    ("cargo_add", Some("manifest_path")),
+    ("cargo_remove", Some("manifest_path")),
+    ("git_checkout", Some("repo_path")),
+];
+
+/// A single file write or edit whose churn crosses the configured limits
+#[derive(Debug, Clone, PartialEq)]
+pub struct LargeEdit {
+    pub path: PathBuf,
+    /// Lines removed or added, whichever is more
+    pub changed_lines: usize,
+    /// Lines in the file before the edit
+    pub total_lines: usize,
+    /// Share of the existing lines the edit removes or replaces
+    pub ratio: f64,
+}
+
+impl std::fmt::Display for LargeEdit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} changes {} line(s), {:.0}% of its {} lines",
+            self.path.display(),
+            self.changed_lines,
+            self.ratio * 100.0,
+            self.total_lines
+        )
+    }
+}
+
+/// Lines removed from `before` and added in `after`
+fn line_churn(before: &str, after: &str) -> (usize, usize) {
+    use similar::{ChangeTag, TextDiff};
+    let diff = TextDiff::from_lines(before, after);
+    diff.iter_all_changes()
+        .fold((0, 0), |(removed, added), change| match change.tag() {
+            ChangeTag::Delete => (removed + 1, added),
+            ChangeTag::Insert => (removed, added + 1),
+            ChangeTag::Equal => (removed, added),
+        })
+}
+
+/// The parts of selfware's source tree the agent's build depends on, relative
+/// to its root; build output, notes and scratch files elsewhere stay editable
+const SELF_SOURCE_PATHS: [&str; 4] = ["src", "Cargo.toml", "Cargo.lock", "build.rs"];
+
+/// Root of selfware's own source tree if `dir` lies inside it: the directory
+/// this binary was built from, or any Cargo package with this crate's name
+pub fn self_source_root(dir: &Path) -> Option<PathBuf> {
+    let dir = dir.canonicalize().ok()?;
+    let build_dir = Path::new(env!("CARGO_MANIFEST_DIR")).canonicalize().ok();
+    dir.ancestors()
+        .find(|ancestor| {
+            build_dir.as_deref() == Some(*ancestor)
+                || std::fs::read_to_string(ancestor.join("Cargo.toml"))
+                    .ok()
+                    .and_then(|text| text.parse::<toml::Table>().ok())
+                    .and_then(|manifest| {
+                        manifest
+                            .get("package")?
+                            .get("name")?
+                            .as_str()
+                            .map(|name| name == env!("CARGO_PKG_NAME"))
+                    })
+                    .unwrap_or(false)
+        })
+        .map(Path::to_path_buf)
+}
+
 /// Guards against dangerous tool calls by validating commands, paths, and content.
 ///
 /// Blocks destructive shell commands, path traversal attacks, secret leakage,
@@ -33,28 +111,229 @@ pub struct SafetyChecker {
     working_dir: PathBuf,
     /// Security scanner for detecting secrets in file content
     security_scanner: SecurityScanner,
+    /// Selfware's own source tree, when working inside it
+    self_source: Option<PathBuf>,
+    /// Whether `--allow-self-edit` was given
+    allow_self_edit: bool,
+    /// Paths edits are restricted to (`/focus`, `--focus`); empty means no restriction
+    focus: Vec<PathBuf>,
 }
 
 impl SafetyChecker {
     pub fn new(config: &SafetyConfig) -> Self {
+        let working_dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
         Self {
             config: config.clone(),
-            working_dir: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+            self_source: config
+                .guard_self_source
+                .then(|| self_source_root(&working_dir))
+                .flatten(),
+            working_dir,
             security_scanner: SecurityScanner::new(),
+            allow_self_edit: false,
+            focus: Vec::new(),
         }
     }
 
+    /// Permit edits to selfware's own source tree (each still needs confirmation)
+    pub fn with_self_edit_allowed(mut self, allowed: bool) -> Self {
+        self.allow_self_edit = allowed;
+        self
+    }
+
+    /// Selfware's own source tree, if the working directory is inside it
+    pub fn self_source(&self) -> Option<&Path> {
+        self.self_source.as_deref()
+    }
+
+    /// Restrict edits to these paths (files or directories, relative to the
+    /// working directory); an empty list lifts the restriction
+    pub fn set_focus(&mut self, paths: &[String]) {
+        self.focus = paths.iter().map(|p| self.resolve(Path::new(p))).collect();
+    }
+
+    /// The paths edits are currently restricted to
+    pub fn focus(&self) -> &[PathBuf] {
+        &self.focus
+    }
+
+    /// The first file a tool call would modify inside selfware's own source
+    /// tree, if any
+    pub fn self_edit_target(&self, tool_name: &str, arguments: &str) -> Option<PathBuf> {
+        let root = self.self_source.as_ref()?;
+        let sources: Vec<PathBuf> = SELF_SOURCE_PATHS.iter().map(|p| root.join(p)).collect();
+        self.edit_targets(tool_name, arguments)
+            .into_iter()
+            .chain(self.tree_write_targets(tool_name, arguments))
+            .find(|target| {
+                sources
+                    .iter()
+                    .any(|source| target.starts_with(source) || source.starts_with(target))
+            })
+    }
+
+    /// The directories (or manifests) a tool that can rewrite files under them
+    /// would run against, and the files a shell command writes, resolved
+    /// against the working directory
+    fn tree_write_targets(&self, tool_name: &str, arguments: &str) -> Vec<PathBuf> {
+        let args = serde_json::from_str::<serde_json::Value>(arguments).unwrap_or_default();
+        if tool_name == "shell_exec" {
+            let cwd = Path::new(args.get("cwd").and_then(|v| v.as_str()).unwrap_or("."));
+            return crate::tools::shell::checked_commands(&args)
+                .iter()
+                .flat_map(|command| crate::tools::shell::written_paths(command))
+                .map(|path| self.resolve(&cwd.join(path)))
+                .collect();
+        }
+        let Some((_, dir_arg)) = TREE_WRITING_TOOLS
+            .iter()
+            .find(|(name, _)| *name == tool_name)
+        else {
+            return Vec::new();
+        };
+        // `cargo_fmt` in check mode only reports
+        if tool_name == "cargo_fmt" && args.get("check").and_then(|v| v.as_bool()) == Some(true) {
+            return Vec::new();
+        }
+        let dir = dir_arg
+            .and_then(|arg| args.get(arg))
+            .and_then(|v| v.as_str())
+            .unwrap_or(".");
+        vec![self.resolve(Path::new(dir))]
+    }
+
+    /// The first file a tool call would modify outside the focus set, if a
+    /// focus is set
+    pub fn outside_focus(&self, tool_name: &str, arguments: &str) -> Option<PathBuf> {
+        if self.focus.is_empty() {
+            return None;
+        }
+        self.edit_targets(tool_name, arguments)
+            .into_iter()
+            .find(|target| !self.focus.iter().any(|focus| target.starts_with(focus)))
+    }
+
+    /// Files a tool call would modify, resolved against the working directory
+    fn edit_targets(&self, tool_name: &str, arguments: &str) -> Vec<PathBuf> {
+        let Ok(args) = serde_json::from_str::<serde_json::Value>(arguments) else {
+            return Vec::new();
+        };
+        let targets: Vec<PathBuf> = if EDIT_TOOLS.contains(&tool_name) {
+            args.get("path")
+                .and_then(|v| v.as_str())
+                .map(PathBuf::from)
+                .into_iter()
+                .collect()
+        } else if tool_name == "apply_patch" {
+            crate::tools::patch::patch_targets(&args)
+        } else if tool_name == "symbol_rename" {
+            crate::tools::refactor::rename_targets(&args)
+        } else {
+            return Vec::new();
+        };
+        targets.iter().map(|target| self.resolve(target)).collect()
+    }
+
+    /// Absolute, normalized form of `path`
+    fn resolve(&self, path: &Path) -> PathBuf {
+        let full = normalize_path_impl(&self.working_dir.join(path));
+        // Resolve symlinked parents so a link cannot hide the real location
+        full.parent()
+            .and_then(|p| p.canonicalize().ok())
+            .map(|p| p.join(full.file_name().unwrap_or_default()))
+            .unwrap_or(full)
+    }
+
+    /// Whether a `file_write` or `file_edit` call would change more of the
+    /// file than `large_edit_lines` / `large_edit_ratio` allow, measured
+    /// against its current content. New files are never flagged.
+    pub fn large_edit(&self, tool_name: &str, arguments: &str) -> Option<LargeEdit> {
+        if self.config.large_edit_lines == 0 {
+            return None;
+        }
+        let args: serde_json::Value = serde_json::from_str(arguments).ok()?;
+        let path = args.get("path")?.as_str()?;
+        let full = self.working_dir.join(path);
+        let current = std::fs::read_to_string(&full).ok()?;
+        let (removed, added) = match tool_name {
+            "file_write" => line_churn(&current, args.get("content")?.as_str()?),
+            "file_edit" => line_churn(
+                args.get("old_str")?.as_str()?,
+                args.get("new_str")?.as_str()?,
+            ),
+            _ => return None,
+        };
+        let total_lines = current.lines().count();
+        let changed_lines = removed.max(added);
+        let ratio = if total_lines == 0 {
+            0.0
+        } else {
+            removed.min(total_lines) as f64 / total_lines as f64
+        };
+        let too_many = changed_lines > self.config.large_edit_lines;
+        let too_much = changed_lines >= self.config.large_edit_min_lines
+            && ratio > self.config.large_edit_ratio;
+        (too_many || too_much).then(|| LargeEdit {
+            path: PathBuf::from(path),
+            changed_lines,
+            total_lines,
+            ratio,
+        })
+    }
+
     /// Create a safety checker with a specific working directory (test helper)
     #[cfg(test)]
     pub fn with_working_dir(config: &SafetyConfig, working_dir: PathBuf) -> Self {
         Self {
             config: config.clone(),
+            self_source: config
+                .guard_self_source
+                .then(|| self_source_root(&working_dir))
+                .flatten(),
             working_dir,
             security_scanner: SecurityScanner::new(),
+            allow_self_edit: false,
+            focus: Vec::new(),
         }
     }
 
     pub fn check_tool_call(&self, call: &ToolCall) -> Result<()> {
+        // Tool-specific checks first, so a dangerous command is reported as such
+        self.check_tool_arguments(call)?;
+        if let Some(target) = self.self_edit_target(&call.function.name, &call.function.arguments) {
+            if !self.allow_self_edit {
+                anyhow::bail!(
+                    "Edit to {} blocked: it is part of selfware's own source tree ({}), and \
+                     changing it could break the agent that is running. Restart with \
+                     --allow-self-edit to permit edits here; each one will still ask for \
+                     confirmation.",
+                    target.display(),
+                    self.self_source
+                        .as_deref()
+                        .unwrap_or(Path::new(""))
+                        .display()
+                );
+            }
+        }
+        if let Some(target) = self.outside_focus(&call.function.name, &call.function.arguments) {
+            anyhow::bail!(
+                "Edit to {} rejected: it is outside the focus set ({}). Keep changes to the \
+                 focused paths, or ask the user to widen the focus with /focus <paths...> \
+                 or lift it with /focus clear.",
+                target.display(),
+                self.focus
+                    .iter()
+                    .map(|p| p.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Checks specific to each tool's arguments
+    fn check_tool_arguments(&self, call: &ToolCall) -> Result<()> {
         match call.function.name.as_str() {
             "file_write" | "file_edit" | "file_read" | "file_delete" | "search"
             | "directory_tree" | "file_list" | "analyze" | "tech_debt_report" => {
@@ -74,15 +353,49 @@ impl SafetyChecker {
                     }
                 }
             }
+            "apply_patch" => {
+                let args: serde_json::Value = serde_json::from_str(&call.function.arguments)?;
+                for path in crate::tools::patch::patch_targets(&args) {
+                    self.check_path(&path.to_string_lossy())?;
+                }
+                let patch = args.get("patch").and_then(|v| v.as_str()).unwrap_or("");
+                let added = crate::tools::patch::added_lines(patch);
+                if !added.is_empty() {
+                    self.check_content_for_secrets(&added)?;
+                }
+            }
+            "symbol_rename" => {
+                let args: serde_json::Value = serde_json::from_str(&call.function.arguments)?;
+                if let Some(base) = args.get("base_dir").and_then(|v| v.as_str()) {
+                    self.check_path(base)?;
+                }
+                for path in crate::tools::refactor::rename_targets(&args) {
+                    self.check_path(&path.to_string_lossy())?;
+                }
+            }
             "shell_exec" => {
                 let args: serde_json::Value = serde_json::from_str(&call.function.arguments)?;
-                let cmd = args.get("command").and_then(|v| v.as_str()).unwrap_or("");
-                self.check_shell_command(cmd)?;
+                for cmd in crate::tools::shell::checked_commands(&args) {
+                    self.check_shell_command(&cmd)?;
+                }
 
                 if let Some(cwd) = args.get("cwd").and_then(|v| v.as_str()) {
                     self.check_path(cwd)?;
                 }
             }
+            "cargo_add" | "cargo_remove" => {
+                let args: serde_json::Value = serde_json::from_str(&call.function.arguments)?;
+                self.check_path(&crate::tools::cargo::manifest_arg(&args))?;
+                if let Some(path) = args.get("path").and_then(|v| v.as_str()) {
+                    self.check_path(path)?;
+                }
+            }
+            "git_branch_create" | "git_checkout" => {
+                let args: serde_json::Value = serde_json::from_str(&call.function.arguments)?;
+                if let Some(path) = args.get("repo_path").and_then(|v| v.as_str()) {
+                    self.check_path(path)?;
+                }
+            }
             "git_commit" | "git_checkpoint" => {
                 // Git operations are generally safe
             }
@@ -182,10 +495,11 @@ impl SafetyChecker {
                 }
             }
             // Read-only tools that don't need safety validation
-            "git_status" | "git_diff" | "grep_search" | "glob_find" | "symbol_search"
-            | "process_list" | "process_logs" | "port_check" | "pip_list" | "pip_freeze"
-            | "npm_scripts" | "container_list" | "container_logs" | "container_images"
-            | "knowledge_query" | "knowledge_stats" | "knowledge_export" => {
+            "git_status" | "git_diff" | "git_blame" | "grep_search" | "glob_find"
+            | "symbol_search" | "find_cycles" | "panic_risk" | "process_list" | "process_logs"
+            | "port_check" | "pip_list" | "pip_freeze" | "npm_scripts" | "container_list"
+            | "container_logs" | "container_images" | "knowledge_query" | "knowledge_stats"
+            | "knowledge_export" | "tool_output" => {
                 // These are read-only operations, safe to execute without additional checks
             }
             // Knowledge mutation tools - validate path-like arguments
@@ -224,6 +538,22 @@ impl SafetyChecker {
                     }
                 }
             }
+            // Contract tools read service directories and write/read a contract file
+            "contract_generate" | "contract_verify" => {
+                let args: serde_json::Value = serde_json::from_str(&call.function.arguments)?;
+                for key in &["consumer", "provider", "output", "contract"] {
+                    if let Some(p) = args.get(*key).and_then(|v| v.as_str()) {
+                        self.check_path(p)?;
+                    }
+                }
+            }
+            // Code review reads the diff and files of a repository
+            "code_review" => {
+                let args: serde_json::Value = serde_json::from_str(&call.function.arguments)?;
+                if let Some(p) = args.get("path").and_then(|v| v.as_str()) {
+                    self.check_path(p)?;
+                }
+            }
             // FIM edit tool — validate path against path policy
             "file_fim_edit" => {
                 let args: serde_json::Value = serde_json::from_str(&call.function.arguments)?;
@@ -1104,6 +1434,22 @@ mod tests {
         assert!(checker.check_tool_call(&call).is_err());
     }
 
+    #[test]
+    fn test_safety_apply_patch_checks_every_target() {
+        let config = SafetyConfig {
+            allowed_paths: vec!["./src/**".to_string()],
+            denied_paths: vec![],
+            ..Default::default()
+        };
+        let checker = SafetyChecker::new(&config);
+
+        let patch = "--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1 +1 @@\n-a\n+b\n\
+                     --- a/etc/hosts\n+++ b/etc/hosts\n@@ -1 +1 @@\n-a\n+b\n";
+        let args = serde_json::json!({"patch": patch, "base_dir": "/"});
+        let call = create_test_call("apply_patch", &args.to_string());
+        assert!(checker.check_tool_call(&call).is_err());
+    }
+
     #[test]
     fn test_safety_shell_exec_with_missing_command() {
         let config = SafetyConfig::default();
@@ -1175,6 +1521,204 @@ mod tests {
             .is_ok());
     }
 
+    #[test]
+    fn test_large_edit_measures_churn_against_current_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let original: String = (0..100).map(|i| format!("line {}\n", i)).collect();
+        std::fs::write(dir.path().join("lib.rs"), &original).unwrap();
+        let checker =
+            SafetyChecker::with_working_dir(&SafetyConfig::default(), dir.path().to_path_buf());
+        let write =
+            |content: &str| serde_json::json!({"path": "lib.rs", "content": content}).to_string();
+
+        // Touching a few lines is fine
+        let tweaked = original.replacen("line 5\n", "line five\n", 1);
+        assert_eq!(checker.large_edit("file_write", &write(&tweaked)), None);
+
+        // Replacing most of the file is flagged by ratio
+        let rewrite: String = (0..100).map(|i| format!("new {}\n", i)).collect();
+        let flagged = checker.large_edit("file_write", &write(&rewrite)).unwrap();
+        assert_eq!((flagged.changed_lines, flagged.total_lines), (100, 100));
+        assert!((flagged.ratio - 1.0).abs() < 1e-9);
+        assert!(flagged.to_string().contains("100% of its 100 lines"));
+
+        // file_edit counts only the replaced span
+        let edit = serde_json::json!({
+            "path": "lib.rs",
+            "old_str": "line 1\nline 2\n",
+            "new_str": "line one\n",
+        })
+        .to_string();
+        assert_eq!(checker.large_edit("file_edit", &edit), None);
+
+        // Appending many lines is flagged by count; new files never are
+        let appended = format!("{}{}", original, "extra\n".repeat(250));
+        assert!(checker
+            .large_edit("file_write", &write(&appended))
+            .is_some());
+        let new_file = serde_json::json!({"path": "new.rs", "content": rewrite}).to_string();
+        assert_eq!(checker.large_edit("file_write", &new_file), None);
+
+        let disabled = SafetyConfig {
+            large_edit_lines: 0,
+            ..Default::default()
+        };
+        let checker = SafetyChecker::with_working_dir(&disabled, dir.path().to_path_buf());
+        assert_eq!(checker.large_edit("file_write", &write(&rewrite)), None);
+    }
+
+    #[test]
+    fn test_safety_blocks_self_edit_without_flag() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"selfware\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        assert_eq!(self_source_root(&root.join("src")), Some(root.clone()));
+
+        let config = SafetyConfig::default();
+        let checker = SafetyChecker::with_working_dir(&config, root.clone());
+        let edit = create_test_call("file_write", r#"{"path": "src/main.rs", "content": "x"}"#);
+        let err = checker.check_tool_call(&edit).unwrap_err().to_string();
+        assert!(err.contains("selfware's own source tree"), "{}", err);
+        assert!(err.contains("--allow-self-edit"), "{}", err);
+        assert_eq!(
+            checker.self_edit_target("file_write", r#"{"path": "src/main.rs"}"#),
+            Some(root.join("src/main.rs"))
+        );
+
+        // Reads are unaffected
+        let read = create_test_call("file_read", r#"{"path": "src/main.rs"}"#);
+        assert!(checker.check_tool_call(&read).is_ok());
+
+        let allowed = SafetyChecker::with_working_dir(&config, root).with_self_edit_allowed(true);
+        assert!(allowed.check_tool_call(&edit).is_ok());
+    }
+
+    #[test]
+    fn test_self_edit_guard_covers_tree_writing_tools() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"selfware\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let checker = SafetyChecker::with_working_dir(&SafetyConfig::default(), root.clone());
+
+        for (tool, args) in [
+            ("shell_exec", r#"{"command": "sed -i s/a/b/ src/main.rs"}"#),
+            ("cargo_fmt", r#"{}"#),
+            ("cargo_add", r#"{"crate": "serde"}"#),
+            ("cargo_remove", r#"{"crate": "serde"}"#),
+            ("git_checkout", r#"{"ref": "main"}"#),
+        ] {
+            let err = checker
+                .check_tool_call(&create_test_call(tool, args))
+                .unwrap_err()
+                .to_string();
+            assert!(
+                err.contains("selfware's own source tree"),
+                "{}: {}",
+                tool,
+                err
+            );
+        }
+        assert_eq!(
+            checker.self_edit_target("cargo_fmt", r#"{"check": true}"#),
+            None
+        );
+
+        let elsewhere = tempfile::tempdir().unwrap();
+        let outside =
+            serde_json::json!({"command": "cargo fmt", "cwd": elsewhere.path()}).to_string();
+        assert_eq!(checker.self_edit_target("shell_exec", &outside), None);
+
+        // Commands that only read, or write outside the sources, run freely
+        for command in [
+            "ls -la",
+            "git status",
+            "cargo test",
+            "rm -rf target",
+            "echo x > notes.txt",
+        ] {
+            let call = serde_json::json!({ "command": command }).to_string();
+            assert_eq!(
+                checker.self_edit_target("shell_exec", &call),
+                None,
+                "{}",
+                command
+            );
+        }
+        let argv = r#"{"program": "sh", "args": ["-c", "echo x > src/lib.rs"]}"#;
+        assert_eq!(
+            checker.self_edit_target("shell_exec", argv),
+            Some(root.join("src/lib.rs"))
+        );
+    }
+
+    #[test]
+    fn test_self_edit_guard_can_be_disabled() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"selfware\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        let config = SafetyConfig {
+            guard_self_source: false,
+            ..Default::default()
+        };
+        let checker = SafetyChecker::with_working_dir(&config, dir.path().into());
+        let edit = create_test_call("file_write", r#"{"path": "src/main.rs", "content": "x"}"#);
+        assert!(checker.self_source().is_none());
+        assert!(checker.check_tool_call(&edit).is_ok());
+    }
+
+    #[test]
+    fn test_self_source_root_ignores_other_packages() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"someone-else\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        assert_eq!(self_source_root(dir.path()), None);
+
+        let checker = SafetyChecker::with_working_dir(&SafetyConfig::default(), dir.path().into());
+        let edit = create_test_call("file_write", r#"{"path": "src/main.rs", "content": "x"}"#);
+        assert!(checker.self_source().is_none());
+        assert!(checker.check_tool_call(&edit).is_ok());
+    }
+
+    #[test]
+    fn test_focus_restricts_edits_to_focused_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src/api")).unwrap();
+        let mut checker =
+            SafetyChecker::with_working_dir(&SafetyConfig::default(), dir.path().into());
+        checker.set_focus(&["src/api".to_string(), "README.md".to_string()]);
+
+        let inside = create_test_call("file_edit", r#"{"path": "src/api/mod.rs"}"#);
+        let listed = create_test_call("file_write", r#"{"path": "./README.md", "content": "x"}"#);
+        let outside = create_test_call("file_write", r#"{"path": "src/lib.rs", "content": "x"}"#);
+        let escape = create_test_call("file_edit", r#"{"path": "src/api/../lib.rs"}"#);
+        let read = create_test_call("file_read", r#"{"path": "src/lib.rs"}"#);
+        assert!(checker.check_tool_call(&inside).is_ok());
+        assert!(checker.check_tool_call(&listed).is_ok());
+        assert!(checker.check_tool_call(&read).is_ok());
+        let err = checker.check_tool_call(&outside).unwrap_err().to_string();
+        assert!(err.contains("outside the focus set"), "{err}");
+        assert!(err.contains("/focus clear"), "{err}");
+        assert!(checker.check_tool_call(&escape).is_err());
+
+        checker.set_focus(&[]);
+        assert!(checker.check_tool_call(&outside).is_ok());
+    }
+
     #[test]
     fn test_safety_blocks_curl_piped_to_sh() {
         let config = SafetyConfig::default();
@@ -1448,6 +1992,28 @@ mod tests {
         assert!(checker.check_tool_call(&call).is_ok());
     }
 
+    #[test]
+    fn test_safety_checks_argv_form_shell_exec() {
+        let config = SafetyConfig::default();
+        let checker = SafetyChecker::new(&config);
+
+        let call = create_test_call(
+            "shell_exec",
+            r#"{"program": "cargo", "args": ["test", "-p", "foo"]}"#,
+        );
+        assert!(checker.check_tool_call(&call).is_ok());
+
+        let call = create_test_call("shell_exec", r#"{"program": "rm", "args": ["-rf", "/"]}"#);
+        assert!(checker.check_tool_call(&call).is_err());
+
+        // Scripts handed to a shell are checked as written, not re-quoted
+        let call = create_test_call(
+            "shell_exec",
+            r#"{"program": "env", "args": ["X=1", "bash", "-ec", "rm -rf /"]}"#,
+        );
+        assert!(checker.check_tool_call(&call).is_err());
+    }
+
     #[test]
     fn test_safety_allows_git_commands() {
         let config = SafetyConfig::default();
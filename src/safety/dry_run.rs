@@ -98,6 +98,41 @@ pub fn preview_tool_call(
                 "Modifies existing file".to_string(),
             )
         }
+        "apply_patch" => {
+            let targets: Vec<String> = crate::tools::patch::patch_targets(arguments)
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect();
+            (
+                format!("Apply patch to {} file(s)", targets.len()),
+                targets,
+                "Modifies existing files".to_string(),
+            )
+        }
+        "symbol_rename" => {
+            let old_path = arguments
+                .get("old_path")
+                .and_then(|v| v.as_str())
+                .unwrap_or("?");
+            let new_name = arguments
+                .get("new_name")
+                .and_then(|v| v.as_str())
+                .unwrap_or("?");
+            let targets: Vec<String> = crate::tools::refactor::rename_targets(arguments)
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect();
+            (
+                format!(
+                    "Rename {} to {} in {} file(s)",
+                    old_path,
+                    new_name,
+                    targets.len()
+                ),
+                targets,
+                "Modifies existing files".to_string(),
+            )
+        }
         "directory_tree" => {
             let path = arguments
                 .get("path")
@@ -110,10 +145,7 @@ pub fn preview_tool_call(
             )
         }
         "shell_exec" => {
-            let cmd = arguments
-                .get("command")
-                .and_then(|v| v.as_str())
-                .unwrap_or("?");
+            let cmd = crate::tools::shell::command_line(arguments).unwrap_or_else(|| "?".into());
             let risk = if cmd.contains("rm") || cmd.contains("delete") {
                 "HIGH - potentially destructive command"
             } else if cmd.contains(">") || cmd.contains("mv") || cmd.contains("cp") {
@@ -122,7 +154,7 @@ pub fn preview_tool_call(
                 "Variable - depends on command"
             };
             (
-                format!("Execute: {}", truncate_str(cmd, 60)),
+                format!("Execute: {}", truncate_str(&cmd, 60)),
                 vec!["(depends on command)".to_string()],
                 risk.to_string(),
             )
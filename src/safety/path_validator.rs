@@ -1,7 +1,7 @@
 //! Shared path validation logic for safety checks.
 
 use crate::config::SafetyConfig;
-use anyhow::Result;
+use anyhow::{Context, Result};
 use std::path::{Path, PathBuf};
 
 /// ELOOP errno value (symlink encountered with O_NOFOLLOW).
@@ -292,60 +292,304 @@ impl PathValidator {
     }
 
     /// Check for symlink-based attacks.
+    ///
+    /// The link chain is resolved by the kernel in one `canonicalize` call
+    /// rather than hop by hop, so a link swapped mid-check cannot slip a
+    /// different target past it.
     pub fn check_symlink_safety(&self, path: &Path) -> Result<PathBuf> {
-        let mut current = path.to_path_buf();
-        let mut visited = std::collections::HashSet::new();
-        let max_depth = 40; // Linux default MAXSYMLINKS
+        let target = match path.canonicalize() {
+            Ok(target) => target,
+            Err(e) if e.raw_os_error() == Some(ELOOP) => {
+                anyhow::bail!("Symlink loop detected: {}", path.display());
+            }
+            // Dangling link: judge it by where it points
+            Err(_) => match std::fs::read_link(path) {
+                Ok(target) if target.is_absolute() => normalize_path(&target),
+                Ok(target) => normalize_path(&path.parent().unwrap_or(Path::new("/")).join(target)),
+                Err(_) => return Ok(path.to_path_buf()),
+            },
+        };
+
+        let target_str = target.to_string_lossy();
+        let dangerous_targets = [
+            "/etc/passwd",
+            "/etc/shadow",
+            "/etc/sudoers",
+            "/root/",
+            "/proc/",
+            "/sys/",
+        ];
 
-        for _ in 0..max_depth {
-            if !current.is_symlink() {
-                break;
+        for dangerous in &dangerous_targets {
+            if target_str.starts_with(dangerous) {
+                anyhow::bail!(
+                    "Symlink points to protected system path: {} -> {}",
+                    path.display(),
+                    target_str
+                );
             }
+        }
 
-            let current_str = current.to_string_lossy().to_string();
-            if visited.contains(&current_str) {
-                anyhow::bail!("Symlink loop detected: {}", path.display());
+        Ok(target)
+    }
+}
+
+/// A directory opened once and used as the root for file operations.
+///
+/// Operations go through a cap-std [`Dir`](cap_std::fs::Dir) handle, which
+/// resolves paths below it one component at a time against open directory
+/// handles, never as path strings. A symlink swapped in after validation
+/// therefore cannot redirect an operation outside the root, and symlinks
+/// inside the root are followed only while their targets stay inside it.
+pub struct CapabilityDir {
+    root: PathBuf,
+    dir: cap_std::fs::Dir,
+}
+
+/// A file opened through a [`CapabilityDir`]
+pub struct CapFile {
+    file: std::fs::File,
+    path: PathBuf,
+}
+
+impl CapFile {
+    /// Path the file was opened at, below the root
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn metadata(&self) -> Result<std::fs::Metadata> {
+        Ok(self.file.metadata()?)
+    }
+
+    pub fn read_to_end(&mut self) -> Result<Vec<u8>> {
+        use std::io::Read;
+        let mut bytes = Vec::new();
+        self.file.read_to_end(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    pub fn read_to_string(&mut self) -> Result<String> {
+        use std::io::Read;
+        let mut content = String::new();
+        self.file.read_to_string(&mut content)?;
+        Ok(content)
+    }
+}
+
+impl CapabilityDir {
+    /// Open `root` as the directory all later operations are confined to.
+    /// `root` must itself be a directory, not a symlink to one.
+    pub fn open(root: impl AsRef<Path>) -> Result<Self> {
+        let root = root.as_ref();
+        let validated = std::fs::symlink_metadata(root)
+            .with_context(|| format!("Failed to open directory {}", root.display()))?;
+        if !validated.is_dir() {
+            anyhow::bail!("Not a directory: {}", root.display());
+        }
+        let dir = cap_std::fs::Dir::open_ambient_dir(root, cap_std::ambient_authority())
+            .with_context(|| format!("Failed to open directory {}", root.display()))?;
+        // The handle must be the directory that was checked, not one swapped
+        // in (through a parent) between the check and the open
+        #[cfg(unix)]
+        {
+            use cap_std::fs::MetadataExt as _;
+            use std::os::unix::fs::MetadataExt as _;
+            let opened = dir.dir_metadata()?;
+            if (opened.dev(), opened.ino()) != (validated.dev(), validated.ino()) {
+                anyhow::bail!("{} changed while it was being opened", root.display());
             }
-            visited.insert(current_str);
+        }
+        Ok(Self {
+            root: root.to_path_buf(),
+            dir,
+        })
+    }
 
-            let target = std::fs::read_link(&current)?;
-            let resolved_target = if target.is_absolute() {
-                target
-            } else {
-                current.parent().unwrap_or(Path::new("/")).join(&target)
-            };
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
 
-            let target_str = resolved_target.to_string_lossy();
-            let dangerous_targets = [
-                "/etc/passwd",
-                "/etc/shadow",
-                "/etc/sudoers",
-                "/root/",
-                "/proc/",
-                "/sys/",
-            ];
-
-            for dangerous in &dangerous_targets {
-                if target_str.starts_with(dangerous) {
-                    anyhow::bail!(
-                        "Symlink points to protected system path: {} -> {}",
-                        path.display(),
-                        target_str
-                    );
+    /// `rel` checked to be relative and to name an entry below the root
+    fn confined<'a>(&self, rel: &'a Path) -> Result<&'a Path> {
+        use std::path::Component;
+
+        let mut depth = 0usize;
+        for component in rel.components() {
+            match component {
+                Component::Normal(_) => depth += 1,
+                Component::ParentDir => {
+                    depth = depth.checked_sub(1).with_context(|| {
+                        format!("{} escapes {}", rel.display(), self.root.display())
+                    })?;
+                }
+                Component::CurDir => {}
+                Component::RootDir | Component::Prefix(_) => {
+                    anyhow::bail!("Path is not relative: {}", rel.display())
                 }
             }
+        }
+        if depth == 0 || rel.file_name().is_none() {
+            anyhow::bail!("Path names no file: {}", rel.display());
+        }
+        Ok(rel)
+    }
 
-            current = resolved_target;
+    /// Open the existing file at `rel` for reading
+    pub fn open_within(&self, rel: impl AsRef<Path>) -> Result<CapFile> {
+        let rel = self.confined(rel.as_ref())?;
+        let path = self.root.join(rel);
+        let file = self
+            .dir
+            .open(rel)
+            .with_context(|| format!("Failed to open {}", path.display()))?
+            .into_std();
+        if file.metadata()?.is_dir() {
+            anyhow::bail!("{} is a directory", path.display());
         }
+        Ok(CapFile { file, path })
+    }
 
-        if visited.len() >= max_depth {
-            anyhow::bail!(
-                "Symlink chain too deep (possible attack): {}",
-                path.display()
-            );
+    /// Whether anything (file, directory or symlink) exists at `rel`. Only a
+    /// missing entry is `false`; any other failure to look is an error.
+    pub fn exists_within(&self, rel: impl AsRef<Path>) -> Result<bool> {
+        let rel = self.confined(rel.as_ref())?;
+        match self.dir.symlink_metadata(rel) {
+            Ok(_) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(anyhow::Error::new(e)
+                .context(format!("Failed to check {}", self.root.join(rel).display()))),
         }
+    }
+
+    /// Replace the file at `rel` with `content`, creating missing parent
+    /// directories. The content is written to a temporary file beside it
+    /// and renamed into place, so readers never see a partial file.
+    pub fn write_within(&self, rel: impl AsRef<Path>, content: &[u8]) -> Result<PathBuf> {
+        use std::io::Write;
+
+        let rel = self.confined(rel.as_ref())?;
+        let path = self.root.join(rel);
+        let written = (|| -> std::io::Result<()> {
+            let parent = rel.parent().unwrap_or(Path::new(""));
+            if !parent.as_os_str().is_empty() {
+                self.dir.create_dir_all(parent)?;
+            }
+            let tmp = parent.join(format!(
+                ".{}.{}.{}.tmp",
+                rel.file_name().unwrap_or_default().to_string_lossy(),
+                std::process::id(),
+                uuid::Uuid::new_v4().simple()
+            ));
+            let mut file = self.dir.open_with(
+                &tmp,
+                cap_std::fs::OpenOptions::new().write(true).create_new(true),
+            )?;
+            let result = file
+                .write_all(content)
+                .and_then(|_| file.sync_all())
+                .and_then(|_| self.dir.rename(&tmp, &self.dir, rel));
+            if result.is_err() {
+                let _ = self.dir.remove_file(&tmp);
+            }
+            result
+        })();
+        written.with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(path)
+    }
 
-        Ok(current)
+    /// Remove the file (or symlink) at `rel`; a directory is refused.
+    pub fn remove_within(&self, rel: impl AsRef<Path>) -> Result<PathBuf> {
+        let rel = self.confined(rel.as_ref())?;
+        let path = self.root.join(rel);
+        if self.dir.symlink_metadata(rel).is_ok_and(|m| m.is_dir()) {
+            anyhow::bail!("{} is a directory, not a file", path.display());
+        }
+        self.dir
+            .remove_file(rel)
+            .with_context(|| format!("Failed to remove {}", path.display()))?;
+        Ok(path)
+    }
+}
+
+/// The directories a tool may reach: one [`CapabilityDir`] per
+/// `allowed_paths` entry, opened once against a working directory. A path is
+/// resolved under the deepest root that contains it, and a path outside
+/// every root is refused.
+pub struct CapabilityRoots {
+    /// Directory relative `allowed_paths` and tool paths are taken from
+    working_dir: PathBuf,
+    allowed_paths: Vec<String>,
+    roots: Vec<(PathBuf, CapabilityDir)>,
+}
+
+impl CapabilityRoots {
+    /// Open the fixed directory each `allowed_paths` glob starts with.
+    /// Roots that do not exist yet are skipped.
+    pub fn open(config: &SafetyConfig, working_dir: &Path) -> Self {
+        let mut roots: Vec<(PathBuf, CapabilityDir)> = Vec::new();
+        for pattern in &config.allowed_paths {
+            let prefix = glob_root(pattern, working_dir);
+            if roots.iter().any(|(root, _)| *root == prefix) {
+                continue;
+            }
+            // The root itself is trusted config, so resolve any link in it
+            // once here; everything below it is walked without following
+            let opened = prefix
+                .canonicalize()
+                .map_err(anyhow::Error::from)
+                .and_then(CapabilityDir::open);
+            match opened {
+                Ok(dir) => roots.push((prefix, dir)),
+                Err(e) => tracing::debug!("Skipping allowed root {}: {:#}", prefix.display(), e),
+            }
+        }
+        Self {
+            working_dir: working_dir.to_path_buf(),
+            allowed_paths: config.allowed_paths.clone(),
+            roots,
+        }
+    }
+
+    /// Whether these roots were opened for `config` in `working_dir`
+    pub fn opened_for(&self, config: &SafetyConfig, working_dir: &Path) -> bool {
+        self.working_dir == working_dir && self.allowed_paths == config.allowed_paths
+    }
+
+    /// The root `path` falls under and the path relative to it. A relative
+    /// `path` is taken from the working directory the roots were opened in.
+    pub fn resolve(&self, path: impl AsRef<Path>) -> Result<(&CapabilityDir, PathBuf)> {
+        let path = path.as_ref();
+        let full = normalize_path(&self.working_dir.join(path));
+        self.roots
+            .iter()
+            .filter(|(root, _)| full.starts_with(root))
+            .max_by_key(|(root, _)| root.components().count())
+            .map(|(root, dir)| {
+                let rel = full.strip_prefix(root).unwrap_or(&full).to_path_buf();
+                (dir, rel)
+            })
+            .with_context(|| format!("{} is outside every allowed path", path.display()))
+    }
+}
+
+/// The leading directory of an `allowed_paths` glob, before its first
+/// wildcard. Relative patterns are taken from the working directory.
+fn glob_root(pattern: &str, working_dir: &Path) -> PathBuf {
+    let pattern = pattern.replace('\\', "/");
+    let fixed: PathBuf = Path::new(&pattern)
+        .components()
+        .take_while(|c| {
+            !c.as_os_str()
+                .to_string_lossy()
+                .contains(['*', '?', '[', '{'])
+        })
+        .collect();
+    if fixed.as_os_str().is_empty() {
+        // A leading wildcard matches anywhere
+        PathBuf::from("/")
+    } else {
+        normalize_path(&working_dir.join(fixed))
     }
 }
 
@@ -392,6 +636,10 @@ mod tests {
             protected_branches: vec![],
             require_confirmation: vec![],
             strict_permissions: false,
+            large_edit_lines: 200,
+            large_edit_ratio: 0.5,
+            large_edit_min_lines: 20,
+            guard_self_source: true,
         }
     }
 
@@ -581,4 +829,186 @@ mod tests {
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("null bytes"));
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_capability_dir_confines_symlinks_to_root() {
+        use std::os::unix::fs::symlink;
+
+        let root = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        std::fs::write(outside.path().join("secret.txt"), "outside").unwrap();
+        std::fs::create_dir(root.path().join("docs")).unwrap();
+        std::fs::write(root.path().join("docs/guide.md"), "inside").unwrap();
+        symlink("docs/guide.md", root.path().join("README.md")).unwrap();
+        symlink(outside.path(), root.path().join("abs")).unwrap();
+        symlink("../..", root.path().join("docs/up")).unwrap();
+
+        let dir = CapabilityDir::open(root.path()).unwrap();
+        let mut readme = dir.open_within("README.md").unwrap();
+        assert_eq!(readme.read_to_string().unwrap(), "inside");
+        assert_eq!(readme.path(), dir.root().join("README.md"));
+
+        assert!(dir.open_within("abs/secret.txt").is_err());
+        assert!(dir.open_within("docs/up/secret.txt").is_err());
+        assert!(dir.open_within("../secret.txt").is_err());
+        assert!(dir.open_within("/etc/hostname").is_err());
+        assert!(dir.write_within("abs/new.txt", b"x").is_err());
+        assert!(!outside.path().join("new.txt").exists());
+
+        let written = dir
+            .write_within("src/nested/new.rs", b"fn main() {}")
+            .unwrap();
+        assert_eq!(std::fs::read(written).unwrap(), b"fn main() {}");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_capability_dir_exists_within() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::create_dir(root.path().join("docs")).unwrap();
+        std::fs::write(root.path().join("docs/guide.md"), "inside").unwrap();
+        std::fs::write(root.path().join("plain"), "").unwrap();
+        std::os::unix::fs::symlink("missing", root.path().join("dangling")).unwrap();
+
+        let dir = CapabilityDir::open(root.path()).unwrap();
+        assert!(dir.exists_within("docs/guide.md").unwrap());
+        assert!(dir.exists_within("docs").unwrap());
+        assert!(dir.exists_within("dangling").unwrap());
+        assert!(!dir.exists_within("docs/new.md").unwrap());
+        assert!(!dir.exists_within("missing/new.md").unwrap());
+        // A file used as a directory is not "missing"
+        assert!(dir.exists_within("plain/new.md").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_capability_dir_remove_within() {
+        let root = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        std::fs::create_dir(root.path().join("docs")).unwrap();
+        std::fs::write(root.path().join("docs/old.md"), "bye").unwrap();
+        std::fs::write(outside.path().join("keep"), "kept").unwrap();
+        std::os::unix::fs::symlink(outside.path(), root.path().join("abs")).unwrap();
+
+        let dir = CapabilityDir::open(root.path()).unwrap();
+        dir.remove_within("docs/old.md").unwrap();
+        assert!(!root.path().join("docs/old.md").exists());
+        assert!(dir.remove_within("docs").is_err());
+        assert!(dir.remove_within("abs/keep").is_err());
+        assert!(outside.path().join("keep").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_capability_dir_refuses_symlinked_root() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::create_dir(root.path().join("real")).unwrap();
+        std::os::unix::fs::symlink("real", root.path().join("link")).unwrap();
+
+        assert!(CapabilityDir::open(root.path().join("real")).is_ok());
+        assert!(CapabilityDir::open(root.path().join("link")).is_err());
+        std::fs::write(root.path().join("file"), "").unwrap();
+        assert!(CapabilityDir::open(root.path().join("file")).is_err());
+    }
+
+    #[test]
+    fn test_capability_roots_follow_allowed_paths() {
+        let work = tempfile::tempdir().unwrap();
+        let extra = tempfile::tempdir().unwrap();
+        std::fs::create_dir(work.path().join("src")).unwrap();
+        let extra_glob = format!("{}/**/*.md", extra.path().display());
+        let config = make_config(vec!["./**", "./src/**", &extra_glob], vec![]);
+        let roots = CapabilityRoots::open(&config, work.path());
+
+        let (dir, rel) = roots.resolve(&work.path().join("src/lib.rs")).unwrap();
+        assert_eq!(dir.root(), work.path().join("src").canonicalize().unwrap());
+        assert_eq!(rel, Path::new("lib.rs"));
+        let (dir, rel) = roots.resolve(&extra.path().join("docs/a.md")).unwrap();
+        assert_eq!(dir.root(), extra.path().canonicalize().unwrap());
+        assert_eq!(rel, Path::new("docs/a.md"));
+
+        // No fallback to the nearest existing parent outside the roots
+        let outside = tempfile::tempdir().unwrap();
+        let err = roots.resolve(&outside.path().join("f")).err().unwrap();
+        assert!(
+            err.to_string().contains("outside every allowed path"),
+            "{}",
+            err
+        );
+        assert!(roots.resolve(&work.path().join("../escape")).is_err());
+
+        // Relative paths are taken from the directory the roots were opened in
+        let (dir, rel) = roots.resolve("src/main.rs").unwrap();
+        assert_eq!(dir.root(), work.path().join("src").canonicalize().unwrap());
+        assert_eq!(rel, Path::new("main.rs"));
+        assert!(roots.opened_for(&config, work.path()));
+        assert!(!roots.opened_for(&config, extra.path()));
+    }
+
+    #[test]
+    fn test_glob_root() {
+        let work = Path::new("/work");
+        assert_eq!(glob_root("./**", work), PathBuf::from("/work"));
+        assert_eq!(glob_root("./src/*.rs", work), PathBuf::from("/work/src"));
+        assert_eq!(glob_root("/home/me/**", work), PathBuf::from("/home/me"));
+        assert_eq!(glob_root("/opt/data", work), PathBuf::from("/opt/data"));
+        assert_eq!(glob_root("**/*.rs", work), PathBuf::from("/"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_capability_dir_survives_symlink_swap() {
+        use std::os::unix::fs::{symlink, MetadataExt};
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+
+        let root = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        std::fs::write(outside.path().join("f"), "outside").unwrap();
+        std::fs::create_dir(root.path().join("real")).unwrap();
+        std::fs::write(root.path().join("real/f"), "inside").unwrap();
+        symlink(outside.path(), root.path().join("evil")).unwrap();
+
+        // An open handle keeps pointing at the inode it validated
+        let dir = CapabilityDir::open(root.path()).unwrap();
+        std::fs::rename(root.path().join("real"), root.path().join("sub")).unwrap();
+        let mut handle = dir.open_within("sub/f").unwrap();
+        let inode = handle.metadata().unwrap().ino();
+        std::fs::rename(root.path().join("sub"), root.path().join("real")).unwrap();
+        std::fs::rename(root.path().join("evil"), root.path().join("sub")).unwrap();
+        assert_eq!(handle.read_to_string().unwrap(), "inside");
+        assert_eq!(
+            std::fs::metadata(root.path().join("real/f")).unwrap().ino(),
+            inode
+        );
+        std::fs::rename(root.path().join("sub"), root.path().join("evil")).unwrap();
+
+        // Race the swap against fresh opens and writes
+        let stop = Arc::new(AtomicBool::new(false));
+        let swapper = {
+            let stop = stop.clone();
+            let root = root.path().to_path_buf();
+            std::thread::spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    for name in ["real", "evil"] {
+                        let _ = std::fs::rename(root.join(name), root.join("sub"));
+                        let _ = std::fs::rename(root.join("sub"), root.join(name));
+                    }
+                }
+            })
+        };
+        for _ in 0..500 {
+            if let Ok(mut file) = dir.open_within("sub/f") {
+                assert_eq!(file.read_to_string().unwrap(), "inside");
+            }
+            let _ = dir.write_within("sub/f", b"inside");
+        }
+        stop.store(true, Ordering::Relaxed);
+        swapper.join().unwrap();
+        assert_eq!(
+            std::fs::read_to_string(outside.path().join("f")).unwrap(),
+            "outside"
+        );
+    }
 }
@@ -218,6 +218,8 @@ pub enum AuditResult {
     Success,
     Failed(String),
     Blocked(String),
+    /// Allowed to run, but matched a rule that would otherwise have asked
+    Flagged(String),
 }
 
 /// YOLO mode manager
@@ -314,12 +316,13 @@ impl YoloManager {
 
         // Check destructive shell commands
         if tool_name == "shell_exec" {
-            if let Some(cmd) = args.get("command").and_then(|c| c.as_str()) {
-                if is_destructive_command(cmd) && !self.config.allow_destructive_shell {
-                    return YoloDecision::RequireConfirmation(
-                        "Destructive shell command requires confirmation".to_string(),
-                    );
-                }
+            let destructive = crate::tools::shell::checked_commands(args)
+                .iter()
+                .any(|cmd| is_destructive_command(cmd));
+            if destructive && !self.config.allow_destructive_shell {
+                return YoloDecision::RequireConfirmation(
+                    "Destructive shell command requires confirmation".to_string(),
+                );
             }
         }
 
@@ -440,6 +443,7 @@ impl YoloManager {
                 AuditResult::Success => success += 1,
                 AuditResult::Failed(_) => failed += 1,
                 AuditResult::Blocked(_) => blocked += 1,
+                AuditResult::Flagged(_) => {}
             }
         }
 
@@ -607,6 +611,27 @@ fn append_to_audit_file(path: &PathBuf, entry: &AuditEntry) -> std::io::Result<(
     file.flush()
 }
 
+/// Append a note about an auto-approved call that a confirmation rule
+/// flagged (such as a large edit) to the YOLO audit log at `path`
+pub fn record_flagged(
+    path: &PathBuf,
+    tool_name: &str,
+    args: &serde_json::Value,
+    reason: &str,
+) -> std::io::Result<()> {
+    let entry = AuditEntry {
+        timestamp: Utc::now(),
+        operation_id: 0,
+        tool_name: tool_name.to_string(),
+        arguments_summary: summarize_args(args),
+        auto_approved: true,
+        result: AuditResult::Flagged(reason.to_string()),
+        duration_ms: 0,
+    };
+    let _guard = AUDIT_FILE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    append_to_audit_file(path, &entry)
+}
+
 /// Global mutex protecting audit file writes from concurrent threads.
 static AUDIT_FILE_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
 
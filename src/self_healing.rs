@@ -58,6 +58,13 @@ pub struct SelfHealingConfig {
     pub max_checkpoints: usize,
     /// Enable proactive health checks
     pub proactive_monitoring: bool,
+    /// Identical consecutive failures before retrying once with reduced context
+    #[serde(default = "default_context_reduction_threshold")]
+    pub context_reduction_threshold: u32,
+}
+
+fn default_context_reduction_threshold() -> u32 {
+    2
 }
 
 impl Default for SelfHealingConfig {
@@ -71,6 +78,7 @@ impl Default for SelfHealingConfig {
             checkpoint_interval_secs: 60,
             max_checkpoints: 10,
             proactive_monitoring: true,
+            context_reduction_threshold: default_context_reduction_threshold(),
         }
     }
 }
@@ -437,6 +445,23 @@ impl RecoveryStrategy {
         }
     }
 
+    /// Drop context that is unrelated to `error` and retry once with a
+    /// prompt that names it. Used when the same failure keeps repeating.
+    pub fn reduce_context(error: &str) -> Self {
+        let mut params = HashMap::new();
+        params.insert("error".to_string(), error.to_string());
+        Self {
+            name: "reduce_context".to_string(),
+            description: "Reduce context and retry with a focused prompt".to_string(),
+            actions: vec![RecoveryAction::Custom {
+                name: "reduce_context".to_string(),
+                params,
+            }],
+            success_probability: 0.6,
+            estimated_duration_ms: 500,
+        }
+    }
+
     pub fn from_name(name: &str) -> Self {
         match name {
             "retry" => Self::retry(),
@@ -1118,6 +1143,13 @@ impl ErrorClass {
         }
     }
 
+    /// Whether the failure comes from the task itself (a bad tool call, a
+    /// failing build) and so could be fixed by the model, rather than from
+    /// the network, the provider or the host.
+    pub fn is_task_error(self) -> bool {
+        matches!(self, ErrorClass::ParseError | ErrorClass::Unknown)
+    }
+
     /// Return the default recovery strategy for this error class.
     pub fn default_strategy(self) -> RecoveryStrategy {
         match self {
@@ -1246,6 +1278,17 @@ pub struct SelfHealingEngine {
     predictor: HealthPredictor,
     /// Recovery executor
     executor: RecoveryExecutor,
+    /// Consecutive identical failures per pattern
+    failure_streaks: RwLock<HashMap<String, FailureStreak>>,
+}
+
+/// A run of identical failures for one error pattern.
+#[derive(Debug, Clone, Default)]
+struct FailureStreak {
+    message: String,
+    count: u32,
+    /// Context reduction was already tried for this streak
+    context_reduced: bool,
 }
 
 impl SelfHealingEngine {
@@ -1255,10 +1298,47 @@ impl SelfHealingEngine {
             state: StateManager::new(config.clone()),
             predictor: HealthPredictor::new(),
             executor: RecoveryExecutor::new(config.clone()),
+            failure_streaks: RwLock::new(HashMap::new()),
             config,
         }
     }
 
+    /// Count an occurrence towards its pattern's streak and report whether
+    /// the streak just reached the context-reduction threshold. Reduction is
+    /// offered once per streak so a still-failing step cannot loop on it.
+    fn should_reduce_context(&self, pattern_key: &str, message: &str) -> bool {
+        let threshold = self.config.context_reduction_threshold;
+        if threshold == 0 {
+            return false;
+        }
+
+        let mut streaks = self.failure_streaks.write();
+        let streak = streaks.entry(pattern_key.to_string()).or_default();
+        if streak.message != message {
+            *streak = FailureStreak {
+                message: message.to_string(),
+                ..Default::default()
+            };
+        }
+        streak.count += 1;
+
+        if streak.count >= threshold && !streak.context_reduced {
+            streak.context_reduced = true;
+            return true;
+        }
+        false
+    }
+
+    /// Whether context reduction has already been applied to the current
+    /// failure streak for this pattern.
+    pub fn context_reduced(&self, error_type: &str, context: &str) -> bool {
+        let pattern_key = format!("{}:{}", error_type, context);
+        self.failure_streaks
+            .read()
+            .get(&pattern_key)
+            .is_some_and(|s| s.context_reduced)
+    }
+
     /// Handle an error with classification, learned strategy selection,
     /// and automatic escalation if the primary strategy fails.
     pub fn handle_error(&self, error: ErrorOccurrence) -> Option<RecoveryExecution> {
@@ -1271,9 +1351,21 @@ impl SelfHealingEngine {
 
         let pattern_key = format!("{}:{}", error.error_type, error.context);
 
-        // Classify the error
+        // Classify the error. The agent reports every failure under one
+        // error type, so the class comes from the message.
         let error_class = ErrorClass::classify(&error.error_type, &error.message);
 
+        // The same task failure again and again: retrying identically only
+        // burns tokens, so shed unrelated context and retry once with a
+        // focused prompt. Transient failures just need the retry.
+        if error_class.is_task_error() && self.should_reduce_context(&pattern_key, &error.message) {
+            let strategy = RecoveryStrategy::reduce_context(&error.message);
+            return Some(
+                self.executor
+                    .execute_for_pattern(&strategy, &self.state, &pattern_key),
+            );
+        }
+
         // Pick recovery strategy: learned recommendation > class-based default
         let strategy = self
             .learner
@@ -1364,6 +1456,7 @@ impl SelfHealingEngine {
     pub fn reset_retry(&self, error_type: &str, context: &str) {
         let pattern_key = format!("{}:{}", error_type, context);
         self.executor.reset_retry_state(&pattern_key);
+        self.failure_streaks.write().remove(&pattern_key);
     }
 
     /// Get components
@@ -2272,6 +2365,87 @@ mod tests {
         assert_eq!(engine.executor().retry_attempt_count("network:api"), 0);
     }
 
+    #[test]
+    fn test_engine_reduces_context_after_identical_failures() {
+        let engine = SelfHealingEngine::default();
+        let error = || ErrorOccurrence::new("tool", "invalid json in tool call", "run_task");
+
+        let first = engine.handle_error(error()).unwrap();
+        assert_ne!(first.strategy, "reduce_context");
+        assert!(!engine.context_reduced("tool", "run_task"));
+
+        let second = engine.handle_error(error()).unwrap();
+        assert_eq!(second.strategy, "reduce_context");
+        assert!(second.success);
+        assert_eq!(second.actions_executed, vec!["reduce_context"]);
+        assert!(engine.context_reduced("tool", "run_task"));
+
+        // Applied once per streak, never again for the same failure
+        let third = engine.handle_error(error()).unwrap();
+        assert_ne!(third.strategy, "reduce_context");
+    }
+
+    #[test]
+    fn test_engine_context_reduction_streak_resets() {
+        let engine = SelfHealingEngine::default();
+        let error = |msg| ErrorOccurrence::new("tool", msg, "run_task");
+
+        // A different message starts a new streak
+        engine.handle_error(error("invalid json: missing field"));
+        let result = engine
+            .handle_error(error("invalid json: unexpected token"))
+            .unwrap();
+        assert_ne!(result.strategy, "reduce_context");
+
+        // Success clears the streak, so reduction is available again
+        engine.handle_error(error("invalid json: unexpected token"));
+        assert!(engine.context_reduced("tool", "run_task"));
+        engine.reset_retry("tool", "run_task");
+        assert!(!engine.context_reduced("tool", "run_task"));
+    }
+
+    #[test]
+    fn test_engine_never_reduces_context_for_transient_errors() {
+        let engine = SelfHealingEngine::default();
+        for message in [
+            "rate limit exceeded (429)",
+            "connection refused",
+            "request timed out",
+            "401 unauthorized",
+        ] {
+            for _ in 0..3 {
+                let error = ErrorOccurrence::new("agent_execution_error", message, "run_task");
+                let result = engine.handle_error(error).unwrap();
+                assert_ne!(result.strategy, "reduce_context", "{}", message);
+            }
+        }
+        assert!(!engine.context_reduced("agent_execution_error", "run_task"));
+
+        // A task error under the same type still qualifies
+        for _ in 0..2 {
+            let error = ErrorOccurrence::new(
+                "agent_execution_error",
+                "Tool 'file_edit' failed: old_str not found",
+                "run_task",
+            );
+            engine.handle_error(error);
+        }
+        assert!(engine.context_reduced("agent_execution_error", "run_task"));
+    }
+
+    #[test]
+    fn test_engine_context_reduction_disabled_by_zero_threshold() {
+        let engine = SelfHealingEngine::new(SelfHealingConfig {
+            context_reduction_threshold: 0,
+            ..Default::default()
+        });
+        for _ in 0..3 {
+            let error = ErrorOccurrence::new("tool", "invalid json in tool call", "run_task");
+            let result = engine.handle_error(error).unwrap();
+            assert_ne!(result.strategy, "reduce_context");
+        }
+    }
+
     // ================================================================
     // Multi-action strategy tests
     // ================================================================
@@ -295,6 +295,12 @@ impl RecoveryExecutor {
                         info!("Custom action: context compression requested");
                         Ok(())
                     }
+                    "reduce_context" => {
+                        // Signal caller to drop unrelated context and re-prompt
+                        let error = params.get("error").map(|s| s.as_str()).unwrap_or("");
+                        info!("Custom action: context reduction requested for '{}'", error);
+                        Ok(())
+                    }
                     "reduce_tool_set" => {
                         // Signal caller to reduce available tools
                         info!("Custom action: tool set reduction requested");
@@ -247,7 +247,13 @@ pub fn is_cacheable(tool_name: &str) -> bool {
 pub fn invalidates_cache(tool_name: &str) -> bool {
     matches!(
         tool_name,
-        "file_write" | "file_edit" | "git_commit" | "git_checkout" | "shell_exec"
+        "file_write"
+            | "file_edit"
+            | "apply_patch"
+            | "symbol_rename"
+            | "git_commit"
+            | "git_checkout"
+            | "shell_exec"
     )
 }
 
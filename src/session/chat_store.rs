@@ -5,12 +5,28 @@
 
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
 
 use crate::api::types::Message;
-use crate::session::encryption::EncryptionManager;
+use crate::bm25::BM25Index;
+use crate::config::ExecutionMode;
+use crate::session::local_first::LocalFirst;
+
+/// Characters of context shown around a search match
+const SNIPPET_CHARS: usize = 160;
+
+/// Age in days at which a session's recency weight halves
+const RECENCY_HALF_LIFE_DAYS: f32 = 30.0;
+
+/// Subdirectory of the chats directory holding context snapshots, kept
+/// apart so they stay out of `/chat list` and search
+const SNAPSHOT_DIR: &str = "snapshots";
 
 /// A saved chat session
 #[derive(Debug, Serialize, Deserialize)]
@@ -38,9 +54,79 @@ pub struct ChatSummary {
     pub message_count: usize,
 }
 
+/// A named working context saved with `/save`: the conversation together
+/// with the files loaded into it and the execution mode
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ContextSnapshot {
+    /// Name of the snapshot
+    pub name: String,
+    /// When the snapshot was saved
+    pub saved_at: DateTime<Utc>,
+    /// Model in use when saved
+    pub model: String,
+    /// Execution mode in effect when saved
+    pub execution_mode: ExecutionMode,
+    /// Files loaded into the context
+    pub context_files: Vec<String>,
+    /// Messages in the conversation
+    pub messages: Vec<Message>,
+}
+
+/// Summary info for listing snapshots
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SnapshotSummary {
+    /// Name of the snapshot
+    pub name: String,
+    /// When the snapshot was saved
+    pub saved_at: DateTime<Utc>,
+    /// Model in use when saved
+    pub model: String,
+    /// Execution mode in effect when saved
+    pub execution_mode: ExecutionMode,
+    /// Number of messages
+    pub message_count: usize,
+    /// Number of context files
+    pub file_count: usize,
+}
+
+/// A saved chat matching a search
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionHit {
+    /// Name of the chat, as accepted by `/chat resume`
+    pub name: String,
+    /// When the chat was saved
+    pub saved_at: DateTime<Utc>,
+    /// Role of the best matching message
+    pub role: String,
+    /// Text around the match in that message
+    pub snippet: String,
+    /// Combined relevance and recency score (higher is better)
+    pub score: f32,
+}
+
+/// A chat as held in the search index
+struct IndexedChat {
+    modified: SystemTime,
+    name: String,
+    saved_at: DateTime<Utc>,
+    /// (role, text) of each non-system message
+    messages: Vec<(String, String)>,
+}
+
+/// Full-text index over saved chats, built on the first search and then
+/// refreshed only for files that changed
+#[derive(Default)]
+struct SearchIndex {
+    /// Indexed chats by file stem
+    chats: HashMap<String, IndexedChat>,
+    bm25: BM25Index,
+}
+
 /// Persistent chat store backed by the filesystem
 pub struct ChatStore {
     chats_dir: PathBuf,
+    storage: LocalFirst,
+    index: Mutex<SearchIndex>,
 }
 
 impl ChatStore {
@@ -51,35 +137,94 @@ impl ChatStore {
             .join("selfware")
             .join("chats");
         std::fs::create_dir_all(&base).context("Failed to create chats directory")?;
-        Ok(Self { chats_dir: base })
+        Ok(Self {
+            chats_dir: base,
+            storage: LocalFirst::global(),
+            index: Mutex::default(),
+        })
     }
 
     /// Fallback constructor that uses a temp directory (for when default location fails)
     pub fn fallback() -> Self {
         Self {
             chats_dir: std::env::temp_dir().join("selfware_chats"),
+            storage: LocalFirst::global(),
+            index: Mutex::default(),
         }
     }
 
+    /// Directory the chats are stored in
+    pub fn chats_dir(&self) -> &PathBuf {
+        &self.chats_dir
+    }
+
     /// Save a chat with the given name
     pub fn save(&self, name: &str, messages: &[Message], model: &str) -> Result<()> {
-        // Ensure directory exists (especially for fallback mode)
-        std::fs::create_dir_all(&self.chats_dir).context("Failed to create chats directory")?;
-
         let chat = SavedChat {
             name: name.to_string(),
             saved_at: Utc::now(),
             model: model.to_string(),
             messages: messages.to_vec(),
         };
-        let path = self.chat_path(name);
-        let json = serde_json::to_string_pretty(&chat)?;
+        self.write_sealed(&self.chat_path(name), &chat)
+            .context("Failed to save chat")
+    }
 
-        let data = if let Some(encryption) = EncryptionManager::get() {
-            encryption.encrypt(json.as_bytes())?
-        } else {
-            json.into_bytes()
-        };
+    /// Load a saved chat by name
+    pub fn load(&self, name: &str) -> Result<SavedChat> {
+        self.read_sealed(&self.chat_path(name), "Chat", name)
+    }
+
+    /// Save a context snapshot under its name, replacing any snapshot of
+    /// the same name
+    pub fn save_snapshot(&self, snapshot: &ContextSnapshot) -> Result<()> {
+        self.write_sealed(&self.snapshot_path(&snapshot.name), snapshot)
+            .context("Failed to save snapshot")
+    }
+
+    /// Load a context snapshot by name
+    pub fn load_snapshot(&self, name: &str) -> Result<ContextSnapshot> {
+        self.read_sealed(&self.snapshot_path(name), "Snapshot", name)
+    }
+
+    /// List all saved context snapshots, newest first
+    pub fn list_snapshots(&self) -> Result<Vec<SnapshotSummary>> {
+        let mut summaries = Vec::new();
+        let dir = self.chats_dir.join(SNAPSHOT_DIR);
+        let entries = std::fs::read_dir(&dir).into_iter().flatten();
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            // Fail closed: skip files that fail decryption.
+            match self.storage.read_to_string(&path).and_then(|json| {
+                serde_json::from_str::<ContextSnapshot>(&json).context("Invalid snapshot file")
+            }) {
+                Ok(snapshot) => summaries.push(SnapshotSummary {
+                    name: snapshot.name,
+                    saved_at: snapshot.saved_at,
+                    model: snapshot.model,
+                    execution_mode: snapshot.execution_mode,
+                    message_count: snapshot.messages.len(),
+                    file_count: snapshot.context_files.len(),
+                }),
+                Err(e) => tracing::warn!("Skipping snapshot file {:?}: {}", path, e),
+            }
+        }
+        summaries.sort_by_key(|s| std::cmp::Reverse(s.saved_at));
+        Ok(summaries)
+    }
+
+    /// Seal `value` as JSON and write it to `path`
+    fn write_sealed<T: Serialize>(&self, path: &Path, value: &T) -> Result<()> {
+        // Ensure directory exists (especially for fallback mode)
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).context("Failed to create chats directory")?;
+        }
+
+        let json = serde_json::to_string_pretty(value)?;
+        let data = self.storage.seal(json.as_bytes())?;
 
         // Atomic write: write to temp file then rename, preventing corruption
         // if the process crashes mid-write or another instance writes concurrently.
@@ -90,37 +235,32 @@ impl ChatStore {
                 .create(true)
                 .truncate(true)
                 .open(&tmp_path)
-                .context("Failed to create chat temp file")?;
-            f.write_all(&data)
-                .context("Failed to write chat temp file")?;
-            f.sync_all().context("Failed to sync chat temp file")?;
+                .context("Failed to create temp file")?;
+            f.write_all(&data).context("Failed to write temp file")?;
+            f.sync_all().context("Failed to sync temp file")?;
         }
-        if let Err(err) = std::fs::rename(&tmp_path, &path) {
+        if let Err(err) = std::fs::rename(&tmp_path, path) {
             let _ = std::fs::remove_file(&tmp_path);
-            return Err(err).context("Failed to atomically replace chat file");
+            return Err(err).context("Failed to atomically replace file");
         }
 
         Ok(())
     }
 
-    /// Load a saved chat by name
-    pub fn load(&self, name: &str) -> Result<SavedChat> {
-        let path = self.chat_path(name);
-        let data = std::fs::read(&path).with_context(|| format!("Chat '{}' not found", name))?;
-
-        let json = if let Some(encryption) = EncryptionManager::get() {
-            // Fail closed: if encryption is enabled and decryption fails, do NOT
-            // silently fall back to reading the data as plain text.
-            let plaintext = encryption.decrypt(&data).context(
-                "Decryption failed for chat file. The file may be corrupt or tampered with.",
-            )?;
-            String::from_utf8(plaintext).context("Decrypted chat is not valid UTF-8")?
-        } else {
-            String::from_utf8(data).context("Chat file is not valid UTF-8")?
-        };
+    /// Read and unseal the JSON at `path`; `kind` and `name` describe it in errors
+    fn read_sealed<T: DeserializeOwned>(&self, path: &Path, kind: &str, name: &str) -> Result<T> {
+        let data = std::fs::read(path).with_context(|| format!("{} '{}' not found", kind, name))?;
+
+        // Fail closed: an encrypted chat that cannot be decrypted is an
+        // error, never read as plain text.
+        let plaintext = self
+            .storage
+            .open(&data)
+            .with_context(|| format!("Failed to open {} '{}'", kind.to_lowercase(), name))?;
+        let json = String::from_utf8(plaintext)
+            .with_context(|| format!("{} file is not valid UTF-8", kind))?;
 
-        let chat: SavedChat = serde_json::from_str(&json)?;
-        Ok(chat)
+        Ok(serde_json::from_str(&json)?)
     }
 
     /// List all saved chats
@@ -131,20 +271,13 @@ impl ChatStore {
                 let path = entry.path();
                 if path.extension().and_then(|e| e.to_str()) == Some("json") {
                     if let Ok(data) = std::fs::read(&path) {
-                        let json_opt = if let Some(encryption) = EncryptionManager::get() {
-                            // Fail closed: skip files that fail decryption.
-                            match encryption.decrypt(&data) {
-                                Ok(p) => String::from_utf8(p).ok(),
-                                Err(_) => {
-                                    tracing::warn!(
-                                        "Skipping chat file {:?}: decryption failed (corrupt or tampered)",
-                                        path
-                                    );
-                                    None
-                                }
+                        // Fail closed: skip files that fail decryption.
+                        let json_opt = match self.storage.open(&data) {
+                            Ok(p) => String::from_utf8(p).ok(),
+                            Err(e) => {
+                                tracing::warn!("Skipping chat file {:?}: {}", path, e);
+                                None
                             }
-                        } else {
-                            String::from_utf8(data).ok()
                         };
 
                         if let Some(json) = json_opt {
@@ -165,6 +298,118 @@ impl ChatStore {
         Ok(summaries)
     }
 
+    /// Full-text search over the messages of every saved chat, returning
+    /// the best match per chat ranked by relevance and recency
+    pub fn search(&self, query: &str, limit: usize) -> Result<Vec<SessionHit>> {
+        let terms = query_terms(query);
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut index = self
+            .index
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Chat search index lock poisoned"))?;
+        self.refresh_index(&mut index);
+
+        let indexed = index.chats.len();
+        let results = index.bm25.search(query, indexed);
+        let Some(best) = results.first().map(|r| r.score) else {
+            return Ok(Vec::new());
+        };
+        let now = Utc::now();
+        let mut hits: Vec<SessionHit> = results
+            .iter()
+            .filter_map(|result| {
+                let chat = index.chats.get(&result.id)?;
+                let (role, text) = chat
+                    .messages
+                    .iter()
+                    .max_by_key(|(_, text)| count_matches(text, &terms))?;
+                let age_days = (now - chat.saved_at).num_hours().max(0) as f32 / 24.0;
+                let recency = 1.0 / (1.0 + age_days / RECENCY_HALF_LIFE_DAYS);
+                Some(SessionHit {
+                    name: chat.name.clone(),
+                    saved_at: chat.saved_at,
+                    role: role.clone(),
+                    snippet: snippet(text, &terms),
+                    score: (result.score / best) * (0.5 + 0.5 * recency),
+                })
+            })
+            .collect();
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+        hits.truncate(limit);
+        Ok(hits)
+    }
+
+    /// Bring the index up to date with the chats directory, re-reading only
+    /// chats saved since they were last indexed
+    fn refresh_index(&self, index: &mut SearchIndex) {
+        let mut present = Vec::new();
+        let entries = std::fs::read_dir(&self.chats_dir).into_iter().flatten();
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            let Ok(modified) = entry.metadata().and_then(|m| m.modified()) else {
+                continue;
+            };
+            present.push(stem.to_string());
+            if index
+                .chats
+                .get(stem)
+                .is_some_and(|c| c.modified == modified)
+            {
+                continue;
+            }
+
+            index.bm25.remove_all(stem);
+            index.chats.remove(stem);
+            let chat = match self.storage.read_to_string(&path).and_then(|json| {
+                serde_json::from_str::<SavedChat>(&json).context("Invalid chat file")
+            }) {
+                Ok(chat) => chat,
+                Err(e) => {
+                    tracing::warn!("Skipping chat file {:?} in search: {}", path, e);
+                    continue;
+                }
+            };
+            let messages: Vec<(String, String)> = chat
+                .messages
+                .iter()
+                .filter(|m| m.role != "system")
+                .map(|m| (m.role.clone(), m.content.text_all()))
+                .filter(|(_, text)| !text.trim().is_empty())
+                .collect();
+            let text: Vec<&str> = messages.iter().map(|(_, t)| t.as_str()).collect();
+            index.bm25.add(stem, text.join("\n"));
+            index.chats.insert(
+                stem.to_string(),
+                IndexedChat {
+                    modified,
+                    name: chat.name,
+                    saved_at: chat.saved_at,
+                    messages,
+                },
+            );
+        }
+
+        let removed: Vec<String> = index
+            .chats
+            .keys()
+            .filter(|stem| !present.contains(stem))
+            .cloned()
+            .collect();
+        for stem in removed {
+            index.bm25.remove_all(&stem);
+            index.chats.remove(&stem);
+        }
+    }
+
     /// Delete a saved chat
     pub fn delete(&self, name: &str) -> Result<()> {
         let path = self.chat_path(name);
@@ -178,21 +423,70 @@ impl ChatStore {
 
     /// Get the file path for a chat name
     fn chat_path(&self, name: &str) -> PathBuf {
-        // Sanitize name for filesystem
-        let safe_name: String = name
-            .chars()
-            .map(|c| {
-                if c.is_alphanumeric() || c == '-' || c == '_' {
-                    c
-                } else {
-                    '_'
-                }
-            })
-            .collect();
-        self.chats_dir.join(format!("{}.json", safe_name))
+        self.chats_dir
+            .join(format!("{}.json", safe_file_name(name)))
+    }
+
+    /// Get the file path for a snapshot name
+    fn snapshot_path(&self, name: &str) -> PathBuf {
+        self.chats_dir
+            .join(SNAPSHOT_DIR)
+            .join(format!("{}.json", safe_file_name(name)))
     }
 }
 
+/// Sanitize a chat or snapshot name for use as a file name
+fn safe_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Lowercased words of a search query
+fn query_terms(query: &str) -> Vec<String> {
+    query
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|t| t.len() >= 2)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Number of query terms occurring in `text`
+fn count_matches(text: &str, terms: &[String]) -> usize {
+    let lower = text.to_lowercase();
+    terms.iter().filter(|t| lower.contains(t.as_str())).count()
+}
+
+/// Text around the first query term in `text`, on one line
+fn snippet(text: &str, terms: &[String]) -> String {
+    let lower = text.to_lowercase();
+    let chars: Vec<char> = text.chars().collect();
+    // Position in chars; clamped because lowercasing can change lengths
+    let start = terms
+        .iter()
+        .filter_map(|t| lower.find(t.as_str()))
+        .min()
+        .map_or(0, |byte| lower[..byte].chars().count())
+        .min(chars.len());
+    let from = start.saturating_sub(SNIPPET_CHARS / 4);
+    let to = (from + SNIPPET_CHARS).min(chars.len());
+    let mut out: String = chars[from..to].iter().collect();
+    out = out.split_whitespace().collect::<Vec<_>>().join(" ");
+    if from > 0 {
+        out.insert(0, '…');
+    }
+    if to < chars.len() {
+        out.push('…');
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -202,6 +496,8 @@ mod tests {
         let dir = TempDir::new().unwrap();
         let store = ChatStore {
             chats_dir: dir.path().to_path_buf(),
+            storage: LocalFirst::new(),
+            index: Mutex::default(),
         };
         (store, dir)
     }
@@ -221,6 +517,139 @@ mod tests {
         assert_eq!(loaded.messages.len(), 2);
     }
 
+    #[test]
+    fn test_save_encrypted_chat() {
+        use crate::session::local_first::KeySource;
+
+        let (mut store, _dir) = test_store();
+        store.storage = LocalFirst::new()
+            .with_encryption(KeySource::Passphrase("chat-store-test".into()))
+            .unwrap();
+        let messages = vec![Message::user("my token is tok-42".to_string())];
+        store.save("sealed", &messages, "test-model").unwrap();
+
+        let raw = std::fs::read(store.chat_path("sealed")).unwrap();
+        assert!(!String::from_utf8_lossy(&raw).contains("tok-42"));
+        assert_eq!(store.load("sealed").unwrap().messages.len(), 1);
+        assert_eq!(store.list().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_search_finds_sessions_and_ranks_by_relevance() {
+        let (store, _dir) = test_store();
+        store
+            .save(
+                "deadlock-fix",
+                &[
+                    Message::system("the deadlock system prompt".to_string()),
+                    Message::user("tests hang in CI".to_string()),
+                    Message::assistant(
+                        "The deadlock comes from holding the mutex across an await point"
+                            .to_string(),
+                    ),
+                ],
+                "model",
+            )
+            .unwrap();
+        store
+            .save(
+                "refactor",
+                &[Message::user(
+                    "split the parser module, no deadlock risk here".to_string(),
+                )],
+                "model",
+            )
+            .unwrap();
+        store
+            .save(
+                "unrelated",
+                &[Message::user("add a readme".to_string())],
+                "model",
+            )
+            .unwrap();
+
+        let hits = store.search("deadlock mutex", 10).unwrap();
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].name, "deadlock-fix");
+        assert_eq!(hits[0].role, "assistant");
+        assert!(hits[0]
+            .snippet
+            .contains("deadlock comes from holding the mutex"));
+        assert!(hits[0].score > hits[1].score);
+        assert!(store.search("", 10).unwrap().is_empty());
+        assert_eq!(store.search("deadlock", 1).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_search_index_follows_saves_and_deletes() {
+        let (store, _dir) = test_store();
+        store
+            .save("a", &[Message::user("first draft".to_string())], "model")
+            .unwrap();
+        assert_eq!(store.search("draft", 10).unwrap().len(), 1);
+        assert!(store.search("tokio", 10).unwrap().is_empty());
+
+        // Re-saving changes the file's mtime, so it is re-indexed
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        store
+            .save(
+                "a",
+                &[Message::user("switch to tokio".to_string())],
+                "model",
+            )
+            .unwrap();
+        assert_eq!(store.search("tokio", 10).unwrap().len(), 1);
+
+        store.delete("a").unwrap();
+        assert!(store.search("tokio", 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_search_snippet_windows_long_messages() {
+        let text = format!("{} needle {}", "x ".repeat(200), "y ".repeat(200));
+        let terms = query_terms("Needle");
+        let snip = snippet(&text, &terms);
+        assert!(snip.starts_with('…') && snip.ends_with('…'));
+        assert!(snip.contains("needle"));
+        assert!(snip.chars().count() <= SNIPPET_CHARS + 2);
+    }
+
+    #[test]
+    fn test_snapshot_roundtrip_kept_apart_from_chats() {
+        let (store, _dir) = test_store();
+        store
+            .save("work", &[Message::user("chat".to_string())], "model")
+            .unwrap();
+        let snapshot = ContextSnapshot {
+            name: "work".to_string(),
+            saved_at: Utc::now(),
+            model: "model".to_string(),
+            execution_mode: ExecutionMode::AutoEdit,
+            context_files: vec!["src/lib.rs".to_string()],
+            messages: vec![
+                Message::system("prompt".to_string()),
+                Message::user("snapshot".to_string()),
+            ],
+        };
+        store.save_snapshot(&snapshot).unwrap();
+
+        let loaded = store.load_snapshot("work").unwrap();
+        assert_eq!(loaded.execution_mode, ExecutionMode::AutoEdit);
+        assert_eq!(loaded.context_files, vec!["src/lib.rs"]);
+        assert_eq!(loaded.messages.len(), 2);
+        // The chat of the same name is untouched and snapshots stay out of
+        // chat listings and search
+        assert_eq!(store.load("work").unwrap().messages.len(), 1);
+        assert_eq!(store.list().unwrap().len(), 1);
+        assert!(store.search("snapshot", 10).unwrap().is_empty());
+
+        let listed = store.list_snapshots().unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].message_count, 2);
+        assert_eq!(listed[0].file_count, 1);
+        assert!(store.load_snapshot("missing").is_err());
+    }
+
     #[test]
     fn test_list_chats() {
         let (store, _dir) = test_store();
@@ -7,6 +7,7 @@
 //! - Tool call history with timing
 //! - Git state for reproducibility
 //! - Error logs for debugging
+//! - Per-step model reasoning, when `store_reasoning` is enabled
 //!
 //! Checkpoints are stored as JSON files and can be resumed with `Agent::resume()`.
 
@@ -20,6 +21,8 @@ use std::path::PathBuf;
 
 use crate::api::types::Message;
 use crate::redact;
+use crate::session::local_first::LocalFirst;
+use crate::tools::tool_output::RetainedOutput;
 
 /// Envelope that wraps a checkpoint with an integrity checksum.
 ///
@@ -130,6 +133,73 @@ pub enum TaskStatus {
     Paused,
 }
 
+impl TaskStatus {
+    /// Completed or failed; retention may prune these
+    pub fn is_finished(&self) -> bool {
+        matches!(self, TaskStatus::Completed | TaskStatus::Failed)
+    }
+}
+
+/// Which finished tasks to keep. In-progress and paused tasks are never
+/// pruned, since they can still be resumed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RetentionPolicy {
+    /// Keep only this many most recently updated finished tasks
+    pub keep_last: Option<usize>,
+    /// Drop finished tasks not updated for this long
+    pub keep_for: Option<std::time::Duration>,
+}
+
+impl RetentionPolicy {
+    pub fn from_config(config: &crate::config::CheckpointConfig) -> Result<Self> {
+        Ok(Self {
+            keep_last: config.keep_last,
+            keep_for: config.keep_for_duration()?,
+        })
+    }
+
+    /// Keeps everything
+    pub fn is_unbounded(&self) -> bool {
+        self.keep_last.is_none() && self.keep_for.is_none()
+    }
+
+    /// Task IDs to prune from `tasks`, oldest first
+    pub fn expired(&self, tasks: &[TaskSummary], now: DateTime<Utc>) -> Vec<String> {
+        let mut finished: Vec<&TaskSummary> =
+            tasks.iter().filter(|t| t.status.is_finished()).collect();
+        finished.sort_by_key(|t| std::cmp::Reverse(t.updated_at));
+
+        let cutoff = self
+            .keep_for
+            .and_then(|age| chrono::Duration::from_std(age).ok())
+            .map(|age| now - age);
+        let mut expired: Vec<String> = finished
+            .iter()
+            .enumerate()
+            .filter(|(rank, task)| {
+                self.keep_last.is_some_and(|n| *rank >= n)
+                    || cutoff.is_some_and(|cutoff| task.updated_at < cutoff)
+            })
+            .map(|(_, task)| task.task_id.clone())
+            .collect();
+        expired.reverse();
+        expired
+    }
+}
+
+/// Characters of model reasoning kept per step
+pub const MAX_REASONING_CHARS_PER_STEP: usize = 8_000;
+
+/// Model reasoning produced during one step
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StepReasoning {
+    pub step: usize,
+    pub text: String,
+    /// Set when the step's text was cut at [`MAX_REASONING_CHARS_PER_STEP`]
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+}
+
 /// A memory entry for serialization
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemoryEntry {
@@ -169,6 +239,22 @@ pub struct GitCheckpointInfo {
     pub modified_files: Vec<String>,
 }
 
+/// Lineage link recorded on a checkpoint that was forked from another task
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ForkOrigin {
+    pub parent_task_id: String,
+    pub forked_at_step: usize,
+}
+
+/// A state saved on request with `/checkpoint`, to fork or resume from later
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Bookmark {
+    pub step: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
 /// Represents the delta/diff between two checkpoints
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CheckpointDelta {
@@ -187,6 +273,8 @@ pub struct CheckpointDelta {
     pub new_memory_entries: Vec<MemoryEntry>,
     pub new_tool_calls: Vec<ToolCallLog>,
     pub new_errors: Vec<ErrorLog>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub new_reasoning: Vec<StepReasoning>,
 
     pub updated_tokens: Option<usize>,
     pub git_checkpoint: Option<GitCheckpointInfo>,
@@ -217,6 +305,38 @@ pub struct TaskCheckpoint {
 
     // Git state
     pub git_checkpoint: Option<GitCheckpointInfo>,
+
+    // Lineage (set when this task was forked from another checkpoint)
+    #[serde(default)]
+    pub fork_origin: Option<ForkOrigin>,
+
+    // Decisions pinned by `/compact`; survives context compression
+    #[serde(default)]
+    pub pinned_note: Option<String>,
+
+    // Assumptions made in place of clarifying an ambiguous task
+    #[serde(default)]
+    pub assumptions: Vec<String>,
+
+    // Full text of tool results elided in the messages above
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_outputs: Vec<RetainedOutput>,
+
+    // Steps saved on request, with optional labels
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bookmarks: Vec<Bookmark>,
+
+    // Paths the task is scoped to with `/focus` or `--focus`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub focus: Vec<String>,
+
+    // Model reasoning per step, kept only when `store_reasoning` is enabled
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reasoning: Vec<StepReasoning>,
+
+    // What the task did, written when it completes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<crate::agent::task_summary::TaskSummary>,
 }
 
 impl TaskCheckpoint {
@@ -242,6 +362,18 @@ impl TaskCheckpoint {
             // Force a full checkpoint write for this transition.
             return None;
         }
+        if self.pinned_note != base.pinned_note {
+            // Pinning also rewrites the system message, which deltas cannot express.
+            return None;
+        }
+        if self.assumptions != base.assumptions
+            || self.tool_outputs != base.tool_outputs
+            || self.bookmarks != base.bookmarks
+            || self.focus != base.focus
+            || self.summary != base.summary
+        {
+            return None;
+        }
         let git_checkpoint = (self.git_checkpoint != base.git_checkpoint)
             .then(|| self.git_checkpoint.clone())
             .flatten();
@@ -267,6 +399,11 @@ impl TaskCheckpoint {
         } else {
             return None;
         };
+        let new_reasoning = if self.reasoning.len() >= base.reasoning.len() {
+            self.reasoning[base.reasoning.len()..].to_vec()
+        } else {
+            return None;
+        };
 
         let has_changes = status.is_some()
             || current_step.is_some()
@@ -275,6 +412,7 @@ impl TaskCheckpoint {
             || !new_memory_entries.is_empty()
             || !new_tool_calls.is_empty()
             || !new_errors.is_empty()
+            || !new_reasoning.is_empty()
             || updated_tokens.is_some()
             || git_checkpoint.is_some();
 
@@ -294,6 +432,7 @@ impl TaskCheckpoint {
             new_memory_entries,
             new_tool_calls,
             new_errors,
+            new_reasoning,
             updated_tokens,
             git_checkpoint,
         })
@@ -328,6 +467,7 @@ impl TaskCheckpoint {
         self.memory_entries.extend(delta.new_memory_entries.clone());
         self.tool_calls.extend(delta.new_tool_calls.clone());
         self.errors.extend(delta.new_errors.clone());
+        self.reasoning.extend(delta.new_reasoning.clone());
 
         if let Some(tokens) = delta.updated_tokens {
             self.estimated_tokens = tokens;
@@ -351,6 +491,10 @@ pub struct TaskSummary {
     pub updated_at: DateTime<Utc>,
     pub tool_call_count: usize,
     pub error_count: usize,
+    #[serde(default)]
+    pub fork_origin: Option<ForkOrigin>,
+    #[serde(default)]
+    pub bookmarks: Vec<Bookmark>,
 }
 
 impl TaskCheckpoint {
@@ -372,6 +516,14 @@ impl TaskCheckpoint {
             tool_calls: Vec::new(),
             errors: Vec::new(),
             git_checkpoint: None,
+            fork_origin: None,
+            pinned_note: None,
+            assumptions: Vec::new(),
+            tool_outputs: Vec::new(),
+            bookmarks: Vec::new(),
+            focus: Vec::new(),
+            reasoning: Vec::new(),
+            summary: None,
         }
     }
 
@@ -386,9 +538,96 @@ impl TaskCheckpoint {
             updated_at: self.updated_at,
             tool_call_count: self.tool_calls.len(),
             error_count: self.errors.len(),
+            fork_origin: self.fork_origin.clone(),
+            bookmarks: self.bookmarks.clone(),
         }
     }
 
+    /// Deep-copy this checkpoint up to `at_step` into a new task lineage.
+    ///
+    /// Messages are cut before the first assistant turn beyond `at_step`, tool
+    /// call logs are trimmed to match the tool results that remain, and errors
+    /// recorded after the step are dropped. The copy is paused so it can be
+    /// resumed explicitly.
+    pub fn fork_at(&self, new_task_id: String, at_step: usize) -> Result<TaskCheckpoint> {
+        if at_step > self.current_step {
+            bail!(
+                "Cannot fork task {} at step {}: checkpoint only reached step {}",
+                self.task_id,
+                at_step,
+                self.current_step
+            );
+        }
+
+        let mut assistant_turns = 0;
+        let cut = self
+            .messages
+            .iter()
+            .position(|m| {
+                if m.role == "assistant" {
+                    assistant_turns += 1;
+                }
+                assistant_turns > at_step
+            })
+            .unwrap_or(self.messages.len());
+        let messages = self.messages[..cut].to_vec();
+
+        let tool_results = messages
+            .iter()
+            .filter(|m| {
+                m.role == "tool"
+                    || (m.role == "user" && m.content.text().starts_with("<tool_result>"))
+            })
+            .count();
+        let tool_calls: Vec<ToolCallLog> =
+            self.tool_calls.iter().take(tool_results).cloned().collect();
+        let errors: Vec<ErrorLog> = self
+            .errors
+            .iter()
+            .filter(|e| e.step <= at_step)
+            .cloned()
+            .collect();
+
+        let now = Utc::now();
+        Ok(TaskCheckpoint {
+            version: CURRENT_CHECKPOINT_VERSION,
+            task_id: new_task_id,
+            task_description: self.task_description.clone(),
+            created_at: now,
+            updated_at: now,
+            status: TaskStatus::Paused,
+            current_step: at_step,
+            current_iteration: 0,
+            messages,
+            memory_entries: self.memory_entries.clone(),
+            estimated_tokens: self.estimated_tokens,
+            tool_calls,
+            errors,
+            git_checkpoint: self.git_checkpoint.clone(),
+            fork_origin: Some(ForkOrigin {
+                parent_task_id: self.task_id.clone(),
+                forked_at_step: at_step,
+            }),
+            pinned_note: self.pinned_note.clone(),
+            assumptions: self.assumptions.clone(),
+            tool_outputs: self.tool_outputs.clone(),
+            bookmarks: self
+                .bookmarks
+                .iter()
+                .filter(|b| b.step <= at_step)
+                .cloned()
+                .collect(),
+            focus: self.focus.clone(),
+            reasoning: self
+                .reasoning
+                .iter()
+                .filter(|r| r.step <= at_step)
+                .cloned()
+                .collect(),
+            summary: None,
+        })
+    }
+
     /// Add a tool call log entry
     pub fn log_tool_call(&mut self, log: ToolCallLog) {
         self.tool_calls.push(log);
@@ -406,6 +645,28 @@ impl TaskCheckpoint {
         self.touch();
     }
 
+    /// Record model reasoning for `step`. Each step keeps at most
+    /// [`MAX_REASONING_CHARS_PER_STEP`] characters across its responses.
+    pub fn log_reasoning(&mut self, step: usize, text: &str) {
+        let text = text.trim();
+        let step_entries = || self.reasoning.iter().filter(|r| r.step == step);
+        if text.is_empty() || step_entries().any(|r| r.truncated) {
+            return;
+        }
+        let used: usize = step_entries().map(|r| r.text.chars().count()).sum();
+        let mut chars = text.chars();
+        let kept = chars
+            .by_ref()
+            .take(MAX_REASONING_CHARS_PER_STEP.saturating_sub(used))
+            .collect();
+        self.reasoning.push(StepReasoning {
+            step,
+            text: kept,
+            truncated: chars.next().is_some(),
+        });
+        self.touch();
+    }
+
     /// Update the step
     pub fn set_step(&mut self, step: usize) {
         self.current_step = step;
@@ -430,6 +691,25 @@ impl TaskCheckpoint {
         self.touch();
     }
 
+    /// Record the assumptions the task proceeds on
+    pub fn set_assumptions(&mut self, assumptions: Vec<String>) {
+        self.assumptions = assumptions;
+        self.touch();
+    }
+
+    /// Bookmark the current step, replacing an earlier bookmark of the same step
+    pub fn add_bookmark(&mut self, label: Option<String>) -> &Bookmark {
+        let step = self.current_step;
+        self.bookmarks.retain(|b| b.step != step);
+        self.bookmarks.push(Bookmark {
+            step,
+            label,
+            created_at: Utc::now(),
+        });
+        self.touch();
+        self.bookmarks.last().expect("bookmark just pushed")
+    }
+
     /// Update token estimate and bump checkpoint version.
     pub fn set_estimated_tokens(&mut self, estimated_tokens: usize) {
         self.estimated_tokens = estimated_tokens;
@@ -440,6 +720,8 @@ impl TaskCheckpoint {
 /// Manager for saving and loading task checkpoints
 pub struct CheckpointManager {
     checkpoints_dir: PathBuf,
+    storage: LocalFirst,
+    retention: RetentionPolicy,
 }
 
 /// Maximum number of incremental deltas before forcing a compacted full write.
@@ -459,7 +741,23 @@ impl CheckpointManager {
                 )
             })?;
         }
-        Ok(Self { checkpoints_dir })
+        Ok(Self {
+            checkpoints_dir,
+            storage: LocalFirst::global(),
+            retention: RetentionPolicy::default(),
+        })
+    }
+
+    /// Prune finished tasks per `retention` whenever a task finishes
+    pub fn with_retention(mut self, retention: RetentionPolicy) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    /// Use `storage` (e.g. encrypted) instead of the installed default
+    pub fn with_storage(mut self, storage: LocalFirst) -> Self {
+        self.storage = storage;
+        self
     }
 
     /// Create a checkpoint manager with default directory
@@ -491,7 +789,19 @@ impl CheckpointManager {
     /// Integrity: An HMAC-SHA-256 digest is computed over the JSON payload and
     /// stored in a wrapper envelope so that `load()` can verify the file has
     /// not been corrupted or tampered with.
+    ///
+    /// Saving a finished task also enforces the retention policy.
     pub fn save(&self, checkpoint: &TaskCheckpoint) -> Result<()> {
+        self.write(checkpoint)?;
+        if checkpoint.status.is_finished() && !self.retention.is_unbounded() {
+            if let Err(e) = self.prune(&self.retention) {
+                tracing::warn!("Failed to prune old checkpoints: {}", e);
+            }
+        }
+        Ok(())
+    }
+
+    fn write(&self, checkpoint: &TaskCheckpoint) -> Result<()> {
         let full_path = self.checkpoint_path(&checkpoint.task_id);
 
         // Prefer a compact delta write when possible to reduce SSD wear.
@@ -560,6 +870,7 @@ impl CheckpointManager {
             .context("Failed to create checkpoint delta envelope")?;
         let line = serde_json::to_string(&envelope)
             .context("Failed to serialize checkpoint delta envelope")?;
+        let line = self.storage.seal_line(&line)?;
 
         let mut file = fs::OpenOptions::new()
             .create(true)
@@ -622,6 +933,7 @@ impl CheckpointManager {
 
         let json =
             serde_json::to_string_pretty(&envelope).context("Failed to format checkpoint JSON")?;
+        let data = self.storage.seal(json.as_bytes())?;
 
         // Atomic write: write to a temp file in the same directory, then rename.
         let suffix = std::time::SystemTime::now()
@@ -641,7 +953,7 @@ impl CheckpointManager {
                 .open(&tmp_path)
                 .with_context(|| format!("Failed to create checkpoint temp file {:?}", tmp_path))?;
             tmp_file
-                .write_all(json.as_bytes())
+                .write_all(&data)
                 .with_context(|| format!("Failed to write checkpoint temp file {:?}", tmp_path))?;
             tmp_file
                 .sync_all()
@@ -749,6 +1061,13 @@ impl CheckpointManager {
             if line.trim().is_empty() {
                 continue;
             }
+            let line = &self.storage.open_line(line).with_context(|| {
+                format!(
+                    "Failed to open checkpoint delta from {:?} line {}",
+                    path,
+                    line_no + 1
+                )
+            })?;
 
             let delta = if let Ok(envelope) = serde_json::from_str::<CheckpointEnvelope>(line) {
                 envelope.verify().with_context(|| {
@@ -789,7 +1108,9 @@ impl CheckpointManager {
 
     /// Attempt to load and verify a checkpoint from a specific path.
     fn try_load_from_path(&self, path: &std::path::Path) -> Result<TaskCheckpoint> {
-        let json = fs::read_to_string(path)
+        let json = self
+            .storage
+            .read_to_string(path)
             .with_context(|| format!("Failed to read checkpoint from {:?}", path))?;
 
         // Try to parse as an envelope first (new format with integrity check)
@@ -926,6 +1247,33 @@ impl CheckpointManager {
         Ok(summaries)
     }
 
+    /// Fork a saved task at `at_step` into a new task and return the new ID.
+    ///
+    /// The original checkpoint is left untouched; the fork records a parent
+    /// link so the journal can show the lineage.
+    pub fn fork(&self, task_id: &str, at_step: usize) -> Result<String> {
+        if !self.checkpoint_path(task_id).exists() {
+            bail!("No checkpoint found for task '{}'", task_id);
+        }
+        let parent = self.load(task_id)?;
+        let new_task_id = uuid::Uuid::new_v4().to_string();
+        let forked = parent.fork_at(new_task_id.clone(), at_step)?;
+        self.save(&forked)?;
+        Ok(new_task_id)
+    }
+
+    /// Delete finished tasks that `policy` no longer keeps; returns their IDs
+    pub fn prune(&self, policy: &RetentionPolicy) -> Result<Vec<String>> {
+        let expired = policy.expired(&self.list_tasks()?, Utc::now());
+        for task_id in &expired {
+            self.delete(task_id)?;
+        }
+        if !expired.is_empty() {
+            tracing::info!("Pruned {} finished checkpoint(s)", expired.len());
+        }
+        Ok(expired)
+    }
+
     /// Delete a checkpoint
     pub fn delete(&self, task_id: &str) -> Result<()> {
         let path = self.checkpoint_path(task_id);
@@ -954,8 +1302,7 @@ impl CheckpointManager {
         self.checkpoint_path(task_id).exists()
     }
 
-    /// Get the checkpoints directory path (test helper)
-    #[cfg(test)]
+    /// Get the checkpoints directory path
     pub fn checkpoints_dir(&self) -> &PathBuf {
         &self.checkpoints_dir
     }
@@ -1295,6 +1642,8 @@ mod tests {
             updated_at: Utc::now(),
             tool_call_count: 10,
             error_count: 2,
+            fork_origin: None,
+            bookmarks: Vec::new(),
         };
         assert_eq!(summary.current_step, 3);
         assert_eq!(summary.tool_call_count, 10);
@@ -1423,6 +1772,115 @@ mod tests {
         assert_eq!(hydrated.version, next.version);
     }
 
+    #[test]
+    fn test_checkpoint_delta_not_used_when_pinned_note_changes() {
+        let mut base = TaskCheckpoint::new("task_pin".to_string(), "Pin test".to_string());
+        base.set_messages(vec![Message::system("sys")]);
+
+        let mut next = base.clone();
+        next.pinned_note = Some("- use sqlite".to_string());
+        next.set_step(1);
+        assert!(next.compute_delta(&base).is_none());
+
+        // Checkpoints written before pinning existed still load
+        let mut json = serde_json::to_value(&base).unwrap();
+        json.as_object_mut().unwrap().remove("pinned_note");
+        let legacy: TaskCheckpoint = serde_json::from_value(json).unwrap();
+        assert!(legacy.pinned_note.is_none());
+    }
+
+    #[test]
+    fn test_checkpoint_keeps_elided_tool_outputs() {
+        let base = TaskCheckpoint::new("task_elide".to_string(), "Elide test".to_string());
+        let mut next = base.clone();
+        next.tool_outputs.push(RetainedOutput {
+            call_id: "call_1".to_string(),
+            output: "full output".to_string(),
+            elided_from: 4,
+        });
+        assert!(next.compute_delta(&base).is_none());
+
+        let json = serde_json::to_string(&next).unwrap();
+        let loaded: TaskCheckpoint = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.tool_outputs, next.tool_outputs);
+        // Nothing elided, nothing written
+        assert!(!serde_json::to_string(&base)
+            .unwrap()
+            .contains("tool_outputs"));
+    }
+
+    #[test]
+    fn test_checkpoint_bookmarks_survive_fork_up_to_step() {
+        let mut cp = TaskCheckpoint::new("task_mark".to_string(), "Mark test".to_string());
+        cp.set_step(2);
+        let base = cp.clone();
+        cp.add_bookmark(Some("before migration".to_string()));
+        assert!(cp.compute_delta(&base).is_none());
+        cp.add_bookmark(Some("retry".to_string()));
+        assert_eq!(cp.bookmarks.len(), 1);
+        cp.set_step(5);
+        cp.add_bookmark(None);
+
+        let summary = cp.to_summary();
+        assert_eq!(summary.bookmarks.len(), 2);
+        assert_eq!(summary.bookmarks[0].label.as_deref(), Some("retry"));
+
+        let forked = cp.fork_at("task_fork".to_string(), 3).unwrap();
+        assert_eq!(forked.bookmarks.len(), 1);
+        assert_eq!(forked.bookmarks[0].step, 2);
+    }
+
+    #[test]
+    fn test_checkpoint_manager_prunes_finished_tasks_on_save() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = CheckpointManager::new(dir.path().to_path_buf())
+            .unwrap()
+            .with_retention(RetentionPolicy {
+                keep_last: Some(2),
+                keep_for: None,
+            });
+
+        let mut paused = TaskCheckpoint::new("paused".to_string(), "Resume me".to_string());
+        paused.set_status(TaskStatus::Paused);
+        paused.updated_at = Utc::now() - chrono::Duration::days(90);
+        manager.save(&paused).unwrap();
+        for i in 0..4 {
+            let mut done = TaskCheckpoint::new(format!("done-{}", i), "Done".to_string());
+            done.set_status(TaskStatus::Completed);
+            done.updated_at = Utc::now() - chrono::Duration::days(10 - i);
+            manager.save(&done).unwrap();
+        }
+
+        let mut ids: Vec<String> = manager
+            .list_tasks()
+            .unwrap()
+            .into_iter()
+            .map(|t| t.task_id)
+            .collect();
+        ids.sort();
+        assert_eq!(ids, vec!["done-2", "done-3", "paused"]);
+
+        let by_age = RetentionPolicy {
+            keep_last: None,
+            keep_for: Some(std::time::Duration::from_secs(7 * 86_400 + 43_200)),
+        };
+        assert_eq!(manager.prune(&by_age).unwrap(), vec!["done-2"]);
+        assert!(manager.load("paused").is_ok());
+    }
+
+    #[test]
+    fn test_checkpoint_assumptions_force_full_save() {
+        let base = TaskCheckpoint::new("task_assume".to_string(), "improve it".to_string());
+        let mut next = base.clone();
+        next.set_assumptions(vec!["Keep changes behaviour-preserving".to_string()]);
+        assert!(next.compute_delta(&base).is_none());
+
+        let mut json = serde_json::to_value(&base).unwrap();
+        json.as_object_mut().unwrap().remove("assumptions");
+        let legacy: TaskCheckpoint = serde_json::from_value(json).unwrap();
+        assert!(legacy.assumptions.is_empty());
+    }
+
     #[test]
     fn test_checkpoint_manager_replays_delta_log() {
         let dir = tempdir().unwrap();
@@ -1458,6 +1916,39 @@ mod tests {
         assert_eq!(loaded.tool_calls.len(), 1);
     }
 
+    #[test]
+    fn test_checkpoint_manager_encrypts_at_rest() {
+        use crate::session::local_first::KeySource;
+
+        let dir = tempdir().unwrap();
+        let storage = LocalFirst::new()
+            .with_encryption(KeySource::Passphrase("checkpoint-test".into()))
+            .unwrap();
+        let manager = CheckpointManager::new(dir.path().to_path_buf())
+            .unwrap()
+            .with_storage(storage);
+
+        let mut checkpoint =
+            TaskCheckpoint::new("task_sealed".to_string(), "Sealed description".to_string());
+        manager.save(&checkpoint).unwrap();
+        checkpoint.set_step(4);
+        manager.save(&checkpoint).unwrap();
+
+        let raw = std::fs::read(manager.checkpoint_path("task_sealed")).unwrap();
+        assert!(!String::from_utf8_lossy(&raw).contains("Sealed description"));
+        let loaded = manager.load("task_sealed").unwrap();
+        assert_eq!(loaded.task_description, "Sealed description");
+        assert_eq!(loaded.current_step, 4);
+
+        // A plaintext reader cannot load the sealed journal
+        let plain = CheckpointManager::new(dir.path().to_path_buf())
+            .unwrap()
+            .with_storage(LocalFirst::new());
+        assert!(plain
+            .try_load_from_path(&manager.checkpoint_path("task_sealed"))
+            .is_err());
+    }
+
     #[test]
     fn test_capture_git_state() {
         // We're in a git repo, so this should work
@@ -1744,6 +2235,46 @@ mod tests {
         assert!(raw.contains("[REDACTED]"));
     }
 
+    #[test]
+    fn test_log_reasoning_caps_steps_and_redacts() {
+        let dir = tempdir().unwrap();
+        let manager = CheckpointManager::new(dir.path().to_path_buf()).unwrap();
+
+        let mut checkpoint =
+            TaskCheckpoint::new("reasoning_test".to_string(), "Reasoning".to_string());
+        checkpoint.log_reasoning(1, "Retry with api_key=sk-secretkey12345678901234567890");
+        checkpoint.log_reasoning(1, "  ");
+        checkpoint.log_reasoning(2, &"x".repeat(MAX_REASONING_CHARS_PER_STEP - 10));
+        checkpoint.log_reasoning(2, &"y".repeat(20));
+        checkpoint.log_reasoning(2, "dropped once the step is truncated");
+        assert_eq!(checkpoint.reasoning.len(), 3);
+        assert!(!checkpoint.reasoning[0].truncated);
+        assert_eq!(checkpoint.reasoning[2].text, "y".repeat(10));
+        assert!(checkpoint.reasoning[2].truncated);
+        manager.save(&checkpoint).unwrap();
+
+        // Later responses travel as deltas
+        checkpoint.log_reasoning(3, "Done.");
+        let base = manager.load("reasoning_test").unwrap();
+        let delta = checkpoint.compute_delta(&base).unwrap();
+        assert_eq!(delta.new_reasoning.len(), 1);
+        manager.save(&checkpoint).unwrap();
+
+        let raw = std::fs::read_to_string(dir.path().join("reasoning_test.json")).unwrap();
+        assert!(!raw.contains("sk-secretkey12345678901234567890"));
+        let loaded = manager.load("reasoning_test").unwrap();
+        assert_eq!(loaded.reasoning.len(), 4);
+        assert!(loaded.reasoning[0].text.contains("[REDACTED]"));
+        assert_eq!(
+            loaded
+                .fork_at("fork".to_string(), 0)
+                .unwrap()
+                .reasoning
+                .len(),
+            0
+        );
+    }
+
     #[test]
     fn test_list_tasks_handles_envelope_format() {
         let dir = tempdir().unwrap();
@@ -1941,6 +2472,7 @@ mod tests {
         let impossible_dir = blocker_file.join("checkpoints");
         let manager = CheckpointManager {
             checkpoints_dir: impossible_dir,
+            storage: LocalFirst::new(),
         };
 
         let checkpoint = TaskCheckpoint::new(
@@ -1950,4 +2482,116 @@ mod tests {
         let result = manager.save_with_retry(&checkpoint);
         assert!(result.is_err());
     }
+
+    fn forkable_checkpoint() -> TaskCheckpoint {
+        let mut checkpoint = TaskCheckpoint::new("parent".to_string(), "Fork me".to_string());
+        checkpoint.set_messages(vec![
+            Message::system("system"),
+            Message::user("do the thing"),
+            Message::assistant("step 1"),
+            Message::user("<tool_result>one</tool_result>"),
+            Message::assistant("step 2"),
+            Message::user("<tool_result>two</tool_result>"),
+            Message::assistant("step 3"),
+        ]);
+        for i in 0..2 {
+            checkpoint.log_tool_call(ToolCallLog {
+                timestamp: Utc::now(),
+                tool_name: format!("tool_{}", i),
+                arguments: "{}".to_string(),
+                result: None,
+                success: true,
+                duration_ms: None,
+            });
+        }
+        checkpoint.log_error(1, "early".to_string(), true);
+        checkpoint.log_error(3, "late".to_string(), false);
+        checkpoint.set_step(3);
+        checkpoint
+    }
+
+    #[test]
+    fn test_fork_at_truncates_to_step() {
+        let parent = forkable_checkpoint();
+        let fork = parent.fork_at("child".to_string(), 1).unwrap();
+
+        assert_eq!(fork.task_id, "child");
+        assert_eq!(fork.current_step, 1);
+        assert_eq!(fork.status, TaskStatus::Paused);
+        assert_eq!(fork.messages.len(), 4);
+        assert_eq!(
+            fork.messages.last().unwrap().content.text(),
+            "<tool_result>one</tool_result>"
+        );
+        assert_eq!(fork.tool_calls.len(), 1);
+        assert_eq!(fork.errors.len(), 1);
+        assert_eq!(
+            fork.fork_origin,
+            Some(ForkOrigin {
+                parent_task_id: "parent".to_string(),
+                forked_at_step: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn test_fork_at_current_step_keeps_everything() {
+        let parent = forkable_checkpoint();
+        let fork = parent.fork_at("child".to_string(), 3).unwrap();
+        assert_eq!(fork.messages.len(), parent.messages.len());
+        assert_eq!(fork.tool_calls.len(), 2);
+        assert_eq!(fork.errors.len(), 2);
+    }
+
+    #[test]
+    fn test_fork_at_beyond_current_step_fails() {
+        let parent = forkable_checkpoint();
+        assert!(parent.fork_at("child".to_string(), 4).is_err());
+    }
+
+    #[test]
+    fn test_manager_fork_persists_lineage_and_keeps_parent() {
+        let dir = tempdir().unwrap();
+        let manager = CheckpointManager::new(dir.path().to_path_buf()).unwrap();
+        manager.save(&forkable_checkpoint()).unwrap();
+
+        let child_id = manager.fork("parent", 2).unwrap();
+        assert_ne!(child_id, "parent");
+
+        let parent = manager.load("parent").unwrap();
+        assert_eq!(parent.messages.len(), 7);
+        assert!(parent.fork_origin.is_none());
+
+        let child = manager.load(&child_id).unwrap();
+        assert_eq!(child.current_step, 2);
+        assert_eq!(child.messages.len(), 6);
+
+        let summaries = manager.list_tasks().unwrap();
+        let child_summary = summaries.iter().find(|s| s.task_id == child_id).unwrap();
+        assert_eq!(
+            child_summary
+                .fork_origin
+                .as_ref()
+                .map(|o| o.parent_task_id.as_str()),
+            Some("parent")
+        );
+    }
+
+    #[test]
+    fn test_manager_fork_missing_task_fails() {
+        let dir = tempdir().unwrap();
+        let manager = CheckpointManager::new(dir.path().to_path_buf()).unwrap();
+        assert!(manager.fork("missing", 0).is_err());
+    }
+
+    #[test]
+    fn test_checkpoint_without_fork_origin_deserializes() {
+        let json =
+            serde_json::to_value(TaskCheckpoint::new("t".to_string(), "d".to_string())).unwrap();
+        let mut obj = json.as_object().unwrap().clone();
+        obj.remove("fork_origin");
+        let loaded: TaskCheckpoint =
+            serde_json::from_value(serde_json::Value::Object(obj)).unwrap();
+        assert!(loaded.fork_origin.is_none());
+    }
 }
@@ -388,6 +388,24 @@ impl EditHistory {
             .collect()
     }
 
+    /// ID the next checkpoint will get; everything created from now on
+    /// has an ID at least this large
+    pub fn next_id(&self) -> EditCheckpointId {
+        EditCheckpointId::new(self.next_id)
+    }
+
+    /// Remove and return the checkpoints created since `id`, oldest first
+    pub fn take_since(&mut self, id: EditCheckpointId) -> Vec<EditCheckpoint> {
+        let start = self
+            .checkpoints
+            .iter()
+            .position(|c| c.id.0 >= id.0)
+            .unwrap_or(self.checkpoints.len());
+        let taken = self.checkpoints.split_off(start);
+        self.current = self.current.min(self.checkpoints.len());
+        taken
+    }
+
     /// Clear all history
     pub fn clear(&mut self) {
         self.checkpoints.clear();
@@ -471,6 +489,24 @@ mod tests {
         assert!(!snapshot.hash.is_empty());
     }
 
+    #[test]
+    fn test_take_since() {
+        let mut history = EditHistory::new();
+        history.create_checkpoint(EditAction::SessionStart);
+        let mark = history.next_id();
+        for name in ["a.rs", "b.rs"] {
+            history.create_checkpoint(EditAction::FileCreate {
+                path: PathBuf::from(name),
+            });
+        }
+
+        let taken = history.take_since(mark);
+        assert_eq!(taken.len(), 2);
+        assert_eq!(taken[0].id, mark);
+        assert_eq!((history.len(), history.position()), (1, 1));
+        assert!(history.take_since(history.next_id()).is_empty());
+    }
+
     #[test]
     fn test_file_snapshot_changed() {
         let s1 = FileSnapshot::new(PathBuf::from("test.rs"), "content1".to_string());
@@ -86,6 +86,16 @@ fn derive_key(password: &str) -> [u8; 32] {
     key
 }
 
+/// Derive a 256-bit key from a password with Argon2id at its default
+/// (OWASP-recommended) cost.
+fn derive_key_argon2(password: &str, salt: &[u8]) -> Result<[u8; 32]> {
+    let mut key = [0u8; 32];
+    argon2::Argon2::default()
+        .hash_password_into(password.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow::anyhow!("Key derivation failed: {}", e))?;
+    Ok(key)
+}
+
 use zeroize::Zeroize;
 
 /// Zero the encryption key when the manager is dropped.
@@ -120,6 +130,19 @@ impl EncryptionManager {
         Self::new_instance(key)
     }
 
+    /// Create a per-session encryption manager from a password, failing if
+    /// the installation salt cannot be persisted.
+    ///
+    /// The key is derived with Argon2id (OWASP parameters: 19 MiB, 2 passes),
+    /// which resists GPU guessing far better than PBKDF2. Unlike
+    /// [`new_from_password`](Self::new_from_password) this never falls back
+    /// to an ephemeral salt, which would make data written with the key
+    /// unreadable in the next session.
+    pub fn try_from_password(password: &str) -> Result<Self> {
+        let salt = load_or_create_salt().context("Failed to load the encryption salt")?;
+        Ok(Self::new_instance(derive_key_argon2(password, &salt)?))
+    }
+
     /// Initialize the global encryption manager with a password.
     ///
     /// This is the legacy entry-point.  For per-session usage prefer
@@ -278,6 +301,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn argon2_key_is_deterministic_per_password_and_salt() {
+        let salt = [9u8; SALT_LEN];
+        let key = derive_key_argon2("hunter2", &salt).unwrap();
+        assert_eq!(key, derive_key_argon2("hunter2", &salt).unwrap());
+        assert_ne!(key, derive_key_argon2("hunter3", &salt).unwrap());
+        assert_ne!(key, derive_key_argon2("hunter2", &[8u8; SALT_LEN]).unwrap());
+    }
+
     // ---- Per-session instance tests ----
 
     #[test]
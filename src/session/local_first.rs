@@ -1,15 +1,24 @@
 //! Local-First Optimization
 //!
 //! Minimize network usage through aggressive caching, offline capabilities,
-//! edge computing patterns, and sync efficiency.
+//! edge computing patterns, and sync efficiency. Session stores written
+//! through [`LocalFirst`] can be encrypted at rest.
 
 #![allow(dead_code, unused_imports, unused_variables)]
 
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use anyhow::{bail, Context, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+
+use crate::config::EncryptionConfig;
+use crate::session::encryption::EncryptionManager;
+
 /// Atomic counter for unique IDs
 static CACHE_ENTRY_COUNTER: AtomicU64 = AtomicU64::new(0);
 static SYNC_ID_COUNTER: AtomicU64 = AtomicU64::new(0);
@@ -1066,6 +1075,266 @@ pub struct LocalFirstStats {
     pub edge_tasks_pending: usize,
 }
 
+// ============================================================================
+// At-rest encryption
+// ============================================================================
+
+/// Prefix marking a file sealed by [`LocalFirst`]
+const SEALED_MAGIC: &[u8] = b"SELFWARE-ENC1\n";
+
+/// Prefix marking a sealed line in a JSONL journal
+const SEALED_LINE_PREFIX: &str = "selfware-enc1:";
+
+/// Environment variable read for [`KeySource::Passphrase`] from config
+pub const PASSPHRASE_ENV: &str = "SELFWARE_PASSPHRASE";
+
+/// Where the at-rest encryption key comes from
+#[derive(Clone)]
+pub enum KeySource {
+    /// Derive the key from a passphrase
+    Passphrase(String),
+    /// Derive the key from the passphrase stored in the OS keyring
+    Keyring,
+}
+
+impl std::fmt::Debug for KeySource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Passphrase(_) => write!(f, "Passphrase([REDACTED])"),
+            Self::Keyring => write!(f, "Keyring"),
+        }
+    }
+}
+
+impl KeySource {
+    /// Key source requested by `[encryption]`, or None for plaintext
+    pub fn from_config(config: &EncryptionConfig) -> Result<Option<Self>> {
+        match config.key_source.as_deref() {
+            None => Ok(None),
+            Some("passphrase") => {
+                let passphrase = std::env::var(PASSPHRASE_ENV)
+                    .ok()
+                    .filter(|p| !p.is_empty())
+                    .with_context(|| {
+                        format!(
+                            "Encryption is configured with key_source = \"passphrase\" but {} is not set",
+                            PASSPHRASE_ENV
+                        )
+                    })?;
+                Ok(Some(Self::Passphrase(passphrase)))
+            }
+            Some("keyring") => Ok(Some(Self::Keyring)),
+            Some(other) => bail!("Unknown encryption key_source '{}'", other),
+        }
+    }
+
+    fn manager(&self) -> Result<EncryptionManager> {
+        let passphrase = match self {
+            Self::Passphrase(passphrase) => passphrase.clone(),
+            Self::Keyring => EncryptionManager::load_from_keychain()
+                .context("Encryption key unavailable: cannot read the OS keyring")?
+                .context("Encryption key unavailable: no passphrase stored in the OS keyring")?,
+        };
+        EncryptionManager::try_from_password(&passphrase)
+    }
+}
+
+static GLOBAL_STORAGE: OnceLock<LocalFirst> = OnceLock::new();
+
+/// Local session storage that transparently encrypts at rest.
+///
+/// Sealed data is AES-256-GCM encrypted behind a marker prefix. With
+/// encryption on, unsealed data is refused rather than trusted, so a
+/// plaintext file cannot be slipped in; existing plaintext stores are
+/// converted with [`migrate_dir`](Self::migrate_dir).
+#[derive(Clone, Default)]
+pub struct LocalFirst {
+    encryption: Option<Arc<EncryptionManager>>,
+}
+
+impl std::fmt::Debug for LocalFirst {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LocalFirst")
+            .field("encrypted", &self.is_encrypted())
+            .finish()
+    }
+}
+
+impl LocalFirst {
+    /// Plaintext storage
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Encrypt everything written from now on. Fails if the key is
+    /// unavailable rather than falling back to plaintext.
+    pub fn with_encryption(mut self, key_source: KeySource) -> Result<Self> {
+        self.encryption = Some(Arc::new(key_source.manager()?));
+        Ok(self)
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        self.encryption.is_some()
+    }
+
+    /// Make this the storage used by session stores created afterwards
+    pub fn install(self) -> Result<()> {
+        GLOBAL_STORAGE
+            .set(self)
+            .map_err(|_| anyhow::anyhow!("Session storage already initialized"))
+    }
+
+    /// The installed storage, or plaintext if none was installed
+    pub fn global() -> Self {
+        GLOBAL_STORAGE.get().cloned().unwrap_or_default()
+    }
+
+    /// Prepare data for writing
+    pub fn seal(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let Some(encryption) = &self.encryption else {
+            return Ok(plaintext.to_vec());
+        };
+        let mut sealed = SEALED_MAGIC.to_vec();
+        sealed.extend(encryption.encrypt(plaintext)?);
+        Ok(sealed)
+    }
+
+    /// Recover data written by [`seal`](Self::seal)
+    pub fn open(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let Some(ciphertext) = data.strip_prefix(SEALED_MAGIC) else {
+            self.refuse_plaintext()?;
+            return Ok(data.to_vec());
+        };
+        self.decrypt(ciphertext)
+    }
+
+    /// Read a file written through this storage as text
+    pub fn read_to_string(&self, path: &Path) -> Result<String> {
+        let data = std::fs::read(path).with_context(|| format!("Failed to read {:?}", path))?;
+        let plaintext = self
+            .open(&data)
+            .with_context(|| format!("Failed to open {:?}", path))?;
+        String::from_utf8(plaintext).with_context(|| format!("{:?} is not valid UTF-8", path))
+    }
+
+    /// Prepare one line of a JSONL journal for appending
+    pub fn seal_line(&self, line: &str) -> Result<String> {
+        let Some(encryption) = &self.encryption else {
+            return Ok(line.to_string());
+        };
+        let ciphertext = encryption.encrypt(line.as_bytes())?;
+        Ok(format!(
+            "{}{}",
+            SEALED_LINE_PREFIX,
+            BASE64.encode(ciphertext)
+        ))
+    }
+
+    /// Recover a line written by [`seal_line`](Self::seal_line)
+    pub fn open_line(&self, line: &str) -> Result<String> {
+        let Some(encoded) = line.strip_prefix(SEALED_LINE_PREFIX) else {
+            self.refuse_plaintext()?;
+            return Ok(line.to_string());
+        };
+        let ciphertext = BASE64
+            .decode(encoded.trim_end())
+            .context("Sealed journal line is not valid base64")?;
+        String::from_utf8(self.decrypt(&ciphertext)?).context("Decrypted line is not valid UTF-8")
+    }
+
+    fn refuse_plaintext(&self) -> Result<()> {
+        if self.is_encrypted() {
+            bail!(
+                "Data is not encrypted but encryption is configured; \
+                 run `selfware migrate --encrypt` to encrypt existing sessions"
+            );
+        }
+        Ok(())
+    }
+
+    fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        let encryption = self.encryption.as_ref().context(
+            "Data is encrypted but no encryption key is configured (set [encryption] key_source)",
+        )?;
+        encryption
+            .decrypt(ciphertext)
+            .context("Decryption failed: wrong key, or the data is corrupt or tampered with")
+    }
+
+    /// Encrypt every plaintext store file in `dir` in place. Files already
+    /// sealed are left alone, so this is safe to re-run.
+    pub fn migrate_dir(&self, dir: &Path) -> Result<MigrationReport> {
+        if !self.is_encrypted() {
+            bail!("Cannot migrate {:?}: encryption is not configured", dir);
+        }
+        let mut report = MigrationReport::default();
+        if !dir.exists() {
+            return Ok(report);
+        }
+
+        for entry in std::fs::read_dir(dir).with_context(|| format!("Failed to list {:?}", dir))? {
+            let path = entry?.path();
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            let is_store =
+                name.ends_with(".json") || name.ends_with(".json.bak") || name.ends_with(".jsonl");
+            if !path.is_file() || !is_store {
+                continue;
+            }
+
+            let data =
+                std::fs::read(&path).with_context(|| format!("Failed to read {:?}", path))?;
+            let sealed = if name.ends_with(".jsonl") {
+                let text = String::from_utf8(data)
+                    .with_context(|| format!("{:?} is not valid UTF-8", path))?;
+                if text
+                    .lines()
+                    .all(|l| l.trim().is_empty() || l.starts_with(SEALED_LINE_PREFIX))
+                {
+                    None
+                } else {
+                    let mut out = String::new();
+                    for line in text.lines().filter(|l| !l.trim().is_empty()) {
+                        let plaintext = if line.starts_with(SEALED_LINE_PREFIX) {
+                            self.open_line(line)?
+                        } else {
+                            line.to_string()
+                        };
+                        out.push_str(&self.seal_line(&plaintext)?);
+                        out.push('\n');
+                    }
+                    Some(out.into_bytes())
+                }
+            } else if data.starts_with(SEALED_MAGIC) {
+                None
+            } else {
+                Some(self.seal(&data)?)
+            };
+
+            let Some(sealed) = sealed else {
+                report.already_encrypted += 1;
+                continue;
+            };
+            let tmp = path.with_extension("migrate.tmp");
+            std::fs::write(&tmp, sealed).with_context(|| format!("Failed to write {:?}", tmp))?;
+            if let Err(e) = std::fs::rename(&tmp, &path) {
+                let _ = std::fs::remove_file(&tmp);
+                return Err(e).with_context(|| format!("Failed to replace {:?}", path));
+            }
+            report.encrypted += 1;
+        }
+        Ok(report)
+    }
+}
+
+/// Outcome of [`LocalFirst::migrate_dir`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MigrationReport {
+    /// Files that were plaintext and are now encrypted
+    pub encrypted: usize,
+    /// Files that were already encrypted
+    pub already_encrypted: usize,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2526,4 +2795,123 @@ mod tests {
         coord.offline().set_status(OfflineStatus::Online);
         assert_eq!(coord.stats().offline_status, OfflineStatus::Online);
     }
+
+    fn encrypted_storage() -> LocalFirst {
+        LocalFirst {
+            encryption: Some(Arc::new(EncryptionManager::new_instance([7u8; 32]))),
+        }
+    }
+
+    #[test]
+    fn test_local_first_seal_open_roundtrip() {
+        let storage = encrypted_storage();
+        let sealed = storage.seal(b"{\"secret\":\"sk-123\"}").unwrap();
+        assert!(sealed.starts_with(SEALED_MAGIC));
+        assert!(!String::from_utf8_lossy(&sealed).contains("sk-123"));
+        assert_eq!(storage.open(&sealed).unwrap(), b"{\"secret\":\"sk-123\"}");
+
+        let line = storage.seal_line("{\"step\":1}").unwrap();
+        assert!(line.starts_with(SEALED_LINE_PREFIX));
+        assert_eq!(storage.open_line(&line).unwrap(), "{\"step\":1}");
+
+        // Plaintext is only read back when encryption is off
+        assert_eq!(LocalFirst::new().open(b"{\"a\":1}").unwrap(), b"{\"a\":1}");
+        assert_eq!(
+            LocalFirst::new().open_line("{\"a\":1}").unwrap(),
+            "{\"a\":1}"
+        );
+    }
+
+    #[test]
+    fn test_local_first_refuses_plaintext_when_encrypted() {
+        let storage = encrypted_storage();
+        let err = storage.open(b"{\"a\":1}").unwrap_err();
+        assert!(
+            err.to_string().contains("selfware migrate --encrypt"),
+            "{}",
+            err
+        );
+        assert!(storage.open_line("{\"a\":1}").is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("task.json");
+        std::fs::write(&path, "{\"a\":1}").unwrap();
+        assert!(storage.read_to_string(&path).is_err());
+        storage.migrate_dir(dir.path()).unwrap();
+        assert_eq!(storage.read_to_string(&path).unwrap(), "{\"a\":1}");
+    }
+
+    #[test]
+    fn test_local_first_fails_loudly_without_key() {
+        let sealed = encrypted_storage().seal(b"secret").unwrap();
+        let err = LocalFirst::new().open(&sealed).unwrap_err();
+        assert!(err.to_string().contains("no encryption key is configured"));
+
+        let other = LocalFirst {
+            encryption: Some(Arc::new(EncryptionManager::new_instance([8u8; 32]))),
+        };
+        assert!(other.open(&sealed).is_err());
+        assert!(LocalFirst::new().migrate_dir(Path::new(".")).is_err());
+    }
+
+    #[test]
+    fn test_local_first_key_source_from_config() {
+        let none = KeySource::from_config(&EncryptionConfig::default()).unwrap();
+        assert!(none.is_none());
+        let keyring = EncryptionConfig {
+            key_source: Some("keyring".into()),
+        };
+        assert!(matches!(
+            KeySource::from_config(&keyring).unwrap(),
+            Some(KeySource::Keyring)
+        ));
+        let unknown = EncryptionConfig {
+            key_source: Some("vault".into()),
+        };
+        assert!(KeySource::from_config(&unknown).is_err());
+        assert_eq!(
+            format!("{:?}", KeySource::Passphrase("hunter2".into())),
+            "Passphrase([REDACTED])"
+        );
+    }
+
+    #[test]
+    fn test_local_first_migrate_dir_is_idempotent() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("task.json"), "{\"api_key\":\"sk-1\"}").unwrap();
+        std::fs::write(
+            dir.path().join("task.deltas.jsonl"),
+            "{\"step\":1}\n{\"step\":2}\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "left alone").unwrap();
+
+        let storage = encrypted_storage();
+        let report = storage.migrate_dir(dir.path()).unwrap();
+        assert_eq!(report.encrypted, 2);
+        assert_eq!(report.already_encrypted, 0);
+
+        let json = std::fs::read(dir.path().join("task.json")).unwrap();
+        assert!(!String::from_utf8_lossy(&json).contains("sk-1"));
+        assert_eq!(
+            storage
+                .read_to_string(&dir.path().join("task.json"))
+                .unwrap(),
+            "{\"api_key\":\"sk-1\"}"
+        );
+        let deltas = std::fs::read_to_string(dir.path().join("task.deltas.jsonl")).unwrap();
+        let lines: Vec<String> = deltas
+            .lines()
+            .map(|l| storage.open_line(l).unwrap())
+            .collect();
+        assert_eq!(lines, vec!["{\"step\":1}", "{\"step\":2}"]);
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("notes.txt")).unwrap(),
+            "left alone"
+        );
+
+        let again = storage.migrate_dir(dir.path()).unwrap();
+        assert_eq!(again.encrypted, 0);
+        assert_eq!(again.already_encrypted, 2);
+    }
 }
@@ -1,6 +1,7 @@
 //! Circuit breaker pattern for fault tolerance
 
 use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
@@ -10,9 +11,11 @@ pub struct CircuitBreaker {
     state: AtomicU32, // 0=Closed, 1=Open, 2=HalfOpen
     failure_count: AtomicU32,
     success_count: AtomicU32,
+    total_failures: AtomicU32,
     config: CircuitBreakerConfig,
     last_failure_time: RwLock<Option<Instant>>,
-    last_state_change: RwLock<Instant>,
+    last_state_change: Mutex<Instant>,
+    last_error: Mutex<Option<String>>,
 }
 
 /// Circuit breaker configuration
@@ -47,6 +50,16 @@ pub enum CircuitState {
     HalfOpen, // Testing if service recovered
 }
 
+impl std::fmt::Display for CircuitState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Closed => write!(f, "closed"),
+            Self::Open => write!(f, "open"),
+            Self::HalfOpen => write!(f, "half-open"),
+        }
+    }
+}
+
 /// Circuit breaker error
 #[derive(Debug, Clone)]
 pub enum CircuitBreakerError<E> {
@@ -74,9 +87,11 @@ impl CircuitBreaker {
             state: AtomicU32::new(0),
             failure_count: AtomicU32::new(0),
             success_count: AtomicU32::new(0),
+            total_failures: AtomicU32::new(0),
             config,
             last_failure_time: RwLock::new(None),
-            last_state_change: RwLock::new(Instant::now()),
+            last_state_change: Mutex::new(Instant::now()),
+            last_error: Mutex::new(None),
         }
     }
 
@@ -96,8 +111,28 @@ impl CircuitBreaker {
             return false;
         }
 
-        let last_change = *self.last_state_change.read().await;
-        last_change.elapsed() >= self.config.reset_timeout
+        self.last_state_change_elapsed() >= self.config.reset_timeout
+    }
+
+    fn last_state_change_elapsed(&self) -> Duration {
+        self.last_state_change
+            .lock()
+            .map(|t| t.elapsed())
+            .unwrap_or_default()
+    }
+
+    /// Force the circuit back to closed without waiting for the reset timeout.
+    /// Returns false if it was already closed.
+    pub async fn reset(&self) -> bool {
+        if self.current_state() == CircuitState::Closed {
+            return false;
+        }
+        self.transition_to(CircuitState::Closed, Some("manual reset"))
+            .await;
+        if let Ok(mut last_error) = self.last_error.lock() {
+            *last_error = None;
+        }
+        true
     }
 
     /// Execute operation with circuit breaker protection
@@ -105,12 +140,14 @@ impl CircuitBreaker {
     where
         F: FnOnce() -> Fut,
         Fut: std::future::Future<Output = Result<T, E>>,
+        E: std::fmt::Display,
     {
         // Check current state
         match self.current_state() {
             CircuitState::Open => {
                 if self.should_attempt_reset().await {
-                    self.transition_to(CircuitState::HalfOpen).await;
+                    self.transition_to(CircuitState::HalfOpen, Some("reset timeout elapsed"))
+                        .await;
                 } else {
                     warn!("Circuit breaker open, rejecting request");
                     return Err(CircuitBreakerError::CircuitOpen);
@@ -134,7 +171,7 @@ impl CircuitBreaker {
                 Ok(result)
             }
             Err(e) => {
-                self.on_failure().await;
+                self.on_failure(&e.to_string()).await;
                 Err(CircuitBreakerError::OperationFailed(e))
             }
         }
@@ -148,7 +185,7 @@ impl CircuitBreaker {
         if self.current_state() == CircuitState::HalfOpen {
             if success_count >= self.config.success_threshold {
                 info!("Circuit breaker closing after successful recovery");
-                self.transition_to(CircuitState::Closed).await;
+                self.transition_to(CircuitState::Closed, None).await;
             }
         } else {
             // Reset failure count in closed state
@@ -157,24 +194,28 @@ impl CircuitBreaker {
     }
 
     /// Handle failed operation
-    async fn on_failure(&self) {
+    async fn on_failure(&self, error: &str) {
         let failure_count = self.failure_count.fetch_add(1, Ordering::SeqCst) + 1;
+        self.total_failures.fetch_add(1, Ordering::Relaxed);
         *self.last_failure_time.write().await = Some(Instant::now());
+        if let Ok(mut last_error) = self.last_error.lock() {
+            *last_error = Some(error.to_string());
+        }
 
         warn!(failure_count = failure_count, "Operation failed");
 
         if self.current_state() == CircuitState::HalfOpen {
             // Any failure in half-open goes back to open
             info!("Failure in half-open, reopening circuit");
-            self.transition_to(CircuitState::Open).await;
+            self.transition_to(CircuitState::Open, Some(error)).await;
         } else if failure_count >= self.config.failure_threshold {
             info!("Failure threshold reached, opening circuit");
-            self.transition_to(CircuitState::Open).await;
+            self.transition_to(CircuitState::Open, Some(error)).await;
         }
     }
 
-    /// Transition to a new state
-    async fn transition_to(&self, new_state: CircuitState) {
+    /// Transition to a new state. `trigger` is the error or event that caused it.
+    async fn transition_to(&self, new_state: CircuitState, trigger: Option<&str>) {
         let state_num = match new_state {
             CircuitState::Closed => 0,
             CircuitState::Open => 1,
@@ -182,30 +223,43 @@ impl CircuitBreaker {
         };
 
         let old_state = self.state.swap(state_num, Ordering::SeqCst);
-        *self.last_state_change.write().await = Instant::now();
+        if let Ok(mut last_change) = self.last_state_change.lock() {
+            *last_change = Instant::now();
+        }
 
         // Reset counters on state change
         self.failure_count.store(0, Ordering::SeqCst);
         self.success_count.store(0, Ordering::SeqCst);
 
-        info!(
-            old_state = ?match old_state {
-                0 => CircuitState::Closed,
-                1 => CircuitState::Open,
-                2 => CircuitState::HalfOpen,
-                _ => CircuitState::Closed,
-            },
-            new_state = ?new_state,
-            "Circuit breaker state changed"
+        let old_state = match old_state {
+            0 => CircuitState::Closed,
+            1 => CircuitState::Open,
+            2 => CircuitState::HalfOpen,
+            _ => CircuitState::Closed,
+        };
+        crate::telemetry::record_circuit_transition(
+            &old_state.to_string(),
+            &new_state.to_string(),
+            trigger,
         );
     }
 
     /// Get metrics
     pub fn metrics(&self) -> CircuitBreakerMetrics {
+        let state = self.current_state();
+        let retry_in = (state == CircuitState::Open).then(|| {
+            self.config
+                .reset_timeout
+                .saturating_sub(self.last_state_change_elapsed())
+        });
         CircuitBreakerMetrics {
-            state: self.current_state(),
+            state,
             failure_count: self.failure_count.load(Ordering::Relaxed),
             success_count: self.success_count.load(Ordering::Relaxed),
+            failure_threshold: self.config.failure_threshold,
+            total_failures: self.total_failures.load(Ordering::Relaxed),
+            last_error: self.last_error.lock().ok().and_then(|e| e.clone()),
+            retry_in,
         }
     }
 }
@@ -216,6 +270,13 @@ pub struct CircuitBreakerMetrics {
     pub state: CircuitState,
     pub failure_count: u32,
     pub success_count: u32,
+    pub failure_threshold: u32,
+    /// Failures since the breaker was created, across state changes.
+    pub total_failures: u32,
+    /// Most recent failure seen by the breaker, cleared on manual reset.
+    pub last_error: Option<String>,
+    /// Time left before an open circuit lets a probe request through.
+    pub retry_in: Option<Duration>,
 }
 
 impl Default for CircuitBreaker {
@@ -418,6 +479,36 @@ mod tests {
         assert!(!cb.should_attempt_reset().await);
     }
 
+    #[tokio::test]
+    async fn test_manual_reset_closes_open_circuit() {
+        let config = CircuitBreakerConfig {
+            failure_threshold: 2,
+            reset_timeout: Duration::from_secs(60),
+            ..CircuitBreakerConfig::default()
+        };
+        let cb = CircuitBreaker::new(config);
+        assert!(!cb.reset().await);
+
+        for _ in 0..2 {
+            let _: Result<i32, _> = cb
+                .call(|| async { Err::<i32, String>("connection refused".into()) })
+                .await;
+        }
+        let metrics = cb.metrics();
+        assert_eq!(metrics.state, CircuitState::Open);
+        assert_eq!(metrics.total_failures, 2);
+        assert_eq!(metrics.last_error.as_deref(), Some("connection refused"));
+        assert!(metrics.retry_in.unwrap() > Duration::from_secs(50));
+
+        assert!(cb.reset().await);
+        let metrics = cb.metrics();
+        assert_eq!(metrics.state, CircuitState::Closed);
+        assert_eq!(metrics.last_error, None);
+        assert_eq!(metrics.retry_in, None);
+        let result: Result<i32, CircuitBreakerError<String>> = cb.call(|| async { Ok(7) }).await;
+        assert_eq!(result.unwrap(), 7);
+    }
+
     #[tokio::test]
     async fn test_success_resets_failure_count_in_closed() {
         let cb = CircuitBreaker::new(fast_config());
@@ -6,10 +6,13 @@
 //! - Environment variables
 //! - Response validation
 //! - Request chaining
+//! - Recording real responses into fixtures and replaying them offline
 
 #![allow(dead_code, unused_imports, unused_variables)]
 
-use std::collections::HashMap;
+use anyhow::{Context, Result};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
 use std::sync::RwLock;
 use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -970,6 +973,367 @@ pub struct ClientStats {
     pub environments_count: usize,
 }
 
+/// Value written in place of redacted header values
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Headers that carry credentials and must never reach a fixture
+const SENSITIVE_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "set-cookie",
+    "x-api-key",
+    "x-auth-token",
+    "x-csrf-token",
+];
+
+/// Upper bound on the shape assertions generated per recording
+const MAX_SHAPE_ASSERTIONS: usize = 40;
+
+/// Replace credential-bearing header values with [`REDACTED`]
+pub fn redact_headers(headers: &HashMap<String, String>) -> BTreeMap<String, String> {
+    headers
+        .iter()
+        .map(|(key, value)| {
+            let lower = key.to_lowercase();
+            let sensitive = SENSITIVE_HEADERS.contains(&lower.as_str())
+                || lower.contains("token")
+                || lower.contains("secret");
+            let value = if sensitive { REDACTED } else { value.as_str() };
+            (key.clone(), value.to_string())
+        })
+        .collect()
+}
+
+/// A recorded request/response pair, as stored in a fixture
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RecordedInteraction {
+    /// Request method
+    pub method: String,
+    /// URL path, without query
+    pub path: String,
+    /// Query parameters; sorted, so their order never affects matching
+    #[serde(default)]
+    pub query: BTreeMap<String, String>,
+    /// Request headers, with credentials redacted
+    #[serde(default)]
+    pub request_headers: BTreeMap<String, String>,
+    /// Request body
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_body: Option<String>,
+    /// Response status
+    pub status: u16,
+    /// Response headers, with credentials redacted
+    #[serde(default)]
+    pub response_headers: BTreeMap<String, String>,
+    /// Response body
+    pub body: String,
+}
+
+impl RecordedInteraction {
+    /// Does this recording answer `method` on `path` with `query`?
+    pub fn matches(&self, method: &str, path: &str, query: &BTreeMap<String, String>) -> bool {
+        self.method == method && self.path == path && &self.query == query
+    }
+
+    /// The recorded response
+    pub fn response(&self) -> HttpResponse {
+        HttpResponse::new(self.status, self.body.clone())
+            .with_headers(self.response_headers.clone().into_iter().collect())
+    }
+
+    /// Generate a `#[test]` that loads the fixture at `fixture_path`
+    /// (relative to the generated test file) and asserts the status and the
+    /// shape of this recording's JSON body: which fields exist and their
+    /// types, not their values.
+    pub fn generate_test(&self, name: &str, fixture_path: &str) -> String {
+        let endpoint = format!("{} {}", self.method, self.path);
+        let query = serde_json::to_string(&self.query).unwrap_or_else(|_| "{}".to_string());
+
+        let mut test = String::new();
+        test.push_str("#[test]\n");
+        test.push_str(&format!("fn {}() {{\n", name));
+        test.push_str(&format!(
+            "    let fixture: serde_json::Value =\n        serde_json::from_str(include_str!({:?})).unwrap();\n",
+            fixture_path
+        ));
+        test.push_str("    let recording = fixture[\"interactions\"]\n");
+        test.push_str("        .as_array()\n        .unwrap()\n        .iter()\n");
+        test.push_str(&format!(
+            "        .find(|r| r[\"method\"] == {:?} && r[\"path\"] == {:?} && r[\"query\"] == serde_json::json!({}))\n",
+            self.method, self.path, query
+        ));
+        test.push_str(&format!(
+            "        .expect({:?});\n",
+            format!("no recording for {}", endpoint)
+        ));
+        test.push_str(&format!(
+            "    assert_eq!(recording[\"status\"], {});\n",
+            self.status
+        ));
+
+        if let Ok(body) = serde_json::from_str::<serde_json::Value>(&self.body) {
+            test.push_str(
+                "    let body: serde_json::Value =\n        serde_json::from_str(recording[\"body\"].as_str().unwrap()).unwrap();\n",
+            );
+            let mut shape = Vec::new();
+            collect_shape(&body, String::new(), &mut shape);
+            for (pointer, check, kind) in shape.into_iter().take(MAX_SHAPE_ASSERTIONS) {
+                test.push_str(&format!(
+                    "    assert!(body.pointer({:?}).is_some_and(|v| v.{}()), {:?});\n",
+                    pointer,
+                    check,
+                    format!("{} should be {}", display_pointer(&pointer), kind)
+                ));
+            }
+        }
+
+        test.push_str("}\n");
+        test
+    }
+}
+
+/// Walk a JSON value, collecting `(pointer, check, kind)` for every node.
+/// Arrays are described by their first element.
+fn collect_shape(
+    value: &serde_json::Value,
+    pointer: String,
+    out: &mut Vec<(String, &'static str, &'static str)>,
+) {
+    use serde_json::Value;
+    let (check, kind) = match value {
+        Value::Null => ("is_null", "null"),
+        Value::Bool(_) => ("is_boolean", "a boolean"),
+        Value::Number(_) => ("is_number", "a number"),
+        Value::String(_) => ("is_string", "a string"),
+        Value::Array(_) => ("is_array", "an array"),
+        Value::Object(_) => ("is_object", "an object"),
+    };
+    out.push((pointer.clone(), check, kind));
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                let escaped = key.replace('~', "~0").replace('/', "~1");
+                collect_shape(child, format!("{}/{}", pointer, escaped), out);
+            }
+        }
+        Value::Array(items) => {
+            if let Some(first) = items.first() {
+                collect_shape(first, format!("{}/0", pointer), out);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn display_pointer(pointer: &str) -> &str {
+    if pointer.is_empty() {
+        "body"
+    } else {
+        pointer
+    }
+}
+
+/// A set of recordings, saved as JSON
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Fixture {
+    /// Fixture format version
+    pub version: u32,
+    /// Recorded interactions
+    pub interactions: Vec<RecordedInteraction>,
+}
+
+impl Default for Fixture {
+    fn default() -> Self {
+        Self {
+            version: 1,
+            interactions: Vec::new(),
+        }
+    }
+}
+
+/// Record/replay harness: captures real responses into a fixture so tests
+/// can replay them offline. Replay matches on method, path and query, so it
+/// is deterministic regardless of the order requests are made in.
+pub struct ApiTesting {
+    fixture: Fixture,
+    client: reqwest::Client,
+}
+
+impl ApiTesting {
+    pub fn new() -> Self {
+        Self::with_fixture(Fixture::default())
+    }
+
+    pub fn with_fixture(fixture: Fixture) -> Self {
+        Self {
+            fixture,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Load recordings from a fixture file
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read fixture {}", path.display()))?;
+        let fixture = serde_json::from_str(&json)
+            .with_context(|| format!("Invalid fixture {}", path.display()))?;
+        Ok(Self::with_fixture(fixture))
+    }
+
+    /// Write the recordings to a fixture file
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let json = serde_json::to_string_pretty(&self.fixture)?;
+        std::fs::write(path, json)
+            .with_context(|| format!("Failed to write fixture {}", path.display()))
+    }
+
+    pub fn fixture(&self) -> &Fixture {
+        &self.fixture
+    }
+
+    /// Record a GET of `url`
+    pub async fn record(&mut self, url: &str) -> Result<&RecordedInteraction> {
+        self.record_request(&HttpRequest::get(url)).await
+    }
+
+    /// Send `request` for real and record the response. Recording the same
+    /// method, path and query again replaces the earlier recording.
+    pub async fn record_request(&mut self, request: &HttpRequest) -> Result<&RecordedInteraction> {
+        let url = request_url(request)?;
+        let method = reqwest::Method::from_bytes(request.method.as_str().as_bytes())?;
+        let mut builder = self
+            .client
+            .request(method, url.clone())
+            .timeout(std::time::Duration::from_millis(request.timeout_ms));
+        for (key, value) in &request.headers {
+            builder = builder.header(key, value);
+        }
+        if let Some(body) = &request.body {
+            builder = builder.body(body.clone());
+        }
+
+        let response = builder
+            .send()
+            .await
+            .with_context(|| format!("Failed to record {} {}", request.method.as_str(), url))?;
+        let status = response.status().as_u16();
+        let response_headers: HashMap<String, String> = response
+            .headers()
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or("").to_string()))
+            .collect();
+        let body = response
+            .text()
+            .await
+            .context("Failed to read response body")?;
+
+        let (path, query) = match_key(&url);
+        let interaction = RecordedInteraction {
+            method: request.method.as_str().to_string(),
+            path,
+            query,
+            request_headers: redact_headers(&request.headers),
+            request_body: request.body.clone(),
+            status,
+            response_headers: redact_headers(&response_headers),
+            body,
+        };
+
+        let interactions = &mut self.fixture.interactions;
+        let index = match interactions
+            .iter()
+            .position(|r| r.matches(&interaction.method, &interaction.path, &interaction.query))
+        {
+            Some(index) => {
+                interactions[index] = interaction;
+                index
+            }
+            None => {
+                interactions.push(interaction);
+                interactions.len() - 1
+            }
+        };
+        Ok(&interactions[index])
+    }
+
+    /// Find the recording that answers `request`
+    pub fn find(&self, request: &HttpRequest) -> Option<&RecordedInteraction> {
+        let url = request_url(request).ok()?;
+        let (path, query) = match_key(&url);
+        self.fixture
+            .interactions
+            .iter()
+            .find(|r| r.matches(request.method.as_str(), &path, &query))
+    }
+
+    /// Answer `request` from the recordings, without touching the network
+    pub fn replay(&self, request: &HttpRequest) -> Option<HttpResponse> {
+        self.find(request).map(RecordedInteraction::response)
+    }
+
+    /// Generate one `#[test]` per recording against the fixture at
+    /// `fixture_path`, relative to the generated test file
+    pub fn generate_tests(&self, fixture_path: &str) -> String {
+        let mut names: HashMap<String, usize> = HashMap::new();
+        self.fixture
+            .interactions
+            .iter()
+            .map(|recording| {
+                let mut name = test_name(recording);
+                let seen = names.entry(name.clone()).or_insert(0);
+                *seen += 1;
+                if *seen > 1 {
+                    name = format!("{}_{}", name, seen);
+                }
+                recording.generate_test(&name, fixture_path)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl Default for ApiTesting {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The request's URL with its query parameters merged in
+fn request_url(request: &HttpRequest) -> Result<reqwest::Url> {
+    let mut url = reqwest::Url::parse(&request.url)
+        .with_context(|| format!("Invalid URL: {}", request.url))?;
+    if !request.query_params.is_empty() {
+        url.query_pairs_mut().extend_pairs(&request.query_params);
+    }
+    Ok(url)
+}
+
+/// Path and sorted query that recordings are matched on
+fn match_key(url: &reqwest::Url) -> (String, BTreeMap<String, String>) {
+    let query = url
+        .query_pairs()
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    (url.path().to_string(), query)
+}
+
+/// Test function name for a recording, e.g. `replay_get_users_id`
+fn test_name(recording: &RecordedInteraction) -> String {
+    let mut name = format!("replay_{}", recording.method.to_lowercase());
+    for part in recording.path.split(|c: char| !c.is_ascii_alphanumeric()) {
+        if !part.is_empty() {
+            name.push('_');
+            name.push_str(&part.to_lowercase());
+        }
+    }
+    name
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1812,4 +2176,99 @@ mod tests {
 
         assert!(result.passed);
     }
+
+    /// Serve `body` as JSON to every connection, echoing nothing back
+    async fn serve_json(body: &'static str) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buf = vec![0u8; 4096];
+                let _ = stream.read(&mut buf).await;
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nSet-Cookie: session=abc\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_api_testing_record_redacts_and_replays_by_key() {
+        let base = serve_json(r#"{"users":[{"id":1,"name":"ada"}],"next":null}"#).await;
+        let mut api = ApiTesting::new();
+        let request = HttpRequest::get(&format!("{}/users?page=2&limit=10", base))
+            .with_auth_bearer("secret-token")
+            .with_header("Accept", "application/json");
+        let recorded = api.record_request(&request).await.unwrap();
+        assert_eq!(recorded.status, 200);
+        assert_eq!(recorded.path, "/users");
+        assert_eq!(recorded.request_headers["Authorization"], REDACTED);
+        assert_eq!(recorded.request_headers["Accept"], "application/json");
+        assert_eq!(recorded.response_headers["set-cookie"], REDACTED);
+
+        // Re-recording the same endpoint replaces rather than duplicates
+        api.record(&format!("{}/users?limit=10&page=2", base))
+            .await
+            .unwrap();
+        api.record(&format!("{}/health", base)).await.unwrap();
+        assert_eq!(api.fixture().interactions.len(), 2);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fixtures/users.json");
+        api.save(&path).unwrap();
+        assert!(!std::fs::read_to_string(&path)
+            .unwrap()
+            .contains("secret-token"));
+
+        // Replay is offline and ignores query order
+        let replayed = ApiTesting::load(&path).unwrap();
+        let request = HttpRequest::get("http://offline.invalid/users")
+            .with_query("limit", "10")
+            .with_query("page", "2");
+        let response = replayed.replay(&request).unwrap();
+        assert!(response.is_success());
+        assert_eq!(response.json_path("users.0.name").unwrap(), "ada");
+        assert!(replayed
+            .replay(&HttpRequest::get(
+                "http://offline.invalid/users?page=3&limit=10"
+            ))
+            .is_none());
+        assert!(replayed
+            .replay(&HttpRequest::post("http://offline.invalid/health"))
+            .is_none());
+    }
+
+    #[test]
+    fn test_api_testing_generates_shape_test() {
+        let api = ApiTesting::with_fixture(Fixture {
+            version: 1,
+            interactions: vec![RecordedInteraction {
+                method: "GET".to_string(),
+                path: "/users/1".to_string(),
+                query: BTreeMap::from([("expand".to_string(), "roles".to_string())]),
+                request_headers: BTreeMap::new(),
+                request_body: None,
+                status: 200,
+                response_headers: BTreeMap::new(),
+                body: r#"{"id":1,"a/b":true,"roles":[{"name":"admin"}],"manager":null}"#
+                    .to_string(),
+            }],
+        });
+        let code = api.generate_tests("fixtures/users.json");
+        assert!(code.starts_with("#[test]\nfn replay_get_users_1() {"));
+        assert!(code.contains(r#"include_str!("fixtures/users.json")"#));
+        assert!(code.contains(r#"r["query"] == serde_json::json!({"expand":"roles"})"#));
+        assert!(code.contains(r#"assert_eq!(recording["status"], 200);"#));
+        assert!(code.contains(r#"body.pointer("/id").is_some_and(|v| v.is_number())"#));
+        assert!(code.contains(r#"body.pointer("/a~1b").is_some_and(|v| v.is_boolean())"#));
+        assert!(code.contains(r#"body.pointer("/roles/0/name").is_some_and(|v| v.is_string())"#));
+        assert!(code.contains(r#"body.pointer("/manager").is_some_and(|v| v.is_null())"#));
+        // Shape only: recorded values are not asserted
+        assert!(!code.contains("admin"));
+    }
 }
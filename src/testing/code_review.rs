@@ -9,7 +9,10 @@
 
 #![allow(dead_code, unused_imports, unused_variables)]
 
-use std::collections::HashMap;
+use crate::analysis::analyzer::{panic_risk, PanicKind};
+use crate::analysis::tech_debt::{DebtItem, DebtSeverity, DebtType};
+use crate::safety::threat_modeling::{self, StrideAnalyzer};
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::RwLock;
 use std::time::{Instant, SystemTime, UNIX_EPOCH};
@@ -407,6 +410,8 @@ pub struct StyleRule {
     pub severity: Severity,
     /// Category
     pub category: ReviewCategory,
+    /// How likely a hit is a real problem rather than a nitpick (0.0-1.0)
+    pub confidence: f32,
 }
 
 /// A style violation found in code
@@ -449,6 +454,7 @@ impl StyleChecker {
             languages: vec!["rust".to_string()],
             severity: Severity::Warning,
             category: ReviewCategory::ErrorHandling,
+            confidence: 0.6,
         });
         self.rules.push(StyleRule {
             id: "rust/expect".to_string(),
@@ -457,6 +463,7 @@ impl StyleChecker {
             languages: vec!["rust".to_string()],
             severity: Severity::Info,
             category: ReviewCategory::ErrorHandling,
+            confidence: 0.3,
         });
         self.rules.push(StyleRule {
             id: "rust/clone".to_string(),
@@ -465,6 +472,7 @@ impl StyleChecker {
             languages: vec!["rust".to_string()],
             severity: Severity::Info,
             category: ReviewCategory::Performance,
+            confidence: 0.3,
         });
         self.rules.push(StyleRule {
             id: "rust/todo".to_string(),
@@ -473,6 +481,7 @@ impl StyleChecker {
             languages: vec!["rust".to_string()],
             severity: Severity::Warning,
             category: ReviewCategory::BestPractice,
+            confidence: 0.8,
         });
         self.rules.push(StyleRule {
             id: "rust/unsafe".to_string(),
@@ -481,6 +490,7 @@ impl StyleChecker {
             languages: vec!["rust".to_string()],
             severity: Severity::Warning,
             category: ReviewCategory::Security,
+            confidence: 0.7,
         });
         self.rules.push(StyleRule {
             id: "rust/panic".to_string(),
@@ -489,6 +499,7 @@ impl StyleChecker {
             languages: vec!["rust".to_string()],
             severity: Severity::Warning,
             category: ReviewCategory::ErrorHandling,
+            confidence: 0.6,
         });
         self
     }
@@ -498,6 +509,11 @@ impl StyleChecker {
         self.rules.push(rule);
     }
 
+    /// Look up a registered rule
+    pub fn rule(&self, id: &str) -> Option<&StyleRule> {
+        self.rules.iter().find(|r| r.id == id)
+    }
+
     /// Check a file for style violations
     pub fn check_file(
         &self,
@@ -874,6 +890,35 @@ impl ComplexityAnalyzer {
         functions
     }
 
+    /// Express a complex function as technical debt, graded by how far it
+    /// exceeds the thresholds
+    pub fn debt_item(&self, func: &FunctionComplexity) -> DebtItem {
+        let overshoot = [
+            func.metrics.cyclomatic as f64 / self.cyclomatic_threshold.max(1) as f64,
+            func.metrics.cognitive as f64 / self.cognitive_threshold.max(1) as f64,
+            func.metrics.max_nesting as f64 / self.nesting_threshold.max(1) as f64,
+        ]
+        .into_iter()
+        .fold(0.0, f64::max);
+        let severity = if overshoot >= 2.5 {
+            DebtSeverity::Critical
+        } else if overshoot >= 1.5 {
+            DebtSeverity::High
+        } else {
+            DebtSeverity::Medium
+        };
+        DebtItem::new(
+            DebtType::Complexity,
+            format!("Function `{}` has high complexity", func.name),
+        )
+        .with_severity(severity)
+        .with_file(func.file.clone())
+        .with_description(format!(
+            "cyclomatic: {}, cognitive: {}, nesting: {}",
+            func.metrics.cyclomatic, func.metrics.cognitive, func.metrics.max_nesting
+        ))
+    }
+
     /// Get complexity report for functions in a diff
     pub fn analyze_diff_complexity(
         &self,
@@ -938,6 +983,8 @@ pub struct ReviewComment {
     pub suggestion: Option<String>,
     /// Whether this requires action before merge
     pub blocking: bool,
+    /// How likely this is a real problem rather than a nitpick (0.0-1.0)
+    pub confidence: f32,
 }
 
 impl ReviewComment {
@@ -959,6 +1006,7 @@ impl ReviewComment {
             severity: Severity::Info,
             suggestion: None,
             blocking: false,
+            confidence: 1.0,
         }
     }
 
@@ -979,6 +1027,69 @@ impl ReviewComment {
         self.suggestion = Some(suggestion);
         self
     }
+
+    pub fn with_confidence(mut self, confidence: f32) -> Self {
+        self.confidence = confidence.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Render as a line-anchored comment, or None for file-level remarks
+    pub fn inline(&self) -> Option<InlineComment> {
+        if self.line == 0 {
+            return None;
+        }
+        Some(InlineComment {
+            path: self.file.display().to_string(),
+            line: self.line,
+            kind: CommentKind::from(&self.category),
+            category: self.category.as_str().to_string(),
+            severity: self.severity.as_str(),
+            confidence: self.confidence,
+            body: self.body.clone(),
+            suggestion: self.suggestion.clone().filter(|s| !s.is_empty()),
+        })
+    }
+}
+
+/// Broad kind of an inline comment, as shown in a PR-style review
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CommentKind {
+    Bug,
+    Style,
+    Security,
+    Perf,
+}
+
+impl From<&ReviewCategory> for CommentKind {
+    fn from(category: &ReviewCategory) -> Self {
+        match category {
+            ReviewCategory::Logic | ReviewCategory::ErrorHandling | ReviewCategory::Testing => {
+                Self::Bug
+            }
+            ReviewCategory::Security => Self::Security,
+            ReviewCategory::Performance => Self::Perf,
+            _ => Self::Style,
+        }
+    }
+}
+
+/// A review comment anchored to a line added by the diff, in the shape
+/// editors and trackers render inline
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct InlineComment {
+    /// File path, as named in the diff
+    pub path: String,
+    /// Line in the new version of the file
+    pub line: u32,
+    pub kind: CommentKind,
+    /// Finer-grained category
+    pub category: String,
+    pub severity: &'static str,
+    pub confidence: f32,
+    pub body: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
 }
 
 /// Review result for a PR or diff
@@ -994,6 +1105,24 @@ pub struct ReviewResult {
     pub duration_ms: u64,
 }
 
+impl ReviewResult {
+    /// Line-anchored comments at or above `min_confidence`, most severe first
+    pub fn inline_comments(&self, min_confidence: f32) -> Vec<InlineComment> {
+        let mut comments: Vec<&ReviewComment> = self
+            .comments
+            .iter()
+            .filter(|c| c.confidence >= min_confidence)
+            .collect();
+        comments.sort_by(|a, b| {
+            b.severity
+                .cmp(&a.severity)
+                .then_with(|| a.file.cmp(&b.file))
+                .then_with(|| a.line.cmp(&b.line))
+        });
+        comments.into_iter().filter_map(|c| c.inline()).collect()
+    }
+}
+
 /// Overall review verdict
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ReviewVerdict {
@@ -1024,6 +1153,8 @@ pub struct CodeReviewAssistant {
     style_checker: StyleChecker,
     /// Complexity analyzer
     complexity_analyzer: ComplexityAnalyzer,
+    /// Security threat patterns
+    stride_analyzer: StrideAnalyzer,
     /// Cache of file contents
     file_cache: RwLock<HashMap<PathBuf, String>>,
     /// Review history for learning
@@ -1036,6 +1167,7 @@ impl CodeReviewAssistant {
             diff_analyzer: DiffAnalyzer::new(),
             style_checker: StyleChecker::new().with_rust_rules(),
             complexity_analyzer: ComplexityAnalyzer::new(),
+            stride_analyzer: StrideAnalyzer::new(),
             file_cache: RwLock::new(HashMap::new()),
             review_history: RwLock::new(Vec::new()),
         }
@@ -1067,33 +1199,132 @@ impl CodeReviewAssistant {
         // Style check
         let style_violations = self.style_checker.check_diff(&files, &file_contents);
         for violation in style_violations {
+            let (category, confidence) = self
+                .style_checker
+                .rule(&violation.rule_id)
+                .map(|r| (r.category.clone(), r.confidence))
+                .unwrap_or((ReviewCategory::Style, 0.5));
             comments.push(
                 ReviewComment::new(violation.file, violation.line, violation.message)
-                    .with_category(ReviewCategory::Style)
+                    .with_category(category)
                     .with_severity(violation.severity)
-                    .with_suggestion(violation.suggestion.unwrap_or_default()),
+                    .with_suggestion(violation.suggestion.unwrap_or_default())
+                    .with_confidence(confidence),
             );
         }
 
-        // Complexity check
+        // Complexity check, graded as technical debt and anchored to the
+        // first line the diff added to the function
         let complex_functions = self
             .complexity_analyzer
             .analyze_diff_complexity(&files, &file_contents);
         for func in complex_functions {
-            let message = format!(
-                "Function `{}` has high complexity (cyclomatic: {}, cognitive: {}, nesting: {})",
-                func.name,
-                func.metrics.cyclomatic,
-                func.metrics.cognitive,
-                func.metrics.max_nesting
-            );
+            let Some(line) = first_added_line_from(&files, &func.file, func.line) else {
+                continue;
+            };
+            let debt = self.complexity_analyzer.debt_item(&func);
+            let (severity, confidence) = match debt.severity {
+                DebtSeverity::Critical => (Severity::Error, 0.9),
+                DebtSeverity::High => (Severity::Warning, 0.7),
+                DebtSeverity::Medium | DebtSeverity::Low => (Severity::Warning, 0.5),
+            };
+            let message = format!("{} ({})", debt.title, debt.description);
             comments.push(
-                ReviewComment::new(func.file, func.line, message)
+                ReviewComment::new(func.file, line, message)
                     .with_category(ReviewCategory::Complexity)
-                    .with_severity(Severity::Warning),
+                    .with_severity(severity)
+                    .with_confidence(confidence),
             );
         }
 
+        // Security check on added lines. Threat patterns are keyword
+        // heuristics, so findings carry low confidence.
+        for file in &files {
+            let (Some(path), Some(language)) = (&file.new_path, &file.language) else {
+                continue;
+            };
+            if language == "markdown" {
+                continue;
+            }
+            let added = added_lines(file);
+            let text: Vec<&str> = added.iter().map(|(_, content)| *content).collect();
+            for threat in self.stride_analyzer.analyze(&text.join("\n"), path) {
+                let Some((line, _)) = threat
+                    .source_line
+                    .and_then(|l| added.get(l.saturating_sub(1)))
+                else {
+                    continue;
+                };
+                let severity = match threat.severity {
+                    threat_modeling::Severity::Low => Severity::Info,
+                    threat_modeling::Severity::Medium => Severity::Warning,
+                    threat_modeling::Severity::High => Severity::Error,
+                    threat_modeling::Severity::Critical => Severity::Critical,
+                };
+                let mut comment = ReviewComment::new(
+                    path.clone(),
+                    *line,
+                    format!("{} ({})", threat.description, threat.category),
+                )
+                .with_category(ReviewCategory::Security)
+                .with_severity(severity)
+                .with_confidence(0.4);
+                if let Some(mitigation) = threat.category.typical_mitigations().first() {
+                    comment = comment.with_suggestion(mitigation.to_string());
+                }
+                comments.push(comment);
+            }
+        }
+
+        // Panic risks in changed Rust files. Unwrap and expect are left to
+        // the style rules; indexing and arithmetic are often guarded by
+        // earlier checks, so findings carry low confidence.
+        let rust_files: Vec<(PathBuf, String)> = files
+            .iter()
+            .filter(|f| f.language.as_deref() == Some("rust"))
+            .filter_map(|f| {
+                let path = f.new_path.as_ref()?;
+                Some((path.clone(), file_contents.get(path)?.clone()))
+            })
+            .collect();
+        for risk in panic_risk(&rust_files).production() {
+            let confidence = match risk.kind {
+                PanicKind::Index => 0.4,
+                PanicKind::Arithmetic => 0.25,
+                PanicKind::Unwrap | PanicKind::Expect => continue,
+            };
+            let severity = if risk.public || risk.callers > 0 {
+                Severity::Warning
+            } else {
+                Severity::Info
+            };
+            let what = match risk.kind {
+                PanicKind::Index => "Indexing panics when out of bounds",
+                _ => "Integer arithmetic can overflow or divide by zero",
+            };
+            let message = match &risk.function {
+                Some(function) => format!("{} (in `{}`)", what, function),
+                None => what.to_string(),
+            };
+            comments.push(
+                ReviewComment::new(risk.file.clone(), risk.line as u32, message)
+                    .with_category(ReviewCategory::ErrorHandling)
+                    .with_severity(severity)
+                    .with_suggestion(risk.suggestion.clone())
+                    .with_confidence(confidence),
+            );
+        }
+
+        // Never comment on lines the diff did not add
+        let added: HashMap<&PathBuf, HashSet<u32>> = files
+            .iter()
+            .filter_map(|f| {
+                let lines = added_lines(f).into_iter().map(|(line, _)| line).collect();
+                Some((f.new_path.as_ref()?, lines))
+            })
+            .collect();
+        comments.retain(|c| c.line == 0 || added.get(&c.file).is_some_and(|l| l.contains(&c.line)));
+
         // Large PR warning
         if diff_stats.lines_added + diff_stats.lines_removed > 500 {
             let message = format!(
@@ -1270,6 +1501,30 @@ impl Default for CodeReviewAssistant {
     }
 }
 
+/// Lines a file diff adds, with their line numbers in the new file
+fn added_lines(file: &FileDiff) -> Vec<(u32, &str)> {
+    file.hunks
+        .iter()
+        .flat_map(|h| h.lines.iter())
+        .filter(|l| l.change_type == ChangeType::Added)
+        .filter_map(|l| Some((l.new_line?, l.content.as_str())))
+        .collect()
+}
+
+/// First added line at or after `line` in the hunk of `path` containing it
+fn first_added_line_from(files: &[FileDiff], path: &Path, line: u32) -> Option<u32> {
+    let file = files.iter().find(|f| f.new_path.as_deref() == Some(path))?;
+    let hunk = file
+        .hunks
+        .iter()
+        .find(|h| (h.new_start..h.new_start + h.new_count).contains(&line))?;
+    hunk.lines
+        .iter()
+        .filter(|l| l.change_type == ChangeType::Added)
+        .filter_map(|l| l.new_line)
+        .find(|&l| l >= line)
+}
+
 /// Statistics from review history
 #[derive(Debug, Clone)]
 pub struct HistoryStats {
@@ -2041,6 +2296,7 @@ diff --git a/src/b.rs b/src/b.rs
             languages: vec!["rust".to_string(), "python".to_string()],
             severity: Severity::Warning,
             category: ReviewCategory::BestPractice,
+            confidence: 0.5,
         };
 
         assert_eq!(rule.id, "custom/rule");
@@ -2058,6 +2314,7 @@ diff --git a/src/b.rs b/src/b.rs
             languages: vec![],
             severity: Severity::Info,
             category: ReviewCategory::Style,
+            confidence: 0.5,
         };
         let cloned = rule.clone();
         assert_eq!(cloned.id, rule.id);
@@ -2113,6 +2370,7 @@ diff --git a/src/b.rs b/src/b.rs
             languages: vec![],
             severity: Severity::Info,
             category: ReviewCategory::Custom("custom".to_string()),
+            confidence: 0.5,
         });
 
         // Rule added successfully (no way to query count, but it shouldn't panic)
@@ -2664,4 +2922,102 @@ diff --git a/src/b.rs b/src/b.rs
         assert_eq!(stats.files_modified, 1);
         assert_eq!(stats.languages.get("rust"), Some(&4));
     }
+
+    #[test]
+    fn test_inline_comments_anchor_to_added_lines() {
+        let assistant = CodeReviewAssistant::new();
+        let diff = r#"diff --git a/src/db.rs b/src/db.rs
+--- a/src/db.rs
++++ b/src/db.rs
+@@ -1,3 +1,4 @@
+ fn lookup(password: &str) {
++    let rows = db.execute_sql(&format!("select * from users where name = {}", name));
+     let x = cache.unwrap();
+ }
+"#;
+        assistant.cache_file(
+            PathBuf::from("src/db.rs"),
+            "fn lookup(password: &str) {\n    let rows = db.execute_sql(&format!(\"select * from users where name = {}\", name));\n    let x = cache.unwrap();\n}\n".to_string(),
+        );
+        let result = assistant.review_diff(diff);
+
+        // The unchanged unwrap and the context line mentioning a password
+        // get no comments
+        assert!(result.comments.iter().all(|c| c.line == 2));
+        let inline = result.inline_comments(0.0);
+        assert!(!inline.is_empty());
+        let sql = inline
+            .iter()
+            .find(|c| c.body.contains("SQL Injection"))
+            .unwrap();
+        assert_eq!(sql.kind, CommentKind::Security);
+        assert_eq!(sql.path, "src/db.rs");
+        assert!(sql.confidence < 0.5);
+        assert!(sql.suggestion.is_some());
+        assert!(result.inline_comments(0.5).is_empty());
+
+        let json = serde_json::to_value(sql).unwrap();
+        assert_eq!(json["kind"], "security");
+        assert_eq!(json["line"], 2);
+    }
+
+    #[test]
+    fn test_panic_risks_on_added_lines() {
+        let assistant = CodeReviewAssistant::new();
+        let diff = r#"diff --git a/src/stats.rs b/src/stats.rs
+--- a/src/stats.rs
++++ b/src/stats.rs
+@@ -1,3 +1,5 @@
+ pub fn mean(values: &[u64], count: u64) -> u64 {
+     let first = values[0];
++    let last = values[values.len() - 1];
++    (first + last) / count
+ }
+"#;
+        assistant.cache_file(
+            PathBuf::from("src/stats.rs"),
+            "pub fn mean(values: &[u64], count: u64) -> u64 {\n    let first = values[0];\n    let last = values[values.len() - 1];\n    (first + last) / count\n}\n".to_string(),
+        );
+        let result = assistant.review_diff(diff);
+
+        let risks: Vec<_> = result
+            .comments
+            .iter()
+            .filter(|c| c.category == ReviewCategory::ErrorHandling)
+            .collect();
+        // Line 2 indexes too, but the diff did not add it
+        let lines: Vec<u32> = risks.iter().map(|c| c.line).collect();
+        assert_eq!(lines, vec![3, 3, 4]);
+        assert!(risks.iter().all(|c| c.severity == Severity::Warning));
+        assert!(risks.iter().all(|c| c.confidence < 0.5 && !c.blocking));
+        assert!(risks[0].body.contains("`mean`"));
+        assert!(risks.iter().any(|c| c
+            .suggestion
+            .as_deref()
+            .is_some_and(|s| s.contains("checked_"))));
+    }
+
+    #[test]
+    fn test_complexity_graded_as_debt() {
+        let analyzer = ComplexityAnalyzer::new();
+        let func = |cyclomatic| FunctionComplexity {
+            name: "f".to_string(),
+            file: PathBuf::from("src/lib.rs"),
+            line: 1,
+            metrics: ComplexityMetrics {
+                cyclomatic,
+                ..Default::default()
+            },
+            is_complex: true,
+        };
+        let debt = analyzer.debt_item(&func(12));
+        assert_eq!(debt.debt_type, DebtType::Complexity);
+        assert_eq!(debt.severity, DebtSeverity::Medium);
+        assert_eq!(debt.files, vec![PathBuf::from("src/lib.rs")]);
+        assert_eq!(analyzer.debt_item(&func(16)).severity, DebtSeverity::High);
+        assert_eq!(
+            analyzer.debt_item(&func(30)).severity,
+            DebtSeverity::Critical
+        );
+    }
 }
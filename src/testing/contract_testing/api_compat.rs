@@ -210,6 +210,10 @@ pub enum CompatibilityChangeType {
     TypeChanged,
     ResponseChanged,
     Deprecated,
+    /// A response field was added
+    FieldAdded,
+    /// A response field the consumer reads was removed
+    FieldRemoved,
 }
 
 impl CompatibilityChangeType {
@@ -220,6 +224,7 @@ impl CompatibilityChangeType {
                 | CompatibilityChangeType::RequiredParameterAdded
                 | CompatibilityChangeType::ParameterRemoved
                 | CompatibilityChangeType::TypeChanged
+                | CompatibilityChangeType::FieldRemoved
         )
     }
 }
@@ -502,6 +507,8 @@ mod tests {
         assert!(!CompatibilityChangeType::ParameterAdded.is_breaking());
         assert!(!CompatibilityChangeType::Deprecated.is_breaking());
         assert!(!CompatibilityChangeType::ResponseChanged.is_breaking());
+        assert!(CompatibilityChangeType::FieldRemoved.is_breaking());
+        assert!(!CompatibilityChangeType::FieldAdded.is_breaking());
     }
 
     #[test]
@@ -0,0 +1,911 @@
+//! Contracts Generated from Source
+//!
+//! Infers a consumer-driven contract by reading a consumer's API client
+//! code, and verifies it by reading the provider's route handlers. No
+//! service has to run: both sides are scanned statically, so the agent can
+//! check a contract right after editing an API.
+//!
+//! The contract is plain JSON:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "consumer": "web",
+//!   "provider": "api",
+//!   "provider_path": "services/api",
+//!   "interactions": [
+//!     {
+//!       "method": "Get",
+//!       "path": "/users/{}",
+//!       "source": "src/client.rs:42",
+//!       "response": {
+//!         "id": { "type": "integer" },
+//!         "email": { "type": "string", "optional": true }
+//!       }
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Path parameters are written `{}` whatever the framework calls them, and
+//! so are literal numeric IDs in client calls.
+//! `response` lists the fields the consumer reads; it is empty when the
+//! response type could not be resolved, in which case only the endpoint is
+//! checked. Field types are inferred from Rust structs; calls from
+//! JavaScript or Python clients contribute endpoints only.
+
+use super::*;
+use crate::testing::contract_testing::api_compat::{CompatibilityChange, CompatibilityChangeType};
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// Current contract format version
+pub const CONTRACT_FORMAT_VERSION: u32 = 1;
+
+/// Directories never scanned for API code
+const SKIPPED_DIRS: &[&str] = &[
+    "target",
+    "node_modules",
+    ".git",
+    "dist",
+    "build",
+    "vendor",
+    "__pycache__",
+    ".venv",
+];
+
+/// Lines after a client call searched for the response type it decodes
+const RESPONSE_TYPE_WINDOW: usize = 6;
+
+/// JSON type of a response field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldType {
+    String,
+    Integer,
+    Number,
+    Boolean,
+    Array,
+    Object,
+    /// Could not be inferred; never reported as a type change
+    Unknown,
+}
+
+/// A response field the consumer reads
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldSpec {
+    #[serde(rename = "type")]
+    pub field_type: FieldType,
+    /// The consumer copes with the field being absent
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub optional: bool,
+}
+
+/// One call the consumer makes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExpectedInteraction {
+    pub method: HttpMethod,
+    /// Normalized path, parameters written `{}`
+    pub path: String,
+    /// Where the call is made (`file:line`)
+    pub source: String,
+    #[serde(default)]
+    pub response: BTreeMap<String, FieldSpec>,
+}
+
+/// A consumer-driven contract inferred from source
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourceContract {
+    pub version: u32,
+    pub consumer: String,
+    pub provider: String,
+    /// Provider directory to verify against, as given at generation
+    pub provider_path: String,
+    pub interactions: Vec<ExpectedInteraction>,
+}
+
+impl SourceContract {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        let contract: Self = serde_json::from_str(json).context("Invalid contract JSON")?;
+        if contract.version > CONTRACT_FORMAT_VERSION {
+            anyhow::bail!(
+                "Contract format version {} is newer than supported ({})",
+                contract.version,
+                CONTRACT_FORMAT_VERSION
+            );
+        }
+        Ok(contract)
+    }
+}
+
+/// An endpoint the provider serves
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProviderEndpoint {
+    pub method: HttpMethod,
+    pub path: String,
+    pub source: String,
+    /// Response fields, when the handler's response type was resolved
+    pub response: Option<BTreeMap<String, FieldSpec>>,
+}
+
+/// Outcome of checking a contract against a provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContractReport {
+    pub consumer: String,
+    pub provider: String,
+    pub changes: Vec<CompatibilityChange>,
+}
+
+impl ContractReport {
+    /// Changes that break the consumer: removed endpoints or fields, changed types
+    pub fn breaking(&self) -> Vec<&CompatibilityChange> {
+        self.changes
+            .iter()
+            .filter(|c| c.change_type.is_breaking())
+            .collect()
+    }
+
+    /// Changes the consumer tolerates, such as fields it does not read
+    pub fn additive(&self) -> Vec<&CompatibilityChange> {
+        self.changes
+            .iter()
+            .filter(|c| !c.change_type.is_breaking())
+            .collect()
+    }
+
+    pub fn is_satisfied(&self) -> bool {
+        self.breaking().is_empty()
+    }
+}
+
+/// Infer the contract `consumer` expects from `provider` by scanning the
+/// consumer's client code for API calls and the response types they decode
+pub fn contract_generate(consumer: &Path, provider: &Path) -> Result<SourceContract> {
+    let files = read_sources(consumer)?;
+    let structs = parse_structs(&files);
+    let mut interactions: Vec<ExpectedInteraction> = Vec::new();
+
+    for file in &files {
+        let lines: Vec<&str> = file.content.lines().collect();
+        for (idx, line) in lines.iter().enumerate() {
+            let Some((method, raw_path)) = client_call(file.lang, line, &lines[idx..]) else {
+                continue;
+            };
+            let Some(path) = api_path(&raw_path) else {
+                continue;
+            };
+            let response = if file.lang == Lang::Rust {
+                response_type_near(&lines, idx)
+                    .and_then(|name| structs.get(&name))
+                    .cloned()
+                    .unwrap_or_default()
+            } else {
+                BTreeMap::new()
+            };
+            let source = format!("{}:{}", file.rel_path, idx + 1);
+            match interactions
+                .iter_mut()
+                .find(|i| i.method == method && i.path == path)
+            {
+                // Several call sites: the contract needs every field any of them reads
+                Some(existing) => {
+                    for (name, spec) in response {
+                        existing.response.entry(name).or_insert(spec);
+                    }
+                }
+                None => interactions.push(ExpectedInteraction {
+                    method,
+                    path,
+                    source,
+                    response,
+                }),
+            }
+        }
+    }
+
+    Ok(SourceContract {
+        version: CONTRACT_FORMAT_VERSION,
+        consumer: service_name(consumer),
+        provider: service_name(provider),
+        provider_path: provider.display().to_string(),
+        interactions,
+    })
+}
+
+/// Check that the provider at `provider` still serves everything `contract`
+/// expects. Missing endpoints or fields and changed field types are
+/// breaking; fields the consumer does not read are reported as additive.
+pub fn contract_verify(contract: &SourceContract, provider: &Path) -> Result<ContractReport> {
+    let endpoints = provider_endpoints(provider)?;
+    let mut changes = Vec::new();
+
+    for expected in &contract.interactions {
+        let label = format!("{} {}", expected.method.as_str(), expected.path);
+        let Some(endpoint) = endpoints
+            .iter()
+            .find(|e| e.method == expected.method && e.path == expected.path)
+        else {
+            changes.push(CompatibilityChange::new(
+                CompatibilityChangeType::EndpointRemoved,
+                &expected.path,
+                format!(
+                    "{} is no longer served (called from {})",
+                    label, expected.source
+                ),
+            ));
+            continue;
+        };
+        let Some(actual) = &endpoint.response else {
+            continue;
+        };
+        for (name, spec) in &expected.response {
+            let field_path = format!("{}.{}", expected.path, name);
+            match actual.get(name) {
+                None if spec.optional => changes.push(CompatibilityChange::new(
+                    CompatibilityChangeType::ResponseChanged,
+                    &field_path,
+                    format!("{}: optional field '{}' is no longer returned", label, name),
+                )),
+                None => changes.push(CompatibilityChange::new(
+                    CompatibilityChangeType::FieldRemoved,
+                    &field_path,
+                    format!("{}: field '{}' was removed", label, name),
+                )),
+                Some(found)
+                    if found.field_type != spec.field_type
+                        && found.field_type != FieldType::Unknown
+                        && spec.field_type != FieldType::Unknown =>
+                {
+                    changes.push(CompatibilityChange::new(
+                        CompatibilityChangeType::TypeChanged,
+                        &field_path,
+                        format!(
+                            "{}: field '{}' changed from {:?} to {:?}",
+                            label, name, spec.field_type, found.field_type
+                        ),
+                    ))
+                }
+                Some(found) if found.optional && !spec.optional => {
+                    changes.push(CompatibilityChange::new(
+                        CompatibilityChangeType::TypeChanged,
+                        &field_path,
+                        format!("{}: field '{}' became optional", label, name),
+                    ))
+                }
+                Some(_) => {}
+            }
+        }
+        for name in actual
+            .keys()
+            .filter(|n| !expected.response.contains_key(*n))
+        {
+            changes.push(CompatibilityChange::new(
+                CompatibilityChangeType::FieldAdded,
+                format!("{}.{}", expected.path, name),
+                format!("{}: field '{}' is returned but not read", label, name),
+            ));
+        }
+    }
+
+    Ok(ContractReport {
+        consumer: contract.consumer.clone(),
+        provider: contract.provider.clone(),
+        changes,
+    })
+}
+
+/// Endpoints served by the code under `provider`, with response fields
+/// where the handler returns `Json<T>` of a known struct
+pub fn provider_endpoints(provider: &Path) -> Result<Vec<ProviderEndpoint>> {
+    let files = read_sources(provider)?;
+    let structs = parse_structs(&files);
+    let returns = handler_return_types(&files);
+    let response_of = |handler: Option<&str>| {
+        handler
+            .and_then(|h| returns.get(h))
+            .and_then(|t| structs.get(t))
+            .cloned()
+    };
+
+    let mut endpoints = Vec::new();
+    for file in &files {
+        let lines: Vec<&str> = file.content.lines().collect();
+        for (idx, line) in lines.iter().enumerate() {
+            for (method, raw_path, handler) in routes(file.lang, line, &lines[idx..]) {
+                let Some(path) = api_path(&raw_path) else {
+                    continue;
+                };
+                endpoints.push(ProviderEndpoint {
+                    method,
+                    path,
+                    source: format!("{}:{}", file.rel_path, idx + 1),
+                    response: response_of(handler.as_deref()),
+                });
+            }
+        }
+    }
+    Ok(endpoints)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Lang {
+    Rust,
+    JavaScript,
+    Python,
+}
+
+struct SourceFile {
+    rel_path: String,
+    lang: Lang,
+    content: String,
+}
+
+fn read_sources(root: &Path) -> Result<Vec<SourceFile>> {
+    if !root.is_dir() {
+        anyhow::bail!("Not a directory: {}", root.display());
+    }
+    let mut files = Vec::new();
+    let walker = WalkDir::new(root).sort_by_file_name().into_iter();
+    for entry in walker.filter_entry(|e| {
+        e.depth() == 0
+            || !e.file_type().is_dir()
+            || !SKIPPED_DIRS.contains(&e.file_name().to_string_lossy().as_ref())
+    }) {
+        let Ok(entry) = entry else { continue };
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let lang = match entry.path().extension().and_then(|e| e.to_str()) {
+            Some("rs") => Lang::Rust,
+            Some("js" | "jsx" | "ts" | "tsx" | "mjs") => Lang::JavaScript,
+            Some("py") => Lang::Python,
+            _ => continue,
+        };
+        let Ok(content) = std::fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        let rel_path = entry
+            .path()
+            .strip_prefix(root)
+            .unwrap_or(entry.path())
+            .display()
+            .to_string();
+        files.push(SourceFile {
+            rel_path,
+            lang,
+            content,
+        });
+    }
+    Ok(files)
+}
+
+fn service_name(path: &Path) -> String {
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "service".to_string())
+}
+
+fn parse_method(name: &str) -> Option<HttpMethod> {
+    match name.to_ascii_lowercase().as_str() {
+        "get" => Some(HttpMethod::Get),
+        "post" => Some(HttpMethod::Post),
+        "put" => Some(HttpMethod::Put),
+        "patch" => Some(HttpMethod::Patch),
+        "delete" => Some(HttpMethod::Delete),
+        _ => None,
+    }
+}
+
+static RUST_CLIENT_CALL: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"\.(get|post|put|patch|delete)\s*\(\s*&?(?:format!\s*\(\s*)?"([^"]+)""#).unwrap()
+});
+static JS_FETCH: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"\bfetch\s*\(\s*[`'"]([^`'"]+)[`'"]"#).unwrap());
+static JS_FETCH_METHOD: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"method\s*:\s*['"](\w+)['"]"#).unwrap());
+static JS_AXIOS: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"\baxios\.(get|post|put|patch|delete)\s*\(\s*[`'"]([^`'"]+)[`'"]"#).unwrap()
+});
+static PY_CLIENT_CALL: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"\b(?:requests|httpx|session|client)\.(get|post|put|patch|delete)\s*\(\s*f?["']([^"']+)["']"#)
+        .unwrap()
+});
+
+/// The method and raw path of an API call on `line`, if it makes one.
+/// `rest` starts at `line` and gives the call's following lines.
+fn client_call(lang: Lang, line: &str, rest: &[&str]) -> Option<(HttpMethod, String)> {
+    let captures = match lang {
+        Lang::Rust => RUST_CLIENT_CALL.captures(line),
+        Lang::Python => PY_CLIENT_CALL.captures(line),
+        Lang::JavaScript => {
+            if let Some(c) = JS_FETCH.captures(line) {
+                let method = rest
+                    .iter()
+                    .take(4)
+                    .find_map(|l| JS_FETCH_METHOD.captures(l))
+                    .and_then(|m| parse_method(&m[1]))
+                    .unwrap_or(HttpMethod::Get);
+                return Some((method, c[1].to_string()));
+            }
+            JS_AXIOS.captures(line)
+        }
+    }?;
+    Some((parse_method(&captures[1])?, captures[2].to_string()))
+}
+
+static JSON_TURBOFISH: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\.json\s*::\s*<\s*(?:Vec\s*<\s*)?(\w+)").unwrap());
+static TYPED_LET: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\blet\s+(?:mut\s+)?\w+\s*:\s*(?:Vec\s*<\s*)?(\w+)").unwrap());
+
+/// The type a Rust client call at `idx` decodes its response into
+fn response_type_near(lines: &[&str], idx: usize) -> Option<String> {
+    let end = (idx + RESPONSE_TYPE_WINDOW).min(lines.len());
+    let window = &lines[idx..end];
+    if let Some(name) = window
+        .iter()
+        .find_map(|l| JSON_TURBOFISH.captures(l))
+        .map(|c| c[1].to_string())
+    {
+        return Some(name);
+    }
+    // `let user: User = client.get(..)` puts the type on or just before the call
+    let start = idx.saturating_sub(1);
+    lines[start..=idx]
+        .iter()
+        .rev()
+        .find_map(|l| TYPED_LET.captures(l))
+        .map(|c| c[1].to_string())
+}
+
+static AXUM_ROUTE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"\.route\s*\(\s*"([^"]+)"\s*,(.*)"#).unwrap());
+static AXUM_HANDLER: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\b(get|post|put|patch|delete)\s*\(\s*([\w:]+)\s*\)").unwrap());
+static ACTIX_ATTR: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"#\[(get|post|put|patch|delete)\s*\(\s*"([^"]+)""#).unwrap());
+static RUST_FN_NAME: Lazy<Regex> = Lazy::new(|| Regex::new(r"\bfn\s+(\w+)").unwrap());
+static EXPRESS_ROUTE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"\b(?:app|router)\.(get|post|put|patch|delete)\s*\(\s*[`'"]([^`'"]+)[`'"]"#)
+        .unwrap()
+});
+static FASTAPI_ROUTE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"@\w+\.(get|post|put|patch|delete)\s*\(\s*["']([^"']+)["']"#).unwrap()
+});
+static FLASK_ROUTE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"@\w+\.route\s*\(\s*["']([^"']+)["'](?:.*methods\s*=\s*\[([^\]]*)\])?"#).unwrap()
+});
+
+/// Routes declared on `line` as (method, raw path, handler name)
+fn routes(lang: Lang, line: &str, rest: &[&str]) -> Vec<(HttpMethod, String, Option<String>)> {
+    let mut found = Vec::new();
+    match lang {
+        Lang::Rust => {
+            if let Some(c) = AXUM_ROUTE.captures(line) {
+                // Handlers may continue on the next lines of a chained route
+                let mut handlers = c[2].to_string();
+                for next in rest.iter().skip(1).take(3) {
+                    if next.contains(".route(") || handlers.contains(';') {
+                        break;
+                    }
+                    handlers.push_str(next);
+                }
+                for h in AXUM_HANDLER.captures_iter(&handlers) {
+                    if let Some(method) = parse_method(&h[1]) {
+                        let handler = h[2].rsplit("::").next().unwrap_or(&h[2]).to_string();
+                        found.push((method, c[1].to_string(), Some(handler)));
+                    }
+                }
+            } else if let Some(c) = ACTIX_ATTR.captures(line) {
+                let handler = rest
+                    .iter()
+                    .skip(1)
+                    .take(4)
+                    .find_map(|l| RUST_FN_NAME.captures(l))
+                    .map(|f| f[1].to_string());
+                if let Some(method) = parse_method(&c[1]) {
+                    found.push((method, c[2].to_string(), handler));
+                }
+            }
+        }
+        Lang::JavaScript => {
+            if let Some(c) = EXPRESS_ROUTE.captures(line) {
+                if let Some(method) = parse_method(&c[1]) {
+                    found.push((method, c[2].to_string(), None));
+                }
+            }
+        }
+        Lang::Python => {
+            if let Some(c) = FASTAPI_ROUTE.captures(line) {
+                if let Some(method) = parse_method(&c[1]) {
+                    found.push((method, c[2].to_string(), None));
+                }
+            } else if let Some(c) = FLASK_ROUTE.captures(line) {
+                let methods = c.get(2).map_or("GET", |m| m.as_str());
+                for name in methods.split(',') {
+                    let name = name.trim().trim_matches(|ch| ch == '"' || ch == '\'');
+                    if let Some(method) = parse_method(name) {
+                        found.push((method, c[1].to_string(), None));
+                    }
+                }
+            }
+        }
+    }
+    found
+}
+
+static FN_RETURNING_JSON: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\bfn\s+(\w+)[^{;]*?->[^{;]*?\bJson\s*<\s*(?:Vec\s*<\s*)?(\w+)").unwrap()
+});
+
+/// Handler name -> struct it returns as JSON, for Rust handlers
+fn handler_return_types(files: &[SourceFile]) -> HashMap<String, String> {
+    let mut returns = HashMap::new();
+    for file in files.iter().filter(|f| f.lang == Lang::Rust) {
+        for c in FN_RETURNING_JSON.captures_iter(&file.content) {
+            returns.insert(c[1].to_string(), c[2].to_string());
+        }
+    }
+    returns
+}
+
+static STRUCT_DEF: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\bstruct\s+(\w+)(?:<[^>{]*>)?\s*\{([^}]*)\}").unwrap());
+static FIELD_DEF: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?s)^\s*(?:pub(?:\([^)]*\))?\s+)?(\w+)\s*:\s*(.+?)\s*$").unwrap());
+static ATTRIBUTE: Lazy<Regex> = Lazy::new(|| Regex::new(r"#\[[^\]]*\]").unwrap());
+static SERDE_RENAME: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"#\[serde\([^\]]*\brename\s*=\s*"([^"]+)""#).unwrap());
+static SERDE_SKIP: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"#\[serde\([^\]]*\bskip(?:_serializing|_deserializing)?\b").unwrap());
+
+/// Named-field Rust structs, keyed by name, as their JSON fields
+fn parse_structs(files: &[SourceFile]) -> HashMap<String, BTreeMap<String, FieldSpec>> {
+    let mut bodies = Vec::new();
+    for file in files.iter().filter(|f| f.lang == Lang::Rust) {
+        for c in STRUCT_DEF.captures_iter(&file.content) {
+            bodies.push((c[1].to_string(), c[2].to_string()));
+        }
+    }
+    let names: Vec<&str> = bodies.iter().map(|(n, _)| n.as_str()).collect();
+
+    let mut structs = HashMap::new();
+    for (name, body) in &bodies {
+        let mut fields = BTreeMap::new();
+        for field in split_fields(body) {
+            if SERDE_SKIP.is_match(&field) {
+                continue;
+            }
+            let rename = SERDE_RENAME.captures(&field).map(|r| r[1].to_string());
+            let bare = ATTRIBUTE.replace_all(&field, "");
+            let Some(f) = FIELD_DEF.captures(&bare) else {
+                continue;
+            };
+            let json_name = rename.unwrap_or_else(|| f[1].to_string());
+            fields.insert(json_name, rust_field_spec(&f[2], &names));
+        }
+        structs.insert(name.clone(), fields);
+    }
+    structs
+}
+
+/// Split a struct body into field declarations (attributes included) at
+/// top-level commas, dropping comments
+fn split_fields(body: &str) -> Vec<String> {
+    let code: String = body
+        .lines()
+        .map(|l| l.split("//").next().unwrap_or(""))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut fields = Vec::new();
+    let mut depth = 0i32;
+    let mut current = String::new();
+    for ch in code.chars() {
+        match ch {
+            '<' | '(' | '[' => depth += 1,
+            '>' | ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                fields.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(ch);
+    }
+    fields.push(current);
+    fields.retain(|f| !f.trim().is_empty());
+    fields
+}
+
+/// JSON shape of a Rust field type
+fn rust_field_spec(ty: &str, structs: &[&str]) -> FieldSpec {
+    let ty = ty.trim();
+    if let Some(inner) = ty.strip_prefix("Option<").and_then(|t| t.strip_suffix('>')) {
+        return FieldSpec {
+            optional: true,
+            ..rust_field_spec(inner, structs)
+        };
+    }
+    let base = ty
+        .trim_start_matches('&')
+        .trim_start_matches("'static ")
+        .split('<')
+        .next()
+        .unwrap_or(ty)
+        .rsplit("::")
+        .next()
+        .unwrap_or(ty)
+        .trim();
+    let field_type = match base {
+        "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16" | "u32" | "u64" | "u128"
+        | "usize" => FieldType::Integer,
+        "f32" | "f64" => FieldType::Number,
+        "bool" => FieldType::Boolean,
+        "String" | "str" | "char" | "Uuid" | "DateTime" | "NaiveDate" | "NaiveDateTime"
+        | "PathBuf" => FieldType::String,
+        "Vec" | "VecDeque" | "HashSet" | "BTreeSet" => FieldType::Array,
+        "HashMap" | "BTreeMap" => FieldType::Object,
+        _ if ty.starts_with('[') => FieldType::Array,
+        _ if structs.contains(&base) => FieldType::Object,
+        _ => FieldType::Unknown,
+    };
+    FieldSpec {
+        field_type,
+        optional: false,
+    }
+}
+
+static PATH_PARAM: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(?::\w+|\{[^}]*\}|<[^>]*>|\$\{[^}]*\}|\d+)$").unwrap());
+
+/// Normalize a path or URL literal to `/segment/{}` form. `None` for
+/// literals that are not API paths, such as URLs to other hosts.
+fn api_path(raw: &str) -> Option<String> {
+    let mut rest = raw.trim();
+    if let Some(after_scheme) = rest
+        .strip_prefix("http://")
+        .or_else(|| rest.strip_prefix("https://"))
+    {
+        let (host, path) = after_scheme.split_at(after_scheme.find('/')?);
+        let host = host.split(':').next().unwrap_or(host);
+        if !matches!(host, "localhost" | "127.0.0.1" | "0.0.0.0") && !host.starts_with('{') {
+            return None;
+        }
+        rest = path;
+    }
+    // A leading placeholder is the base URL: `{}/users`, `${BASE}/users`
+    rest = &rest[rest.find('/')?..];
+    let path = rest.split(['?', '#']).next().unwrap_or(rest);
+
+    let segments: Vec<&str> = path
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .map(|s| if PATH_PARAM.is_match(s) { "{}" } else { s })
+        .collect();
+    Some(format!("/{}", segments.join("/")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, rel: &str, content: &str) {
+        let path = dir.join(rel);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    const CLIENT: &str = r#"
+#[derive(Deserialize)]
+pub struct User {
+    pub id: u64,
+    #[serde(rename = "displayName")]
+    pub name: String,
+    pub email: Option<String>,
+}
+
+async fn fetch_user(client: &Client, base: &str, id: u64) -> Result<User> {
+    let user = client
+        .get(format!("{}/users/{}", base, id))
+        .send()
+        .await?
+        .json::<User>()
+        .await?;
+    Ok(user)
+}
+
+async fn delete_user(client: &Client, base: &str, id: u64) -> Result<()> {
+    client.delete(format!("{}/users/{}", base, id)).send().await?;
+    client.get("https://api.github.com/zen").send().await?;
+    Ok(())
+}
+"#;
+
+    fn provider(user_struct: &str) -> String {
+        format!(
+            r#"
+{}
+
+pub fn router() -> Router {{
+    Router::new()
+        .route("/users/:id", get(get_user).delete(remove_user))
+        .route("/health", get(health))
+}}
+
+async fn get_user(Path(id): Path<u64>) -> Result<Json<User>, StatusCode> {{
+    todo!()
+}}
+"#,
+            user_struct
+        )
+    }
+
+    #[test]
+    fn test_contract_generate_reads_client_calls() {
+        let dir = tempfile::tempdir().unwrap();
+        let consumer = dir.path().join("web");
+        write(&consumer, "src/client.rs", CLIENT);
+        write(
+            &consumer,
+            "target/debug/gen.rs",
+            r#"client.get("/ignored")"#,
+        );
+        write(
+            &consumer,
+            "ui/app.ts",
+            "await fetch(`${BASE}/orders/${id}`, {\n  method: 'POST',\n});",
+        );
+
+        let contract = contract_generate(&consumer, &dir.path().join("api")).unwrap();
+        assert_eq!(contract.consumer, "web");
+        assert_eq!(contract.provider, "api");
+        let paths: Vec<_> = contract
+            .interactions
+            .iter()
+            .map(|i| format!("{} {}", i.method.as_str(), i.path))
+            .collect();
+        assert_eq!(
+            paths,
+            vec!["GET /users/{}", "DELETE /users/{}", "POST /orders/{}"]
+        );
+
+        let get = &contract.interactions[0];
+        assert_eq!(get.source, "src/client.rs:12");
+        assert_eq!(get.response["id"].field_type, FieldType::Integer);
+        assert_eq!(get.response["displayName"].field_type, FieldType::String);
+        assert!(get.response["email"].optional);
+
+        let parsed = SourceContract::from_json(&contract.to_json()).unwrap();
+        assert_eq!(parsed, contract);
+        let json: serde_json::Value = serde_json::from_str(&contract.to_json()).unwrap();
+        assert_eq!(json["interactions"][0]["response"]["id"]["type"], "integer");
+    }
+
+    #[test]
+    fn test_contract_verify_separates_breaking_from_additive() {
+        let dir = tempfile::tempdir().unwrap();
+        let consumer = dir.path().join("web");
+        let api = dir.path().join("api");
+        write(&consumer, "src/client.rs", CLIENT);
+        write(
+            &api,
+            "src/routes.rs",
+            &provider(
+                "pub struct User { pub id: u64, #[serde(rename = \"displayName\")]\n pub name: String, pub email: Option<String> }",
+            ),
+        );
+        let contract = contract_generate(&consumer, &api).unwrap();
+        let report = contract_verify(&contract, &api).unwrap();
+        assert!(report.is_satisfied(), "{:?}", report.changes);
+        assert!(report.changes.is_empty());
+
+        // id becomes a string, displayName goes away, email is dropped, avatar is new
+        write(
+            &api,
+            "src/routes.rs",
+            &provider("pub struct User {\n    pub id: String,\n    pub avatar: String,\n}"),
+        );
+        let report = contract_verify(&contract, &api).unwrap();
+        assert!(!report.is_satisfied());
+        let kinds = |changes: Vec<&CompatibilityChange>| {
+            changes
+                .iter()
+                .map(|c| (c.change_type, c.path.clone()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            kinds(report.breaking()),
+            vec![
+                (
+                    CompatibilityChangeType::FieldRemoved,
+                    "/users/{}.displayName".to_string()
+                ),
+                (
+                    CompatibilityChangeType::TypeChanged,
+                    "/users/{}.id".to_string()
+                ),
+            ]
+        );
+        assert_eq!(
+            kinds(report.additive()),
+            vec![
+                (
+                    CompatibilityChangeType::ResponseChanged,
+                    "/users/{}.email".to_string()
+                ),
+                (
+                    CompatibilityChangeType::FieldAdded,
+                    "/users/{}.avatar".to_string()
+                ),
+            ]
+        );
+
+        // Dropping the route breaks both calls
+        write(&api, "src/routes.rs", ".route(\"/health\", get(health))");
+        let report = contract_verify(&contract, &api).unwrap();
+        assert_eq!(report.breaking().len(), 2);
+        assert!(report
+            .breaking()
+            .iter()
+            .all(|c| c.change_type == CompatibilityChangeType::EndpointRemoved));
+    }
+
+    #[test]
+    fn test_provider_endpoints_across_frameworks() {
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            dir.path(),
+            "actix.rs",
+            "#[get(\"/items/{id}\")]\nasync fn item() -> Json<Item> {}\nstruct Item { id: i32 }",
+        );
+        write(dir.path(), "server.js", "router.post('/items', create);");
+        write(
+            dir.path(),
+            "app.py",
+            "@app.route(\"/items/<int:id>\", methods=[\"PUT\", \"DELETE\"])\ndef item(id): ...",
+        );
+
+        let endpoints = provider_endpoints(dir.path()).unwrap();
+        let summary: Vec<_> = endpoints
+            .iter()
+            .map(|e| format!("{} {}", e.method.as_str(), e.path))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                "GET /items/{}",
+                "PUT /items/{}",
+                "DELETE /items/{}",
+                "POST /items"
+            ]
+        );
+        assert_eq!(
+            endpoints[0].response.as_ref().unwrap()["id"].field_type,
+            FieldType::Integer
+        );
+    }
+
+    #[test]
+    fn test_api_path_normalization() {
+        assert_eq!(api_path("{}/users/{}").as_deref(), Some("/users/{}"));
+        assert_eq!(
+            api_path("/users/:id/posts?page=2").as_deref(),
+            Some("/users/{}/posts")
+        );
+        assert_eq!(
+            api_path("http://localhost:8080/v1/items/").as_deref(),
+            Some("/v1/items")
+        );
+        assert_eq!(api_path("https://example.com/users"), None);
+        assert_eq!(api_path("no path here"), None);
+    }
+}
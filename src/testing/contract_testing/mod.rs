@@ -24,6 +24,7 @@ fn current_timestamp() -> u64 {
 pub mod api_compat;
 pub mod containers;
 pub mod contracts;
+pub mod generation;
 pub mod stubs;
 
 // Re-export everything from contracts
@@ -32,6 +33,12 @@ pub use contracts::{
     InteractionResult, Matcher, VerificationResult,
 };
 
+// Re-export everything from generation
+pub use generation::{
+    contract_generate, contract_verify, provider_endpoints, ContractReport, ExpectedInteraction,
+    FieldSpec, FieldType, ProviderEndpoint, SourceContract,
+};
+
 // Re-export everything from stubs
 pub use stubs::{FaultType, MockServer, RequestLogEntry, StubMapping, StubRequest, StubResponse};
 
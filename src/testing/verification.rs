@@ -7,7 +7,9 @@
 //! 4. Commit: Only on green, or explicit override
 
 use anyhow::{Context, Result};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::time::Instant;
 use tokio::process::Command;
@@ -40,6 +42,8 @@ pub enum CheckType {
     Format,
     /// Custom command
     Custom,
+    /// Lines added by the agent matching `deny_patterns`
+    DenyPattern,
 }
 
 impl CheckType {
@@ -50,6 +54,7 @@ impl CheckType {
             Self::Lint => "lint",
             Self::Format => "format",
             Self::Custom => "custom",
+            Self::DenyPattern => "deny_pattern",
         }
     }
 }
@@ -108,6 +113,56 @@ pub enum SideEffectType {
     TestRemoved,
 }
 
+/// A line the agent added that matches a deny pattern
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PatternViolation {
+    pub file: String,
+    /// 1-based line number in the current file
+    pub line: usize,
+    pub pattern: String,
+    pub text: String,
+}
+
+impl std::fmt::Display for PatternViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}: {}", self.file, self.line, self.text)
+    }
+}
+
+/// Debug and placeholder code the agent should not leave behind
+pub const DEFAULT_DENY_PATTERNS: &[&str] = &[
+    r"\bdbg!\s*\(",
+    r#"\be?println!\s*\(\s*"\[?DEBUG"#,
+    r"\btodo!\s*\(",
+    r"\bunimplemented!\s*\(",
+];
+
+/// Compile [`DEFAULT_DENY_PATTERNS`]
+pub fn default_deny_patterns() -> Vec<Regex> {
+    DEFAULT_DENY_PATTERNS
+        .iter()
+        .map(|p| Regex::new(p).expect("default deny pattern is valid"))
+        .collect()
+}
+
+/// Serialize regexes as their source strings
+mod regex_list_serde {
+    use super::*;
+    use serde::de::Error;
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(patterns: &[Regex], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(patterns.iter().map(Regex::as_str))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Regex>, D::Error> {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|p| Regex::new(p).map_err(D::Error::custom))
+            .collect()
+    }
+}
+
 /// Configuration for verification gates
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VerificationConfig {
@@ -129,6 +184,10 @@ pub struct VerificationConfig {
     pub exclude_patterns: Vec<String>,
     /// Custom verification commands
     pub custom_checks: Vec<CustomCheck>,
+    /// Patterns that fail verification when they appear on lines the agent
+    /// added. Lines that were already there never match. Empty disables.
+    #[serde(with = "regex_list_serde", default = "default_deny_patterns")]
+    pub deny_patterns: Vec<Regex>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -156,6 +215,7 @@ impl Default for VerificationConfig {
                 "*.toml".to_string(),
             ],
             custom_checks: vec![],
+            deny_patterns: default_deny_patterns(),
         }
     }
 }
@@ -189,6 +249,10 @@ pub struct VerificationGate {
     config: VerificationConfig,
     project_root: PathBuf,
     last_results: Option<VerificationReport>,
+    /// File contents before the agent first edited them this task
+    baselines: HashMap<String, String>,
+    /// Violations already shown at completion; they no longer block
+    acknowledged: HashSet<(String, String)>,
 }
 
 impl VerificationGate {
@@ -197,7 +261,75 @@ impl VerificationGate {
             config,
             project_root: project_root.as_ref().to_path_buf(),
             last_results: None,
+            baselines: HashMap::new(),
+            acknowledged: HashSet::new(),
+        }
+    }
+
+    /// Remember `file`'s content before an edit, so only lines added after
+    /// it are checked against `deny_patterns`. The first baseline wins.
+    pub fn record_baseline(&mut self, file: &str, content: &str) {
+        self.baselines
+            .entry(file.to_string())
+            .or_insert_with(|| content.to_string());
+    }
+
+    /// Forget baselines and acknowledgements, e.g. when a new task starts
+    pub fn reset_baselines(&mut self) {
+        self.baselines.clear();
+        self.acknowledged.clear();
+    }
+
+    /// Lines added to `files` since their baselines that match a deny
+    /// pattern. Files without a baseline are skipped.
+    pub fn scan_added_lines(&self, files: &[String]) -> Vec<PatternViolation> {
+        if self.config.deny_patterns.is_empty() {
+            return Vec::new();
+        }
+        let mut violations = Vec::new();
+        for file in files {
+            let Some(baseline) = self.baselines.get(file) else {
+                continue;
+            };
+            let current = std::fs::read_to_string(self.project_root.join(file)).unwrap_or_default();
+            let diff = similar::TextDiff::from_lines(baseline.as_str(), current.as_str());
+            for change in diff.iter_all_changes() {
+                if change.tag() != similar::ChangeTag::Insert {
+                    continue;
+                }
+                let text = change.value().trim();
+                if let Some(pattern) = self.config.deny_patterns.iter().find(|p| p.is_match(text)) {
+                    violations.push(PatternViolation {
+                        file: file.clone(),
+                        line: change.new_index().map_or(0, |i| i + 1),
+                        pattern: pattern.as_str().to_string(),
+                        text: text.to_string(),
+                    });
+                }
+            }
+        }
+        violations
+    }
+
+    /// Violations in every edited file that have not been reported yet.
+    /// Each is returned once: reporting it counts as the chance to clean
+    /// up, and a violation the agent keeps after that is acknowledged.
+    pub fn unacknowledged_violations(&mut self) -> Vec<PatternViolation> {
+        let mut files: Vec<String> = self.baselines.keys().cloned().collect();
+        files.sort();
+        let violations: Vec<_> = self
+            .scan_added_lines(&files)
+            .into_iter()
+            .filter(|v| {
+                !self
+                    .acknowledged
+                    .contains(&(v.file.clone(), v.text.clone()))
+            })
+            .collect();
+        for v in &violations {
+            self.acknowledged.insert((v.file.clone(), v.text.clone()));
         }
+        violations
     }
 
     /// Run verification after a file change
@@ -273,6 +405,13 @@ impl VerificationGate {
             }
         }
 
+        if let Some(result) = self.run_deny_pattern_check(&files_to_check) {
+            if !result.passed {
+                suggested_next_steps.push("Remove leftover debug or placeholder code".to_string());
+            }
+            checks.push(result);
+        }
+
         // Run custom checks
         for custom in &self.config.custom_checks {
             if self.should_run_custom_check(custom, &files_to_check) {
@@ -307,6 +446,36 @@ impl VerificationGate {
         Ok(report)
     }
 
+    /// Verify a dependency added or removed in `manifest`. Manifests are
+    /// excluded from [`verify_change`](Self::verify_change), but a bad
+    /// dependency change breaks the build just the same, so type check.
+    pub async fn verify_dependency_change(
+        &mut self,
+        manifest: &str,
+        trigger: &str,
+    ) -> Result<VerificationReport> {
+        let start = Instant::now();
+        let check = self.run_cargo_check().await?;
+        let overall_passed = check.passed;
+        let next_step = if overall_passed {
+            "All checks passed - safe to proceed"
+        } else {
+            "Fix the build or revert the dependency change"
+        };
+        let report = VerificationReport {
+            triggered_by: trigger.to_string(),
+            timestamp: chrono::Utc::now(),
+            total_duration_ms: start.elapsed().as_millis() as u64,
+            checks: vec![check],
+            overall_passed,
+            affected_files: vec![manifest.to_string()],
+            side_effects: vec![],
+            suggested_next_steps: vec![next_step.to_string()],
+        };
+        self.last_results = Some(report.clone());
+        Ok(report)
+    }
+
     /// Quick verification - just type check
     pub async fn quick_verify(&mut self, _changed_files: &[String]) -> Result<bool> {
         let result = self.run_cargo_check().await?;
@@ -441,6 +610,43 @@ impl VerificationGate {
         })
     }
 
+    /// Check lines added to edited files against `deny_patterns`
+    fn run_deny_pattern_check(&self, files: &[String]) -> Option<CheckResult> {
+        if self.config.deny_patterns.is_empty()
+            || !files.iter().any(|f| self.baselines.contains_key(f))
+        {
+            return None;
+        }
+        let start = Instant::now();
+        let violations = self.scan_added_lines(files);
+        let errors: Vec<VerificationError> = violations
+            .iter()
+            .map(|v| VerificationError {
+                file: v.file.clone(),
+                line: Some(v.line as u32),
+                column: None,
+                message: format!("Added line matches `{}`: {}", v.pattern, v.text),
+                code: None,
+                severity: ErrorSeverity::Error,
+                suggestion: Some("Remove it before finishing".to_string()),
+            })
+            .collect();
+
+        Some(CheckResult {
+            check_type: CheckType::DenyPattern,
+            passed: errors.is_empty(),
+            duration_ms: start.elapsed().as_millis() as u64,
+            output: violations
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("\n"),
+            errors,
+            warnings: vec![],
+            suggestions: vec![],
+        })
+    }
+
     /// Run a custom check
     async fn run_custom_check(&self, check: &CustomCheck) -> Result<CheckResult> {
         let start = Instant::now();
@@ -2544,4 +2750,50 @@ mod tests {
         assert!(display.contains("Step two"));
         assert!(display.contains("Step three"));
     }
+
+    #[test]
+    fn test_deny_patterns_only_flag_added_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let before = "fn a() {\n    todo!()\n}\n";
+        std::fs::write(dir.path().join("lib.rs"), before).unwrap();
+        let mut gate = VerificationGate::new(dir.path(), VerificationConfig::default());
+        gate.record_baseline("lib.rs", before);
+
+        let after = "fn a() {\n    todo!()\n}\nfn b() {\n    dbg!(1);\n    println!(\"DEBUG x\");\n    println!(\"done\");\n}\n";
+        std::fs::write(dir.path().join("lib.rs"), after).unwrap();
+
+        let files = vec!["lib.rs".to_string()];
+        let violations = gate.scan_added_lines(&files);
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[0].line, 5);
+        assert_eq!(violations[0].to_string(), "lib.rs:5: dbg!(1);");
+        assert_eq!(violations[1].line, 6);
+
+        let check = gate.run_deny_pattern_check(&files).unwrap();
+        assert!(!check.passed);
+        assert_eq!(check.errors[0].line, Some(5));
+
+        // Reported once at completion, then acknowledged
+        assert_eq!(gate.unacknowledged_violations().len(), 2);
+        assert!(gate.unacknowledged_violations().is_empty());
+
+        // Files the agent never edited are not checked
+        assert!(gate.scan_added_lines(&["other.rs".to_string()]).is_empty());
+    }
+
+    #[test]
+    fn test_deny_patterns_roundtrip_as_strings() {
+        let config = VerificationConfig {
+            deny_patterns: vec![Regex::new(r"FIXME").unwrap()],
+            ..Default::default()
+        };
+        let json = serde_json::to_value(&config).unwrap();
+        assert_eq!(json["deny_patterns"], serde_json::json!(["FIXME"]));
+        let parsed: VerificationConfig = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.deny_patterns[0].as_str(), "FIXME");
+
+        let mut json = serde_json::to_value(&config).unwrap();
+        json["deny_patterns"] = serde_json::json!(["("]);
+        assert!(serde_json::from_value::<VerificationConfig>(json).is_err());
+    }
 }
@@ -104,6 +104,113 @@ pub fn estimate_content_tokens(content: &str) -> usize {
     count
 }
 
+/// Which tokenizer counts are made with: `"qwen"` (exact for Qwen models),
+/// `"cl100k"` (a close approximation) or `"heuristic"` (characters / 4)
+pub fn tokenizer_name() -> &'static str {
+    match &*TOKENIZER {
+        TokenizerState::Qwen(_) => "qwen",
+        TokenizerState::Tiktoken(_) => "cl100k",
+        TokenizerState::Heuristic => "heuristic",
+    }
+}
+
+/// A text shortened by [`elide_middle`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Elided {
+    /// Head, elision marker and tail
+    pub text: String,
+    /// Char offset in the original text where the elided part starts
+    pub start: usize,
+    /// Chars left out
+    pub chars: usize,
+    /// Line breaks left out, counting JSON-escaped `\n`
+    pub lines: usize,
+}
+
+/// Shorten `text` to roughly `max_tokens` by keeping its head and tail and
+/// replacing the middle with a marker such as "… 1,200 lines elided …".
+///
+/// `head_fraction` of the budget goes to the head and the rest to the tail.
+/// Cuts land on line breaks (real or JSON-escaped) when one is near. `note`,
+/// if not empty, is added to the marker. Returns `None` when the text
+/// already fits.
+pub fn elide_middle(
+    text: &str,
+    max_tokens: usize,
+    head_fraction: f32,
+    note: &str,
+) -> Option<Elided> {
+    let tokens = estimate_content_tokens(text);
+    if tokens <= max_tokens {
+        return None;
+    }
+    let bytes_per_token = text.len() as f64 / tokens as f64;
+    let head_fraction = head_fraction.clamp(0.0, 1.0) as f64;
+    let head_bytes = (max_tokens as f64 * head_fraction * bytes_per_token) as usize;
+    let tail_bytes = (max_tokens as f64 * (1.0 - head_fraction) * bytes_per_token) as usize;
+
+    let mut head_end = text.floor_char_boundary(head_bytes);
+    let mut tail_start = text.ceil_char_boundary(text.len().saturating_sub(tail_bytes));
+    if let Some(cut) = last_line_break(&text[..head_end]).filter(|i| *i >= head_end / 2) {
+        head_end = cut;
+    }
+    let tail = &text[tail_start..];
+    if let Some((pos, len)) = first_line_break(tail).filter(|(i, _)| *i <= tail.len() / 2) {
+        tail_start += pos + len;
+    }
+    if head_end >= tail_start {
+        return None;
+    }
+
+    let elided = &text[head_end..tail_start];
+    let lines = elided.matches('\n').count() + elided.matches("\\n").count();
+    let chars = elided.chars().count();
+    let what = if lines > 0 {
+        format!("{} lines", group_thousands(lines))
+    } else {
+        format!("{} chars", group_thousands(chars))
+    };
+    let marker = if note.is_empty() {
+        format!("… {} elided …", what)
+    } else {
+        format!("… {} elided ({}) …", what, note)
+    };
+    Some(Elided {
+        text: format!("{}\n{}\n{}", &text[..head_end], marker, &text[tail_start..]),
+        start: text[..head_end].chars().count(),
+        chars,
+        lines,
+    })
+}
+
+/// Byte offset of the last line break in `text`
+fn last_line_break(text: &str) -> Option<usize> {
+    text.rfind('\n').max(text.rfind("\\n"))
+}
+
+/// Byte offset and length of the first line break in `text`
+fn first_line_break(text: &str) -> Option<(usize, usize)> {
+    let real = text.find('\n').map(|i| (i, 1));
+    let escaped = text.find("\\n").map(|i| (i, 2));
+    match (real, escaped) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Format `n` with comma thousands separators
+fn group_thousands(n: usize) -> String {
+    let digits = n.to_string();
+    let mut out = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
+
 /// Compute a fast 64-bit hash of the content string for cache keying.
 fn hash_content(content: &str) -> u64 {
     let mut hasher = std::collections::hash_map::DefaultHasher::new();
@@ -161,4 +268,43 @@ mod tests {
         let c = hash_content("world");
         assert_ne!(a, c);
     }
+
+    #[test]
+    fn test_elide_middle_keeps_head_and_tail() {
+        let text: String = (1..=2000).map(|i| format!("line {}\n", i)).collect();
+        assert!(elide_middle(&text, 1_000_000, 0.5, "").is_none());
+
+        let elided = elide_middle(&text, 400, 0.75, "see call_1").unwrap();
+        assert!(elided.text.starts_with("line 1\nline 2\n"));
+        assert!(elided.text.trim_end().ends_with("line 2000"));
+        assert!(elided.text.contains(" lines elided (see call_1) …"));
+        assert!(estimate_content_tokens(&elided.text) < 500);
+        // The head gets the larger share
+        let marker = elided.text.find('…').unwrap();
+        assert!(marker > elided.text.len() - marker);
+        // The elided range maps back onto the original text
+        let kept_head: String = text.chars().take(elided.start).collect();
+        assert!(elided.text.starts_with(&kept_head));
+        let kept_tail: String = text.chars().skip(elided.start + elided.chars).collect();
+        assert!(elided.text.ends_with(&kept_tail));
+    }
+
+    #[test]
+    fn test_elide_middle_counts_escaped_lines_in_json() {
+        let stdout: String = (1..=3000).map(|i| format!("row {}\n", i)).collect();
+        let json = serde_json::json!({ "stdout": stdout }).to_string();
+        let elided = elide_middle(&json, 300, 0.5, "").unwrap();
+        assert!(elided.lines > 2000);
+        assert!(elided
+            .text
+            .contains(&format!("{} lines elided", group_thousands(elided.lines))));
+        assert!(elided.text.ends_with("row 3000\\n\"}"));
+    }
+
+    #[test]
+    fn test_group_thousands() {
+        assert_eq!(group_thousands(7), "7");
+        assert_eq!(group_thousands(1200), "1,200");
+        assert_eq!(group_thousands(1234567), "1,234,567");
+    }
 }
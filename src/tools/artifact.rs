@@ -0,0 +1,209 @@
+//! Artifacts: files a tool produces besides its text output
+//!
+//! Tool output stays a JSON value. A tool that writes a screenshot, a PDF or
+//! a diagram lists the files under [`ARTIFACTS_KEY`] with [`attach`], and the
+//! agent decides how the model sees them: images go through the vision path
+//! when the model supports it, everything else becomes a text description
+//! from [`describe`]. Text-only tools need no changes.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use xcap::image;
+
+/// Key of the artifact list in a tool result
+pub const ARTIFACTS_KEY: &str = "artifacts";
+
+/// Largest image attached to a message; bigger ones are only described
+const MAX_ATTACHED_IMAGE_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Most characters of a text artifact (SVG, Mermaid, DOT) quoted in its
+/// description
+const MAX_QUOTED_CHARS: usize = 4_000;
+
+/// A file produced by a tool
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Artifact {
+    pub path: PathBuf,
+    pub mime_type: String,
+}
+
+impl Artifact {
+    pub fn new(path: impl Into<PathBuf>, mime_type: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            mime_type: mime_type.into(),
+        }
+    }
+
+    /// Artifact with the MIME type guessed from the file extension
+    pub fn from_path(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let mime_type = mime_for(&path).to_string();
+        Self { path, mime_type }
+    }
+
+    /// Raster image a vision model can look at
+    pub fn is_image(&self) -> bool {
+        matches!(
+            self.mime_type.as_str(),
+            "image/png" | "image/jpeg" | "image/gif" | "image/webp"
+        )
+    }
+
+    /// Text the model can read directly, such as SVG or diagram sources
+    pub fn is_text(&self) -> bool {
+        self.mime_type.starts_with("text/")
+            || matches!(
+                self.mime_type.as_str(),
+                "image/svg+xml" | "application/json"
+            )
+    }
+
+    /// Base64 of the image, for a `data:` URL
+    pub fn image_base64(&self) -> Result<String> {
+        if !self.is_image() {
+            bail!(
+                "{} is not an image ({})",
+                self.path.display(),
+                self.mime_type
+            );
+        }
+        let size = std::fs::metadata(&self.path)
+            .with_context(|| format!("Artifact not found: {}", self.path.display()))?
+            .len();
+        if size > MAX_ATTACHED_IMAGE_BYTES {
+            bail!(
+                "{} is too large to attach ({} bytes)",
+                self.path.display(),
+                size
+            );
+        }
+        super::vision::encode_image_file(&self.path.to_string_lossy())
+    }
+}
+
+/// MIME type for a file extension, `application/octet-stream` if unknown
+pub fn mime_for(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase);
+    match extension.as_deref() {
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("svg") => "image/svg+xml",
+        Some("pdf") => "application/pdf",
+        Some("json") => "application/json",
+        Some("mmd" | "mermaid") => "text/vnd.mermaid",
+        Some("dot" | "gv") => "text/vnd.graphviz",
+        Some("md") => "text/markdown",
+        Some("html" | "htm") => "text/html",
+        Some("txt" | "log") => "text/plain",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Add artifacts to a tool result. Non-object results are left alone.
+pub fn attach(output: &mut Value, artifacts: impl IntoIterator<Item = Artifact>) {
+    let Some(object) = output.as_object_mut() else {
+        return;
+    };
+    let list = object
+        .entry(ARTIFACTS_KEY)
+        .or_insert_with(|| Value::Array(Vec::new()));
+    if let Some(list) = list.as_array_mut() {
+        list.extend(
+            artifacts
+                .into_iter()
+                .filter_map(|a| serde_json::to_value(a).ok()),
+        );
+    }
+}
+
+/// Artifacts listed in a tool result, skipping malformed entries
+pub fn artifacts(output: &Value) -> Vec<Artifact> {
+    output
+        .get(ARTIFACTS_KEY)
+        .and_then(|v| v.as_array())
+        .map(|list| {
+            list.iter()
+                .filter_map(|a| serde_json::from_value(a.clone()).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Text description of an artifact for a model that cannot see it: kind,
+/// size and, for images, dimensions; text artifacts are quoted
+pub fn describe(artifact: &Artifact) -> String {
+    let path = artifact.path.display();
+    let Ok(metadata) = std::fs::metadata(&artifact.path) else {
+        return format!("{} ({}), file missing", path, artifact.mime_type);
+    };
+    let mut description = format!(
+        "{} ({}, {} bytes)",
+        path,
+        artifact.mime_type,
+        metadata.len()
+    );
+    if artifact.is_image() {
+        match image::image_dimensions(&artifact.path) {
+            Ok((width, height)) => {
+                description.push_str(&format!(", {}x{} pixels", width, height));
+            }
+            Err(_) => description.push_str(", unreadable image"),
+        }
+        description.push_str(". The current model cannot view images");
+    } else if artifact.is_text() {
+        if let Ok(text) = std::fs::read_to_string(&artifact.path) {
+            let quoted: String = text.chars().take(MAX_QUOTED_CHARS).collect();
+            let more = if quoted.len() < text.len() {
+                "\n[truncated]"
+            } else {
+                ""
+            };
+            description.push_str(&format!(":\n{}{}", quoted, more));
+        }
+    }
+    description
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attach_and_describe_artifacts() {
+        let dir = tempfile::tempdir().unwrap();
+        let png = dir.path().join("shot.png");
+        image::RgbaImage::new(3, 2).save(&png).unwrap();
+        let diagram = dir.path().join("flow.mmd");
+        std::fs::write(&diagram, "graph TD\n  A --> B\n").unwrap();
+
+        let mut output = serde_json::json!({"success": true});
+        attach(
+            &mut output,
+            [Artifact::from_path(&png), Artifact::from_path(&diagram)],
+        );
+        let listed = artifacts(&output);
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[0].mime_type, "image/png");
+        assert!(listed[0].is_image());
+        assert_eq!(listed[1].mime_type, "text/vnd.mermaid");
+        assert!(listed[1].is_text());
+
+        assert!(describe(&listed[0]).contains("3x2 pixels"));
+        assert!(describe(&listed[1]).contains("A --> B"));
+        assert!(!listed[0].image_base64().unwrap().is_empty());
+        assert!(listed[1].image_base64().is_err());
+
+        // Plain results carry no artifacts
+        assert!(artifacts(&serde_json::json!({"stdout": "ok"})).is_empty());
+        let missing = Artifact::new("/nonexistent/x.pdf", "application/pdf");
+        assert!(describe(&missing).contains("file missing"));
+    }
+}
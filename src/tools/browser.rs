@@ -12,6 +12,7 @@ use std::process::Stdio;
 use std::sync::LazyLock;
 use tokio::process::Command;
 
+use super::artifact::{self, Artifact};
 use super::Tool;
 
 // ============================================================================
@@ -500,7 +501,7 @@ impl Tool for BrowserScreenshot {
                     None
                 };
 
-                Ok(json!({
+                let mut result = json!({
                     "success": output.status.success() && file_exists,
                     "browser": "chrome",
                     "url": pinned_target.url,
@@ -510,7 +511,11 @@ impl Tool for BrowserScreenshot {
                     "file_size": file_size,
                     "dimensions": format!("{}x{}", width, height),
                     "stderr": if stderr.is_empty() { None } else { Some(truncate_output(&stderr, 500)) }
-                }))
+                });
+                if file_exists {
+                    artifact::attach(&mut result, [Artifact::new(output_path, "image/png")]);
+                }
+                Ok(result)
             }
             BrowserType::Playwright => {
                 let safe_url = escape_js_string(&pinned_target.url);
@@ -569,7 +574,7 @@ const {{ chromium }} = require('playwright');
                     None
                 };
 
-                Ok(json!({
+                let mut result = json!({
                     "success": output.status.success() && file_exists,
                     "browser": "playwright",
                     "url": pinned_target.url,
@@ -578,7 +583,11 @@ const {{ chromium }} = require('playwright');
                     "file_exists": file_exists,
                     "file_size": file_size,
                     "dimensions": format!("{}x{}", width, height)
-                }))
+                });
+                if file_exists {
+                    artifact::attach(&mut result, [Artifact::from_path(output_path)]);
+                }
+                Ok(result)
             }
             BrowserType::Curl => Err(anyhow::anyhow!(
                 "Screenshots require Chrome or Playwright. Curl cannot take screenshots."
@@ -686,7 +695,7 @@ impl Tool for BrowserPdf {
                     None
                 };
 
-                Ok(json!({
+                let mut result = json!({
                     "success": output.status.success() && file_exists,
                     "browser": "chrome",
                     "url": pinned_target.url,
@@ -695,7 +704,11 @@ impl Tool for BrowserPdf {
                     "file_exists": file_exists,
                     "file_size": file_size,
                     "stderr": if stderr.is_empty() { None } else { Some(truncate_output(&stderr, 500)) }
-                }))
+                });
+                if file_exists {
+                    artifact::attach(&mut result, [Artifact::new(output_path, "application/pdf")]);
+                }
+                Ok(result)
             }
             _ => Err(anyhow::anyhow!("PDF generation requires Chrome/Chromium")),
         }
@@ -979,6 +992,57 @@ fn resolve_and_pin_target(url: &str) -> Result<PinnedTarget> {
     })
 }
 
+/// Fetch `url` under the same target rules as the browser tools and return
+/// its readable text. Redirects are followed only within the pinned host.
+pub(crate) async fn fetch_page_text(url: &str, timeout_secs: u64) -> Result<String> {
+    let target = resolve_and_pin_target(url)?;
+    let pinned_host = target.host.clone();
+    let mut builder = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(timeout_secs))
+        .user_agent("Mozilla/5.0 (compatible; Selfware/1.0)")
+        .redirect(reqwest::redirect::Policy::custom(move |attempt| {
+            if attempt.previous().len() >= 5 {
+                attempt.error("Too many redirects")
+            } else if attempt.url().host_str() != Some(pinned_host.as_str()) {
+                attempt.error("Redirect to another host blocked")
+            } else {
+                attempt.follow()
+            }
+        }));
+    if !target.host_is_ip {
+        builder = builder.resolve(
+            &target.host,
+            std::net::SocketAddr::new(target.ip, target.port),
+        );
+    }
+    let client = builder.build().context("Failed to build HTTP client")?;
+
+    let response = client
+        .get(&target.url)
+        .send()
+        .await
+        .with_context(|| format!("Failed to fetch {}", target.url))?;
+    let status = response.status();
+    if !status.is_success() {
+        anyhow::bail!("{} returned HTTP {}", target.url, status);
+    }
+    let is_html = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.contains("html"));
+    let body = response
+        .text()
+        .await
+        .context("Failed to read response body")?;
+
+    Ok(if is_html {
+        extract_text_from_html(&body)
+    } else {
+        body
+    })
+}
+
 fn is_private_network_ip(ip: &IpAddr) -> bool {
     match ip {
         IpAddr::V4(v4) => {
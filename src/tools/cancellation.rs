@@ -0,0 +1,102 @@
+//! Cooperative cancellation for in-flight tool calls and API streams
+//!
+//! The agent's Ctrl+C handler flips a shared `Arc<AtomicBool>`. Checking that
+//! flag between steps is not enough for long-running work, so
+//! [`CancellationToken`] wraps the same flag and adds an awaitable
+//! [`cancelled`](CancellationToken::cancelled) future that tools can race
+//! against their own work with `tokio::select!`.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// How often [`CancellationToken::cancelled`] re-checks the flag when it was
+/// set directly (e.g. by a signal handler) rather than via [`CancellationToken::cancel`].
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Cloneable handle that signals cancellation to running work.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    flag: Arc<AtomicBool>,
+    notify: Arc<Notify>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wrap an existing cancel flag so that setting it also cancels this token.
+    pub fn from_flag(flag: Arc<AtomicBool>) -> Self {
+        Self {
+            flag,
+            notify: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Request cancellation and wake every task waiting on [`cancelled`](Self::cancelled).
+    pub fn cancel(&self) {
+        self.flag.store(true, Ordering::Relaxed);
+        self.notify.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.flag.load(Ordering::Relaxed)
+    }
+
+    /// Resolve once cancellation has been requested.
+    pub async fn cancelled(&self) {
+        loop {
+            if self.is_cancelled() {
+                return;
+            }
+            tokio::select! {
+                _ = self.notify.notified() => {}
+                _ = tokio::time::sleep(POLL_INTERVAL) => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[tokio::test]
+    async fn test_cancel_wakes_waiter() {
+        let token = CancellationToken::new();
+        let waiter = token.clone();
+        let handle = tokio::spawn(async move { waiter.cancelled().await });
+        token.cancel();
+        tokio::time::timeout(Duration::from_secs(1), handle)
+            .await
+            .expect("waiter should wake")
+            .unwrap();
+        assert!(token.is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn test_shared_flag_set_directly_is_observed() {
+        let flag = Arc::new(AtomicBool::new(false));
+        let token = CancellationToken::from_flag(Arc::clone(&flag));
+        let start = Instant::now();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(30)).await;
+            flag.store(true, Ordering::Relaxed);
+        });
+        tokio::time::timeout(Duration::from_secs(1), token.cancelled())
+            .await
+            .expect("flag store should cancel the token");
+        assert!(start.elapsed() < Duration::from_millis(500));
+    }
+
+    #[tokio::test]
+    async fn test_not_cancelled_by_default() {
+        let token = CancellationToken::new();
+        assert!(!token.is_cancelled());
+        let waited = tokio::time::timeout(Duration::from_millis(50), token.cancelled()).await;
+        assert!(waited.is_err());
+    }
+}
@@ -7,6 +7,9 @@ use serde_json::Value;
 use std::collections::HashMap;
 use tracing::instrument;
 
+/// Captured output kept per failing test
+const MAX_FAILURE_OUTPUT_CHARS: usize = 4000;
+
 /// Maximum output buffer size from a cargo command (16 MB).
 /// Prevents a runaway cargo process from consuming unlimited memory.
 const MAX_CARGO_OUTPUT_SIZE: usize = 16 * 1024 * 1024;
@@ -30,6 +33,8 @@ pub struct CargoTest;
 pub struct CargoCheck;
 pub struct CargoClippy;
 pub struct CargoFmt;
+pub struct CargoAdd;
+pub struct CargoRemove;
 
 /// Represents a single test result
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -49,10 +54,26 @@ pub enum TestStatus {
     Ignored,
 }
 
+/// How a cargo test run ended, so a build failure isn't mistaken for a
+/// failing test
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TestOutcome {
+    Passed,
+    TestsFailed,
+    CompileError,
+    /// Cargo failed before running tests, e.g. an unknown package
+    CargoError,
+}
+
 /// Structured output from cargo test
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CargoTestOutput {
     pub success: bool,
+    pub outcome: TestOutcome,
+    /// Compiler errors when the tests failed to build
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<CompilerError>,
     pub summary: TestSummary,
     pub tests: Vec<TestResult>,
     pub failures: Vec<FailureDetail>,
@@ -152,7 +173,9 @@ impl Tool for CargoTest {
     }
 
     fn description(&self) -> &str {
-        "Run cargo test with structured output parsing. Returns detailed test results including pass/fail status, failure messages, and locations."
+        "Run cargo test, optionally only tests matching a name filter in one package. Returns \
+         structured pass/fail results with captured output for failures. A build failure is \
+         reported as outcome compile_error with compiler errors, not as failing tests."
     }
 
     fn schema(&self) -> Value {
@@ -161,7 +184,9 @@ impl Tool for CargoTest {
             "properties": {
                 "package": {"type": "string", "description": "Specific package to test"},
                 "test_name": {"type": "string", "description": "Specific test to run (substring match)"},
+                "exact": {"type": "boolean", "default": false, "description": "Match test_name exactly instead of as a substring"},
                 "release": {"type": "boolean", "default": false, "description": "Run tests in release mode"},
+                "json_format": {"type": "boolean", "default": false, "description": "Ask libtest for JSON events with timings (needs a nightly toolchain)"},
                 "no_fail_fast": {"type": "boolean", "default": true, "description": "Run all tests even if some fail"}
             }
         })
@@ -170,7 +195,10 @@ impl Tool for CargoTest {
     #[instrument(level = "info", skip(self, args), fields(tool_name = self.name()))]
     async fn execute(&self, args: Value) -> Result<Value> {
         let mut cmd = tokio::process::Command::new("cargo");
+        cmd.kill_on_drop(true);
         cmd.arg("test");
+        // Compiler diagnostics as JSON on stdout; libtest output is unaffected
+        cmd.arg("--message-format=json");
 
         if let Some(pkg) = args.get("package").and_then(|v| v.as_str()) {
             cmd.arg("-p").arg(pkg);
@@ -196,6 +224,21 @@ impl Tool for CargoTest {
             cmd.arg("--no-fail-fast");
         }
 
+        let mut harness_args = Vec::new();
+        if args.get("exact").and_then(|v| v.as_bool()).unwrap_or(false) {
+            harness_args.push("--exact");
+        }
+        if args
+            .get("json_format")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+        {
+            harness_args.extend(["-Z", "unstable-options", "--format=json", "--report-time"]);
+        }
+        if !harness_args.is_empty() {
+            cmd.arg("--").args(harness_args);
+        }
+
         cmd.env("RUST_BACKTRACE", "1");
 
         let output = cmd.output().await.context("Failed to execute cargo test")?;
@@ -204,6 +247,12 @@ impl Tool for CargoTest {
 
         // Parse test results from output
         let (tests, failures) = parse_test_output(&stdout, &stderr);
+        let (mut errors, _) = parse_cargo_json_messages(&stdout);
+        for error in &mut errors {
+            if error.suggestion.is_none() {
+                error.suggestion = ErrorAnalyzer::suggest_fix(error);
+            }
+        }
 
         let passed = tests
             .iter()
@@ -218,8 +267,37 @@ impl Tool for CargoTest {
             .filter(|t| t.status == TestStatus::Ignored)
             .count();
 
+        let outcome =
+            if !errors.is_empty() || (tests.is_empty() && stderr.contains("could not compile")) {
+                TestOutcome::CompileError
+            } else if failed > 0 {
+                TestOutcome::TestsFailed
+            } else if !output.status.success() {
+                // A test binary can exit non-zero without a parsable result,
+                // e.g. when it aborts
+                if tests.is_empty() && !stderr.contains("Running ") {
+                    TestOutcome::CargoError
+                } else {
+                    TestOutcome::TestsFailed
+                }
+            } else {
+                TestOutcome::Passed
+            };
+        if outcome != TestOutcome::CompileError && outcome != TestOutcome::CargoError {
+            record_test_run(&tests);
+        }
+
+        // Compiler and libtest JSON lines are already parsed
+        let stdout: String = stdout
+            .lines()
+            .filter(|l| !l.starts_with('{'))
+            .flat_map(|l| [l, "\n"])
+            .collect();
+
         let result = CargoTestOutput {
-            success: output.status.success() && failed == 0,
+            success: outcome == TestOutcome::Passed,
+            outcome,
+            errors,
             summary: TestSummary {
                 passed,
                 failed,
@@ -261,6 +339,7 @@ impl Tool for CargoCheck {
     #[instrument(level = "info", skip(self, args), fields(tool_name = self.name()))]
     async fn execute(&self, args: Value) -> Result<Value> {
         let mut cmd = tokio::process::Command::new("cargo");
+        cmd.kill_on_drop(true);
         cmd.arg("check");
         cmd.arg("--message-format=json");
 
@@ -368,6 +447,7 @@ impl Tool for CargoClippy {
     #[instrument(level = "info", skip(self, args), fields(tool_name = self.name()))]
     async fn execute(&self, args: Value) -> Result<Value> {
         let mut cmd = tokio::process::Command::new("cargo");
+        cmd.kill_on_drop(true);
         cmd.arg("clippy");
         cmd.arg("--message-format=json");
 
@@ -468,6 +548,7 @@ impl Tool for CargoFmt {
     #[instrument(level = "info", skip(self, args), fields(tool_name = self.name()))]
     async fn execute(&self, args: Value) -> Result<Value> {
         let mut cmd = tokio::process::Command::new("cargo");
+        cmd.kill_on_drop(true);
         cmd.arg("fmt");
 
         if args.get("all").and_then(|v| v.as_bool()).unwrap_or(true) {
@@ -488,104 +569,447 @@ impl Tool for CargoFmt {
     }
 }
 
+/// SPDX identifiers (or prefixes) of copyleft licenses. Weak copyleft
+/// such as LGPL and MPL is included: it still puts conditions on how the
+/// crate's own files are shared.
+const COPYLEFT_LICENSES: &[&str] = &[
+    "GPL", "AGPL", "LGPL", "MPL", "EPL", "EUPL", "CDDL", "OSL", "CC-BY-SA", "SSPL",
+];
+
+#[async_trait]
+impl Tool for CargoAdd {
+    fn name(&self) -> &str {
+        "cargo_add"
+    }
+
+    fn description(&self) -> &str {
+        "Add a dependency with cargo add instead of editing Cargo.toml by hand. Cargo picks the \
+         newest version matching `version` (default: latest). Returns the Cargo.toml diff, the \
+         resolved version and the crate's license, flagging copyleft licenses."
+    }
+
+    fn schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "required": ["crate"],
+            "properties": {
+                "crate": {"type": "string", "description": "Crate name, e.g. serde"},
+                "version": {"type": "string", "description": "Version requirement, e.g. 1.0 or ^0.4 (default: latest)"},
+                "features": {"type": "array", "items": {"type": "string"}, "description": "Features to enable"},
+                "dev": {"type": "boolean", "default": false, "description": "Add to dev-dependencies"},
+                "path": {"type": "string", "description": "Add a local crate from this directory instead of crates.io"},
+                "manifest_path": {"type": "string", "default": "Cargo.toml", "description": "Manifest to change"}
+            }
+        })
+    }
+
+    #[instrument(level = "info", skip(self, args), fields(tool_name = self.name()))]
+    async fn execute(&self, args: Value) -> Result<Value> {
+        let name = crate_arg(&args)?;
+        let manifest = manifest_arg(&args);
+        let before = read_manifest(&manifest).await?;
+
+        let mut cmd = tokio::process::Command::new("cargo");
+        cmd.kill_on_drop(true);
+        cmd.arg("add").arg("--manifest-path").arg(&manifest);
+        match args.get("version").and_then(|v| v.as_str()) {
+            Some(version) if !version.trim().is_empty() => {
+                cmd.arg(format!("{}@{}", name, version.trim()))
+            }
+            _ => cmd.arg(name),
+        };
+        if let Some(path) = args.get("path").and_then(|v| v.as_str()) {
+            cmd.arg("--path").arg(path);
+        }
+        let features: Vec<&str> = args
+            .get("features")
+            .and_then(|v| v.as_array())
+            .map(|f| f.iter().filter_map(|v| v.as_str()).collect())
+            .unwrap_or_default();
+        if !features.is_empty() {
+            cmd.arg("--features").arg(features.join(","));
+        }
+        if args.get("dev").and_then(|v| v.as_bool()).unwrap_or(false) {
+            cmd.arg("--dev");
+        }
+
+        let output = cmd.output().await.context("Failed to execute cargo add")?;
+        let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+        if !output.status.success() {
+            anyhow::bail!("cargo add {} failed: {}", name, stderr.trim());
+        }
+
+        let after = read_manifest(&manifest).await?;
+        let package = resolved_package(&manifest, name).await;
+        let version = package
+            .as_ref()
+            .and_then(|p| p["version"].as_str())
+            .map(str::to_string);
+        let license = package
+            .as_ref()
+            .and_then(|p| p["license"].as_str())
+            .map(str::to_string);
+        let warning = match &license {
+            Some(license) if is_copyleft(license) => Some(format!(
+                "{} is licensed {}, a copyleft license; check it is compatible with this \
+                 project's license before shipping",
+                name, license
+            )),
+            Some(_) => None,
+            None => Some(format!(
+                "License of {} unknown: its metadata is not in the local cargo cache yet",
+                name
+            )),
+        };
+
+        Ok(serde_json::json!({
+            "success": true,
+            "crate": name,
+            "version": version,
+            "license": license,
+            "warning": warning,
+            "diff": manifest_diff(&manifest, &before, &after),
+            "output": stderr.trim(),
+        }))
+    }
+}
+
+#[async_trait]
+impl Tool for CargoRemove {
+    fn name(&self) -> &str {
+        "cargo_remove"
+    }
+
+    fn description(&self) -> &str {
+        "Remove a dependency with cargo remove. Returns the Cargo.toml diff."
+    }
+
+    fn schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "required": ["crate"],
+            "properties": {
+                "crate": {"type": "string", "description": "Crate name to remove"},
+                "dev": {"type": "boolean", "default": false, "description": "Remove from dev-dependencies"},
+                "manifest_path": {"type": "string", "default": "Cargo.toml", "description": "Manifest to change"}
+            }
+        })
+    }
+
+    #[instrument(level = "info", skip(self, args), fields(tool_name = self.name()))]
+    async fn execute(&self, args: Value) -> Result<Value> {
+        let name = crate_arg(&args)?;
+        let manifest = manifest_arg(&args);
+        let before = read_manifest(&manifest).await?;
+
+        let mut cmd = tokio::process::Command::new("cargo");
+        cmd.kill_on_drop(true);
+        cmd.arg("remove")
+            .arg("--manifest-path")
+            .arg(&manifest)
+            .arg(name);
+        if args.get("dev").and_then(|v| v.as_bool()).unwrap_or(false) {
+            cmd.arg("--dev");
+        }
+
+        let output = cmd
+            .output()
+            .await
+            .context("Failed to execute cargo remove")?;
+        let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+        if !output.status.success() {
+            anyhow::bail!("cargo remove {} failed: {}", name, stderr.trim());
+        }
+
+        let after = read_manifest(&manifest).await?;
+        Ok(serde_json::json!({
+            "success": true,
+            "crate": name,
+            "diff": manifest_diff(&manifest, &before, &after),
+            "output": stderr.trim(),
+        }))
+    }
+}
+
+fn crate_arg(args: &Value) -> Result<&str> {
+    args.get("crate")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .context("Missing required argument: crate")
+}
+
+/// Manifest a dependency tool changes, `Cargo.toml` unless given
+pub(crate) fn manifest_arg(args: &Value) -> String {
+    args.get("manifest_path")
+        .and_then(|v| v.as_str())
+        .unwrap_or("Cargo.toml")
+        .to_string()
+}
+
+/// Outcome, counts and failing test names of a `cargo_test` result. The
+/// checkpoint logs this instead of the full result, whose summary would
+/// otherwise be cut off by the log's truncation.
+pub(crate) fn test_digest(result: &Value) -> Value {
+    let failed: Vec<&Value> = result["failures"]
+        .as_array()
+        .map(|failures| failures.iter().map(|f| &f["test_name"]).collect())
+        .unwrap_or_default();
+    serde_json::json!({
+        "outcome": result["outcome"],
+        "summary": result["summary"],
+        "failed_tests": failed,
+    })
+}
+
+async fn read_manifest(path: &str) -> Result<String> {
+    tokio::fs::read_to_string(path)
+        .await
+        .with_context(|| format!("Failed to read {}", path))
+}
+
+/// Unified diff of a manifest before and after a dependency change
+fn manifest_diff(path: &str, before: &str, after: &str) -> String {
+    similar::TextDiff::from_lines(before, after)
+        .unified_diff()
+        .context_radius(2)
+        .header(path, path)
+        .to_string()
+}
+
+/// The package `name` resolved to as a dependency of the manifest's package,
+/// from `cargo metadata --offline`. `None` when cargo has not downloaded its
+/// metadata yet.
+async fn resolved_package(manifest: &str, name: &str) -> Option<Value> {
+    let output = tokio::process::Command::new("cargo")
+        .kill_on_drop(true)
+        .args([
+            "metadata",
+            "--format-version",
+            "1",
+            "--offline",
+            "--manifest-path",
+        ])
+        .arg(manifest)
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let metadata: Value = serde_json::from_slice(&output.stdout).ok()?;
+    find_dependency(&metadata, name).cloned()
+}
+
+/// Find the package for dependency `name` of the metadata's root package.
+/// Falls back to any package with that name when there is no root, e.g. a
+/// virtual workspace manifest.
+fn find_dependency<'a>(metadata: &'a Value, name: &str) -> Option<&'a Value> {
+    let packages = metadata["packages"].as_array()?;
+    let by_id = |id: &str| packages.iter().find(|p| p["id"] == id);
+    let resolve = &metadata["resolve"];
+    let root_deps = resolve["root"].as_str().and_then(|root| {
+        resolve["nodes"]
+            .as_array()?
+            .iter()
+            .find(|n| n["id"] == root)?["deps"]
+            .as_array()
+    });
+    if let Some(deps) = root_deps {
+        return deps
+            .iter()
+            .filter_map(|d| by_id(d["pkg"].as_str()?))
+            .find(|p| p["name"] == name);
+    }
+    packages.iter().rev().find(|p| p["name"] == name)
+}
+
+/// Whether an SPDX license expression leaves no permissive choice. `MIT OR
+/// GPL-3.0` is fine; `GPL-3.0` and `MIT AND LGPL-2.1` are not.
+fn is_copyleft(expression: &str) -> bool {
+    let is_copyleft_id = |id: &str| {
+        let id = id.trim_matches(|c: char| c == '(' || c == ')' || c.is_whitespace());
+        COPYLEFT_LICENSES
+            .iter()
+            .any(|prefix| id.to_ascii_uppercase().starts_with(prefix))
+    };
+    // Crates.io also has legacy "MIT/Apache-2.0" style expressions
+    expression
+        .split(" OR ")
+        .flat_map(|alt| alt.split('/'))
+        .all(|alternative| alternative.split(" AND ").any(is_copyleft_id))
+}
+
+/// Record a finished run in the test dashboard
+fn record_test_run(tests: &[TestResult]) {
+    use crate::observability::test_dashboard::{Test, TestDashboard};
+
+    let results = tests
+        .iter()
+        .map(|result| {
+            let mut test = Test::new(result.name.clone());
+            let duration = chrono::Duration::milliseconds(result.duration_ms.unwrap_or(0) as i64);
+            match result.status {
+                TestStatus::Passed => test.pass(duration),
+                TestStatus::Failed => {
+                    test.fail(duration, result.failure_message.clone().unwrap_or_default());
+                    test.failure_location = result.failure_location.clone();
+                }
+                TestStatus::Ignored => test.ignore(),
+            }
+            test
+        })
+        .collect();
+    if let Ok(mut dashboard) = TestDashboard::global().lock() {
+        dashboard.record_run(results);
+    }
+}
+
 /// Parse test output into structured results
+///
+/// Understands both the human libtest format and `--format=json` events,
+/// and ignores cargo's own JSON messages.
 fn parse_test_output(stdout: &str, stderr: &str) -> (Vec<TestResult>, Vec<FailureDetail>) {
-    let mut tests = Vec::new();
-    let mut failures = Vec::new();
-    let mut current_failure: Option<FailureDetail> = None;
-    let mut in_failure_block = false;
-    let mut failure_output = String::new();
+    let mut tests: Vec<TestResult> = Vec::new();
+    let mut outputs: HashMap<String, String> = HashMap::new();
+    let mut current_block: Option<String> = None;
 
     // Combine stdout and stderr for parsing
     let combined = format!("{}\n{}", stdout, stderr);
 
     for line in combined.lines() {
-        // Parse test results: "test module::test_name ... ok"
-        if line.starts_with("test ")
-            && (line.contains(" ... ok")
-                || line.contains(" ... FAILED")
-                || line.contains(" ... ignored"))
-        {
-            let parts: Vec<&str> = line.split(" ... ").collect();
-            if parts.len() >= 2 {
-                let name = parts[0]
-                    .strip_prefix("test ")
-                    .unwrap_or(parts[0])
-                    .to_string();
-                let status = if parts[1].contains("ok") {
-                    TestStatus::Passed
-                } else if parts[1].contains("FAILED") {
-                    TestStatus::Failed
-                } else {
-                    TestStatus::Ignored
-                };
-
-                tests.push(TestResult {
-                    name: name.clone(),
-                    status: status.clone(),
-                    duration_ms: None,
-                    failure_message: None,
-                    failure_location: None,
-                });
-
-                if status == TestStatus::Failed {
-                    current_failure = Some(FailureDetail {
-                        test_name: name,
-                        message: String::new(),
-                        location: None,
-                        stdout: None,
-                    });
-                }
+        if line.starts_with('{') {
+            if let Some(test) = parse_libtest_json_event(line, &mut outputs) {
+                tests.push(test);
             }
+            continue;
         }
 
-        // Detect failure block start
-        if line.contains("---- ") && line.contains(" stdout ----") {
-            in_failure_block = true;
-            failure_output.clear();
+        // "---- module::test_name stdout ----" starts a failure's output
+        if let Some(name) = line
+            .strip_prefix("---- ")
+            .and_then(|l| l.strip_suffix(" stdout ----"))
+        {
+            current_block = Some(name.to_string());
+            outputs.entry(name.to_string()).or_default();
             continue;
         }
-
-        // Collect failure output
-        if in_failure_block {
-            if line.starts_with("----") {
-                in_failure_block = false;
-                if let Some(ref mut failure) = current_failure {
-                    failure.stdout = Some(failure_output.clone());
-                    // Extract panic message
-                    if let Some(panic_line) =
-                        failure_output.lines().find(|l| l.contains("panicked at"))
-                    {
-                        failure.message = panic_line.to_string();
-                        // Try to extract location
-                        if let Some(loc_start) = panic_line.find('\'') {
-                            if let Some(loc_end) = panic_line.rfind('\'') {
-                                failure.location =
-                                    Some(panic_line[loc_start + 1..loc_end].to_string());
-                            }
-                        }
-                    }
-                    failures.push(failure.clone());
-                    current_failure = None;
-                }
-            } else {
-                failure_output.push_str(line);
-                failure_output.push('\n');
+        if let Some(name) = &current_block {
+            if line == "failures:" || line.starts_with("test result:") || line == "----" {
+                current_block = None;
+            } else if let Some(output) = outputs.get_mut(name) {
+                output.push_str(line);
+                output.push('\n');
             }
+            continue;
         }
+
+        // "test module::test_name ... ok"
+        let Some((name, status)) = line
+            .strip_prefix("test ")
+            .and_then(|l| l.split_once(" ... "))
+        else {
+            continue;
+        };
+        let status = if status.starts_with("ok") {
+            TestStatus::Passed
+        } else if status.starts_with("FAILED") {
+            TestStatus::Failed
+        } else if status.starts_with("ignored") {
+            TestStatus::Ignored
+        } else {
+            continue;
+        };
+        tests.push(TestResult {
+            name: name.to_string(),
+            status,
+            duration_ms: None,
+            failure_message: None,
+            failure_location: None,
+        });
     }
 
-    // Handle any remaining failure
-    if let Some(mut failure) = current_failure {
-        if !failure_output.is_empty() {
-            failure.stdout = Some(failure_output);
-        }
-        failures.push(failure);
+    let mut failures = Vec::new();
+    for test in tests.iter_mut().filter(|t| t.status == TestStatus::Failed) {
+        let output = outputs.remove(&test.name).filter(|o| !o.trim().is_empty());
+        let (message, location) = output.as_deref().and_then(parse_panic).unwrap_or_default();
+        test.failure_message = (!message.is_empty()).then(|| message.clone());
+        test.failure_location = location.clone();
+        failures.push(FailureDetail {
+            test_name: test.name.clone(),
+            message,
+            location,
+            stdout: output.map(|o| {
+                let trimmed = o.trim_end();
+                match trimmed.char_indices().nth(MAX_FAILURE_OUTPUT_CHARS) {
+                    Some((cut, _)) => format!("{}\n[truncated]", &trimmed[..cut]),
+                    None => trimmed.to_string(),
+                }
+            }),
+        });
     }
 
     (tests, failures)
 }
 
+/// Parse one libtest `--format=json` event, collecting a failure's stdout
+fn parse_libtest_json_event(
+    line: &str,
+    outputs: &mut HashMap<String, String>,
+) -> Option<TestResult> {
+    let event: Value = serde_json::from_str(line).ok()?;
+    if event.get("type")?.as_str()? != "test" {
+        return None;
+    }
+    let name = event.get("name")?.as_str()?.to_string();
+    let status = match event.get("event")?.as_str()? {
+        "ok" => TestStatus::Passed,
+        "failed" | "timeout" => TestStatus::Failed,
+        "ignored" => TestStatus::Ignored,
+        _ => return None,
+    };
+    if let Some(stdout) = event.get("stdout").and_then(|s| s.as_str()) {
+        outputs.insert(name.clone(), stdout.to_string());
+    }
+    Some(TestResult {
+        name,
+        status,
+        duration_ms: event
+            .get("exec_time")
+            .and_then(|t| t.as_f64())
+            .map(|secs| (secs * 1000.0) as u64),
+        failure_message: None,
+        failure_location: None,
+    })
+}
+
+/// Extract the panic message and location from a failing test's output
+///
+/// Handles both `panicked at 'msg', file:line:col` and the newer
+/// `panicked at file:line:col:` followed by the message on its own lines.
+fn parse_panic(output: &str) -> Option<(String, Option<String>)> {
+    let mut lines = output.lines().skip_while(|l| !l.contains("panicked at"));
+    let panic_line = lines.next()?;
+    let rest = &panic_line[panic_line.find("panicked at ")? + "panicked at ".len()..];
+
+    if rest.starts_with('\'') {
+        let location = rest
+            .rsplit_once("', ")
+            .map(|(_, loc)| loc.trim().to_string());
+        return Some((panic_line.to_string(), location));
+    }
+    let location = rest.trim_end_matches(':').trim().to_string();
+    let payload: Vec<&str> = lines
+        .take_while(|l| !l.starts_with("note:") && !l.starts_with("stack backtrace:"))
+        .collect();
+    let message = if payload.is_empty() {
+        panic_line.to_string()
+    } else {
+        format!("{}\n{}", panic_line, payload.join("\n").trim_end())
+    };
+    Some((message, (!location.is_empty()).then_some(location)))
+}
+
 /// Parse cargo JSON messages into compiler errors and warnings
 /// This function is public to allow reuse by the verification module
 pub fn parse_cargo_json_messages(output: &str) -> (Vec<CompilerError>, Vec<CompilerError>) {
@@ -881,6 +1305,83 @@ mod tests {
         assert!(schema["properties"]["all"].is_object());
     }
 
+    #[test]
+    fn test_is_copyleft() {
+        assert!(is_copyleft("GPL-3.0-only"));
+        assert!(is_copyleft("MIT AND LGPL-2.1-or-later"));
+        assert!(is_copyleft("MPL-2.0"));
+        assert!(!is_copyleft("MIT OR Apache-2.0"));
+        assert!(!is_copyleft("MIT/Apache-2.0"));
+        assert!(!is_copyleft("MIT OR GPL-3.0"));
+        assert!(!is_copyleft("(MIT OR Apache-2.0) AND Unicode-DFS-2016"));
+    }
+
+    #[test]
+    fn test_find_dependency_uses_root_dependencies() {
+        let metadata = serde_json::json!({
+            "packages": [
+                {"id": "app", "name": "app", "version": "0.1.0"},
+                {"id": "rand@0.7", "name": "rand", "version": "0.7.3", "license": "MIT"},
+                {"id": "rand@0.8", "name": "rand", "version": "0.8.5", "license": "MIT OR Apache-2.0"}
+            ],
+            "resolve": {
+                "root": "app",
+                "nodes": [{"id": "app", "deps": [{"name": "rand", "pkg": "rand@0.7"}]}]
+            }
+        });
+        assert_eq!(
+            find_dependency(&metadata, "rand").unwrap()["version"],
+            "0.7.3"
+        );
+        assert!(find_dependency(&metadata, "serde").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cargo_add_and_remove_local_crate() {
+        let dir = tempfile::tempdir().unwrap();
+        for (name, license) in [("app", "MIT"), ("gpl_dep", "GPL-3.0-only")] {
+            let root = dir.path().join(name);
+            std::fs::create_dir_all(root.join("src")).unwrap();
+            std::fs::write(
+                root.join("Cargo.toml"),
+                format!(
+                    "[package]\nname = \"{}\"\nversion = \"0.1.0\"\nedition = \"2021\"\nlicense = \"{}\"\n\n[dependencies]\n",
+                    name, license
+                ),
+            )
+            .unwrap();
+            std::fs::write(root.join("src/lib.rs"), "").unwrap();
+        }
+        let manifest = dir.path().join("app/Cargo.toml");
+        let manifest = manifest.to_str().unwrap();
+
+        let added = CargoAdd
+            .execute(serde_json::json!({
+                "crate": "gpl_dep",
+                "path": dir.path().join("gpl_dep"),
+                "manifest_path": manifest
+            }))
+            .await
+            .unwrap();
+        assert_eq!(added["version"], "0.1.0");
+        assert_eq!(added["license"], "GPL-3.0-only");
+        assert!(added["warning"].as_str().unwrap().contains("copyleft"));
+        let diff = added["diff"].as_str().unwrap();
+        assert!(diff.contains("+gpl_dep = {"), "{}", diff);
+
+        let removed = CargoRemove
+            .execute(serde_json::json!({"crate": "gpl_dep", "manifest_path": manifest}))
+            .await
+            .unwrap();
+        assert!(removed["diff"].as_str().unwrap().contains("-gpl_dep = {"));
+
+        let err = CargoRemove
+            .execute(serde_json::json!({"crate": "gpl_dep", "manifest_path": manifest}))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("cargo remove gpl_dep failed"));
+    }
+
     #[test]
     fn test_parse_test_output_basic() {
         let stdout = "test tests::test_basic ... ok\ntest tests::test_fail ... FAILED\ntest tests::test_ignore ... ignored";
@@ -908,6 +1409,64 @@ thread 'tests::test_fail' panicked at 'assertion failed', src/lib.rs:10:5
         assert!(failures[0].message.contains("panicked"));
     }
 
+    #[test]
+    fn test_parse_test_output_keeps_each_failures_output() {
+        let stdout = "\
+running 3 tests
+test a::first ... FAILED
+test a::second ... FAILED
+test a::third ... ok
+
+failures:
+
+---- a::first stdout ----
+thread 'a::first' panicked at src/a.rs:10:5:
+assertion `left == right` failed
+  left: 1
+ right: 2
+note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace
+
+---- a::second stdout ----
+thread 'a::second' panicked at 'boom', src/a.rs:20:9
+
+failures:
+    a::first
+    a::second
+
+test result: FAILED. 1 passed; 2 failed; 0 ignored; 0 measured; 0 filtered out
+";
+        let (tests, failures) = parse_test_output(stdout, "");
+
+        assert_eq!(tests.len(), 3);
+        assert_eq!(failures.len(), 2);
+        assert_eq!(failures[0].test_name, "a::first");
+        assert_eq!(failures[0].location.as_deref(), Some("src/a.rs:10:5"));
+        assert!(failures[0].message.contains("left: 1"));
+        assert!(!failures[0].message.contains("note:"));
+        assert_eq!(failures[1].test_name, "a::second");
+        assert_eq!(failures[1].location.as_deref(), Some("src/a.rs:20:9"));
+        assert!(failures[1].stdout.as_deref().unwrap().contains("boom"));
+        assert_eq!(tests[0].failure_location.as_deref(), Some("src/a.rs:10:5"));
+    }
+
+    #[test]
+    fn test_parse_test_output_libtest_json() {
+        let stdout = r#"{"reason":"compiler-artifact","target":{"name":"demo"}}
+{ "type": "suite", "event": "started", "test_count": 2 }
+{ "type": "test", "event": "started", "name": "a::ok" }
+{ "type": "test", "name": "a::ok", "event": "ok", "exec_time": 0.25 }
+{ "type": "test", "name": "a::bad", "event": "failed", "exec_time": 0.001, "stdout": "thread 'a::bad' panicked at src/a.rs:3:5:\nexplicit panic\n" }
+{ "type": "suite", "event": "failed", "passed": 1, "failed": 1 }"#;
+        let (tests, failures) = parse_test_output(stdout, "");
+
+        assert_eq!(tests.len(), 2);
+        assert_eq!(tests[0].status, TestStatus::Passed);
+        assert_eq!(tests[0].duration_ms, Some(250));
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].location.as_deref(), Some("src/a.rs:3:5"));
+        assert!(failures[0].message.contains("explicit panic"));
+    }
+
     #[test]
     fn test_parse_compiler_message() {
         let json = serde_json::json!({
@@ -1161,6 +1720,8 @@ thread 'tests::test_fail' panicked at 'assertion failed', src/lib.rs:10:5
     fn test_cargo_test_output_struct() {
         let output = CargoTestOutput {
             success: true,
+            outcome: TestOutcome::Passed,
+            errors: vec![],
             summary: TestSummary {
                 passed: 5,
                 failed: 0,
@@ -1475,6 +2036,8 @@ test result: FAILED. 0 passed; 1 failed; 0 ignored"#;
     fn test_cargo_test_output_with_failures() {
         let output = CargoTestOutput {
             success: false,
+            outcome: TestOutcome::TestsFailed,
+            errors: vec![],
             summary: TestSummary {
                 passed: 5,
                 failed: 2,
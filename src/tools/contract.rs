@@ -0,0 +1,199 @@
+//! Consumer-driven contract tools
+//!
+//! `contract_generate` records what a consumer service expects from a
+//! provider in the same repository; `contract_verify` checks the provider
+//! still satisfies it, e.g. after changing an API.
+
+use super::Tool;
+use crate::testing::contract_testing::{contract_generate, contract_verify, SourceContract};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// Directory contracts are written to unless an output path is given
+const CONTRACTS_DIR: &str = "contracts";
+
+pub struct ContractGenerate;
+pub struct ContractVerify;
+
+#[async_trait]
+impl Tool for ContractGenerate {
+    fn name(&self) -> &str {
+        "contract_generate"
+    }
+
+    fn description(&self) -> &str {
+        "Generate a consumer-driven contract by scanning the consumer's API client code for the \
+         endpoints it calls and the response fields it reads. Writes inspectable JSON to \
+         contracts/<consumer>-<provider>.json. Use before changing an API between services."
+    }
+
+    fn schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "required": ["consumer", "provider"],
+            "properties": {
+                "consumer": {
+                    "type": "string",
+                    "description": "Directory of the consuming service"
+                },
+                "provider": {
+                    "type": "string",
+                    "description": "Directory of the service it calls"
+                },
+                "output": {
+                    "type": "string",
+                    "description": "Where to write the contract (default: contracts/<consumer>-<provider>.json)"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: Value) -> Result<Value> {
+        let consumer = required_str(&args, "consumer")?.to_string();
+        let provider = required_str(&args, "provider")?.to_string();
+        let output = args
+            .get("output")
+            .and_then(|v| v.as_str())
+            .map(PathBuf::from);
+
+        tokio::task::spawn_blocking(move || -> Result<Value> {
+            let contract = contract_generate(Path::new(&consumer), Path::new(&provider))?;
+            let output = output.unwrap_or_else(|| {
+                Path::new(CONTRACTS_DIR)
+                    .join(format!("{}-{}.json", contract.consumer, contract.provider))
+            });
+            if let Some(parent) = output.parent().filter(|p| !p.as_os_str().is_empty()) {
+                std::fs::create_dir_all(parent)
+                    .with_context(|| format!("Failed to create {}", parent.display()))?;
+            }
+            std::fs::write(&output, contract.to_json())
+                .with_context(|| format!("Failed to write {}", output.display()))?;
+
+            Ok(serde_json::json!({
+                "path": output.display().to_string(),
+                "consumer": contract.consumer,
+                "provider": contract.provider,
+                "interactions": contract.interactions.iter().map(|i| serde_json::json!({
+                    "endpoint": format!("{} {}", i.method.as_str(), i.path),
+                    "source": i.source,
+                    "fields": i.response.keys().collect::<Vec<_>>(),
+                })).collect::<Vec<_>>(),
+            }))
+        })
+        .await?
+    }
+}
+
+#[async_trait]
+impl Tool for ContractVerify {
+    fn name(&self) -> &str {
+        "contract_verify"
+    }
+
+    fn description(&self) -> &str {
+        "Verify a provider still satisfies a contract written by contract_generate. Reports \
+         breaking changes (removed endpoints or fields, changed types) separately from additive \
+         ones. Run after changing an API another service consumes."
+    }
+
+    fn schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "required": ["contract"],
+            "properties": {
+                "contract": {
+                    "type": "string",
+                    "description": "Path to the contract JSON"
+                },
+                "provider": {
+                    "type": "string",
+                    "description": "Provider directory (default: the one recorded in the contract)"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: Value) -> Result<Value> {
+        let contract_path = required_str(&args, "contract")?.to_string();
+        let provider = args
+            .get("provider")
+            .and_then(|v| v.as_str())
+            .map(String::from);
+
+        tokio::task::spawn_blocking(move || -> Result<Value> {
+            let json = std::fs::read_to_string(&contract_path)
+                .with_context(|| format!("Failed to read contract {}", contract_path))?;
+            let contract = SourceContract::from_json(&json)?;
+            let provider = provider.unwrap_or_else(|| contract.provider_path.clone());
+            let report = contract_verify(&contract, Path::new(&provider))?;
+
+            Ok(serde_json::json!({
+                "satisfied": report.is_satisfied(),
+                "consumer": report.consumer,
+                "provider": report.provider,
+                "breaking": report.breaking(),
+                "additive": report.additive(),
+            }))
+        })
+        .await?
+    }
+}
+
+fn required_str<'a>(args: &'a Value, key: &str) -> Result<&'a str> {
+    args.get(key)
+        .and_then(|v| v.as_str())
+        .with_context(|| format!("Missing required parameter: {}", key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_contract_tools_generate_then_verify() {
+        let dir = tempfile::tempdir().unwrap();
+        let consumer = dir.path().join("web");
+        let provider = dir.path().join("api");
+        std::fs::create_dir_all(&consumer).unwrap();
+        std::fs::create_dir_all(&provider).unwrap();
+        std::fs::write(
+            consumer.join("client.rs"),
+            "struct Item { id: u32 }\nlet item: Item = client.get(\"/items/1\").send()?.json()?;",
+        )
+        .unwrap();
+        std::fs::write(
+            provider.join("routes.rs"),
+            ".route(\"/items/:id\", get(item))\nasync fn item() -> Json<Item> {}\nstruct Item { id: u32 }",
+        )
+        .unwrap();
+
+        let output = dir.path().join("contracts/web-api.json");
+        let generated = ContractGenerate
+            .execute(serde_json::json!({
+                "consumer": consumer.display().to_string(),
+                "provider": provider.display().to_string(),
+                "output": output.display().to_string(),
+            }))
+            .await
+            .unwrap();
+        assert_eq!(generated["interactions"][0]["endpoint"], "GET /items/{}");
+        assert_eq!(generated["interactions"][0]["fields"][0], "id");
+
+        let args = serde_json::json!({"contract": output.display().to_string()});
+        let verified = ContractVerify.execute(args.clone()).await.unwrap();
+        assert_eq!(verified["satisfied"], true);
+
+        std::fs::write(
+            provider.join("routes.rs"),
+            ".route(\"/items/:id\", get(item))\nasync fn item() -> Json<Item> {}\nstruct Item { id: String }",
+        )
+        .unwrap();
+        let verified = ContractVerify.execute(args).await.unwrap();
+        assert_eq!(verified["satisfied"], false);
+        assert_eq!(verified["breaking"][0]["change_type"], "TypeChanged");
+
+        assert!(ContractVerify.execute(serde_json::json!({})).await.is_err());
+    }
+}
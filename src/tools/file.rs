@@ -1,15 +1,13 @@
 use super::Tool;
 use crate::config::SafetyConfig;
-use crate::safety::path_validator::PathValidator;
+use crate::safety::path_validator::{CapabilityRoots, PathValidator};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use serde::Deserialize;
 use serde_json::Value;
 use std::fs;
-use std::io::Write;
-use std::path::Path;
-use std::sync::OnceLock;
-use tempfile::NamedTempFile;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
 
 /// Global safety configuration set at startup from the user-loaded config.
 /// `validate_tool_path` reads from this so that user-configured `allowed_paths`
@@ -38,6 +36,8 @@ pub fn init_safety_config(config: &SafetyConfig) {
 const MAX_READ_SIZE: u64 = 50 * 1024 * 1024;
 /// Maximum file size for writes (10 MB) to prevent accidentally writing huge files.
 const MAX_WRITE_SIZE: usize = 10 * 1024 * 1024;
+/// Default ceiling on the content a single `file_read` call returns (256 KB).
+const DEFAULT_MAX_READ_BYTES: usize = 256 * 1024;
 
 /// Read file contents. Supports optional per-instance safety configuration
 /// for multi-agent scenarios via [`FileRead::with_safety_config`].
@@ -46,6 +46,9 @@ pub struct FileRead {
     /// Per-instance safety config. When `Some`, overrides the global `SAFETY_CONFIG`.
     /// When `None`, falls back to the global or default config (backward compatible).
     pub safety_config: Option<SafetyConfig>,
+    /// Ceiling on returned content in bytes. `None` uses [`DEFAULT_MAX_READ_BYTES`].
+    pub max_read_bytes: Option<usize>,
+    roots: ToolRoots,
 }
 
 /// Write or overwrite entire file. Supports optional per-instance safety configuration
@@ -54,6 +57,7 @@ pub struct FileRead {
 pub struct FileWrite {
     /// Per-instance safety config. When `Some`, overrides the global `SAFETY_CONFIG`.
     pub safety_config: Option<SafetyConfig>,
+    roots: ToolRoots,
 }
 
 /// Apply surgical edit to file. Supports optional per-instance safety configuration
@@ -62,6 +66,7 @@ pub struct FileWrite {
 pub struct FileEdit {
     /// Per-instance safety config. When `Some`, overrides the global `SAFETY_CONFIG`.
     pub safety_config: Option<SafetyConfig>,
+    roots: ToolRoots,
 }
 
 /// Delete a file. Supports optional per-instance safety configuration
@@ -70,6 +75,7 @@ pub struct FileEdit {
 pub struct FileDelete {
     /// Per-instance safety config. When `Some`, overrides the global `SAFETY_CONFIG`.
     pub safety_config: Option<SafetyConfig>,
+    roots: ToolRoots,
 }
 
 /// List directory structure. Supports optional per-instance safety configuration
@@ -90,23 +96,36 @@ pub struct DirectoryTree {
 
 impl FileRead {
     pub fn new() -> Self {
-        Self::default()
+        Self {
+            safety_config: None,
+            max_read_bytes: None,
+            roots: ToolRoots::default(),
+        }
     }
     pub fn with_safety_config(config: SafetyConfig) -> Self {
         Self {
+            roots: ToolRoots::default(),
             safety_config: Some(config),
+            max_read_bytes: None,
         }
     }
+    /// Set the per-call content ceiling (usually `agent.max_read_bytes`).
+    pub fn with_max_read_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_read_bytes = Some(max_bytes);
+        self
+    }
 }
 
 impl FileWrite {
     pub fn new() -> Self {
         Self {
             safety_config: None,
+            roots: ToolRoots::default(),
         }
     }
     pub fn with_safety_config(config: SafetyConfig) -> Self {
         Self {
+            roots: ToolRoots::default(),
             safety_config: Some(config),
         }
     }
@@ -116,10 +135,12 @@ impl FileEdit {
     pub fn new() -> Self {
         Self {
             safety_config: None,
+            roots: ToolRoots::default(),
         }
     }
     pub fn with_safety_config(config: SafetyConfig) -> Self {
         Self {
+            roots: ToolRoots::default(),
             safety_config: Some(config),
         }
     }
@@ -129,10 +150,12 @@ impl FileDelete {
     pub fn new() -> Self {
         Self {
             safety_config: None,
+            roots: ToolRoots::default(),
         }
     }
     pub fn with_safety_config(config: SafetyConfig) -> Self {
         Self {
+            roots: ToolRoots::default(),
             safety_config: Some(config),
         }
     }
@@ -158,7 +181,8 @@ impl Tool for FileRead {
     }
 
     fn description(&self) -> &str {
-        "Read file contents. Use for examining code, configs, or any text file."
+        "Read file contents. Use for examining code, configs, or any text file. \
+         Large files are returned a page at a time; pass start_line to continue."
     }
 
     fn schema(&self) -> Value {
@@ -169,6 +193,18 @@ impl Tool for FileRead {
                     "type": "string",
                     "description": "Absolute or relative path to the file"
                 },
+                "start_line": {
+                    "type": "integer",
+                    "description": "First line to return (1-indexed, default 1)"
+                },
+                "end_line": {
+                    "type": "integer",
+                    "description": "Last line to return (inclusive, default end of file)"
+                },
+                "max_bytes": {
+                    "type": "integer",
+                    "description": "Cap on returned content in bytes (never above the configured ceiling)"
+                },
                 "line_range": {
                     "type": "array",
                     "items": {"type": "integer"},
@@ -186,45 +222,119 @@ impl Tool for FileRead {
         struct Args {
             path: String,
             line_range: Option<(usize, usize)>,
+            start_line: Option<usize>,
+            end_line: Option<usize>,
+            max_bytes: Option<usize>,
         }
 
         let args: Args = serde_json::from_value(args)?;
         validate_tool_path(&args.path, self.safety_config.as_ref())?;
-        let path = Path::new(&args.path);
+        let roots = self.roots.current(self.safety_config.as_ref())?;
+        let (dir, rel) = roots.resolve(&args.path)?;
+        let mut file = dir
+            .open_within(&rel)
+            .with_context(|| format!("Failed to read file: {}", args.path))?;
 
         // Check file size before reading to prevent OOM on huge files
-        if let Ok(metadata) = fs::metadata(path) {
-            if metadata.len() > MAX_READ_SIZE {
-                anyhow::bail!(
-                    "File too large to read: {} bytes (limit: {} bytes)",
-                    metadata.len(),
-                    MAX_READ_SIZE
-                );
-            }
+        let size = file.metadata()?.len();
+        if size > MAX_READ_SIZE {
+            anyhow::bail!(
+                "File too large to read: {} bytes (limit: {} bytes)",
+                size,
+                MAX_READ_SIZE
+            );
         }
 
-        let content = fs::read_to_string(path)
+        let content = file
+            .read_to_string()
             .with_context(|| format!("Failed to read file: {}", args.path))?;
 
         let total_lines = content.lines().count();
-        let (start, end) = args.line_range.unwrap_or((1, total_lines));
-
-        let selected_content: String = content
-            .lines()
-            .skip(start.saturating_sub(1))
-            .take(end.saturating_sub(start) + 1)
-            .collect::<Vec<_>>()
-            .join("\n");
-
-        Ok(serde_json::json!({
-            "content": selected_content,
+        let (start, end) = if args.start_line.is_some() || args.end_line.is_some() {
+            (
+                args.start_line.unwrap_or(1),
+                args.end_line.unwrap_or(total_lines),
+            )
+        } else {
+            args.line_range.unwrap_or((1, total_lines))
+        };
+
+        let ceiling = self.max_read_bytes.unwrap_or(DEFAULT_MAX_READ_BYTES);
+        let budget = args.max_bytes.map_or(ceiling, |b| b.min(ceiling)).max(1);
+        let first_line = start.max(1);
+        let page = read_page(&content, first_line, end.saturating_sub(start) + 1, budget);
+        let last_line = first_line + page.lines_shown.saturating_sub(1);
+
+        let mut result = serde_json::json!({
+            "content": page.content,
             "total_lines": total_lines,
-            "truncated": args.line_range.is_some(),
+            "truncated": page.lines_shown < total_lines || page.cut_line,
             "encoding": "utf-8"
-        }))
+        });
+        if page.lines_shown > 0 {
+            result["start_line"] = serde_json::json!(first_line);
+            result["end_line"] = serde_json::json!(last_line);
+            result["header"] = serde_json::json!(format!(
+                "Lines {}-{} of {}{}",
+                first_line,
+                last_line,
+                total_lines,
+                if page.cut_line {
+                    " (line cut at byte limit)"
+                } else {
+                    ""
+                }
+            ));
+            if last_line < total_lines {
+                result["next_start_line"] = serde_json::json!(last_line + 1);
+                result["more"] = serde_json::json!(format!(
+                    "{} more lines available; call file_read with start_line={} to continue",
+                    total_lines - last_line,
+                    last_line + 1
+                ));
+            }
+        }
+        Ok(result)
     }
 }
 
+/// One page of `file_read` output.
+struct ReadPage {
+    content: String,
+    lines_shown: usize,
+    /// True when a single line was longer than the byte budget and had to be cut.
+    cut_line: bool,
+}
+
+/// Take up to `count` lines starting at 1-indexed `start`, stopping before the
+/// joined content would exceed `budget` bytes. A first line that alone exceeds
+/// the budget is cut on a UTF-8 character boundary so a page is never empty.
+fn read_page(content: &str, start: usize, count: usize, budget: usize) -> ReadPage {
+    let mut page = ReadPage {
+        content: String::new(),
+        lines_shown: 0,
+        cut_line: false,
+    };
+    for line in content.lines().skip(start - 1).take(count) {
+        let separator = usize::from(page.lines_shown > 0);
+        if page.content.len() + separator + line.len() > budget {
+            if page.lines_shown == 0 {
+                page.content
+                    .push_str(&line[..line.floor_char_boundary(budget)]);
+                page.lines_shown = 1;
+                page.cut_line = true;
+            }
+            break;
+        }
+        if separator == 1 {
+            page.content.push('\n');
+        }
+        page.content.push_str(line);
+        page.lines_shown += 1;
+    }
+    page
+}
+
 #[async_trait]
 impl Tool for FileWrite {
     fn name(&self) -> &str {
@@ -232,7 +342,9 @@ impl Tool for FileWrite {
     }
 
     fn description(&self) -> &str {
-        "Write or overwrite entire file. Creates parent directories if needed."
+        "Create a file with the given content, creating parent directories unless create_dirs is \
+         false. Refuses to replace an existing file unless overwrite is true; to change part of \
+         a file, use file_edit instead."
     }
 
     fn schema(&self) -> Value {
@@ -241,6 +353,8 @@ impl Tool for FileWrite {
             "properties": {
                 "path": {"type": "string"},
                 "content": {"type": "string"},
+                "overwrite": {"type": "boolean", "default": false, "description": "Replace the file if it already exists"},
+                "create_dirs": {"type": "boolean", "default": true, "description": "Create missing parent directories"},
                 "backup": {"type": "boolean", "default": true}
             },
             "required": ["path", "content"]
@@ -254,11 +368,27 @@ impl Tool for FileWrite {
             content: String,
             #[serde(default = "default_true")]
             backup: bool,
+            #[serde(default)]
+            overwrite: bool,
+            #[serde(default = "default_true")]
+            create_dirs: bool,
         }
 
         let args: Args = serde_json::from_value(args)?;
         validate_tool_path(&args.path, self.safety_config.as_ref())?;
-        let path = Path::new(&args.path);
+        let roots = self.roots.current(self.safety_config.as_ref())?;
+        let (dir, rel) = roots.resolve(&args.path)?;
+
+        if !args.create_dirs {
+            if let Some(parent) = rel.parent().filter(|p| !p.as_os_str().is_empty()) {
+                if !dir.exists_within(parent)? {
+                    anyhow::bail!(
+                        "Parent directory {} does not exist; pass create_dirs: true to create it",
+                        dir.root().join(parent).display()
+                    );
+                }
+            }
+        }
 
         // Check write size limit to prevent accidentally writing huge files
         if args.content.len() > MAX_WRITE_SIZE {
@@ -269,22 +399,36 @@ impl Tool for FileWrite {
             );
         }
 
+        // Only a path that is known not to exist counts as new; an existing
+        // file that cannot be read must not slip past the overwrite guard
+        let exists = dir.exists_within(&rel)?;
+        if exists && !args.overwrite {
+            anyhow::bail!(
+                "{} already exists. Use file_edit to change it, or pass overwrite: true to replace it entirely.",
+                args.path
+            );
+        }
+
         // Detect no-op writes (content identical to existing file)
-        if path.exists() {
-            if let Ok(existing) = fs::read_to_string(path) {
-                if existing == args.content {
-                    anyhow::bail!("file_write is a no-op — the file already has this exact content. You need to change the content to make an actual modification.");
-                }
+        let existing = if exists {
+            Some(dir.open_within(&rel)?.read_to_end()?)
+        } else {
+            None
+        };
+        if let Some(existing) = &existing {
+            if existing == args.content.as_bytes() {
+                anyhow::bail!("file_write is a no-op — the file already has this exact content. You need to change the content to make an actual modification.");
             }
         }
 
         // Create backup if exists
-        if args.backup && path.exists() {
-            let backup_path = format!("{}.bak", args.path);
-            fs::copy(path, &backup_path)?;
+        if let (true, Some(existing)) = (args.backup, &existing) {
+            let mut backup = rel.clone().into_os_string();
+            backup.push(".bak");
+            dir.write_within(backup, existing)?;
         }
 
-        write_atomic(path, &args.content)?;
+        dir.write_within(&rel, args.content.as_bytes())?;
 
         Ok(serde_json::json!({
             "success": true,
@@ -326,7 +470,9 @@ impl Tool for FileEdit {
 
         let args: Args = serde_json::from_value(args)?;
         validate_tool_path(&args.path, self.safety_config.as_ref())?;
-        let content = fs::read_to_string(&args.path)?;
+        let roots = self.roots.current(self.safety_config.as_ref())?;
+        let (dir, rel) = roots.resolve(&args.path)?;
+        let content = dir.open_within(&rel)?.read_to_string()?;
 
         // Check for exactly one match
         let matches = content.matches(&args.old_str).count();
@@ -341,7 +487,7 @@ impl Tool for FileEdit {
         }
 
         let new_content = content.replace(&args.old_str, &args.new_str);
-        write_atomic(Path::new(&args.path), &new_content)?;
+        dir.write_within(&rel, new_content.as_bytes())?;
 
         Ok(serde_json::json!({
             "success": true,
@@ -382,16 +528,13 @@ impl Tool for FileDelete {
 
         let args: Args = serde_json::from_value(args)?;
         validate_tool_path(&args.path, self.safety_config.as_ref())?;
-        let path = Path::new(&args.path);
+        let roots = self.roots.current(self.safety_config.as_ref())?;
+        let (dir, rel) = roots.resolve(&args.path)?;
 
-        if !path.exists() {
+        if !dir.exists_within(&rel)? {
             anyhow::bail!("File not found: {}", args.path);
         }
-        if path.is_dir() {
-            anyhow::bail!("Path is a directory, not a file: {}", args.path);
-        }
-
-        fs::remove_file(path).with_context(|| format!("Failed to delete file: {}", args.path))?;
+        dir.remove_within(&rel)?;
 
         Ok(serde_json::json!({
             "deleted": true,
@@ -407,7 +550,9 @@ impl Tool for DirectoryTree {
     }
 
     fn description(&self) -> &str {
-        "List directory structure. Use to understand project layout."
+        "List directory structure. Use to understand project layout. Large or deep directories are \
+         collapsed into summaries (file count, LOC, main extensions); drill in by calling again on \
+         that path or with an `include` glob."
     }
 
     fn schema(&self) -> Value {
@@ -415,8 +560,11 @@ impl Tool for DirectoryTree {
             "type": "object",
             "properties": {
                 "path": {"type": "string"},
-                "max_depth": {"type": "integer", "default": 3},
-                "include_hidden": {"type": "boolean", "default": false}
+                "max_depth": {"type": "integer", "default": 3, "description": "Directories deeper than this are summarized, not expanded"},
+                "include_hidden": {"type": "boolean", "default": false},
+                "max_entries": {"type": "integer", "default": 500, "description": "Stop listing after this many entries"},
+                "summarize_over": {"type": "integer", "default": 200, "description": "Collapse directories containing more files than this"},
+                "include": {"type": "string", "description": "Glob (relative to path) whose matches are always expanded, e.g. \"src/tools/**\""}
             },
             "required": ["path"]
         })
@@ -430,51 +578,354 @@ impl Tool for DirectoryTree {
             max_depth: usize,
             #[serde(default)]
             include_hidden: bool,
+            #[serde(default = "default_max_entries")]
+            max_entries: usize,
+            #[serde(default = "default_summarize_over")]
+            summarize_over: usize,
+            include: Option<String>,
         }
 
         let args: Args = serde_json::from_value(args)?;
         validate_tool_path(&args.path, self.safety_config.as_ref())?;
 
-        let mut entries = vec![];
-        for entry in walkdir::WalkDir::new(&args.path)
-            .max_depth(args.max_depth)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
-            let path = entry.path();
-            let metadata = entry.metadata()?;
-
-            if !args.include_hidden
-                && entry
-                    .file_name()
-                    .to_str()
-                    .map(|s| s.starts_with('.'))
-                    .unwrap_or(false)
-            {
-                continue;
-            }
-
-            entries.push(serde_json::json!({
-                "path": path.display().to_string(),
+        let include = args
+            .include
+            .as_deref()
+            .map(glob::Pattern::new)
+            .transpose()
+            .context("Invalid include glob")?;
+
+        let root = Path::new(&args.path);
+        let mut walker = TreeWalker {
+            root,
+            include_hidden: args.include_hidden,
+            max_depth: args.max_depth,
+            max_entries: args.max_entries,
+            summarize_over: args.summarize_over,
+            include,
+            entries: Vec::new(),
+            collapsed: 0,
+            truncated: false,
+            loc_budget: LOC_SCAN_BUDGET,
+            scanned_bytes: 0,
+            scanned_lines: 0,
+        };
+        if let Ok(metadata) = fs::metadata(root) {
+            walker.push(serde_json::json!({
+                "path": root.display().to_string(),
                 "type": if metadata.is_dir() { "directory" } else { "file" },
                 "size": metadata.len()
             }));
+            if metadata.is_dir() {
+                let tree = walker.scan(root);
+                walker.visit(&tree, 0);
+            }
         }
 
         Ok(serde_json::json!({
             "root": args.path,
-            "entries": entries,
-            "total": entries.len()
+            "total": walker.entries.len(),
+            "collapsed": walker.collapsed,
+            "truncated": walker.truncated,
+            "entries": walker.entries,
         }))
     }
 }
 
+/// Files larger than this are counted but not scanned for line counts.
+const LOC_SCAN_LIMIT: u64 = 1024 * 1024;
+/// Bytes one `directory_tree` call reads in total to count lines; past it,
+/// line counts are estimated from file sizes.
+const LOC_SCAN_BUDGET: u64 = 8 * 1024 * 1024;
+/// Bytes per line assumed for estimates before any file has been scanned.
+const DEFAULT_LINE_BYTES: u64 = 40;
+
+/// A directory scanned once, bottom-up, so expand/collapse decisions read
+/// aggregates instead of re-walking the subtree.
+#[derive(Default)]
+struct ScannedDir {
+    children: Vec<(PathBuf, u64, ScannedEntry)>,
+    /// Files and directories anywhere below, hidden ones excluded
+    files: usize,
+    dirs: usize,
+    bytes: u64,
+    extensions: std::collections::HashMap<String, usize>,
+    /// The include glob matches this directory or something below it
+    included: bool,
+}
+
+enum ScannedEntry {
+    File,
+    /// Listed and counted as a file, never followed
+    Symlink,
+    Dir(ScannedDir),
+}
+
+/// Depth-first walk for [`DirectoryTree`] that collapses large directories.
+struct TreeWalker<'a> {
+    root: &'a Path,
+    include_hidden: bool,
+    max_depth: usize,
+    max_entries: usize,
+    summarize_over: usize,
+    include: Option<glob::Pattern>,
+    entries: Vec<Value>,
+    collapsed: usize,
+    truncated: bool,
+    /// Bytes still available for line counting
+    loc_budget: u64,
+    /// Bytes and lines of the text files counted so far, for estimates
+    scanned_bytes: u64,
+    scanned_lines: usize,
+}
+
+impl TreeWalker<'_> {
+    fn push(&mut self, entry: Value) -> bool {
+        if self.entries.len() >= self.max_entries {
+            self.truncated = true;
+            return false;
+        }
+        self.entries.push(entry);
+        true
+    }
+
+    fn is_hidden(&self, path: &Path) -> bool {
+        !self.include_hidden
+            && path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .map(|n| n.starts_with('.'))
+                .unwrap_or(false)
+    }
+
+    fn relative<'p>(&self, path: &'p Path) -> &'p Path {
+        path.strip_prefix(self.root).unwrap_or(path)
+    }
+
+    fn is_included(&self, path: &Path) -> bool {
+        self.include
+            .as_ref()
+            .is_some_and(|pattern| pattern.matches_path(self.relative(path)))
+    }
+
+    /// Scan `dir` and everything below it without following symlinks.
+    fn scan(&self, dir: &Path) -> ScannedDir {
+        let mut node = ScannedDir::default();
+        let Ok(read_dir) = fs::read_dir(dir) else {
+            return node;
+        };
+        let mut children: Vec<_> = read_dir.filter_map(|e| e.ok()).map(|e| e.path()).collect();
+        children.sort();
+
+        for path in children {
+            if self.is_hidden(&path) {
+                continue;
+            }
+            let Ok(metadata) = fs::symlink_metadata(&path) else {
+                continue;
+            };
+            let entry = if metadata.is_dir() {
+                let mut sub = self.scan(&path);
+                sub.included |= self.is_included(&path);
+                node.files += sub.files;
+                node.dirs += sub.dirs + 1;
+                node.bytes += sub.bytes;
+                node.included |= sub.included;
+                for (ext, count) in &sub.extensions {
+                    *node.extensions.entry(ext.clone()).or_insert(0) += count;
+                }
+                ScannedEntry::Dir(sub)
+            } else {
+                node.files += 1;
+                node.bytes += metadata.len();
+                node.included |= self.is_included(&path);
+                let ext = path
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .unwrap_or("")
+                    .to_string();
+                *node.extensions.entry(ext).or_insert(0) += 1;
+                if metadata.file_type().is_symlink() {
+                    ScannedEntry::Symlink
+                } else {
+                    ScannedEntry::File
+                }
+            };
+            node.children.push((path, metadata.len(), entry));
+        }
+        node
+    }
+
+    fn visit(&mut self, dir: &ScannedDir, depth: usize) {
+        for (path, size, entry) in &dir.children {
+            if self.truncated {
+                return;
+            }
+            let sub = match entry {
+                ScannedEntry::File | ScannedEntry::Symlink => {
+                    let kind = if matches!(entry, ScannedEntry::File) {
+                        "file"
+                    } else {
+                        "symlink"
+                    };
+                    self.push(serde_json::json!({
+                        "path": path.display().to_string(),
+                        "type": kind,
+                        "size": size
+                    }));
+                    continue;
+                }
+                ScannedEntry::Dir(sub) => sub,
+            };
+
+            let expand =
+                sub.included || (depth + 1 < self.max_depth && sub.files <= self.summarize_over);
+            if expand {
+                if self.push(serde_json::json!({
+                    "path": path.display().to_string(),
+                    "type": "directory",
+                    "size": size
+                })) {
+                    self.visit(sub, depth + 1);
+                }
+            } else {
+                let summary = self.summarize(sub);
+                if self.push(serde_json::json!({
+                    "path": path.display().to_string(),
+                    "type": "directory",
+                    "size": size,
+                    "collapsed": true,
+                    "summary": summary.to_json(),
+                    "description": summary.describe(&self.relative(path).display().to_string()),
+                })) {
+                    self.collapsed += 1;
+                }
+            }
+        }
+    }
+
+    fn summarize(&mut self, scanned: &ScannedDir) -> DirSummary {
+        let mut summary = DirSummary {
+            files: scanned.files,
+            dirs: scanned.dirs,
+            bytes: scanned.bytes,
+            ..Default::default()
+        };
+        self.count_lines(scanned, &mut summary);
+        let mut extensions: Vec<(String, usize)> = scanned
+            .extensions
+            .iter()
+            .map(|(ext, count)| (ext.clone(), *count))
+            .collect();
+        extensions.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        extensions.truncate(3);
+        summary.top_extensions = extensions;
+        summary
+    }
+
+    /// Add the line counts of the files below `scanned`, reading them while
+    /// the call's budget lasts and estimating from their sizes after that.
+    fn count_lines(&mut self, scanned: &ScannedDir, summary: &mut DirSummary) {
+        for (path, size, entry) in &scanned.children {
+            match entry {
+                ScannedEntry::Dir(sub) => self.count_lines(sub, summary),
+                ScannedEntry::Symlink => {}
+                ScannedEntry::File if *size > LOC_SCAN_LIMIT => {}
+                ScannedEntry::File if *size <= self.loc_budget => {
+                    self.loc_budget -= size;
+                    if let Ok(bytes) = fs::read(path) {
+                        if !bytes.contains(&0) {
+                            let lines = bytes.iter().filter(|b| **b == b'\n').count();
+                            summary.lines += lines;
+                            self.scanned_bytes += bytes.len() as u64;
+                            self.scanned_lines += lines;
+                        }
+                    }
+                }
+                ScannedEntry::File => {
+                    let line_bytes = match self.scanned_lines {
+                        0 => DEFAULT_LINE_BYTES,
+                        lines => (self.scanned_bytes / lines as u64).max(1),
+                    };
+                    summary.lines += (size / line_bytes) as usize;
+                    summary.lines_estimated = true;
+                }
+            }
+        }
+    }
+}
+
+/// Aggregate statistics for a collapsed directory.
+#[derive(Debug, Default)]
+struct DirSummary {
+    files: usize,
+    dirs: usize,
+    bytes: u64,
+    lines: usize,
+    /// Some line counts are estimated from file sizes
+    lines_estimated: bool,
+    top_extensions: Vec<(String, usize)>,
+}
+
+impl DirSummary {
+    fn to_json(&self) -> Value {
+        serde_json::json!({
+            "files": self.files,
+            "dirs": self.dirs,
+            "bytes": self.bytes,
+            "lines": self.lines,
+            "lines_estimated": self.lines_estimated,
+            "top_extensions": self
+                .top_extensions
+                .iter()
+                .map(|(ext, count)| serde_json::json!({"ext": ext, "files": count}))
+                .collect::<Vec<_>>(),
+        })
+    }
+
+    /// One-line description, e.g. `src/ — 42 files, 8.1k LOC, mostly .rs`.
+    fn describe(&self, name: &str) -> String {
+        let mut text = format!(
+            "{}/ — {} files, {}{} LOC",
+            name.trim_end_matches('/'),
+            self.files,
+            if self.lines_estimated { "~" } else { "" },
+            compact_count(self.lines)
+        );
+        if let Some((ext, _)) = self
+            .top_extensions
+            .first()
+            .filter(|(ext, _)| !ext.is_empty())
+        {
+            text.push_str(&format!(", mostly .{}", ext));
+        }
+        text
+    }
+}
+
+/// Render a count as `950`, `8.1k`, or `2.3M`.
+fn compact_count(n: usize) -> String {
+    if n >= 1_000_000 {
+        format!("{:.1}M", n as f64 / 1_000_000.0)
+    } else if n >= 1_000 {
+        format!("{:.1}k", n as f64 / 1_000.0)
+    } else {
+        n.to_string()
+    }
+}
+
 fn default_true() -> bool {
     true
 }
 fn default_three() -> usize {
     3
 }
+fn default_max_entries() -> usize {
+    500
+}
+fn default_summarize_over() -> usize {
+    200
+}
 
 /// Validate that a tool path is safe to access.
 ///
@@ -509,19 +960,34 @@ pub(super) fn validate_tool_path(path: &str, instance_config: Option<&SafetyConf
     PathValidator::new(config, working_dir).validate(path)
 }
 
-/// Write content to a file atomically using a temporary file and rename.
-fn write_atomic(path: &Path, content: &str) -> Result<()> {
-    let parent = path
-        .parent()
-        .ok_or_else(|| anyhow::anyhow!("Invalid file path (no parent)"))?;
-    fs::create_dir_all(parent)?;
-
-    let mut temp = NamedTempFile::new_in(parent)?;
-    temp.write_all(content.as_bytes())?;
-    temp.persist(path)
-        .map_err(|e| anyhow::anyhow!("Failed to persist atomic write: {}", e))?;
-
-    Ok(())
+/// A tool's capability roots: opened on first use for its effective safety
+/// config (its own, else the global one, else the default) in the current
+/// directory, then reused until either of those changes.
+#[derive(Default)]
+pub(crate) struct ToolRoots(Mutex<Option<Arc<CapabilityRoots>>>);
+
+impl ToolRoots {
+    /// The roots for a tool path lookup now; relative paths resolve against
+    /// the directory they were opened in, which is the current one
+    pub(crate) fn current(
+        &self,
+        instance_config: Option<&SafetyConfig>,
+    ) -> Result<Arc<CapabilityRoots>> {
+        let default_config = SafetyConfig::default();
+        let config = instance_config
+            .or_else(|| SAFETY_CONFIG.get())
+            .unwrap_or(&default_config);
+        let working_dir = std::env::current_dir().context("Failed to read working directory")?;
+        let mut cached = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        match cached.as_ref() {
+            Some(roots) if roots.opened_for(config, &working_dir) => Ok(roots.clone()),
+            _ => {
+                let roots = Arc::new(CapabilityRoots::open(config, &working_dir));
+                *cached = Some(roots.clone());
+                Ok(roots)
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -529,6 +995,14 @@ mod tests {
     use super::*;
     use tempfile::TempDir;
 
+    /// A safety config whose only allowed path is `dir`
+    fn scoped_to(dir: &TempDir) -> SafetyConfig {
+        SafetyConfig {
+            allowed_paths: vec![format!("{}/**", dir.path().display())],
+            ..Default::default()
+        }
+    }
+
     #[test]
     fn test_file_read_name() {
         let tool = FileRead::new();
@@ -555,7 +1029,7 @@ mod tests {
         let file_path = temp_dir.path().join("test.txt");
         fs::write(&file_path, "line1\nline2\nline3").unwrap();
 
-        let tool = FileRead::new();
+        let tool = FileRead::with_safety_config(scoped_to(&temp_dir));
         let args = serde_json::json!({"path": file_path.to_str().unwrap()});
 
         let result = tool.execute(args).await.unwrap();
@@ -569,7 +1043,7 @@ mod tests {
         let file_path = temp_dir.path().join("test.txt");
         fs::write(&file_path, "line1\nline2\nline3\nline4\nline5").unwrap();
 
-        let tool = FileRead::new();
+        let tool = FileRead::with_safety_config(scoped_to(&temp_dir));
         let args = serde_json::json!({
             "path": file_path.to_str().unwrap(),
             "line_range": [2, 4]
@@ -602,7 +1076,7 @@ mod tests {
         let temp_dir = TempDir::new().unwrap();
         let file_path = temp_dir.path().join("output.txt");
 
-        let tool = FileWrite::new();
+        let tool = FileWrite::with_safety_config(scoped_to(&temp_dir));
         let args = serde_json::json!({
             "path": file_path.to_str().unwrap(),
             "content": "Hello, World!"
@@ -623,10 +1097,11 @@ mod tests {
         let file_path = temp_dir.path().join("existing.txt");
         fs::write(&file_path, "original content").unwrap();
 
-        let tool = FileWrite::new();
+        let tool = FileWrite::with_safety_config(scoped_to(&temp_dir));
         let args = serde_json::json!({
             "path": file_path.to_str().unwrap(),
             "content": "new content",
+            "overwrite": true,
             "backup": true
         });
 
@@ -648,7 +1123,7 @@ mod tests {
             .join("nested")
             .join("file.txt");
 
-        let tool = FileWrite::new();
+        let tool = FileWrite::with_safety_config(scoped_to(&temp_dir));
         let args = serde_json::json!({
             "path": file_path.to_str().unwrap(),
             "content": "nested content"
@@ -657,6 +1132,170 @@ mod tests {
         let result = tool.execute(args).await.unwrap();
         assert_eq!(result["success"], true);
         assert!(file_path.exists());
+
+        let explicit = temp_dir.path().join("other").join("file.txt");
+        let args = serde_json::json!({
+            "path": explicit.to_str().unwrap(),
+            "content": "nested content",
+            "create_dirs": true
+        });
+        tool.execute(args).await.unwrap();
+        assert!(explicit.exists());
+    }
+
+    #[tokio::test]
+    async fn test_file_write_refuses_missing_parent_without_create_dirs() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("missing").join("file.txt");
+
+        let tool = FileWrite::with_safety_config(scoped_to(&temp_dir));
+        let args = serde_json::json!({
+            "path": file_path.to_str().unwrap(),
+            "content": "nested content",
+            "create_dirs": false
+        });
+
+        let err = tool.execute(args).await.unwrap_err().to_string();
+        assert!(err.contains("create_dirs"), "{}", err);
+        assert!(!temp_dir.path().join("missing").exists());
+
+        // An existing parent is fine
+        let top = temp_dir.path().join("top.txt");
+        let args = serde_json::json!({
+            "path": top.to_str().unwrap(),
+            "content": "top",
+            "create_dirs": false
+        });
+        tool.execute(args).await.unwrap();
+        assert_eq!(fs::read_to_string(&top).unwrap(), "top");
+    }
+
+    #[tokio::test]
+    async fn test_file_write_refuses_to_clobber_without_overwrite() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("existing.txt");
+        fs::write(&file_path, "original content").unwrap();
+
+        let tool = FileWrite::with_safety_config(scoped_to(&temp_dir));
+        let args = serde_json::json!({
+            "path": file_path.to_str().unwrap(),
+            "content": "new content"
+        });
+
+        let err = tool.execute(args).await.unwrap_err().to_string();
+        assert!(err.contains("already exists") && err.contains("file_edit"));
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "original content");
+        assert!(!temp_dir.path().join("existing.txt.bak").exists());
+    }
+
+    #[tokio::test]
+    async fn test_file_write_refuses_paths_outside_allowed_roots() {
+        let allowed = TempDir::new().unwrap();
+        let other = TempDir::new().unwrap();
+        let file_path = other.path().join("out.txt");
+
+        let tool = FileWrite::with_safety_config(scoped_to(&allowed));
+        let args = serde_json::json!({
+            "path": file_path.to_str().unwrap(),
+            "content": "x"
+        });
+        let err = tool.execute(args).await.unwrap_err();
+        assert!(
+            err.to_string().contains("outside every allowed path"),
+            "{}",
+            err
+        );
+        assert!(!file_path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_file_write_refuses_unreadable_existing_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir_path = temp_dir.path().join("taken");
+        fs::create_dir(&dir_path).unwrap();
+
+        let tool = FileWrite::with_safety_config(scoped_to(&temp_dir));
+        let args = serde_json::json!({
+            "path": dir_path.to_str().unwrap(),
+            "content": "new content"
+        });
+
+        let err = tool.execute(args).await.unwrap_err().to_string();
+        assert!(err.contains("already exists"), "{}", err);
+        assert!(dir_path.is_dir());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_file_tools_do_not_follow_symlinks_out_of_root() {
+        let temp_dir = TempDir::new().unwrap();
+        let outside = TempDir::new().unwrap();
+        fs::write(outside.path().join("secret.txt"), "outside").unwrap();
+        std::os::unix::fs::symlink(outside.path(), temp_dir.path().join("out")).unwrap();
+        let through_link = temp_dir.path().join("out/secret.txt");
+
+        let read = FileRead::new()
+            .execute(serde_json::json!({"path": through_link.to_str().unwrap()}))
+            .await;
+        assert!(read.is_err());
+
+        let write = FileWrite::new()
+            .execute(serde_json::json!({
+                "path": through_link.to_str().unwrap(),
+                "content": "overwritten"
+            }))
+            .await;
+        assert!(write.is_err());
+        assert_eq!(
+            fs::read_to_string(outside.path().join("secret.txt")).unwrap(),
+            "outside"
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_file_delete_does_not_follow_symlinks_out_of_root() {
+        let temp_dir = TempDir::new().unwrap();
+        let outside = TempDir::new().unwrap();
+        fs::write(outside.path().join("secret.txt"), "outside").unwrap();
+        std::os::unix::fs::symlink(outside.path(), temp_dir.path().join("out")).unwrap();
+        std::os::unix::fs::symlink(
+            outside.path().join("secret.txt"),
+            temp_dir.path().join("link.txt"),
+        )
+        .unwrap();
+
+        let tool = FileDelete::with_safety_config(scoped_to(&temp_dir));
+        let through_dir = temp_dir.path().join("out/secret.txt");
+        let result = tool
+            .execute(serde_json::json!({"path": through_dir.to_str().unwrap()}))
+            .await;
+        assert!(result.is_err());
+        assert!(outside.path().join("secret.txt").exists());
+
+        // A symlink itself is removed, never the file it points to
+        let link = temp_dir.path().join("link.txt");
+        tool.execute(serde_json::json!({"path": link.to_str().unwrap()}))
+            .await
+            .unwrap();
+        assert!(fs::symlink_metadata(&link).is_err());
+        assert!(outside.path().join("secret.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn test_file_tools_reopen_roots_when_config_changes() {
+        let first = TempDir::new().unwrap();
+        let second = TempDir::new().unwrap();
+        let target = second.path().join("out.txt");
+
+        let mut tool = FileWrite::with_safety_config(scoped_to(&first));
+        let args = serde_json::json!({"path": target.to_str().unwrap(), "content": "x"});
+        let err = tool.execute(args.clone()).await.unwrap_err().to_string();
+        assert!(err.contains("outside every allowed path"), "{}", err);
+
+        tool.safety_config = Some(scoped_to(&second));
+        tool.execute(args).await.unwrap();
+        assert_eq!(fs::read_to_string(&target).unwrap(), "x");
     }
 
     #[test]
@@ -671,7 +1310,7 @@ mod tests {
         let file_path = temp_dir.path().join("edit.txt");
         fs::write(&file_path, "Hello, World!").unwrap();
 
-        let tool = FileEdit::new();
+        let tool = FileEdit::with_safety_config(scoped_to(&temp_dir));
         let args = serde_json::json!({
             "path": file_path.to_str().unwrap(),
             "old_str": "World",
@@ -691,7 +1330,7 @@ mod tests {
         let file_path = temp_dir.path().join("edit.txt");
         fs::write(&file_path, "Hello, World!").unwrap();
 
-        let tool = FileEdit::new();
+        let tool = FileEdit::with_safety_config(scoped_to(&temp_dir));
         let args = serde_json::json!({
             "path": file_path.to_str().unwrap(),
             "old_str": "NotFound",
@@ -709,7 +1348,7 @@ mod tests {
         let file_path = temp_dir.path().join("edit.txt");
         fs::write(&file_path, "Hello Hello Hello").unwrap();
 
-        let tool = FileEdit::new();
+        let tool = FileEdit::with_safety_config(scoped_to(&temp_dir));
         let args = serde_json::json!({
             "path": file_path.to_str().unwrap(),
             "old_str": "Hello",
@@ -804,7 +1443,7 @@ mod tests {
         let file_path = temp_dir.path().join("empty.txt");
         fs::write(&file_path, "").unwrap();
 
-        let tool = FileRead::new();
+        let tool = FileRead::with_safety_config(scoped_to(&temp_dir));
         let args = serde_json::json!({"path": file_path.to_str().unwrap()});
 
         let result = tool.execute(args).await.unwrap();
@@ -818,7 +1457,7 @@ mod tests {
         let file_path = temp_dir.path().join("test.txt");
         fs::write(&file_path, "line1\nline2\nline3").unwrap();
 
-        let tool = FileRead::new();
+        let tool = FileRead::with_safety_config(scoped_to(&temp_dir));
         let args = serde_json::json!({
             "path": file_path.to_str().unwrap(),
             "line_range": [100, 200]
@@ -834,7 +1473,7 @@ mod tests {
         let file_path = temp_dir.path().join("single.txt");
         fs::write(&file_path, "only one line").unwrap();
 
-        let tool = FileRead::new();
+        let tool = FileRead::with_safety_config(scoped_to(&temp_dir));
         let args = serde_json::json!({"path": file_path.to_str().unwrap()});
 
         let result = tool.execute(args).await.unwrap();
@@ -842,13 +1481,84 @@ mod tests {
         assert_eq!(result["content"], "only one line");
     }
 
+    #[tokio::test]
+    async fn test_file_read_pages_large_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("big.txt");
+        let body: Vec<String> = (1..=5000).map(|i| format!("line {:04}", i)).collect();
+        fs::write(&file_path, body.join("\n")).unwrap();
+
+        // Each line is 9 bytes plus a separator, so 100 bytes fits 10 lines.
+        let tool = FileRead::with_safety_config(scoped_to(&temp_dir)).with_max_read_bytes(100);
+        let args = serde_json::json!({"path": file_path.to_str().unwrap()});
+        let result = tool.execute(args).await.unwrap();
+        assert_eq!(result["total_lines"], 5000);
+        assert_eq!(result["end_line"], 10);
+        assert_eq!(result["truncated"], true);
+        assert_eq!(result["next_start_line"], 11);
+        assert_eq!(result["header"], "Lines 1-10 of 5000");
+        assert!(result["more"].as_str().unwrap().contains("start_line=11"));
+
+        let args = serde_json::json!({
+            "path": file_path.to_str().unwrap(),
+            "start_line": 11,
+            "end_line": 12
+        });
+        let result = tool.execute(args).await.unwrap();
+        assert_eq!(result["content"], "line 0011\nline 0012");
+        assert_eq!(result["next_start_line"], 13);
+    }
+
+    #[tokio::test]
+    async fn test_file_read_max_bytes_cannot_exceed_ceiling() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("test.txt");
+        fs::write(&file_path, "aaaa\nbbbb\ncccc").unwrap();
+
+        let tool = FileRead::with_safety_config(scoped_to(&temp_dir)).with_max_read_bytes(9);
+        let args = serde_json::json!({"path": file_path.to_str().unwrap(), "max_bytes": 1000});
+        let result = tool.execute(args).await.unwrap();
+        assert_eq!(result["content"], "aaaa\nbbbb");
+
+        let args = serde_json::json!({"path": file_path.to_str().unwrap(), "max_bytes": 4});
+        let result = tool.execute(args).await.unwrap();
+        assert_eq!(result["content"], "aaaa");
+        assert_eq!(result["next_start_line"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_file_read_whole_file_is_not_truncated() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("test.txt");
+        fs::write(&file_path, "line1\nline2").unwrap();
+
+        let tool = FileRead::with_safety_config(scoped_to(&temp_dir));
+        let args = serde_json::json!({"path": file_path.to_str().unwrap()});
+        let result = tool.execute(args).await.unwrap();
+        assert_eq!(result["truncated"], false);
+        assert!(result.get("more").is_none());
+    }
+
+    #[test]
+    fn test_read_page_never_splits_multibyte_chars() {
+        // "日本語" is 9 bytes; a 7-byte budget must stop after two characters.
+        let page = read_page("日本語\n中文", 1, 2, 7);
+        assert_eq!(page.content, "日本");
+        assert!(page.cut_line);
+        assert_eq!(page.lines_shown, 1);
+
+        let page = read_page("日本語\n中文", 1, 2, 12);
+        assert_eq!(page.content, "日本語");
+        assert!(!page.cut_line);
+    }
+
     #[tokio::test]
     async fn test_file_read_with_unicode() {
         let temp_dir = TempDir::new().unwrap();
         let file_path = temp_dir.path().join("unicode.txt");
         fs::write(&file_path, "日本語\n한국어\n中文").unwrap();
 
-        let tool = FileRead::new();
+        let tool = FileRead::with_safety_config(scoped_to(&temp_dir));
         let args = serde_json::json!({"path": file_path.to_str().unwrap()});
 
         let result = tool.execute(args).await.unwrap();
@@ -863,10 +1573,11 @@ mod tests {
         let file_path = temp_dir.path().join("no_backup.txt");
         fs::write(&file_path, "original").unwrap();
 
-        let tool = FileWrite::new();
+        let tool = FileWrite::with_safety_config(scoped_to(&temp_dir));
         let args = serde_json::json!({
             "path": file_path.to_str().unwrap(),
             "content": "new content",
+            "overwrite": true,
             "backup": false
         });
 
@@ -881,7 +1592,7 @@ mod tests {
         let temp_dir = TempDir::new().unwrap();
         let file_path = temp_dir.path().join("brand_new.txt");
 
-        let tool = FileWrite::new();
+        let tool = FileWrite::with_safety_config(scoped_to(&temp_dir));
         let args = serde_json::json!({
             "path": file_path.to_str().unwrap(),
             "content": "new file content",
@@ -902,7 +1613,7 @@ mod tests {
         let file_path = temp_dir.path().join("delete.txt");
         fs::write(&file_path, "Hello, World!").unwrap();
 
-        let tool = FileEdit::new();
+        let tool = FileEdit::with_safety_config(scoped_to(&temp_dir));
         let args = serde_json::json!({
             "path": file_path.to_str().unwrap(),
             "old_str": ", World",
@@ -922,7 +1633,7 @@ mod tests {
         let file_path = temp_dir.path().join("multiline.txt");
         fs::write(&file_path, "line1\nline2\nline3").unwrap();
 
-        let tool = FileEdit::new();
+        let tool = FileEdit::with_safety_config(scoped_to(&temp_dir));
         let args = serde_json::json!({
             "path": file_path.to_str().unwrap(),
             "old_str": "line1\nline2",
@@ -959,7 +1670,7 @@ mod tests {
         let file_path = temp_dir.path().join("noop.txt");
         fs::write(&file_path, "Hello, World!").unwrap();
 
-        let tool = FileEdit::new();
+        let tool = FileEdit::with_safety_config(scoped_to(&temp_dir));
         let args = serde_json::json!({
             "path": file_path.to_str().unwrap(),
             "old_str": "Hello",
@@ -997,6 +1708,148 @@ mod tests {
         assert!(!has_deep);
     }
 
+    #[tokio::test]
+    async fn test_directory_tree_collapses_large_directories() {
+        let temp_dir = TempDir::new().unwrap();
+        let big = temp_dir.path().join("big");
+        fs::create_dir(&big).unwrap();
+        for i in 0..5 {
+            fs::write(big.join(format!("m{}.rs", i)), "fn a() {}\nfn b() {}\n").unwrap();
+        }
+        fs::write(big.join("notes.md"), "hi\n").unwrap();
+        fs::write(temp_dir.path().join("top.txt"), "").unwrap();
+
+        let tool = DirectoryTree::new();
+        let args = serde_json::json!({
+            "path": temp_dir.path().to_str().unwrap(),
+            "summarize_over": 3
+        });
+
+        let result = tool.execute(args).await.unwrap();
+        let entries = result["entries"].as_array().unwrap();
+        assert_eq!(result["collapsed"], 1);
+        assert!(!entries
+            .iter()
+            .any(|e| e["path"].as_str().unwrap().ends_with("m0.rs")));
+
+        let summary = entries.iter().find(|e| e["collapsed"] == true).unwrap();
+        assert_eq!(summary["summary"]["files"], 6);
+        assert_eq!(summary["summary"]["lines"], 11);
+        assert_eq!(summary["summary"]["top_extensions"][0]["ext"], "rs");
+        assert_eq!(summary["description"], "big/ — 6 files, 11 LOC, mostly .rs");
+    }
+
+    #[tokio::test]
+    async fn test_directory_tree_include_glob_expands_collapsed_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let big = temp_dir.path().join("big").join("inner");
+        fs::create_dir_all(&big).unwrap();
+        for i in 0..5 {
+            fs::write(big.join(format!("f{}.rs", i)), "").unwrap();
+        }
+
+        let tool = DirectoryTree::new();
+        let args = serde_json::json!({
+            "path": temp_dir.path().to_str().unwrap(),
+            "summarize_over": 1,
+            "max_depth": 1,
+            "include": "big/inner/**"
+        });
+
+        let result = tool.execute(args).await.unwrap();
+        let entries = result["entries"].as_array().unwrap();
+        assert_eq!(result["collapsed"], 0);
+        assert!(entries
+            .iter()
+            .any(|e| e["path"].as_str().unwrap().ends_with("f4.rs")));
+    }
+
+    #[tokio::test]
+    async fn test_directory_tree_max_entries_truncates() {
+        let temp_dir = TempDir::new().unwrap();
+        for i in 0..10 {
+            fs::write(temp_dir.path().join(format!("f{}.txt", i)), "").unwrap();
+        }
+
+        let tool = DirectoryTree::new();
+        let args = serde_json::json!({
+            "path": temp_dir.path().to_str().unwrap(),
+            "max_entries": 4
+        });
+
+        let result = tool.execute(args).await.unwrap();
+        assert_eq!(result["total"], 4);
+        assert_eq!(result["truncated"], true);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_directory_tree_does_not_follow_symlinks() {
+        let temp_dir = TempDir::new().unwrap();
+        let outside = TempDir::new().unwrap();
+        fs::write(outside.path().join("secret.txt"), "x\n").unwrap();
+        std::os::unix::fs::symlink(outside.path(), temp_dir.path().join("link")).unwrap();
+        // A link back to the root must not loop either
+        std::os::unix::fs::symlink(temp_dir.path(), temp_dir.path().join("loop")).unwrap();
+
+        let tool = DirectoryTree::new();
+        let args = serde_json::json!({"path": temp_dir.path().to_str().unwrap()});
+        let result = tool.execute(args).await.unwrap();
+        let entries = result["entries"].as_array().unwrap();
+
+        assert!(!entries
+            .iter()
+            .any(|e| e["path"].as_str().unwrap().contains("secret")));
+        let link = entries
+            .iter()
+            .find(|e| e["path"].as_str().unwrap().ends_with("link"))
+            .unwrap();
+        assert_eq!(link["type"], "symlink");
+        assert_eq!(result["total"], 3);
+    }
+
+    #[test]
+    fn test_tree_walker_estimates_lines_past_budget() {
+        let temp_dir = TempDir::new().unwrap();
+        let big = temp_dir.path().join("big");
+        fs::create_dir(&big).unwrap();
+        fs::write(big.join("a.txt"), "one\ntwo\n").unwrap();
+        fs::write(big.join("b.txt"), "three\nfours\n").unwrap();
+
+        let mut walker = TreeWalker {
+            root: temp_dir.path(),
+            include_hidden: false,
+            max_depth: 0,
+            max_entries: 10,
+            summarize_over: 0,
+            include: None,
+            entries: Vec::new(),
+            collapsed: 0,
+            truncated: false,
+            loc_budget: 8,
+            scanned_bytes: 0,
+            scanned_lines: 0,
+        };
+        let tree = walker.scan(temp_dir.path());
+        walker.visit(&tree, 0);
+
+        // a.txt is read, b.txt (12 bytes) is estimated at a.txt's 4 bytes a line
+        let summary = &walker.entries[0]["summary"];
+        assert_eq!(summary["lines"], 5);
+        assert_eq!(summary["lines_estimated"], true);
+        assert_eq!(
+            walker.entries[0]["description"],
+            "big/ — 2 files, ~5 LOC, mostly .txt"
+        );
+    }
+
+    #[test]
+    fn test_compact_count() {
+        assert_eq!(compact_count(950), "950");
+        assert_eq!(compact_count(8_120), "8.1k");
+        assert_eq!(compact_count(2_300_000), "2.3M");
+    }
+
     #[tokio::test]
     async fn test_directory_tree_nonexistent_path() {
         let tool = DirectoryTree::new();
@@ -1050,7 +1903,7 @@ mod tests {
         let file_path = temp_dir.path().join("test.txt");
         fs::write(&file_path, "line1\nline2\nline3").unwrap();
 
-        let tool = FileRead::new();
+        let tool = FileRead::with_safety_config(scoped_to(&temp_dir));
         let args = serde_json::json!({
             "path": file_path.to_str().unwrap(),
             "line_range": [3, 1]
@@ -1065,7 +1918,7 @@ mod tests {
         let temp_dir = TempDir::new().unwrap();
         let file_path = temp_dir.path().join("empty_write.txt");
 
-        let tool = FileWrite::new();
+        let tool = FileWrite::with_safety_config(scoped_to(&temp_dir));
         let args = serde_json::json!({
             "path": file_path.to_str().unwrap(),
             "content": ""
@@ -1154,7 +2007,7 @@ mod tests {
         fs::write(&file_path, "delete me").unwrap();
         assert!(file_path.exists());
 
-        let tool = FileDelete::new();
+        let tool = FileDelete::with_safety_config(scoped_to(&temp_dir));
         let args = serde_json::json!({"path": file_path.to_str().unwrap()});
 
         let result = tool.execute(args).await.unwrap();
@@ -1167,7 +2020,7 @@ mod tests {
         let temp_dir = TempDir::new().unwrap();
         let file_path = temp_dir.path().join("nonexistent.txt");
 
-        let tool = FileDelete::new();
+        let tool = FileDelete::with_safety_config(scoped_to(&temp_dir));
         let args = serde_json::json!({"path": file_path.to_str().unwrap()});
 
         let result = tool.execute(args).await;
@@ -1181,7 +2034,7 @@ mod tests {
         let dir_path = temp_dir.path().join("subdir");
         fs::create_dir(&dir_path).unwrap();
 
-        let tool = FileDelete::new();
+        let tool = FileDelete::with_safety_config(scoped_to(&temp_dir));
         let args = serde_json::json!({"path": dir_path.to_str().unwrap()});
 
         let result = tool.execute(args).await;
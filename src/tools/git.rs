@@ -1,10 +1,12 @@
 use super::Tool;
 use anyhow::{Context, Result};
 use async_trait::async_trait;
-use git2::{Repository, StatusOptions};
+use git2::{BlameOptions, Oid, Repository, Status, StatusOptions};
 use serde_json::Value;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
 use tracing::{info, warn};
 
 /// Validate a git tag name to prevent shell injection.
@@ -67,6 +69,241 @@ pub struct GitDiff;
 pub struct GitCommit;
 pub struct GitPush;
 pub struct GitCheckpoint;
+pub struct GitBlame;
+pub struct GitBranchCreate;
+pub struct GitCheckout;
+
+/// Maximum number of cached blames before the cache is cleared.
+const MAX_BLAME_CACHE_ENTRIES: usize = 64;
+
+/// Blames keyed by file and HEAD commit, so a new commit invalidates them
+type BlameCache = HashMap<(PathBuf, Oid), Arc<FileBlame>>;
+
+static BLAME_CACHE: LazyLock<Mutex<BlameCache>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Contiguous lines last changed by the same commit
+struct BlameRegion {
+    start: usize,
+    end: usize,
+    commit: String,
+    boundary: bool,
+}
+
+struct BlameCommit {
+    author: String,
+    date: String,
+    summary: String,
+}
+
+struct FileBlame {
+    regions: Vec<BlameRegion>,
+    commits: HashMap<String, BlameCommit>,
+}
+
+impl FileBlame {
+    fn compute(repo: &Repository, rel_path: &Path, head: Oid) -> Result<Self> {
+        let mut opts = BlameOptions::new();
+        opts.newest_commit(head);
+        let blame = repo
+            .blame_file(rel_path, Some(&mut opts))
+            .with_context(|| format!("Failed to blame {}", rel_path.display()))?;
+
+        let mut regions: Vec<BlameRegion> = Vec::new();
+        let mut commits = HashMap::new();
+        for hunk in blame.iter() {
+            let lines = hunk.lines_in_hunk();
+            if lines == 0 {
+                continue;
+            }
+            let id = hunk.final_commit_id();
+            let short = id.to_string()[..7].to_string();
+            if !commits.contains_key(&short) {
+                let commit = repo.find_commit(id)?;
+                let author = commit.author();
+                let date = chrono::DateTime::from_timestamp(author.when().seconds(), 0)
+                    .map(|d| d.format("%Y-%m-%d").to_string())
+                    .unwrap_or_default();
+                commits.insert(
+                    short.clone(),
+                    BlameCommit {
+                        author: author.name().unwrap_or("unknown").to_string(),
+                        date,
+                        summary: commit.summary().unwrap_or("").to_string(),
+                    },
+                );
+            }
+
+            let start = hunk.final_start_line();
+            let end = start + lines - 1;
+            match regions.last_mut() {
+                Some(last) if last.commit == short && last.end + 1 == start => last.end = end,
+                _ => regions.push(BlameRegion {
+                    start,
+                    end,
+                    commit: short,
+                    boundary: hunk.is_boundary(),
+                }),
+            }
+        }
+        Ok(Self { regions, commits })
+    }
+}
+
+/// Blame `path` at HEAD, reusing the cached result for the same HEAD
+fn blame_at_head(path: &Path, start: usize, end: Option<usize>) -> Result<Value> {
+    let abs = std::fs::canonicalize(path)
+        .with_context(|| format!("File not found: {}", path.display()))?;
+    let explain = |explanation: String| {
+        serde_json::json!({
+            "path": path.display().to_string(),
+            "tracked": false,
+            "explanation": explanation,
+        })
+    };
+
+    let Ok(repo) = Repository::discover(abs.parent().unwrap_or(Path::new("/"))) else {
+        return Ok(explain(format!(
+            "{} is not inside a git repository, so it has no history",
+            path.display()
+        )));
+    };
+    let Some(workdir) = repo.workdir().and_then(|w| std::fs::canonicalize(w).ok()) else {
+        return Ok(explain(
+            "Bare repositories have no working files to blame".into(),
+        ));
+    };
+    let rel_path = abs.strip_prefix(&workdir)?.to_path_buf();
+    let Ok(head) = repo.head().and_then(|h| h.peel_to_commit()) else {
+        return Ok(explain("The repository has no commits yet".into()));
+    };
+    if head.tree()?.get_path(&rel_path).is_err() {
+        return Ok(explain(format!(
+            "{} is not tracked at HEAD (new or ignored file), so it has no history yet",
+            rel_path.display()
+        )));
+    }
+
+    let key = (abs.clone(), head.id());
+    let cached = BLAME_CACHE.lock().ok().and_then(|c| c.get(&key).cloned());
+    let blame = match cached {
+        Some(blame) => blame,
+        None => {
+            let blame = Arc::new(FileBlame::compute(&repo, &rel_path, head.id())?);
+            if let Ok(mut cache) = BLAME_CACHE.lock() {
+                if cache.len() >= MAX_BLAME_CACHE_ENTRIES {
+                    cache.clear();
+                }
+                cache.insert(key, blame.clone());
+            }
+            blame
+        }
+    };
+
+    let shallow = repo.is_shallow();
+    let end = end.unwrap_or(usize::MAX);
+    let mut regions = Vec::new();
+    let mut commits = BTreeMap::new();
+    for region in blame
+        .regions
+        .iter()
+        .filter(|r| r.end >= start && r.start <= end)
+    {
+        let (from, to) = (region.start.max(start), region.end.min(end));
+        let mut entry = serde_json::json!({
+            "lines": if from == to { from.to_string() } else { format!("{}-{}", from, to) },
+            "commit": region.commit,
+        });
+        // In a full clone the root commit is also a boundary; only a
+        // shallow clone makes the attribution uncertain
+        if shallow && region.boundary {
+            entry["boundary"] = true.into();
+        }
+        regions.push(entry);
+        if let Some(commit) = blame.commits.get(&region.commit) {
+            commits.insert(
+                region.commit.clone(),
+                serde_json::json!({
+                    "author": commit.author,
+                    "date": commit.date,
+                    "summary": commit.summary,
+                }),
+            );
+        }
+    }
+
+    let mut notes = Vec::new();
+    if shallow {
+        notes.push(
+            "Shallow clone: lines marked boundary are attributed to the oldest fetched commit \
+             but may be older. Run `git fetch --unshallow` for full history.",
+        );
+    }
+    let dirty = Status::WT_MODIFIED | Status::INDEX_MODIFIED;
+    if repo
+        .status_file(&rel_path)
+        .is_ok_and(|s| s.intersects(dirty))
+    {
+        notes.push("Line numbers are from HEAD; the working copy has uncommitted changes.");
+    }
+
+    let mut result = serde_json::json!({
+        "path": rel_path.display().to_string(),
+        "tracked": true,
+        "head": head.id().to_string()[..7],
+        "regions": regions,
+        "commits": commits,
+    });
+    if !notes.is_empty() {
+        result["notes"] = notes.into();
+    }
+    Ok(result)
+}
+
+#[async_trait]
+impl Tool for GitBlame {
+    fn name(&self) -> &str {
+        "git_blame"
+    }
+
+    fn description(&self) -> &str {
+        "Show who last changed lines of a file and why: contiguous regions with their commit, \
+         plus each commit's author, date and message. Use to understand why code is the way it \
+         is before changing it."
+    }
+
+    fn schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "path": {"type": "string", "description": "File to blame"},
+                "start_line": {"type": "integer", "description": "First line (1-based, default: 1)"},
+                "end_line": {"type": "integer", "description": "Last line, inclusive (default: end of file)"}
+            },
+            "required": ["path"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> Result<Value> {
+        let path = args
+            .get("path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing required parameter: path"))?
+            .to_string();
+        let start = args
+            .get("start_line")
+            .and_then(|v| v.as_u64())
+            .map_or(1, |l| l.max(1) as usize);
+        let end = args
+            .get("end_line")
+            .and_then(|v| v.as_u64())
+            .map(|l| l as usize);
+        if end.is_some_and(|end| end < start) {
+            anyhow::bail!("end_line must not be before start_line");
+        }
+
+        tokio::task::spawn_blocking(move || blame_at_head(Path::new(&path), start, end)).await?
+    }
+}
 
 #[async_trait]
 impl Tool for GitCheckpoint {
@@ -194,6 +431,206 @@ impl Tool for GitCheckpoint {
     }
 }
 
+/// Branch name (or `None` when detached) and commit HEAD points at
+fn head_summary(repo: &Repository) -> Result<Value> {
+    let head = repo.head().context("Repository has no HEAD commit")?;
+    let commit = head.peel_to_commit()?;
+    Ok(serde_json::json!({
+        "branch": head.is_branch().then(|| head.shorthand()).flatten(),
+        "head": commit.id().to_string(),
+        "summary": commit.summary().unwrap_or(""),
+    }))
+}
+
+/// Paths with uncommitted changes, untracked files included
+fn uncommitted_paths(repo: &Repository) -> Result<Vec<String>> {
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(true).include_ignored(false);
+    Ok(repo
+        .statuses(Some(&mut opts))?
+        .iter()
+        .filter(|s| !s.status().is_ignored())
+        .filter_map(|s| s.path().map(str::to_string))
+        .collect())
+}
+
+/// Check out `target` without overwriting anything: the tree must be clean
+/// or `stash` set, in which case changes (untracked files included) are
+/// stashed first. Returns the stash commit, if one was made.
+fn checkout_ref(repo: &mut Repository, target: &str, stash: bool) -> Result<Option<String>> {
+    // Resolve before stashing so a typo leaves the tree alone
+    repo.revparse_single(target)
+        .with_context(|| format!("Unknown ref '{}'", target))?;
+    let dirty = uncommitted_paths(repo)?;
+    let mut stashed = None;
+    if !dirty.is_empty() {
+        if !stash {
+            let shown: Vec<&str> = dirty.iter().take(10).map(String::as_str).collect();
+            anyhow::bail!(
+                "Refusing to check out '{}': {} uncommitted change(s) would be left behind or \
+                 lost ({}{}). Commit them, or pass stash: true to stash them first.",
+                target,
+                dirty.len(),
+                shown.join(", "),
+                if dirty.len() > shown.len() {
+                    ", ..."
+                } else {
+                    ""
+                }
+            );
+        }
+        let sig = repo
+            .signature()
+            .or_else(|_| git2::Signature::now("selfware", "selfware@localhost"))?;
+        let message = format!("selfware: before checkout of {}", target);
+        let oid = repo
+            .stash_save(&sig, &message, Some(git2::StashFlags::INCLUDE_UNTRACKED))
+            .context("Failed to stash uncommitted changes")?;
+        stashed = Some(oid.to_string());
+    }
+
+    let (object, reference) = repo.revparse_ext(target)?;
+    let mut opts = git2::build::CheckoutBuilder::new();
+    opts.safe();
+    repo.checkout_tree(&object, Some(&mut opts))
+        .with_context(|| format!("Failed to check out '{}'", target))?;
+    match reference
+        .as_ref()
+        .filter(|r| r.is_branch())
+        .and_then(|r| r.name())
+    {
+        Some(name) => repo.set_head(name)?,
+        None => repo.set_head_detached(object.peel_to_commit()?.id())?,
+    }
+    Ok(stashed)
+}
+
+#[async_trait]
+impl Tool for GitBranchCreate {
+    fn name(&self) -> &str {
+        "git_branch_create"
+    }
+
+    fn description(&self) -> &str {
+        "Create a git branch and switch to it, keeping uncommitted changes. Use for work that \
+         should happen on a feature branch. Starts from HEAD unless `from` is given."
+    }
+
+    fn schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "name": {"type": "string", "description": "New branch name, e.g. feature/parser-errors"},
+                "from": {"type": "string", "description": "Commit, branch or tag to start from (default: HEAD)"},
+                "checkout": {"type": "boolean", "default": true, "description": "Switch to the new branch"},
+                "repo_path": {"type": "string", "description": "Repository path (default: current)"}
+            },
+            "required": ["name"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> Result<Value> {
+        let name = args
+            .get("name")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing required parameter: name"))?
+            .to_string();
+        let from = args
+            .get("from")
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+        let checkout = args
+            .get("checkout")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+        let repo_path = args
+            .get("repo_path")
+            .and_then(|v| v.as_str())
+            .unwrap_or(".")
+            .to_string();
+
+        tokio::task::spawn_blocking(move || {
+            if !git2::Branch::name_is_valid(&name)? {
+                anyhow::bail!("'{}' is not a valid branch name", name);
+            }
+            let mut repo = Repository::discover(&repo_path)?;
+            let start = match &from {
+                Some(from) => repo
+                    .revparse_single(from)
+                    .with_context(|| format!("Unknown ref '{}'", from))?
+                    .peel_to_commit()?,
+                None => repo.head()?.peel_to_commit()?,
+            };
+            let start_id = start.id();
+            repo.branch(&name, &start, false)
+                .with_context(|| format!("Failed to create branch '{}'", name))?;
+            drop(start);
+
+            if checkout {
+                let at_head = repo.head()?.peel_to_commit()?.id() == start_id;
+                if at_head {
+                    // Same commit: only HEAD moves, the working tree stays as is
+                    repo.set_head(&format!("refs/heads/{}", name))?;
+                } else {
+                    checkout_ref(&mut repo, &name, false)?;
+                }
+            }
+            let mut result = head_summary(&repo)?;
+            result["created"] = Value::from(name);
+            result["checked_out"] = Value::from(checkout);
+            Ok(result)
+        })
+        .await?
+    }
+}
+
+#[async_trait]
+impl Tool for GitCheckout {
+    fn name(&self) -> &str {
+        "git_checkout"
+    }
+
+    fn description(&self) -> &str {
+        "Switch to a branch, tag or commit. Refuses when there are uncommitted changes, so edits \
+         are never lost; pass stash: true to stash them first (git stash pop restores them)."
+    }
+
+    fn schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "ref": {"type": "string", "description": "Branch, tag or commit to check out"},
+                "stash": {"type": "boolean", "default": false, "description": "Stash uncommitted changes (including untracked files) before switching"},
+                "repo_path": {"type": "string", "description": "Repository path (default: current)"}
+            },
+            "required": ["ref"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> Result<Value> {
+        let target = args
+            .get("ref")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing required parameter: ref"))?
+            .to_string();
+        let stash = args.get("stash").and_then(|v| v.as_bool()).unwrap_or(false);
+        let repo_path = args
+            .get("repo_path")
+            .and_then(|v| v.as_str())
+            .unwrap_or(".")
+            .to_string();
+
+        tokio::task::spawn_blocking(move || {
+            let mut repo = Repository::discover(&repo_path)?;
+            let stashed = checkout_ref(&mut repo, &target, stash)?;
+            let mut result = head_summary(&repo)?;
+            result["stash"] = Value::from(stashed);
+            Ok(result)
+        })
+        .await?
+    }
+}
+
 #[async_trait]
 impl Tool for GitStatus {
     fn name(&self) -> &str {
@@ -539,6 +976,131 @@ mod tests {
         assert!(schema["properties"]["files"].is_object());
     }
 
+    #[tokio::test]
+    async fn test_branch_create_and_guarded_checkout() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        std::fs::write(dir.path().join("lib.rs"), "fn a() {}\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("lib.rs")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = git2::Signature::now("dev", "dev@example.com").unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "Initial", &tree, &[])
+            .unwrap();
+        let base = repo.head().unwrap().shorthand().unwrap().to_string();
+        let repo_path = dir.path().to_str().unwrap();
+
+        // An edit in progress comes along to the new branch
+        std::fs::write(dir.path().join("lib.rs"), "fn a() { todo }\n").unwrap();
+        let created = GitBranchCreate
+            .execute(serde_json::json!({"name": "feature/x", "repo_path": repo_path}))
+            .await
+            .unwrap();
+        assert_eq!(created["branch"], "feature/x");
+        assert_eq!(created["summary"], "Initial");
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("lib.rs")).unwrap(),
+            "fn a() { todo }\n"
+        );
+
+        let err = GitBranchCreate
+            .execute(serde_json::json!({"name": "bad..name", "repo_path": repo_path}))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not a valid branch name"));
+
+        let err = GitCheckout
+            .execute(serde_json::json!({"ref": base, "repo_path": repo_path}))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("uncommitted change"), "{}", err);
+        assert!(err.to_string().contains("lib.rs"));
+
+        let switched = GitCheckout
+            .execute(serde_json::json!({"ref": base, "stash": true, "repo_path": repo_path}))
+            .await
+            .unwrap();
+        assert_eq!(switched["branch"], base.as_str());
+        assert!(switched["stash"].is_string());
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("lib.rs")).unwrap(),
+            "fn a() {}\n"
+        );
+
+        let err = GitCheckout
+            .execute(serde_json::json!({"ref": "no-such-branch", "repo_path": repo_path}))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Unknown ref"));
+    }
+
+    #[tokio::test]
+    async fn test_git_blame_summarizes_regions() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let commit = |author: &str, content: &str, message: &str| {
+            std::fs::write(dir.path().join("lib.rs"), content).unwrap();
+            let mut index = repo.index().unwrap();
+            index.add_path(Path::new("lib.rs")).unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let sig = git2::Signature::now(author, "dev@example.com").unwrap();
+            let parents: Vec<_> = repo
+                .head()
+                .ok()
+                .map(|h| h.peel_to_commit().unwrap())
+                .into_iter()
+                .collect();
+            let parents: Vec<_> = parents.iter().collect();
+            repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+                .unwrap();
+        };
+        commit("alice", "a\nb\nc\nd\n", "Add lib");
+        commit("bob", "a\nB\nC\nd\n", "Fix b and c\n\nLonger body");
+
+        let path = dir.path().join("lib.rs").display().to_string();
+        let result = GitBlame
+            .execute(serde_json::json!({"path": path}))
+            .await
+            .unwrap();
+        let regions = result["regions"].as_array().unwrap();
+        assert_eq!(regions.len(), 3);
+        assert_eq!(regions[0]["lines"], "1");
+        assert_eq!(regions[1]["lines"], "2-3");
+        let fix = regions[1]["commit"].as_str().unwrap();
+        assert_eq!(result["commits"][fix]["author"], "bob");
+        assert_eq!(result["commits"][fix]["summary"], "Fix b and c");
+        assert!(result.get("notes").is_none());
+
+        // A range only reports the commits it touches
+        let result = GitBlame
+            .execute(serde_json::json!({"path": path, "start_line": 3, "end_line": 3}))
+            .await
+            .unwrap();
+        assert_eq!(result["regions"][0]["lines"], "3");
+        assert_eq!(result["commits"].as_object().unwrap().len(), 1);
+
+        std::fs::write(dir.path().join("lib.rs"), "a\nB\nC\nd\ne\n").unwrap();
+        let result = GitBlame
+            .execute(serde_json::json!({"path": path}))
+            .await
+            .unwrap();
+        assert!(result["notes"][0].as_str().unwrap().contains("uncommitted"));
+
+        // Untracked files are explained, not errors
+        std::fs::write(dir.path().join("new.rs"), "x\n").unwrap();
+        let result = GitBlame
+            .execute(serde_json::json!({"path": dir.path().join("new.rs")}))
+            .await
+            .unwrap();
+        assert_eq!(result["tracked"], false);
+        assert!(result["explanation"]
+            .as_str()
+            .unwrap()
+            .contains("not tracked"));
+    }
+
     #[test]
     fn test_git_checkpoint_name() {
         let tool = GitCheckpoint;
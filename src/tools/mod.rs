@@ -5,9 +5,12 @@ use serde_json::Value;
 use std::collections::HashMap;
 
 pub mod analyzer;
+pub mod artifact;
 pub mod browser;
+pub mod cancellation;
 pub mod cargo;
 pub mod container;
+pub mod contract;
 pub mod file;
 pub mod fim;
 pub mod git;
@@ -15,28 +18,42 @@ pub mod git;
 pub mod hot_reload;
 pub mod http;
 pub mod knowledge;
+pub mod output_schema;
 pub mod package;
+pub mod patch;
 pub mod process;
+pub mod rag;
+pub mod refactor;
+pub mod review;
 pub mod screen_capture;
 pub mod search;
 pub mod shell;
+pub mod tool_output;
 pub mod vision;
 
+pub use cancellation::CancellationToken;
+
 use browser::{BrowserEval, BrowserFetch, BrowserLinks, BrowserPdf, BrowserScreenshot};
-use cargo::{CargoCheck, CargoClippy, CargoFmt, CargoTest};
+use cargo::{CargoAdd, CargoCheck, CargoClippy, CargoFmt, CargoRemove, CargoTest};
 use container::{
     ComposeDown, ComposeUp, ContainerBuild, ContainerExec, ContainerImages, ContainerList,
     ContainerLogs, ContainerPull, ContainerRemove, ContainerRun, ContainerStop,
 };
+use contract::{ContractGenerate, ContractVerify};
 use file::{DirectoryTree, FileDelete, FileEdit, FileRead, FileWrite};
-use git::{GitCheckpoint, GitCommit, GitDiff, GitPush, GitStatus};
+use git::{
+    GitBlame, GitBranchCreate, GitCheckout, GitCheckpoint, GitCommit, GitDiff, GitPush, GitStatus,
+};
 use http::HttpRequest;
 use knowledge::{
     KnowledgeAdd, KnowledgeClear, KnowledgeExport, KnowledgeQuery, KnowledgeRelate,
     KnowledgeRemove, KnowledgeStats as KnowledgeStatsTool,
 };
 use package::{NpmInstall, NpmRun, NpmScripts, PipFreeze, PipInstall, PipList, YarnInstall};
+use patch::ApplyPatch;
 use process::{PortCheck, ProcessList, ProcessLogs, ProcessRestart, ProcessStart, ProcessStop};
+use refactor::{FindCycles, SymbolRename};
+use review::{CodeReview, PanicRisk};
 use screen_capture::ScreenCapture;
 use search::{GlobFind, GrepSearch, SymbolSearch};
 use shell::ShellExec;
@@ -78,18 +95,94 @@ pub fn truncate_with_pagination(
 /// A tool that can be executed by the agent. Each tool has a name, description,
 /// JSON schema for its arguments, and an async `execute` method. Tools are
 /// registered in a [`ToolRegistry`] and invoked by name during agent execution.
+///
+/// Output is JSON. Tools that produce files, such as screenshots, list them
+/// as [`artifact::Artifact`]s so the agent can show or describe them to the
+/// model.
 #[async_trait]
 pub trait Tool: Send + Sync {
     fn name(&self) -> &str;
     fn description(&self) -> &str;
     fn schema(&self) -> Value;
     async fn execute(&self, args: Value) -> Result<Value>;
+
+    /// Schema results must match, checked by the registry and the agent
+    /// before a result reaches the model. `None` (the default) skips the
+    /// check; see [`output_schema`] for the keywords understood.
+    fn output_schema(&self) -> Option<Value> {
+        None
+    }
+
+    /// Execute, giving up as soon as `cancel` fires.
+    ///
+    /// The default races [`execute`](Self::execute) against the token and
+    /// drops the future on cancellation. Tools that spawn processes should
+    /// either set `kill_on_drop` or override this to kill the child directly.
+    async fn execute_cancellable(&self, args: Value, cancel: &CancellationToken) -> Result<Value> {
+        tokio::select! {
+            result = self.execute(args) => result,
+            _ = cancel.cancelled() => Err(crate::errors::ToolError::Cancelled.into()),
+        }
+    }
+}
+
+/// Pass `output` through if it matches the tool's output schema, otherwise
+/// fail with [`ToolError::InvalidOutput`](crate::errors::ToolError::InvalidOutput)
+pub fn validate_output(tool: &dyn Tool, output: Value) -> Result<Value> {
+    if let Some(schema) = tool.output_schema() {
+        if let Err(message) = output_schema::check(&schema, &output) {
+            return Err(crate::errors::ToolError::InvalidOutput {
+                name: tool.name().to_string(),
+                message,
+            }
+            .into());
+        }
+    }
+    Ok(output)
+}
+
+/// Task-scoped allow/deny lists restricting which registered tools are
+/// enabled. Entries are tool names or glob patterns such as `git_*`.
+#[derive(Debug, Clone, Default)]
+pub struct ToolFilter {
+    allow: Vec<glob::Pattern>,
+    deny: Vec<glob::Pattern>,
+}
+
+impl ToolFilter {
+    pub fn new(config: &crate::config::ToolsConfig) -> Result<Self> {
+        let compile = |key: &str, patterns: &[String]| {
+            patterns
+                .iter()
+                .map(|p| {
+                    glob::Pattern::new(p)
+                        .map_err(|e| anyhow::anyhow!("Invalid {} entry '{}': {}", key, p, e))
+                })
+                .collect::<Result<Vec<_>>>()
+        };
+        Ok(Self {
+            allow: compile("tools.allow", &config.allow)?,
+            deny: compile("tools.deny", &config.deny)?,
+        })
+    }
+
+    /// Whether any tool can be disabled by this filter
+    pub fn is_restricted(&self) -> bool {
+        !self.allow.is_empty() || !self.deny.is_empty()
+    }
+
+    pub fn allows(&self, name: &str) -> bool {
+        (self.allow.is_empty() || self.allow.iter().any(|p| p.matches(name)))
+            && !self.deny.iter().any(|p| p.matches(name))
+    }
 }
 
 /// Name-keyed registry of available tools. Created with all built-in tools
 /// pre-registered; additional tools can be added at runtime via [`register`](Self::register).
+/// A [`ToolFilter`] hides disabled tools from lookups, listings and definitions.
 pub struct ToolRegistry {
     tools: HashMap<String, Box<dyn Tool>>,
+    filter: ToolFilter,
 }
 
 impl ToolRegistry {
@@ -97,27 +190,35 @@ impl ToolRegistry {
     pub fn new() -> Self {
         let mut registry = Self {
             tools: HashMap::new(),
+            filter: ToolFilter::default(),
         };
 
         // File operations
         registry.register(FileRead::new());
         registry.register(FileWrite::new());
         registry.register(FileEdit::new());
+        registry.register(ApplyPatch::new());
+        registry.register(SymbolRename::new());
         registry.register(FileDelete::new());
         registry.register(DirectoryTree::new());
 
         // Git operations
         registry.register(GitStatus);
         registry.register(GitDiff);
+        registry.register(GitBlame);
         registry.register(GitCommit);
         registry.register(GitPush);
         registry.register(GitCheckpoint);
+        registry.register(GitBranchCreate);
+        registry.register(GitCheckout);
 
         // Cargo/Build operations
         registry.register(CargoTest);
         registry.register(CargoCheck);
         registry.register(CargoClippy);
         registry.register(CargoFmt);
+        registry.register(CargoAdd);
+        registry.register(CargoRemove);
 
         // System operations
         registry.register(ShellExec);
@@ -126,10 +227,19 @@ impl ToolRegistry {
         registry.register(GrepSearch);
         registry.register(GlobFind);
         registry.register(SymbolSearch);
+        registry.register(FindCycles);
 
         // HTTP/Web operations
         registry.register(HttpRequest);
 
+        // Contract tools
+        registry.register(ContractGenerate);
+        registry.register(ContractVerify);
+
+        // Review tools
+        registry.register(CodeReview);
+        registry.register(PanicRisk);
+
         // Process management operations
         registry.register(ProcessStart);
         registry.register(ProcessStop);
@@ -191,28 +301,71 @@ impl ToolRegistry {
         self.tools.insert(tool.name().to_string(), Box::new(tool));
     }
 
-    /// Look up a tool by name, returning `None` if not found.
+    /// Restrict the enabled tools. Fails if an entry matches no registered
+    /// tool, so a misspelt name does not silently change the tool set.
+    pub fn set_filter(&mut self, filter: ToolFilter) -> Result<()> {
+        for (key, patterns) in [("tools.allow", &filter.allow), ("tools.deny", &filter.deny)] {
+            if let Some(unmatched) = patterns
+                .iter()
+                .find(|p| !self.tools.keys().any(|name| p.matches(name)))
+            {
+                anyhow::bail!("{} entry '{}' matches no tool", key, unmatched);
+            }
+        }
+        self.filter = filter;
+        Ok(())
+    }
+
+    /// Whether `name` is registered but disabled by the filter
+    pub fn is_disabled(&self, name: &str) -> bool {
+        self.tools.contains_key(name) && !self.filter.allows(name)
+    }
+
+    /// Names of the enabled tools, sorted, when a filter restricts them
+    pub fn restricted_names(&self) -> Option<Vec<&str>> {
+        if !self.filter.is_restricted() {
+            return None;
+        }
+        let mut names: Vec<&str> = self.list().iter().map(|t| t.name()).collect();
+        names.sort_unstable();
+        Some(names)
+    }
+
+    /// Look up an enabled tool by name, returning `None` if not found.
     pub fn get(&self, name: &str) -> Option<&dyn Tool> {
-        self.tools.get(name).map(|t| t.as_ref())
+        self.tools
+            .get(name)
+            .filter(|_| self.filter.allows(name))
+            .map(|t| t.as_ref())
     }
 
-    /// Return references to all registered tools.
+    /// Return references to all enabled tools.
     pub fn list(&self) -> Vec<&dyn Tool> {
-        self.tools.values().map(|t| t.as_ref()).collect()
+        self.enabled().collect()
+    }
+
+    fn enabled(&self) -> impl Iterator<Item = &dyn Tool> {
+        self.tools
+            .iter()
+            .filter(|(name, _)| self.filter.allows(name))
+            .map(|(_, t)| t.as_ref())
     }
 
     /// Execute a tool by name with the given arguments
     pub async fn execute(&self, name: &str, args: serde_json::Value) -> Result<serde_json::Value> {
+        if self.is_disabled(name) {
+            anyhow::bail!("Tool '{}' is disabled for this task", name);
+        }
         let tool = self
             .get(name)
             .ok_or_else(|| anyhow::anyhow!("Unknown tool: {}", name))?;
-        tool.execute(args).await
+        let output = tool.execute(args).await?;
+        validate_output(tool, output)
     }
 
-    /// Build API-compatible tool definitions for all registered tools.
+    /// Build API-compatible tool definitions for all enabled tools.
     pub fn definitions(&self) -> Vec<crate::api::types::ToolDefinition> {
-        self.tools
-            .values()
+        self.enabled()
             .map(|tool| crate::api::types::ToolDefinition {
                 def_type: "function".to_string(),
                 function: crate::api::types::FunctionDefinition {
@@ -259,6 +412,51 @@ mod tests {
         assert!(tools.len() > 5);
     }
 
+    #[tokio::test]
+    async fn test_tool_filter_hides_and_rejects_disabled_tools() {
+        let mut registry = ToolRegistry::new();
+        assert!(registry.restricted_names().is_none());
+        let config = crate::config::ToolsConfig {
+            allow: vec!["file_read".into(), "git_*".into()],
+            deny: vec!["git_push".into()],
+        };
+        registry
+            .set_filter(ToolFilter::new(&config).unwrap())
+            .unwrap();
+
+        assert!(registry.get("file_read").is_some());
+        assert!(registry.get("git_status").is_some());
+        assert!(registry.get("git_push").is_none());
+        assert!(registry.get("shell_exec").is_none());
+        assert!(registry.is_disabled("shell_exec"));
+        assert!(!registry.is_disabled("nonexistent_tool"));
+
+        let names = registry.restricted_names().unwrap();
+        assert!(names.contains(&"git_diff"));
+        assert!(!names.contains(&"git_push"));
+        assert_eq!(registry.definitions().len(), names.len());
+
+        let err = registry
+            .execute("shell_exec", serde_json::json!({"command": "ls"}))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("disabled for this task"));
+    }
+
+    #[test]
+    fn test_tool_filter_rejects_unknown_names() {
+        let mut registry = ToolRegistry::new();
+        let config = crate::config::ToolsConfig {
+            allow: vec![],
+            deny: vec!["gti_*".into()],
+        };
+        let err = registry
+            .set_filter(ToolFilter::new(&config).unwrap())
+            .unwrap_err();
+        assert!(err.to_string().contains("gti_*"));
+        assert!(registry.restricted_names().is_none());
+    }
+
     #[test]
     fn test_tool_registry_default() {
         let registry = ToolRegistry::default();
@@ -403,6 +601,26 @@ mod tests {
         assert!(registry.get("browser_links").is_some());
     }
 
+    #[test]
+    fn test_contract_tools_registered() {
+        let registry = ToolRegistry::new();
+        assert!(registry.get("contract_generate").is_some());
+        assert!(registry.get("contract_verify").is_some());
+    }
+
+    #[test]
+    fn test_git_blame_tool_registered() {
+        let registry = ToolRegistry::new();
+        assert!(registry.get("git_blame").is_some());
+    }
+
+    #[test]
+    fn test_code_review_tool_registered() {
+        let registry = ToolRegistry::new();
+        assert!(registry.get("code_review").is_some());
+        assert!(registry.get("panic_risk").is_some());
+    }
+
     #[test]
     fn test_knowledge_tools_registered() {
         let registry = ToolRegistry::new();
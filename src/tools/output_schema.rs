@@ -0,0 +1,184 @@
+//! Checking tool results against a declared output schema
+//!
+//! A tool opts in by returning a schema from
+//! [`Tool::output_schema`](super::Tool::output_schema). A result that does
+//! not match becomes a [`ToolError::InvalidOutput`](crate::errors::ToolError)
+//! naming the first mismatch, so the model gets a short error it can act on
+//! instead of the malformed result.
+//!
+//! Only the JSON Schema keywords tool results need are understood: `type`,
+//! `enum`, `required`, `properties`, `items`, and `contentMediaType:
+//! "application/json"` on strings, for text (such as a command's stdout)
+//! that should itself parse as JSON. Other keywords are ignored.
+
+use serde_json::Value;
+
+/// Check `value` against `schema`, returning the first mismatch as
+/// `"<path>: <problem>"` with `$` for the root
+pub fn check(schema: &Value, value: &Value) -> Result<(), String> {
+    check_at(schema, value, "$")
+}
+
+fn check_at(schema: &Value, value: &Value, path: &str) -> Result<(), String> {
+    if let Some(expected) = schema.get("type") {
+        let allowed: Vec<&str> = match expected {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !allowed.is_empty() && !allowed.iter().any(|t| has_type(value, t)) {
+            return Err(format!(
+                "{}: expected {}, got {}",
+                path,
+                allowed.join(" or "),
+                describe(value)
+            ));
+        }
+    }
+
+    if let Some(options) = schema.get("enum").and_then(Value::as_array) {
+        if !options.contains(value) {
+            return Err(format!(
+                "{}: {} is not one of {}",
+                path,
+                value,
+                Value::from(options.clone())
+            ));
+        }
+    }
+
+    if let Some(text) = value.as_str() {
+        if schema.get("contentMediaType").and_then(Value::as_str) == Some("application/json") {
+            if let Err(e) = serde_json::from_str::<Value>(text) {
+                return Err(format!(
+                    "{}: expected JSON text, got {} ({})",
+                    path,
+                    excerpt(text),
+                    e
+                ));
+            }
+        }
+    }
+
+    if let Some(object) = value.as_object() {
+        if let Some(required) = schema.get("required").and_then(Value::as_array) {
+            if let Some(missing) = required
+                .iter()
+                .filter_map(Value::as_str)
+                .find(|key| !object.contains_key(*key))
+            {
+                return Err(format!("{}: missing required field '{}'", path, missing));
+            }
+        }
+        if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+            for (key, field_schema) in properties {
+                if let Some(field) = object.get(key) {
+                    check_at(field_schema, field, &format!("{}.{}", path, key))?;
+                }
+            }
+        }
+    }
+
+    if let (Some(items), Some(elements)) = (schema.get("items"), value.as_array()) {
+        for (i, element) in elements.iter().enumerate() {
+            check_at(items, element, &format!("{}[{}]", path, i))?;
+        }
+    }
+
+    Ok(())
+}
+
+fn has_type(value: &Value, name: &str) -> bool {
+    match name {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+/// Type name and a short excerpt of a value, for error messages
+fn describe(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => format!("boolean {}", b),
+        Value::Number(n) => format!("number {}", n),
+        Value::String(s) => format!("string {}", excerpt(s)),
+        Value::Array(a) => format!("array of {}", a.len()),
+        Value::Object(_) => "object".to_string(),
+    }
+}
+
+/// The start of `text`, quoted; enough to recognise what a tool produced
+fn excerpt(text: &str) -> String {
+    const MAX_CHARS: usize = 60;
+    let mut short: String = text.chars().take(MAX_CHARS).collect();
+    if text.chars().count() > MAX_CHARS {
+        short.push('…');
+    }
+    format!("{:?}", short)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_check_reports_first_mismatch_with_path() {
+        let schema = json!({
+            "type": "object",
+            "required": ["exit_code", "rows"],
+            "properties": {
+                "exit_code": {"type": "integer"},
+                "status": {"enum": ["ok", "failed"]},
+                "rows": {"type": "array", "items": {"type": "object", "required": ["id"]}}
+            }
+        });
+
+        assert!(check(&schema, &json!({"exit_code": 0, "rows": [{"id": 1}]})).is_ok());
+        assert_eq!(
+            check(&schema, &json!("cargo: command not found")).unwrap_err(),
+            "$: expected object, got string \"cargo: command not found\""
+        );
+        assert_eq!(
+            check(&schema, &json!({"exit_code": 0})).unwrap_err(),
+            "$: missing required field 'rows'"
+        );
+        assert_eq!(
+            check(&schema, &json!({"exit_code": 1.5, "rows": []})).unwrap_err(),
+            "$.exit_code: expected integer, got number 1.5"
+        );
+        assert_eq!(
+            check(&schema, &json!({"exit_code": 0, "rows": [{"id": 1}, {}]})).unwrap_err(),
+            "$.rows[1]: missing required field 'id'"
+        );
+        assert!(check(
+            &schema,
+            &json!({"exit_code": 0, "rows": [], "status": "maybe"})
+        )
+        .unwrap_err()
+        .starts_with("$.status: \"maybe\" is not one of"));
+    }
+
+    #[test]
+    fn test_check_json_text() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "stdout": {"type": "string", "contentMediaType": "application/json"}
+            }
+        });
+        assert!(check(&schema, &json!({"stdout": "{\"ok\": true}"})).is_ok());
+        let err = check(&schema, &json!({"stdout": "Usage: jq [OPTIONS]"})).unwrap_err();
+        assert!(
+            err.starts_with("$.stdout: expected JSON text, got \"Usage: jq [OPTIONS]\""),
+            "{}",
+            err
+        );
+    }
+}
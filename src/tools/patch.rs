@@ -0,0 +1,732 @@
+//! Unified diff application
+//!
+//! `apply_patch` accepts a standard (optionally multi-file) unified diff and
+//! applies every hunk or none of them. Hunks are located at their stated line
+//! first, then searched outward within [`MAX_FUZZ_OFFSET`] lines, and finally
+//! matched ignoring trailing whitespace, so a patch generated against a
+//! slightly older version of a file still applies.
+
+use super::file::{validate_tool_path, ToolRoots};
+use super::Tool;
+use crate::config::SafetyConfig;
+use crate::safety::path_validator::CapabilityDir;
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// How far (in lines) a hunk may drift from its stated position.
+const MAX_FUZZ_OFFSET: usize = 100;
+
+/// Applies unified diffs atomically across one or more files.
+#[derive(Default)]
+pub struct ApplyPatch {
+    /// Per-instance safety config. When `Some`, overrides the global `SAFETY_CONFIG`.
+    pub safety_config: Option<SafetyConfig>,
+    roots: ToolRoots,
+}
+
+impl ApplyPatch {
+    pub fn new() -> Self {
+        Self {
+            safety_config: None,
+            roots: ToolRoots::default(),
+        }
+    }
+    pub fn with_safety_config(config: SafetyConfig) -> Self {
+        Self {
+            roots: ToolRoots::default(),
+            safety_config: Some(config),
+        }
+    }
+}
+
+/// One `@@ -a,b +c,d @@` block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Hunk {
+    header: String,
+    old_start: usize,
+    /// Lines expected in the original (context and removals)
+    old_lines: Vec<String>,
+    /// Lines in the result (context and additions)
+    new_lines: Vec<String>,
+}
+
+/// All hunks targeting one file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FilePatch {
+    /// `None` when the file is being created
+    old_path: Option<String>,
+    /// `None` when the file is being deleted
+    new_path: Option<String>,
+    hunks: Vec<Hunk>,
+}
+
+impl FilePatch {
+    fn target(&self) -> &str {
+        self.new_path
+            .as_deref()
+            .or(self.old_path.as_deref())
+            .unwrap_or_default()
+    }
+}
+
+/// Where and how a hunk applied.
+#[derive(Debug, Clone, Serialize)]
+pub struct HunkReport {
+    pub index: usize,
+    pub header: String,
+    /// Line (1-based) in the original file where the hunk matched
+    pub line: usize,
+    /// Signed distance from the line stated in the header
+    pub offset: i64,
+    /// Whether trailing whitespace had to be ignored to match
+    pub fuzzy: bool,
+}
+
+fn strip_prefix(path: &str) -> Option<String> {
+    let path = path.split('\t').next().unwrap_or(path).trim();
+    if path == "/dev/null" {
+        return None;
+    }
+    Some(
+        path.strip_prefix("a/")
+            .or_else(|| path.strip_prefix("b/"))
+            .unwrap_or(path)
+            .to_string(),
+    )
+}
+
+/// Parse `start[,count]` from a hunk header range; `count` defaults to 1.
+fn parse_range(range: &str) -> Result<(usize, usize)> {
+    let mut parts = range.splitn(2, ',');
+    let start = parts.next().unwrap_or_default();
+    let count = parts.next().unwrap_or("1");
+    let parse = |s: &str| {
+        s.parse::<usize>()
+            .with_context(|| format!("Invalid hunk range '{}'", range))
+    };
+    Ok((parse(start)?, parse(count)?))
+}
+
+/// Parse a unified diff into per-file hunks.
+fn parse_patch(patch: &str) -> Result<Vec<FilePatch>> {
+    let mut files: Vec<FilePatch> = Vec::new();
+    let mut lines = patch.lines().peekable();
+
+    while let Some(line) = lines.next() {
+        if let Some(old) = line.strip_prefix("--- ") {
+            let Some(new) = lines.next().and_then(|l| l.strip_prefix("+++ ")) else {
+                bail!("Expected '+++' line after '--- {}'", old);
+            };
+            files.push(FilePatch {
+                old_path: strip_prefix(old),
+                new_path: strip_prefix(new),
+                hunks: Vec::new(),
+            });
+            continue;
+        }
+
+        if line.starts_with("@@") {
+            let Some(file) = files.last_mut() else {
+                bail!("Hunk '{}' appears before any '---'/'+++' file header", line);
+            };
+            let ranges = line
+                .trim_start_matches('@')
+                .split("@@")
+                .next()
+                .unwrap_or_default()
+                .trim();
+            let range = |sign: char| {
+                ranges
+                    .split_whitespace()
+                    .find_map(|r| r.strip_prefix(sign))
+                    .with_context(|| format!("Malformed hunk header '{}'", line))
+                    .and_then(parse_range)
+            };
+            let (old_start, old_count) = range('-')?;
+            let (_, new_count) = range('+')?;
+
+            let mut hunk = Hunk {
+                header: line.to_string(),
+                // A pure insertion ("-N,0") goes *after* line N
+                old_start: if old_count == 0 {
+                    old_start + 1
+                } else {
+                    old_start
+                },
+                old_lines: Vec::new(),
+                new_lines: Vec::new(),
+            };
+            // Consume exactly the line counts from the header, so removed
+            // lines that look like "--- x" are not mistaken for file headers.
+            let (mut old_seen, mut new_seen) = (0, 0);
+            while old_seen < old_count || new_seen < new_count {
+                let Some(body) = lines.next() else {
+                    bail!("Hunk '{}' ends before its stated line count", line);
+                };
+                if let Some(rest) = body.strip_prefix('+') {
+                    hunk.new_lines.push(rest.to_string());
+                    new_seen += 1;
+                } else if let Some(rest) = body.strip_prefix('-') {
+                    hunk.old_lines.push(rest.to_string());
+                    old_seen += 1;
+                } else if body.starts_with('\\') {
+                    // "\ No newline at end of file"
+                } else {
+                    // Context; some tools strip the leading space from blank lines
+                    let rest = body.strip_prefix(' ').unwrap_or(body);
+                    hunk.old_lines.push(rest.to_string());
+                    hunk.new_lines.push(rest.to_string());
+                    old_seen += 1;
+                    new_seen += 1;
+                }
+            }
+            while lines.peek().is_some_and(|l| l.starts_with('\\')) {
+                lines.next();
+            }
+            file.hunks.push(hunk);
+        }
+        // Anything else ("diff --git", "index ...") is informational
+    }
+
+    if files.is_empty() {
+        bail!("No file headers ('--- a/...' / '+++ b/...') found in patch");
+    }
+    if let Some(empty) = files.iter().find(|f| f.hunks.is_empty()) {
+        bail!("Patch for '{}' contains no hunks", empty.target());
+    }
+    Ok(files)
+}
+
+/// Files an `apply_patch` call would touch, resolved against its `base_dir`,
+/// for callers that validate or snapshot targets before execution.
+pub fn patch_targets(args: &Value) -> Vec<PathBuf> {
+    let Some(patch) = args.get("patch").and_then(|v| v.as_str()) else {
+        return Vec::new();
+    };
+    let base = args.get("base_dir").and_then(|v| v.as_str()).map(Path::new);
+    parse_patch(patch)
+        .map(|files| {
+            files
+                .iter()
+                .map(|f| match base {
+                    Some(base) => base.join(f.target()),
+                    None => PathBuf::from(f.target()),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Lines each file of an `apply_patch` call gains and loses, resolved
+/// against its `base_dir` like [`patch_targets`].
+pub fn line_changes(args: &Value) -> Vec<(PathBuf, usize, usize)> {
+    let Some(patch) = args.get("patch").and_then(|v| v.as_str()) else {
+        return Vec::new();
+    };
+    let base = args.get("base_dir").and_then(|v| v.as_str()).map(Path::new);
+    let Ok(files) = parse_patch(patch) else {
+        return Vec::new();
+    };
+    files
+        .iter()
+        .map(|f| {
+            let (mut added, mut removed) = (0, 0);
+            for hunk in &f.hunks {
+                let old: Vec<&str> = hunk.old_lines.iter().map(String::as_str).collect();
+                let new: Vec<&str> = hunk.new_lines.iter().map(String::as_str).collect();
+                for change in similar::TextDiff::from_slices(&old, &new).iter_all_changes() {
+                    match change.tag() {
+                        similar::ChangeTag::Insert => added += 1,
+                        similar::ChangeTag::Delete => removed += 1,
+                        similar::ChangeTag::Equal => {}
+                    }
+                }
+            }
+            let path = match base {
+                Some(base) => base.join(f.target()),
+                None => PathBuf::from(f.target()),
+            };
+            (path, added, removed)
+        })
+        .collect()
+}
+
+/// Lines a patch adds, for secret scanning before it is applied.
+pub fn added_lines(patch: &str) -> String {
+    parse_patch(patch)
+        .map(|files| {
+            files
+                .iter()
+                .flat_map(|f| &f.hunks)
+                .flat_map(|h| h.new_lines.iter().filter(|l| !h.old_lines.contains(l)))
+                .cloned()
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .unwrap_or_default()
+}
+
+fn matches_at(content: &[String], at: usize, expected: &[String], fuzzy: bool) -> bool {
+    if at + expected.len() > content.len() {
+        return false;
+    }
+    content[at..at + expected.len()]
+        .iter()
+        .zip(expected)
+        .all(|(have, want)| {
+            if fuzzy {
+                have.trim_end() == want.trim_end()
+            } else {
+                have == want
+            }
+        })
+}
+
+/// Find where `hunk` applies at or after `floor`, preferring the nearest
+/// position to `expected` (which is itself at least `floor`).
+fn locate(content: &[String], hunk: &Hunk, expected: usize, floor: usize) -> Option<(usize, bool)> {
+    if hunk.old_lines.is_empty() {
+        // Pure insertion: trust the header position
+        return Some((expected.min(content.len()), false));
+    }
+    for fuzzy in [false, true] {
+        for distance in 0..=MAX_FUZZ_OFFSET {
+            let candidates = [
+                expected.checked_add(distance),
+                expected.checked_sub(distance).filter(|at| *at >= floor),
+            ];
+            for at in candidates.into_iter().flatten() {
+                if matches_at(content, at, &hunk.old_lines, fuzzy) {
+                    return Some((at, fuzzy));
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Apply every hunk of `patch` to `original`, or report the first that fails.
+fn apply_file(original: &str, patch: &FilePatch) -> Result<(String, Vec<HunkReport>)> {
+    let trailing_newline = original.is_empty() || original.ends_with('\n');
+    let mut content: Vec<String> = original.lines().map(String::from).collect();
+    let mut reports = Vec::new();
+    // Shift between original line numbers and positions in `content`
+    let mut delta: i64 = 0;
+    // Hunks must apply in order and may not overlap
+    let mut floor = 0usize;
+
+    for (index, hunk) in patch.hunks.iter().enumerate() {
+        let stated = hunk.old_start.saturating_sub(1);
+        let expected = (stated as i64 + delta).max(floor as i64) as usize;
+        let Some((at, fuzzy)) = locate(&content, hunk, expected, floor) else {
+            let first = hunk
+                .old_lines
+                .first()
+                .map(|l| format!(" (expected '{}')", l.trim()))
+                .unwrap_or_default();
+            bail!(
+                "hunk {} {} did not apply: context not found within {} lines of line {}{}",
+                index + 1,
+                hunk.header,
+                MAX_FUZZ_OFFSET,
+                hunk.old_start,
+                first
+            );
+        };
+
+        content.splice(
+            at..at + hunk.old_lines.len(),
+            hunk.new_lines.iter().cloned(),
+        );
+        let original_line = (at as i64 - delta) as usize;
+        reports.push(HunkReport {
+            index: index + 1,
+            header: hunk.header.clone(),
+            line: original_line + 1,
+            offset: original_line as i64 - stated as i64,
+            fuzzy,
+        });
+        delta += hunk.new_lines.len() as i64 - hunk.old_lines.len() as i64;
+        floor = at + hunk.new_lines.len();
+    }
+
+    let mut result = content.join("\n");
+    if trailing_newline && !result.is_empty() {
+        result.push('\n');
+    }
+    Ok((result, reports))
+}
+
+/// Planned change for one file, computed before anything is written.
+struct PlannedChange<'a> {
+    path: PathBuf,
+    /// Capability root the file is written through, and its path below it
+    dir: &'a CapabilityDir,
+    rel: PathBuf,
+    original: Option<String>,
+    /// `None` deletes the file
+    new_content: Option<String>,
+    hunks: Vec<HunkReport>,
+}
+
+fn status(change: &PlannedChange) -> &'static str {
+    match (&change.original, &change.new_content) {
+        (None, _) => "created",
+        (_, None) => "deleted",
+        _ => "modified",
+    }
+}
+
+/// Write every planned change; on failure restore the files already written.
+fn commit_changes(changes: &[PlannedChange]) -> Result<()> {
+    for (done, change) in changes.iter().enumerate() {
+        let result = match &change.new_content {
+            Some(content) => change.dir.write_within(&change.rel, content.as_bytes()),
+            None => change.dir.remove_within(&change.rel),
+        };
+        if let Err(e) = result {
+            for written in &changes[..done] {
+                let _ = match &written.original {
+                    Some(original) => written.dir.write_within(&written.rel, original.as_bytes()),
+                    None => written.dir.remove_within(&written.rel),
+                };
+            }
+            return Err(e).with_context(|| {
+                format!(
+                    "Failed to write {}; earlier files were rolled back",
+                    change.path.display()
+                )
+            });
+        }
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl Tool for ApplyPatch {
+    fn name(&self) -> &str {
+        "apply_patch"
+    }
+
+    fn description(&self) -> &str {
+        "Apply a unified diff (one or more files, multiple hunks). All hunks apply or none do; \
+         hunks may be offset from their stated line. Prefer this over file_edit for multi-hunk changes."
+    }
+
+    fn schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "patch": {"type": "string", "description": "Unified diff with '--- a/path' / '+++ b/path' headers and '@@' hunks"},
+                "base_dir": {"type": "string", "description": "Directory patch paths are relative to (default: current directory)"}
+            },
+            "required": ["patch"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Args {
+            patch: String,
+            base_dir: Option<String>,
+        }
+
+        let args: Args = serde_json::from_value(args)?;
+        let files = parse_patch(&args.patch)?;
+        let base = args.base_dir.as_deref().map(Path::new);
+
+        let roots = self.roots.current(self.safety_config.as_ref())?;
+        let mut changes = Vec::new();
+        for file in &files {
+            let path = match base {
+                Some(base) => base.join(file.target()),
+                None => PathBuf::from(file.target()),
+            };
+            let path_str = path.to_string_lossy().to_string();
+            validate_tool_path(&path_str, self.safety_config.as_ref())?;
+            let (dir, rel) = roots.resolve(&path_str)?;
+
+            let original = match &file.old_path {
+                Some(_) => Some(
+                    dir.open_within(&rel)
+                        .and_then(|mut f| f.read_to_string())
+                        .with_context(|| format!("Failed to read {}", path_str))?,
+                ),
+                None => {
+                    if dir.exists_within(&rel)? {
+                        bail!("Patch creates {} but it already exists", path_str);
+                    }
+                    None
+                }
+            };
+
+            let (patched, hunks) = apply_file(original.as_deref().unwrap_or(""), file)
+                .with_context(|| format!("{}: patch rejected, no files changed", path_str))?;
+
+            changes.push(PlannedChange {
+                path,
+                dir,
+                rel,
+                original,
+                new_content: file.new_path.as_ref().map(|_| patched),
+                hunks,
+            });
+        }
+
+        commit_changes(&changes)?;
+
+        let hunks_applied: usize = changes.iter().map(|c| c.hunks.len()).sum();
+        Ok(serde_json::json!({
+            "success": true,
+            "hunks_applied": hunks_applied,
+            "files": changes
+                .iter()
+                .map(|c| serde_json::json!({
+                    "path": c.path.to_string_lossy(),
+                    "status": status(c),
+                    "hunks": c.hunks,
+                }))
+                .collect::<Vec<_>>(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    async fn run(dir: &TempDir, patch: &str) -> Result<Value> {
+        let tool = ApplyPatch::with_safety_config(SafetyConfig {
+            allowed_paths: vec![format!("{}/**", dir.path().display())],
+            ..Default::default()
+        });
+        let args = serde_json::json!({
+            "patch": patch,
+            "base_dir": dir.path().to_str().unwrap()
+        });
+        tool.execute(args).await
+    }
+
+    #[test]
+    fn test_apply_patch_name() {
+        assert_eq!(ApplyPatch::new().name(), "apply_patch");
+    }
+
+    #[test]
+    fn test_parse_patch_multi_file() {
+        let patch = "\
+diff --git a/a.txt b/a.txt
+--- a/a.txt
++++ b/a.txt
+@@ -1,2 +1,2 @@
+ one
+-two
++TWO
+--- /dev/null
++++ b/new.txt
+@@ -0,0 +1 @@
++hello
+";
+        let files = parse_patch(patch).unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].target(), "a.txt");
+        assert_eq!(files[0].hunks[0].old_lines, vec!["one", "two"]);
+        assert_eq!(files[0].hunks[0].new_lines, vec!["one", "TWO"]);
+        assert_eq!(files[1].old_path, None);
+        assert_eq!(
+            patch_targets(&serde_json::json!({"patch": patch, "base_dir": "/w"})),
+            vec![PathBuf::from("/w/a.txt"), PathBuf::from("/w/new.txt")]
+        );
+        assert_eq!(added_lines(patch), "TWO\nhello");
+        assert_eq!(
+            line_changes(&serde_json::json!({"patch": patch})),
+            vec![
+                (PathBuf::from("a.txt"), 1, 1),
+                (PathBuf::from("new.txt"), 1, 0)
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_apply_multi_hunk_with_offset() {
+        let dir = TempDir::new().unwrap();
+        // Two extra lines at the top shift every hunk by +2
+        fs::write(
+            dir.path().join("lib.rs"),
+            "// header\n// header\nfn a() {\n    1\n}\n\nfn b() {\n    2\n}\n",
+        )
+        .unwrap();
+        let patch = "\
+--- a/lib.rs
++++ b/lib.rs
+@@ -1,3 +1,3 @@
+ fn a() {
+-    1
++    10
+ }
+@@ -5,3 +5,3 @@
+ fn b() {
+-    2
++    20
+ }
+";
+        let result = run(&dir, patch).await.unwrap();
+        assert_eq!(result["hunks_applied"], 2);
+        assert_eq!(result["files"][0]["hunks"][0]["offset"], 2);
+        assert_eq!(result["files"][0]["status"], "modified");
+        assert_eq!(
+            fs::read_to_string(dir.path().join("lib.rs")).unwrap(),
+            "// header\n// header\nfn a() {\n    10\n}\n\nfn b() {\n    20\n}\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_later_hunk_skips_matches_before_earlier_hunk() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("f.txt"), "a\nb\nc\nd\ne\na\n").unwrap();
+        // The nearest "a" to the second hunk lies before the first hunk; the
+        // search must pass it over and find the one after
+        let patch = "--- a/f.txt\n+++ b/f.txt\n@@ -2,1 +2,1 @@\n-b\n+B\n@@ -3,1 +3,1 @@\n-a\n+A\n";
+        run(&dir, patch).await.unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.path().join("f.txt")).unwrap(),
+            "a\nB\nc\nd\ne\nA\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_patch_refuses_files_outside_allowed_roots() {
+        let dir = TempDir::new().unwrap();
+        let outside = TempDir::new().unwrap();
+        fs::write(outside.path().join("f.txt"), "a\n").unwrap();
+        let tool = ApplyPatch::with_safety_config(SafetyConfig {
+            allowed_paths: vec![format!("{}/**", dir.path().display())],
+            ..Default::default()
+        });
+        let args = serde_json::json!({
+            "patch": "--- a/f.txt\n+++ b/f.txt\n@@ -1,1 +1,1 @@\n-a\n+b\n",
+            "base_dir": outside.path().to_str().unwrap()
+        });
+        assert!(tool.execute(args).await.is_err());
+        assert_eq!(
+            fs::read_to_string(outside.path().join("f.txt")).unwrap(),
+            "a\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_apply_fuzzy_trailing_whitespace() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("f.txt"), "alpha   \nbeta\n").unwrap();
+        let patch = "--- a/f.txt\n+++ b/f.txt\n@@ -1,2 +1,2 @@\n alpha\n-beta\n+gamma\n";
+        let result = run(&dir, patch).await.unwrap();
+        assert_eq!(result["files"][0]["hunks"][0]["fuzzy"], true);
+        assert_eq!(
+            fs::read_to_string(dir.path().join("f.txt")).unwrap(),
+            "alpha\ngamma\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failed_hunk_rolls_back_all_files() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("a.txt"), "one\ntwo\n").unwrap();
+        fs::write(dir.path().join("b.txt"), "three\nfour\n").unwrap();
+        let patch = "\
+--- a/a.txt
++++ b/a.txt
+@@ -1,2 +1,2 @@
+ one
+-two
++2
+--- a/b.txt
++++ b/b.txt
+@@ -1,2 +1,2 @@
+ three
+-missing
++4
+";
+        let err = run(&dir, patch).await.unwrap_err();
+        let message = format!("{:#}", err);
+        assert!(message.contains("b.txt"));
+        assert!(message.contains("hunk 1"));
+        assert_eq!(
+            fs::read_to_string(dir.path().join("a.txt")).unwrap(),
+            "one\ntwo\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_and_delete_files() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("old.txt"), "bye\n").unwrap();
+        let patch = "\
+--- /dev/null
++++ b/sub/new.txt
+@@ -0,0 +1,2 @@
++hello
++world
+--- a/old.txt
++++ /dev/null
+@@ -1 +0,0 @@
+-bye
+";
+        let result = run(&dir, patch).await.unwrap();
+        assert_eq!(result["files"][0]["status"], "created");
+        assert_eq!(result["files"][1]["status"], "deleted");
+        assert_eq!(
+            fs::read_to_string(dir.path().join("sub/new.txt")).unwrap(),
+            "hello\nworld\n"
+        );
+        assert!(!dir.path().join("old.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn test_no_trailing_newline_preserved() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("f.txt"), "a\nb").unwrap();
+        let patch =
+            "--- a/f.txt\n+++ b/f.txt\n@@ -1,2 +1,2 @@\n a\n-b\n+c\n\\ No newline at end of file\n";
+        run(&dir, patch).await.unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.path().join("f.txt")).unwrap(),
+            "a\nc"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pure_insertion_goes_after_stated_line() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("f.txt"), "a\nb\nc\n").unwrap();
+        let patch = "--- a/f.txt\n+++ b/f.txt\n@@ -2,0 +3 @@\n+inserted\n";
+        run(&dir, patch).await.unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.path().join("f.txt")).unwrap(),
+            "a\nb\ninserted\nc\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_removed_line_resembling_header_is_not_a_file_header() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("f.sql"), "select 1;\n-- old comment\n").unwrap();
+        let patch = "--- a/f.sql\n+++ b/f.sql\n@@ -1,2 +1,2 @@\n select 1;\n--- old comment\n+-- new comment\n";
+        run(&dir, patch).await.unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.path().join("f.sql")).unwrap(),
+            "select 1;\n-- new comment\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rejects_patch_without_headers() {
+        let dir = TempDir::new().unwrap();
+        assert!(run(&dir, "@@ -1 +1 @@\n-a\n+b\n").await.is_err());
+        assert!(run(&dir, "just some text").await.is_err());
+    }
+}
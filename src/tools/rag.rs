@@ -0,0 +1,410 @@
+//! On-demand retrieval over the codebase
+//!
+//! `rag_search` lets the model pull in relevant code when it decides it needs
+//! more context, with file/line provenance and scores. Chunks it already
+//! returned are not inserted again until the context is trimmed, and each
+//! call stays within a token cap. While a focus set is active, chunks from
+//! the focused files rank higher. `rag_reindex` picks up changed files.
+
+use super::Tool;
+use crate::cognitive::rag::{RagConfig, RagEngine};
+use crate::token_count::estimate_content_tokens;
+use crate::vector_store::{EmbeddingBackend, SearchResult, TfIdfEmbeddingProvider};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// Dimension of the local TF-IDF embeddings
+const EMBEDDING_DIMENSION: usize = 384;
+
+/// Chunks returned by one `rag_search` call unless asked otherwise
+const DEFAULT_K: usize = 5;
+const MAX_K: usize = 20;
+
+/// Tokens one call may insert unless asked otherwise, and the hard cap
+const DEFAULT_MAX_TOKENS: usize = 4000;
+const MAX_INSERT_TOKENS: usize = 8000;
+
+/// Score multiplier for chunks from focused files
+const FOCUS_BOOST: f32 = 1.5;
+
+/// The index shared by the RAG tools, built on first use, and the chunks
+/// already inserted into the context
+pub struct RagState {
+    root: PathBuf,
+    engine: tokio::sync::Mutex<Option<RagEngine>>,
+    inserted: Mutex<HashSet<String>>,
+    focus: Mutex<Vec<PathBuf>>,
+}
+
+impl RagState {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            engine: tokio::sync::Mutex::new(None),
+            inserted: Mutex::new(HashSet::new()),
+            focus: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Rank chunks from these files or directories (absolute paths) above
+    /// others; an empty list removes the bias
+    pub fn set_focus(&self, paths: Vec<PathBuf>) {
+        if let Ok(mut focus) = self.focus.lock() {
+            *focus = paths;
+        }
+    }
+
+    /// Reorder results so chunks inside the focus set score `FOCUS_BOOST`
+    /// times higher
+    fn bias_to_focus(&self, results: &mut [SearchResult]) {
+        let Ok(focus) = self.focus.lock() else {
+            return;
+        };
+        if focus.is_empty() {
+            return;
+        }
+        for result in results.iter_mut() {
+            let file = self.root.join(&result.chunk.metadata.file_path);
+            if focus.iter().any(|f| file.starts_with(f)) {
+                result.score *= FOCUS_BOOST;
+            }
+        }
+        results.sort_by(|a, b| b.score.total_cmp(&a.score));
+    }
+
+    /// Forget which chunks were inserted, e.g. once the context was trimmed
+    /// or compressed and they may no longer be there
+    pub fn forget_inserted(&self) {
+        if let Ok(mut inserted) = self.inserted.lock() {
+            inserted.clear();
+        }
+    }
+
+    /// Chunks inserted into the context so far, as `file:start-end`
+    pub fn inserted_chunks(&self) -> Vec<String> {
+        let mut chunks: Vec<String> = self
+            .inserted
+            .lock()
+            .map(|inserted| inserted.iter().cloned().collect())
+            .unwrap_or_default();
+        chunks.sort();
+        chunks
+    }
+
+    fn new_engine(&self) -> RagEngine {
+        let provider = Arc::new(EmbeddingBackend::TfIdf(TfIdfEmbeddingProvider::new(
+            EMBEDDING_DIMENSION,
+        )));
+        let config = RagConfig {
+            top_k: MAX_K * 3,
+            min_score: 0.05,
+            max_context_tokens: MAX_INSERT_TOKENS,
+            ..Default::default()
+        };
+        RagEngine::new(&self.root, provider, config)
+    }
+}
+
+/// Key identifying a chunk across searches
+fn chunk_key(file: &std::path::Path, start_line: usize, end_line: usize) -> String {
+    format!("{}:{}-{}", file.display(), start_line, end_line)
+}
+
+pub struct RagSearch {
+    state: Arc<RagState>,
+}
+
+impl RagSearch {
+    pub fn new(state: Arc<RagState>) -> Self {
+        Self { state }
+    }
+}
+
+pub struct RagReindex {
+    state: Arc<RagState>,
+}
+
+impl RagReindex {
+    pub fn new(state: Arc<RagState>) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Tool for RagSearch {
+    fn name(&self) -> &str {
+        "rag_search"
+    }
+
+    fn description(&self) -> &str {
+        "Search the codebase by meaning and return the most relevant chunks with file, line \
+         range and score. Use when you need context you have not read yet; chunks already \
+         returned this session are skipped. Run rag_reindex after changing files."
+    }
+
+    fn schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "required": ["query"],
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "What to look for, e.g. 'where retries are configured'"
+                },
+                "k": {
+                    "type": "integer",
+                    "description": "Maximum chunks to return",
+                    "default": DEFAULT_K
+                },
+                "max_tokens": {
+                    "type": "integer",
+                    "description": "Token budget for the returned chunks (capped at 8000)",
+                    "default": DEFAULT_MAX_TOKENS
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: Value) -> Result<Value> {
+        let query = args
+            .get("query")
+            .and_then(|v| v.as_str())
+            .filter(|q| !q.trim().is_empty())
+            .context("Missing required parameter: query")?;
+        let k = args
+            .get("k")
+            .and_then(|v| v.as_u64())
+            .map_or(DEFAULT_K, |k| k as usize)
+            .clamp(1, MAX_K);
+        let max_tokens = args
+            .get("max_tokens")
+            .and_then(|v| v.as_u64())
+            .map_or(DEFAULT_MAX_TOKENS, |t| t as usize)
+            .min(MAX_INSERT_TOKENS);
+
+        let mut engine = self.state.engine.lock().await;
+        if engine.is_none() {
+            let mut built = self.state.new_engine();
+            built.build_index().await?;
+            *engine = Some(built);
+        }
+        let Some(engine) = engine.as_ref() else {
+            unreachable!("index built above");
+        };
+        // Ask for extra chunks so skipped ones can be replaced
+        let mut found = engine.search(query, k * 3).await?;
+        self.state.bias_to_focus(&mut found);
+
+        let mut inserted = self
+            .state
+            .inserted
+            .lock()
+            .map_err(|_| anyhow::anyhow!("RAG state lock poisoned"))?;
+        let mut results = Vec::new();
+        let mut skipped_in_context = 0;
+        let mut tokens = 0;
+        let mut truncated = false;
+        for result in found {
+            if results.len() >= k {
+                break;
+            }
+            let meta = &result.chunk.metadata;
+            let key = chunk_key(&meta.file_path, meta.start_line, meta.end_line);
+            if inserted.contains(&key) {
+                skipped_in_context += 1;
+                continue;
+            }
+            let chunk_tokens = estimate_content_tokens(&result.chunk.content);
+            if tokens + chunk_tokens > max_tokens {
+                truncated = true;
+                break;
+            }
+            tokens += chunk_tokens;
+            inserted.insert(key);
+            results.push(serde_json::json!({
+                "file": meta.file_path.display().to_string(),
+                "start_line": meta.start_line,
+                "end_line": meta.end_line,
+                "symbol": meta.symbol_name,
+                "score": result.score,
+                "content": result.chunk.content,
+            }));
+        }
+
+        Ok(serde_json::json!({
+            "query": query,
+            "results": results,
+            "skipped_in_context": skipped_in_context,
+            "tokens": tokens,
+            "truncated": truncated,
+            "indexed_files": engine.stats().total_files,
+        }))
+    }
+}
+
+#[async_trait]
+impl Tool for RagReindex {
+    fn name(&self) -> &str {
+        "rag_reindex"
+    }
+
+    fn description(&self) -> &str {
+        "Update the rag_search index after changing, adding or deleting files. Only changed \
+         files are re-indexed unless full is set."
+    }
+
+    fn schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "full": {
+                    "type": "boolean",
+                    "description": "Rebuild the whole index",
+                    "default": false
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: Value) -> Result<Value> {
+        let full = args.get("full").and_then(|v| v.as_bool()).unwrap_or(false);
+
+        let mut engine = self.state.engine.lock().await;
+        let (rebuilt, changed) = match engine.as_mut() {
+            Some(existing) if !full => (false, existing.update_index().await?.len()),
+            _ => {
+                let mut built = self.state.new_engine();
+                built.build_index().await?;
+                *engine = Some(built);
+                (true, 0)
+            }
+        };
+        let stats = engine
+            .as_ref()
+            .map(|e| e.stats().clone())
+            .unwrap_or_default();
+
+        Ok(serde_json::json!({
+            "rebuilt": rebuilt,
+            "changed_files": changed,
+            "indexed_files": stats.total_files,
+            "chunks": stats.total_chunks,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_fixture(dir: &std::path::Path) {
+        std::fs::write(
+            dir.join("retry.rs"),
+            "/// Exponential backoff for retrying failed requests\n\
+             pub fn retry_delay(attempt: u32) -> u64 {\n    100 * 2u64.pow(attempt)\n}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("render.rs"),
+            "/// Draw the garden widget\npub fn render_garden(width: u16) -> String {\n    \
+             \"flowers\".repeat(width as usize)\n}\n",
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_rag_search_returns_provenance_and_skips_inserted_chunks() {
+        let dir = tempfile::tempdir().unwrap();
+        write_fixture(dir.path());
+        let state = Arc::new(RagState::new(dir.path()));
+        let search = RagSearch::new(state.clone());
+        let args = serde_json::json!({"query": "retry backoff delay attempt", "k": 1});
+
+        let first = search.execute(args.clone()).await.unwrap();
+        let hit = &first["results"][0];
+        assert!(hit["file"].as_str().unwrap().ends_with("retry.rs"));
+        assert!(hit["start_line"].as_u64().unwrap() >= 1);
+        assert!(hit["content"].as_str().unwrap().contains("retry_delay"));
+        assert!(first["tokens"].as_u64().unwrap() > 0);
+
+        // The same chunk is not inserted twice
+        let second = search.execute(args.clone()).await.unwrap();
+        assert!(second["skipped_in_context"].as_u64().unwrap() >= 1);
+        assert!(second["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|r| r["content"] != hit["content"]));
+
+        state.forget_inserted();
+        let third = search.execute(args).await.unwrap();
+        assert_eq!(third["results"][0]["content"], hit["content"]);
+
+        assert!(search.execute(serde_json::json!({})).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rag_search_respects_token_cap_and_reindex_picks_up_files() {
+        let dir = tempfile::tempdir().unwrap();
+        write_fixture(dir.path());
+        let state = Arc::new(RagState::new(dir.path()));
+        let search = RagSearch::new(state.clone());
+
+        let capped = search
+            .execute(serde_json::json!({"query": "retry backoff delay attempt", "max_tokens": 1}))
+            .await
+            .unwrap();
+        assert!(capped["results"].as_array().unwrap().is_empty());
+        assert_eq!(capped["truncated"], true);
+
+        std::fs::write(
+            dir.path().join("cache.rs"),
+            "pub fn evict_lru(cache: &mut Cache) {\n    cache.pop_oldest();\n}\n",
+        )
+        .unwrap();
+        let reindex = RagReindex::new(state.clone());
+        let result = reindex.execute(serde_json::json!({})).await.unwrap();
+        assert_eq!(result["rebuilt"], false);
+        assert_eq!(result["changed_files"], 1);
+        assert_eq!(result["indexed_files"], 3);
+
+        let found = search
+            .execute(serde_json::json!({"query": "evict entries from the cache"}))
+            .await
+            .unwrap();
+        assert!(found["results"][0]["file"]
+            .as_str()
+            .unwrap()
+            .ends_with("cache.rs"));
+    }
+
+    #[tokio::test]
+    async fn test_rag_search_ranks_focused_files_first() {
+        let dir = tempfile::tempdir().unwrap();
+        write_fixture(dir.path());
+        let state = Arc::new(RagState::new(dir.path()));
+        let search = RagSearch::new(state.clone());
+        let args = serde_json::json!({"query": "retry_delay render_garden", "k": 2});
+
+        let unbiased = search.execute(args.clone()).await.unwrap();
+        let files: Vec<&str> = unbiased["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["file"].as_str().unwrap())
+            .collect();
+        assert_eq!(files.len(), 2, "{unbiased}");
+
+        state.forget_inserted();
+        let runner_up = dir
+            .path()
+            .join(std::path::Path::new(files[1]).file_name().unwrap());
+        state.set_focus(vec![runner_up.clone()]);
+        let biased = search.execute(args).await.unwrap();
+        assert_eq!(biased["results"][0]["file"], files[1], "{biased}");
+    }
+}
@@ -0,0 +1,386 @@
+//! Refactoring tools
+//!
+//! `symbol_rename` renames a Rust item and every reference to it across the
+//! crate. [`RenamePlan`] tells real references from unrelated text with the
+//! same spelling, and the edits are applied as one [`ApplyPatch`] so every
+//! file changes or none does.
+//!
+//! `find_cycles` reports circular dependencies between the crate's modules,
+//! each with the edge that is cheapest to cut.
+
+use super::patch::ApplyPatch;
+use super::Tool;
+use crate::analysis::code_graph::CycleEdge;
+use crate::analysis::references::{module_graph, RenamePlan};
+use crate::config::SafetyConfig;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// Renames a Rust item and its references in one atomic patch.
+#[derive(Default)]
+pub struct SymbolRename {
+    /// Per-instance safety config. When `Some`, overrides the global `SAFETY_CONFIG`.
+    pub safety_config: Option<SafetyConfig>,
+}
+
+impl SymbolRename {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn with_safety_config(config: SafetyConfig) -> Self {
+        Self {
+            safety_config: Some(config),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Args {
+    old_path: String,
+    new_name: String,
+    base_dir: Option<String>,
+    #[serde(default)]
+    dry_run: bool,
+}
+
+impl Args {
+    fn root(&self) -> &Path {
+        Path::new(self.base_dir.as_deref().unwrap_or("."))
+    }
+
+    /// `path` as seen from the working directory
+    fn display(&self, path: &Path) -> PathBuf {
+        match &self.base_dir {
+            Some(base) => Path::new(base).join(path),
+            None => path.to_path_buf(),
+        }
+    }
+}
+
+/// Files a `symbol_rename` call would edit, resolved against its `base_dir`,
+/// for callers that validate or snapshot targets before execution. Empty
+/// when the rename cannot be planned.
+pub fn rename_targets(args: &Value) -> Vec<PathBuf> {
+    let Ok(args) = Args::deserialize(args) else {
+        return Vec::new();
+    };
+    RenamePlan::build(args.root(), &args.old_path, &args.new_name)
+        .map(|plan| plan.files().iter().map(|f| args.display(f)).collect())
+        .unwrap_or_default()
+}
+
+/// Files a successful `symbol_rename` reported editing. Reads the leading
+/// JSON object, so text appended to the result is ignored.
+pub fn renamed_files(result: &str) -> Vec<PathBuf> {
+    serde_json::Deserializer::from_str(result)
+        .into_iter::<Value>()
+        .next()
+        .and_then(|v| v.ok())
+        .and_then(|v| v.get("files").cloned())
+        .and_then(|files| serde_json::from_value(files).ok())
+        .unwrap_or_default()
+}
+
+/// Unified diff of every file the plan rewrites
+fn plan_patch(plan: &RenamePlan) -> String {
+    plan.rewrite()
+        .iter()
+        .map(|(path, before, after)| {
+            let path = path.display();
+            similar::TextDiff::from_lines(before, after)
+                .unified_diff()
+                .context_radius(2)
+                .header(&format!("a/{}", path), &format!("b/{}", path))
+                .to_string()
+        })
+        .collect()
+}
+
+#[async_trait]
+impl Tool for SymbolRename {
+    fn name(&self) -> &str {
+        "symbol_rename"
+    }
+
+    fn description(&self) -> &str {
+        "Rename a Rust item (fn, struct, enum, trait, type, const, static) and every reference \
+         to it across the crate, including use declarations and re-exports. Unrelated names with \
+         the same text, fields, methods and shadowing locals are left alone. All files change or \
+         none do; returns the edited files. Prefer this over text replacement for renames."
+    }
+
+    fn schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "old_path": {"type": "string", "description": "Path of the item, e.g. 'crate::tools::patch::parse_patch' or 'tools::patch::parse_patch'"},
+                "new_name": {"type": "string", "description": "New identifier for the item"},
+                "base_dir": {"type": "string", "description": "Crate root containing Cargo.toml and src/ (default: current directory)"},
+                "dry_run": {"type": "boolean", "description": "Return the patch without applying it (default: false)"}
+            },
+            "required": ["old_path", "new_name"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> Result<Value> {
+        let args: Args = serde_json::from_value(args)?;
+        let plan = RenamePlan::build(args.root(), &args.old_path, &args.new_name)?;
+        let patch = plan_patch(&plan);
+        let files: Vec<String> = plan
+            .files()
+            .iter()
+            .map(|f| args.display(f).display().to_string())
+            .collect();
+
+        if args.dry_run {
+            return Ok(serde_json::json!({
+                "success": true,
+                "dry_run": true,
+                "definition": plan.definition,
+                "references": plan.references(),
+                "files": files,
+                "patch": patch,
+            }));
+        }
+
+        let mut patch_args = serde_json::json!({ "patch": patch });
+        if let Some(base) = &args.base_dir {
+            patch_args["base_dir"] = Value::from(base.as_str());
+        }
+        let apply = match &self.safety_config {
+            Some(config) => ApplyPatch::with_safety_config(config.clone()),
+            None => ApplyPatch::new(),
+        };
+        apply
+            .execute(patch_args)
+            .await
+            .with_context(|| format!("Rename of '{}' was not applied", args.old_path))?;
+
+        Ok(serde_json::json!({
+            "success": true,
+            "old_path": args.old_path,
+            "new_name": args.new_name,
+            "definition": plan.definition,
+            "references": plan.references(),
+            "files": files,
+        }))
+    }
+}
+
+/// Cycles reported when the call does not set `max_cycles`
+const DEFAULT_MAX_CYCLES: usize = 50;
+
+/// Reports module dependency cycles with a suggested break point.
+pub struct FindCycles;
+
+fn edge_json(edge: &CycleEdge) -> Value {
+    serde_json::json!({
+        "from": edge.from,
+        "to": edge.to,
+        "references": edge.weight as usize,
+    })
+}
+
+#[async_trait]
+impl Tool for FindCycles {
+    fn name(&self) -> &str {
+        "find_cycles"
+    }
+
+    fn description(&self) -> &str {
+        "Find circular dependencies between the crate's modules. Each cycle lists its modules, the \
+         edges forming it with their reference counts, and a suggested break point: the edge with \
+         the fewest references. Use before breaking circular dependencies. Output is sorted, so \
+         repeated runs match."
+    }
+
+    fn schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "base_dir": {"type": "string", "description": "Crate root containing Cargo.toml and src/ (default: current directory)"},
+                "include_nested": {"type": "boolean", "description": "Also count edges between a module and its own submodules, e.g. `use super::*` (default: false)"},
+                "max_cycles": {"type": "integer", "description": "Most cycles to list (default: 50)"}
+            }
+        })
+    }
+
+    async fn execute(&self, args: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Args {
+            base_dir: Option<String>,
+            #[serde(default)]
+            include_nested: bool,
+            max_cycles: Option<usize>,
+        }
+
+        let args: Args = serde_json::from_value(args)?;
+        let root = PathBuf::from(args.base_dir.as_deref().unwrap_or("."));
+        let include_nested = args.include_nested;
+        let graph = tokio::task::spawn_blocking(move || module_graph(&root, include_nested))
+            .await
+            .context("Module graph task failed")??;
+        let cycles = graph.dependency_cycles();
+        let max_cycles = args.max_cycles.unwrap_or(DEFAULT_MAX_CYCLES);
+
+        Ok(serde_json::json!({
+            "success": true,
+            "modules": graph.node_count(),
+            "dependencies": graph.edge_count(),
+            "cycle_count": cycles.len(),
+            "truncated": cycles.len() > max_cycles,
+            "cycles": cycles
+                .iter()
+                .take(max_cycles)
+                .map(|cycle| serde_json::json!({
+                    "members": cycle.members,
+                    "edges": cycle.edges.iter().map(edge_json).collect::<Vec<_>>(),
+                    "break_at": edge_json(&cycle.break_at),
+                }))
+                .collect::<Vec<_>>(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn demo_crate() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |path: &str, content: &str| {
+            let path = dir.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        write("Cargo.toml", "[package]\nname = \"demo\"\n");
+        write("src/lib.rs", "pub mod a;\npub mod b;\n");
+        write("src/a.rs", "pub struct Widget;\n\nimpl Widget {}\n");
+        write(
+            "src/b.rs",
+            "use crate::a::Widget;\n\npub fn make() -> Widget {\n    Widget\n}\n",
+        );
+        dir
+    }
+
+    fn args(dir: &tempfile::TempDir, dry_run: bool) -> Value {
+        serde_json::json!({
+            "old_path": "crate::a::Widget",
+            "new_name": "Gadget",
+            "base_dir": dir.path().to_str().unwrap(),
+            "dry_run": dry_run,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_symbol_rename_applies_across_files() {
+        let dir = demo_crate();
+        let tool = SymbolRename::with_safety_config(SafetyConfig {
+            allowed_paths: vec![format!("{}/**", dir.path().display())],
+            ..Default::default()
+        });
+
+        let preview = tool.execute(args(&dir, true)).await.unwrap();
+        assert!(preview["patch"]
+            .as_str()
+            .unwrap()
+            .contains("+use crate::a::Gadget;"));
+        assert_eq!(rename_targets(&args(&dir, false)).len(), 2);
+        let b = std::fs::read_to_string(dir.path().join("src/b.rs")).unwrap();
+        assert!(b.contains("Widget"), "dry run must not write");
+
+        let result = tool.execute(args(&dir, false)).await.unwrap();
+        assert_eq!(result["references"], 5);
+        assert_eq!(result["files"].as_array().unwrap().len(), 2);
+        assert_eq!(result["definition"], "src/a.rs:1");
+        let b = std::fs::read_to_string(dir.path().join("src/b.rs")).unwrap();
+        assert_eq!(
+            b,
+            "use crate::a::Gadget;\n\npub fn make() -> Gadget {\n    Gadget\n}\n"
+        );
+
+        let reported = renamed_files(&format!("{}\n\n<verification_failed>", result));
+        assert_eq!(reported.len(), 2);
+
+        // The old item is gone, so repeating the rename fails cleanly
+        let err = tool.execute(args(&dir, false)).await.unwrap_err();
+        assert!(err.to_string().contains("No item 'Widget'"), "{err}");
+        assert!(rename_targets(&args(&dir, false)).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_find_cycles_reports_module_cycles_and_weakest_edge() {
+        let dir = demo_crate();
+        let write = |path: &str, content: &str| {
+            std::fs::write(dir.path().join(path), content).unwrap();
+        };
+        write(
+            "src/lib.rs",
+            "pub mod a;\npub mod b;\npub mod c;\npub mod d;\n",
+        );
+        write(
+            "src/a.rs",
+            "use crate::b::{one, two};\n\npub fn a() {\n    one();\n    two();\n}\n",
+        );
+        write(
+            "src/b.rs",
+            "pub fn one() {\n    crate::a::a();\n}\npub fn two() {}\n",
+        );
+        write("src/c.rs", "pub mod inner;\npub use inner::x;\n");
+        std::fs::create_dir_all(dir.path().join("src/c")).unwrap();
+        write(
+            "src/c/inner.rs",
+            "use super::super::d;\npub fn x() {\n    super::x();\n}\n",
+        );
+        write("src/d.rs", "pub fn d() {\n    crate::c::x();\n}\n");
+        let base = dir.path().to_str().unwrap();
+
+        let result = FindCycles
+            .execute(serde_json::json!({ "base_dir": base }))
+            .await
+            .unwrap();
+        assert_eq!(result["cycle_count"], 1);
+        assert_eq!(result["truncated"], false);
+        let cycle = &result["cycles"][0];
+        assert_eq!(
+            cycle["members"],
+            serde_json::json!(["crate::a", "crate::b"])
+        );
+        assert_eq!(cycle["edges"].as_array().unwrap().len(), 2);
+        assert_eq!(
+            cycle["break_at"],
+            serde_json::json!({"from": "crate::b", "to": "crate::a", "references": 1})
+        );
+
+        // c re-exports its child, which calls back up: a cycle only when
+        // parent/child edges are counted
+        let nested = FindCycles
+            .execute(serde_json::json!({
+                "base_dir": base,
+                "include_nested": true,
+                "max_cycles": 1,
+            }))
+            .await
+            .unwrap();
+        assert_eq!(nested["cycle_count"], 2);
+        assert_eq!(nested["truncated"], true);
+        assert_eq!(nested["cycles"].as_array().unwrap().len(), 1);
+        let nested = FindCycles
+            .execute(serde_json::json!({ "base_dir": base, "include_nested": true }))
+            .await
+            .unwrap();
+        assert_eq!(
+            nested["cycles"][1]["members"],
+            serde_json::json!(["crate::c", "crate::c::inner", "crate::d"])
+        );
+
+        let again = FindCycles
+            .execute(serde_json::json!({ "base_dir": base }))
+            .await
+            .unwrap();
+        assert_eq!(again, result);
+    }
+}
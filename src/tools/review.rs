@@ -0,0 +1,276 @@
+//! Code review tool
+//!
+//! `code_review` reviews a diff the way a PR reviewer would: comments are
+//! anchored to lines the diff adds, categorized as bug, style, security or
+//! perf, and carry a severity and a confidence so nitpicks can be filtered.
+//!
+//! `panic_risk` lists the places a crate can panic, hottest first, with a
+//! safer pattern for each.
+
+use super::Tool;
+use crate::analysis::analyzer::panic_risk_in;
+use crate::testing::code_review::{CodeReviewAssistant, DiffAnalyzer, ReviewVerdict};
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// Findings listed when the caller gives no limit
+const DEFAULT_PANIC_RISK_LIMIT: usize = 50;
+
+pub struct CodeReview;
+
+#[async_trait]
+impl Tool for CodeReview {
+    fn name(&self) -> &str {
+        "code_review"
+    }
+
+    fn description(&self) -> &str {
+        "Review changes like a PR reviewer. Returns comments anchored to added lines, each with \
+         a kind (bug, style, security, perf), severity and confidence. Reviews the working tree \
+         diff unless a diff is given. Use when asked to review changes."
+    }
+
+    fn schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "diff": {
+                    "type": "string",
+                    "description": "Unified diff to review (default: git diff of the repository)"
+                },
+                "path": {
+                    "type": "string",
+                    "description": "Repository directory",
+                    "default": "."
+                },
+                "staged": {
+                    "type": "boolean",
+                    "description": "Review staged changes instead of the working tree",
+                    "default": false
+                },
+                "min_confidence": {
+                    "type": "number",
+                    "description": "Drop comments below this confidence (0.0-1.0), e.g. 0.5 to skip nitpicks",
+                    "default": 0.0
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: Value) -> Result<Value> {
+        let repo_path = args
+            .get("path")
+            .and_then(|v| v.as_str())
+            .unwrap_or(".")
+            .to_string();
+        let min_confidence = args
+            .get("min_confidence")
+            .and_then(|v| v.as_f64())
+            .unwrap_or(0.0) as f32;
+
+        let diff = match args.get("diff").and_then(|v| v.as_str()) {
+            Some(diff) => diff.to_string(),
+            None => {
+                let staged = args
+                    .get("staged")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+                let mut cmd = tokio::process::Command::new("git");
+                cmd.arg("-C").arg(&repo_path).arg("diff");
+                if staged {
+                    cmd.arg("--cached");
+                }
+                let output = cmd.output().await?;
+                if !output.status.success() {
+                    bail!(
+                        "git diff failed: {}",
+                        String::from_utf8_lossy(&output.stderr).trim()
+                    );
+                }
+                String::from_utf8_lossy(&output.stdout).to_string()
+            }
+        };
+
+        tokio::task::spawn_blocking(move || -> Result<Value> {
+            let assistant = CodeReviewAssistant::new();
+            // Whole-file checks need the new contents; they still only
+            // report on added lines
+            for file in DiffAnalyzer::new().parse_diff(&diff) {
+                if let Some(path) = file.new_path {
+                    if let Ok(content) = std::fs::read_to_string(Path::new(&repo_path).join(&path))
+                    {
+                        assistant.cache_file(path, content);
+                    }
+                }
+            }
+
+            let result = assistant.review_diff(&diff);
+            let comments = result.inline_comments(min_confidence);
+            let inline_total = result.comments.iter().filter(|c| c.line > 0).count();
+            let notes: Vec<&str> = result
+                .comments
+                .iter()
+                .filter(|c| c.line == 0)
+                .map(|c| c.body.as_str())
+                .collect();
+            let verdict = match result.verdict {
+                ReviewVerdict::Approved => "approved",
+                ReviewVerdict::RequestChanges => "request_changes",
+                ReviewVerdict::Comment => "comment",
+            };
+
+            Ok(serde_json::json!({
+                "verdict": verdict,
+                "files_reviewed": result.stats.files_reviewed,
+                "filtered": inline_total - comments.len(),
+                "comments": comments,
+                "notes": notes,
+            }))
+        })
+        .await?
+    }
+}
+
+pub struct PanicRisk;
+
+#[async_trait]
+impl Tool for PanicRisk {
+    fn name(&self) -> &str {
+        "panic_risk"
+    }
+
+    fn description(&self) -> &str {
+        "Find code that can panic: .unwrap(), .expect(), indexing and integer arithmetic. \
+         Findings are ranked by how hot the code is (public API, number of callers), marked as \
+         test or production code, and come with a safer pattern (`?`, `get()`, `checked_add`). \
+         Use when asked to make code more robust."
+    }
+
+    fn schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "base_dir": {"type": "string", "description": "Directory to scan for .rs files (default: current directory)"},
+                "include_tests": {"type": "boolean", "description": "Also list findings in test code (default: false)"},
+                "limit": {"type": "integer", "description": "Most findings to list (default: 50)"}
+            }
+        })
+    }
+
+    async fn execute(&self, args: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Args {
+            base_dir: Option<String>,
+            #[serde(default)]
+            include_tests: bool,
+            limit: Option<usize>,
+        }
+
+        let args: Args = serde_json::from_value(args)?;
+        let root = PathBuf::from(args.base_dir.as_deref().unwrap_or("."));
+        let report = tokio::task::spawn_blocking(move || panic_risk_in(&root))
+            .await
+            .context("Panic risk task failed")??;
+        let findings: Vec<_> = report
+            .findings
+            .iter()
+            .filter(|f| args.include_tests || !f.in_test)
+            .collect();
+        let limit = args.limit.unwrap_or(DEFAULT_PANIC_RISK_LIMIT);
+
+        Ok(serde_json::json!({
+            "success": true,
+            "files_scanned": report.files_scanned,
+            "counts": report.counts(),
+            "total": findings.len(),
+            "truncated": findings.len() > limit,
+            "findings": findings.into_iter().take(limit).collect::<Vec<_>>(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_code_review_comments_on_added_lines_only() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(
+            dir.path().join("src/lib.rs"),
+            "fn load() {\n    let a = old.unwrap();\n    let b = new.unwrap();\n    let c = b.clone();\n}\n",
+        )
+        .unwrap();
+        let diff = "diff --git a/src/lib.rs b/src/lib.rs\n--- a/src/lib.rs\n+++ b/src/lib.rs\n\
+                    @@ -1,3 +1,5 @@\n fn load() {\n     let a = old.unwrap();\n\
+                    +    let b = new.unwrap();\n+    let c = b.clone();\n }\n";
+
+        let result = CodeReview
+            .execute(serde_json::json!({
+                "diff": diff,
+                "path": dir.path().display().to_string(),
+            }))
+            .await
+            .unwrap();
+        let comments = result["comments"].as_array().unwrap();
+        assert_eq!(comments.len(), 2);
+        assert!(comments.iter().all(|c| c["path"] == "src/lib.rs"));
+        assert_eq!(comments[0]["line"], 3);
+        assert_eq!(comments[0]["kind"], "bug");
+        assert_eq!(comments[0]["severity"], "warning");
+        assert_eq!(comments[1]["line"], 4);
+        assert_eq!(comments[1]["kind"], "perf");
+
+        // Raising the bar drops the clone nitpick
+        let result = CodeReview
+            .execute(serde_json::json!({
+                "diff": diff,
+                "path": dir.path().display().to_string(),
+                "min_confidence": 0.5,
+            }))
+            .await
+            .unwrap();
+        assert_eq!(result["comments"].as_array().unwrap().len(), 1);
+        assert_eq!(result["filtered"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_panic_risk_skips_tests_unless_asked() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(
+            dir.path().join("src/lib.rs"),
+            "pub fn first(v: &[u8]) -> u8 {\n    v[0]\n}\n\n#[cfg(test)]\nmod tests {\n    \
+             #[test]\n    fn t() {\n        let _ = \"1\".parse::<u8>().unwrap();\n    }\n}\n",
+        )
+        .unwrap();
+        let base_dir = dir.path().display().to_string();
+
+        let result = PanicRisk
+            .execute(serde_json::json!({"base_dir": base_dir}))
+            .await
+            .unwrap();
+        assert_eq!(result["files_scanned"], 1);
+        let findings = result["findings"].as_array().unwrap();
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0]["file"], "src/lib.rs");
+        assert_eq!(findings[0]["line"], 2);
+        assert_eq!(findings[0]["kind"], "index");
+        assert_eq!(findings[0]["function"], "first");
+        assert_eq!(findings[0]["public"], true);
+        assert!(findings[0]["suggestion"].as_str().unwrap().contains("get"));
+        assert_eq!(result["counts"]["index"], 1);
+
+        let result = PanicRisk
+            .execute(serde_json::json!({"base_dir": base_dir, "include_tests": true, "limit": 1}))
+            .await
+            .unwrap();
+        assert_eq!(result["total"], 2);
+        assert_eq!(result["truncated"], true);
+        assert_eq!(result["findings"].as_array().unwrap().len(), 1);
+    }
+}
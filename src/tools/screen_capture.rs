@@ -8,6 +8,7 @@ use async_trait::async_trait;
 use serde_json::{json, Value};
 use xcap::image;
 
+use super::artifact::{self, Artifact};
 use super::Tool;
 
 /// Capture a screenshot of the screen, a window, or a region.
@@ -122,14 +123,16 @@ impl Tool for ScreenCapture {
         if let Some(path) = output_path {
             std::fs::write(path, &png_bytes)
                 .with_context(|| format!("Failed to write screenshot to {}", path))?;
-            Ok(json!({
+            let mut result = json!({
                 "success": true,
                 "target": target,
                 "width": img_width,
                 "height": img_height,
                 "output_path": path,
                 "file_size_bytes": png_bytes.len(),
-            }))
+            });
+            artifact::attach(&mut result, [Artifact::new(path, "image/png")]);
+            Ok(result)
         } else {
             let base64_data =
                 base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &png_bytes);
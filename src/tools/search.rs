@@ -73,12 +73,101 @@ pub struct SymbolSearch;
 struct GrepMatch {
     file: String,
     line: u32,
+    /// Last line of the match; differs from `line` only in multiline mode
+    end_line: u32,
     column: u32,
     content: String,
     context_before: Vec<String>,
     context_after: Vec<String>,
 }
 
+/// A contiguous block of output lines (matches plus context) in one file.
+/// Matches whose context overlaps share a hunk.
+#[derive(Debug, Serialize, Deserialize)]
+struct GrepHunk {
+    file: String,
+    start_line: u32,
+    end_line: u32,
+    matches: usize,
+}
+
+/// Location of a match within a file (0-based lines, 1-based column)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct LineMatch {
+    start_line: usize,
+    end_line: usize,
+    column: usize,
+}
+
+/// Assemble the regex source for `grep_search` from its flags.
+fn build_pattern(
+    pattern: &str,
+    case_insensitive: bool,
+    whole_word: bool,
+    fixed_string: bool,
+    multiline: bool,
+) -> String {
+    let mut source = if fixed_string {
+        regex::escape(pattern)
+    } else {
+        pattern.to_string()
+    };
+    if whole_word {
+        source = format!(r"\b(?:{})\b", source);
+    }
+    let mut flags = String::new();
+    if case_insensitive {
+        flags.push('i');
+    }
+    if multiline {
+        flags.push_str("ms");
+    }
+    if flags.is_empty() {
+        source
+    } else {
+        format!("(?{}){}", flags, source)
+    }
+}
+
+/// Find the first match on each line. In multiline mode the regex runs over
+/// the whole file so a match may span several lines.
+fn find_matches(regex: &Regex, content: &str, lines: &[&str], multiline: bool) -> Vec<LineMatch> {
+    if !multiline {
+        return lines
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, line)| {
+                regex.find(line).map(|m| LineMatch {
+                    start_line: idx,
+                    end_line: idx,
+                    column: m.start() + 1,
+                })
+            })
+            .collect();
+    }
+
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(content.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    let line_of = |offset: usize| line_starts.partition_point(|&start| start <= offset) - 1;
+    let last_line = lines.len().saturating_sub(1);
+
+    let mut found: Vec<LineMatch> = Vec::new();
+    for m in regex.find_iter(content) {
+        let start_line = line_of(m.start()).min(last_line);
+        if found.last().is_some_and(|prev| prev.end_line >= start_line) {
+            continue;
+        }
+        let end_line = line_of(m.end().saturating_sub(1).max(m.start())).min(last_line);
+        found.push(LineMatch {
+            start_line,
+            end_line,
+            column: m.start() - line_starts[start_line] + 1,
+        });
+    }
+    found
+}
+
 /// Result of a glob find operation
 #[derive(Debug, Serialize, Deserialize)]
 struct FileInfo {
@@ -104,7 +193,7 @@ impl Tool for GrepSearch {
     }
 
     fn description(&self) -> &str {
-        "Search for regex patterns in files. Returns matching lines with context. Use for finding code patterns, error messages, or specific text."
+        "Search for regex patterns in files. Returns matching lines with context, grouped into hunks; adjacent matches share context instead of repeating it. Use for finding code patterns, error messages, or specific text."
     }
 
     fn schema(&self) -> Value {
@@ -135,6 +224,29 @@ impl Tool for GrepSearch {
                     "default": 2,
                     "description": "Lines of context before and after match"
                 },
+                "before": {
+                    "type": "integer",
+                    "description": "Lines of context before each match (like rg -B); overrides context_lines"
+                },
+                "after": {
+                    "type": "integer",
+                    "description": "Lines of context after each match (like rg -A); overrides context_lines"
+                },
+                "whole_word": {
+                    "type": "boolean",
+                    "default": false,
+                    "description": "Only match at word boundaries"
+                },
+                "fixed_string": {
+                    "type": "boolean",
+                    "default": false,
+                    "description": "Treat the pattern as a literal string, not a regex"
+                },
+                "multiline": {
+                    "type": "boolean",
+                    "default": false,
+                    "description": "Let matches span lines ('.' also matches newlines)"
+                },
                 "max_matches": {
                     "type": "integer",
                     "default": 100,
@@ -182,6 +294,18 @@ impl Tool for GrepSearch {
                 .get("context_lines")
                 .and_then(|v| v.as_u64())
                 .unwrap_or(2) as usize;
+            let before = args
+                .get("before")
+                .and_then(|v| v.as_u64())
+                .map_or(context_lines, |v| v as usize);
+            let after = args
+                .get("after")
+                .and_then(|v| v.as_u64())
+                .map_or(context_lines, |v| v as usize);
+            let flag = |name: &str| args.get(name).and_then(|v| v.as_bool()).unwrap_or(false);
+            let whole_word = flag("whole_word");
+            let fixed_string = flag("fixed_string");
+            let multiline = flag("multiline");
             let max_matches = args
                 .get("max_matches")
                 .and_then(|v| v.as_u64())
@@ -191,11 +315,13 @@ impl Tool for GrepSearch {
             let exclude_pattern = args.get("exclude").and_then(|v| v.as_str());
 
             // Build regex (uses a bounded cache to avoid recompilation)
-            let full_pattern = if case_insensitive {
-                format!("(?i){}", pattern_str)
-            } else {
-                pattern_str.to_string()
-            };
+            let full_pattern = build_pattern(
+                pattern_str,
+                case_insensitive,
+                whole_word,
+                fixed_string,
+                multiline,
+            );
             let regex = cached_regex(&full_pattern)?;
 
             // Build include/exclude globs
@@ -210,6 +336,7 @@ impl Tool for GrepSearch {
 
             let path = Path::new(path_str);
             let mut matches = Vec::new();
+            let mut hunks: Vec<GrepHunk> = Vec::new();
             let mut total_matches = 0;
 
             // Collect files to search
@@ -268,47 +395,74 @@ impl Tool for GrepSearch {
                 };
 
                 let lines: Vec<&str> = content.lines().collect();
+                let file_matches = find_matches(&regex, &content, &lines, multiline);
+                let file = file_path.to_string_lossy().to_string();
+                // Last line index already emitted (as a match or context) in this file
+                let mut shown_until: Option<usize> = None;
 
-                for (line_num, line) in lines.iter().enumerate() {
+                for (i, found) in file_matches.iter().enumerate() {
                     if matches.len() >= max_matches {
                         break;
                     }
+                    total_matches += 1;
 
-                    if let Some(m) = regex.find(line) {
-                        total_matches += 1;
+                    // Skip matches before the offset
+                    if total_matches <= skip_offset {
+                        continue;
+                    }
 
-                        // Skip matches before the offset
-                        if total_matches <= skip_offset {
-                            continue;
+                    // Context already shown for the previous match is not repeated,
+                    // and after-context stops where the next match begins.
+                    let first_unshown = shown_until.map_or(0, |l| l + 1);
+                    let before_start = found.start_line.saturating_sub(before).max(first_unshown);
+                    let mut after_end = (found.end_line + 1 + after).min(lines.len());
+                    if matches.len() + 1 < max_matches {
+                        if let Some(next) = file_matches.get(i + 1) {
+                            after_end = after_end.min(next.start_line);
                         }
-
-                        // Get context
-                        let start = line_num.saturating_sub(context_lines);
-                        let end = (line_num + context_lines + 1).min(lines.len());
-
-                        let context_before: Vec<String> = lines[start..line_num]
-                            .iter()
-                            .map(|s| s.to_string())
-                            .collect();
-
-                        let context_after: Vec<String> = if line_num + 1 < lines.len() {
-                            lines[(line_num + 1)..end]
-                                .iter()
-                                .map(|s| s.to_string())
-                                .collect()
-                        } else {
-                            vec![]
-                        };
-
-                        matches.push(GrepMatch {
-                            file: file_path.to_string_lossy().to_string(),
-                            line: (line_num + 1) as u32,
-                            column: (m.start() + 1) as u32,
-                            content: line.to_string(),
-                            context_before,
-                            context_after,
+                    }
+                    let after_start = (found.end_line + 1).min(after_end);
+
+                    let starts_new_hunk = match shown_until {
+                        Some(l) => before_start > l + 1,
+                        None => true,
+                    };
+                    if starts_new_hunk {
+                        hunks.push(GrepHunk {
+                            file: file.clone(),
+                            start_line: (before_start + 1) as u32,
+                            end_line: 0,
+                            matches: 0,
                         });
                     }
+                    if let Some(hunk) = hunks.last_mut() {
+                        hunk.end_line = after_end.max(found.end_line + 1) as u32;
+                        hunk.matches += 1;
+                    }
+                    shown_until = Some(after_end.saturating_sub(1).max(found.end_line));
+
+                    matches.push(GrepMatch {
+                        file: file.clone(),
+                        line: (found.start_line + 1) as u32,
+                        end_line: (found.end_line + 1) as u32,
+                        column: found.column as u32,
+                        content: lines
+                            .get(found.start_line..=found.end_line)
+                            .map(|l| l.join("\n"))
+                            .unwrap_or_default(),
+                        context_before: lines
+                            .get(before_start..found.start_line)
+                            .unwrap_or_default()
+                            .iter()
+                            .map(|s| s.to_string())
+                            .collect(),
+                        context_after: lines
+                            .get(after_start..after_end)
+                            .unwrap_or_default()
+                            .iter()
+                            .map(|s| s.to_string())
+                            .collect(),
+                    });
                 }
             }
 
@@ -317,6 +471,7 @@ impl Tool for GrepSearch {
 
             Ok(serde_json::json!({
                 "matches": matches,
+                "hunks": hunks,
                 "count": matches.len(),
                 "total_matches": total_matches,
                 "truncated": truncated,
@@ -601,7 +756,7 @@ static SYMBOL_REGEXES: Lazy<SymbolRegexes> = Lazy::new(|| SymbolRegexes {
 
 /// Build regex patterns for different Rust symbol types.
 /// The underlying regexes are compiled once via `Lazy` statics.
-fn build_symbol_patterns(
+pub(crate) fn build_symbol_patterns(
     symbol_type: &str,
     _name_pattern: &str,
 ) -> Result<Vec<(&'static Regex, &'static str)>> {
@@ -747,6 +902,126 @@ mod tests {
         assert!(result["truncated"].as_bool().unwrap());
     }
 
+    #[tokio::test]
+    async fn test_grep_search_before_after_override_context_lines() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("test.rs");
+        fs::write(&file_path, "a\nb\nc\nMATCH\nd\ne\nf\n").unwrap();
+
+        let tool = GrepSearch;
+        let result = tool
+            .execute(serde_json::json!({
+                "pattern": "MATCH",
+                "path": file_path.to_str().unwrap(),
+                "before": 3,
+                "after": 1
+            }))
+            .await
+            .unwrap();
+
+        let m = &result["matches"][0];
+        assert_eq!(m["context_before"], serde_json::json!(["a", "b", "c"]));
+        assert_eq!(m["context_after"], serde_json::json!(["d"]));
+        assert_eq!(result["hunks"][0]["start_line"], 1);
+        assert_eq!(result["hunks"][0]["end_line"], 5);
+    }
+
+    #[tokio::test]
+    async fn test_grep_search_adjacent_matches_share_hunk() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("test.rs");
+        fs::write(&file_path, "x\nhit 1\ny\nhit 2\nz\n\n\n\n\nhit 3\n").unwrap();
+
+        let tool = GrepSearch;
+        let result = tool
+            .execute(serde_json::json!({
+                "pattern": "hit",
+                "path": file_path.to_str().unwrap(),
+                "context_lines": 1
+            }))
+            .await
+            .unwrap();
+
+        let matches = result["matches"].as_array().unwrap();
+        assert_eq!(matches.len(), 3);
+        // "y" is shown once, as after-context of the first match only
+        assert_eq!(matches[0]["context_after"], serde_json::json!(["y"]));
+        assert!(matches[1]["context_before"].as_array().unwrap().is_empty());
+
+        let hunks = result["hunks"].as_array().unwrap();
+        assert_eq!(hunks.len(), 2);
+        assert_eq!(hunks[0]["matches"], 2);
+        assert_eq!(hunks[1]["start_line"], 9);
+    }
+
+    #[tokio::test]
+    async fn test_grep_search_fixed_string_and_whole_word() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("test.rs");
+        fs::write(&file_path, "let v = vec![1];\nlet vector = 2;\nvec!\n").unwrap();
+
+        let tool = GrepSearch;
+        let literal = tool
+            .execute(serde_json::json!({
+                "pattern": "vec![",
+                "path": file_path.to_str().unwrap(),
+                "fixed_string": true
+            }))
+            .await
+            .unwrap();
+        assert_eq!(literal["count"], 1);
+        assert_eq!(literal["matches"][0]["column"], 9);
+
+        let word = tool
+            .execute(serde_json::json!({
+                "pattern": "vec",
+                "path": file_path.to_str().unwrap(),
+                "whole_word": true
+            }))
+            .await
+            .unwrap();
+        // "vector" is excluded
+        assert_eq!(word["count"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_grep_search_multiline_spans_lines() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("test.rs");
+        fs::write(
+            &file_path,
+            "fn main() {\n    foo(\n        bar,\n    );\n}\n",
+        )
+        .unwrap();
+
+        let tool = GrepSearch;
+        let result = tool
+            .execute(serde_json::json!({
+                "pattern": r"foo\(.*?\);",
+                "path": file_path.to_str().unwrap(),
+                "multiline": true,
+                "context_lines": 0
+            }))
+            .await
+            .unwrap();
+
+        assert_eq!(result["count"], 1);
+        let m = &result["matches"][0];
+        assert_eq!(m["line"], 2);
+        assert_eq!(m["end_line"], 4);
+        assert_eq!(m["content"], "    foo(\n        bar,\n    );");
+    }
+
+    #[test]
+    fn test_build_pattern_flags() {
+        assert_eq!(build_pattern("a.b", false, false, true, false), r"a\.b");
+        assert_eq!(
+            build_pattern("x", true, true, false, false),
+            r"(?i)\b(?:x)\b"
+        );
+        assert_eq!(build_pattern("x", false, false, false, true), "(?ms)x");
+    }
+
     #[tokio::test]
     async fn test_glob_find_basic() {
         let tool = GlobFind;
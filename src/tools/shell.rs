@@ -1,4 +1,4 @@
-use super::Tool;
+use super::{CancellationToken, Tool};
 use anyhow::Result;
 use async_trait::async_trait;
 use serde::Deserialize;
@@ -6,6 +6,7 @@ use serde_json::Value;
 use std::collections::HashMap;
 use std::path::Path;
 use std::time::Duration;
+use tracing::warn;
 
 /// Returns the platform-appropriate shell and flag for command execution.
 ///
@@ -18,6 +19,340 @@ pub fn default_shell() -> (&'static str, &'static str) {
     }
 }
 
+/// Quote one argument for display, the way a POSIX shell would need it.
+fn quote_arg(arg: &str) -> String {
+    let plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=:,+@%".contains(c));
+    if plain {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
+
+/// The command a `shell_exec` call runs, as one line: the `command` string,
+/// or the `program` and `args` of the argv form with arguments quoted.
+///
+/// Safety checks and displays use this so both forms are treated alike.
+pub fn command_line(args: &Value) -> Option<String> {
+    if let Some(command) = args.get("command").and_then(|v| v.as_str()) {
+        return Some(command.to_string());
+    }
+    let program = args.get("program").and_then(|v| v.as_str())?;
+    let mut line = quote_arg(program);
+    for arg in args
+        .get("args")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+    {
+        line.push(' ');
+        line.push_str(&quote_arg(arg.as_str().unwrap_or_default()));
+    }
+    Some(line)
+}
+
+/// Every command line a safety check should judge for a `shell_exec` call:
+/// the `command` string, or for the argv form the arguments as the program
+/// receives them (unquoted) plus any shell script hidden in them.
+pub fn checked_commands(args: &Value) -> Vec<String> {
+    if let Some(command) = args.get("command").and_then(|v| v.as_str()) {
+        return vec![command.to_string()];
+    }
+    let Some(program) = args.get("program").and_then(|v| v.as_str()) else {
+        return Vec::new();
+    };
+    let argv: Vec<String> = args
+        .get("args")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .map(|a| a.as_str().unwrap_or_default().to_string())
+        .collect();
+    let mut commands = vec![std::iter::once(program)
+        .chain(argv.iter().map(String::as_str))
+        .collect::<Vec<_>>()
+        .join(" ")];
+    commands.extend(argv_scripts(program, &argv).into_iter().map(str::to_string));
+    commands
+}
+
+/// Shell command strings an argv command hands to a shell: the script of
+/// a shell run with `-c` (also inside flag clusters like `-ec`), looking
+/// past `env` and `exec` wrappers, and the command line of `env -S`.
+fn argv_scripts<'a>(program: &'a str, args: &'a [String]) -> Vec<&'a str> {
+    let mut scripts = Vec::new();
+    let (mut program, mut rest) = (program, args);
+    loop {
+        let name = Path::new(program)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or(program);
+        let mut i = 0;
+        match name {
+            "env" => {
+                while let Some(arg) = rest.get(i) {
+                    match arg.as_str() {
+                        "-u" | "--unset" | "-C" | "--chdir" => i += 2,
+                        "-S" | "--split-string" => {
+                            scripts.extend(rest.get(i + 1).map(String::as_str));
+                            i += 2;
+                        }
+                        _ if arg.starts_with("--split-string=") => {
+                            scripts.push(&arg["--split-string=".len()..]);
+                            i += 1;
+                        }
+                        _ if arg.starts_with('-') || arg.contains('=') => i += 1,
+                        _ => break,
+                    }
+                }
+            }
+            "exec" => {
+                while let Some(arg) = rest.get(i) {
+                    match arg.as_str() {
+                        "-a" => i += 2,
+                        _ if arg.starts_with('-') => i += 1,
+                        _ => break,
+                    }
+                }
+            }
+            _ if is_shell(name) => {
+                scripts.extend(shell_c_script(rest));
+                return scripts;
+            }
+            _ => return scripts,
+        }
+        let Some(next) = rest.get(i) else {
+            return scripts;
+        };
+        program = next;
+        rest = &rest[i + 1..];
+    }
+}
+
+/// The command string of a shell given `-c`, alone or in a cluster such as
+/// `-ec` or `-lc`: the first operand after the options
+fn shell_c_script(args: &[String]) -> Option<&str> {
+    let mut reads_command = false;
+    let mut i = 0;
+    while let Some(arg) = args.get(i) {
+        i += 1;
+        match arg.as_str() {
+            "--" => break,
+            // Options that take a value
+            "-o" | "+o" | "-O" | "+O" => i += 1,
+            _ if arg.starts_with("--") => {}
+            _ if arg.starts_with('-') || arg.starts_with('+') => {
+                reads_command |= arg.starts_with('-') && arg.contains('c');
+            }
+            _ => return reads_command.then_some(arg.as_str()),
+        }
+    }
+    args.get(i).filter(|_| reads_command).map(String::as_str)
+}
+
+/// Programs that write the files named by their operands; the second field
+/// says whether only the last operand (the destination) is written
+const FILE_WRITING_PROGRAMS: &[(&str, bool)] = &[
+    ("rm", false),
+    ("rmdir", false),
+    ("unlink", false),
+    ("shred", false),
+    ("mv", false),
+    ("touch", false),
+    ("mkdir", false),
+    ("chmod", false),
+    ("chown", false),
+    ("truncate", false),
+    ("tee", false),
+    ("cp", true),
+    ("ln", true),
+    ("install", true),
+];
+
+/// Git subcommands that rewrite the working tree
+const TREE_WRITING_GIT: &[&str] = &[
+    "checkout",
+    "switch",
+    "restore",
+    "reset",
+    "stash",
+    "apply",
+    "am",
+    "pull",
+    "merge",
+    "rebase",
+    "cherry-pick",
+    "revert",
+    "rm",
+    "mv",
+    "clean",
+];
+
+/// Paths a command line may write, relative to the directory it runs in:
+/// operands of file-writing programs, redirect targets, and `.` for commands
+/// that can rewrite anything in the tree (`cargo fmt`, `git checkout`,
+/// `patch`, ...). Read-only commands such as `ls`, `git status` or
+/// `cargo test` yield nothing.
+pub fn written_paths(command: &str) -> Vec<String> {
+    let tokens = shlex::split(command)
+        .unwrap_or_else(|| command.split_whitespace().map(str::to_string).collect());
+    let mut written = Vec::new();
+    let mut segment = Vec::new();
+    let mut tokens = tokens.into_iter();
+    while let Some(token) = tokens.next() {
+        let token = match token.strip_suffix(';') {
+            Some(head) if !head.is_empty() => {
+                segment.push(head.to_string());
+                ";".to_string()
+            }
+            _ => token,
+        };
+        if matches!(token.as_str(), "&&" | "||" | ";" | "|" | "&") {
+            written.extend(segment_written_paths(&std::mem::take(&mut segment)));
+            continue;
+        }
+        let redirect = token.trim_start_matches(|c: char| c.is_ascii_digit() || c == '&');
+        if let Some(target) = redirect
+            .strip_prefix(">>")
+            .or_else(|| redirect.strip_prefix('>'))
+        {
+            let target = match target.strip_prefix('|').unwrap_or(target) {
+                "" => tokens.next().unwrap_or_default(),
+                target => target.to_string(),
+            };
+            if !target.is_empty() && !target.starts_with('&') && target != "/dev/null" {
+                written.push(target);
+            }
+            continue;
+        }
+        segment.push(token);
+    }
+    written.extend(segment_written_paths(&segment));
+    written
+}
+
+/// Paths one simple command (no pipes or `&&`) may write
+fn segment_written_paths(words: &[String]) -> Vec<String> {
+    let start = words
+        .iter()
+        .position(|w| {
+            (!w.contains('=') || w.starts_with('-'))
+                && !matches!(
+                    w.as_str(),
+                    "sudo" | "env" | "exec" | "nohup" | "time" | "command"
+                )
+        })
+        .unwrap_or(words.len());
+    let Some((program, args)) = words[start..].split_first() else {
+        return Vec::new();
+    };
+    let name = Path::new(program)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(program);
+    let operands: Vec<&String> = args.iter().filter(|a| !a.starts_with('-')).collect();
+    let has_flag = |flag: &str| args.iter().any(|a| a == flag);
+    let tree = || vec![".".to_string()];
+
+    if let Some((_, last_only)) = FILE_WRITING_PROGRAMS.iter().find(|(p, _)| *p == name) {
+        let written = if *last_only {
+            &operands[operands.len().saturating_sub(1)..]
+        } else {
+            &operands[..]
+        };
+        return written.iter().map(|s| s.to_string()).collect();
+    }
+    match name {
+        "sed" | "perl"
+            if args
+                .iter()
+                .any(|a| a.starts_with("-i") || a.starts_with("--in-place")) =>
+        {
+            // Without -e the first operand of sed is the script
+            let skip = usize::from(name == "sed" && !has_flag("-e") && !has_flag("-f"));
+            operands.into_iter().skip(skip).cloned().collect()
+        }
+        "dd" => args
+            .iter()
+            .filter_map(|a| a.strip_prefix("of="))
+            .map(str::to_string)
+            .collect(),
+        "find" if has_flag("-delete") => operands
+            .first()
+            .map_or_else(tree, |dir| vec![dir.to_string()]),
+        "patch" | "xargs" => tree(),
+        "git" => match operands.first().map(|s| s.as_str()) {
+            Some(sub) if TREE_WRITING_GIT.contains(&sub) => tree(),
+            _ => Vec::new(),
+        },
+        "cargo" => match operands
+            .iter()
+            .find(|a| !a.starts_with('+'))
+            .map(|s| s.as_str())
+        {
+            Some("fmt") if !has_flag("--check") => tree(),
+            Some("fix" | "add" | "remove" | "rm" | "update") => tree(),
+            Some("clippy") if has_flag("--fix") => tree(),
+            _ => Vec::new(),
+        },
+        _ if is_shell(name) => shell_c_script(args).map(written_paths).unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+/// Shell constructs the string form refuses: they run hidden commands or
+/// smuggle in extra ones. Pipes, redirects and `&&` remain allowed.
+const FORBIDDEN_SHELL_SYNTAX: &[(&str, &str)] = &[
+    ("$(", "command substitution"),
+    ("`", "command substitution"),
+    ("<(", "process substitution"),
+    (">(", "process substitution"),
+    ("\n", "a line break"),
+    ("\r", "a line break"),
+];
+
+/// Stricter checks for a command run through the shell
+fn check_shell_string(command: &str) -> Result<()> {
+    for (syntax, what) in FORBIDDEN_SHELL_SYNTAX {
+        if command.contains(syntax) {
+            anyhow::bail!(
+                "Command string contains {} ({:?}); pass program and args instead",
+                what,
+                syntax
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Whether `program` is a shell, whose `-c` argument is a command string
+fn is_shell(program: &str) -> bool {
+    let name = Path::new(program)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(program);
+    matches!(name, "sh" | "bash" | "zsh" | "dash" | "ksh" | "fish")
+}
+
+/// Warning returned with results of the string form
+const STRING_FORM_WARNING: &str = "Ran through the shell. Prefer {\"program\": ..., \"args\": [...]}, which needs no quoting and cannot be injected into.";
+
+/// Kill the process group led by `pid`. Dropping the child only kills the
+/// shell; this also takes down anything it spawned.
+fn kill_process_group(pid: Option<u32>) {
+    #[cfg(unix)]
+    if let Some(pgid) = pid.and_then(|p| i32::try_from(p).ok()) {
+        use nix::sys::signal::{killpg, Signal};
+        use nix::unistd::Pid;
+        let _ = killpg(Pid::from_raw(pgid), Signal::SIGKILL);
+    }
+    #[cfg(not(unix))]
+    let _ = pid;
+}
+
 pub struct ShellExec;
 
 #[async_trait]
@@ -27,28 +362,52 @@ impl Tool for ShellExec {
     }
 
     fn description(&self) -> &str {
-        "Execute shell command. Use for builds, tests, and system operations. Runs with timeout."
+        "Execute a command. Use for builds, tests, and system operations. Runs with timeout. \
+         Prefer program + args, which runs the program directly without a shell; \
+         `command` goes through the shell and only suits pipes or redirects."
     }
 
     fn schema(&self) -> Value {
         serde_json::json!({
             "type": "object",
             "properties": {
-                "command": {"type": "string", "description": "Command to execute"},
+                "program": {"type": "string", "description": "Program to run directly, without a shell (preferred)"},
+                "args": {"type": "array", "items": {"type": "string"}, "description": "Arguments passed to program as-is, no quoting needed"},
+                "command": {"type": "string", "description": "Shell command line, only when pipes or redirects are needed"},
                 "cwd": {"type": "string", "description": "Working directory"},
                 "timeout_secs": {"type": "integer", "default": 60, "description": "Timeout in seconds"},
                 "env": {"type": "object", "additionalProperties": {"type": "string"}},
                 "output_offset": {"type": "integer", "default": 0, "description": "Character offset for paginated output"},
                 "output_limit": {"type": "integer", "default": 10000, "description": "Maximum characters per output page"}
-            },
-            "required": ["command"]
+            }
         })
     }
 
+    fn output_schema(&self) -> Option<Value> {
+        Some(serde_json::json!({
+            "type": "object",
+            "required": ["exit_code", "stdout", "stderr", "timed_out"],
+            "properties": {
+                "exit_code": {"type": "integer"},
+                "stdout": {"type": "string"},
+                "stderr": {"type": "string"},
+                "timed_out": {"type": "boolean"}
+            }
+        }))
+    }
+
     async fn execute(&self, args: Value) -> Result<Value> {
+        self.execute_cancellable(args, &CancellationToken::new())
+            .await
+    }
+
+    async fn execute_cancellable(&self, args: Value, cancel: &CancellationToken) -> Result<Value> {
         #[derive(Deserialize)]
         struct Args {
-            command: String,
+            command: Option<String>,
+            program: Option<String>,
+            #[serde(default)]
+            args: Vec<String>,
             cwd: Option<String>,
             #[serde(default = "default_timeout")]
             timeout_secs: u64,
@@ -68,6 +427,8 @@ impl Tool for ShellExec {
             10000
         }
 
+        let line = command_line(&args).unwrap_or_default();
+        let checked = checked_commands(&args);
         let mut args: Args = serde_json::from_value(args)?;
 
         // Cap timeout to prevent indefinite hangs (1 hour max)
@@ -76,17 +437,47 @@ impl Tool for ShellExec {
 
         // Command length limit to prevent abuse
         const MAX_COMMAND_LENGTH: usize = 10_000;
-        if args.command.len() > MAX_COMMAND_LENGTH {
+        if line.len() > MAX_COMMAND_LENGTH {
             anyhow::bail!(
                 "Command exceeds maximum length of {} characters",
                 MAX_COMMAND_LENGTH
             );
         }
 
+        // The argv form spawns the program directly, so nothing in its
+        // arguments is interpreted. Handing a script to a shell with `-c`
+        // gets the same checks as the string form.
+        let (mut cmd, shell_scripts) = match (&args.command, &args.program) {
+            (Some(_), Some(_)) => anyhow::bail!("Pass either command or program, not both"),
+            (None, None) => anyhow::bail!("Missing program (or command)"),
+            (Some(command), None) => {
+                let (shell, flag) = default_shell();
+                let mut cmd = tokio::process::Command::new(shell);
+                cmd.arg(flag).arg(command);
+                (cmd, vec![command.as_str()])
+            }
+            (None, Some(program)) => {
+                if program.trim().is_empty() {
+                    anyhow::bail!("program must not be empty");
+                }
+                if program.contains('\0') || args.args.iter().any(|a| a.contains('\0')) {
+                    anyhow::bail!("program and args must not contain null bytes");
+                }
+                let scripts = argv_scripts(program, &args.args);
+                let mut cmd = tokio::process::Command::new(program);
+                cmd.args(&args.args);
+                (cmd, scripts)
+            }
+        };
+        let via_shell = !shell_scripts.is_empty();
+        for script in shell_scripts {
+            warn!("shell_exec running through the shell: {}", script);
+            check_shell_string(script)?;
+        }
+
         // Block dangerous patterns that are common in reverse shells and
         // data exfiltration payloads. This is defense-in-depth; the safety
         // checker provides the primary validation layer.
-        let lower_cmd = args.command.to_lowercase();
         let dangerous_patterns: &[&str] = &[
             "/dev/tcp/",
             "/dev/udp/",
@@ -94,9 +485,12 @@ impl Tool for ShellExec {
             "| sh -i",
             "mkfifo /tmp",
         ];
-        for pattern in dangerous_patterns {
-            if lower_cmd.contains(pattern) {
-                anyhow::bail!("Blocked potentially dangerous shell pattern: {}", pattern);
+        for command in &checked {
+            let lower_cmd = command.to_lowercase();
+            for pattern in dangerous_patterns {
+                if lower_cmd.contains(pattern) {
+                    anyhow::bail!("Blocked potentially dangerous shell pattern: {}", pattern);
+                }
             }
         }
         // Validate cwd: must be an absolute path without path traversal components
@@ -131,10 +525,14 @@ impl Tool for ShellExec {
             }
         }
 
-        let (shell, flag) = default_shell();
-        let mut cmd = tokio::process::Command::new(shell);
         cmd.kill_on_drop(true);
-        cmd.arg(flag).arg(&args.command);
+        cmd.stdin(std::process::Stdio::null());
+        cmd.stdout(std::process::Stdio::piped());
+        cmd.stderr(std::process::Stdio::piped());
+        // Run in a fresh process group so cancellation reaches grandchildren
+        // spawned by the shell, not just the shell itself.
+        #[cfg(unix)]
+        cmd.process_group(0);
 
         if let Some(cwd) = &args.cwd {
             cmd.current_dir(cwd);
@@ -143,8 +541,18 @@ impl Tool for ShellExec {
         cmd.envs(&args.env);
 
         let start = std::time::Instant::now();
-        let output =
-            tokio::time::timeout(Duration::from_secs(args.timeout_secs), cmd.output()).await;
+        let child = cmd.spawn()?;
+        let pid = child.id();
+        let output = tokio::select! {
+            output = tokio::time::timeout(
+                Duration::from_secs(args.timeout_secs),
+                child.wait_with_output(),
+            ) => output,
+            _ = cancel.cancelled() => {
+                kill_process_group(pid);
+                return Err(crate::errors::ToolError::Cancelled.into());
+            }
+        };
 
         let (exit_code, stdout, stderr, timed_out) = match output {
             Ok(Ok(output)) => (
@@ -154,7 +562,10 @@ impl Tool for ShellExec {
                 false,
             ),
             Ok(Err(e)) => return Err(e.into()),
-            Err(_) => (-1, "".to_string(), "Command timed out".to_string(), true),
+            Err(_) => {
+                kill_process_group(pid);
+                (-1, "".to_string(), "Command timed out".to_string(), true)
+            }
         };
 
         let duration_ms = start.elapsed().as_millis() as u64;
@@ -164,7 +575,7 @@ impl Tool for ShellExec {
         let (stderr_page, stderr_pagination) =
             super::truncate_with_pagination(&stderr, args.output_offset, args.output_limit);
 
-        Ok(serde_json::json!({
+        let mut result = serde_json::json!({
             "exit_code": exit_code,
             "stdout": stdout_page,
             "stderr": stderr_page,
@@ -172,7 +583,11 @@ impl Tool for ShellExec {
             "stderr_pagination": stderr_pagination,
             "duration_ms": duration_ms,
             "timed_out": timed_out
-        }))
+        });
+        if via_shell {
+            result["warning"] = STRING_FORM_WARNING.into();
+        }
+        Ok(result)
     }
 }
 
@@ -343,6 +758,61 @@ mod tests {
         assert!(result["stderr"].as_str().unwrap().contains("timed out"));
     }
 
+    #[tokio::test]
+    async fn test_shell_exec_cancel_kills_command_promptly() {
+        let tool = ShellExec;
+        let token = CancellationToken::new();
+        let canceller = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            canceller.cancel();
+        });
+
+        let start = std::time::Instant::now();
+        let args = serde_json::json!({
+            "command": "sleep 30; echo finished",
+            "timeout_secs": 60
+        });
+        let err = tool.execute_cancellable(args, &token).await.unwrap_err();
+        let elapsed = start.elapsed();
+
+        assert!(err.to_string().contains("cancelled"));
+        // Generous for loaded CI runners, yet far short of the 30s sleep.
+        assert!(
+            elapsed < Duration::from_secs(10),
+            "cancellation took {:?}",
+            elapsed
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_shell_exec_cancel_kills_grandchildren() {
+        let dir = tempfile::tempdir().unwrap();
+        let pid_file = dir.path().join("pid");
+        let tool = ShellExec;
+        let token = CancellationToken::new();
+        let canceller = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            canceller.cancel();
+        });
+
+        let args = serde_json::json!({
+            "command": format!("sleep 30 & echo $! > {}; wait", pid_file.display()),
+            "timeout_secs": 60
+        });
+        assert!(tool.execute_cancellable(args, &token).await.is_err());
+
+        let pid = std::fs::read_to_string(&pid_file).unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        // A killed process is either gone or a zombie awaiting reaping
+        let alive = std::fs::read_to_string(format!("/proc/{}/stat", pid.trim()))
+            .map(|stat| !stat.contains(") Z "))
+            .unwrap_or(false);
+        assert!(!alive, "grandchild sleep should have been killed");
+    }
+
     #[tokio::test]
     async fn test_shell_exec_empty_env() {
         let tool = ShellExec;
@@ -575,4 +1045,130 @@ mod tests {
         // Should not error due to length (command itself will succeed)
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    #[cfg(not(target_os = "windows"))]
+    async fn test_argv_form_passes_args_uninterpreted() {
+        let tool = ShellExec;
+        let args = serde_json::json!({
+            "program": "echo",
+            "args": ["$(whoami)", "a;b", "*"],
+            "timeout_secs": 5
+        });
+        let result = tool.execute(args).await.unwrap();
+        assert_eq!(result["exit_code"], 0);
+        assert_eq!(result["stdout"], "$(whoami) a;b *\n");
+        assert!(result.get("warning").is_none());
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_os = "windows"))]
+    async fn test_string_form_warns_and_rejects_substitution() {
+        let tool = ShellExec;
+        let result = tool
+            .execute(serde_json::json!({"command": "echo ok | cat", "timeout_secs": 5}))
+            .await
+            .unwrap();
+        assert_eq!(result["stdout"], "ok\n");
+        assert!(result["warning"].as_str().unwrap().contains("program"));
+
+        for command in ["echo $(whoami)", "echo `id`", "diff <(ls) b", "ls\nid"] {
+            let err = tool
+                .execute(serde_json::json!({ "command": command }))
+                .await
+                .unwrap_err();
+            assert!(
+                err.to_string().contains("pass program and args"),
+                "{}",
+                command
+            );
+        }
+
+        // A shell's -c script is a command string too, however it is reached
+        for argv in [
+            serde_json::json!({"program": "/bin/sh", "args": ["-c", "echo $(id)"]}),
+            serde_json::json!({"program": "bash", "args": ["-ec", "echo $(id)"]}),
+            serde_json::json!({"program": "bash", "args": ["-o", "pipefail", "-lc", "echo $(id)"]}),
+            serde_json::json!({"program": "env", "args": ["FOO=1", "sh", "-c", "echo $(id)"]}),
+            serde_json::json!({"program": "/usr/bin/env", "args": ["-S", "sh -c 'echo $(id)'"]}),
+        ] {
+            let err = tool.execute(argv.clone()).await.unwrap_err();
+            assert!(err.to_string().contains("command substitution"), "{}", argv);
+        }
+    }
+
+    #[test]
+    fn test_checked_commands_unwrap_wrappers_and_flag_clusters() {
+        let argv = |program: &str, args: &[&str]| {
+            checked_commands(&serde_json::json!({"program": program, "args": args}))
+        };
+        assert_eq!(
+            argv("cargo", &["test", "-p", "foo bar"]),
+            vec!["cargo test -p foo bar"]
+        );
+        assert_eq!(
+            argv("bash", &["-lc", "rm -rf /"]),
+            vec!["bash -lc rm -rf /", "rm -rf /"]
+        );
+        assert_eq!(
+            argv(
+                "env",
+                &["-u", "HOME", "A=b", "exec", "-a", "x", "zsh", "-c", "id"]
+            ),
+            vec!["env -u HOME A=b exec -a x zsh -c id", "id"]
+        );
+        // A script file, not a command string
+        assert_eq!(argv("sh", &["build.sh", "-c"]), vec!["sh build.sh -c"]);
+        assert_eq!(argv("bash", &["--norc", "-x", "run.sh"]).len(), 1);
+        assert_eq!(
+            checked_commands(&serde_json::json!({"command": "ls | wc"})),
+            vec!["ls | wc"]
+        );
+    }
+
+    #[test]
+    fn test_written_paths_only_for_writing_commands() {
+        for read_only in [
+            "ls -la",
+            "git status && git diff",
+            "cargo test --workspace 2>&1 | tail -n 20",
+            "grep -rn foo src > /dev/null",
+            "cargo fmt --check",
+        ] {
+            assert!(written_paths(read_only).is_empty(), "{}", read_only);
+        }
+        assert_eq!(written_paths("rm -rf ./target"), vec!["./target"]);
+        assert_eq!(written_paths("cp src/a.rs /tmp/b.rs"), vec!["/tmp/b.rs"]);
+        assert_eq!(
+            written_paths("sed -i s/a/b/ src/main.rs; echo done"),
+            vec!["src/main.rs"]
+        );
+        assert_eq!(written_paths("echo x >> src/lib.rs"), vec!["src/lib.rs"]);
+        assert_eq!(written_paths("dd if=/dev/zero of=out.img"), vec!["out.img"]);
+        assert_eq!(written_paths("bash -c 'touch src/x.rs'"), vec!["src/x.rs"]);
+        for tree_wide in ["cargo fmt", "git checkout main", "cargo clippy --fix"] {
+            assert_eq!(written_paths(tree_wide), vec!["."], "{}", tree_wide);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_command_and_program_are_exclusive() {
+        let tool = ShellExec;
+        let both = serde_json::json!({"command": "ls", "program": "ls"});
+        assert!(tool.execute(both).await.is_err());
+        assert!(tool.execute(serde_json::json!({})).await.is_err());
+    }
+
+    #[test]
+    fn test_command_line_quotes_argv() {
+        let argv =
+            serde_json::json!({"program": "cargo", "args": ["test", "-p", "foo bar", "it's"]});
+        assert_eq!(
+            command_line(&argv).unwrap(),
+            "cargo test -p 'foo bar' 'it'\\''s'"
+        );
+        let string = serde_json::json!({"command": "ls -la"});
+        assert_eq!(command_line(&string).unwrap(), "ls -la");
+        assert_eq!(command_line(&serde_json::json!({})), None);
+    }
 }
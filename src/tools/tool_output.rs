@@ -0,0 +1,215 @@
+//! Full output of elided tool results
+//!
+//! Tool results larger than `agent.max_tool_result_tokens` enter the context
+//! with their middle elided. The full text is retained in a
+//! [`ToolOutputStore`] (and the task checkpoint), and `tool_output` reads
+//! any part of it back.
+
+use super::Tool;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// Elided results kept before the oldest is dropped
+const MAX_RETAINED_OUTPUTS: usize = 32;
+
+/// Chars returned by one `tool_output` call unless asked otherwise
+const DEFAULT_READ_CHARS: usize = 8000;
+
+/// The full text of a tool result that was elided in context
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetainedOutput {
+    pub call_id: String,
+    pub output: String,
+    /// Char offset where the elided part starts
+    pub elided_from: usize,
+}
+
+/// Full outputs of recently elided tool results, by tool call ID
+#[derive(Debug, Default)]
+pub struct ToolOutputStore {
+    outputs: Mutex<VecDeque<RetainedOutput>>,
+}
+
+impl ToolOutputStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Retain an output, dropping the oldest beyond the limit
+    pub fn insert(&self, retained: RetainedOutput) {
+        if let Ok(mut outputs) = self.outputs.lock() {
+            outputs.retain(|o| o.call_id != retained.call_id);
+            outputs.push_back(retained);
+            while outputs.len() > MAX_RETAINED_OUTPUTS {
+                outputs.pop_front();
+            }
+        }
+    }
+
+    pub fn get(&self, call_id: &str) -> Option<RetainedOutput> {
+        let outputs = self.outputs.lock().ok()?;
+        outputs.iter().find(|o| o.call_id == call_id).cloned()
+    }
+
+    /// All retained outputs, oldest first
+    pub fn snapshot(&self) -> Vec<RetainedOutput> {
+        self.outputs
+            .lock()
+            .map(|o| o.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Replace the retained outputs, e.g. when resuming a task
+    pub fn restore(&self, retained: Vec<RetainedOutput>) {
+        if let Ok(mut outputs) = self.outputs.lock() {
+            *outputs = retained.into();
+            while outputs.len() > MAX_RETAINED_OUTPUTS {
+                outputs.pop_front();
+            }
+        }
+    }
+}
+
+/// Reads the full output of an elided tool result
+pub struct ToolOutput {
+    store: Arc<ToolOutputStore>,
+}
+
+impl ToolOutput {
+    pub fn new(store: Arc<ToolOutputStore>) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl Tool for ToolOutput {
+    fn name(&self) -> &str {
+        "tool_output"
+    }
+
+    fn description(&self) -> &str {
+        "Read the part of a large tool result that was elided from context. Pass the call_id \
+         named in the '… lines elided …' marker; by default reads from where the elision starts."
+    }
+
+    fn schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "required": ["call_id"],
+            "properties": {
+                "call_id": {
+                    "type": "string",
+                    "description": "Tool call ID from the elision marker"
+                },
+                "start": {
+                    "type": "integer",
+                    "description": "Char offset to read from (default: start of the elided part)"
+                },
+                "max_chars": {
+                    "type": "integer",
+                    "description": "Maximum chars to return",
+                    "default": DEFAULT_READ_CHARS
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: Value) -> Result<Value> {
+        let call_id = args
+            .get("call_id")
+            .and_then(|v| v.as_str())
+            .context("Missing required parameter: call_id")?;
+        let retained = self.store.get(call_id).with_context(|| {
+            format!(
+                "No retained output for call_id '{}'; only elided results are kept",
+                call_id
+            )
+        })?;
+        let start = args
+            .get("start")
+            .and_then(|v| v.as_u64())
+            .map_or(retained.elided_from, |s| s as usize);
+        let max_chars = args
+            .get("max_chars")
+            .and_then(|v| v.as_u64())
+            .map_or(DEFAULT_READ_CHARS, |m| m as usize)
+            .max(1);
+
+        let total_chars = retained.output.chars().count();
+        let content: String = retained
+            .output
+            .chars()
+            .skip(start)
+            .take(max_chars)
+            .collect();
+        let end = (start + content.chars().count()).min(total_chars);
+
+        Ok(serde_json::json!({
+            "call_id": call_id,
+            "start": start,
+            "end": end,
+            "total_chars": total_chars,
+            "next_start": (end < total_chars).then_some(end),
+            "content": content,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_tool_output_reads_retained_output() {
+        let store = Arc::new(ToolOutputStore::new());
+        store.insert(RetainedOutput {
+            call_id: "call_1".into(),
+            output: "0123456789".into(),
+            elided_from: 4,
+        });
+        let tool = ToolOutput::new(store.clone());
+
+        let result = tool
+            .execute(serde_json::json!({"call_id": "call_1", "max_chars": 3}))
+            .await
+            .unwrap();
+        assert_eq!(result["content"], "456");
+        assert_eq!(result["next_start"], 7);
+
+        let result = tool
+            .execute(serde_json::json!({"call_id": "call_1", "start": 8}))
+            .await
+            .unwrap();
+        assert_eq!(result["content"], "89");
+        assert!(result["next_start"].is_null());
+
+        assert!(tool
+            .execute(serde_json::json!({"call_id": "missing"}))
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn test_tool_output_store_keeps_most_recent() {
+        let store = ToolOutputStore::new();
+        for i in 0..MAX_RETAINED_OUTPUTS + 2 {
+            store.insert(RetainedOutput {
+                call_id: format!("call_{}", i),
+                output: "x".into(),
+                elided_from: 0,
+            });
+        }
+        assert!(store.get("call_0").is_none());
+        assert!(store
+            .get(&format!("call_{}", MAX_RETAINED_OUTPUTS + 1))
+            .is_some());
+        assert_eq!(store.snapshot().len(), MAX_RETAINED_OUTPUTS);
+
+        store.restore(vec![]);
+        assert!(store.snapshot().is_empty());
+    }
+}
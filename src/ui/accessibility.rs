@@ -0,0 +1,25 @@
+//! Accessibility and Wellbeing
+//!
+//! `--accessible` asks for plain output a screen reader can follow; the
+//! [`wellness`] module nudges toward breaks during long sessions,
+//! [`dyslexia_friendly`] renders code and diffs for readability, and
+//! [`literate`] writes a finished task up as a readable document.
+
+pub mod dyslexia_friendly;
+pub mod literate;
+pub mod wellness;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Set by `--accessible`
+static ACCESSIBLE: AtomicBool = AtomicBool::new(false);
+
+/// Enable plain output for screen readers.
+pub fn set_accessible(enabled: bool) {
+    ACCESSIBLE.store(enabled, Ordering::Relaxed);
+}
+
+/// Check if accessible output is requested.
+pub fn is_accessible() -> bool {
+    ACCESSIBLE.load(Ordering::Relaxed)
+}
@@ -0,0 +1,153 @@
+//! Dyslexia-friendly rendering of code and diffs
+//!
+//! Enabled with `dyslexia_friendly = true` in the config. Code blocks and
+//! diffs are rendered with:
+//!
+//! - a blank line between lines, since terminals cannot change line height;
+//! - a lower-contrast highlighting theme instead of the default one;
+//! - diff markers that differ in shape (`[+]`, `[-]`), not only in color,
+//!   colored from the Okabe-Ito palette (blue and orange) rather than red
+//!   and green, with no strikethrough on removed lines.
+//!
+//! Without color (`NO_COLOR`, `--no-color`, `--accessible`) the markers are
+//! printed alone. The font is the terminal's to choose; [`FONT_HINT`] names
+//! ones that help.
+
+use colored::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Set from `Config.dyslexia_friendly`
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Highlighting theme for code blocks in this mode
+pub const CODE_THEME: &str = "Solarized (dark)";
+
+/// Monospace fonts designed for readers with dyslexia, for the terminal
+/// profile
+pub const FONT_HINT: &str = "OpenDyslexic Mono, Comic Mono or Atkinson Hyperlegible Mono";
+
+/// Okabe-Ito blue, for added lines
+pub const ADDED_RGB: (u8, u8, u8) = (0, 114, 178);
+
+/// Okabe-Ito orange, for removed lines
+pub const REMOVED_RGB: (u8, u8, u8) = (230, 159, 0);
+
+/// Marker in front of an added line
+pub const ADDED_MARKER: &str = "[+] ";
+
+/// Marker in front of a removed line
+pub const REMOVED_MARKER: &str = "[-] ";
+
+/// Marker in front of an unchanged line, as wide as the others
+pub const CONTEXT_MARKER: &str = "    ";
+
+/// Turn the mode on or off.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Check if code and diffs should be rendered for readability.
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Kind of a line in a unified diff
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLine {
+    /// `diff --git`, `---`, `+++` and similar file headers
+    Header,
+    /// `@@ ... @@`
+    Hunk,
+    Added,
+    Removed,
+    Context,
+}
+
+/// Classify one line of a unified diff
+pub fn classify(line: &str) -> DiffLine {
+    if line.starts_with("+++ ")
+        || line.starts_with("--- ")
+        || line.starts_with("diff ")
+        || line.starts_with("index ")
+    {
+        DiffLine::Header
+    } else if line.starts_with("@@") {
+        DiffLine::Hunk
+    } else if line.starts_with('+') {
+        DiffLine::Added
+    } else if line.starts_with('-') {
+        DiffLine::Removed
+    } else {
+        DiffLine::Context
+    }
+}
+
+/// Render one unified diff line with a shape marker, colored when `color`
+pub fn render_diff_line(line: &str, color: bool) -> String {
+    let kind = classify(line);
+    let (marker, text) = match kind {
+        DiffLine::Added => (ADDED_MARKER, &line[1..]),
+        DiffLine::Removed => (REMOVED_MARKER, &line[1..]),
+        DiffLine::Context => (CONTEXT_MARKER, line.strip_prefix(' ').unwrap_or(line)),
+        DiffLine::Header | DiffLine::Hunk => ("", line),
+    };
+    if !color {
+        return format!("{}{}", marker, text);
+    }
+    let styled = |s: &str| match kind {
+        DiffLine::Added => {
+            let (r, g, b) = ADDED_RGB;
+            s.truecolor(r, g, b).bold().to_string()
+        }
+        DiffLine::Removed => {
+            let (r, g, b) = REMOVED_RGB;
+            s.truecolor(r, g, b).to_string()
+        }
+        DiffLine::Header => s.bold().to_string(),
+        DiffLine::Hunk => s.dimmed().to_string(),
+        DiffLine::Context => s.to_string(),
+    };
+    format!("{}{}", styled(marker), styled(text))
+}
+
+/// Render a unified diff line by line, spaced out
+pub fn render_diff(diff: &str, color: bool) -> String {
+    spaced(
+        &diff
+            .lines()
+            .map(|line| render_diff_line(line, color))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    )
+}
+
+/// Text with a blank line after each line, ending in a newline
+pub fn spaced(text: &str) -> String {
+    text.lines().map(|line| format!("{}\n\n", line)).collect()
+}
+
+/// Whether a fenced block's language tag marks a diff
+pub fn is_diff_lang(lang: &str) -> bool {
+    matches!(lang, "diff" | "patch" | "udiff")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_markers_differ_without_color() {
+        let diff = "--- a/x.rs\n+++ b/x.rs\n@@ -1,2 +1,2 @@\n keep\n-old\n+new\n";
+        let rendered = render_diff(diff, false);
+        assert_eq!(
+            rendered,
+            "--- a/x.rs\n\n+++ b/x.rs\n\n@@ -1,2 +1,2 @@\n\n    keep\n\n[-] old\n\n[+] new\n\n"
+        );
+
+        // Markers stay when colored, and removed lines are not struck out
+        assert!(render_diff_line("+new", true).contains("[+] "));
+        let removed = render_diff_line("-old", true);
+        assert!(removed.contains("[-] ") && !removed.contains("\x1b[9m"));
+        assert!(is_diff_lang("patch") && !is_diff_lang("rust"));
+    }
+}
@@ -0,0 +1,455 @@
+//! Literate export of a finished task
+//!
+//! Turns a task's checkpoint into one Markdown document that reads as a
+//! narrative: the goal, the plan (the agent's first response), each step
+//! with its reasoning and tool calls, the edits as fenced diffs, and the
+//! outcome. Steps link to the files they touched and a closing index links
+//! each file back to its steps, so the document can go straight into a PR
+//! description or a write-up.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+
+use crate::api::types::Message;
+use crate::checkpoint::{CheckpointManager, TaskCheckpoint, TaskStatus, ToolCallLog};
+
+/// Longest line of a tool argument shown in a step summary
+const MAX_ARGUMENT_CHARS: usize = 80;
+
+/// Most lines of a diff or new file shown before it is cut
+const MAX_DIFF_LINES: usize = 200;
+
+/// Markdown narrative of the saved task `task_id`
+pub fn export(task_id: &str) -> Result<String> {
+    let manager =
+        CheckpointManager::default_path().context("Failed to initialize checkpoint manager")?;
+    let checkpoint = manager.load(task_id)?;
+    let root = std::env::current_dir().unwrap_or_default();
+    Ok(render(&checkpoint, &root))
+}
+
+/// One tool call in a step, with its logged outcome when known
+struct Call {
+    name: String,
+    arguments: Value,
+    success: Option<bool>,
+    error: Option<String>,
+}
+
+/// Markdown narrative of `checkpoint`; file links are made relative to
+/// `root` when they fall under it
+pub fn render(checkpoint: &TaskCheckpoint, root: &Path) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# {}\n", title(&checkpoint.task_description));
+    let _ = writeln!(
+        out,
+        "*Task `{}`, {} from {} to {}.*\n",
+        checkpoint.task_id,
+        status_label(&checkpoint.status),
+        checkpoint.created_at.format("%Y-%m-%d %H:%M UTC"),
+        checkpoint.updated_at.format("%Y-%m-%d %H:%M UTC")
+    );
+
+    out.push_str("## Goal\n\n");
+    let _ = writeln!(out, "{}\n", checkpoint.task_description.trim());
+    if !checkpoint.assumptions.is_empty() {
+        out.push_str("Assumed:\n\n");
+        for assumption in &checkpoint.assumptions {
+            let _ = writeln!(out, "- {}", assumption);
+        }
+        out.push('\n');
+    }
+
+    let mut log = checkpoint.tool_calls.iter();
+    let mut steps = checkpoint
+        .messages
+        .iter()
+        .filter(|m| m.role == "assistant")
+        .map(|m| {
+            let (text, calls) = split_message(m);
+            let calls = calls
+                .into_iter()
+                .map(|(name, arguments)| {
+                    let logged = next_logged(&mut log, &name);
+                    Call {
+                        success: logged.map(|l| l.success),
+                        error: logged.filter(|l| !l.success).and_then(|l| l.result.clone()),
+                        name,
+                        arguments,
+                    }
+                })
+                .collect::<Vec<_>>();
+            (text, calls)
+        })
+        .collect::<Vec<_>>();
+
+    // The first response is the plan unless it went straight to work
+    let plan = match steps.first() {
+        Some((text, calls)) if calls.is_empty() && !text.is_empty() => Some(steps.remove(0).0),
+        _ => None,
+    };
+    out.push_str("## Plan\n\n");
+    match &plan {
+        Some(text) => {
+            let _ = writeln!(out, "{}\n", text);
+        }
+        None => out.push_str("*No separate plan was recorded.*\n\n"),
+    }
+    if let Some(reasoning) = reasoning_for(checkpoint, 0) {
+        let _ = writeln!(out, "{}", quote(reasoning));
+    }
+
+    let mut touched: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    out.push_str("## Steps\n\n");
+    if steps.is_empty() {
+        out.push_str("*No steps were recorded.*\n\n");
+    }
+    for (index, (text, calls)) in steps.iter().enumerate() {
+        let number = index + 1;
+        let _ = writeln!(out, "### Step {}\n", number);
+        if let Some(reasoning) = reasoning_for(checkpoint, number) {
+            let _ = writeln!(out, "{}", quote(reasoning));
+        }
+        if !text.is_empty() {
+            let _ = writeln!(out, "{}\n", text);
+        }
+
+        let mut files = Vec::new();
+        for call in calls {
+            for path in call_paths(call) {
+                let path = display_path(&path, root);
+                if !files.contains(&path) {
+                    files.push(path);
+                }
+            }
+        }
+        if !files.is_empty() {
+            let links: Vec<String> = files.iter().map(|f| file_link(f)).collect();
+            let _ = writeln!(out, "Files: {}\n", links.join(", "));
+            for file in files {
+                let steps = touched.entry(file).or_default();
+                if !steps.contains(&number) {
+                    steps.push(number);
+                }
+            }
+        }
+
+        for call in calls {
+            let mark = match call.success {
+                Some(true) => "",
+                Some(false) => " (failed)",
+                None => " (no result recorded)",
+            };
+            let _ = writeln!(
+                out,
+                "- `{}` {}{}",
+                call.name,
+                summarize_arguments(&call.arguments),
+                mark
+            );
+            if let Some(ref error) = call.error {
+                let _ = writeln!(out, "  > {}", first_line(error));
+            }
+        }
+        if !calls.is_empty() {
+            out.push('\n');
+        }
+        for call in calls.iter().filter(|c| c.success != Some(false)) {
+            if let Some(diff) = call_diff(call, root) {
+                let _ = writeln!(out, "```diff\n{}```\n", diff);
+            }
+        }
+    }
+
+    out.push_str("## Outcome\n\n");
+    let _ = writeln!(
+        out,
+        "{} after {} step{} and {} tool call{}.\n",
+        capitalize(status_label(&checkpoint.status)),
+        steps.len(),
+        if steps.len() == 1 { "" } else { "s" },
+        checkpoint.tool_calls.len(),
+        if checkpoint.tool_calls.len() == 1 {
+            ""
+        } else {
+            "s"
+        }
+    );
+    if !checkpoint.errors.is_empty() {
+        out.push_str("Errors along the way:\n\n");
+        for error in &checkpoint.errors {
+            let _ = writeln!(
+                out,
+                "- Step {}: {}{}",
+                error.step,
+                first_line(&error.error),
+                if error.recovered { " (recovered)" } else { "" }
+            );
+        }
+        out.push('\n');
+    }
+    if let Some(ref note) = checkpoint.pinned_note {
+        let _ = writeln!(out, "Pinned decisions:\n\n{}\n", quote(note));
+    }
+
+    if !touched.is_empty() {
+        out.push_str("## Files touched\n\n");
+        for (file, steps) in &touched {
+            let links: Vec<String> = steps
+                .iter()
+                .map(|n| format!("[step {}](#step-{})", n, n))
+                .collect();
+            let _ = writeln!(out, "- {}: {}", file_link(file), links.join(", "));
+        }
+        out.push('\n');
+    }
+
+    out.truncate(out.trim_end().len());
+    out.push('\n');
+    out
+}
+
+/// Prose and tool calls of an assistant message, native or XML
+fn split_message(message: &Message) -> (String, Vec<(String, Value)>) {
+    let content = message.content.text();
+    if let Some(ref native) = message.tool_calls {
+        let calls = native
+            .iter()
+            .map(|c| {
+                let arguments = serde_json::from_str(&c.function.arguments).unwrap_or(Value::Null);
+                (c.function.name.clone(), arguments)
+            })
+            .collect();
+        return (content.trim().to_string(), calls);
+    }
+    let parsed = crate::tool_parser::parse_tool_calls(content);
+    let calls = parsed
+        .tool_calls
+        .into_iter()
+        .map(|c| (c.tool_name, c.arguments))
+        .collect();
+    (parsed.text_content.trim().to_string(), calls)
+}
+
+/// Next logged call of `name`, skipping log entries with no message (for
+/// example calls made while the history was later compressed)
+fn next_logged<'a>(
+    log: &mut std::slice::Iter<'a, ToolCallLog>,
+    name: &str,
+) -> Option<&'a ToolCallLog> {
+    let mut ahead = log.clone();
+    let found = ahead.find(|l| l.tool_name == name)?;
+    *log = ahead;
+    Some(found)
+}
+
+fn reasoning_for(checkpoint: &TaskCheckpoint, step: usize) -> Option<&str> {
+    checkpoint
+        .reasoning
+        .iter()
+        .find(|r| r.step == step)
+        .map(|r| r.text.trim())
+        .filter(|t| !t.is_empty())
+}
+
+/// Files a call reads or changes
+fn call_paths(call: &Call) -> Vec<PathBuf> {
+    if call.name == "apply_patch" {
+        return crate::tools::patch::patch_targets(&call.arguments);
+    }
+    if !call.name.starts_with("file_") {
+        return Vec::new();
+    }
+    call.arguments
+        .get("path")
+        .and_then(|p| p.as_str())
+        .map(|p| vec![PathBuf::from(p)])
+        .unwrap_or_default()
+}
+
+/// Diff body for an edit, new-file listing for a write, the patch itself
+/// for `apply_patch`
+fn call_diff(call: &Call, root: &Path) -> Option<String> {
+    let arg = |key: &str| call.arguments.get(key).and_then(|v| v.as_str());
+    let text = match call.name.as_str() {
+        "file_edit" => {
+            let path = display_path(Path::new(arg("path")?), root);
+            similar::TextDiff::from_lines(arg("old_str")?, arg("new_str")?)
+                .unified_diff()
+                .context_radius(2)
+                .header(&format!("a/{}", path), &format!("b/{}", path))
+                .to_string()
+        }
+        "file_write" => {
+            let path = display_path(Path::new(arg("path")?), root);
+            let mut text = format!("+++ b/{}\n", path);
+            for line in arg("content")?.lines() {
+                text.push('+');
+                text.push_str(line);
+                text.push('\n');
+            }
+            text
+        }
+        "apply_patch" => arg("patch")?.to_string(),
+        _ => return None,
+    };
+    let lines: Vec<&str> = text.lines().collect();
+    let mut body = lines
+        .iter()
+        .take(MAX_DIFF_LINES)
+        .map(|l| format!("{}\n", l))
+        .collect::<String>();
+    if lines.len() > MAX_DIFF_LINES {
+        let _ = writeln!(body, "# ... {} more lines", lines.len() - MAX_DIFF_LINES);
+    }
+    Some(body)
+}
+
+/// `key=value` pairs of a call's scalar arguments, long values shortened
+fn summarize_arguments(arguments: &Value) -> String {
+    let Some(object) = arguments.as_object() else {
+        return String::new();
+    };
+    object
+        .iter()
+        .filter_map(|(key, value)| {
+            let value = match value {
+                Value::String(s) => first_line(s),
+                Value::Number(_) | Value::Bool(_) => value.to_string(),
+                _ => return None,
+            };
+            Some(format!("`{}={}`", key, value.replace('`', "'")))
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn display_path(path: &Path, root: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .display()
+        .to_string()
+}
+
+fn file_link(path: &str) -> String {
+    format!("[`{}`]({})", path, path.replace(' ', "%20"))
+}
+
+fn quote(text: &str) -> String {
+    text.lines()
+        .map(|l| {
+            if l.is_empty() {
+                ">\n".to_string()
+            } else {
+                format!("> {}\n", l)
+            }
+        })
+        .collect()
+}
+
+fn first_line(text: &str) -> String {
+    let line = text.trim().lines().next().unwrap_or("");
+    if line.chars().count() > MAX_ARGUMENT_CHARS {
+        let cut: String = line.chars().take(MAX_ARGUMENT_CHARS).collect();
+        format!("{}…", cut)
+    } else {
+        line.to_string()
+    }
+}
+
+fn title(description: &str) -> String {
+    let line = first_line(description);
+    if line.is_empty() {
+        "Untitled task".to_string()
+    } else {
+        line
+    }
+}
+
+fn status_label(status: &TaskStatus) -> &'static str {
+    match status {
+        TaskStatus::InProgress => "in progress",
+        TaskStatus::Completed => "completed",
+        TaskStatus::Failed => "failed",
+        TaskStatus::Paused => "paused",
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::types::{ToolCall, ToolFunction};
+    use crate::checkpoint::StepReasoning;
+
+    fn logged(name: &str, success: bool, result: &str) -> ToolCallLog {
+        ToolCallLog {
+            timestamp: chrono::Utc::now(),
+            tool_name: name.to_string(),
+            arguments: String::new(),
+            result: Some(result.to_string()),
+            success,
+            duration_ms: None,
+        }
+    }
+
+    #[test]
+    fn test_render_task_narrative() {
+        let mut checkpoint = TaskCheckpoint::new(
+            "task-1".to_string(),
+            "Fix the greeting\nsecond line".to_string(),
+        );
+        checkpoint.status = TaskStatus::Completed;
+        let edit = serde_json::json!({
+            "path": "/work/src/main.rs",
+            "old_str": "println!(\"hi\");\n",
+            "new_str": "println!(\"hello\");\n"
+        });
+        let mut native = Message::assistant("Editing the greeting.");
+        native.tool_calls = Some(vec![ToolCall {
+            id: "call_1".to_string(),
+            call_type: "function".to_string(),
+            function: ToolFunction {
+                name: "file_edit".to_string(),
+                arguments: edit.to_string(),
+            },
+        }]);
+        checkpoint.messages = vec![
+            Message::system("system"),
+            Message::user("Fix the greeting"),
+            Message::assistant("1. Change the greeting in main.rs"),
+            native,
+            Message::assistant("Done: the greeting now says hello."),
+        ];
+        checkpoint.tool_calls = vec![logged("file_edit", true, "ok")];
+        checkpoint.reasoning = vec![StepReasoning {
+            step: 1,
+            text: "The string lives in main".to_string(),
+            truncated: false,
+        }];
+
+        let doc = render(&checkpoint, Path::new("/work"));
+        assert!(doc.starts_with("# Fix the greeting\n"));
+        assert!(doc.contains("## Plan\n\n1. Change the greeting in main.rs\n"));
+        assert!(doc.contains("### Step 1\n\n> The string lives in main\n"));
+        assert!(doc.contains("Files: [`src/main.rs`](src/main.rs)"));
+        assert!(doc.contains("- `file_edit` `new_str=println!(\"hello\");`"));
+        assert!(doc.contains(
+            "```diff\n--- a/src/main.rs\n+++ b/src/main.rs\n@@ -1 +1 @@\n-println!(\"hi\");\n+println!(\"hello\");\n```"
+        ));
+        assert!(doc.contains("### Step 2\n\nDone: the greeting now says hello."));
+        assert!(doc.contains("Completed after 2 steps and 1 tool call."));
+        assert!(doc.contains("- [`src/main.rs`](src/main.rs): [step 1](#step-1)"));
+    }
+}
@@ -0,0 +1,256 @@
+//! Break reminders for long sessions
+//!
+//! Active time is counted from the gaps between interactions: prompts sent
+//! and tasks finished. A gap longer than [`IDLE_BREAK`] counts as a break
+//! and starts a new stretch; shorter gaps, including the time a task runs,
+//! count as use. Once a stretch reaches the chosen interval, a reminder is
+//! offered at the next prompt, then again an interval later unless a break
+//! is taken first.
+//!
+//! The interval and on/off choice are set with `/wellness` and kept in
+//! `wellness.json` under the local data directory.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Reminder interval when none has been chosen
+pub const DEFAULT_INTERVAL_MINUTES: u64 = 90;
+
+/// Shortest and longest intervals `/wellness <minutes>` accepts
+pub const MIN_INTERVAL_MINUTES: u64 = 10;
+pub const MAX_INTERVAL_MINUTES: u64 = 8 * 60;
+
+/// A gap between interactions at least this long counts as a break
+pub const IDLE_BREAK: Duration = Duration::from_secs(5 * 60);
+
+/// Reminder preferences, kept across sessions
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WellnessPrefs {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Continuous use before a reminder
+    #[serde(default = "default_interval_minutes")]
+    pub interval_minutes: u64,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_interval_minutes() -> u64 {
+    DEFAULT_INTERVAL_MINUTES
+}
+
+impl Default for WellnessPrefs {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_minutes: DEFAULT_INTERVAL_MINUTES,
+        }
+    }
+}
+
+impl WellnessPrefs {
+    /// `wellness.json` under the local data directory
+    pub fn default_path() -> PathBuf {
+        dirs::data_local_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("selfware")
+            .join("wellness.json")
+    }
+
+    /// Saved preferences, or the defaults if none are saved or the file
+    /// cannot be read
+    pub fn load(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to save {}", path.display()))
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_minutes * 60)
+    }
+}
+
+/// Active-time tracker and reminder schedule for one session
+#[derive(Debug)]
+pub struct Wellness {
+    prefs: WellnessPrefs,
+    path: PathBuf,
+    last_activity: Option<Instant>,
+    /// Active time this session
+    total: Duration,
+    /// Active time since the last break
+    stretch: Duration,
+    /// Stretch length at which the next reminder is due
+    next_reminder: Duration,
+}
+
+impl Wellness {
+    /// Tracker with preferences from the default path
+    pub fn load() -> Self {
+        Self::with_path(WellnessPrefs::default_path())
+    }
+
+    /// Tracker with preferences from `path`
+    pub fn with_path(path: PathBuf) -> Self {
+        let prefs = WellnessPrefs::load(&path);
+        Self {
+            next_reminder: prefs.interval(),
+            prefs,
+            path,
+            last_activity: None,
+            total: Duration::ZERO,
+            stretch: Duration::ZERO,
+        }
+    }
+
+    pub fn prefs(&self) -> &WellnessPrefs {
+        &self.prefs
+    }
+
+    /// Active time this session, idle gaps excluded
+    pub fn active_time(&self) -> Duration {
+        self.total
+    }
+
+    /// Active time since the last break
+    pub fn stretch(&self) -> Duration {
+        self.stretch
+    }
+
+    /// Note an interaction: a prompt sent or a task finished
+    pub fn record_activity(&mut self) {
+        self.record_activity_at(Instant::now());
+    }
+
+    fn record_activity_at(&mut self, now: Instant) {
+        if let Some(last) = self.last_activity {
+            let gap = now.saturating_duration_since(last);
+            if gap >= IDLE_BREAK {
+                self.stretch = Duration::ZERO;
+                self.next_reminder = self.prefs.interval();
+            } else {
+                self.total += gap;
+                self.stretch += gap;
+            }
+        }
+        self.last_activity = Some(now);
+    }
+
+    /// The reminder to show at the prompt, if one is due. Showing it
+    /// dismisses it until another interval of use has passed.
+    pub fn due_reminder(&mut self, accessible: bool) -> Option<String> {
+        if !self.prefs.enabled || self.stretch < self.next_reminder {
+            return None;
+        }
+        self.next_reminder = self.stretch + self.prefs.interval();
+        Some(reminder_text(self.stretch, accessible))
+    }
+
+    /// Turn reminders on or off, saving the choice
+    pub fn set_enabled(&mut self, enabled: bool) -> Result<()> {
+        self.prefs.enabled = enabled;
+        self.prefs.save(&self.path)
+    }
+
+    /// Change the reminder interval, saving the choice
+    pub fn set_interval(&mut self, minutes: u64) -> Result<()> {
+        if !(MIN_INTERVAL_MINUTES..=MAX_INTERVAL_MINUTES).contains(&minutes) {
+            bail!(
+                "Interval must be between {} and {} minutes",
+                MIN_INTERVAL_MINUTES,
+                MAX_INTERVAL_MINUTES
+            );
+        }
+        self.prefs.interval_minutes = minutes;
+        self.prefs.enabled = true;
+        self.next_reminder = self.stretch.max(self.prefs.interval());
+        self.prefs.save(&self.path)
+    }
+}
+
+/// Duration as `1h 05m` or `45m`
+pub fn format_minutes(duration: Duration) -> String {
+    let minutes = duration.as_secs() / 60;
+    if minutes >= 60 {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+/// Gentle break reminder; plain text when `accessible`
+pub fn reminder_text(stretch: Duration, accessible: bool) -> String {
+    let body = format!(
+        "You've been working for {} without a break. A few minutes away from the screen \
+         (stretch, water, rest your eyes) is a good idea.",
+        format_minutes(stretch)
+    );
+    if accessible {
+        format!("Break reminder: {} Type /wellness off to stop these.", body)
+    } else {
+        format!("☕ {} (/wellness off to stop these)", body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn minutes(m: u64) -> Duration {
+        Duration::from_secs(m * 60)
+    }
+
+    #[test]
+    fn test_reminder_after_continuous_use_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("wellness.json");
+        let mut wellness = Wellness::with_path(path.clone());
+        wellness.set_interval(30).unwrap();
+
+        // Steady use in 4-minute steps: due after 30 minutes
+        let start = Instant::now();
+        for step in 0..=7 {
+            wellness.record_activity_at(start + minutes(step * 4));
+        }
+        assert_eq!(wellness.stretch(), minutes(28));
+        assert!(wellness.due_reminder(false).is_none());
+        wellness.record_activity_at(start + minutes(32));
+        let text = wellness.due_reminder(true).unwrap();
+        assert!(text.starts_with("Break reminder: You've been working for 32m"));
+        // Dismissed until another interval has passed
+        assert!(wellness.due_reminder(true).is_none());
+
+        // An idle gap is a break and is not counted as use
+        wellness.record_activity_at(start + minutes(60));
+        assert_eq!(wellness.stretch(), Duration::ZERO);
+        assert_eq!(wellness.active_time(), minutes(32));
+        assert!(wellness.due_reminder(false).is_none());
+
+        // The preference persists
+        wellness.set_enabled(false).unwrap();
+        let reloaded = Wellness::with_path(path);
+        assert_eq!(
+            reloaded.prefs(),
+            &WellnessPrefs {
+                enabled: false,
+                interval_minutes: 30
+            }
+        );
+        let mut wellness = reloaded;
+        assert!(wellness.set_interval(1).is_err());
+        assert_eq!(format_minutes(minutes(65)), "1h 05m");
+    }
+}
@@ -6,7 +6,8 @@
 use anyhow::Result;
 use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::process::Command;
 use std::time::SystemTime;
 use tracing::{debug, warn};
 use walkdir::WalkDir;
@@ -24,6 +25,10 @@ pub struct GardenPlant {
     pub last_tended_days: u64,
     pub growth_stage: GrowthStage,
     pub plant_type: PlantType,
+    /// Commits touching this file, or 0 outside a git repository
+    pub commit_count: usize,
+    /// Line coverage from an lcov report, when one was found
+    pub coverage_pct: Option<f32>,
 }
 
 /// Growth stages based on file maturity
@@ -37,18 +42,48 @@ pub enum GrowthStage {
     Wilting,     // Not touched in 90+ days
 }
 
+/// Commits after which an untested file is considered high churn
+const HIGH_CHURN_COMMITS: usize = 10;
+
+/// Line coverage at which a plant is drawn as healthy rather than wilting
+pub const HEALTHY_COVERAGE_PCT: f32 = 50.0;
+
+/// Commits after which a file grows one stage beyond its size
+const VIGOROUS_COMMITS: usize = 20;
+
 impl GrowthStage {
-    pub fn from_metrics(lines: usize, _age_days: u64, last_tended_days: u64) -> Self {
+    /// Stage a file by size, promoted by frequent commits and demoted to
+    /// `Wilting` when it is stale or churns without any test coverage.
+    pub fn from_metrics(
+        lines: usize,
+        _age_days: u64,
+        last_tended_days: u64,
+        commit_count: usize,
+        coverage_pct: Option<f32>,
+    ) -> Self {
         if last_tended_days > 90 {
             return GrowthStage::Wilting;
         }
+        if commit_count >= HIGH_CHURN_COMMITS && coverage_pct.is_some_and(|pct| pct <= 0.0) {
+            return GrowthStage::Wilting;
+        }
 
-        match lines {
+        let stage = match lines {
             0..=50 => GrowthStage::Seedling,
             51..=200 => GrowthStage::Sprout,
             201..=500 => GrowthStage::Established,
             501..=1000 => GrowthStage::Mature,
             _ => GrowthStage::Ancient,
+        };
+
+        if commit_count < VIGOROUS_COMMITS {
+            return stage;
+        }
+        match stage {
+            GrowthStage::Seedling => GrowthStage::Sprout,
+            GrowthStage::Sprout => GrowthStage::Established,
+            GrowthStage::Established => GrowthStage::Mature,
+            other => other,
         }
     }
 
@@ -390,6 +425,203 @@ impl DigitalGarden {
     }
 }
 
+/// Where coverage tools commonly write an lcov report, relative to the repository root
+const LCOV_CANDIDATES: &[&str] = &[
+    "lcov.info",
+    "coverage/lcov.info",
+    "target/lcov.info",
+    "target/coverage/lcov.info",
+];
+
+/// Commit history for one file
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct FileHistory {
+    commits: usize,
+    first_commit: u64,
+    last_commit: u64,
+}
+
+/// Real project signals for the garden: git churn and lcov coverage.
+///
+/// Both sources are optional. Outside a git repository plants fall back to
+/// file modification times, and without an lcov report they carry no coverage.
+#[derive(Debug, Default)]
+pub struct GardenSignals {
+    root: PathBuf,
+    history: HashMap<PathBuf, FileHistory>,
+    coverage: HashMap<PathBuf, f32>,
+    now: u64,
+}
+
+impl GardenSignals {
+    /// Collect git history and coverage for the repository containing `dir`.
+    pub fn gather(dir: &Path) -> Self {
+        let root = git_toplevel(dir).unwrap_or_else(|| dir.to_path_buf());
+        let root = root.canonicalize().unwrap_or(root);
+
+        let history = git_log_names(&root)
+            .map(|log| parse_git_log(&log))
+            .unwrap_or_default();
+
+        let coverage = LCOV_CANDIDATES
+            .iter()
+            .map(|candidate| root.join(candidate))
+            .find(|report| report.is_file())
+            .and_then(|report| {
+                fs::read_to_string(&report)
+                    .map_err(|err| {
+                        debug!("Failed to read lcov report '{}': {}", report.display(), err)
+                    })
+                    .ok()
+            })
+            .map(|report| parse_lcov(&report, &root))
+            .unwrap_or_default();
+
+        Self {
+            root,
+            history,
+            coverage,
+            now: SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+        }
+    }
+
+    /// Days since the first and last commit, and the commit count, for `path`.
+    /// `None` when the file has no history (untracked or not in a repository).
+    pub fn history(&self, path: &Path) -> Option<(u64, u64, usize)> {
+        let history = self.history.get(&self.relative(path))?;
+        Some((
+            self.now.saturating_sub(history.first_commit) / 86400,
+            self.now.saturating_sub(history.last_commit) / 86400,
+            history.commits,
+        ))
+    }
+
+    /// Line coverage percentage for `path` from the lcov report.
+    pub fn coverage(&self, path: &Path) -> Option<f32> {
+        self.coverage.get(&self.relative(path)).copied()
+    }
+
+    fn relative(&self, path: &Path) -> PathBuf {
+        let absolute = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        absolute
+            .strip_prefix(&self.root)
+            .map(Path::to_path_buf)
+            .unwrap_or(absolute)
+    }
+}
+
+fn git_toplevel(dir: &Path) -> Option<PathBuf> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["rev-parse", "--show-toplevel"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let root = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!root.is_empty()).then(|| PathBuf::from(root))
+}
+
+fn git_log_names(root: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args([
+            "-c",
+            "core.quotePath=false",
+            "log",
+            "--no-renames",
+            "--format=%x00%ct",
+            "--name-only",
+        ])
+        .output()
+        .map_err(|err| debug!("Failed to run git log for the garden: {}", err))
+        .ok()?;
+    if !output.status.success() {
+        debug!(
+            "git log failed for the garden: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Parse `git log --format=%x00%ct --name-only` output, newest commit first,
+/// into per-file commit counts and first/last commit timestamps.
+fn parse_git_log(log: &str) -> HashMap<PathBuf, FileHistory> {
+    let mut files: HashMap<PathBuf, FileHistory> = HashMap::new();
+    let mut timestamp = 0;
+
+    for line in log.lines() {
+        if let Some(stamp) = line.strip_prefix('\0') {
+            timestamp = stamp.trim().parse().unwrap_or(0);
+            continue;
+        }
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let entry = files.entry(PathBuf::from(line)).or_insert(FileHistory {
+            commits: 0,
+            first_commit: timestamp,
+            last_commit: timestamp,
+        });
+        entry.commits += 1;
+        entry.first_commit = timestamp;
+    }
+
+    files
+}
+
+/// Parse an lcov tracefile into line coverage percentages keyed by path
+/// relative to `root`.
+fn parse_lcov(report: &str, root: &Path) -> HashMap<PathBuf, f32> {
+    let mut coverage = HashMap::new();
+    let mut source: Option<PathBuf> = None;
+    let (mut found, mut hit) = (0usize, 0usize);
+
+    for line in report.lines() {
+        let line = line.trim();
+        if let Some(path) = line.strip_prefix("SF:") {
+            source = Some(PathBuf::from(path));
+            (found, hit) = (0, 0);
+        } else if let Some(data) = line.strip_prefix("DA:") {
+            found += 1;
+            let count = data.split(',').nth(1).unwrap_or("0");
+            if count.trim().parse::<u64>().unwrap_or(0) > 0 {
+                hit += 1;
+            }
+        } else if line == "end_of_record" {
+            let Some(path) = source.take() else {
+                continue;
+            };
+            let absolute = if path.is_absolute() {
+                path.canonicalize().unwrap_or(path)
+            } else {
+                root.join(&path)
+            };
+            let relative = absolute
+                .strip_prefix(root)
+                .map(Path::to_path_buf)
+                .unwrap_or(absolute);
+            let pct = if found == 0 {
+                0.0
+            } else {
+                hit as f32 / found as f32 * 100.0
+            };
+            coverage.insert(relative, pct);
+        }
+    }
+
+    coverage
+}
+
 /// Build a digital garden visualization from a path.
 pub fn build_garden_from_path(path: &str) -> Result<DigitalGarden> {
     let project_name = Path::new(path)
@@ -404,6 +636,7 @@ pub fn build_garden_from_path(path: &str) -> Result<DigitalGarden> {
         });
 
     let mut garden = DigitalGarden::new(&project_name);
+    let signals = GardenSignals::gather(Path::new(path));
 
     let sep = std::path::MAIN_SEPARATOR_STR;
 
@@ -493,7 +726,12 @@ pub fn build_garden_from_path(path: &str) -> Result<DigitalGarden> {
                 0
             });
 
-        let age_days = now.saturating_sub(modified) / 86400;
+        let modified_days = now.saturating_sub(modified) / 86400;
+        let (age_days, last_tended_days, commit_count) =
+            signals
+                .history(entry.path())
+                .unwrap_or((modified_days, modified_days, 0));
+        let coverage_pct = signals.coverage(entry.path());
 
         let plant = GardenPlant {
             path: path_str.clone(),
@@ -501,9 +739,17 @@ pub fn build_garden_from_path(path: &str) -> Result<DigitalGarden> {
             extension: ext.to_string(),
             lines,
             age_days,
-            last_tended_days: age_days,
-            growth_stage: GrowthStage::from_metrics(lines, age_days, age_days),
+            last_tended_days,
+            growth_stage: GrowthStage::from_metrics(
+                lines,
+                age_days,
+                last_tended_days,
+                commit_count,
+                coverage_pct,
+            ),
             plant_type: PlantType::from_path(&path_str),
+            commit_count,
+            coverage_pct,
         };
 
         garden.add_plant(plant);
@@ -514,14 +760,30 @@ pub fn build_garden_from_path(path: &str) -> Result<DigitalGarden> {
 
 /// Render a single file in garden view
 pub fn render_plant(plant: &GardenPlant) -> String {
-    format!(
+    let mut line = format!(
         "{} {} {} — {} lines, {} days old",
         plant.growth_stage.glyph(),
         plant.name.as_str().emphasis(),
         format!("({})", plant.growth_stage.description()).muted(),
         plant.lines.to_string().muted(),
         plant.age_days.to_string().muted()
-    )
+    );
+    if plant.commit_count > 0 {
+        line.push_str(&format!(
+            ", {} commits",
+            plant.commit_count.to_string().muted()
+        ));
+    }
+    if let Some(pct) = plant.coverage_pct {
+        let label = format!("{:.0}% covered", pct);
+        line.push_str(", ");
+        line.push_str(&if pct < HEALTHY_COVERAGE_PCT {
+            label.garden_wilting()
+        } else {
+            label.garden_healthy()
+        });
+    }
+    line
 }
 
 /// Quick garden status for the status bar
@@ -550,6 +812,7 @@ pub fn scan_directory(dir: &Path) -> DigitalGarden {
         .unwrap_or_else(|| "project".to_string());
 
     let mut garden = DigitalGarden::new(&project_name);
+    let signals = GardenSignals::gather(dir);
 
     // Code file extensions to include
     let code_extensions = [
@@ -614,15 +877,29 @@ pub fn scan_directory(dir: &Path) -> DigitalGarden {
             .map(|n| n.to_string_lossy().to_string())
             .unwrap_or_default();
 
+        let (age_days, last_tended_days, commit_count) =
+            signals
+                .history(path)
+                .unwrap_or((age_days, last_modified_days, 0));
+        let coverage_pct = signals.coverage(path);
+
         let plant = GardenPlant {
             path: path_str,
             name,
             extension: ext,
             lines,
             age_days,
-            last_tended_days: last_modified_days,
-            growth_stage: GrowthStage::from_metrics(lines, age_days, last_modified_days),
+            last_tended_days,
+            growth_stage: GrowthStage::from_metrics(
+                lines,
+                age_days,
+                last_tended_days,
+                commit_count,
+                coverage_pct,
+            ),
             plant_type: PlantType::from_path(path.to_string_lossy().as_ref()),
+            commit_count,
+            coverage_pct,
         };
 
         garden.add_plant(plant);
@@ -637,14 +914,20 @@ mod tests {
 
     #[test]
     fn test_growth_stage_from_metrics() {
-        assert_eq!(GrowthStage::from_metrics(10, 1, 1), GrowthStage::Seedling);
-        assert_eq!(GrowthStage::from_metrics(100, 30, 5), GrowthStage::Sprout);
         assert_eq!(
-            GrowthStage::from_metrics(300, 60, 10),
+            GrowthStage::from_metrics(10, 1, 1, 0, None),
+            GrowthStage::Seedling
+        );
+        assert_eq!(
+            GrowthStage::from_metrics(100, 30, 5, 0, None),
+            GrowthStage::Sprout
+        );
+        assert_eq!(
+            GrowthStage::from_metrics(300, 60, 10, 0, None),
             GrowthStage::Established
         );
         assert_eq!(
-            GrowthStage::from_metrics(100, 30, 100),
+            GrowthStage::from_metrics(100, 30, 100, 0, None),
             GrowthStage::Wilting
         );
     }
@@ -652,33 +935,60 @@ mod tests {
     #[test]
     fn test_growth_stage_all_stages() {
         // Seedling: 0-50 lines
-        assert_eq!(GrowthStage::from_metrics(0, 1, 1), GrowthStage::Seedling);
-        assert_eq!(GrowthStage::from_metrics(50, 1, 1), GrowthStage::Seedling);
+        assert_eq!(
+            GrowthStage::from_metrics(0, 1, 1, 0, None),
+            GrowthStage::Seedling
+        );
+        assert_eq!(
+            GrowthStage::from_metrics(50, 1, 1, 0, None),
+            GrowthStage::Seedling
+        );
 
         // Sprout: 51-200 lines
-        assert_eq!(GrowthStage::from_metrics(51, 1, 1), GrowthStage::Sprout);
-        assert_eq!(GrowthStage::from_metrics(200, 1, 1), GrowthStage::Sprout);
+        assert_eq!(
+            GrowthStage::from_metrics(51, 1, 1, 0, None),
+            GrowthStage::Sprout
+        );
+        assert_eq!(
+            GrowthStage::from_metrics(200, 1, 1, 0, None),
+            GrowthStage::Sprout
+        );
 
         // Established: 201-500 lines
         assert_eq!(
-            GrowthStage::from_metrics(201, 1, 1),
+            GrowthStage::from_metrics(201, 1, 1, 0, None),
             GrowthStage::Established
         );
         assert_eq!(
-            GrowthStage::from_metrics(500, 1, 1),
+            GrowthStage::from_metrics(500, 1, 1, 0, None),
             GrowthStage::Established
         );
 
         // Mature: 501-1000 lines
-        assert_eq!(GrowthStage::from_metrics(501, 1, 1), GrowthStage::Mature);
-        assert_eq!(GrowthStage::from_metrics(1000, 1, 1), GrowthStage::Mature);
+        assert_eq!(
+            GrowthStage::from_metrics(501, 1, 1, 0, None),
+            GrowthStage::Mature
+        );
+        assert_eq!(
+            GrowthStage::from_metrics(1000, 1, 1, 0, None),
+            GrowthStage::Mature
+        );
 
         // Ancient: >1000 lines
-        assert_eq!(GrowthStage::from_metrics(1001, 1, 1), GrowthStage::Ancient);
-        assert_eq!(GrowthStage::from_metrics(5000, 1, 1), GrowthStage::Ancient);
+        assert_eq!(
+            GrowthStage::from_metrics(1001, 1, 1, 0, None),
+            GrowthStage::Ancient
+        );
+        assert_eq!(
+            GrowthStage::from_metrics(5000, 1, 1, 0, None),
+            GrowthStage::Ancient
+        );
 
         // Wilting overrides all (>90 days)
-        assert_eq!(GrowthStage::from_metrics(5000, 1, 91), GrowthStage::Wilting);
+        assert_eq!(
+            GrowthStage::from_metrics(5000, 1, 91, 0, None),
+            GrowthStage::Wilting
+        );
     }
 
     #[test]
@@ -778,6 +1088,8 @@ mod tests {
             last_tended_days: 5,
             growth_stage: GrowthStage::Sprout,
             plant_type: PlantType::Flower,
+            commit_count: 0,
+            coverage_pct: None,
         });
 
         assert_eq!(bed.health_score, 1.0);
@@ -797,6 +1109,8 @@ mod tests {
             last_tended_days: 5,
             growth_stage: GrowthStage::Sprout,
             plant_type: PlantType::Vegetable,
+            commit_count: 0,
+            coverage_pct: None,
         });
 
         // Add wilting plant
@@ -809,6 +1123,8 @@ mod tests {
             last_tended_days: 150,
             growth_stage: GrowthStage::Wilting,
             plant_type: PlantType::Vegetable,
+            commit_count: 0,
+            coverage_pct: None,
         });
 
         // 1 of 2 wilting = 0.5 health (not > 0.5, so FROST)
@@ -829,6 +1145,8 @@ mod tests {
             last_tended_days: 150,
             growth_stage: GrowthStage::Wilting,
             plant_type: PlantType::Vegetable,
+            commit_count: 0,
+            coverage_pct: None,
         });
 
         bed.add_plant(GardenPlant {
@@ -840,6 +1158,8 @@ mod tests {
             last_tended_days: 150,
             growth_stage: GrowthStage::Wilting,
             plant_type: PlantType::Vegetable,
+            commit_count: 0,
+            coverage_pct: None,
         });
 
         assert_eq!(bed.health_score, 0.0);
@@ -863,6 +1183,8 @@ mod tests {
             last_tended_days: 1,
             growth_stage: GrowthStage::Seedling,
             plant_type: PlantType::Vegetable,
+            commit_count: 0,
+            coverage_pct: None,
         });
 
         assert_eq!(bed.health_indicator(), Glyphs::bloom());
@@ -881,6 +1203,8 @@ mod tests {
             last_tended_days: 1,
             growth_stage: GrowthStage::Seedling,
             plant_type: PlantType::Flower,
+            commit_count: 0,
+            coverage_pct: None,
         });
 
         assert_eq!(garden.total_plants, 1);
@@ -901,6 +1225,8 @@ mod tests {
             last_tended_days: 1,
             growth_stage: GrowthStage::Sprout,
             plant_type: PlantType::Flower,
+            commit_count: 0,
+            coverage_pct: None,
         });
 
         // Add to tests/
@@ -913,6 +1239,8 @@ mod tests {
             last_tended_days: 1,
             growth_stage: GrowthStage::Seedling,
             plant_type: PlantType::Pollinator,
+            commit_count: 0,
+            coverage_pct: None,
         });
 
         assert_eq!(garden.total_plants, 2);
@@ -933,6 +1261,8 @@ mod tests {
             last_tended_days: 1,
             growth_stage: GrowthStage::Sprout,
             plant_type: PlantType::Flower,
+            commit_count: 0,
+            coverage_pct: None,
         });
 
         let output = garden.render();
@@ -968,6 +1298,8 @@ mod tests {
                 last_tended_days: 1,
                 growth_stage: GrowthStage::Established,
                 plant_type: PlantType::Vegetable,
+                commit_count: 0,
+                coverage_pct: None,
             });
         }
 
@@ -1003,6 +1335,8 @@ mod tests {
             last_tended_days: 5,
             growth_stage: GrowthStage::Established,
             plant_type: PlantType::Flower,
+            commit_count: 0,
+            coverage_pct: None,
         };
 
         let rendered = render_plant(&plant);
@@ -1025,6 +1359,8 @@ mod tests {
             last_tended_days: 1,
             growth_stage: GrowthStage::Sprout,
             plant_type: PlantType::Flower,
+            commit_count: 0,
+            coverage_pct: None,
         });
 
         let status = garden_status_short(&garden);
@@ -1046,6 +1382,8 @@ mod tests {
                 last_tended_days: 1,
                 growth_stage: GrowthStage::Sprout,
                 plant_type: PlantType::Vegetable,
+                commit_count: 0,
+                coverage_pct: None,
             });
         }
 
@@ -1069,6 +1407,8 @@ mod tests {
                 last_tended_days: 150,
                 growth_stage: GrowthStage::Wilting,
                 plant_type: PlantType::Vegetable,
+                commit_count: 0,
+                coverage_pct: None,
             });
         }
 
@@ -1082,6 +1422,8 @@ mod tests {
             last_tended_days: 1,
             growth_stage: GrowthStage::Seedling,
             plant_type: PlantType::Vegetable,
+            commit_count: 0,
+            coverage_pct: None,
         });
 
         let status = garden_status_short(&garden);
@@ -1119,6 +1461,8 @@ mod tests {
             last_tended_days: 1,
             growth_stage: GrowthStage::Seedling,
             plant_type: PlantType::Vegetable,
+            commit_count: 0,
+            coverage_pct: None,
         });
 
         garden.add_plant(GardenPlant {
@@ -1130,6 +1474,8 @@ mod tests {
             last_tended_days: 5,
             growth_stage: GrowthStage::Mature,
             plant_type: PlantType::Vegetable,
+            commit_count: 0,
+            coverage_pct: None,
         });
 
         garden.add_plant(GardenPlant {
@@ -1141,6 +1487,8 @@ mod tests {
             last_tended_days: 150,
             growth_stage: GrowthStage::Wilting,
             plant_type: PlantType::Vegetable,
+            commit_count: 0,
+            coverage_pct: None,
         });
 
         // Render should include correct counts
@@ -1159,6 +1507,8 @@ mod tests {
             last_tended_days: 5,
             growth_stage: GrowthStage::Sprout,
             plant_type: PlantType::Vegetable,
+            commit_count: 0,
+            coverage_pct: None,
         };
 
         let cloned = plant.clone();
@@ -1178,6 +1528,8 @@ mod tests {
             last_tended_days: 5,
             growth_stage: GrowthStage::Sprout,
             plant_type: PlantType::Vegetable,
+            commit_count: 0,
+            coverage_pct: None,
         });
 
         let cloned = bed.clone();
@@ -1197,10 +1549,87 @@ mod tests {
             last_tended_days: 1,
             growth_stage: GrowthStage::Seedling,
             plant_type: PlantType::Flower,
+            commit_count: 0,
+            coverage_pct: None,
         });
 
         let cloned = garden.clone();
         assert_eq!(cloned.project_name, garden.project_name);
         assert_eq!(cloned.total_plants, garden.total_plants);
     }
+
+    #[test]
+    fn test_growth_stage_churn_and_coverage() {
+        // Heavily churned file with no coverage wilts even when fresh
+        assert_eq!(
+            GrowthStage::from_metrics(300, 10, 1, 12, Some(0.0)),
+            GrowthStage::Wilting
+        );
+        // Same churn with tests stays healthy
+        assert_eq!(
+            GrowthStage::from_metrics(300, 10, 1, 12, Some(80.0)),
+            GrowthStage::Established
+        );
+        // Unknown coverage never wilts on churn alone
+        assert_eq!(
+            GrowthStage::from_metrics(300, 10, 1, 12, None),
+            GrowthStage::Established
+        );
+        // Frequent commits promote one stage
+        assert_eq!(
+            GrowthStage::from_metrics(30, 10, 1, 25, Some(90.0)),
+            GrowthStage::Sprout
+        );
+        assert_eq!(
+            GrowthStage::from_metrics(2000, 10, 1, 25, None),
+            GrowthStage::Ancient
+        );
+    }
+
+    #[test]
+    fn test_parse_git_log_counts_commits_per_file() {
+        let log = "\u{0}300\n\nsrc/a.rs\nsrc/b.rs\n\u{0}200\n\nsrc/a.rs\n\u{0}100\n\nsrc/a.rs\n";
+        let history = parse_git_log(log);
+
+        let a = history[Path::new("src/a.rs")];
+        assert_eq!(a.commits, 3);
+        assert_eq!(a.last_commit, 300);
+        assert_eq!(a.first_commit, 100);
+
+        let b = history[Path::new("src/b.rs")];
+        assert_eq!(b.commits, 1);
+        assert_eq!((b.first_commit, b.last_commit), (300, 300));
+    }
+
+    #[test]
+    fn test_parse_lcov_line_coverage() {
+        let root = Path::new("/repo");
+        let report = "TN:\nSF:/repo/src/a.rs\nDA:1,3\nDA:2,0\nDA:3,1\nDA:4,0\nend_of_record\n\
+                      SF:src/b.rs\nDA:1,0\nend_of_record\n";
+        let coverage = parse_lcov(report, root);
+        assert_eq!(coverage[Path::new("src/a.rs")], 50.0);
+        assert_eq!(coverage[Path::new("src/b.rs")], 0.0);
+    }
+
+    #[test]
+    fn test_build_garden_reads_lcov_report() {
+        // Default temp names start with '.', which the garden skips as hidden
+        let dir = tempfile::Builder::new().prefix("garden").tempdir().unwrap();
+        std::fs::write(dir.path().join("lib.rs"), "fn a() {}\nfn b() {}\n").unwrap();
+        std::fs::write(
+            dir.path().join("lcov.info"),
+            "SF:lib.rs\nDA:1,1\nDA:2,0\nend_of_record\n",
+        )
+        .unwrap();
+
+        let garden = build_garden_from_path(dir.path().to_str().unwrap()).unwrap();
+        let plant = garden
+            .beds
+            .values()
+            .flat_map(|b| &b.plants)
+            .find(|p| p.name == "lib.rs")
+            .unwrap();
+        assert_eq!(plant.coverage_pct, Some(50.0));
+        assert!(render_plant(plant).contains("50% covered"));
+    }
 }
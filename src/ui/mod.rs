@@ -4,12 +4,14 @@
 //! Built around the philosophy: software you own, software that knows you,
 //! software that lasts.
 
+pub mod accessibility;
 pub mod animations;
 pub mod banners;
 pub mod components;
 pub mod garden;
 pub mod loading_phrases;
 pub mod mascot;
+pub mod panes;
 pub mod spinner;
 pub mod style;
 pub mod theme;
@@ -0,0 +1,338 @@
+//! Live Agent Panes
+//!
+//! Renders concurrent agent streams side by side in the terminal: each
+//! running agent gets its own pane with a header and an independent
+//! spinner, and streamed text is routed to the pane of the agent that
+//! produced it. The live region is redrawn in place with ANSI cursor
+//! movement; finished agents leave a one-line summary above it and free
+//! their pane for the next agent.
+//!
+//! Without ANSI support, complete lines are printed prefixed with the
+//! agent's name instead.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+use colored::Colorize;
+use unicode_width::UnicodeWidthChar;
+
+use crate::ui::animations::SPINNER_DOTS;
+
+/// Lines of output kept visible per pane
+pub const DEFAULT_PANE_LINES: usize = 4;
+
+/// Where an agent is in its run
+#[derive(Debug, Clone, PartialEq)]
+pub enum PaneStatus {
+    /// Started, no output yet
+    Waiting,
+    Streaming,
+    Done(Duration),
+    Failed(String),
+}
+
+/// One agent's pane
+#[derive(Debug, Clone)]
+struct Pane {
+    title: String,
+    status: PaneStatus,
+    started: Instant,
+    /// Last complete lines, oldest first
+    lines: VecDeque<String>,
+    /// Text after the last newline
+    partial: String,
+}
+
+impl Pane {
+    fn new(title: String) -> Self {
+        Self {
+            title,
+            status: PaneStatus::Waiting,
+            started: Instant::now(),
+            lines: VecDeque::new(),
+            partial: String::new(),
+        }
+    }
+
+    /// Append streamed text, returning the lines it completed
+    fn push(&mut self, text: &str, keep: usize) -> Vec<String> {
+        if self.status == PaneStatus::Waiting {
+            self.status = PaneStatus::Streaming;
+        }
+        self.partial.push_str(text);
+        let mut completed = Vec::new();
+        while let Some(pos) = self.partial.find('\n') {
+            let line: String = self.partial.drain(..=pos).collect();
+            let line = line.trim_end().to_string();
+            if line.is_empty() {
+                continue;
+            }
+            completed.push(line.clone());
+            self.lines.push_back(line);
+            if self.lines.len() > keep {
+                self.lines.pop_front();
+            }
+        }
+        completed
+    }
+
+    /// Visible body lines, including the line still being streamed
+    fn visible(&self, keep: usize) -> Vec<&str> {
+        let partial = self.partial.trim_end();
+        let mut lines: Vec<&str> = self.lines.iter().map(String::as_str).collect();
+        if !partial.is_empty() {
+            lines.push(partial);
+        }
+        let skip = lines.len().saturating_sub(keep);
+        lines.split_off(skip)
+    }
+}
+
+/// Panes for concurrently running agents, keyed by agent ID
+#[derive(Debug)]
+pub struct PaneSet {
+    panes: BTreeMap<usize, Pane>,
+    lines_per_pane: usize,
+    width: usize,
+    live: bool,
+    color: bool,
+    /// Lines of the live region currently on screen
+    drawn: usize,
+}
+
+impl PaneSet {
+    /// Create a pane set that redraws in place when `live`, or prints
+    /// prefixed lines otherwise
+    pub fn new(lines_per_pane: usize, live: bool) -> Self {
+        let width = crossterm::terminal::size()
+            .map(|(w, _)| w as usize)
+            .unwrap_or(80);
+        Self {
+            panes: BTreeMap::new(),
+            lines_per_pane: lines_per_pane.max(1),
+            width: width.max(20),
+            live,
+            color: crate::ui::spinner::supports_color(),
+            drawn: 0,
+        }
+    }
+
+    /// Render at a fixed width without color (for tests and logs)
+    pub fn with_width(mut self, width: usize) -> Self {
+        self.width = width.max(20);
+        self.color = false;
+        self
+    }
+
+    /// Open a pane for an agent
+    pub fn open(&mut self, agent_id: usize, title: impl Into<String>) {
+        self.panes.insert(agent_id, Pane::new(title.into()));
+    }
+
+    /// Number of open panes
+    pub fn len(&self) -> usize {
+        self.panes.len()
+    }
+
+    /// Check if no pane is open
+    pub fn is_empty(&self) -> bool {
+        self.panes.is_empty()
+    }
+
+    /// Route streamed text to an agent's pane. In plain mode the lines it
+    /// completes are returned, prefixed with the agent's name, for printing.
+    pub fn push(&mut self, agent_id: usize, text: &str) -> Vec<String> {
+        let keep = self.lines_per_pane;
+        let Some(pane) = self.panes.get_mut(&agent_id) else {
+            return Vec::new();
+        };
+        let completed = pane.push(text, keep);
+        if self.live {
+            return Vec::new();
+        }
+        completed
+            .into_iter()
+            .map(|line| {
+                format!(
+                    "{} │ {}",
+                    paint(self.color, &pane.title, |s| s.dimmed()),
+                    line
+                )
+            })
+            .collect()
+    }
+
+    /// Close an agent's pane, returning the summary line that replaces it
+    pub fn close(&mut self, agent_id: usize, status: PaneStatus) -> Option<String> {
+        let pane = self.panes.remove(&agent_id)?;
+        let line = match &status {
+            PaneStatus::Done(duration) => format!(
+                "  {} {} completed in {:.2}s",
+                paint(self.color, "✓", |s| s.bright_green()),
+                pane.title,
+                duration.as_secs_f64()
+            ),
+            PaneStatus::Failed(error) => {
+                format!(
+                    "  {} {} failed: {}",
+                    paint(self.color, "✗", |s| s.bright_red()),
+                    pane.title,
+                    error
+                )
+            }
+            PaneStatus::Waiting | PaneStatus::Streaming => return None,
+        };
+        Some(line)
+    }
+
+    /// Render the live region: a header per pane followed by its last lines
+    pub fn render(&self) -> Vec<String> {
+        let mut out = Vec::new();
+        for pane in self.panes.values() {
+            // Each pane's spinner runs off its own clock
+            let elapsed = pane.started.elapsed();
+            let frame = SPINNER_DOTS[(elapsed.as_millis() / 100) as usize % SPINNER_DOTS.len()];
+            let state = match &pane.status {
+                PaneStatus::Waiting => "waiting",
+                PaneStatus::Streaming => "streaming",
+                PaneStatus::Done(_) => "done",
+                PaneStatus::Failed(_) => "failed",
+            };
+            let header = format!(
+                "┌ {} {} · {} {:.1}s",
+                frame,
+                pane.title,
+                state,
+                elapsed.as_secs_f64()
+            );
+            let header = truncate_to_width(&header, self.width);
+            out.push(paint(self.color, &header, |s| s.bright_cyan()));
+            let body = pane.visible(self.lines_per_pane);
+            for i in 0..self.lines_per_pane {
+                let line = body.get(i).copied().unwrap_or("");
+                out.push(format!(
+                    "{} {}",
+                    paint(self.color, "│", |s| s.bright_black()),
+                    truncate_to_width(line, self.width.saturating_sub(2))
+                ));
+            }
+        }
+        out
+    }
+
+    /// Redraw the live region in place
+    pub fn draw(&mut self, out: &mut impl Write) -> io::Result<()> {
+        if !self.live {
+            return Ok(());
+        }
+        self.clear(out)?;
+        let lines = self.render();
+        for line in &lines {
+            writeln!(out, "{}", line)?;
+        }
+        self.drawn = lines.len();
+        out.flush()
+    }
+
+    /// Print a line above the live region so it scrolls away normally
+    pub fn print_above(&mut self, out: &mut impl Write, line: &str) -> io::Result<()> {
+        self.clear(out)?;
+        writeln!(out, "{}", line)?;
+        self.drawn = 0;
+        out.flush()
+    }
+
+    /// Erase the live region
+    pub fn clear(&mut self, out: &mut impl Write) -> io::Result<()> {
+        if self.live && self.drawn > 0 {
+            write!(out, "\x1b[{}A\x1b[J", self.drawn)?;
+            self.drawn = 0;
+        }
+        Ok(())
+    }
+}
+
+/// Apply a color only when the terminal supports it
+fn paint(color: bool, text: &str, style: impl Fn(&str) -> colored::ColoredString) -> String {
+    if color {
+        style(text).to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+/// Cut `text` to at most `width` terminal columns
+fn truncate_to_width(text: &str, width: usize) -> String {
+    let mut used = 0;
+    let mut out = String::new();
+    for c in text.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w > width {
+            break;
+        }
+        used += w;
+        out.push(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_panes_route_chunks_by_agent() {
+        let mut panes = PaneSet::new(2, true).with_width(40);
+        panes.open(0, "Agent-0-Architect");
+        panes.open(1, "Agent-1-Coder");
+
+        // Interleaved chunks land in the right pane
+        panes.push(0, "design ");
+        panes.push(1, "fn main() {}\n");
+        panes.push(0, "first\nsecond\nthird\n");
+        let lines = panes.render();
+        assert_eq!(lines.len(), 6);
+        assert!(lines[0].contains("Agent-0-Architect · streaming"));
+        assert_eq!(lines[1], "│ second");
+        assert_eq!(lines[2], "│ third");
+        assert!(lines[3].contains("Agent-1-Coder"));
+        assert_eq!(lines[4], "│ fn main() {}");
+        assert_eq!(lines[5], "│ ");
+
+        let summary = panes
+            .close(0, PaneStatus::Done(Duration::from_millis(1500)))
+            .unwrap();
+        assert!(summary.contains("Agent-0-Architect completed in 1.50s"));
+        assert_eq!(panes.len(), 1);
+        assert!(panes.push(0, "late\n").is_empty());
+    }
+
+    #[test]
+    fn test_panes_redraw_in_place_and_plain_fallback() {
+        let mut panes = PaneSet::new(1, true).with_width(30);
+        panes.open(3, "Agent-3-Tester");
+        panes.push(3, "a very long line that will not fit in thirty columns\n");
+
+        let mut out = Vec::new();
+        panes.draw(&mut out).unwrap();
+        panes.draw(&mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        // The second draw moves up over the two lines of the first
+        assert!(text.contains("\x1b[2A\x1b[J"));
+        assert!(text
+            .lines()
+            .all(|l| l.trim_start_matches("\x1b[2A\x1b[J").chars().count() <= 30));
+
+        let mut plain = PaneSet::new(1, false).with_width(80);
+        plain.open(0, "Agent-0-Coder");
+        assert!(plain.push(0, "partial").is_empty());
+        assert_eq!(
+            plain.push(0, " line\n"),
+            vec!["Agent-0-Coder │ partial line"]
+        );
+        let mut out = Vec::new();
+        plain.draw(&mut out).unwrap();
+        assert!(out.is_empty());
+    }
+}
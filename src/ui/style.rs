@@ -456,12 +456,16 @@ pub fn tool_metaphor(tool_name: &str) -> &'static str {
         "git_diff" => "comparing growth",
         "git_commit" => "preserving your harvest",
         "git_checkpoint" => "marking the season",
+        "git_branch_create" => "grafting a new branch",
+        "git_checkout" => "walking to another row",
 
         // Cargo/build operations
         "cargo_test" => "testing the soil",
         "cargo_check" => "inspecting the joinery",
         "cargo_clippy" => "polishing",
         "cargo_fmt" => "tidying the workshop",
+        "cargo_add" => "planting a new crop",
+        "cargo_remove" => "pulling weeds",
 
         // Search operations
         "grep_search" => "foraging",
@@ -42,6 +42,8 @@ pub enum TuiEvent {
     GardenHealthUpdate { health: f64 },
     /// Log message
     Log { level: LogLevel, message: String },
+    /// Context, usage and plan progress of the running task
+    RunStats(crate::observability::dashboard::RunStats),
 }
 
 /// Dashboard state containing all widget data
@@ -194,6 +196,7 @@ impl DashboardState {
             TuiEvent::Log { level, message } => {
                 self.log(level, &message);
             }
+            TuiEvent::RunStats(_) => {}
         }
     }
 }
@@ -0,0 +1,564 @@
+//! Interactive Garden Explorer
+//!
+//! A full-screen, navigable list of every plant in the garden:
+//! - ↑/↓ (or j/k) move between plants
+//! - Enter inspects the selected file: symbols and tech debt in a side panel
+//! - `/` filters plants by name or extension
+//! - `a` hands the file back to the agent for a full analysis
+//! - `q` or Esc returns to chat
+
+use super::TuiPalette;
+use crate::analysis::file_insight::FileInsight;
+use crate::analysis::tech_debt::DebtSeverity;
+use crate::ui::garden::{DigitalGarden, GardenPlant, GrowthStage, HEALTHY_COVERAGE_PCT};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+use std::path::Path;
+
+/// Symbols listed in the side panel before the rest are summarized
+const MAX_PANEL_SYMBOLS: usize = 20;
+
+/// Debt items listed in the side panel before the rest are summarized
+const MAX_PANEL_DEBT: usize = 10;
+
+/// What the explorer wants the caller to do after a key press
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExplorerAction {
+    /// Keep the explorer open
+    Continue,
+    /// Close the explorer and return to chat
+    Quit,
+    /// Close the explorer and ask the agent to analyze this file
+    Analyze(String),
+}
+
+/// Full-screen garden navigator with a per-file insight panel
+pub struct GardenExplorer {
+    project_name: String,
+    /// Every plant in the garden, ordered by path
+    plants: Vec<GardenPlant>,
+    /// Indices into `plants` that match the current filter
+    visible: Vec<usize>,
+    /// Position within `visible`
+    selected: usize,
+    list_state: ListState,
+    filter: String,
+    /// True while `/` filter input is being typed
+    filtering: bool,
+    /// Insight for the most recently inspected plant
+    insight: Option<FileInsight>,
+    /// Error from the last inspection, if it failed
+    insight_error: Option<String>,
+}
+
+impl GardenExplorer {
+    /// Create an explorer over `garden`, flattening its beds into one list.
+    pub fn new(garden: &DigitalGarden) -> Self {
+        let mut plants: Vec<GardenPlant> = garden
+            .beds
+            .values()
+            .flat_map(|bed| bed.plants.iter().cloned())
+            .collect();
+        plants.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let mut explorer = Self {
+            project_name: garden.project_name.clone(),
+            plants,
+            visible: Vec::new(),
+            selected: 0,
+            list_state: ListState::default(),
+            filter: String::new(),
+            filtering: false,
+            insight: None,
+            insight_error: None,
+        };
+        explorer.apply_filter();
+        explorer
+    }
+
+    /// The plant under the cursor, if any match the filter
+    pub fn selected_plant(&self) -> Option<&GardenPlant> {
+        self.visible
+            .get(self.selected)
+            .and_then(|&index| self.plants.get(index))
+    }
+
+    /// Number of plants matching the current filter
+    pub fn visible_count(&self) -> usize {
+        self.visible.len()
+    }
+
+    /// Handle a terminal event and report what the caller should do next.
+    pub fn handle_event(&mut self, event: Event) -> ExplorerAction {
+        match event {
+            Event::Key(key) => self.handle_key(key),
+            _ => ExplorerAction::Continue,
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> ExplorerAction {
+        if key.code == KeyCode::Char('c') && key.modifiers == KeyModifiers::CONTROL {
+            return ExplorerAction::Quit;
+        }
+
+        if self.filtering {
+            match key.code {
+                KeyCode::Enter => self.filtering = false,
+                KeyCode::Esc => {
+                    self.filtering = false;
+                    self.filter.clear();
+                    self.apply_filter();
+                }
+                KeyCode::Backspace => {
+                    self.filter.pop();
+                    self.apply_filter();
+                }
+                KeyCode::Char(c) => {
+                    self.filter.push(c);
+                    self.apply_filter();
+                }
+                _ => {}
+            }
+            return ExplorerAction::Continue;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return ExplorerAction::Quit,
+            KeyCode::Up | KeyCode::Char('k') => self.select_prev(),
+            KeyCode::Down | KeyCode::Char('j') => self.select_next(),
+            KeyCode::Home => self.select(0),
+            KeyCode::End => self.select(self.visible.len().saturating_sub(1)),
+            KeyCode::Char('/') => self.filtering = true,
+            KeyCode::Enter => self.inspect_selected(),
+            KeyCode::Char('a') => {
+                if let Some(plant) = self.selected_plant() {
+                    return ExplorerAction::Analyze(plant.path.clone());
+                }
+            }
+            _ => {}
+        }
+        ExplorerAction::Continue
+    }
+
+    fn select(&mut self, index: usize) {
+        self.selected = index.min(self.visible.len().saturating_sub(1));
+        self.insight_error = None;
+        self.list_state.select(if self.visible.is_empty() {
+            None
+        } else {
+            Some(self.selected)
+        });
+    }
+
+    fn select_prev(&mut self) {
+        if self.visible.is_empty() {
+            return;
+        }
+        let index = if self.selected == 0 {
+            self.visible.len() - 1
+        } else {
+            self.selected - 1
+        };
+        self.select(index);
+    }
+
+    fn select_next(&mut self) {
+        if self.visible.is_empty() {
+            return;
+        }
+        self.select((self.selected + 1) % self.visible.len());
+    }
+
+    /// Recompute the visible plants, keeping the cursor on the same plant when
+    /// it still matches.
+    fn apply_filter(&mut self) {
+        let current = self.visible.get(self.selected).copied();
+        let needle = self.filter.trim().to_lowercase();
+        let extension = needle.trim_start_matches('.');
+
+        self.visible = self
+            .plants
+            .iter()
+            .enumerate()
+            .filter(|(_, plant)| {
+                needle.is_empty()
+                    || plant.name.to_lowercase().contains(&needle)
+                    || plant.extension.eq_ignore_ascii_case(extension)
+            })
+            .map(|(index, _)| index)
+            .collect();
+
+        let position = current
+            .and_then(|index| self.visible.iter().position(|&v| v == index))
+            .unwrap_or(0);
+        self.select(position);
+    }
+
+    /// Run the local file analysis for the selected plant.
+    fn inspect_selected(&mut self) {
+        let Some(plant) = self.selected_plant() else {
+            return;
+        };
+        match FileInsight::inspect(Path::new(&plant.path)) {
+            Ok(insight) => {
+                self.insight = Some(insight);
+                self.insight_error = None;
+            }
+            Err(err) => {
+                self.insight = None;
+                self.insight_error = Some(format!("{:#}", err));
+            }
+        }
+    }
+
+    /// Render the explorer into the whole frame.
+    pub fn render(&mut self, frame: &mut Frame) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(5), Constraint::Length(1)])
+            .split(frame.area());
+
+        let panes = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+            .split(chunks[0]);
+
+        self.render_list(frame, panes[0]);
+        self.render_panel(frame, panes[1]);
+        self.render_footer(frame, chunks[1]);
+    }
+
+    fn render_list(&mut self, frame: &mut Frame, area: Rect) {
+        let title = if self.filter.is_empty() {
+            format!(" 🌳 {} — {} plants ", self.project_name, self.plants.len())
+        } else {
+            format!(
+                " 🌳 {} — {}/{} plants matching '{}' ",
+                self.project_name,
+                self.visible.len(),
+                self.plants.len(),
+                self.filter
+            )
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(TuiPalette::title_style())
+            .title(Span::styled(title, TuiPalette::title_style()));
+
+        let items: Vec<ListItem> = self
+            .visible
+            .iter()
+            .map(|&index| {
+                let plant = &self.plants[index];
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        format!("{} ", plant.growth_stage.glyph()),
+                        stage_style(plant.growth_stage),
+                    ),
+                    Span::raw(plant.path.clone()),
+                    Span::styled(
+                        format!("  {} lines", plant.lines),
+                        TuiPalette::muted_style(),
+                    ),
+                ]))
+            })
+            .collect();
+
+        if items.is_empty() {
+            let empty = Paragraph::new("  No plants match the filter.")
+                .style(TuiPalette::muted_style())
+                .block(block);
+            frame.render_widget(empty, area);
+            return;
+        }
+
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(TuiPalette::selected_style());
+        frame.render_stateful_widget(list, area, &mut self.list_state);
+    }
+
+    fn render_panel(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(TuiPalette::border_style())
+            .title(Span::styled(" Analysis ", TuiPalette::title_style()));
+
+        let lines = match self.selected_plant() {
+            Some(plant) => self.panel_lines(plant),
+            None => vec![Line::from(Span::styled(
+                "Nothing selected",
+                TuiPalette::muted_style(),
+            ))],
+        };
+
+        let panel = Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false });
+        frame.render_widget(panel, area);
+    }
+
+    fn panel_lines(&self, plant: &GardenPlant) -> Vec<Line<'static>> {
+        let mut lines = vec![
+            Line::from(Span::styled(plant.path.clone(), TuiPalette::path_style())),
+            Line::from(vec![
+                Span::styled("Stage: ", TuiPalette::muted_style()),
+                Span::styled(
+                    format!(
+                        "{} {}",
+                        plant.growth_stage.glyph(),
+                        plant.growth_stage.description()
+                    ),
+                    stage_style(plant.growth_stage),
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled("Commits: ", TuiPalette::muted_style()),
+                Span::raw(plant.commit_count.to_string()),
+                Span::styled("  Coverage: ", TuiPalette::muted_style()),
+                match plant.coverage_pct {
+                    Some(pct) if pct < HEALTHY_COVERAGE_PCT => {
+                        Span::styled(format!("{:.0}%", pct), TuiPalette::warning_style())
+                    }
+                    Some(pct) => Span::styled(format!("{:.0}%", pct), TuiPalette::success_style()),
+                    None => Span::styled("unknown", TuiPalette::muted_style()),
+                },
+            ]),
+            Line::from(""),
+        ];
+
+        if let Some(err) = &self.insight_error {
+            lines.push(Line::from(Span::styled(
+                err.clone(),
+                TuiPalette::error_style(),
+            )));
+            return lines;
+        }
+
+        let Some(insight) = self
+            .insight
+            .as_ref()
+            .filter(|insight| insight.path == Path::new(&plant.path))
+        else {
+            lines.push(Line::from(Span::styled(
+                "Press Enter to inspect symbols and tech debt",
+                TuiPalette::muted_style(),
+            )));
+            return lines;
+        };
+
+        lines.push(Line::from(Span::styled(
+            format!(
+                "Tech debt — {} items, ~{:.1}h to fix",
+                insight.metrics.total_items,
+                insight.debt.iter().map(|d| d.estimated_hours).sum::<f64>()
+            ),
+            TuiPalette::title_style(),
+        )));
+        if insight.debt.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "  none found",
+                TuiPalette::success_style(),
+            )));
+        }
+        for item in insight.debt.iter().take(MAX_PANEL_DEBT) {
+            let style = match item.severity {
+                DebtSeverity::Low => TuiPalette::muted_style(),
+                DebtSeverity::Medium => TuiPalette::warning_style(),
+                DebtSeverity::High | DebtSeverity::Critical => TuiPalette::error_style(),
+            };
+            let mut spans = vec![Span::styled(format!("  • {}", item.title), style)];
+            if !item.description.is_empty() {
+                spans.push(Span::styled(
+                    format!(" — {}", item.description),
+                    TuiPalette::muted_style(),
+                ));
+            }
+            lines.push(Line::from(spans));
+        }
+        if insight.debt.len() > MAX_PANEL_DEBT {
+            lines.push(Line::from(Span::styled(
+                format!("  … and {} more", insight.debt.len() - MAX_PANEL_DEBT),
+                TuiPalette::muted_style(),
+            )));
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!("Symbols — {}", insight.symbols.len()),
+            TuiPalette::title_style(),
+        )));
+        if insight.symbols.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "  none detected",
+                TuiPalette::muted_style(),
+            )));
+        }
+        for symbol in insight.symbols.iter().take(MAX_PANEL_SYMBOLS) {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  {:>5} ", symbol.line_number.unwrap_or_default()),
+                    TuiPalette::muted_style(),
+                ),
+                Span::styled(
+                    format!("{:<8} ", symbol.node_type.as_str()),
+                    Style::default().fg(TuiPalette::COPPER),
+                ),
+                Span::styled(
+                    symbol.name.clone(),
+                    if symbol.visibility.is_some() {
+                        Style::default().add_modifier(Modifier::BOLD)
+                    } else {
+                        Style::default()
+                    },
+                ),
+            ]));
+        }
+        if insight.symbols.len() > MAX_PANEL_SYMBOLS {
+            lines.push(Line::from(Span::styled(
+                format!("  … and {} more", insight.symbols.len() - MAX_PANEL_SYMBOLS),
+                TuiPalette::muted_style(),
+            )));
+        }
+
+        lines
+    }
+
+    fn render_footer(&self, frame: &mut Frame, area: Rect) {
+        let line = if self.filtering {
+            Line::from(vec![
+                Span::styled("/", TuiPalette::title_style()),
+                Span::raw(self.filter.clone()),
+                Span::styled("▏  Enter apply · Esc clear", TuiPalette::muted_style()),
+            ])
+        } else {
+            Line::from(Span::styled(
+                " ↑↓ move · Enter inspect · / filter · a analyze with agent · q back to chat",
+                TuiPalette::muted_style(),
+            ))
+        };
+        frame.render_widget(Paragraph::new(line), area);
+    }
+}
+
+fn stage_style(stage: GrowthStage) -> Style {
+    match stage {
+        GrowthStage::Wilting => TuiPalette::warning_style(),
+        GrowthStage::Ancient => Style::default().fg(TuiPalette::COPPER),
+        _ => TuiPalette::success_style(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::garden::PlantType;
+
+    fn plant(path: &str, ext: &str) -> GardenPlant {
+        GardenPlant {
+            path: path.to_string(),
+            name: Path::new(path)
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .to_string(),
+            extension: ext.to_string(),
+            lines: 10,
+            age_days: 1,
+            last_tended_days: 1,
+            growth_stage: GrowthStage::Seedling,
+            plant_type: PlantType::Vegetable,
+            commit_count: 0,
+            coverage_pct: None,
+        }
+    }
+
+    fn explorer() -> GardenExplorer {
+        let mut garden = DigitalGarden::new("test");
+        garden.add_plant(plant("src/main.rs", "rs"));
+        garden.add_plant(plant("src/garden.rs", "rs"));
+        garden.add_plant(plant("docs/guide.md", "md"));
+        GardenExplorer::new(&garden)
+    }
+
+    fn key(code: KeyCode) -> Event {
+        Event::Key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    fn type_filter(explorer: &mut GardenExplorer, text: &str) {
+        explorer.handle_event(key(KeyCode::Char('/')));
+        for c in text.chars() {
+            explorer.handle_event(key(KeyCode::Char(c)));
+        }
+        explorer.handle_event(key(KeyCode::Enter));
+    }
+
+    #[test]
+    fn test_arrows_wrap_around_sorted_plants() {
+        let mut explorer = explorer();
+        assert_eq!(explorer.selected_plant().unwrap().path, "docs/guide.md");
+        explorer.handle_event(key(KeyCode::Down));
+        assert_eq!(explorer.selected_plant().unwrap().path, "src/garden.rs");
+        explorer.handle_event(key(KeyCode::Up));
+        explorer.handle_event(key(KeyCode::Up));
+        assert_eq!(explorer.selected_plant().unwrap().path, "src/main.rs");
+    }
+
+    #[test]
+    fn test_filter_by_name_and_extension() {
+        let mut explorer = explorer();
+        type_filter(&mut explorer, ".rs");
+        assert_eq!(explorer.visible_count(), 2);
+
+        explorer.handle_event(key(KeyCode::Char('/')));
+        explorer.handle_event(key(KeyCode::Esc));
+        assert_eq!(explorer.visible_count(), 3);
+
+        type_filter(&mut explorer, "GARD");
+        assert_eq!(explorer.visible_count(), 1);
+        assert_eq!(explorer.selected_plant().unwrap().name, "garden.rs");
+    }
+
+    #[test]
+    fn test_q_only_quits_outside_filter_input() {
+        let mut explorer = explorer();
+        explorer.handle_event(key(KeyCode::Char('/')));
+        assert_eq!(
+            explorer.handle_event(key(KeyCode::Char('q'))),
+            ExplorerAction::Continue
+        );
+        explorer.handle_event(key(KeyCode::Enter));
+        assert_eq!(
+            explorer.handle_event(key(KeyCode::Char('q'))),
+            ExplorerAction::Quit
+        );
+    }
+
+    #[test]
+    fn test_enter_inspects_and_a_requests_analysis() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("lib.rs");
+        std::fs::write(&file, "pub fn grow() {}\n// TODO: prune\n").unwrap();
+        let path = file.display().to_string();
+
+        let mut garden = DigitalGarden::new("test");
+        garden.add_plant(plant(&path, "rs"));
+        let mut explorer = GardenExplorer::new(&garden);
+
+        explorer.handle_event(key(KeyCode::Enter));
+        let insight = explorer.insight.as_ref().unwrap();
+        assert_eq!(insight.symbols.len(), 1);
+        assert_eq!(insight.debt.len(), 1);
+
+        assert_eq!(
+            explorer.handle_event(key(KeyCode::Char('a'))),
+            ExplorerAction::Analyze(path)
+        );
+    }
+}
@@ -7,7 +7,7 @@
 //! - Health indicators with visual feedback
 
 use super::TuiPalette;
-use crate::ui::garden::{DigitalGarden, GardenPlant, GrowthStage, PlantType};
+use crate::ui::garden::{DigitalGarden, GardenPlant, GrowthStage, PlantType, HEALTHY_COVERAGE_PCT};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Modifier, Style},
@@ -587,6 +587,24 @@ impl GardenView {
                             },
                         ),
                     ]),
+                    Line::from(""),
+                    Line::from(vec![
+                        Span::styled("Commits: ", TuiPalette::muted_style()),
+                        Span::styled(plant.commit_count.to_string(), TuiPalette::muted_style()),
+                    ]),
+                    Line::from(""),
+                    Line::from(vec![
+                        Span::styled("Coverage: ", TuiPalette::muted_style()),
+                        match plant.coverage_pct {
+                            Some(pct) if pct < HEALTHY_COVERAGE_PCT => {
+                                Span::styled(format!("{:.0}%", pct), TuiPalette::warning_style())
+                            }
+                            Some(pct) => {
+                                Span::styled(format!("{:.0}%", pct), TuiPalette::success_style())
+                            }
+                            None => Span::styled("unknown", TuiPalette::muted_style()),
+                        },
+                    ]),
                 ]
             }
             None => {
@@ -671,6 +689,8 @@ mod tests {
             last_tended_days: 1,
             growth_stage: GrowthStage::Established,
             plant_type: PlantType::Flower,
+            commit_count: 0,
+            coverage_pct: None,
         });
         bed.add_plant(GardenPlant {
             path: "src/lib.rs".to_string(),
@@ -681,6 +701,8 @@ mod tests {
             last_tended_days: 0,
             growth_stage: GrowthStage::Mature,
             plant_type: PlantType::Flower,
+            commit_count: 0,
+            coverage_pct: None,
         });
         garden.total_plants = 2;
         garden.total_lines = 350;
@@ -778,6 +800,8 @@ mod tests {
                 last_tended_days: 0,
                 growth_stage: GrowthStage::Seedling,
                 plant_type: PlantType::Vegetable,
+                commit_count: 0,
+                coverage_pct: None,
             },
             bed_path: "test".to_string(),
         };
@@ -847,6 +871,8 @@ mod tests {
                 last_tended_days: 1,
                 growth_stage: GrowthStage::Established,
                 plant_type: PlantType::Flower,
+                commit_count: 0,
+                coverage_pct: None,
             },
             bed_path: "src".to_string(),
         };
@@ -15,6 +15,7 @@ use syntect::highlighting::{Theme, ThemeSet};
 use syntect::parsing::SyntaxSet;
 
 use super::TuiPalette;
+use crate::ui::accessibility::dyslexia_friendly;
 
 /// Convert HeadingLevel to usize for repeat operations
 fn heading_level_to_usize(level: HeadingLevel) -> usize {
@@ -43,7 +44,12 @@ impl MarkdownRenderer {
     pub fn new() -> Self {
         let syntax_set = SyntaxSet::load_defaults_newlines();
         let theme_set = ThemeSet::load_defaults();
-        let theme = theme_set.themes["base16-ocean.dark"].clone();
+        let theme_name = if dyslexia_friendly::is_enabled() {
+            dyslexia_friendly::CODE_THEME
+        } else {
+            "base16-ocean.dark"
+        };
+        let theme = theme_set.themes[theme_name].clone();
 
         let mut options = Options::empty();
         options.insert(Options::ENABLE_STRIKETHROUGH);
@@ -134,16 +140,39 @@ impl MarkdownRenderer {
             Style::default().fg(TuiPalette::COPPER),
         )));
 
+        let friendly = dyslexia_friendly::is_enabled();
         for change in diff.iter_all_changes() {
-            let (prefix, style) = match change.tag() {
-                ChangeTag::Delete => (
+            let (prefix, style) = match (change.tag(), friendly) {
+                (ChangeTag::Delete, false) => (
                     "- ",
                     Style::default()
                         .fg(Color::Red)
                         .add_modifier(Modifier::CROSSED_OUT),
                 ),
-                ChangeTag::Insert => ("+ ", Style::default().fg(Color::Green)),
-                ChangeTag::Equal => ("  ", Style::default().fg(TuiPalette::STONE)),
+                (ChangeTag::Insert, false) => ("+ ", Style::default().fg(Color::Green)),
+                (ChangeTag::Equal, false) => ("  ", Style::default().fg(TuiPalette::STONE)),
+                // Shape markers and a blue/orange pair that stay apart
+                // without telling red from green
+                (ChangeTag::Delete, true) => {
+                    let (r, g, b) = dyslexia_friendly::REMOVED_RGB;
+                    (
+                        dyslexia_friendly::REMOVED_MARKER,
+                        Style::default().fg(Color::Rgb(r, g, b)),
+                    )
+                }
+                (ChangeTag::Insert, true) => {
+                    let (r, g, b) = dyslexia_friendly::ADDED_RGB;
+                    (
+                        dyslexia_friendly::ADDED_MARKER,
+                        Style::default()
+                            .fg(Color::Rgb(r, g, b))
+                            .add_modifier(Modifier::BOLD),
+                    )
+                }
+                (ChangeTag::Equal, true) => (
+                    dyslexia_friendly::CONTEXT_MARKER,
+                    Style::default().fg(TuiPalette::STONE),
+                ),
             };
 
             let text = change.to_string();
@@ -153,6 +182,12 @@ impl MarkdownRenderer {
                 Span::styled(prefix, style),
                 Span::styled(text.to_string(), style),
             ]));
+            if friendly {
+                lines.push(Line::from(Span::styled(
+                    "│ ",
+                    Style::default().fg(TuiPalette::STONE),
+                )));
+            }
         }
 
         lines.push(Line::from(Span::styled(
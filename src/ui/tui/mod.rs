@@ -7,6 +7,7 @@
 pub mod animation;
 mod app;
 mod dashboard_widgets;
+mod garden_explorer;
 pub mod garden_view;
 mod layout;
 mod markdown;
@@ -21,6 +22,7 @@ pub use dashboard_widgets::{
     render_active_tools, render_garden_health, render_help_overlay, render_logs, render_status_bar,
     ActiveTool, DashboardState, LogEntry, LogLevel, SharedDashboardState, TuiEvent,
 };
+pub use garden_explorer::{ExplorerAction, GardenExplorer};
 pub use garden_view::{render_garden_view, GardenFocus, GardenItem, GardenView};
 pub use layout::{LayoutEngine, LayoutNode, LayoutPreset, Pane, PaneId, PaneType, SplitDirection};
 pub use markdown::MarkdownRenderer;
@@ -2281,6 +2283,41 @@ pub fn run_tui_swarm() -> Result<()> {
     Ok(())
 }
 
+/// Run the interactive garden explorer over `garden`.
+///
+/// Returns the path of a file the user asked the agent to analyze (`a`),
+/// or `None` when they left with `q`/Esc.
+///
+/// Keyboard controls:
+/// - ↑/↓ or j/k: Move between plants
+/// - Enter: Inspect symbols and tech debt for the selected file
+/// - /: Filter plants by name or extension
+/// - a: Return to chat and analyze the selected file
+/// - q, Esc: Return to chat
+pub fn run_garden_explorer(garden: &crate::ui::garden::DigitalGarden) -> Result<Option<String>> {
+    let mut terminal = TuiTerminal::new()?;
+    let mut explorer = GardenExplorer::new(garden);
+
+    let outcome = loop {
+        terminal.terminal().draw(|frame| explorer.render(frame))?;
+
+        if signal_received() {
+            break None;
+        }
+        let Some(event) = read_event(100)? else {
+            continue;
+        };
+        match explorer.handle_event(event) {
+            ExplorerAction::Continue => {}
+            ExplorerAction::Quit => break None,
+            ExplorerAction::Analyze(path) => break Some(path),
+        }
+    };
+
+    terminal.restore()?;
+    Ok(outcome)
+}
+
 /// Run the TUI swarm with a custom swarm configuration
 ///
 /// Similar to `run_tui_swarm` but allows specifying custom agent roles.
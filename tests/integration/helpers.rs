@@ -32,6 +32,10 @@ pub fn test_config() -> Config {
             protected_branches: vec!["main".to_string()],
             require_confirmation: vec![],
             strict_permissions: false,
+            large_edit_lines: 200,
+            large_edit_ratio: 0.5,
+            large_edit_min_lines: 20,
+            guard_self_source: true,
         },
         agent: AgentConfig {
             max_iterations: 10, // Limit for tests
@@ -93,6 +93,7 @@ mod ui_config_tests {
             verbose_mode: false,
             show_tokens: true,
             animation_speed: 2.0,
+            ..Default::default()
         };
 
         let toml = toml::to_string(&config).unwrap();
@@ -165,6 +166,10 @@ mod safety_config_tests {
             protected_branches: vec!["main".to_string()],
             require_confirmation: vec!["git push".to_string()],
             strict_permissions: false,
+            large_edit_lines: 200,
+            large_edit_ratio: 0.5,
+            large_edit_min_lines: 20,
+            guard_self_source: true,
         };
 
         let toml = toml::to_string(&config).unwrap();
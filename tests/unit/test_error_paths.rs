@@ -202,6 +202,7 @@ mod file_write_error_tests {
             .execute(serde_json::json!({
                 "path": file_path.to_str().unwrap(),
                 "content": "new content",
+                "overwrite": true,
                 "backup": true
             }))
             .await;
@@ -228,6 +229,7 @@ mod file_write_error_tests {
             .execute(serde_json::json!({
                 "path": file_path.to_str().unwrap(),
                 "content": "new",
+                "overwrite": true,
                 "backup": false
             }))
             .await;
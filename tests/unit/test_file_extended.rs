@@ -110,6 +110,7 @@ async fn test_file_write_with_backup() {
     let args = json!({
         "path": file_path.to_str().unwrap(),
         "content": "new content",
+        "overwrite": true,
         "backup": true
     });
 
@@ -133,6 +134,7 @@ async fn test_file_write_without_backup() {
     let args = json!({
         "path": file_path.to_str().unwrap(),
         "content": "new content",
+        "overwrite": true,
         "backup": false
     });
 
@@ -88,6 +88,10 @@ async fn test_file_write_restricted_directory_returns_error() {
         protected_branches: vec![],
         require_confirmation: vec![],
         strict_permissions: false,
+        large_edit_lines: 200,
+        large_edit_ratio: 0.5,
+        large_edit_min_lines: 20,
+        guard_self_source: true,
     };
 
     let tool = FileWrite::with_safety_config(safety);